@@ -0,0 +1,92 @@
+-- 本地优先同步 (MySQL 服务器)
+-- 与 SQLite 客户端使用相同的 sync_id / version / synced_version 字段。
+-- updated_at 为记录的最后修改时间 (用于后写者胜出)，客户端推送时会显式写入；
+-- synced_at 为服务器收到修改的时间，客户端据此增量拉取。
+-- 直接连接服务器的客户端修改记录时，由触发器递增版本，删除时写入墓碑供其他客户端拉取。
+
+ALTER TABLE subjects
+    ADD COLUMN sync_id CHAR(32) NULL,
+    ADD COLUMN version BIGINT NOT NULL DEFAULT 1,
+    ADD COLUMN synced_version BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    ADD COLUMN synced_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP;
+UPDATE subjects SET sync_id = REPLACE(UUID(), '-', ''), updated_at = created_at;
+ALTER TABLE subjects ADD UNIQUE KEY uk_subjects_sync_id (sync_id), ADD KEY idx_subjects_synced_at (user_id, synced_at);
+
+ALTER TABLE tasks
+    ADD COLUMN sync_id CHAR(32) NULL,
+    ADD COLUMN version BIGINT NOT NULL DEFAULT 1,
+    ADD COLUMN synced_version BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN synced_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP;
+UPDATE tasks SET sync_id = REPLACE(UUID(), '-', ''), updated_at = updated_at;
+ALTER TABLE tasks ADD UNIQUE KEY uk_tasks_sync_id (sync_id), ADD KEY idx_tasks_synced_at (user_id, synced_at);
+
+ALTER TABLE pomodoro_sessions
+    ADD COLUMN sync_id CHAR(32) NULL,
+    ADD COLUMN version BIGINT NOT NULL DEFAULT 1,
+    ADD COLUMN synced_version BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    ADD COLUMN synced_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP;
+UPDATE pomodoro_sessions SET sync_id = REPLACE(UUID(), '-', ''), updated_at = COALESCE(end_time, start_time);
+ALTER TABLE pomodoro_sessions ADD UNIQUE KEY uk_pomodoro_sessions_sync_id (sync_id), ADD KEY idx_pomodoro_sessions_synced_at (user_id, synced_at);
+
+ALTER TABLE daily_reviews
+    ADD COLUMN sync_id CHAR(32) NULL,
+    ADD COLUMN version BIGINT NOT NULL DEFAULT 1,
+    ADD COLUMN synced_version BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    ADD COLUMN synced_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP;
+UPDATE daily_reviews SET sync_id = REPLACE(UUID(), '-', ''), updated_at = created_at;
+ALTER TABLE daily_reviews ADD UNIQUE KEY uk_daily_reviews_sync_id (sync_id), ADD KEY idx_daily_reviews_synced_at (user_id, synced_at);
+
+-- 服务器端删除记录 (墓碑)
+CREATE TABLE IF NOT EXISTS sync_tombstones (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    entity VARCHAR(20) NOT NULL,
+    sync_id CHAR(32) NOT NULL,
+    user_id BIGINT NOT NULL,
+    version BIGINT NOT NULL,
+    deleted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    KEY idx_sync_tombstones_user (user_id, deleted_at),
+    KEY idx_sync_tombstones_sync_id (sync_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TRIGGER trg_subjects_sync_insert BEFORE INSERT ON subjects FOR EACH ROW
+    SET NEW.sync_id = COALESCE(NEW.sync_id, REPLACE(UUID(), '-', ''));
+
+CREATE TRIGGER trg_subjects_sync_update BEFORE UPDATE ON subjects FOR EACH ROW
+    SET NEW.version = IF(NEW.version = OLD.version, OLD.version + 1, NEW.version);
+
+CREATE TRIGGER trg_subjects_sync_delete AFTER DELETE ON subjects FOR EACH ROW
+    INSERT INTO sync_tombstones (entity, sync_id, user_id, version)
+    VALUES ('subject', OLD.sync_id, OLD.user_id, OLD.version);
+
+CREATE TRIGGER trg_tasks_sync_insert BEFORE INSERT ON tasks FOR EACH ROW
+    SET NEW.sync_id = COALESCE(NEW.sync_id, REPLACE(UUID(), '-', ''));
+
+CREATE TRIGGER trg_tasks_sync_update BEFORE UPDATE ON tasks FOR EACH ROW
+    SET NEW.version = IF(NEW.version = OLD.version, OLD.version + 1, NEW.version);
+
+CREATE TRIGGER trg_tasks_sync_delete AFTER DELETE ON tasks FOR EACH ROW
+    INSERT INTO sync_tombstones (entity, sync_id, user_id, version)
+    VALUES ('task', OLD.sync_id, OLD.user_id, OLD.version);
+
+CREATE TRIGGER trg_pomodoro_sessions_sync_insert BEFORE INSERT ON pomodoro_sessions FOR EACH ROW
+    SET NEW.sync_id = COALESCE(NEW.sync_id, REPLACE(UUID(), '-', ''));
+
+CREATE TRIGGER trg_pomodoro_sessions_sync_update BEFORE UPDATE ON pomodoro_sessions FOR EACH ROW
+    SET NEW.version = IF(NEW.version = OLD.version, OLD.version + 1, NEW.version);
+
+CREATE TRIGGER trg_pomodoro_sessions_sync_delete AFTER DELETE ON pomodoro_sessions FOR EACH ROW
+    INSERT INTO sync_tombstones (entity, sync_id, user_id, version)
+    VALUES ('pomodoro', OLD.sync_id, OLD.user_id, OLD.version);
+
+CREATE TRIGGER trg_daily_reviews_sync_insert BEFORE INSERT ON daily_reviews FOR EACH ROW
+    SET NEW.sync_id = COALESCE(NEW.sync_id, REPLACE(UUID(), '-', ''));
+
+CREATE TRIGGER trg_daily_reviews_sync_update BEFORE UPDATE ON daily_reviews FOR EACH ROW
+    SET NEW.version = IF(NEW.version = OLD.version, OLD.version + 1, NEW.version);
+
+CREATE TRIGGER trg_daily_reviews_sync_delete AFTER DELETE ON daily_reviews FOR EACH ROW
+    INSERT INTO sync_tombstones (entity, sync_id, user_id, version)
+    VALUES ('review', OLD.sync_id, OLD.user_id, OLD.version);
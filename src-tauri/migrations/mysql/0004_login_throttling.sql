@@ -0,0 +1,20 @@
+-- 登录限流与临时锁定
+-- login_attempts 记录每次登录的结果；login_throttles 按用户名记录连续失败次数与锁定截止时间，
+-- 登录成功或管理员解锁后删除。用户名不要求对应已存在的用户。
+
+CREATE TABLE IF NOT EXISTS login_attempts (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    username VARCHAR(50) NOT NULL,
+    success BOOLEAN NOT NULL,
+    attempted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    KEY idx_login_attempts_username (username, attempted_at),
+    KEY idx_login_attempts_attempted_at (attempted_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS login_throttles (
+    username VARCHAR(50) NOT NULL PRIMARY KEY,
+    failed_count INT NOT NULL DEFAULT 0,
+    last_failed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    locked_until TIMESTAMP NULL,
+    KEY idx_login_throttles_locked_until (locked_until)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
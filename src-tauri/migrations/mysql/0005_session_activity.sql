@@ -0,0 +1,6 @@
+-- 会话活跃时间
+-- last_used_at 为会话最近一次使用的时间，会话每次使用时顺延过期时间；过期会话由后台任务定期清理。
+
+ALTER TABLE user_sessions ADD COLUMN last_used_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP;
+UPDATE user_sessions SET last_used_at = created_at;
+ALTER TABLE user_sessions ADD KEY idx_user_sessions_user (user_id, last_used_at), ADD KEY idx_user_sessions_expires_at (expires_at);
@@ -0,0 +1,12 @@
+-- 导师与学生分配
+-- 导师角色只能查看分配给自己的学生的统计与学习计划；删除任一用户时一并删除分配关系。
+
+CREATE TABLE IF NOT EXISTS mentor_students (
+    mentor_id BIGINT NOT NULL,
+    student_id BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (mentor_id, student_id),
+    KEY idx_mentor_students_student (student_id),
+    CONSTRAINT fk_mentor_students_mentor FOREIGN KEY (mentor_id) REFERENCES users(id) ON DELETE CASCADE,
+    CONSTRAINT fk_mentor_students_student FOREIGN KEY (student_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,26 @@
+-- 邀请码自助注册
+-- invite_codes 由管理员生成，包含预设角色、有效期和最大使用次数；invite_code_uses 记录每个邀请码注册的账户。
+
+CREATE TABLE IF NOT EXISTS invite_codes (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    code VARCHAR(32) NOT NULL,
+    role VARCHAR(20) NOT NULL DEFAULT 'user',
+    max_uses INT NOT NULL DEFAULT 1,
+    used_count INT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP NOT NULL,
+    created_by BIGINT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    revoked_at TIMESTAMP NULL,
+    UNIQUE KEY uk_invite_codes_code (code),
+    CONSTRAINT fk_invite_codes_created_by FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS invite_code_uses (
+    invite_code_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    used_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (invite_code_id, user_id),
+    KEY idx_invite_code_uses_user (user_id),
+    CONSTRAINT fk_invite_code_uses_code FOREIGN KEY (invite_code_id) REFERENCES invite_codes(id) ON DELETE CASCADE,
+    CONSTRAINT fk_invite_code_uses_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
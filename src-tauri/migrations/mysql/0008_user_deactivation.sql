@@ -0,0 +1,16 @@
+-- 停用用户与审计记录
+-- deactivated_at 非空表示账户已停用：禁止登录但保留全部数据。
+-- audit_events 记录管理操作，不设外键，删除用户后记录仍然保留。
+
+ALTER TABLE users ADD COLUMN deactivated_at TIMESTAMP NULL;
+
+CREATE TABLE IF NOT EXISTS audit_events (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    actor_id BIGINT NULL,
+    action VARCHAR(50) NOT NULL,
+    target_user_id BIGINT NULL,
+    metadata TEXT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    KEY idx_audit_events_created_at (created_at),
+    KEY idx_audit_events_target (target_user_id, created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,4 @@
+-- 审计日志查询索引
+-- 审计日志按操作者或操作类型筛选并按时间倒序分页。
+
+ALTER TABLE audit_events ADD KEY idx_audit_events_actor (actor_id, created_at), ADD KEY idx_audit_events_action (action, created_at);
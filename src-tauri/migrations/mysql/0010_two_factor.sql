@@ -0,0 +1,32 @@
+-- 两步验证 (TOTP)
+-- user_totp 保存用户的 TOTP 密钥，enabled_at 为空表示尚未完成绑定；last_used_step 防止同一验证码被重复使用。
+-- user_recovery_codes 保存一次性恢复码的哈希；login_challenges 为通过密码验证、等待提交验证码的登录。
+
+CREATE TABLE IF NOT EXISTS user_totp (
+    user_id BIGINT NOT NULL PRIMARY KEY,
+    secret VARCHAR(64) NOT NULL,
+    enabled_at TIMESTAMP NULL,
+    last_used_step BIGINT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_user_totp_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS user_recovery_codes (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    code_hash VARCHAR(255) NOT NULL,
+    used_at TIMESTAMP NULL,
+    KEY idx_user_recovery_codes_user (user_id),
+    CONSTRAINT fk_user_recovery_codes_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS login_challenges (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    challenge_token VARCHAR(64) NOT NULL,
+    user_id BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP NOT NULL,
+    UNIQUE KEY uk_login_challenges_token (challenge_token),
+    KEY idx_login_challenges_expires_at (expires_at),
+    CONSTRAINT fk_login_challenges_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,35 @@
+-- 重复任务
+-- task_series 保存重复任务的内容与 RRULE 形式的重复规则；每一次重复在首次查询当天任务时生成为 tasks 中的记录，
+-- 以 series_id 与 occurrence_date 对应到系列中的某一次，之后可单独完成、修改或删除。
+-- task_series_skips 记录被单独删除的重复，避免再次生成。
+
+CREATE TABLE IF NOT EXISTS task_series (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    subject_id BIGINT NULL,
+    start_date DATE NOT NULL,
+    start_time TIME NOT NULL,
+    end_time TIME NOT NULL,
+    content VARCHAR(500) NOT NULL,
+    alarm_enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    alarm_time TIME NULL,
+    rrule VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    KEY idx_task_series_user (user_id, start_date),
+    CONSTRAINT fk_task_series_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    CONSTRAINT fk_task_series_subject FOREIGN KEY (subject_id) REFERENCES subjects(id) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS task_series_skips (
+    series_id BIGINT NOT NULL,
+    occurrence_date DATE NOT NULL,
+    PRIMARY KEY (series_id, occurrence_date),
+    CONSTRAINT fk_task_series_skips_series FOREIGN KEY (series_id) REFERENCES task_series(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+ALTER TABLE tasks
+    ADD COLUMN series_id BIGINT NULL,
+    ADD COLUMN occurrence_date DATE NULL,
+    ADD UNIQUE KEY uk_tasks_series_occurrence (series_id, occurrence_date),
+    ADD CONSTRAINT fk_tasks_series FOREIGN KEY (series_id) REFERENCES task_series(id) ON DELETE SET NULL;
@@ -0,0 +1,16 @@
+-- 任务清单
+-- task_checklist_items 保存任务下按 position 排列的清单项及各自的完成状态；
+-- tasks.progress 为清单的完成百分比 (0-100，没有清单项时为 0)，随清单项的变化更新，用于统计部分完成的任务。
+
+CREATE TABLE IF NOT EXISTS task_checklist_items (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    task_id BIGINT NOT NULL,
+    position INT NOT NULL,
+    content VARCHAR(500) NOT NULL,
+    completed BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    KEY idx_task_checklist_items_task (task_id, position),
+    CONSTRAINT fk_task_checklist_items_task FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+ALTER TABLE tasks ADD COLUMN progress INT NOT NULL DEFAULT 0;
@@ -0,0 +1,27 @@
+-- 任务优先级与标签
+-- tasks.priority / task_series.priority 为任务的优先级 (low、normal、high)，重复任务生成的任务沿用系列的优先级。
+-- task_tags 保存用户自定义的标签 (同一用户内名称唯一)，task_tag_links 为任务与标签的多对多关联，
+-- 删除任务或标签时关联随之删除。
+
+ALTER TABLE tasks ADD COLUMN priority VARCHAR(20) NOT NULL DEFAULT 'normal';
+
+ALTER TABLE task_series ADD COLUMN priority VARCHAR(20) NOT NULL DEFAULT 'normal';
+
+CREATE TABLE IF NOT EXISTS task_tags (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    name VARCHAR(30) NOT NULL,
+    color VARCHAR(20) NOT NULL DEFAULT '#6B7280',
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_task_tags_user_name (user_id, name),
+    CONSTRAINT fk_task_tags_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS task_tag_links (
+    task_id BIGINT NOT NULL,
+    tag_id BIGINT NOT NULL,
+    PRIMARY KEY (task_id, tag_id),
+    KEY idx_task_tag_links_tag (tag_id),
+    CONSTRAINT fk_task_tag_links_task FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    CONSTRAINT fk_task_tag_links_tag FOREIGN KEY (tag_id) REFERENCES task_tags(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
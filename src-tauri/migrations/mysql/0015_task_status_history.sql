@@ -0,0 +1,16 @@
+-- 任务状态变更记录
+-- tasks.status_reason 为最近一次状态变更的原因 (标记为未完成时必填，如 时间不够、太难、状态差)。
+-- task_status_history 按时间记录任务的每次状态变更，删除任务时随之删除。
+
+ALTER TABLE tasks ADD COLUMN status_reason VARCHAR(200) NULL;
+
+CREATE TABLE IF NOT EXISTS task_status_history (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    task_id BIGINT NOT NULL,
+    from_status VARCHAR(20) NOT NULL,
+    to_status VARCHAR(20) NOT NULL,
+    reason VARCHAR(200) NULL,
+    changed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    KEY idx_task_status_history_task (task_id, id),
+    CONSTRAINT fk_task_status_history_task FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
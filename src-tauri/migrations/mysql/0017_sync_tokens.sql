@@ -0,0 +1,13 @@
+-- 同步账户验证
+-- 客户端凭服务器账户密码换取同步令牌，之后每次同步都需出示令牌。
+-- 令牌格式为 "<id>.<secret>"，服务器只保存 secret 的哈希，删除用户时随之删除。
+
+CREATE TABLE IF NOT EXISTS sync_tokens (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    token_hash VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMP NULL,
+    KEY idx_sync_tokens_user (user_id),
+    CONSTRAINT fk_sync_tokens_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,47 @@
+-- 同步重复任务 (MySQL 服务器)
+-- task_series 与 task_series_skips 加入本地优先同步 (字段含义同 0003)，tasks 的 series_id / occurrence_date 随任务同步。
+-- task_series_skips 增加自增 id 与 user_id，直接连接服务器的客户端插入时由触发器按所属的系列填写 user_id。
+
+ALTER TABLE task_series
+    ADD COLUMN sync_id CHAR(32) NULL,
+    ADD COLUMN version BIGINT NOT NULL DEFAULT 1,
+    ADD COLUMN synced_version BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN synced_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP;
+UPDATE task_series SET sync_id = REPLACE(UUID(), '-', ''), updated_at = updated_at;
+ALTER TABLE task_series ADD UNIQUE KEY uk_task_series_sync_id (sync_id), ADD KEY idx_task_series_synced_at (user_id, synced_at);
+
+ALTER TABLE task_series_skips
+    ADD COLUMN id BIGINT NOT NULL AUTO_INCREMENT FIRST,
+    ADD UNIQUE KEY uk_task_series_skips_id (id),
+    ADD COLUMN user_id BIGINT NULL,
+    ADD COLUMN sync_id CHAR(32) NULL,
+    ADD COLUMN version BIGINT NOT NULL DEFAULT 1,
+    ADD COLUMN synced_version BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    ADD COLUMN synced_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP;
+UPDATE task_series_skips k JOIN task_series s ON s.id = k.series_id
+    SET k.user_id = s.user_id, k.sync_id = REPLACE(UUID(), '-', '');
+ALTER TABLE task_series_skips
+    ADD UNIQUE KEY uk_task_series_skips_sync_id (sync_id),
+    ADD KEY idx_task_series_skips_synced_at (user_id, synced_at);
+
+CREATE TRIGGER trg_task_series_sync_insert BEFORE INSERT ON task_series FOR EACH ROW
+    SET NEW.sync_id = COALESCE(NEW.sync_id, REPLACE(UUID(), '-', ''));
+
+CREATE TRIGGER trg_task_series_sync_update BEFORE UPDATE ON task_series FOR EACH ROW
+    SET NEW.version = IF(NEW.version = OLD.version, OLD.version + 1, NEW.version);
+
+CREATE TRIGGER trg_task_series_sync_delete AFTER DELETE ON task_series FOR EACH ROW
+    INSERT INTO sync_tombstones (entity, sync_id, user_id, version)
+    VALUES ('series', OLD.sync_id, OLD.user_id, OLD.version);
+
+CREATE TRIGGER trg_task_series_skips_sync_insert BEFORE INSERT ON task_series_skips FOR EACH ROW
+    SET NEW.sync_id = COALESCE(NEW.sync_id, REPLACE(UUID(), '-', '')),
+        NEW.user_id = COALESCE(NEW.user_id, (SELECT user_id FROM task_series WHERE id = NEW.series_id));
+
+CREATE TRIGGER trg_task_series_skips_sync_update BEFORE UPDATE ON task_series_skips FOR EACH ROW
+    SET NEW.version = IF(NEW.version = OLD.version, OLD.version + 1, NEW.version);
+
+CREATE TRIGGER trg_task_series_skips_sync_delete AFTER DELETE ON task_series_skips FOR EACH ROW
+    INSERT INTO sync_tombstones (entity, sync_id, user_id, version)
+    VALUES ('series_skip', OLD.sync_id, OLD.user_id, OLD.version);
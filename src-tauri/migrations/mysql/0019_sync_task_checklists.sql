@@ -0,0 +1,27 @@
+-- 同步任务清单 (MySQL 服务器)
+-- task_checklist_items 加入本地优先同步 (字段含义同 0003)，tasks.progress 随任务同步。
+-- 清单项原来没有 user_id，直接连接服务器的客户端插入时由触发器按所属的任务填写。
+
+ALTER TABLE task_checklist_items
+    ADD COLUMN user_id BIGINT NULL,
+    ADD COLUMN sync_id CHAR(32) NULL,
+    ADD COLUMN version BIGINT NOT NULL DEFAULT 1,
+    ADD COLUMN synced_version BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    ADD COLUMN synced_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP;
+UPDATE task_checklist_items c JOIN tasks t ON t.id = c.task_id
+    SET c.user_id = t.user_id, c.sync_id = REPLACE(UUID(), '-', ''), c.updated_at = c.created_at;
+ALTER TABLE task_checklist_items
+    ADD UNIQUE KEY uk_task_checklist_items_sync_id (sync_id),
+    ADD KEY idx_task_checklist_items_synced_at (user_id, synced_at);
+
+CREATE TRIGGER trg_task_checklist_items_sync_insert BEFORE INSERT ON task_checklist_items FOR EACH ROW
+    SET NEW.sync_id = COALESCE(NEW.sync_id, REPLACE(UUID(), '-', '')),
+        NEW.user_id = COALESCE(NEW.user_id, (SELECT user_id FROM tasks WHERE id = NEW.task_id));
+
+CREATE TRIGGER trg_task_checklist_items_sync_update BEFORE UPDATE ON task_checklist_items FOR EACH ROW
+    SET NEW.version = IF(NEW.version = OLD.version, OLD.version + 1, NEW.version);
+
+CREATE TRIGGER trg_task_checklist_items_sync_delete AFTER DELETE ON task_checklist_items FOR EACH ROW
+    INSERT INTO sync_tombstones (entity, sync_id, user_id, version)
+    VALUES ('checklist_item', OLD.sync_id, OLD.user_id, OLD.version);
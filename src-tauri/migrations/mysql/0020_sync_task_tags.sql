@@ -0,0 +1,48 @@
+-- 同步任务标签 (MySQL 服务器)
+-- task_tags 与 task_tag_links 加入本地优先同步 (字段含义同 0003)，tasks / task_series 的 priority 随记录同步。
+-- task_tag_links 增加自增 id 与 user_id，直接连接服务器的客户端插入时由触发器按所属的任务填写 user_id。
+
+ALTER TABLE task_tags
+    ADD COLUMN sync_id CHAR(32) NULL,
+    ADD COLUMN version BIGINT NOT NULL DEFAULT 1,
+    ADD COLUMN synced_version BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    ADD COLUMN synced_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP;
+UPDATE task_tags SET sync_id = REPLACE(UUID(), '-', ''), updated_at = created_at;
+ALTER TABLE task_tags ADD UNIQUE KEY uk_task_tags_sync_id (sync_id), ADD KEY idx_task_tags_synced_at (user_id, synced_at);
+
+ALTER TABLE task_tag_links
+    ADD COLUMN id BIGINT NOT NULL AUTO_INCREMENT FIRST,
+    ADD UNIQUE KEY uk_task_tag_links_id (id),
+    ADD COLUMN user_id BIGINT NULL,
+    ADD COLUMN sync_id CHAR(32) NULL,
+    ADD COLUMN version BIGINT NOT NULL DEFAULT 1,
+    ADD COLUMN synced_version BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    ADD COLUMN synced_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP;
+UPDATE task_tag_links l JOIN tasks t ON t.id = l.task_id
+    SET l.user_id = t.user_id, l.sync_id = REPLACE(UUID(), '-', '');
+ALTER TABLE task_tag_links
+    ADD UNIQUE KEY uk_task_tag_links_sync_id (sync_id),
+    ADD KEY idx_task_tag_links_synced_at (user_id, synced_at);
+
+CREATE TRIGGER trg_task_tags_sync_insert BEFORE INSERT ON task_tags FOR EACH ROW
+    SET NEW.sync_id = COALESCE(NEW.sync_id, REPLACE(UUID(), '-', ''));
+
+CREATE TRIGGER trg_task_tags_sync_update BEFORE UPDATE ON task_tags FOR EACH ROW
+    SET NEW.version = IF(NEW.version = OLD.version, OLD.version + 1, NEW.version);
+
+CREATE TRIGGER trg_task_tags_sync_delete AFTER DELETE ON task_tags FOR EACH ROW
+    INSERT INTO sync_tombstones (entity, sync_id, user_id, version)
+    VALUES ('tag', OLD.sync_id, OLD.user_id, OLD.version);
+
+CREATE TRIGGER trg_task_tag_links_sync_insert BEFORE INSERT ON task_tag_links FOR EACH ROW
+    SET NEW.sync_id = COALESCE(NEW.sync_id, REPLACE(UUID(), '-', '')),
+        NEW.user_id = COALESCE(NEW.user_id, (SELECT user_id FROM tasks WHERE id = NEW.task_id));
+
+CREATE TRIGGER trg_task_tag_links_sync_update BEFORE UPDATE ON task_tag_links FOR EACH ROW
+    SET NEW.version = IF(NEW.version = OLD.version, OLD.version + 1, NEW.version);
+
+CREATE TRIGGER trg_task_tag_links_sync_delete AFTER DELETE ON task_tag_links FOR EACH ROW
+    INSERT INTO sync_tombstones (entity, sync_id, user_id, version)
+    VALUES ('tag_link', OLD.sync_id, OLD.user_id, OLD.version);
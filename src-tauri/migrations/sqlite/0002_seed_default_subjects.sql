@@ -0,0 +1,15 @@
+-- 为尚未拥有默认科目的用户补齐默认科目 (subjects.is_default = TRUE，不可删除)
+-- 新用户的默认科目由 subject_service::seed_default_subjects 在创建账号时写入
+
+INSERT INTO subjects (user_id, name, color, is_default)
+SELECT u.id, d.name, d.color, TRUE
+FROM users u
+CROSS JOIN (
+    SELECT '政治' AS name, '#EF4444' AS color
+    UNION ALL SELECT '英语', '#3B82F6'
+    UNION ALL SELECT '数学', '#10B981'
+    UNION ALL SELECT '专业课', '#F59E0B'
+) d
+WHERE NOT EXISTS (
+    SELECT 1 FROM subjects s WHERE s.user_id = u.id AND s.name = d.name
+);
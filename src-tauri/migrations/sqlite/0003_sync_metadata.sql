@@ -0,0 +1,231 @@
+-- 本地优先同步 (SQLite 客户端)
+-- sync_id 在客户端与服务器之间唯一标识同一条记录；version 为记录版本，每次本地修改递增；
+-- synced_version 为最近一次与服务器一致时的版本，0 表示从未同步。
+-- 同步引擎写入时总会修改 version 或 synced_version，触发器据此区分本地修改与同步写入，
+-- 只有本地修改会递增版本并写入待同步变更 sync_changes。
+
+-- subjects
+ALTER TABLE subjects ADD COLUMN sync_id TEXT;
+ALTER TABLE subjects ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE subjects ADD COLUMN synced_version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE subjects ADD COLUMN updated_at TEXT;
+UPDATE subjects SET sync_id = lower(hex(randomblob(16))), updated_at = created_at;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_subjects_sync_id ON subjects(sync_id);
+
+-- tasks
+ALTER TABLE tasks ADD COLUMN sync_id TEXT;
+ALTER TABLE tasks ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE tasks ADD COLUMN synced_version INTEGER NOT NULL DEFAULT 0;
+UPDATE tasks SET sync_id = lower(hex(randomblob(16)));
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tasks_sync_id ON tasks(sync_id);
+
+-- pomodoro_sessions
+ALTER TABLE pomodoro_sessions ADD COLUMN sync_id TEXT;
+ALTER TABLE pomodoro_sessions ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE pomodoro_sessions ADD COLUMN synced_version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE pomodoro_sessions ADD COLUMN updated_at TEXT;
+UPDATE pomodoro_sessions SET sync_id = lower(hex(randomblob(16))), updated_at = COALESCE(end_time, start_time);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_pomodoro_sessions_sync_id ON pomodoro_sessions(sync_id);
+
+-- daily_reviews
+ALTER TABLE daily_reviews ADD COLUMN sync_id TEXT;
+ALTER TABLE daily_reviews ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE daily_reviews ADD COLUMN synced_version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE daily_reviews ADD COLUMN updated_at TEXT;
+UPDATE daily_reviews SET sync_id = lower(hex(randomblob(16))), updated_at = created_at;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_daily_reviews_sync_id ON daily_reviews(sync_id);
+
+-- 待推送到服务器的本地变更 (同一记录只保留最新一次)
+CREATE TABLE IF NOT EXISTS sync_changes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity TEXT NOT NULL,
+    record_id INTEGER NOT NULL,
+    sync_id TEXT,
+    user_id INTEGER NOT NULL,
+    operation TEXT NOT NULL,
+    base_version INTEGER NOT NULL DEFAULT 0,
+    changed_at TEXT NOT NULL,
+    UNIQUE (entity, record_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_changes_user ON sync_changes(user_id);
+
+-- 同步冲突记录 (按 updated_at 后写者胜出，落败一方的数据保留供用户查看)
+CREATE TABLE IF NOT EXISTS sync_conflicts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    entity TEXT NOT NULL,
+    sync_id TEXT NOT NULL,
+    local_data TEXT,
+    remote_data TEXT,
+    resolution TEXT NOT NULL,
+    resolved INTEGER NOT NULL DEFAULT 0,
+    detected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_conflicts_user ON sync_conflicts(user_id, resolved);
+
+-- 每个用户的同步进度
+CREATE TABLE IF NOT EXISTS sync_state (
+    user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    last_pulled_at TEXT,
+    last_sync_at TEXT,
+    last_error TEXT
+);
+
+-- 已有数据全部加入待同步队列
+
+INSERT OR IGNORE INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+SELECT 'subject', id, user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now') FROM subjects;
+
+INSERT OR IGNORE INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+SELECT 'task', id, user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now') FROM tasks;
+
+INSERT OR IGNORE INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+SELECT 'pomodoro', id, user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now') FROM pomodoro_sessions;
+
+INSERT OR IGNORE INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+SELECT 'review', id, user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now') FROM daily_reviews;
+
+-- 原有的 updated_at 触发器会覆盖同步写入的时间戳，由下面的同步触发器取代
+DROP TRIGGER IF EXISTS trg_tasks_updated_at;
+
+CREATE TRIGGER IF NOT EXISTS trg_subjects_sync_id AFTER INSERT ON subjects
+WHEN NEW.sync_id IS NULL OR NEW.updated_at IS NULL
+BEGIN
+    UPDATE subjects SET sync_id = COALESCE(sync_id, lower(hex(randomblob(16)))),
+        updated_at = COALESCE(updated_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_subjects_sync_insert AFTER INSERT ON subjects
+WHEN NEW.synced_version = 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'subject' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+    VALUES ('subject', NEW.id, NEW.user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_subjects_sync_update AFTER UPDATE ON subjects
+WHEN NEW.synced_version = OLD.synced_version AND NEW.version = OLD.version
+    AND NEW.sync_id IS OLD.sync_id
+BEGIN
+    UPDATE subjects SET version = OLD.version + 1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id;
+    DELETE FROM sync_changes WHERE entity = 'subject' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    VALUES ('subject', NEW.id, NEW.sync_id, NEW.user_id, 'upsert', NEW.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_subjects_sync_delete AFTER DELETE ON subjects
+WHEN OLD.synced_version >= 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'subject' AND record_id = OLD.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    SELECT 'subject', OLD.id, OLD.sync_id, OLD.user_id, 'delete', OLD.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE OLD.synced_version > 0;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_tasks_sync_id AFTER INSERT ON tasks
+WHEN NEW.sync_id IS NULL OR NEW.updated_at IS NULL
+BEGIN
+    UPDATE tasks SET sync_id = COALESCE(sync_id, lower(hex(randomblob(16)))),
+        updated_at = COALESCE(updated_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_tasks_sync_insert AFTER INSERT ON tasks
+WHEN NEW.synced_version = 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'task' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+    VALUES ('task', NEW.id, NEW.user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_tasks_sync_update AFTER UPDATE ON tasks
+WHEN NEW.synced_version = OLD.synced_version AND NEW.version = OLD.version
+    AND NEW.sync_id IS OLD.sync_id
+BEGIN
+    UPDATE tasks SET version = OLD.version + 1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id;
+    DELETE FROM sync_changes WHERE entity = 'task' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    VALUES ('task', NEW.id, NEW.sync_id, NEW.user_id, 'upsert', NEW.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_tasks_sync_delete AFTER DELETE ON tasks
+WHEN OLD.synced_version >= 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'task' AND record_id = OLD.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    SELECT 'task', OLD.id, OLD.sync_id, OLD.user_id, 'delete', OLD.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE OLD.synced_version > 0;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_pomodoro_sessions_sync_id AFTER INSERT ON pomodoro_sessions
+WHEN NEW.sync_id IS NULL OR NEW.updated_at IS NULL
+BEGIN
+    UPDATE pomodoro_sessions SET sync_id = COALESCE(sync_id, lower(hex(randomblob(16)))),
+        updated_at = COALESCE(updated_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_pomodoro_sessions_sync_insert AFTER INSERT ON pomodoro_sessions
+WHEN NEW.synced_version = 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'pomodoro' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+    VALUES ('pomodoro', NEW.id, NEW.user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_pomodoro_sessions_sync_update AFTER UPDATE ON pomodoro_sessions
+WHEN NEW.synced_version = OLD.synced_version AND NEW.version = OLD.version
+    AND NEW.sync_id IS OLD.sync_id
+BEGIN
+    UPDATE pomodoro_sessions SET version = OLD.version + 1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id;
+    DELETE FROM sync_changes WHERE entity = 'pomodoro' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    VALUES ('pomodoro', NEW.id, NEW.sync_id, NEW.user_id, 'upsert', NEW.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_pomodoro_sessions_sync_delete AFTER DELETE ON pomodoro_sessions
+WHEN OLD.synced_version >= 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'pomodoro' AND record_id = OLD.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    SELECT 'pomodoro', OLD.id, OLD.sync_id, OLD.user_id, 'delete', OLD.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE OLD.synced_version > 0;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_daily_reviews_sync_id AFTER INSERT ON daily_reviews
+WHEN NEW.sync_id IS NULL OR NEW.updated_at IS NULL
+BEGIN
+    UPDATE daily_reviews SET sync_id = COALESCE(sync_id, lower(hex(randomblob(16)))),
+        updated_at = COALESCE(updated_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_daily_reviews_sync_insert AFTER INSERT ON daily_reviews
+WHEN NEW.synced_version = 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'review' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+    VALUES ('review', NEW.id, NEW.user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_daily_reviews_sync_update AFTER UPDATE ON daily_reviews
+WHEN NEW.synced_version = OLD.synced_version AND NEW.version = OLD.version
+    AND NEW.sync_id IS OLD.sync_id
+BEGIN
+    UPDATE daily_reviews SET version = OLD.version + 1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id;
+    DELETE FROM sync_changes WHERE entity = 'review' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    VALUES ('review', NEW.id, NEW.sync_id, NEW.user_id, 'upsert', NEW.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_daily_reviews_sync_delete AFTER DELETE ON daily_reviews
+WHEN OLD.synced_version >= 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'review' AND record_id = OLD.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    SELECT 'review', OLD.id, OLD.sync_id, OLD.user_id, 'delete', OLD.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE OLD.synced_version > 0;
+END;
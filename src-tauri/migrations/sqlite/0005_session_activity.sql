@@ -0,0 +1,7 @@
+-- 会话活跃时间
+-- last_used_at 为会话最近一次使用的时间，会话每次使用时顺延过期时间；过期会话由后台任务定期清理。
+
+ALTER TABLE user_sessions ADD COLUMN last_used_at TEXT;
+UPDATE user_sessions SET last_used_at = created_at;
+CREATE INDEX IF NOT EXISTS idx_user_sessions_user ON user_sessions(user_id, last_used_at);
+CREATE INDEX IF NOT EXISTS idx_user_sessions_expires_at ON user_sessions(expires_at);
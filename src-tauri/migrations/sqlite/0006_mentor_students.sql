@@ -0,0 +1,11 @@
+-- 导师与学生分配
+-- 导师角色只能查看分配给自己的学生的统计与学习计划；删除任一用户时一并删除分配关系。
+
+CREATE TABLE IF NOT EXISTS mentor_students (
+    mentor_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    student_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    PRIMARY KEY (mentor_id, student_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_mentor_students_student ON mentor_students(student_id);
@@ -0,0 +1,23 @@
+-- 邀请码自助注册
+-- invite_codes 由管理员生成，包含预设角色、有效期和最大使用次数；invite_code_uses 记录每个邀请码注册的账户。
+
+CREATE TABLE IF NOT EXISTS invite_codes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    code TEXT NOT NULL UNIQUE,
+    role TEXT NOT NULL DEFAULT 'user',
+    max_uses INTEGER NOT NULL DEFAULT 1,
+    used_count INTEGER NOT NULL DEFAULT 0,
+    expires_at TEXT NOT NULL,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    revoked_at TEXT
+);
+
+CREATE TABLE IF NOT EXISTS invite_code_uses (
+    invite_code_id INTEGER NOT NULL REFERENCES invite_codes(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    used_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    PRIMARY KEY (invite_code_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_invite_code_uses_user ON invite_code_uses(user_id);
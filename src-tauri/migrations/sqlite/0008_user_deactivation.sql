@@ -0,0 +1,17 @@
+-- 停用用户与审计记录
+-- deactivated_at 非空表示账户已停用：禁止登录但保留全部数据。
+-- audit_events 记录管理操作，不设外键，删除用户后记录仍然保留。
+
+ALTER TABLE users ADD COLUMN deactivated_at TEXT;
+
+CREATE TABLE IF NOT EXISTS audit_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    actor_id INTEGER,
+    action TEXT NOT NULL,
+    target_user_id INTEGER,
+    metadata TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_created_at ON audit_events(created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_events_target ON audit_events(target_user_id, created_at);
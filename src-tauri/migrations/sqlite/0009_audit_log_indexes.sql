@@ -0,0 +1,5 @@
+-- 审计日志查询索引
+-- 审计日志按操作者或操作类型筛选并按时间倒序分页。
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_actor ON audit_events(actor_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_events_action ON audit_events(action, created_at);
@@ -0,0 +1,36 @@
+-- 重复任务
+-- task_series 保存重复任务的内容与 RRULE 形式的重复规则；每一次重复在首次查询当天任务时生成为 tasks 中的记录，
+-- 以 series_id 与 occurrence_date 对应到系列中的某一次，之后可单独完成、修改或删除。
+-- task_series_skips 记录被单独删除的重复，避免再次生成。
+
+CREATE TABLE IF NOT EXISTS task_series (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    subject_id INTEGER REFERENCES subjects(id) ON DELETE SET NULL,
+    start_date TEXT NOT NULL,
+    start_time TEXT NOT NULL,
+    end_time TEXT NOT NULL,
+    content TEXT NOT NULL,
+    alarm_enabled INTEGER NOT NULL DEFAULT 0,
+    alarm_time TEXT,
+    rrule TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_series_user ON task_series(user_id, start_date);
+
+CREATE TRIGGER IF NOT EXISTS trg_task_series_updated_at AFTER UPDATE ON task_series
+BEGIN
+    UPDATE task_series SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TABLE IF NOT EXISTS task_series_skips (
+    series_id INTEGER NOT NULL REFERENCES task_series(id) ON DELETE CASCADE,
+    occurrence_date TEXT NOT NULL,
+    PRIMARY KEY (series_id, occurrence_date)
+);
+
+ALTER TABLE tasks ADD COLUMN series_id INTEGER REFERENCES task_series(id) ON DELETE SET NULL;
+ALTER TABLE tasks ADD COLUMN occurrence_date TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tasks_series_occurrence ON tasks(series_id, occurrence_date);
@@ -0,0 +1,10 @@
+-- 未完成任务顺延
+-- tasks.carried_from_id 指向被顺延的原任务；原任务的状态改为 failed 或 rolled_over。
+-- study_preferences.carry_over_mode 为空表示不自动顺延，否则为 move 或 copy；
+-- last_carry_over_date 记录最近一次自动顺延的日期，每天只执行一次。
+
+ALTER TABLE tasks ADD COLUMN carried_from_id INTEGER REFERENCES tasks(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_tasks_carried_from ON tasks(carried_from_id);
+
+ALTER TABLE study_preferences ADD COLUMN carry_over_mode TEXT;
+ALTER TABLE study_preferences ADD COLUMN last_carry_over_date TEXT;
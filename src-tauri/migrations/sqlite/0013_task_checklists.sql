@@ -0,0 +1,16 @@
+-- 任务清单
+-- task_checklist_items 保存任务下按 position 排列的清单项及各自的完成状态；
+-- tasks.progress 为清单的完成百分比 (0-100，没有清单项时为 0)，随清单项的变化更新，用于统计部分完成的任务。
+
+CREATE TABLE IF NOT EXISTS task_checklist_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    completed INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_checklist_items_task ON task_checklist_items(task_id, position);
+
+ALTER TABLE tasks ADD COLUMN progress INTEGER NOT NULL DEFAULT 0;
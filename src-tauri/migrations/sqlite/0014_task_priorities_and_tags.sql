@@ -0,0 +1,25 @@
+-- 任务优先级与标签
+-- tasks.priority / task_series.priority 为任务的优先级 (low、normal、high)，重复任务生成的任务沿用系列的优先级。
+-- task_tags 保存用户自定义的标签 (同一用户内名称唯一)，task_tag_links 为任务与标签的多对多关联，
+-- 删除任务或标签时关联随之删除。
+
+ALTER TABLE tasks ADD COLUMN priority TEXT NOT NULL DEFAULT 'normal';
+
+ALTER TABLE task_series ADD COLUMN priority TEXT NOT NULL DEFAULT 'normal';
+
+CREATE TABLE IF NOT EXISTS task_tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    color TEXT NOT NULL DEFAULT '#6B7280',
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    UNIQUE (user_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS task_tag_links (
+    task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES task_tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (task_id, tag_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_tag_links_tag ON task_tag_links(tag_id);
@@ -0,0 +1,16 @@
+-- 任务状态变更记录
+-- tasks.status_reason 为最近一次状态变更的原因 (标记为未完成时必填，如 时间不够、太难、状态差)。
+-- task_status_history 按时间记录任务的每次状态变更，删除任务时随之删除。
+
+ALTER TABLE tasks ADD COLUMN status_reason TEXT;
+
+CREATE TABLE IF NOT EXISTS task_status_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    from_status TEXT NOT NULL,
+    to_status TEXT NOT NULL,
+    reason TEXT,
+    changed_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_status_history_task ON task_status_history(task_id, id);
@@ -0,0 +1,7 @@
+-- 强制修改初始密码
+-- must_change_password 非零时账户只能修改密码或退出登录。
+-- 初始化时创建的默认管理员 (admin/admin123) 仍使用初始密码时需要先修改密码。
+
+ALTER TABLE users ADD COLUMN must_change_password INTEGER NOT NULL DEFAULT 0;
+
+UPDATE users SET must_change_password = 1 WHERE username = 'admin' AND password = 'admin123';
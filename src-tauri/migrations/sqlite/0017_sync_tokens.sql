@@ -0,0 +1,5 @@
+-- 同步账户验证
+-- 用户输入服务器账户密码验证通过后，服务器为本机发放同步令牌，保存在 sync_state.server_token 中；
+-- 之后每次同步 (包括后台同步) 都凭该令牌访问服务器上的同名账户，没有令牌的用户不会同步。
+
+ALTER TABLE sync_state ADD COLUMN server_token TEXT;
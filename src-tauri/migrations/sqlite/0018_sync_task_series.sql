@@ -0,0 +1,114 @@
+-- 同步重复任务
+-- task_series 与 task_series_skips 加入本地优先同步 (字段含义同 0003)，tasks 的 series_id / occurrence_date 随任务同步。
+-- task_series_skips 重建为带自增 id 的表以便记录待同步变更，user_id 在插入时由触发器按所属的系列填写。
+
+-- task_series
+-- 原有的 updated_at 触发器会覆盖同步写入的时间戳，由下面的同步触发器取代
+DROP TRIGGER IF EXISTS trg_task_series_updated_at;
+
+ALTER TABLE task_series ADD COLUMN sync_id TEXT;
+ALTER TABLE task_series ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE task_series ADD COLUMN synced_version INTEGER NOT NULL DEFAULT 0;
+UPDATE task_series SET sync_id = lower(hex(randomblob(16)));
+CREATE UNIQUE INDEX IF NOT EXISTS idx_task_series_sync_id ON task_series(sync_id);
+
+-- task_series_skips
+CREATE TABLE task_series_skips_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    series_id INTEGER NOT NULL REFERENCES task_series(id) ON DELETE CASCADE,
+    occurrence_date TEXT NOT NULL,
+    user_id INTEGER REFERENCES users(id) ON DELETE CASCADE,
+    sync_id TEXT,
+    version INTEGER NOT NULL DEFAULT 1,
+    synced_version INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT,
+    UNIQUE (series_id, occurrence_date)
+);
+
+INSERT INTO task_series_skips_new (series_id, occurrence_date, user_id, sync_id, updated_at)
+SELECT k.series_id, k.occurrence_date, s.user_id, lower(hex(randomblob(16))), s.updated_at
+FROM task_series_skips k JOIN task_series s ON s.id = k.series_id;
+
+DROP TABLE task_series_skips;
+ALTER TABLE task_series_skips_new RENAME TO task_series_skips;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_task_series_skips_sync_id ON task_series_skips(sync_id);
+
+-- 已有数据全部加入待同步队列
+
+INSERT OR IGNORE INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+SELECT 'series', id, user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now') FROM task_series;
+
+INSERT OR IGNORE INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+SELECT 'series_skip', id, user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now') FROM task_series_skips;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_series_sync_id AFTER INSERT ON task_series
+WHEN NEW.sync_id IS NULL OR NEW.updated_at IS NULL
+BEGIN
+    UPDATE task_series SET sync_id = COALESCE(sync_id, lower(hex(randomblob(16)))),
+        updated_at = COALESCE(updated_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_series_sync_insert AFTER INSERT ON task_series
+WHEN NEW.synced_version = 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'series' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+    VALUES ('series', NEW.id, NEW.user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_series_sync_update AFTER UPDATE ON task_series
+WHEN NEW.synced_version = OLD.synced_version AND NEW.version = OLD.version
+    AND NEW.sync_id IS OLD.sync_id
+BEGIN
+    UPDATE task_series SET version = OLD.version + 1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id;
+    DELETE FROM sync_changes WHERE entity = 'series' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    VALUES ('series', NEW.id, NEW.sync_id, NEW.user_id, 'upsert', NEW.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_series_sync_delete AFTER DELETE ON task_series
+WHEN OLD.synced_version >= 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'series' AND record_id = OLD.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    SELECT 'series', OLD.id, OLD.sync_id, OLD.user_id, 'delete', OLD.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE OLD.synced_version > 0;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_series_skips_sync_id AFTER INSERT ON task_series_skips
+WHEN NEW.sync_id IS NULL OR NEW.updated_at IS NULL OR NEW.user_id IS NULL
+BEGIN
+    UPDATE task_series_skips SET sync_id = COALESCE(sync_id, lower(hex(randomblob(16)))),
+        updated_at = COALESCE(updated_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+        user_id = COALESCE(user_id, (SELECT user_id FROM task_series WHERE id = NEW.series_id))
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_series_skips_sync_insert AFTER INSERT ON task_series_skips
+WHEN NEW.synced_version = 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'series_skip' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+    VALUES ('series_skip', NEW.id, COALESCE(NEW.user_id, (SELECT user_id FROM task_series WHERE id = NEW.series_id)),
+        'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_series_skips_sync_update AFTER UPDATE ON task_series_skips
+WHEN NEW.synced_version = OLD.synced_version AND NEW.version = OLD.version
+    AND NEW.sync_id IS OLD.sync_id
+BEGIN
+    UPDATE task_series_skips SET version = OLD.version + 1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id;
+    DELETE FROM sync_changes WHERE entity = 'series_skip' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    VALUES ('series_skip', NEW.id, NEW.sync_id, NEW.user_id, 'upsert', NEW.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_series_skips_sync_delete AFTER DELETE ON task_series_skips
+WHEN OLD.synced_version >= 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'series_skip' AND record_id = OLD.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    SELECT 'series_skip', OLD.id, OLD.sync_id, OLD.user_id, 'delete', OLD.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE OLD.synced_version > 0;
+END;
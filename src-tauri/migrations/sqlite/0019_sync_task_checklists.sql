@@ -0,0 +1,53 @@
+-- 同步任务清单
+-- task_checklist_items 加入本地优先同步 (字段含义同 0003)，tasks.progress 随任务同步。
+-- 清单项原来没有 user_id，插入时由触发器按所属的任务填写。
+
+ALTER TABLE task_checklist_items ADD COLUMN user_id INTEGER REFERENCES users(id) ON DELETE CASCADE;
+ALTER TABLE task_checklist_items ADD COLUMN sync_id TEXT;
+ALTER TABLE task_checklist_items ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE task_checklist_items ADD COLUMN synced_version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE task_checklist_items ADD COLUMN updated_at TEXT;
+UPDATE task_checklist_items SET sync_id = lower(hex(randomblob(16))), updated_at = created_at,
+    user_id = (SELECT user_id FROM tasks WHERE id = task_checklist_items.task_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_task_checklist_items_sync_id ON task_checklist_items(sync_id);
+
+-- 已有数据全部加入待同步队列
+INSERT OR IGNORE INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+SELECT 'checklist_item', id, user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now') FROM task_checklist_items;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_checklist_items_sync_id AFTER INSERT ON task_checklist_items
+WHEN NEW.sync_id IS NULL OR NEW.updated_at IS NULL OR NEW.user_id IS NULL
+BEGIN
+    UPDATE task_checklist_items SET sync_id = COALESCE(sync_id, lower(hex(randomblob(16)))),
+        updated_at = COALESCE(updated_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+        user_id = COALESCE(user_id, (SELECT user_id FROM tasks WHERE id = NEW.task_id))
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_checklist_items_sync_insert AFTER INSERT ON task_checklist_items
+WHEN NEW.synced_version = 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'checklist_item' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+    VALUES ('checklist_item', NEW.id, COALESCE(NEW.user_id, (SELECT user_id FROM tasks WHERE id = NEW.task_id)),
+        'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_checklist_items_sync_update AFTER UPDATE ON task_checklist_items
+WHEN NEW.synced_version = OLD.synced_version AND NEW.version = OLD.version
+    AND NEW.sync_id IS OLD.sync_id
+BEGIN
+    UPDATE task_checklist_items SET version = OLD.version + 1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id;
+    DELETE FROM sync_changes WHERE entity = 'checklist_item' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    VALUES ('checklist_item', NEW.id, NEW.sync_id, NEW.user_id, 'upsert', NEW.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_checklist_items_sync_delete AFTER DELETE ON task_checklist_items
+WHEN OLD.synced_version >= 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'checklist_item' AND record_id = OLD.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    SELECT 'checklist_item', OLD.id, OLD.sync_id, OLD.user_id, 'delete', OLD.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE OLD.synced_version > 0;
+END;
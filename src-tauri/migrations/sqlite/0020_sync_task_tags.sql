@@ -0,0 +1,113 @@
+-- 同步任务标签
+-- task_tags 与 task_tag_links 加入本地优先同步 (字段含义同 0003)，tasks / task_series 的 priority 随记录同步。
+-- task_tag_links 重建为带自增 id 的表以便记录待同步变更，user_id 在插入时由触发器按所属的任务填写。
+
+-- task_tags
+ALTER TABLE task_tags ADD COLUMN sync_id TEXT;
+ALTER TABLE task_tags ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE task_tags ADD COLUMN synced_version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE task_tags ADD COLUMN updated_at TEXT;
+UPDATE task_tags SET sync_id = lower(hex(randomblob(16))), updated_at = created_at;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_task_tags_sync_id ON task_tags(sync_id);
+
+-- task_tag_links
+CREATE TABLE task_tag_links_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES task_tags(id) ON DELETE CASCADE,
+    user_id INTEGER REFERENCES users(id) ON DELETE CASCADE,
+    sync_id TEXT,
+    version INTEGER NOT NULL DEFAULT 1,
+    synced_version INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT,
+    UNIQUE (task_id, tag_id)
+);
+
+INSERT INTO task_tag_links_new (task_id, tag_id, user_id, sync_id, updated_at)
+SELECT l.task_id, l.tag_id, t.user_id, lower(hex(randomblob(16))), strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+FROM task_tag_links l JOIN tasks t ON t.id = l.task_id;
+
+DROP TABLE task_tag_links;
+ALTER TABLE task_tag_links_new RENAME TO task_tag_links;
+CREATE INDEX IF NOT EXISTS idx_task_tag_links_tag ON task_tag_links(tag_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_task_tag_links_sync_id ON task_tag_links(sync_id);
+
+-- 已有数据全部加入待同步队列
+
+INSERT OR IGNORE INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+SELECT 'tag', id, user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now') FROM task_tags;
+
+INSERT OR IGNORE INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+SELECT 'tag_link', id, user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now') FROM task_tag_links;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_tags_sync_id AFTER INSERT ON task_tags
+WHEN NEW.sync_id IS NULL OR NEW.updated_at IS NULL
+BEGIN
+    UPDATE task_tags SET sync_id = COALESCE(sync_id, lower(hex(randomblob(16)))),
+        updated_at = COALESCE(updated_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_tags_sync_insert AFTER INSERT ON task_tags
+WHEN NEW.synced_version = 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'tag' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+    VALUES ('tag', NEW.id, NEW.user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_tags_sync_update AFTER UPDATE ON task_tags
+WHEN NEW.synced_version = OLD.synced_version AND NEW.version = OLD.version
+    AND NEW.sync_id IS OLD.sync_id
+BEGIN
+    UPDATE task_tags SET version = OLD.version + 1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id;
+    DELETE FROM sync_changes WHERE entity = 'tag' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    VALUES ('tag', NEW.id, NEW.sync_id, NEW.user_id, 'upsert', NEW.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_tags_sync_delete AFTER DELETE ON task_tags
+WHEN OLD.synced_version >= 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'tag' AND record_id = OLD.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    SELECT 'tag', OLD.id, OLD.sync_id, OLD.user_id, 'delete', OLD.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE OLD.synced_version > 0;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_tag_links_sync_id AFTER INSERT ON task_tag_links
+WHEN NEW.sync_id IS NULL OR NEW.updated_at IS NULL OR NEW.user_id IS NULL
+BEGIN
+    UPDATE task_tag_links SET sync_id = COALESCE(sync_id, lower(hex(randomblob(16)))),
+        updated_at = COALESCE(updated_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+        user_id = COALESCE(user_id, (SELECT user_id FROM tasks WHERE id = NEW.task_id))
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_tag_links_sync_insert AFTER INSERT ON task_tag_links
+WHEN NEW.synced_version = 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'tag_link' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+    VALUES ('tag_link', NEW.id, COALESCE(NEW.user_id, (SELECT user_id FROM tasks WHERE id = NEW.task_id)),
+        'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_tag_links_sync_update AFTER UPDATE ON task_tag_links
+WHEN NEW.synced_version = OLD.synced_version AND NEW.version = OLD.version
+    AND NEW.sync_id IS OLD.sync_id
+BEGIN
+    UPDATE task_tag_links SET version = OLD.version + 1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id;
+    DELETE FROM sync_changes WHERE entity = 'tag_link' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    VALUES ('tag_link', NEW.id, NEW.sync_id, NEW.user_id, 'upsert', NEW.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_tag_links_sync_delete AFTER DELETE ON task_tag_links
+WHEN OLD.synced_version >= 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'tag_link' AND record_id = OLD.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    SELECT 'tag_link', OLD.id, OLD.sync_id, OLD.user_id, 'delete', OLD.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE OLD.synced_version > 0;
+END;
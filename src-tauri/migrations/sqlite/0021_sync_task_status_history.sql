@@ -0,0 +1,53 @@
+-- 同步任务状态变更记录
+-- task_status_history 加入本地优先同步 (字段含义同 0003)，tasks.status_reason 随任务同步。
+-- 变更记录原来没有 user_id，插入时由触发器按所属的任务填写。
+
+ALTER TABLE task_status_history ADD COLUMN user_id INTEGER REFERENCES users(id) ON DELETE CASCADE;
+ALTER TABLE task_status_history ADD COLUMN sync_id TEXT;
+ALTER TABLE task_status_history ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE task_status_history ADD COLUMN synced_version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE task_status_history ADD COLUMN updated_at TEXT;
+UPDATE task_status_history SET sync_id = lower(hex(randomblob(16))), updated_at = changed_at,
+    user_id = (SELECT user_id FROM tasks WHERE id = task_status_history.task_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_task_status_history_sync_id ON task_status_history(sync_id);
+
+-- 已有数据全部加入待同步队列
+INSERT OR IGNORE INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+SELECT 'status_change', id, user_id, 'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now') FROM task_status_history;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_status_history_sync_id AFTER INSERT ON task_status_history
+WHEN NEW.sync_id IS NULL OR NEW.updated_at IS NULL OR NEW.user_id IS NULL
+BEGIN
+    UPDATE task_status_history SET sync_id = COALESCE(sync_id, lower(hex(randomblob(16)))),
+        updated_at = COALESCE(updated_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+        user_id = COALESCE(user_id, (SELECT user_id FROM tasks WHERE id = NEW.task_id))
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_status_history_sync_insert AFTER INSERT ON task_status_history
+WHEN NEW.synced_version = 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'status_change' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, user_id, operation, changed_at)
+    VALUES ('status_change', NEW.id, COALESCE(NEW.user_id, (SELECT user_id FROM tasks WHERE id = NEW.task_id)),
+        'upsert', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_status_history_sync_update AFTER UPDATE ON task_status_history
+WHEN NEW.synced_version = OLD.synced_version AND NEW.version = OLD.version
+    AND NEW.sync_id IS OLD.sync_id
+BEGIN
+    UPDATE task_status_history SET version = OLD.version + 1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = NEW.id;
+    DELETE FROM sync_changes WHERE entity = 'status_change' AND record_id = NEW.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    VALUES ('status_change', NEW.id, NEW.sync_id, NEW.user_id, 'upsert', NEW.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_status_history_sync_delete AFTER DELETE ON task_status_history
+WHEN OLD.synced_version >= 0
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'status_change' AND record_id = OLD.id;
+    INSERT INTO sync_changes (entity, record_id, sync_id, user_id, operation, base_version, changed_at)
+    SELECT 'status_change', OLD.id, OLD.sync_id, OLD.user_id, 'delete', OLD.synced_version, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE OLD.synced_version > 0;
+END;
@@ -1,34 +1,47 @@
 // 管理员命令
-use crate::models::{UserResponse, CreateUserInput, UserRole};
-use crate::services::{auth_service, user_service};
+use crate::commands::auth::{authorize, SessionState};
+use crate::config;
+use crate::error::AppError;
+use crate::models::{
+    AuditLogPage, AuditLogQuery, CreateInviteCodeInput, CreateUserInput, InviteCodeResponse, LoginAttempt,
+    LoginThrottle, Permission, UserArchiveResponse, UserImportReport, UserResponse,
+};
+use crate::repositories::SqlRepository;
+use crate::services::stats_service::{self, UserActivity};
+use crate::services::{
+    audit_service, auth_service, invite_service, mentor_service, two_factor_service, user_csv_service,
+    user_service,
+};
+use chrono::{Local, NaiveDate};
+use tauri::State;
 
-/// 获取所有用户 (仅管理员)
+/// 获取所有用户 (需要用户管理权限)
 #[tauri::command]
-pub async fn get_all_users(session_token: String) -> Result<Vec<UserResponse>, String> {
-    // 验证管理员权限
-    let user = auth_service::validate_session(&session_token).await?;
-    if user.role != UserRole::Admin {
-        return Err("权限不足".to_string());
-    }
-    
-    user_service::get_all_users().await
+pub async fn get_all_users(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<Vec<UserResponse>, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    authorize(&session, session_token, Permission::UsersManage).await?;
+
+    user_service::get_all_users(&repo).await
 }
 
-/// 创建用户 (仅管理员)
+/// 创建用户 (需要用户管理权限)
 #[tauri::command]
 pub async fn create_user(
-    session_token: String,
+    session_token: Option<String>,
     username: String,
     password: String,
     display_name: String,
     role: String,
-) -> Result<UserResponse, String> {
-    // 验证管理员权限
-    let user = auth_service::validate_session(&session_token).await?;
-    if user.role != UserRole::Admin {
-        return Err("权限不足".to_string());
-    }
-    
+    session: State<'_, SessionState>,
+) -> Result<UserResponse, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    let user = authorize(&session, session_token, Permission::UsersManage).await?;
+
     let input = CreateUserInput {
         username,
         password,
@@ -36,38 +49,301 @@ pub async fn create_user(
         role,
     };
     
-    user_service::create_user(input).await
+    user_service::create_user(&repo, Some(user.id), input).await
 }
 
-/// 删除用户 (仅管理员)
+/// 获取所有用户在时间范围内的学习活跃情况 (需要用户管理权限)
 #[tauri::command]
-pub async fn delete_user(session_token: String, user_id: i64) -> Result<(), String> {
-    // 验证管理员权限
-    let user = auth_service::validate_session(&session_token).await?;
-    if user.role != UserRole::Admin {
-        return Err("权限不足".to_string());
-    }
-    
-    // 不能删除自己
-    if user.id == user_id {
-        return Err("不能删除自己".to_string());
-    }
-    
-    user_service::delete_user(user_id).await
+pub async fn get_user_activity(
+    session_token: Option<String>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    session: State<'_, SessionState>,
+) -> Result<Vec<UserActivity>, AppError> {
+    // 验证用户管理权限
+    authorize(&session, session_token, Permission::UsersManage).await?;
+
+    stats_service::get_user_activity(start_date, end_date, Local::now().date_naive()).await
+}
+
+/// 从 CSV 批量导入用户 (需要用户管理权限，试运行时只返回校验结果)
+#[tauri::command]
+pub async fn import_users_csv(
+    session_token: Option<String>,
+    csv: String,
+    dry_run: bool,
+    session: State<'_, SessionState>,
+) -> Result<UserImportReport, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    let user = authorize(&session, session_token, Permission::UsersManage).await?;
+
+    user_csv_service::import_users(&repo, user.id, &csv, dry_run).await
+}
+
+/// 导出用户列表为 CSV (需要用户管理权限)
+#[tauri::command]
+pub async fn export_users_csv(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<String, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    authorize(&session, session_token, Permission::UsersManage).await?;
+
+    user_csv_service::export_users(&repo).await
+}
+
+/// 停用用户 (需要用户管理权限)
+#[tauri::command]
+pub async fn deactivate_user(
+    session_token: Option<String>,
+    user_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<UserResponse, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    let user = authorize(&session, session_token, Permission::UsersManage).await?;
+
+    user_service::deactivate_user(&repo, user.id, user_id).await
 }
 
-/// 重置用户密码 (仅管理员)
+/// 重新启用用户 (需要用户管理权限)
+#[tauri::command]
+pub async fn reactivate_user(
+    session_token: Option<String>,
+    user_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<UserResponse, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    let user = authorize(&session, session_token, Permission::UsersManage).await?;
+
+    user_service::reactivate_user(&repo, user.id, user_id).await
+}
+
+/// 永久删除已停用的用户，删除前导出全部数据 (需要用户管理权限)
+#[tauri::command]
+pub async fn delete_user(
+    session_token: Option<String>,
+    user_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<UserArchiveResponse, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    let user = authorize(&session, session_token, Permission::UsersManage).await?;
+
+    user_service::delete_user(&repo, user.id, user_id, &config::export_dir()?).await
+}
+
+/// 重置用户密码 (需要用户管理权限)
 #[tauri::command]
 pub async fn reset_user_password(
-    session_token: String,
+    session_token: Option<String>,
     user_id: i64,
     new_password: String,
-) -> Result<(), String> {
-    // 验证管理员权限
-    let user = auth_service::validate_session(&session_token).await?;
-    if user.role != UserRole::Admin {
-        return Err("权限不足".to_string());
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    let user = authorize(&session, session_token, Permission::UsersManage).await?;
+
+    user_service::reset_user_password(&repo, user.id, user_id, &new_password).await
+}
+
+/// 重置用户的两步验证 (需要用户管理权限，用户丢失身份验证器时使用)
+#[tauri::command]
+pub async fn reset_user_two_factor(
+    session_token: Option<String>,
+    user_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    let user = authorize(&session, session_token, Permission::UsersManage).await?;
+
+    two_factor_service::reset_for_user(&repo, user.id, user_id).await
+}
+
+/// 获取被临时锁定的账户 (需要用户管理权限)
+#[tauri::command]
+pub async fn get_locked_accounts(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<Vec<LoginThrottle>, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    authorize(&session, session_token, Permission::UsersManage).await?;
+
+    auth_service::get_locked_accounts(&repo).await
+}
+
+/// 解除账户锁定 (需要用户管理权限)
+#[tauri::command]
+pub async fn unlock_account(
+    session_token: Option<String>,
+    username: String,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    let user = authorize(&session, session_token, Permission::UsersManage).await?;
+
+    auth_service::unlock_account(&repo, user.id, &username).await
+}
+
+/// 获取最近的登录记录 (需要用户管理权限)
+#[tauri::command]
+pub async fn get_login_attempts(
+    session_token: Option<String>,
+    username: Option<String>,
+    limit: Option<i64>,
+    session: State<'_, SessionState>,
+) -> Result<Vec<LoginAttempt>, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    authorize(&session, session_token, Permission::UsersManage).await?;
+
+    auth_service::get_login_attempts(&repo, username.as_deref(), limit.unwrap_or(100)).await
+}
+
+/// 分页查询审计日志，可按操作者、被操作用户、操作和时间范围过滤 (需要用户管理权限)
+#[tauri::command]
+pub async fn get_audit_log(
+    session_token: Option<String>,
+    query: Option<AuditLogQuery>,
+    session: State<'_, SessionState>,
+) -> Result<AuditLogPage, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    authorize(&session, session_token, Permission::UsersManage).await?;
+
+    audit_service::get_audit_log(&repo, query.unwrap_or_default()).await
+}
+
+/// 修改用户角色 (需要用户管理权限)
+#[tauri::command]
+pub async fn set_user_role(
+    session_token: Option<String>,
+    user_id: i64,
+    role: String,
+    session: State<'_, SessionState>,
+) -> Result<UserResponse, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    let user = authorize(&session, session_token, Permission::UsersManage).await?;
+
+    // 不能修改自己的角色，避免失去管理权限
+    if user.id == user_id {
+        return Err(AppError::Validation("不能修改自己的角色".to_string()));
     }
-    
-    user_service::reset_user_password(user_id, &new_password).await
+
+    user_service::set_user_role(&repo, user.id, user_id, &role).await
+}
+
+/// 获取导师的学生 (需要用户管理权限)
+#[tauri::command]
+pub async fn get_mentor_students(
+    session_token: Option<String>,
+    mentor_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<Vec<UserResponse>, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    authorize(&session, session_token, Permission::UsersManage).await?;
+
+    mentor_service::get_students(&repo, mentor_id).await
+}
+
+/// 将学生分配给导师 (需要用户管理权限)
+#[tauri::command]
+pub async fn assign_student(
+    session_token: Option<String>,
+    mentor_id: i64,
+    student_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    authorize(&session, session_token, Permission::UsersManage).await?;
+
+    mentor_service::assign_student(&repo, mentor_id, student_id).await
+}
+
+/// 取消学生分配 (需要用户管理权限)
+#[tauri::command]
+pub async fn unassign_student(
+    session_token: Option<String>,
+    mentor_id: i64,
+    student_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    authorize(&session, session_token, Permission::UsersManage).await?;
+
+    mentor_service::unassign_student(&repo, mentor_id, student_id).await
+}
+
+/// 创建邀请码 (需要用户管理权限)
+#[tauri::command]
+pub async fn create_invite_code(
+    session_token: Option<String>,
+    role: String,
+    max_uses: i32,
+    expires_in_days: i64,
+    session: State<'_, SessionState>,
+) -> Result<InviteCodeResponse, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    let user = authorize(&session, session_token, Permission::UsersManage).await?;
+
+    let input = CreateInviteCodeInput {
+        role,
+        max_uses,
+        expires_in_days,
+    };
+
+    invite_service::create_invite_code(&repo, user.id, input).await
+}
+
+/// 获取全部邀请码 (需要用户管理权限)
+#[tauri::command]
+pub async fn get_invite_codes(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<Vec<InviteCodeResponse>, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    authorize(&session, session_token, Permission::UsersManage).await?;
+
+    invite_service::get_invite_codes(&repo).await
+}
+
+/// 撤销邀请码 (需要用户管理权限)
+#[tauri::command]
+pub async fn revoke_invite_code(
+    session_token: Option<String>,
+    invite_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    authorize(&session, session_token, Permission::UsersManage).await?;
+
+    invite_service::revoke_invite_code(&repo, invite_id).await
+}
+
+/// 获取通过邀请码注册的账户 (需要用户管理权限)
+#[tauri::command]
+pub async fn get_invite_code_users(
+    session_token: Option<String>,
+    invite_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<Vec<UserResponse>, AppError> {
+    // 验证用户管理权限
+    let repo = SqlRepository::current()?;
+    authorize(&session, session_token, Permission::UsersManage).await?;
+
+    invite_service::get_invite_code_users(&repo, invite_id).await
 }
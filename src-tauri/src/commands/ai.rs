@@ -1,23 +1,30 @@
 // AI 命令
+use crate::commands::auth::{current_user, SessionState};
+use crate::error::AppError;
 use crate::models::{AIConfigResponse, SaveAIConfigInput, AIContext, TaskSuggestion};
-use crate::services::{auth_service, ai_service};
+use crate::services::ai_service;
+use tauri::State;
 
 /// 获取 AI 配置
 #[tauri::command]
-pub async fn get_ai_config(session_token: String) -> Result<AIConfigResponse, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+pub async fn get_ai_config(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<AIConfigResponse, AppError> {
+    let user = current_user(&session, session_token).await?;
     ai_service::get_ai_config(user.id).await
 }
 
 /// 保存 AI 配置
 #[tauri::command]
 pub async fn save_ai_config(
-    session_token: String,
+    session_token: Option<String>,
     api_key: String,
     model_name: Option<String>,
     api_endpoint: Option<String>,
-) -> Result<AIConfigResponse, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+    session: State<'_, SessionState>,
+) -> Result<AIConfigResponse, AppError> {
+    let user = current_user(&session, session_token).await?;
     let input = SaveAIConfigInput {
         api_key,
         model_name,
@@ -28,29 +35,34 @@ pub async fn save_ai_config(
 
 /// 测试 AI 连接
 #[tauri::command]
-pub async fn test_ai_connection(session_token: String) -> Result<bool, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+pub async fn test_ai_connection(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<bool, AppError> {
+    let user = current_user(&session, session_token).await?;
     ai_service::test_ai_connection(user.id).await
 }
 
 /// 生成 AI 计划
 #[tauri::command]
 pub async fn generate_ai_plan(
-    session_token: String,
+    session_token: Option<String>,
     context: AIContext,
     model_name: Option<String>,
-) -> Result<Vec<TaskSuggestion>, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+    session: State<'_, SessionState>,
+) -> Result<Vec<TaskSuggestion>, AppError> {
+    let user = current_user(&session, session_token).await?;
     ai_service::generate_ai_plan(user.id, context, model_name).await
 }
 
 /// AI 聊天答疑
 #[tauri::command]
 pub async fn ai_chat(
-    session_token: String,
+    session_token: Option<String>,
     message: String,
     history: Vec<crate::models::ChatMessage>,
-) -> Result<String, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+    session: State<'_, SessionState>,
+) -> Result<String, AppError> {
+    let user = current_user(&session, session_token).await?;
     ai_service::ai_chat(user.id, message, history).await
 }
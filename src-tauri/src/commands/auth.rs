@@ -1,9 +1,15 @@
 // 认证命令
-use crate::models::{LoginResponse, UserResponse};
-use crate::services::auth_service;
-use std::sync::Mutex;
+use crate::config;
+use crate::error::AppError;
+use crate::models::{
+    LoginResponse, LoginResult, Permission, RegisterInput, SessionResponse, User, UserResponse,
+};
+use crate::repositories::SqlRepository;
+use crate::services::{auth_service, invite_service, two_factor_service};
+use std::sync::{Mutex, MutexGuard};
+use tauri::State;
 
-/// 当前会话状态
+/// 当前会话状态 (登录后由后端保存会话令牌，退出登录时清除)
 pub struct SessionState {
     pub session_token: Mutex<Option<String>>,
 }
@@ -16,49 +22,199 @@ impl Default for SessionState {
     }
 }
 
-/// 登录命令
+impl SessionState {
+    fn lock(&self) -> MutexGuard<'_, Option<String>> {
+        self.session_token.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 后端保存的会话令牌
+    pub fn get(&self) -> Option<String> {
+        self.lock().clone()
+    }
+
+    /// 保存会话令牌
+    pub fn set(&self, session_token: String) {
+        *self.lock() = Some(session_token);
+    }
+
+    /// 清除会话令牌 (仅当保存的是指定令牌时)
+    pub fn clear(&self, session_token: &str) {
+        let mut saved = self.lock();
+        if saved.as_deref() == Some(session_token) {
+            *saved = None;
+        }
+    }
+
+    /// 解析命令使用的会话令牌：显式传入的令牌优先 (多窗口场景)，否则使用后端保存的会话
+    pub fn resolve(&self, session_token: Option<String>) -> Result<String, AppError> {
+        session_token
+            .filter(|t| !t.is_empty())
+            .or_else(|| self.get())
+            .ok_or_else(|| AppError::Unauthorized("未登录".to_string()))
+    }
+}
+
+/// 验证会话并返回当前用户 (各命令开头调用，未传令牌时使用后端保存的会话)
+/// (需要修改初始密码的账户只能修改密码、查看登录会话或退出登录)
+pub(crate) async fn current_user(session: &SessionState, session_token: Option<String>) -> Result<User, AppError> {
+    let session_token = session.resolve(session_token)?;
+    let user = auth_service::validate_session(&SqlRepository::current()?, &session_token).await?;
+    auth_service::require_password_changed(&user)?;
+    Ok(user)
+}
+
+/// 统一的授权检查：验证会话并要求当前用户拥有指定权限
+/// (安全策略要求管理员启用两步验证时，未启用的管理员只能访问个人设置)
+pub(crate) async fn authorize(
+    session: &SessionState,
+    session_token: Option<String>,
+    permission: Permission,
+) -> Result<User, AppError> {
+    let user = current_user(session, session_token).await?;
+    auth_service::require_permission(&user, permission)?;
+    let require_admin_two_factor = config::load().unwrap_or_default().security.require_admin_two_factor;
+    two_factor_service::check_policy(&SqlRepository::current()?, &user, require_admin_two_factor).await?;
+    Ok(user)
+}
+
+/// 登录命令 (登录成功后由后端保存会话，需要两步验证时返回登录挑战)
 #[tauri::command]
-pub async fn login(username: String, password: String) -> Result<LoginResponse, String> {
-    auth_service::login(&username, &password).await
+pub async fn login(
+    username: String,
+    password: String,
+    session: State<'_, SessionState>,
+) -> Result<LoginResult, AppError> {
+    let repo = SqlRepository::current()?;
+    let result = auth_service::login(&repo, &username, &password).await?;
+    if let LoginResult::Authenticated(response) = &result {
+        session.set(response.session_token.clone());
+    }
+    Ok(result)
+}
+
+/// 提交两步验证码完成登录 (验证通过后由后端保存会话)
+#[tauri::command]
+pub async fn verify_login_code(
+    challenge_token: String,
+    code: String,
+    session: State<'_, SessionState>,
+) -> Result<LoginResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let response = auth_service::verify_login_code(&repo, &challenge_token, &code).await?;
+    session.set(response.session_token.clone());
+    Ok(response)
+}
+
+/// 使用邀请码注册命令 (注册成功后直接登录)
+#[tauri::command]
+pub async fn register_with_invite(
+    invite_code: String,
+    username: String,
+    password: String,
+    display_name: String,
+    session: State<'_, SessionState>,
+) -> Result<LoginResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let input = RegisterInput {
+        invite_code,
+        username,
+        password,
+        display_name,
+    };
+    let response = invite_service::register_with_invite(&repo, input).await?;
+    session.set(response.session_token.clone());
+    Ok(response)
 }
 
 /// 退出登录命令
 #[tauri::command]
-pub async fn logout(session_token: String) -> Result<(), String> {
-    auth_service::logout(&session_token).await
+pub async fn logout(session_token: Option<String>, session: State<'_, SessionState>) -> Result<(), AppError> {
+    let session_token = session.resolve(session_token)?;
+    let repo = SqlRepository::current()?;
+    auth_service::logout(&repo, &session_token).await?;
+    session.clear(&session_token);
+    Ok(())
 }
 
 /// 修改密码命令
 #[tauri::command]
 pub async fn change_password(
-    session_token: String,
+    session_token: Option<String>,
     current_password: String,
     new_password: String,
-) -> Result<(), String> {
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
     // 验证会话
-    let user = auth_service::validate_session(&session_token).await?;
-    
+    let session_token = session.resolve(session_token)?;
+    let repo = SqlRepository::current()?;
+    let user = auth_service::validate_session(&repo, &session_token).await?;
+
     // 修改密码
-    auth_service::change_password(user.id, &current_password, &new_password).await
+    auth_service::change_password(&repo, user.id, &session_token, &current_password, &new_password).await
 }
 
-/// 获取当前用户信息
+/// 获取当前用户信息 (后端尚未保存会话时，恢复为传入的会话)
 #[tauri::command]
-pub async fn get_current_user(session_token: String) -> Result<UserResponse, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+pub async fn get_current_user(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<UserResponse, AppError> {
+    let session_token = session.resolve(session_token)?;
+    let user = auth_service::validate_session(&SqlRepository::current()?, &session_token).await?;
+    if session.get().is_none() {
+        session.set(session_token);
+    }
     Ok(UserResponse::from(user))
 }
 
 /// 修改昵称命令
 #[tauri::command]
 pub async fn change_display_name(
-    session_token: String,
+    session_token: Option<String>,
     new_display_name: String,
-) -> Result<UserResponse, String> {
+    session: State<'_, SessionState>,
+) -> Result<UserResponse, AppError> {
     // 验证会话
-    let user = auth_service::validate_session(&session_token).await?;
-    
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+
     // 修改昵称
-    let updated_user = auth_service::change_display_name(user.id, &new_display_name).await?;
+    let updated_user = auth_service::change_display_name(&repo, user.id, &new_display_name).await?;
     Ok(UserResponse::from(updated_user))
 }
+
+/// 获取当前用户的登录会话
+#[tauri::command]
+pub async fn get_my_sessions(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<Vec<SessionResponse>, AppError> {
+    let session_token = session.resolve(session_token)?;
+    let repo = SqlRepository::current()?;
+    let user = auth_service::validate_session(&repo, &session_token).await?;
+    auth_service::get_sessions(&repo, user.id, &session_token).await
+}
+
+/// 注销当前用户的指定会话
+#[tauri::command]
+pub async fn revoke_session(
+    session_token: Option<String>,
+    session_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    auth_service::revoke_session(&repo, user.id, session_id).await
+}
+
+/// 注销当前用户的其他会话
+#[tauri::command]
+pub async fn revoke_other_sessions(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    let session_token = session.resolve(session_token)?;
+    let repo = SqlRepository::current()?;
+    let user = auth_service::validate_session(&repo, &session_token).await?;
+    auth_service::revoke_other_sessions(&repo, user.id, &session_token).await
+}
@@ -0,0 +1,51 @@
+// 配置命令
+use crate::db::{self, DbError};
+use crate::commands::auth::{authorize, SessionState};
+use crate::error::AppError;
+use crate::models::{
+    ConnectionStatus, DbConfigResponse, Permission, SaveDbConfigInput, SchemaVersionResponse,
+};
+use crate::services::config_service;
+use tauri::State;
+
+/// 获取数据库配置 (密码已隐藏)
+#[tauri::command]
+pub async fn get_db_config() -> Result<DbConfigResponse, AppError> {
+    config_service::get_db_config().await
+}
+
+/// 首次运行配置数据库
+#[tauri::command]
+pub async fn setup_db_config(input: SaveDbConfigInput) -> Result<DbConfigResponse, AppError> {
+    config_service::setup_db_config(input).await
+}
+
+/// 修改数据库配置 (需要系统配置权限，重启后生效)
+#[tauri::command]
+pub async fn save_db_config(
+    session_token: Option<String>,
+    input: SaveDbConfigInput,
+    session: State<'_, SessionState>,
+) -> Result<DbConfigResponse, AppError> {
+    // 数据库未连接时无法校验身份，只能通过首次配置入口修复
+    if !db::is_initialized() {
+        return Err(DbError::Unavailable.into());
+    }
+
+    // 验证系统配置权限
+    authorize(&session, session_token, Permission::SystemConfig).await?;
+
+    config_service::save_db_config(input).await
+}
+
+/// 获取数据库结构版本
+#[tauri::command]
+pub async fn get_schema_version() -> Result<SchemaVersionResponse, AppError> {
+    config_service::get_schema_version().await
+}
+
+/// 获取数据库连接状态 (未登录时也可调用)
+#[tauri::command]
+pub async fn get_connection_status() -> Result<ConnectionStatus, AppError> {
+    config_service::get_connection_status().await
+}
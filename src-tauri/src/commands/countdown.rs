@@ -1,24 +1,31 @@
 // 倒计时命令
+use crate::commands::auth::{current_user, SessionState};
+use crate::error::AppError;
 use crate::models::{CountdownResponse, CreateCountdownInput};
-use crate::services::{auth_service, countdown_service};
+use crate::services::countdown_service;
 use chrono::{DateTime, Utc};
+use tauri::State;
 
 /// 获取倒计时列表
 #[tauri::command]
-pub async fn get_countdowns(session_token: String) -> Result<Vec<CountdownResponse>, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+pub async fn get_countdowns(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<Vec<CountdownResponse>, AppError> {
+    let user = current_user(&session, session_token).await?;
     countdown_service::get_countdowns(user.id).await
 }
 
 /// 创建倒计时
 #[tauri::command]
 pub async fn create_countdown(
-    session_token: String,
+    session_token: Option<String>,
     name: String,
     target_time: DateTime<Utc>,
     notify_enabled: Option<bool>,
-) -> Result<CountdownResponse, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+    session: State<'_, SessionState>,
+) -> Result<CountdownResponse, AppError> {
+    let user = current_user(&session, session_token).await?;
     let input = CreateCountdownInput {
         name,
         target_time,
@@ -29,7 +36,11 @@ pub async fn create_countdown(
 
 /// 删除倒计时
 #[tauri::command]
-pub async fn delete_countdown(session_token: String, countdown_id: i64) -> Result<(), String> {
-    let user = auth_service::validate_session(&session_token).await?;
+pub async fn delete_countdown(
+    session_token: Option<String>,
+    countdown_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    let user = current_user(&session, session_token).await?;
     countdown_service::delete_countdown(user.id, countdown_id).await
 }
@@ -0,0 +1,49 @@
+// 导师命令 (只读查看学生的统计与学习计划)
+use crate::commands::auth::{authorize, SessionState};
+use crate::error::AppError;
+use crate::models::{Permission, TaskResponse, UserResponse};
+use crate::repositories::SqlRepository;
+use crate::services::stats_service::Statistics;
+use crate::services::{mentor_service, stats_service, task_service};
+use chrono::NaiveDate;
+use tauri::State;
+
+/// 获取分配给当前导师的学生
+#[tauri::command]
+pub async fn get_my_students(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<Vec<UserResponse>, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = authorize(&session, session_token, Permission::UsersViewStats).await?;
+    mentor_service::get_students(&repo, user.id).await
+}
+
+/// 获取学生的统计数据
+#[tauri::command]
+pub async fn get_student_stats(
+    session_token: Option<String>,
+    student_id: i64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    session: State<'_, SessionState>,
+) -> Result<Statistics, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = authorize(&session, session_token, Permission::UsersViewStats).await?;
+    mentor_service::ensure_student_access(&repo, &user, Permission::UsersViewStats, student_id).await?;
+    stats_service::get_stats(student_id, start_date, end_date).await
+}
+
+/// 获取学生指定日期的学习计划
+#[tauri::command]
+pub async fn get_student_tasks(
+    session_token: Option<String>,
+    student_id: i64,
+    date: NaiveDate,
+    session: State<'_, SessionState>,
+) -> Result<Vec<TaskResponse>, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = authorize(&session, session_token, Permission::UsersViewPlans).await?;
+    mentor_service::ensure_student_access(&repo, &user, Permission::UsersViewPlans, student_id).await?;
+    task_service::get_tasks_by_date(&repo, student_id, date).await
+}
@@ -1,12 +1,17 @@
 // Tauri 命令模块
 
 pub mod auth;
+pub mod two_factor;
 pub mod admin;
+pub mod mentor;
 pub mod subject;
 pub mod countdown;
 pub mod task;
+pub mod tag;
 pub mod pomodoro;
 pub mod stats;
 pub mod review;
 pub mod ai;
 pub mod preference;
+pub mod config;
+pub mod sync;
@@ -1,20 +1,27 @@
 // 学习偏好命令
+use crate::commands::auth::{current_user, SessionState};
+use crate::error::AppError;
 use crate::models::{StudyPreferenceResponse, SaveStudyPreferenceInput};
-use crate::services::{auth_service, preference_service};
+use crate::services::preference_service;
+use tauri::State;
 
 /// 获取学习偏好
 #[tauri::command]
-pub async fn get_study_preference(session_token: String) -> Result<StudyPreferenceResponse, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+pub async fn get_study_preference(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<StudyPreferenceResponse, AppError> {
+    let user = current_user(&session, session_token).await?;
     preference_service::get_study_preference(user.id).await
 }
 
 /// 保存学习偏好
 #[tauri::command]
 pub async fn save_study_preference(
-    session_token: String,
+    session_token: Option<String>,
     input: SaveStudyPreferenceInput,
-) -> Result<StudyPreferenceResponse, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+    session: State<'_, SessionState>,
+) -> Result<StudyPreferenceResponse, AppError> {
+    let user = current_user(&session, session_token).await?;
     preference_service::save_study_preference(user.id, input).await
 }
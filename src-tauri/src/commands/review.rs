@@ -1,27 +1,32 @@
 // 复盘命令
+use crate::commands::auth::{current_user, SessionState};
+use crate::error::AppError;
 use crate::models::{DailyReview, SaveReviewInput};
-use crate::services::{auth_service, review_service};
+use crate::services::review_service;
 use chrono::NaiveDate;
+use tauri::State;
 
 /// 获取指定日期的复盘
 #[tauri::command]
 pub async fn get_review_by_date(
-    session_token: String,
+    session_token: Option<String>,
     date: NaiveDate,
-) -> Result<Option<DailyReview>, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+    session: State<'_, SessionState>,
+) -> Result<Option<DailyReview>, AppError> {
+    let user = current_user(&session, session_token).await?;
     review_service::get_review_by_date(user.id, date).await
 }
 
 /// 保存复盘
 #[tauri::command]
 pub async fn save_review(
-    session_token: String,
+    session_token: Option<String>,
     review_date: NaiveDate,
     feelings: Option<String>,
     difficulties: Option<String>,
-) -> Result<DailyReview, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+    session: State<'_, SessionState>,
+) -> Result<DailyReview, AppError> {
+    let user = current_user(&session, session_token).await?;
     let input = SaveReviewInput {
         review_date,
         feelings,
@@ -33,9 +38,10 @@ pub async fn save_review(
 /// 获取复盘历史
 #[tauri::command]
 pub async fn get_review_history(
-    session_token: String,
+    session_token: Option<String>,
     limit: Option<i32>,
-) -> Result<Vec<DailyReview>, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+    session: State<'_, SessionState>,
+) -> Result<Vec<DailyReview>, AppError> {
+    let user = current_user(&session, session_token).await?;
     review_service::get_review_history(user.id, limit.unwrap_or(30)).await
 }
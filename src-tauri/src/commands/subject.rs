@@ -1,29 +1,44 @@
 // 科目命令
+use crate::commands::auth::{current_user, SessionState};
+use crate::error::AppError;
 use crate::models::{Subject, CreateSubjectInput};
-use crate::services::{auth_service, subject_service};
+use crate::repositories::SqlRepository;
+use crate::services::subject_service;
+use tauri::State;
 
 /// 获取科目列表
 #[tauri::command]
-pub async fn get_subjects(session_token: String) -> Result<Vec<Subject>, String> {
-    let user = auth_service::validate_session(&session_token).await?;
-    subject_service::get_subjects(user.id).await
+pub async fn get_subjects(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<Vec<Subject>, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    subject_service::get_subjects(&repo, user.id).await
 }
 
 /// 创建科目
 #[tauri::command]
 pub async fn create_subject(
-    session_token: String,
+    session_token: Option<String>,
     name: String,
     color: Option<String>,
-) -> Result<Subject, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+    session: State<'_, SessionState>,
+) -> Result<Subject, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
     let input = CreateSubjectInput { name, color };
-    subject_service::create_subject(user.id, input).await
+    subject_service::create_subject(&repo, user.id, input).await
 }
 
 /// 删除科目
 #[tauri::command]
-pub async fn delete_subject(session_token: String, subject_id: i64) -> Result<(), String> {
-    let user = auth_service::validate_session(&session_token).await?;
-    subject_service::delete_subject(user.id, subject_id).await
+pub async fn delete_subject(
+    session_token: Option<String>,
+    subject_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    subject_service::delete_subject(&repo, user.id, subject_id).await
 }
@@ -0,0 +1,68 @@
+// 同步命令
+use crate::commands::auth::{current_user, SessionState};
+use crate::error::AppError;
+use crate::models::{SyncConflict, SyncReport, SyncStatusResponse};
+use crate::services::sync_service;
+use tauri::State;
+
+/// 获取同步状态 (待同步变更与上次同步时间)
+#[tauri::command]
+pub async fn sync_status(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<SyncStatusResponse, AppError> {
+    let user = current_user(&session, session_token).await?;
+    sync_service::get_sync_status(user.id).await
+}
+
+/// 立即与服务器同步
+#[tauri::command]
+pub async fn sync_now(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<SyncReport, AppError> {
+    let user = current_user(&session, session_token).await?;
+    sync_service::sync_user(user.id).await
+}
+
+/// 获取未处理的同步冲突
+#[tauri::command]
+pub async fn get_sync_conflicts(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<Vec<SyncConflict>, AppError> {
+    let user = current_user(&session, session_token).await?;
+    sync_service::get_sync_conflicts(user.id).await
+}
+
+/// 将同步冲突标记为已查看
+#[tauri::command]
+pub async fn resolve_sync_conflict(
+    session_token: Option<String>,
+    conflict_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    let user = current_user(&session, session_token).await?;
+    sync_service::resolve_sync_conflict(user.id, conflict_id).await
+}
+
+/// 输入服务器上同名账户的密码，为本机启用同步
+#[tauri::command]
+pub async fn link_sync_account(
+    session_token: Option<String>,
+    server_password: String,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    let user = current_user(&session, session_token).await?;
+    sync_service::link_account(user.id, &server_password).await
+}
+
+/// 清除本机的同步令牌 (停止同步)
+#[tauri::command]
+pub async fn unlink_sync_account(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    let user = current_user(&session, session_token).await?;
+    sync_service::unlink_account(user.id).await
+}
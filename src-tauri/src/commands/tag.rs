@@ -0,0 +1,56 @@
+// 任务标签命令
+use crate::commands::auth::{current_user, SessionState};
+use crate::error::AppError;
+use crate::models::{CreateTagInput, Tag, UpdateTagInput};
+use crate::repositories::SqlRepository;
+use crate::services::tag_service;
+use tauri::State;
+
+/// 获取标签列表
+#[tauri::command]
+pub async fn get_tags(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<Vec<Tag>, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    tag_service::get_tags(&repo, user.id).await
+}
+
+/// 创建标签
+#[tauri::command]
+pub async fn create_tag(
+    session_token: Option<String>,
+    name: String,
+    color: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<Tag, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    tag_service::create_tag(&repo, user.id, CreateTagInput { name, color }).await
+}
+
+/// 修改标签
+#[tauri::command]
+pub async fn update_tag(
+    session_token: Option<String>,
+    tag_id: i64,
+    input: UpdateTagInput,
+    session: State<'_, SessionState>,
+) -> Result<Tag, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    tag_service::update_tag(&repo, user.id, tag_id, input).await
+}
+
+/// 删除标签
+#[tauri::command]
+pub async fn delete_tag(
+    session_token: Option<String>,
+    tag_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    tag_service::delete_tag(&repo, user.id, tag_id).await
+}
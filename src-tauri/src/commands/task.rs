@@ -1,19 +1,50 @@
 // 任务命令
-use crate::models::{TaskResponse, CreateTaskInput, UpdateTaskInput};
-use crate::services::{auth_service, task_service};
+use crate::commands::auth::{current_user, SessionState};
+use crate::error::AppError;
+use crate::models::{
+    CarryOverMode, CarryOverReport, CreateTaskInput, CreateTaskSeriesInput, FailureReasonStats, RecurrenceScope,
+    TaskDay, TaskFilter, TaskPriority, TaskResponse, TaskSeriesResponse, TaskStatus, TaskStatusChange, TimeSlot,
+    UpdateChecklistItemInput, UpdateTaskInput,
+};
+use crate::repositories::SqlRepository;
+use crate::services::{
+    carry_over_service, checklist_service, preference_service, schedule_service, tag_service, task_service,
+    task_status_service,
+};
 use chrono::{NaiveDate, NaiveTime};
+use tauri::State;
 
 /// 获取指定日期的任务
 #[tauri::command]
-pub async fn get_tasks_by_date(session_token: String, date: NaiveDate) -> Result<Vec<TaskResponse>, String> {
-    let user = auth_service::validate_session(&session_token).await?;
-    task_service::get_tasks_by_date(user.id, date).await
+pub async fn get_tasks_by_date(
+    session_token: Option<String>,
+    date: NaiveDate,
+    session: State<'_, SessionState>,
+) -> Result<Vec<TaskResponse>, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    task_service::get_tasks_by_date(&repo, user.id, date).await
+}
+
+/// 获取日期范围内的任务 (按天分组，用于周视图和月视图)
+#[tauri::command]
+pub async fn get_tasks_in_range(
+    session_token: Option<String>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    filter: Option<TaskFilter>,
+    session: State<'_, SessionState>,
+) -> Result<Vec<TaskDay>, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    task_service::get_tasks_in_range(&repo, user.id, start_date, end_date, filter.unwrap_or_default()).await
 }
 
 /// 创建任务
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_task(
-    session_token: String,
+    session_token: Option<String>,
     subject_id: Option<i64>,
     task_date: NaiveDate,
     start_time: NaiveTime,
@@ -21,8 +52,11 @@ pub async fn create_task(
     content: String,
     alarm_enabled: Option<bool>,
     alarm_time: Option<NaiveTime>,
-) -> Result<TaskResponse, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+    priority: Option<TaskPriority>,
+    session: State<'_, SessionState>,
+) -> Result<TaskResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
     let input = CreateTaskInput {
         subject_id,
         task_date,
@@ -31,14 +65,17 @@ pub async fn create_task(
         content,
         alarm_enabled,
         alarm_time,
+        priority,
     };
-    task_service::create_task(user.id, input).await
+    let window = preference_service::get_study_window(user.id).await?;
+    task_service::create_task(&repo, user.id, input, &window).await
 }
 
-/// 更新任务
+/// 更新任务 (重复任务默认仅修改这一次)
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn update_task(
-    session_token: String,
+    session_token: Option<String>,
     task_id: i64,
     subject_id: Option<i64>,
     start_time: Option<NaiveTime>,
@@ -46,8 +83,12 @@ pub async fn update_task(
     content: Option<String>,
     alarm_enabled: Option<bool>,
     alarm_time: Option<NaiveTime>,
-) -> Result<TaskResponse, String> {
-    let user = auth_service::validate_session(&session_token).await?;
+    priority: Option<TaskPriority>,
+    scope: Option<RecurrenceScope>,
+    session: State<'_, SessionState>,
+) -> Result<TaskResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
     let input = UpdateTaskInput {
         subject_id,
         start_time,
@@ -55,31 +96,202 @@ pub async fn update_task(
         content,
         alarm_enabled,
         alarm_time,
+        priority,
     };
-    task_service::update_task(user.id, task_id, input).await
+    let window = preference_service::get_study_window(user.id).await?;
+    task_service::update_task(&repo, user.id, task_id, input, scope.unwrap_or_default(), &window).await
 }
 
-/// 删除任务
+/// 删除任务 (重复任务默认仅删除这一次)
 #[tauri::command]
-pub async fn delete_task(session_token: String, task_id: i64) -> Result<(), String> {
-    let user = auth_service::validate_session(&session_token).await?;
-    task_service::delete_task(user.id, task_id).await
+pub async fn delete_task(
+    session_token: Option<String>,
+    task_id: i64,
+    scope: Option<RecurrenceScope>,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    task_service::delete_task(&repo, user.id, task_id, scope.unwrap_or_default()).await
+}
+
+/// 创建重复任务
+#[tauri::command]
+pub async fn create_task_series(
+    session_token: Option<String>,
+    input: CreateTaskSeriesInput,
+    session: State<'_, SessionState>,
+) -> Result<TaskSeriesResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    task_service::create_task_series(&repo, user.id, input).await
+}
+
+/// 获取重复任务的规则
+#[tauri::command]
+pub async fn get_task_series(
+    session_token: Option<String>,
+    series_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<TaskSeriesResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    task_service::get_task_series(&repo, user.id, series_id).await
+}
+
+/// 查询指定日期可安排指定时长 (分钟) 的空闲时段
+#[tauri::command]
+pub async fn find_free_slots(
+    session_token: Option<String>,
+    date: NaiveDate,
+    duration_minutes: i64,
+    session: State<'_, SessionState>,
+) -> Result<Vec<TimeSlot>, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    let window = preference_service::get_study_window(user.id).await?;
+    schedule_service::find_free_slots(&repo, user.id, date, duration_minutes, &window).await
+}
+
+/// 把某天未完成的任务顺延到之后的日期 (默认移动，原任务标记为已顺延)
+#[tauri::command]
+pub async fn carry_over_tasks(
+    session_token: Option<String>,
+    from_date: NaiveDate,
+    to_date: NaiveDate,
+    mode: Option<CarryOverMode>,
+    session: State<'_, SessionState>,
+) -> Result<CarryOverReport, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    let window = preference_service::get_study_window(user.id).await?;
+    carry_over_service::carry_over_tasks(&repo, user.id, from_date, to_date, mode.unwrap_or_default(), &window).await
+}
+
+/// 在任务清单末尾添加一项
+#[tauri::command]
+pub async fn add_checklist_item(
+    session_token: Option<String>,
+    task_id: i64,
+    content: String,
+    session: State<'_, SessionState>,
+) -> Result<TaskResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    checklist_service::add_checklist_item(&repo, user.id, task_id, &content).await
+}
+
+/// 修改清单项 (勾选全部清单项后任务自动完成)
+#[tauri::command]
+pub async fn update_checklist_item(
+    session_token: Option<String>,
+    item_id: i64,
+    input: UpdateChecklistItemInput,
+    session: State<'_, SessionState>,
+) -> Result<TaskResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    checklist_service::update_checklist_item(&repo, user.id, item_id, input).await
+}
+
+/// 删除清单项
+#[tauri::command]
+pub async fn delete_checklist_item(
+    session_token: Option<String>,
+    item_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<TaskResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    checklist_service::delete_checklist_item(&repo, user.id, item_id).await
+}
+
+/// 调整清单顺序
+#[tauri::command]
+pub async fn reorder_checklist_items(
+    session_token: Option<String>,
+    task_id: i64,
+    item_ids: Vec<i64>,
+    session: State<'_, SessionState>,
+) -> Result<TaskResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    checklist_service::reorder_checklist_items(&repo, user.id, task_id, &item_ids).await
+}
+
+/// 设置任务的标签 (替换原有标签)
+#[tauri::command]
+pub async fn set_task_tags(
+    session_token: Option<String>,
+    task_id: i64,
+    tag_ids: Vec<i64>,
+    session: State<'_, SessionState>,
+) -> Result<TaskResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    tag_service::set_task_tags(&repo, user.id, task_id, &tag_ids).await
 }
 
 /// 切换任务状态
 #[tauri::command]
-pub async fn toggle_task_status(session_token: String, task_id: i64) -> Result<TaskResponse, String> {
-    let user = auth_service::validate_session(&session_token).await?;
-    task_service::toggle_task_status(user.id, task_id).await
+pub async fn toggle_task_status(
+    session_token: Option<String>,
+    task_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<TaskResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    task_service::toggle_task_status(&repo, user.id, task_id).await
+}
+
+/// 设置任务状态 (标记为未完成时需要填写原因)
+#[tauri::command]
+pub async fn set_task_status(
+    session_token: Option<String>,
+    task_id: i64,
+    status: TaskStatus,
+    reason: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<TaskResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    task_status_service::set_task_status(&repo, user.id, task_id, status, reason).await
+}
+
+/// 获取任务的状态变更记录
+#[tauri::command]
+pub async fn get_task_status_history(
+    session_token: Option<String>,
+    task_id: i64,
+    session: State<'_, SessionState>,
+) -> Result<Vec<TaskStatusChange>, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    task_status_service::get_task_status_history(&repo, user.id, task_id).await
+}
+
+/// 统计日期范围内未完成任务的原因
+#[tauri::command]
+pub async fn get_failure_reasons(
+    session_token: Option<String>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    session: State<'_, SessionState>,
+) -> Result<Vec<FailureReasonStats>, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    task_status_service::get_failure_reasons(&repo, user.id, start_date, end_date).await
 }
 
 /// 内容检查 - 模糊匹配
 #[tauri::command]
 pub async fn check_content(
-    session_token: String,
+    session_token: Option<String>,
     date: NaiveDate,
     content: String,
-) -> Result<Vec<TaskResponse>, String> {
-    let user = auth_service::validate_session(&session_token).await?;
-    task_service::check_content(user.id, date, &content).await
+    session: State<'_, SessionState>,
+) -> Result<Vec<TaskResponse>, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    task_service::check_content(&repo, user.id, date, &content).await
 }
@@ -0,0 +1,71 @@
+// 两步验证命令 (当前用户自助管理)
+use crate::commands::auth::{current_user, SessionState};
+use crate::config;
+use crate::error::AppError;
+use crate::models::{RecoveryCodesResponse, TotpEnrollmentResponse, TwoFactorStatus};
+use crate::repositories::SqlRepository;
+use crate::services::two_factor_service;
+use tauri::State;
+
+/// 安全策略是否要求管理员启用两步验证
+fn require_admin_two_factor() -> bool {
+    config::load().unwrap_or_default().security.require_admin_two_factor
+}
+
+/// 获取当前用户的两步验证状态
+#[tauri::command]
+pub async fn get_two_factor_status(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<TwoFactorStatus, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    two_factor_service::get_status(&repo, &user, require_admin_two_factor()).await
+}
+
+/// 开始绑定身份验证器，返回密钥和 otpauth 链接
+#[tauri::command]
+pub async fn begin_totp_enrollment(
+    session_token: Option<String>,
+    session: State<'_, SessionState>,
+) -> Result<TotpEnrollmentResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    two_factor_service::begin_enrollment(&repo, &user).await
+}
+
+/// 提交验证码完成绑定，返回一次性恢复码
+#[tauri::command]
+pub async fn confirm_totp_enrollment(
+    session_token: Option<String>,
+    code: String,
+    session: State<'_, SessionState>,
+) -> Result<RecoveryCodesResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    two_factor_service::confirm_enrollment(&repo, user.id, &code).await
+}
+
+/// 关闭两步验证 (需要当前密码)
+#[tauri::command]
+pub async fn disable_two_factor(
+    session_token: Option<String>,
+    current_password: String,
+    session: State<'_, SessionState>,
+) -> Result<(), AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    two_factor_service::disable(&repo, &user, &current_password, require_admin_two_factor()).await
+}
+
+/// 重新生成恢复码 (需要当前的验证码)
+#[tauri::command]
+pub async fn regenerate_recovery_codes(
+    session_token: Option<String>,
+    code: String,
+    session: State<'_, SessionState>,
+) -> Result<RecoveryCodesResponse, AppError> {
+    let repo = SqlRepository::current()?;
+    let user = current_user(&session, session_token).await?;
+    two_factor_service::regenerate_recovery_codes(&repo, user.id, &code).await
+}
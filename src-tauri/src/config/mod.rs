@@ -0,0 +1,363 @@
+// 应用配置模块
+// 配置文件保存在 Tauri 应用配置目录下的 config.json，环境变量优先级高于配置文件
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+// 应用配置目录 (由 setup 钩子注入)
+static CONFIG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// 配置文件名
+const CONFIG_FILE: &str = "config.json";
+
+/// 默认本地数据库文件名
+const SQLITE_FILE: &str = "study_planner.db";
+
+/// 用户数据归档目录名
+const EXPORT_DIR: &str = "exports";
+
+/// 环境变量前缀
+const ENV_PREFIX: &str = "STUDY_PLANNER_";
+
+/// 存储后端
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseBackend {
+    #[default]
+    MySql,
+    Sqlite,
+}
+
+impl std::str::FromStr for DatabaseBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mysql" => Ok(DatabaseBackend::MySql),
+            "sqlite" => Ok(DatabaseBackend::Sqlite),
+            _ => Err(format!("不支持的存储后端: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for DatabaseBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatabaseBackend::MySql => write!(f, "mysql"),
+            DatabaseBackend::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
+/// 数据库连接配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatabaseConfig {
+    pub backend: DatabaseBackend,
+    /// 本地 SQLite 文件路径，为空时使用配置目录下的默认文件
+    pub sqlite_path: String,
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub password: String,
+    pub database: String,
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub connect_timeout_secs: u64,
+    pub idle_timeout_secs: u64,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            backend: DatabaseBackend::MySql,
+            sqlite_path: String::new(),
+            host: "127.0.0.1".to_string(),
+            port: 3306,
+            user: "root".to_string(),
+            password: String::new(),
+            database: "study_planner".to_string(),
+            max_connections: 10,
+            min_connections: 0,
+            connect_timeout_secs: 10,
+            idle_timeout_secs: 600,
+        }
+    }
+}
+
+/// 同步配置 (本地 SQLite 与共享 MySQL 服务器之间)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    pub enabled: bool,
+    /// 共享 MySQL 服务器连接，仅使用其中的 MySQL 相关字段
+    pub server: DatabaseConfig,
+    /// 后台自动同步间隔 (秒)
+    pub interval_secs: u64,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            server: DatabaseConfig::default(),
+            interval_secs: 300,
+        }
+    }
+}
+
+/// 审计日志配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// 审计记录保留天数，超过后由后台任务清理 (0 表示永久保留)
+    pub retention_days: u32,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self { retention_days: 365 }
+    }
+}
+
+/// 安全策略配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecurityConfig {
+    /// 要求管理员账户启用两步验证 (未启用时只能访问个人设置)
+    pub require_admin_two_factor: bool,
+    /// 登录尝试记录保留天数，超过后由后台任务清理 (0 表示永久保留)
+    pub login_attempt_retention_days: u32,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            require_admin_two_factor: false,
+            login_attempt_retention_days: 90,
+        }
+    }
+}
+
+/// 应用配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub database: DatabaseConfig,
+    pub sync: SyncConfig,
+    pub audit: AuditConfig,
+    pub security: SecurityConfig,
+}
+
+/// 初始化配置目录
+pub fn init(config_dir: PathBuf) {
+    let _ = CONFIG_DIR.set(config_dir);
+}
+
+/// 获取配置文件路径
+pub fn config_path() -> Option<PathBuf> {
+    CONFIG_DIR.get().map(|dir| dir.join(CONFIG_FILE))
+}
+
+/// 配置文件是否已存在
+pub fn has_config_file() -> bool {
+    config_path().map(|p| p.exists()).unwrap_or(false)
+}
+
+/// 是否已完成数据库配置 (配置文件或环境变量)
+pub fn is_configured() -> bool {
+    has_config_file() || env_var("DB_HOST").is_some() || env_var("DB_BACKEND").is_some()
+}
+
+/// 解析本地 SQLite 文件路径
+pub fn sqlite_path(db: &DatabaseConfig) -> Result<PathBuf, AppError> {
+    if !db.sqlite_path.is_empty() {
+        return Ok(PathBuf::from(&db.sqlite_path));
+    }
+    CONFIG_DIR
+        .get()
+        .map(|dir| dir.join(SQLITE_FILE))
+        .ok_or_else(|| AppError::Config {
+            message: "配置目录未初始化".to_string(),
+            details: "无法确定 SQLite 文件路径".to_string(),
+        })
+}
+
+/// 用户数据归档目录 (位于配置目录下)
+pub fn export_dir() -> Result<PathBuf, AppError> {
+    CONFIG_DIR
+        .get()
+        .map(|dir| dir.join(EXPORT_DIR))
+        .ok_or_else(|| AppError::Config {
+            message: "配置目录未初始化".to_string(),
+            details: "无法确定数据归档目录".to_string(),
+        })
+}
+
+/// 读取配置文件 (不含环境变量覆盖)
+pub fn load_file() -> Result<AppConfig, AppError> {
+    let path = match config_path() {
+        Some(p) if p.exists() => p,
+        _ => return Ok(AppConfig::default()),
+    };
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(AppError::config("读取配置文件失败"))?;
+    serde_json::from_str(&content).map_err(AppError::config("解析配置文件失败"))
+}
+
+/// 加载生效配置 (配置文件 + 环境变量覆盖)
+pub fn load() -> Result<AppConfig, AppError> {
+    let mut config = load_file()?;
+    apply_env_overrides(&mut config);
+    Ok(config)
+}
+
+/// 保存配置文件
+pub fn save(config: &AppConfig) -> Result<(), AppError> {
+    let path = config_path().ok_or_else(|| AppError::Config {
+        message: "配置目录未初始化".to_string(),
+        details: "无法确定配置文件路径".to_string(),
+    })?;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(AppError::config("创建配置目录失败"))?;
+    }
+
+    let content = serde_json::to_string_pretty(config)
+        .map_err(AppError::config("序列化配置失败"))?;
+    std::fs::write(&path, content).map_err(AppError::config("写入配置文件失败"))?;
+
+    Ok(())
+}
+
+/// 列出当前生效的环境变量覆盖项
+pub fn env_overrides() -> Vec<String> {
+    DB_ENV_KEYS
+        .iter()
+        .chain(SYNC_ENV_KEYS.iter())
+        .chain(AUDIT_ENV_KEYS.iter())
+        .chain(SECURITY_ENV_KEYS.iter())
+        .filter(|key| env_var(key).is_some())
+        .map(|key| format!("{}{}", ENV_PREFIX, key))
+        .collect()
+}
+
+// 支持的数据库环境变量
+const DB_ENV_KEYS: [&str; 11] = [
+    "DB_BACKEND",
+    "DB_SQLITE_PATH",
+    "DB_HOST",
+    "DB_PORT",
+    "DB_USER",
+    "DB_PASSWORD",
+    "DB_NAME",
+    "DB_MAX_CONNECTIONS",
+    "DB_MIN_CONNECTIONS",
+    "DB_CONNECT_TIMEOUT",
+    "DB_IDLE_TIMEOUT",
+];
+
+// 支持的同步环境变量
+const SYNC_ENV_KEYS: [&str; 7] = [
+    "SYNC_ENABLED",
+    "SYNC_HOST",
+    "SYNC_PORT",
+    "SYNC_USER",
+    "SYNC_PASSWORD",
+    "SYNC_DB_NAME",
+    "SYNC_INTERVAL",
+];
+
+// 支持的审计日志环境变量
+const AUDIT_ENV_KEYS: [&str; 1] = ["AUDIT_RETENTION_DAYS"];
+
+// 支持的安全策略环境变量
+const SECURITY_ENV_KEYS: [&str; 1] = ["REQUIRE_ADMIN_TWO_FACTOR"];
+
+/// 读取带前缀的环境变量
+fn env_var(key: &str) -> Option<String> {
+    std::env::var(format!("{}{}", ENV_PREFIX, key))
+        .ok()
+        .filter(|v| !v.is_empty())
+}
+
+/// 读取并解析带前缀的环境变量，格式错误时忽略
+fn env_parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+    env_var(key).and_then(|v| v.parse().ok())
+}
+
+/// 应用环境变量覆盖
+fn apply_env_overrides(config: &mut AppConfig) {
+    let db = &mut config.database;
+
+    if let Some(v) = env_parse("DB_BACKEND") {
+        db.backend = v;
+    }
+    if let Some(v) = env_var("DB_SQLITE_PATH") {
+        db.sqlite_path = v;
+    }
+    if let Some(v) = env_var("DB_HOST") {
+        db.host = v;
+    }
+    if let Some(v) = env_parse("DB_PORT") {
+        db.port = v;
+    }
+    if let Some(v) = env_var("DB_USER") {
+        db.user = v;
+    }
+    if let Some(v) = env_var("DB_PASSWORD") {
+        db.password = v;
+    }
+    if let Some(v) = env_var("DB_NAME") {
+        db.database = v;
+    }
+    if let Some(v) = env_parse("DB_MAX_CONNECTIONS") {
+        db.max_connections = v;
+    }
+    if let Some(v) = env_parse("DB_MIN_CONNECTIONS") {
+        db.min_connections = v;
+    }
+    if let Some(v) = env_parse("DB_CONNECT_TIMEOUT") {
+        db.connect_timeout_secs = v;
+    }
+    if let Some(v) = env_parse("DB_IDLE_TIMEOUT") {
+        db.idle_timeout_secs = v;
+    }
+
+    let sync = &mut config.sync;
+
+    if let Some(v) = env_parse("SYNC_ENABLED") {
+        sync.enabled = v;
+    }
+    if let Some(v) = env_var("SYNC_HOST") {
+        sync.server.host = v;
+    }
+    if let Some(v) = env_parse("SYNC_PORT") {
+        sync.server.port = v;
+    }
+    if let Some(v) = env_var("SYNC_USER") {
+        sync.server.user = v;
+    }
+    if let Some(v) = env_var("SYNC_PASSWORD") {
+        sync.server.password = v;
+    }
+    if let Some(v) = env_var("SYNC_DB_NAME") {
+        sync.server.database = v;
+    }
+    if let Some(v) = env_parse("SYNC_INTERVAL") {
+        sync.interval_secs = v;
+    }
+
+    if let Some(v) = env_parse("AUDIT_RETENTION_DAYS") {
+        config.audit.retention_days = v;
+    }
+
+    if let Some(v) = env_parse("REQUIRE_ADMIN_TWO_FACTOR") {
+        config.security.require_admin_two_factor = v;
+    }
+    if let Some(v) = env_parse("LOGIN_ATTEMPT_RETENTION_DAYS") {
+        config.security.login_attempt_retention_days = v;
+    }
+}
@@ -0,0 +1,146 @@
+// 数据库连接管理
+// 连接池初始化失败时在后台按指数退避重试，连接状态变化时通知监听者 (由 lib.rs 转发为前端事件)
+use super::{init_pool, is_initialized};
+use crate::config;
+use crate::models::{ConnectionState, ConnectionStatus};
+use chrono::Utc;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+use tokio::sync::{Mutex, Notify};
+
+/// 连接状态变化事件名
+pub const STATUS_EVENT: &str = "db-connection-changed";
+
+/// 重试间隔上限 (秒)
+const MAX_BACKOFF_SECS: u64 = 60;
+
+type Listener = Box<dyn Fn(&ConnectionStatus) + Send + Sync>;
+
+// 当前连接状态
+static STATUS: RwLock<ConnectionStatus> = RwLock::new(ConnectionStatus {
+    state: ConnectionState::NotConfigured,
+    attempts: 0,
+    last_error: None,
+    next_retry_at: None,
+    connected_at: None,
+});
+
+// 状态变化监听者
+static LISTENER: OnceLock<Listener> = OnceLock::new();
+
+// 同一时间只进行一次连接尝试
+static CONNECT_LOCK: Mutex<()> = Mutex::const_new(());
+
+// 唤醒后台重试循环
+static WAKE: Notify = Notify::const_new();
+
+/// 第 attempt 次失败后的重试间隔：1s, 2s, 4s ... 最长 60s
+pub fn backoff_delay(attempt: u32) -> Duration {
+    let secs = 1u64
+        .checked_shl(attempt.saturating_sub(1))
+        .unwrap_or(u64::MAX)
+        .min(MAX_BACKOFF_SECS);
+    Duration::from_secs(secs)
+}
+
+/// 设置状态变化监听者 (只能设置一次)
+pub fn set_listener(listener: impl Fn(&ConnectionStatus) + Send + Sync + 'static) {
+    let _ = LISTENER.set(Box::new(listener));
+}
+
+/// 获取当前连接状态
+pub fn status() -> ConnectionStatus {
+    STATUS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 更新连接状态，状态类型变化时通知监听者
+fn update(apply: impl FnOnce(&mut ConnectionStatus)) {
+    let (changed, current) = {
+        let mut status = STATUS.write().unwrap_or_else(|e| e.into_inner());
+        let previous = status.state;
+        apply(&mut status);
+        (status.state != previous, status.clone())
+    };
+
+    if changed {
+        if let Some(listener) = LISTENER.get() {
+            listener(&current);
+        }
+    }
+}
+
+/// 立即尝试连接数据库 (已连接时直接返回)
+pub async fn connect() -> Result<(), sqlx::Error> {
+    let _guard = CONNECT_LOCK.lock().await;
+
+    if is_initialized() {
+        return Ok(());
+    }
+    if !config::is_configured() {
+        update(|s| s.state = ConnectionState::NotConfigured);
+        return Err(sqlx::Error::Configuration("数据库尚未配置".into()));
+    }
+
+    update(|s| s.state = ConnectionState::Connecting);
+
+    match init_pool().await {
+        Ok(()) => {
+            update(|s| {
+                s.state = ConnectionState::Connected;
+                s.attempts = 0;
+                s.last_error = None;
+                s.next_retry_at = None;
+                s.connected_at = Some(Utc::now());
+            });
+            Ok(())
+        }
+        Err(e) => {
+            update(|s| {
+                s.state = ConnectionState::Unavailable;
+                s.attempts += 1;
+                s.last_error = Some(e.to_string());
+                s.next_retry_at = chrono::Duration::from_std(backoff_delay(s.attempts))
+                    .ok()
+                    .map(|d| Utc::now() + d);
+            });
+            Err(e)
+        }
+    }
+}
+
+/// 唤醒后台重试循环 (保存配置后立即重试)
+pub fn wake() {
+    WAKE.notify_one();
+}
+
+/// 后台连接循环：失败后按指数退避重试，直到连接成功 (由 setup 钩子启动)
+pub async fn run() {
+    loop {
+        if is_initialized() {
+            return;
+        }
+
+        // 未配置时等待首次配置完成
+        if !config::is_configured() {
+            update(|s| s.state = ConnectionState::NotConfigured);
+            WAKE.notified().await;
+            continue;
+        }
+
+        match connect().await {
+            Ok(()) => {
+                println!("数据库连接池初始化成功");
+                return;
+            }
+            Err(e) => {
+                let delay = backoff_delay(status().attempts);
+                eprintln!("数据库连接池初始化失败: {}，{} 秒后重试", e, delay.as_secs());
+
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = WAKE.notified() => {}
+                }
+            }
+        }
+    }
+}
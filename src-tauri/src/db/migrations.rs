@@ -0,0 +1,49 @@
+// 数据库迁移
+// 迁移文件位于 src-tauri/migrations/<后端>/，编译时嵌入，启动时按版本号依次执行
+use super::DbPool;
+use crate::config::DatabaseBackend;
+use chrono::{DateTime, Utc};
+use sqlx::migrate::{MigrateError, Migrator};
+
+static MYSQL_MIGRATOR: Migrator = sqlx::migrate!("./migrations/mysql");
+static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+/// 已执行的迁移记录
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub description: String,
+    pub installed_on: DateTime<Utc>,
+}
+
+/// 获取对应后端的迁移器
+pub fn migrator(backend: DatabaseBackend) -> &'static Migrator {
+    match backend {
+        DatabaseBackend::MySql => &MYSQL_MIGRATOR,
+        DatabaseBackend::Sqlite => &SQLITE_MIGRATOR,
+    }
+}
+
+/// 应用内置的最新迁移版本
+pub fn latest_version(backend: DatabaseBackend) -> i64 {
+    migrator(backend).iter().map(|m| m.version).max().unwrap_or(0)
+}
+
+/// 执行尚未应用的迁移
+pub async fn run(pool: &DbPool) -> Result<(), MigrateError> {
+    match pool {
+        DbPool::MySql(p) => MYSQL_MIGRATOR.run(p).await,
+        DbPool::Sqlite(p) => SQLITE_MIGRATOR.run(p).await,
+    }
+}
+
+/// 查询已成功执行的迁移
+pub async fn applied(pool: &DbPool) -> Result<Vec<AppliedMigration>, sqlx::Error> {
+    const SQL: &str = "SELECT version, description, installed_on FROM _sqlx_migrations
+                       WHERE success = TRUE ORDER BY version ASC";
+
+    match pool {
+        DbPool::MySql(p) => sqlx::query_as(SQL).fetch_all(p).await,
+        DbPool::Sqlite(p) => sqlx::query_as(SQL).fetch_all(p).await,
+    }
+}
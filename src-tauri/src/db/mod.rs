@@ -1,43 +1,176 @@
 // 数据库连接模块
-use sqlx::mysql::{MySqlPool, MySqlPoolOptions};
-use std::sync::OnceLock;
+// 支持远程 MySQL 与本地 SQLite 两种存储后端，由应用配置选择
+use crate::config::{self, DatabaseBackend, DatabaseConfig};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use sqlx::mysql::{MySqlConnectOptions, MySqlPool, MySqlPoolOptions, MySqlQueryResult};
+use sqlx::sqlite::{
+    SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions, SqliteQueryResult,
+};
+use std::sync::RwLock;
+use std::time::Duration;
 
-// 全局数据库连接池
-static DB_POOL: OnceLock<MySqlPool> = OnceLock::new();
+pub mod connection;
+pub mod migrations;
 
-// 数据库配置
-const DB_HOST: &str = "47.86.24.6";
-const DB_USER: &str = "root";
-const DB_PASSWORD: &str = "lsmx050320";
-const DB_NAME: &str = "study_planner";
+// 全局数据库连接池 (连接成功前为空)
+static DB_POOL: RwLock<Option<DbPool>> = RwLock::new(None);
 
-/// 初始化数据库连接池
+/// 数据库访问错误
+#[derive(Debug, thiserror::Error)]
+pub enum DbError {
+    /// 连接池尚未建立 (未配置或正在重试连接)
+    #[error("数据库暂不可用，请稍后重试")]
+    Unavailable,
+}
+
+/// 数据库连接池 (按存储后端区分)
+#[derive(Debug, Clone)]
+pub enum DbPool {
+    MySql(MySqlPool),
+    Sqlite(SqlitePool),
+}
+
+impl DbPool {
+    /// 当前存储后端
+    pub fn backend(&self) -> DatabaseBackend {
+        match self {
+            DbPool::MySql(_) => DatabaseBackend::MySql,
+            DbPool::Sqlite(_) => DatabaseBackend::Sqlite,
+        }
+    }
+}
+
+/// 在当前连接池 (或指定的连接池) 上执行同一段查询代码
+///
+/// 代码块会针对每种后端分别展开，因此其中的 SQL 必须同时兼容 MySQL 与 SQLite。
+/// 使用当前连接池时，连接池不可用会通过 `?` 提前返回 [`DbError::Unavailable`]。
+macro_rules! with_pool {
+    ($pool:ident => $body:expr) => {
+        $crate::db::with_pool!(&$crate::db::get_pool()?, $pool => $body)
+    };
+    ($db:expr, $pool:ident => $body:expr) => {
+        match $db {
+            $crate::db::DbPool::MySql($pool) => $body,
+            $crate::db::DbPool::Sqlite($pool) => $body,
+        }
+    };
+}
+pub(crate) use with_pool;
+
+/// 跨后端的执行结果 (替代 MySQL 专用的 last_insert_id)
+#[derive(Debug, Clone, Copy)]
+pub struct ExecResult {
+    pub rows_affected: u64,
+    pub insert_id: i64,
+}
+
+impl From<MySqlQueryResult> for ExecResult {
+    fn from(r: MySqlQueryResult) -> Self {
+        ExecResult {
+            rows_affected: r.rows_affected(),
+            insert_id: r.last_insert_id() as i64,
+        }
+    }
+}
+
+impl From<SqliteQueryResult> for ExecResult {
+    fn from(r: SqliteQueryResult) -> Self {
+        ExecResult {
+            rows_affected: r.rows_affected(),
+            insert_id: r.last_insert_rowid(),
+        }
+    }
+}
+
+/// 日期范围转换为 UTC 时间边界 [开始日 00:00, 结束日次日 00:00)
+///
+/// 用于替代 `DATE(column) BETWEEN ? AND ?`，两种后端都能直接比较。
+pub fn day_bounds(start_date: NaiveDate, end_date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = start_date.and_time(NaiveTime::MIN).and_utc();
+    let end = (end_date + chrono::Duration::days(1)).and_time(NaiveTime::MIN).and_utc();
+    (start, end)
+}
+
+/// 根据配置构建 MySQL 连接参数
+pub fn mysql_options(db: &DatabaseConfig) -> MySqlConnectOptions {
+    MySqlConnectOptions::new()
+        .host(&db.host)
+        .port(db.port)
+        .username(&db.user)
+        .password(&db.password)
+        .database(&db.database)
+}
+
+/// 初始化数据库连接池 (读取应用配置并执行迁移)
 pub async fn init_pool() -> Result<(), sqlx::Error> {
-    let database_url = format!(
-        "mysql://{}:{}@{}/{}",
-        DB_USER, DB_PASSWORD, DB_HOST, DB_NAME
-    );
+    if !config::is_configured() {
+        return Err(sqlx::Error::Configuration("数据库尚未配置".into()));
+    }
 
-    let pool = MySqlPoolOptions::new()
-        .max_connections(10)
-        .connect(&database_url)
-        .await?;
+    let config = config::load().map_err(|e| sqlx::Error::Configuration(e.into()))?;
+    let db = &config.database;
 
-    DB_POOL
-        .set(pool)
-        .map_err(|_| sqlx::Error::Configuration("数据库连接池已初始化".into()))?;
+    let pool = match db.backend {
+        DatabaseBackend::MySql => {
+            let options = mysql_options(db);
+
+            let pool = MySqlPoolOptions::new()
+                .max_connections(db.max_connections)
+                .min_connections(db.min_connections)
+                .acquire_timeout(Duration::from_secs(db.connect_timeout_secs))
+                .idle_timeout(Duration::from_secs(db.idle_timeout_secs))
+                .connect_with(options)
+                .await?;
+
+            DbPool::MySql(pool)
+        }
+        DatabaseBackend::Sqlite => {
+            let path = config::sqlite_path(db).map_err(|e| sqlx::Error::Configuration(e.into()))?;
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+
+            let options = SqliteConnectOptions::new()
+                .filename(&path)
+                .create_if_missing(true)
+                .foreign_keys(true)
+                .journal_mode(SqliteJournalMode::Wal);
+
+            let pool = SqlitePoolOptions::new()
+                .max_connections(db.max_connections)
+                .acquire_timeout(Duration::from_secs(db.connect_timeout_secs))
+                .connect_with(options)
+                .await?;
+
+            DbPool::Sqlite(pool)
+        }
+    };
+
+    // 启动时应用尚未执行的迁移
+    migrations::run(&pool).await?;
+
+    *DB_POOL.write().unwrap_or_else(|e| e.into_inner()) = Some(pool);
 
     Ok(())
 }
 
-/// 获取数据库连接池
-pub fn get_pool() -> &'static MySqlPool {
-    DB_POOL.get().expect("数据库连接池未初始化")
+/// 连接池是否已初始化
+pub fn is_initialized() -> bool {
+    DB_POOL.read().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+/// 获取数据库连接池 (连接池内部为共享引用，克隆开销很小)
+pub fn get_pool() -> Result<DbPool, DbError> {
+    DB_POOL
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .ok_or(DbError::Unavailable)
 }
 
 /// 测试数据库连接
 pub async fn test_connection() -> Result<bool, sqlx::Error> {
-    let pool = get_pool();
-    sqlx::query("SELECT 1").execute(pool).await?;
+    let pool = get_pool().map_err(|e| sqlx::Error::Configuration(e.into()))?;
+    with_pool!(&pool, pool => sqlx::query("SELECT 1").execute(pool).await.map(ExecResult::from))?;
     Ok(true)
 }
@@ -0,0 +1,155 @@
+// 统一错误类型
+// 命令返回错误时序列化为 {code, message, details}，前端按 code 区分错误类型并本地化提示文本
+use crate::db::DbError;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+/// 应用错误
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    /// 未登录、登录失败或会话已过期
+    #[error("{0}")]
+    Unauthorized(String),
+
+    /// 已登录但权限不足
+    #[error("{0}")]
+    Forbidden(String),
+
+    /// 记录不存在
+    #[error("{0}")]
+    NotFound(String),
+
+    /// 输入校验失败
+    #[error("{0}")]
+    Validation(String),
+
+    /// 与现有数据冲突 (如用户名已存在)
+    #[error("{0}")]
+    Conflict(String),
+
+    /// 任务时间与当天已有任务重叠 (details 为冲突任务的 ID，以逗号分隔)
+    #[error("{message}")]
+    ScheduleConflict { message: String, task_ids: Vec<i64> },
+
+    /// 尝试过于频繁或账户已被临时锁定
+    #[error("{0}")]
+    RateLimited(String),
+
+    /// 连接池尚未建立
+    #[error(transparent)]
+    DatabaseUnavailable(#[from] DbError),
+
+    /// 数据库操作失败
+    #[error("{message}: {source}")]
+    Database {
+        message: String,
+        #[source]
+        source: sqlx::Error,
+    },
+
+    /// AI 服务请求失败或返回内容无法解析
+    #[error("{message}: {details}")]
+    AiProvider { message: String, details: String },
+
+    /// 同步服务器不可用
+    #[error("{message}: {details}")]
+    Sync { message: String, details: String },
+
+    /// 配置文件读写失败
+    #[error("{message}: {details}")]
+    Config { message: String, details: String },
+}
+
+impl AppError {
+    /// 稳定的错误码，前端据此处理错误
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::NotFound(_) => "not_found",
+            AppError::Validation(_) => "validation",
+            AppError::Conflict(_) => "conflict",
+            AppError::ScheduleConflict { .. } => "schedule_conflict",
+            AppError::RateLimited(_) => "rate_limited",
+            AppError::DatabaseUnavailable(_) => "database_unavailable",
+            AppError::Database { .. } => "database",
+            AppError::AiProvider { .. } => "ai_provider",
+            AppError::Sync { .. } => "sync",
+            AppError::Config { .. } => "config",
+        }
+    }
+
+    /// 面向用户的提示文本 (不含底层错误信息)
+    pub fn message(&self) -> String {
+        match self {
+            AppError::Unauthorized(m)
+            | AppError::Forbidden(m)
+            | AppError::NotFound(m)
+            | AppError::Validation(m)
+            | AppError::Conflict(m)
+            | AppError::RateLimited(m) => m.clone(),
+            AppError::DatabaseUnavailable(e) => e.to_string(),
+            AppError::ScheduleConflict { message, .. }
+            | AppError::Database { message, .. }
+            | AppError::AiProvider { message, .. }
+            | AppError::Sync { message, .. }
+            | AppError::Config { message, .. } => message.clone(),
+        }
+    }
+
+    /// 底层错误信息 (用于排查问题)
+    pub fn details(&self) -> Option<String> {
+        match self {
+            AppError::Database { source, .. } => Some(source.to_string()),
+            AppError::ScheduleConflict { task_ids, .. } => Some(
+                task_ids
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            AppError::AiProvider { details, .. }
+            | AppError::Sync { details, .. }
+            | AppError::Config { details, .. } => Some(details.clone()),
+            _ => None,
+        }
+    }
+
+    /// 将数据库错误包装为带说明的错误，查询不到记录时视为 NotFound
+    ///
+    /// 用法: `.map_err(AppError::db("查询任务失败"))?`
+    pub fn db(message: impl Into<String>) -> impl FnOnce(sqlx::Error) -> AppError {
+        let message = message.into();
+        move |source| match source {
+            sqlx::Error::RowNotFound => AppError::NotFound(message),
+            source => AppError::Database { message, source },
+        }
+    }
+
+    /// 将 AI 服务错误包装为带说明的错误
+    pub fn ai(message: impl Into<String>) -> impl FnOnce(reqwest::Error) -> AppError {
+        let message = message.into();
+        move |e| AppError::AiProvider {
+            message,
+            details: e.to_string(),
+        }
+    }
+
+    /// 将配置文件错误包装为带说明的错误
+    pub fn config<E: std::fmt::Display>(message: impl Into<String>) -> impl FnOnce(E) -> AppError {
+        let message = message.into();
+        move |e| AppError::Config {
+            message,
+            details: e.to_string(),
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("AppError", 3)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("message", &self.message())?;
+        s.serialize_field("details", &self.details())?;
+        s.end()
+    }
+}
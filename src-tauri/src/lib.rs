@@ -1,8 +1,12 @@
 // 考研学习规划助手 - Tauri 后端
+use tauri::{Emitter, Manager};
 
 // 模块声明
+pub mod config;
 pub mod db;
+pub mod error;
 pub mod models;
+pub mod repositories;
 pub mod services;
 pub mod commands;
 pub mod utils;
@@ -12,11 +16,11 @@ mod tests;
 
 /// 测试数据库连接命令
 #[tauri::command]
-async fn test_db_connection() -> Result<String, String> {
-    match db::test_connection().await {
-        Ok(_) => Ok("数据库连接成功".to_string()),
-        Err(e) => Err(format!("数据库连接失败: {}", e)),
-    }
+async fn test_db_connection() -> Result<String, error::AppError> {
+    db::test_connection()
+        .await
+        .map_err(error::AppError::db("数据库连接失败"))?;
+    Ok("数据库连接成功".to_string())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -24,29 +28,80 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_notification::init())
+        // 后端保存的当前会话
+        .manage(commands::auth::SessionState::default())
         .setup(|app| {
-            // 初始化数据库连接池
-            tauri::async_runtime::spawn(async {
-                match db::init_pool().await {
-                    Ok(_) => println!("数据库连接池初始化成功"),
-                    Err(e) => eprintln!("数据库连接池初始化失败: {}", e),
-                }
+            // 加载应用配置目录
+            config::init(app.path().app_config_dir()?);
+
+            // 连接状态变化时通知前端
+            let handle = app.handle().clone();
+            db::connection::set_listener(move |status| {
+                let _ = handle.emit(db::connection::STATUS_EVENT, status);
             });
+
+            // 初始化数据库连接池 (失败时在后台按退避策略重试)
+            tauri::async_runtime::spawn(db::connection::run());
+
+            // 后台定时同步 (未启用同步时直接跳过)
+            tauri::async_runtime::spawn(services::sync_service::run_background());
+
+            // 后台定时清理过期会话
+            tauri::async_runtime::spawn(services::auth_service::run_session_cleanup());
+
+            // 后台按保留天数清理过期审计记录
+            tauri::async_runtime::spawn(services::audit_service::run_audit_cleanup());
+
+            // 后台按学习偏好自动顺延未完成的任务
+            tauri::async_runtime::spawn(services::carry_over_service::run_carry_over());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             test_db_connection,
             // 认证命令
             commands::auth::login,
+            commands::auth::verify_login_code,
+            commands::auth::register_with_invite,
             commands::auth::logout,
             commands::auth::change_password,
             commands::auth::get_current_user,
             commands::auth::change_display_name,
+            commands::auth::get_my_sessions,
+            commands::auth::revoke_session,
+            commands::auth::revoke_other_sessions,
+            // 两步验证命令
+            commands::two_factor::get_two_factor_status,
+            commands::two_factor::begin_totp_enrollment,
+            commands::two_factor::confirm_totp_enrollment,
+            commands::two_factor::disable_two_factor,
+            commands::two_factor::regenerate_recovery_codes,
             // 用户管理命令
             commands::admin::get_all_users,
+            commands::admin::get_user_activity,
             commands::admin::create_user,
+            commands::admin::import_users_csv,
+            commands::admin::export_users_csv,
+            commands::admin::deactivate_user,
+            commands::admin::reactivate_user,
             commands::admin::delete_user,
             commands::admin::reset_user_password,
+            commands::admin::reset_user_two_factor,
+            commands::admin::get_locked_accounts,
+            commands::admin::unlock_account,
+            commands::admin::get_login_attempts,
+            commands::admin::get_audit_log,
+            commands::admin::set_user_role,
+            commands::admin::get_mentor_students,
+            commands::admin::assign_student,
+            commands::admin::unassign_student,
+            commands::admin::create_invite_code,
+            commands::admin::get_invite_codes,
+            commands::admin::revoke_invite_code,
+            commands::admin::get_invite_code_users,
+            // 导师命令
+            commands::mentor::get_my_students,
+            commands::mentor::get_student_stats,
+            commands::mentor::get_student_tasks,
             // 科目命令
             commands::subject::get_subjects,
             commands::subject::create_subject,
@@ -57,10 +112,27 @@ pub fn run() {
             commands::countdown::delete_countdown,
             // 任务命令
             commands::task::get_tasks_by_date,
+            commands::task::get_tasks_in_range,
             commands::task::create_task,
             commands::task::update_task,
             commands::task::delete_task,
+            commands::task::create_task_series,
+            commands::task::get_task_series,
+            commands::task::find_free_slots,
+            commands::task::carry_over_tasks,
+            commands::task::add_checklist_item,
+            commands::task::update_checklist_item,
+            commands::task::delete_checklist_item,
+            commands::task::reorder_checklist_items,
+            commands::task::set_task_tags,
+            commands::tag::get_tags,
+            commands::tag::create_tag,
+            commands::tag::update_tag,
+            commands::tag::delete_tag,
             commands::task::toggle_task_status,
+            commands::task::set_task_status,
+            commands::task::get_task_status_history,
+            commands::task::get_failure_reasons,
             commands::task::check_content,
             // 番茄钟命令
             commands::pomodoro::start_pomodoro,
@@ -82,6 +154,19 @@ pub fn run() {
             // 学习偏好命令
             commands::preference::get_study_preference,
             commands::preference::save_study_preference,
+            // 配置命令
+            commands::config::get_db_config,
+            commands::config::setup_db_config,
+            commands::config::save_db_config,
+            commands::config::get_schema_version,
+            commands::config::get_connection_status,
+            // 同步命令
+            commands::sync::sync_status,
+            commands::sync::sync_now,
+            commands::sync::get_sync_conflicts,
+            commands::sync::resolve_sync_conflict,
+            commands::sync::link_sync_account,
+            commands::sync::unlink_sync_account,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,193 @@
+// 审计记录数据模型
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 审计操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    /// 登录成功 (创建会话)
+    #[serde(rename = "session.login")]
+    SessionLogin,
+    /// 退出登录
+    #[serde(rename = "session.logout")]
+    SessionLogout,
+    /// 注销指定会话
+    #[serde(rename = "session.revoke")]
+    SessionRevoke,
+    /// 注销其他全部会话
+    #[serde(rename = "session.revoke_others")]
+    SessionRevokeOthers,
+    /// 用户修改自己的密码
+    #[serde(rename = "password.change")]
+    PasswordChange,
+    /// 管理员重置用户密码
+    #[serde(rename = "password.reset")]
+    PasswordReset,
+    /// 解除账户登录锁定
+    #[serde(rename = "account.unlock")]
+    AccountUnlock,
+    /// 创建用户 (管理员创建或邀请码注册)
+    #[serde(rename = "user.create")]
+    UserCreate,
+    /// 修改用户角色
+    #[serde(rename = "user.role_change")]
+    UserRoleChange,
+    /// 停用用户
+    #[serde(rename = "user.deactivate")]
+    UserDeactivate,
+    /// 重新启用用户
+    #[serde(rename = "user.reactivate")]
+    UserReactivate,
+    /// 永久删除用户 (删除前已导出数据)
+    #[serde(rename = "user.delete")]
+    UserDelete,
+    /// 启用两步验证
+    #[serde(rename = "two_factor.enable")]
+    TwoFactorEnable,
+    /// 用户关闭两步验证
+    #[serde(rename = "two_factor.disable")]
+    TwoFactorDisable,
+    /// 管理员重置用户的两步验证
+    #[serde(rename = "two_factor.reset")]
+    TwoFactorReset,
+    /// 重新生成恢复码
+    #[serde(rename = "two_factor.recovery_codes")]
+    TwoFactorRecoveryCodes,
+    /// 保存 AI 配置
+    #[serde(rename = "ai_config.save")]
+    AiConfigSave,
+    /// 按保留期限清理过期审计记录
+    #[serde(rename = "audit.purge")]
+    AuditPurge,
+}
+
+impl AuditAction {
+    /// 全部操作
+    pub const ALL: [AuditAction; 18] = [
+        AuditAction::SessionLogin,
+        AuditAction::SessionLogout,
+        AuditAction::SessionRevoke,
+        AuditAction::SessionRevokeOthers,
+        AuditAction::PasswordChange,
+        AuditAction::PasswordReset,
+        AuditAction::AccountUnlock,
+        AuditAction::UserCreate,
+        AuditAction::UserRoleChange,
+        AuditAction::UserDeactivate,
+        AuditAction::UserReactivate,
+        AuditAction::UserDelete,
+        AuditAction::TwoFactorEnable,
+        AuditAction::TwoFactorDisable,
+        AuditAction::TwoFactorReset,
+        AuditAction::TwoFactorRecoveryCodes,
+        AuditAction::AiConfigSave,
+        AuditAction::AuditPurge,
+    ];
+
+    /// 操作名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::SessionLogin => "session.login",
+            AuditAction::SessionLogout => "session.logout",
+            AuditAction::SessionRevoke => "session.revoke",
+            AuditAction::SessionRevokeOthers => "session.revoke_others",
+            AuditAction::PasswordChange => "password.change",
+            AuditAction::PasswordReset => "password.reset",
+            AuditAction::AccountUnlock => "account.unlock",
+            AuditAction::UserCreate => "user.create",
+            AuditAction::UserRoleChange => "user.role_change",
+            AuditAction::UserDeactivate => "user.deactivate",
+            AuditAction::UserReactivate => "user.reactivate",
+            AuditAction::UserDelete => "user.delete",
+            AuditAction::TwoFactorEnable => "two_factor.enable",
+            AuditAction::TwoFactorDisable => "two_factor.disable",
+            AuditAction::TwoFactorReset => "two_factor.reset",
+            AuditAction::TwoFactorRecoveryCodes => "two_factor.recovery_codes",
+            AuditAction::AiConfigSave => "ai_config.save",
+            AuditAction::AuditPurge => "audit.purge",
+        }
+    }
+
+    /// 解析操作名称
+    pub fn parse(action: &str) -> Option<AuditAction> {
+        AuditAction::ALL.into_iter().find(|a| a.as_str() == action)
+    }
+}
+
+impl std::fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// 审计记录
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AuditEvent {
+    pub id: i64,
+    /// 操作者 (系统操作为空)
+    pub actor_id: Option<i64>,
+    pub action: String,
+    /// 被操作的用户
+    pub target_user_id: Option<i64>,
+    /// 附加信息 (JSON)
+    pub metadata: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 审计记录过滤条件 (为空的条件不过滤，时间范围为 [since, until))
+#[derive(Debug, Clone, Default)]
+pub struct AuditEventFilter {
+    pub actor_id: Option<i64>,
+    pub target_user_id: Option<i64>,
+    pub action: Option<AuditAction>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl AuditEventFilter {
+    /// 记录是否满足过滤条件
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        self.actor_id.is_none_or(|id| event.actor_id == Some(id))
+            && self.target_user_id.is_none_or(|id| event.target_user_id == Some(id))
+            && self.action.is_none_or(|a| event.action == a.as_str())
+            && self.since.is_none_or(|t| event.created_at >= t)
+            && self.until.is_none_or(|t| event.created_at < t)
+    }
+}
+
+/// 审计日志查询参数
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AuditLogQuery {
+    /// 页码 (从 1 开始)
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+    pub actor_id: Option<i64>,
+    pub target_user_id: Option<i64>,
+    pub action: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// 审计记录响应 (附带操作者和被操作用户的用户名)
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEventResponse {
+    pub id: i64,
+    pub actor_id: Option<i64>,
+    pub actor_username: Option<String>,
+    pub action: String,
+    pub target_user_id: Option<i64>,
+    pub target_username: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 审计日志分页结果
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditLogPage {
+    pub events: Vec<AuditEventResponse>,
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
+}
@@ -0,0 +1,61 @@
+// 未完成任务顺延模型
+use crate::models::{TaskResponse, TaskStatus};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// 顺延方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CarryOverMode {
+    /// 移动：原任务标记为已顺延
+    #[default]
+    Move,
+    /// 复制：原任务标记为未完成
+    Copy,
+}
+
+impl CarryOverMode {
+    /// 解析数据库中保存的顺延方式
+    pub fn parse(s: &str) -> Option<CarryOverMode> {
+        match s {
+            "move" => Some(CarryOverMode::Move),
+            "copy" => Some(CarryOverMode::Copy),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CarryOverMode::Move => "move",
+            CarryOverMode::Copy => "copy",
+        }
+    }
+
+    /// 顺延后原任务的状态
+    pub fn original_status(&self) -> TaskStatus {
+        match self {
+            CarryOverMode::Move => TaskStatus::RolledOver,
+            CarryOverMode::Copy => TaskStatus::Failed,
+        }
+    }
+
+    /// 顺延后原任务的状态原因 (复制时原任务标记为未完成，须有原因)
+    pub fn original_reason(&self) -> Option<&'static str> {
+        match self {
+            CarryOverMode::Move => None,
+            CarryOverMode::Copy => Some("已顺延"),
+        }
+    }
+}
+
+/// 顺延结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CarryOverReport {
+    pub from_date: NaiveDate,
+    pub to_date: NaiveDate,
+    pub mode: CarryOverMode,
+    /// 在目标日期新建的任务 (carried_from_id 指向原任务)
+    pub carried: Vec<TaskResponse>,
+    /// 目标日期没有足够空闲时段、仍留在原日期的任务
+    pub skipped: Vec<TaskResponse>,
+}
@@ -0,0 +1,31 @@
+// 任务清单模型
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 清单项 (按 position 排列)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ChecklistItem {
+    pub id: i64,
+    pub task_id: i64,
+    pub position: i32,
+    pub content: String,
+    pub completed: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 修改清单项输入 (只修改提供的字段)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateChecklistItemInput {
+    pub content: Option<String>,
+    pub completed: Option<bool>,
+}
+
+/// 清单的完成百分比 (向下取整，没有清单项时为 0)
+pub fn checklist_progress(items: &[ChecklistItem]) -> i32 {
+    if items.is_empty() {
+        return 0;
+    }
+    let completed = items.iter().filter(|item| item.completed).count();
+    (completed * 100 / items.len()) as i32
+}
@@ -0,0 +1,106 @@
+// 数据库配置数据模型
+use crate::config::{DatabaseBackend, DatabaseConfig};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 数据库配置响应 (隐藏密码)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbConfigResponse {
+    pub backend: DatabaseBackend,
+    pub sqlite_path: String,
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub password_masked: String,
+    pub database: String,
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub connect_timeout_secs: u64,
+    pub idle_timeout_secs: u64,
+    pub is_configured: bool,
+    pub config_path: Option<String>,
+    pub env_overrides: Vec<String>,
+}
+
+impl From<&DatabaseConfig> for DbConfigResponse {
+    fn from(c: &DatabaseConfig) -> Self {
+        let password_masked = if c.password.is_empty() {
+            String::new()
+        } else {
+            "********".to_string()
+        };
+        DbConfigResponse {
+            backend: c.backend,
+            sqlite_path: c.sqlite_path.clone(),
+            host: c.host.clone(),
+            port: c.port,
+            user: c.user.clone(),
+            password_masked,
+            database: c.database.clone(),
+            max_connections: c.max_connections,
+            min_connections: c.min_connections,
+            connect_timeout_secs: c.connect_timeout_secs,
+            idle_timeout_secs: c.idle_timeout_secs,
+            is_configured: false,
+            config_path: None,
+            env_overrides: vec![],
+        }
+    }
+}
+
+/// 保存数据库配置输入 (未提供的字段保持不变)
+#[derive(Debug, Clone, Deserialize)]
+pub struct SaveDbConfigInput {
+    pub backend: Option<DatabaseBackend>,
+    pub sqlite_path: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub database: Option<String>,
+    pub max_connections: Option<u32>,
+    pub min_connections: Option<u32>,
+    pub connect_timeout_secs: Option<u64>,
+    pub idle_timeout_secs: Option<u64>,
+}
+
+/// 已执行的迁移
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationInfo {
+    pub version: i64,
+    pub description: String,
+    pub installed_on: DateTime<Utc>,
+}
+
+/// 数据库结构版本响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaVersionResponse {
+    pub backend: DatabaseBackend,
+    pub current_version: i64,
+    pub latest_version: i64,
+    pub is_up_to_date: bool,
+    pub applied: Vec<MigrationInfo>,
+}
+
+/// 数据库连接状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionState {
+    /// 尚未配置数据库，等待首次配置
+    NotConfigured,
+    Connecting,
+    Connected,
+    /// 连接失败，正在后台重试
+    Unavailable,
+}
+
+/// 数据库连接状态响应 (同时作为状态变化事件的内容)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionStatus {
+    pub state: ConnectionState,
+    /// 连续失败次数
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub next_retry_at: Option<DateTime<Utc>>,
+    pub connected_at: Option<DateTime<Utc>>,
+}
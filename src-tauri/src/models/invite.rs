@@ -0,0 +1,100 @@
+// 邀请码数据模型
+use super::UserRole;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 邀请码
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InviteCode {
+    pub id: i64,
+    pub code: String,
+    /// 注册账户的预设角色
+    #[sqlx(try_from = "String")]
+    pub role: UserRole,
+    pub max_uses: i32,
+    pub used_count: i32,
+    pub expires_at: DateTime<Utc>,
+    /// 创建邀请码的管理员 (账户删除后为空)
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// 邀请码状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InviteCodeStatus {
+    Active,
+    Expired,
+    Exhausted,
+    Revoked,
+}
+
+impl InviteCode {
+    /// 邀请码在指定时间的状态
+    pub fn status_at(&self, now: DateTime<Utc>) -> InviteCodeStatus {
+        if self.revoked_at.is_some() {
+            InviteCodeStatus::Revoked
+        } else if self.used_count >= self.max_uses {
+            InviteCodeStatus::Exhausted
+        } else if self.expires_at <= now {
+            InviteCodeStatus::Expired
+        } else {
+            InviteCodeStatus::Active
+        }
+    }
+}
+
+/// 邀请码响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InviteCodeResponse {
+    pub id: i64,
+    pub code: String,
+    pub role: String,
+    pub role_label: String,
+    pub max_uses: i32,
+    pub used_count: i32,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub status: InviteCodeStatus,
+}
+
+impl InviteCodeResponse {
+    /// 按指定时间计算状态
+    pub fn from_code(invite: InviteCode, now: DateTime<Utc>) -> Self {
+        let status = invite.status_at(now);
+        InviteCodeResponse {
+            id: invite.id,
+            code: invite.code,
+            role: invite.role.to_string(),
+            role_label: invite.role.label().to_string(),
+            max_uses: invite.max_uses,
+            used_count: invite.used_count,
+            expires_at: invite.expires_at,
+            created_at: invite.created_at,
+            revoked_at: invite.revoked_at,
+            status,
+        }
+    }
+}
+
+/// 创建邀请码输入
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateInviteCodeInput {
+    pub role: String,
+    pub max_uses: i32,
+    /// 有效期 (天)
+    pub expires_in_days: i64,
+}
+
+/// 使用邀请码注册输入
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegisterInput {
+    pub invite_code: String,
+    pub username: String,
+    pub password: String,
+    pub display_name: String,
+}
+
@@ -1,19 +1,47 @@
 // 数据模型模块
 
 pub mod user;
+pub mod permission;
+pub mod invite;
+pub mod audit;
+pub mod two_factor;
+pub mod user_export;
+pub mod user_import;
 pub mod subject;
 pub mod countdown;
 pub mod task;
+pub mod task_series;
+pub mod checklist;
+pub mod tag;
+pub mod task_history;
+pub mod schedule;
+pub mod carry_over;
 pub mod pomodoro;
 pub mod review;
 pub mod ai_config;
 pub mod study_preference;
+pub mod db_config;
+pub mod sync;
 
 pub use user::*;
+pub use permission::*;
+pub use invite::*;
+pub use audit::*;
+pub use two_factor::*;
+pub use user_export::*;
+pub use user_import::*;
 pub use subject::*;
 pub use countdown::*;
 pub use task::*;
+pub use task_series::*;
+pub use checklist::*;
+pub use tag::*;
+pub use task_history::*;
+pub use schedule::*;
+pub use carry_over::*;
 pub use pomodoro::*;
 pub use review::*;
 pub use ai_config::*;
 pub use study_preference::*;
+pub use db_config::*;
+pub use sync::*;
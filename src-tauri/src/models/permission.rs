@@ -0,0 +1,74 @@
+// 权限模型
+// 每个角色对应一组命名权限，命令统一通过授权检查校验权限，不再直接比较角色
+use super::UserRole;
+use serde::{Deserialize, Serialize};
+
+/// 命名权限
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Permission {
+    /// 管理用户账户 (创建、删除、重置密码、分配角色和学生、解除锁定)
+    #[serde(rename = "users.manage")]
+    UsersManage,
+    /// 查看学生的学习统计
+    #[serde(rename = "users.view_stats")]
+    UsersViewStats,
+    /// 查看学生的学习计划
+    #[serde(rename = "users.view_plans")]
+    UsersViewPlans,
+    /// 修改系统配置 (数据库连接等)
+    #[serde(rename = "system.config")]
+    SystemConfig,
+}
+
+impl Permission {
+    /// 全部权限
+    pub const ALL: [Permission; 4] = [
+        Permission::UsersManage,
+        Permission::UsersViewStats,
+        Permission::UsersViewPlans,
+        Permission::SystemConfig,
+    ];
+
+    /// 权限名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::UsersManage => "users.manage",
+            Permission::UsersViewStats => "users.view_stats",
+            Permission::UsersViewPlans => "users.view_plans",
+            Permission::SystemConfig => "system.config",
+        }
+    }
+}
+
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// 导师权限 (仅限分配给自己的学生)
+const MENTOR_PERMISSIONS: [Permission; 2] = [
+    Permission::UsersViewStats,
+    Permission::UsersViewPlans,
+];
+
+impl UserRole {
+    /// 角色拥有的权限
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            UserRole::Admin => &Permission::ALL,
+            UserRole::Mentor => &MENTOR_PERMISSIONS,
+            UserRole::User => &[],
+        }
+    }
+
+    /// 是否拥有指定权限
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+
+    /// 查看学生数据的权限是否仅限于分配给自己的学生
+    pub fn is_scoped_to_students(&self) -> bool {
+        matches!(self, UserRole::Mentor)
+    }
+}
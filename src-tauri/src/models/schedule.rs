@@ -0,0 +1,72 @@
+// 任务时间段模型 (时间冲突、学习时段提醒与空闲时段)
+use crate::models::StudyPreference;
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+
+/// 学习时段 (取自学习偏好，未设置偏好时使用默认值)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StudyWindow {
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub lunch_break_start: NaiveTime,
+    pub lunch_break_end: NaiveTime,
+}
+
+impl Default for StudyWindow {
+    fn default() -> Self {
+        let time = |h| NaiveTime::from_hms_opt(h, 0, 0).unwrap_or_default();
+        StudyWindow {
+            start_time: time(7),
+            end_time: time(22),
+            lunch_break_start: time(12),
+            lunch_break_end: time(14),
+        }
+    }
+}
+
+impl From<&StudyPreference> for StudyWindow {
+    fn from(p: &StudyPreference) -> Self {
+        StudyWindow {
+            start_time: p.start_time,
+            end_time: p.end_time,
+            lunch_break_start: p.lunch_break_start,
+            lunch_break_end: p.lunch_break_end,
+        }
+    }
+}
+
+/// 不符合学习偏好的类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleWarningKind {
+    /// 超出每天的学习时段
+    OutsideStudyWindow,
+    /// 与午休重叠
+    LunchBreak,
+}
+
+/// 不符合学习偏好的提醒 (不阻止保存)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScheduleWarning {
+    pub kind: ScheduleWarningKind,
+    pub message: String,
+}
+
+/// 一段时间
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TimeSlot {
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    /// 时长 (分钟)
+    pub minutes: i64,
+}
+
+impl TimeSlot {
+    pub fn new(start_time: NaiveTime, end_time: NaiveTime) -> Self {
+        TimeSlot {
+            start_time,
+            end_time,
+            minutes: (end_time - start_time).num_minutes(),
+        }
+    }
+}
@@ -1,22 +1,18 @@
 // 学习偏好模型
+use crate::models::CarryOverMode;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
 /// 学习阶段
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum StudyPhase {
+    #[default]
     Foundation,  // 基础阶段
     Strengthen,  // 强化阶段
     Sprint,      // 冲刺阶段
 }
 
-impl Default for StudyPhase {
-    fn default() -> Self {
-        StudyPhase::Foundation
-    }
-}
-
 impl From<String> for StudyPhase {
     fn from(s: String) -> Self {
         match s.as_str() {
@@ -27,12 +23,12 @@ impl From<String> for StudyPhase {
     }
 }
 
-impl ToString for StudyPhase {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for StudyPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            StudyPhase::Foundation => "foundation".to_string(),
-            StudyPhase::Strengthen => "strengthen".to_string(),
-            StudyPhase::Sprint => "sprint".to_string(),
+            StudyPhase::Foundation => write!(f, "foundation"),
+            StudyPhase::Strengthen => write!(f, "strengthen"),
+            StudyPhase::Sprint => write!(f, "sprint"),
         }
     }
 }
@@ -54,6 +50,10 @@ pub struct StudyPreference {
     pub notes: Option<String>,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    /// 自动顺延方式 (为空表示不自动顺延)
+    pub carry_over_mode: Option<String>,
+    /// 最近一次自动顺延的日期
+    pub last_carry_over_date: Option<chrono::NaiveDate>,
 }
 
 /// 学习偏好响应
@@ -72,6 +72,7 @@ pub struct StudyPreferenceResponse {
     pub exam_date: Option<String>,
     pub days_until_exam: Option<i64>,
     pub notes: Option<String>,
+    pub carry_over_mode: Option<CarryOverMode>,
 }
 
 impl From<StudyPreference> for StudyPreferenceResponse {
@@ -109,6 +110,7 @@ impl From<StudyPreference> for StudyPreferenceResponse {
             exam_date: p.exam_date.map(|d| d.format("%Y-%m-%d").to_string()),
             days_until_exam: days_until,
             notes: p.notes,
+            carry_over_mode: p.carry_over_mode.as_deref().and_then(CarryOverMode::parse),
         }
     }
 }
@@ -126,6 +128,9 @@ pub struct SaveStudyPreferenceInput {
     pub weak_subjects: Vec<String>,
     pub exam_date: Option<String>,
     pub notes: Option<String>,
+    /// 每天自动把之前未完成的任务顺延到当天 (为空表示不自动顺延)
+    #[serde(default)]
+    pub carry_over_mode: Option<CarryOverMode>,
 }
 
 /// 默认学习偏好响应
@@ -145,6 +150,7 @@ impl Default for StudyPreferenceResponse {
             exam_date: None,
             days_until_exam: None,
             notes: None,
+            carry_over_mode: None,
         }
     }
 }
@@ -3,6 +3,14 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+/// 默认科目 (名称, 颜色)，与迁移 0002_seed_default_subjects 保持一致
+pub const DEFAULT_SUBJECTS: [(&str, &str); 4] = [
+    ("政治", "#EF4444"),
+    ("英语", "#3B82F6"),
+    ("数学", "#10B981"),
+    ("专业课", "#F59E0B"),
+];
+
 /// 科目模型
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Subject {
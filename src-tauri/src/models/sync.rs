@@ -0,0 +1,74 @@
+// 同步数据模型
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 冲突处理结果
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    LocalWins,
+    RemoteWins,
+}
+
+impl From<String> for ConflictResolution {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "local_wins" => ConflictResolution::LocalWins,
+            _ => ConflictResolution::RemoteWins,
+        }
+    }
+}
+
+impl std::fmt::Display for ConflictResolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConflictResolution::LocalWins => write!(f, "local_wins"),
+            ConflictResolution::RemoteWins => write!(f, "remote_wins"),
+        }
+    }
+}
+
+/// 同步冲突记录 (local_data / remote_data 为 JSON，记录已被删除时为空)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SyncConflict {
+    pub id: i64,
+    pub entity: String,
+    pub sync_id: String,
+    pub local_data: Option<String>,
+    pub remote_data: Option<String>,
+    #[sqlx(try_from = "String")]
+    pub resolution: ConflictResolution,
+    pub resolved: bool,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// 各类记录的待同步数量
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PendingChangeCount {
+    pub entity: String,
+    pub count: i64,
+}
+
+/// 同步状态响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncStatusResponse {
+    pub enabled: bool,
+    pub server: String,
+    /// 是否已验证服务器账户 (未验证时不会同步)
+    pub linked: bool,
+    pub pending_changes: i64,
+    pub pending_by_entity: Vec<PendingChangeCount>,
+    pub unresolved_conflicts: i64,
+    pub last_sync_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+/// 单次同步结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncReport {
+    pub pushed: u32,
+    pub pulled: u32,
+    pub conflicts: u32,
+    pub finished_at: Option<DateTime<Utc>>,
+}
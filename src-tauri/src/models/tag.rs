@@ -0,0 +1,38 @@
+// 任务标签数据模型
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 标签的默认颜色
+pub const DEFAULT_TAG_COLOR: &str = "#6B7280";
+
+/// 标签模型 (用户自定义，可跨科目对任务分组，如 "背诵"、"刷题")
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Tag {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub color: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 任务与标签的关联
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, FromRow, PartialEq, Eq)]
+pub struct TaskTagLink {
+    pub task_id: i64,
+    pub tag_id: i64,
+}
+
+/// 创建标签输入
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateTagInput {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+/// 修改标签输入 (只修改提供的字段)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateTagInput {
+    pub name: Option<String>,
+    pub color: Option<String>,
+}
@@ -1,15 +1,18 @@
 // 任务数据模型
+use crate::models::{ChecklistItem, ScheduleWarning, Tag};
 use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
 /// 任务状态
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 pub enum TaskStatus {
     Pending,
     Completed,
     Failed,
+    /// 已顺延到其他日期 (不再计入当天的任务)
+    RolledOver,
 }
 
 impl From<String> for TaskStatus {
@@ -17,6 +20,7 @@ impl From<String> for TaskStatus {
         match s.as_str() {
             "completed" => TaskStatus::Completed,
             "failed" => TaskStatus::Failed,
+            "rolled_over" => TaskStatus::RolledOver,
             _ => TaskStatus::Pending,
         }
     }
@@ -28,17 +32,50 @@ impl std::fmt::Display for TaskStatus {
             TaskStatus::Pending => write!(f, "pending"),
             TaskStatus::Completed => write!(f, "completed"),
             TaskStatus::Failed => write!(f, "failed"),
+            TaskStatus::RolledOver => write!(f, "rolled_over"),
         }
     }
 }
 
 impl TaskStatus {
-    /// 切换到下一个状态
-    pub fn next(&self) -> Self {
+    /// 切换到下一个状态 (在待完成和已完成之间切换，标记为未完成需要填写原因)；
+    /// 已顺延的任务已有顺延后的新任务，不能再切换
+    pub fn next(&self) -> Option<Self> {
+        match self {
+            TaskStatus::Pending => Some(TaskStatus::Completed),
+            TaskStatus::Completed | TaskStatus::Failed => Some(TaskStatus::Pending),
+            TaskStatus::RolledOver => None,
+        }
+    }
+}
+
+/// 任务优先级
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskPriority {
+    Low,
+    #[default]
+    Normal,
+    /// 必须完成的任务 (如真题模考)
+    High,
+}
+
+impl From<String> for TaskPriority {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "low" => TaskPriority::Low,
+            "high" => TaskPriority::High,
+            _ => TaskPriority::Normal,
+        }
+    }
+}
+
+impl std::fmt::Display for TaskPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            TaskStatus::Pending => TaskStatus::Completed,
-            TaskStatus::Completed => TaskStatus::Failed,
-            TaskStatus::Failed => TaskStatus::Pending,
+            TaskPriority::Low => write!(f, "low"),
+            TaskPriority::Normal => write!(f, "normal"),
+            TaskPriority::High => write!(f, "high"),
         }
     }
 }
@@ -59,11 +96,24 @@ pub struct Task {
     pub alarm_time: Option<NaiveTime>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// 所属的重复任务系列 (普通任务为空)
+    pub series_id: Option<i64>,
+    /// 对应系列中的哪一次重复
+    pub occurrence_date: Option<NaiveDate>,
+    /// 由哪个未完成任务顺延而来
+    pub carried_from_id: Option<i64>,
+    /// 清单的完成百分比 (没有清单时为 0)
+    pub progress: i32,
+    #[sqlx(try_from = "String")]
+    pub priority: TaskPriority,
+    /// 最近一次状态变更的原因
+    pub status_reason: Option<String>,
 }
 
 /// 任务响应 (包含科目信息)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskResponse {
+    /// 任务 ID (尚未生成的重复任务为负数)
     pub id: i64,
     pub subject_id: Option<i64>,
     pub subject_name: Option<String>,
@@ -73,8 +123,24 @@ pub struct TaskResponse {
     pub end_time: NaiveTime,
     pub content: String,
     pub status: String,
+    /// 最近一次状态变更的原因 (未完成的任务为未完成的原因)
+    pub status_reason: Option<String>,
     pub alarm_enabled: bool,
     pub alarm_time: Option<NaiveTime>,
+    pub series_id: Option<i64>,
+    pub carried_from_id: Option<i64>,
+    pub priority: TaskPriority,
+    /// 完成百分比 (已完成的任务为 100，否则为清单的完成百分比)
+    pub progress: i32,
+    /// 按顺序排列的清单项
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItem>,
+    /// 任务的标签 (按名称排序)
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    /// 不符合学习偏好的提醒 (仅在创建和修改任务时返回)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ScheduleWarning>,
 }
 
 /// 创建任务输入
@@ -87,10 +153,12 @@ pub struct CreateTaskInput {
     pub content: String,
     pub alarm_enabled: Option<bool>,
     pub alarm_time: Option<NaiveTime>,
+    /// 优先级 (默认为 normal)
+    pub priority: Option<TaskPriority>,
 }
 
 /// 更新任务输入
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct UpdateTaskInput {
     pub subject_id: Option<i64>,
     pub start_time: Option<NaiveTime>,
@@ -98,4 +166,78 @@ pub struct UpdateTaskInput {
     pub content: Option<String>,
     pub alarm_enabled: Option<bool>,
     pub alarm_time: Option<NaiveTime>,
+    pub priority: Option<TaskPriority>,
+}
+
+/// 任务及其科目信息 (与科目表联合查询的结果)
+#[derive(Debug, Clone, FromRow)]
+pub struct TaskWithSubject {
+    #[sqlx(flatten)]
+    pub task: Task,
+    pub subject_name: Option<String>,
+    pub subject_color: Option<String>,
+}
+
+impl From<TaskWithSubject> for TaskResponse {
+    fn from(row: TaskWithSubject) -> Self {
+        let task = row.task;
+        TaskResponse {
+            id: task.id,
+            subject_id: task.subject_id,
+            subject_name: row.subject_name,
+            subject_color: row.subject_color,
+            task_date: task.task_date,
+            start_time: task.start_time,
+            end_time: task.end_time,
+            content: task.content,
+            status: task.status.to_string(),
+            status_reason: task.status_reason,
+            alarm_enabled: task.alarm_enabled,
+            alarm_time: task.alarm_time,
+            series_id: task.series_id,
+            carried_from_id: task.carried_from_id,
+            priority: task.priority,
+            progress: if task.status == TaskStatus::Completed { 100 } else { task.progress },
+            checklist: Vec::new(),
+            tags: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// 多日任务查询的筛选条件 (均为可选)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TaskFilter {
+    pub subject_id: Option<i64>,
+    pub status: Option<TaskStatus>,
+    /// 任务内容包含的文字 (不区分大小写)
+    pub query: Option<String>,
+    pub priority: Option<TaskPriority>,
+    /// 带有该标签的任务
+    pub tag_id: Option<i64>,
+}
+
+impl TaskFilter {
+    /// 任务 (及其标签 ID) 是否满足筛选条件
+    pub fn matches(&self, task: &Task, tag_ids: &[i64]) -> bool {
+        self.subject_id.is_none_or(|id| task.subject_id == Some(id))
+            && self.status.as_ref().is_none_or(|s| task.status == *s)
+            && self.priority.is_none_or(|p| task.priority == p)
+            && self.tag_id.is_none_or(|id| tag_ids.contains(&id))
+            && self
+                .query
+                .as_ref()
+                .is_none_or(|q| task.content.to_lowercase().contains(&q.to_lowercase()))
+    }
+}
+
+/// 一天的任务及汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskDay {
+    pub date: NaiveDate,
+    pub tasks: Vec<TaskResponse>,
+    pub total_tasks: usize,
+    pub completed_tasks: usize,
+    /// 计划学习时长 (分钟)
+    pub planned_minutes: i64,
 }
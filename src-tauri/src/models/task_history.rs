@@ -0,0 +1,26 @@
+// 任务状态变更记录模型
+use crate::models::TaskStatus;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 任务的一次状态变更
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TaskStatusChange {
+    pub id: i64,
+    pub task_id: i64,
+    #[sqlx(try_from = "String")]
+    pub from_status: TaskStatus,
+    #[sqlx(try_from = "String")]
+    pub to_status: TaskStatus,
+    pub reason: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// 未完成原因统计 (按任务数从多到少排列)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailureReasonStats {
+    /// 未完成的原因 (没有填写原因的任务为空)
+    pub reason: Option<String>,
+    pub count: usize,
+}
@@ -0,0 +1,279 @@
+// 重复任务数据模型
+use crate::models::{Task, TaskPriority, TaskStatus};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 重复频率
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+}
+
+fn default_interval() -> u32 {
+    1
+}
+
+/// 重复规则 (RRULE 的子集：FREQ、INTERVAL、BYDAY、UNTIL、COUNT)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    /// 每隔几天 / 几周重复一次
+    #[serde(default = "default_interval")]
+    pub interval: u32,
+    /// 按周重复时的星期 (1-7 表示周一至周日，为空时取开始日期的星期)
+    #[serde(default)]
+    pub weekdays: Vec<u32>,
+    /// 最后一次重复的日期 (含当天)
+    pub until: Option<NaiveDate>,
+    /// 重复次数 (含开始日期)
+    pub count: Option<u32>,
+}
+
+/// RRULE 中的星期缩写 (周一至周日)
+const WEEKDAY_CODES: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+impl RecurrenceRule {
+    /// 解析 RRULE 文本，如 `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;UNTIL=20261231`
+    pub fn parse(rrule: &str) -> Option<RecurrenceRule> {
+        let mut rule = RecurrenceRule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            weekdays: Vec::new(),
+            until: None,
+            count: None,
+        };
+        let mut frequency = None;
+
+        for part in rrule.trim().trim_start_matches("RRULE:").split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part.split_once('=')?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        _ => return None,
+                    })
+                }
+                "INTERVAL" => rule.interval = value.parse().ok()?,
+                "BYDAY" => {
+                    rule.weekdays = value
+                        .split(',')
+                        .map(|day| {
+                            let day = day.to_ascii_uppercase();
+                            WEEKDAY_CODES.iter().position(|c| *c == day).map(|i| i as u32 + 1)
+                        })
+                        .collect::<Option<Vec<_>>>()?
+                }
+                "UNTIL" => rule.until = Some(NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()?),
+                "COUNT" => rule.count = Some(value.parse().ok()?),
+                _ => return None,
+            }
+        }
+
+        rule.frequency = frequency?;
+        Some(rule)
+    }
+
+    /// 按周重复时实际使用的星期
+    fn effective_weekdays(&self, start: NaiveDate) -> Vec<u32> {
+        if self.weekdays.is_empty() {
+            vec![start.weekday().number_from_monday()]
+        } else {
+            self.weekdays.clone()
+        }
+    }
+
+    /// 日期是否符合重复模式 (不考虑 UNTIL 和 COUNT)
+    fn matches(&self, start: NaiveDate, weekdays: &[u32], date: NaiveDate) -> bool {
+        if date < start || self.interval == 0 {
+            return false;
+        }
+        match self.frequency {
+            Frequency::Daily => (date - start).num_days() % self.interval as i64 == 0,
+            Frequency::Weekly => {
+                let week_start = |d: NaiveDate| d.week(Weekday::Mon).first_day();
+                let weeks = (week_start(date) - week_start(start)).num_days() / 7;
+                weeks % self.interval as i64 == 0 && weekdays.contains(&date.weekday().number_from_monday())
+            }
+        }
+    }
+
+    /// 从开始日期起的全部重复日期 (按时间顺序；没有 UNTIL 和 COUNT 时无限延续，调用方需自行截断)
+    pub fn occurrences(&self, start: NaiveDate) -> impl Iterator<Item = NaiveDate> + '_ {
+        let weekdays = self.effective_weekdays(start);
+        start
+            .iter_days()
+            .take_while(move |d| self.until.is_none_or(|until| *d <= until))
+            .filter(move |d| self.matches(start, &weekdays, *d))
+            .take(self.count.map_or(usize::MAX, |c| c as usize))
+    }
+
+    /// 指定日期是否为一次重复
+    pub fn occurs_on(&self, start: NaiveDate, date: NaiveDate) -> bool {
+        self.occurrences(start).take_while(|d| *d <= date).any(|d| d == date)
+    }
+
+    /// 指定日期之前的重复次数
+    pub fn count_before(&self, start: NaiveDate, date: NaiveDate) -> u32 {
+        self.occurrences(start).take_while(|d| *d < date).count() as u32
+    }
+}
+
+impl std::fmt::Display for RecurrenceRule {
+    /// 输出 RRULE 文本
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+        };
+        write!(f, "FREQ={};INTERVAL={}", frequency, self.interval)?;
+        if !self.weekdays.is_empty() {
+            let days: Vec<&str> = self
+                .weekdays
+                .iter()
+                .filter_map(|d| WEEKDAY_CODES.get((*d as usize).wrapping_sub(1)).copied())
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for RecurrenceRule {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        RecurrenceRule::parse(&s).ok_or_else(|| format!("无效的重复规则: {}", s))
+    }
+}
+
+/// 修改或删除重复任务时的范围
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RecurrenceScope {
+    /// 仅此一次
+    #[default]
+    This,
+    /// 此次及之后的全部重复
+    Following,
+}
+
+/// 尚未生成的重复任务的 ID 中，每个系列占用的范围 (按距 1970-01-01 的天数区分日期)
+const OCCURRENCE_ID_SPAN: i64 = 100_000;
+
+/// 尚未生成的重复任务的 ID：由系列 ID 和日期组成的负数，修改、完成或删除时再生成为实际的任务
+pub fn occurrence_task_id(series_id: i64, date: NaiveDate) -> i64 {
+    -(series_id * OCCURRENCE_ID_SPAN + (date - DateTime::UNIX_EPOCH.date_naive()).num_days())
+}
+
+/// 解析尚未生成的重复任务的 ID，返回 (系列 ID, 日期)；实际任务的 ID 返回 None
+pub fn parse_occurrence_task_id(task_id: i64) -> Option<(i64, NaiveDate)> {
+    let id = task_id.checked_neg().filter(|id| *id > 0)?;
+    let (series_id, days) = (id / OCCURRENCE_ID_SPAN, id % OCCURRENCE_ID_SPAN);
+    (series_id > 0).then(|| (series_id, DateTime::UNIX_EPOCH.date_naive() + Duration::days(days)))
+}
+
+/// 重复任务系列模型 (每一次重复在查询时按规则展开，修改、完成或删除时才生成为 tasks 中的记录)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TaskSeries {
+    pub id: i64,
+    pub user_id: i64,
+    pub subject_id: Option<i64>,
+    pub start_date: NaiveDate,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub content: String,
+    pub alarm_enabled: bool,
+    pub alarm_time: Option<NaiveTime>,
+    #[sqlx(rename = "rrule", try_from = "String")]
+    pub rule: RecurrenceRule,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[sqlx(try_from = "String")]
+    pub priority: TaskPriority,
+}
+
+impl TaskSeries {
+    /// 系列在指定日期尚未生成的一次重复 (待完成，ID 见 occurrence_task_id)
+    pub fn occurrence(&self, date: NaiveDate) -> Task {
+        Task {
+            id: occurrence_task_id(self.id, date),
+            user_id: self.user_id,
+            subject_id: self.subject_id,
+            task_date: date,
+            start_time: self.start_time,
+            end_time: self.end_time,
+            content: self.content.clone(),
+            status: TaskStatus::Pending,
+            alarm_enabled: self.alarm_enabled,
+            alarm_time: self.alarm_time,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            series_id: Some(self.id),
+            occurrence_date: Some(date),
+            carried_from_id: None,
+            progress: 0,
+            priority: self.priority,
+            status_reason: None,
+        }
+    }
+}
+
+/// 重复任务系列响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskSeriesResponse {
+    pub id: i64,
+    pub subject_id: Option<i64>,
+    pub start_date: NaiveDate,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub content: String,
+    pub alarm_enabled: bool,
+    pub alarm_time: Option<NaiveTime>,
+    pub priority: TaskPriority,
+    pub rule: RecurrenceRule,
+    /// RRULE 文本形式
+    pub rrule: String,
+}
+
+impl From<TaskSeries> for TaskSeriesResponse {
+    fn from(series: TaskSeries) -> Self {
+        TaskSeriesResponse {
+            id: series.id,
+            subject_id: series.subject_id,
+            start_date: series.start_date,
+            start_time: series.start_time,
+            end_time: series.end_time,
+            content: series.content,
+            alarm_enabled: series.alarm_enabled,
+            alarm_time: series.alarm_time,
+            priority: series.priority,
+            rrule: series.rule.to_string(),
+            rule: series.rule,
+        }
+    }
+}
+
+/// 创建重复任务输入
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateTaskSeriesInput {
+    pub subject_id: Option<i64>,
+    pub start_date: NaiveDate,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub content: String,
+    pub alarm_enabled: Option<bool>,
+    pub alarm_time: Option<NaiveTime>,
+    /// 优先级 (默认为 normal，生成的任务沿用)
+    pub priority: Option<TaskPriority>,
+    pub rule: RecurrenceRule,
+}
@@ -0,0 +1,94 @@
+// 两步验证数据模型
+use super::LoginResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 用户的 TOTP 密钥
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserTotp {
+    pub user_id: i64,
+    /// Base32 编码的密钥
+    pub secret: String,
+    /// 完成绑定的时间 (为空表示已生成密钥但尚未验证)
+    pub enabled_at: Option<DateTime<Utc>>,
+    /// 最近一次使用的时间步 (同一时间步的验证码不能重复使用)
+    pub last_used_step: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl UserTotp {
+    /// 是否已启用
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+}
+
+/// 一次性恢复码 (只保存哈希)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RecoveryCode {
+    pub id: i64,
+    pub user_id: i64,
+    pub code_hash: String,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+/// 通过密码验证、等待提交验证码的登录
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LoginChallenge {
+    pub id: i64,
+    pub challenge_token: String,
+    pub user_id: i64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 需要两步验证时返回的登录挑战
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwoFactorChallenge {
+    pub challenge_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 登录结果：未启用两步验证时直接登录，否则需要再提交验证码才会发放会话
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum LoginResult {
+    Authenticated(LoginResponse),
+    TwoFactorRequired(TwoFactorChallenge),
+}
+
+impl LoginResult {
+    /// 已登录时的会话信息
+    pub fn session(self) -> Option<LoginResponse> {
+        match self {
+            LoginResult::Authenticated(response) => Some(response),
+            LoginResult::TwoFactorRequired(_) => None,
+        }
+    }
+}
+
+/// 开始绑定 TOTP 的响应 (密钥只在绑定时返回一次)
+#[derive(Debug, Clone, Serialize)]
+pub struct TotpEnrollmentResponse {
+    pub secret: String,
+    /// otpauth 链接，可生成二维码供身份验证器扫描
+    pub provisioning_uri: String,
+}
+
+/// 新生成的恢复码 (明文只返回一次)
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+/// 当前用户的两步验证状态
+#[derive(Debug, Clone, Serialize)]
+pub struct TwoFactorStatus {
+    pub enabled: bool,
+    pub enabled_at: Option<DateTime<Utc>>,
+    /// 剩余可用的恢复码数量
+    pub recovery_codes_remaining: usize,
+    /// 当前账户是否被要求启用两步验证
+    pub required: bool,
+}
@@ -1,4 +1,5 @@
 // 用户数据模型
+use super::Permission;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -8,15 +9,34 @@ use sqlx::FromRow;
 #[serde(rename_all = "lowercase")]
 pub enum UserRole {
     Admin,
+    Mentor,
     User,
 }
 
+impl UserRole {
+    /// 解析角色名称，未知角色返回 None
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "admin" => Some(UserRole::Admin),
+            "mentor" => Some(UserRole::Mentor),
+            "user" => Some(UserRole::User),
+            _ => None,
+        }
+    }
+
+    /// 角色显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            UserRole::Admin => "管理员",
+            UserRole::Mentor => "导师",
+            UserRole::User => "普通用户",
+        }
+    }
+}
+
 impl From<String> for UserRole {
     fn from(s: String) -> Self {
-        match s.as_str() {
-            "admin" => UserRole::Admin,
-            _ => UserRole::User,
-        }
+        UserRole::parse(&s).unwrap_or(UserRole::User)
     }
 }
 
@@ -24,6 +44,7 @@ impl std::fmt::Display for UserRole {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             UserRole::Admin => write!(f, "admin"),
+            UserRole::Mentor => write!(f, "mentor"),
             UserRole::User => write!(f, "user"),
         }
     }
@@ -41,6 +62,17 @@ pub struct User {
     pub role: UserRole,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// 停用时间 (为空表示账户正常)
+    pub deactivated_at: Option<DateTime<Utc>>,
+    /// 是否需要先修改初始密码
+    pub must_change_password: bool,
+}
+
+impl User {
+    /// 账户是否正常 (未停用)
+    pub fn is_active(&self) -> bool {
+        self.deactivated_at.is_none()
+    }
 }
 
 /// 用户响应 (不包含密码)
@@ -51,21 +83,26 @@ pub struct UserResponse {
     pub display_name: String,
     pub role: String,
     pub role_label: String,
+    pub permissions: Vec<Permission>,
+    pub is_active: bool,
+    pub deactivated_at: Option<DateTime<Utc>>,
+    pub must_change_password: bool,
     pub created_at: DateTime<Utc>,
 }
 
 impl From<User> for UserResponse {
     fn from(user: User) -> Self {
-        let role_label = match user.role {
-            UserRole::Admin => "管理员".to_string(),
-            UserRole::User => "普通用户".to_string(),
-        };
+        let is_active = user.is_active();
         UserResponse {
             id: user.id,
             username: user.username,
             display_name: user.display_name,
             role: user.role.to_string(),
-            role_label,
+            role_label: user.role.label().to_string(),
+            permissions: user.role.permissions().to_vec(),
+            is_active,
+            deactivated_at: user.deactivated_at,
+            must_change_password: user.must_change_password,
             created_at: user.created_at,
         }
     }
@@ -88,6 +125,35 @@ pub struct UserSession {
     pub session_token: String,
     pub expires_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+}
+
+/// 登录会话信息 (不包含令牌)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionResponse {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub is_current: bool,
+}
+
+/// 登录尝试记录
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LoginAttempt {
+    pub id: i64,
+    pub username: String,
+    pub success: bool,
+    pub attempted_at: DateTime<Utc>,
+}
+
+/// 按用户名记录的连续登录失败状态
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LoginThrottle {
+    pub username: String,
+    pub failed_count: i32,
+    pub last_failed_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
 }
 
 /// 登录响应
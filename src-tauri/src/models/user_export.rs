@@ -0,0 +1,46 @@
+// 用户数据导出模型
+use super::{
+    ChecklistItem, Countdown, DailyReview, PomodoroSession, StudyPreferenceResponse, Subject, Tag, Task, TaskSeries,
+    TaskStatusChange, TaskTagLink, UserResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// 用户的全部学习数据
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UserData {
+    pub subjects: Vec<Subject>,
+    pub tasks: Vec<Task>,
+    pub checklist_items: Vec<ChecklistItem>,
+    pub tags: Vec<Tag>,
+    pub task_tags: Vec<TaskTagLink>,
+    pub task_status_history: Vec<TaskStatusChange>,
+    pub task_series: Vec<TaskSeries>,
+    pub pomodoros: Vec<PomodoroSession>,
+    pub reviews: Vec<DailyReview>,
+    pub countdowns: Vec<Countdown>,
+    pub preference: Option<StudyPreferenceResponse>,
+}
+
+/// 用户数据归档 (永久删除前导出)
+#[derive(Debug, Clone, Serialize)]
+pub struct UserArchive {
+    pub exported_at: DateTime<Utc>,
+    pub user: UserResponse,
+    #[serde(flatten)]
+    pub data: UserData,
+}
+
+/// 归档结果
+#[derive(Debug, Clone, Serialize)]
+pub struct UserArchiveResponse {
+    pub user_id: i64,
+    pub username: String,
+    /// 归档文件路径
+    pub path: PathBuf,
+    pub tasks: usize,
+    pub pomodoros: usize,
+    pub reviews: usize,
+    pub countdowns: usize,
+}
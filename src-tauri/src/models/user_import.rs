@@ -0,0 +1,46 @@
+// 用户批量导入模型
+use serde::Serialize;
+
+/// CSV 中一行用户的校验结果
+#[derive(Debug, Clone, Serialize)]
+pub struct UserImportRow {
+    /// CSV 行号 (从 1 开始，含表头)
+    pub line: usize,
+    pub username: String,
+    pub display_name: String,
+    pub role: String,
+    /// 校验错误 (为空表示可以导入)
+    pub errors: Vec<String>,
+}
+
+impl UserImportRow {
+    /// 是否通过校验
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// 新账户的初始密码
+#[derive(Debug, Clone, Serialize)]
+pub struct UserCredential {
+    pub username: String,
+    pub display_name: String,
+    pub role: String,
+    pub initial_password: String,
+}
+
+/// 批量导入结果 (任一行未通过校验时不创建任何账户)
+#[derive(Debug, Clone, Serialize)]
+pub struct UserImportReport {
+    pub dry_run: bool,
+    pub total: usize,
+    /// 通过校验的行数
+    pub valid: usize,
+    /// 实际创建的账户数
+    pub created: usize,
+    pub rows: Vec<UserImportRow>,
+    /// 初始密码清单 (仅实际创建时返回，明文只返回一次)
+    pub credentials: Vec<UserCredential>,
+    /// 初始密码清单的 CSV 文本，便于下载分发
+    pub credentials_csv: Option<String>,
+}
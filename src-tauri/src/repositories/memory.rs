@@ -0,0 +1,1379 @@
+// 内存仓储实现 (用于测试)
+// 行为与数据库表结构保持一致：用户名唯一，删除用户级联删除其数据，删除科目时任务的科目置空
+use super::{
+    AuditRepository, InviteCodeRepository, LoginAttemptRepository, MentorRepository,
+    ChecklistRepository, SessionRepository, SubjectRepository, TagRepository, TaskRepository, TaskSeriesRepository,
+    TwoFactorRepository, UserRepository,
+};
+use crate::error::AppError;
+use crate::models::{
+    AuditAction, AuditEvent, AuditEventFilter, ChecklistItem, CreateTaskInput, CreateTaskSeriesInput,
+    CreateUserInput, InviteCode, LoginAttempt, LoginChallenge, LoginThrottle, RecurrenceRule, RecoveryCode,
+    Subject, Tag, Task, TaskFilter, TaskSeries, TaskStatus, TaskStatusChange, TaskTagLink, TaskWithSubject,
+    UpdateChecklistItemInput, UpdateTagInput, UpdateTaskInput, User, UserData, UserRole, UserSession, UserTotp,
+    DEFAULT_SUBJECTS,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::sync::{Mutex, MutexGuard};
+
+/// 内存仓储
+#[derive(Debug, Default)]
+pub struct MemoryRepository {
+    data: Mutex<MemoryData>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryData {
+    next_id: i64,
+    users: Vec<User>,
+    sessions: Vec<UserSession>,
+    login_attempts: Vec<LoginAttempt>,
+    login_throttles: Vec<LoginThrottle>,
+    /// (导师 ID, 学生 ID)
+    mentor_students: Vec<(i64, i64)>,
+    invite_codes: Vec<InviteCode>,
+    /// (邀请码 ID, 用户 ID, 注册时间)
+    invite_uses: Vec<(i64, i64, DateTime<Utc>)>,
+    audit_events: Vec<AuditEvent>,
+    totps: Vec<UserTotp>,
+    recovery_codes: Vec<RecoveryCode>,
+    login_challenges: Vec<LoginChallenge>,
+    subjects: Vec<Subject>,
+    tasks: Vec<Task>,
+    task_series: Vec<TaskSeries>,
+    /// (系列 ID, 被单独删除的日期)
+    task_series_skips: Vec<(i64, NaiveDate)>,
+    checklist_items: Vec<ChecklistItem>,
+    tags: Vec<Tag>,
+    task_tags: Vec<TaskTagLink>,
+    task_status_history: Vec<TaskStatusChange>,
+}
+
+impl MemoryData {
+    /// 分配自增 ID
+    fn next_id(&mut self) -> i64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// 删除任务或标签后清理清单项、标签关联和状态记录 (对应数据库中的级联删除)
+    fn remove_orphan_task_rows(&mut self) {
+        let (tasks, tags) = (&self.tasks, &self.tags);
+        self.checklist_items.retain(|c| tasks.iter().any(|t| t.id == c.task_id));
+        self.task_status_history.retain(|h| tasks.iter().any(|t| t.id == h.task_id));
+        self.task_tags
+            .retain(|l| tasks.iter().any(|t| t.id == l.task_id) && tags.iter().any(|g| g.id == l.tag_id));
+    }
+
+    /// 更新任务状态及原因并记录这次变更
+    fn change_task_status(&mut self, user_id: i64, task_id: i64, status: &TaskStatus, reason: Option<&str>) {
+        let id = self.next_id();
+        let now = Utc::now();
+        let Some(task) = self.tasks.iter_mut().find(|t| t.id == task_id && t.user_id == user_id) else {
+            return;
+        };
+        let from_status = std::mem::replace(&mut task.status, status.clone());
+        task.status_reason = reason.map(str::to_string);
+        task.updated_at = now;
+        self.task_status_history.push(TaskStatusChange {
+            id,
+            task_id,
+            from_status,
+            to_status: status.clone(),
+            reason: task.status_reason.clone(),
+            changed_at: now,
+        });
+    }
+
+    /// 任务的标签 ID
+    fn task_tag_ids(&self, task_id: i64) -> Vec<i64> {
+        self.task_tags.iter().filter(|l| l.task_id == task_id).map(|l| l.tag_id).collect()
+    }
+
+    /// 用户的其他标签中是否已有同名标签
+    fn tag_name_taken(&self, user_id: i64, name: &str, except: Option<i64>) -> bool {
+        self.tags.iter().any(|g| g.user_id == user_id && g.name == name && Some(g.id) != except)
+    }
+}
+
+impl MemoryRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn data(&self) -> MutexGuard<'_, MemoryData> {
+        self.data.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl UserRepository for MemoryRepository {
+    async fn list_users(&self) -> Result<Vec<User>, AppError> {
+        let mut users = self.data().users.clone();
+        users.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+        Ok(users)
+    }
+
+    async fn find_user(&self, user_id: i64) -> Result<Option<User>, AppError> {
+        Ok(self.data().users.iter().find(|u| u.id == user_id).cloned())
+    }
+
+    async fn find_user_by_username(&self, username: &str) -> Result<Option<User>, AppError> {
+        Ok(self.data().users.iter().find(|u| u.username == username).cloned())
+    }
+
+    async fn create_user(&self, input: &CreateUserInput) -> Result<i64, AppError> {
+        let mut data = self.data();
+        if data.users.iter().any(|u| u.username == input.username) {
+            return Err(AppError::Conflict("用户名已存在".to_string()));
+        }
+
+        let id = data.next_id();
+        let now = Utc::now();
+        data.users.push(User {
+            id,
+            username: input.username.clone(),
+            password: input.password.clone(),
+            display_name: input.display_name.clone(),
+            role: UserRole::from(input.role.clone()),
+            created_at: now,
+            updated_at: now,
+            deactivated_at: None,
+            must_change_password: false,
+        });
+        Ok(id)
+    }
+
+    async fn create_users(&self, inputs: &[CreateUserInput]) -> Result<Vec<i64>, AppError> {
+        // 先检查全部用户名，任一冲突时不创建任何用户
+        {
+            let data = self.data();
+            let mut usernames: Vec<&str> = data.users.iter().map(|u| u.username.as_str()).collect();
+            for input in inputs {
+                if usernames.contains(&input.username.as_str()) {
+                    return Err(AppError::Conflict("用户名已存在".to_string()));
+                }
+                usernames.push(&input.username);
+            }
+        }
+
+        let mut user_ids = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            let user_id = self.create_user(input).await?;
+            self.set_must_change_password(user_id, true).await?;
+            for (name, color) in DEFAULT_SUBJECTS {
+                self.create_subject(user_id, name, color, true).await?;
+            }
+            user_ids.push(user_id);
+        }
+        Ok(user_ids)
+    }
+
+    async fn delete_user(&self, user_id: i64) -> Result<(), AppError> {
+        let mut data = self.data();
+        data.users.retain(|u| u.id != user_id);
+        data.sessions.retain(|s| s.user_id != user_id);
+        data.mentor_students.retain(|&(m, s)| m != user_id && s != user_id);
+        data.invite_uses.retain(|&(_, u, _)| u != user_id);
+        for invite in data.invite_codes.iter_mut().filter(|i| i.created_by == Some(user_id)) {
+            invite.created_by = None;
+        }
+        data.totps.retain(|t| t.user_id != user_id);
+        data.recovery_codes.retain(|c| c.user_id != user_id);
+        data.login_challenges.retain(|c| c.user_id != user_id);
+        data.subjects.retain(|s| s.user_id != user_id);
+        data.tasks.retain(|t| t.user_id != user_id);
+        data.tags.retain(|g| g.user_id != user_id);
+        data.remove_orphan_task_rows();
+        let series_ids: Vec<i64> = data.task_series.iter().filter(|s| s.user_id == user_id).map(|s| s.id).collect();
+        data.task_series.retain(|s| s.user_id != user_id);
+        data.task_series_skips.retain(|(id, _)| !series_ids.contains(id));
+        Ok(())
+    }
+
+    async fn update_password(&self, user_id: i64, password: &str) -> Result<(), AppError> {
+        if let Some(user) = self.data().users.iter_mut().find(|u| u.id == user_id) {
+            user.password = password.to_string();
+            user.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+
+    async fn update_display_name(&self, user_id: i64, display_name: &str) -> Result<(), AppError> {
+        if let Some(user) = self.data().users.iter_mut().find(|u| u.id == user_id) {
+            user.display_name = display_name.to_string();
+            user.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+
+    async fn update_role(&self, user_id: i64, role: &UserRole) -> Result<(), AppError> {
+        if let Some(user) = self.data().users.iter_mut().find(|u| u.id == user_id) {
+            user.role = role.clone();
+            user.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+
+    async fn set_deactivated(&self, user_id: i64, deactivated_at: Option<DateTime<Utc>>) -> Result<(), AppError> {
+        if let Some(user) = self.data().users.iter_mut().find(|u| u.id == user_id) {
+            user.deactivated_at = deactivated_at;
+            user.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+
+    async fn set_must_change_password(&self, user_id: i64, required: bool) -> Result<(), AppError> {
+        if let Some(user) = self.data().users.iter_mut().find(|u| u.id == user_id) {
+            user.must_change_password = required;
+        }
+        Ok(())
+    }
+
+    /// 内存仓储只保存科目、任务和标签，其余数据为空
+    async fn load_user_data(&self, user_id: i64) -> Result<UserData, AppError> {
+        let mut tasks: Vec<Task> = self.data().tasks.iter().filter(|t| t.user_id == user_id).cloned().collect();
+        tasks.sort_by(|a, b| a.task_date.cmp(&b.task_date).then(a.start_time.cmp(&b.start_time)));
+        let mut task_series: Vec<TaskSeries> =
+            self.data().task_series.iter().filter(|s| s.user_id == user_id).cloned().collect();
+        task_series.sort_by_key(|s| s.start_date);
+        let mut checklist_items: Vec<ChecklistItem> = self
+            .data()
+            .checklist_items
+            .iter()
+            .filter(|c| tasks.iter().any(|t| t.id == c.task_id))
+            .cloned()
+            .collect();
+        checklist_items.sort_by_key(|c| (c.task_id, c.position));
+        let mut task_tags: Vec<TaskTagLink> = self
+            .data()
+            .task_tags
+            .iter()
+            .filter(|l| tasks.iter().any(|t| t.id == l.task_id))
+            .copied()
+            .collect();
+        task_tags.sort_by_key(|l| (l.task_id, l.tag_id));
+        let task_status_history: Vec<TaskStatusChange> = self
+            .data()
+            .task_status_history
+            .iter()
+            .filter(|h| tasks.iter().any(|t| t.id == h.task_id))
+            .cloned()
+            .collect();
+
+        Ok(UserData {
+            subjects: self.list_subjects(user_id).await?,
+            tasks,
+            checklist_items,
+            tags: self.list_tags(user_id).await?,
+            task_tags,
+            task_status_history,
+            task_series,
+            ..UserData::default()
+        })
+    }
+}
+
+impl SessionRepository for MemoryRepository {
+    async fn create_session(
+        &self,
+        user_id: i64,
+        session_token: &str,
+        created_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut data = self.data();
+        let id = data.next_id();
+        data.sessions.push(UserSession {
+            id,
+            user_id,
+            session_token: session_token.to_string(),
+            expires_at,
+            created_at,
+            last_used_at: created_at,
+        });
+        Ok(())
+    }
+
+    async fn find_session(&self, session_token: &str) -> Result<Option<UserSession>, AppError> {
+        Ok(self
+            .data()
+            .sessions
+            .iter()
+            .find(|s| s.session_token == session_token)
+            .cloned())
+    }
+
+    async fn list_sessions(&self, user_id: i64) -> Result<Vec<UserSession>, AppError> {
+        let mut sessions: Vec<UserSession> = self
+            .data()
+            .sessions
+            .iter()
+            .filter(|s| s.user_id == user_id)
+            .cloned()
+            .collect();
+        sessions.sort_by(|a, b| b.last_used_at.cmp(&a.last_used_at).then(b.id.cmp(&a.id)));
+        Ok(sessions)
+    }
+
+    async fn touch_session(
+        &self,
+        session_token: &str,
+        last_used_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        if let Some(session) = self.data().sessions.iter_mut().find(|s| s.session_token == session_token) {
+            session.last_used_at = last_used_at;
+            session.expires_at = expires_at;
+        }
+        Ok(())
+    }
+
+    async fn delete_session(&self, session_token: &str) -> Result<(), AppError> {
+        self.data().sessions.retain(|s| s.session_token != session_token);
+        Ok(())
+    }
+
+    async fn delete_user_session(&self, user_id: i64, session_id: i64) -> Result<(), AppError> {
+        self.data().sessions.retain(|s| !(s.id == session_id && s.user_id == user_id));
+        Ok(())
+    }
+
+    async fn delete_user_sessions(&self, user_id: i64, except_token: Option<&str>) -> Result<(), AppError> {
+        self.data()
+            .sessions
+            .retain(|s| s.user_id != user_id || except_token == Some(s.session_token.as_str()));
+        Ok(())
+    }
+
+    async fn delete_expired_sessions(&self, now: DateTime<Utc>) -> Result<u64, AppError> {
+        let mut data = self.data();
+        let before = data.sessions.len();
+        data.sessions.retain(|s| s.expires_at > now);
+        Ok((before - data.sessions.len()) as u64)
+    }
+}
+
+impl LoginAttemptRepository for MemoryRepository {
+    async fn record_login_attempt(
+        &self,
+        username: &str,
+        success: bool,
+        attempted_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut data = self.data();
+        let id = data.next_id();
+        data.login_attempts.push(LoginAttempt {
+            id,
+            username: username.to_string(),
+            success,
+            attempted_at,
+        });
+        Ok(())
+    }
+
+    async fn list_login_attempts(&self, username: Option<&str>, limit: i64) -> Result<Vec<LoginAttempt>, AppError> {
+        let mut attempts: Vec<LoginAttempt> = self
+            .data()
+            .login_attempts
+            .iter()
+            .filter(|a| username.is_none_or(|u| a.username == u))
+            .cloned()
+            .collect();
+        attempts.sort_by(|a, b| b.attempted_at.cmp(&a.attempted_at).then(b.id.cmp(&a.id)));
+        attempts.truncate(usize::try_from(limit).unwrap_or(0));
+        Ok(attempts)
+    }
+
+    async fn find_login_throttle(&self, username: &str) -> Result<Option<LoginThrottle>, AppError> {
+        Ok(self
+            .data()
+            .login_throttles
+            .iter()
+            .find(|t| t.username == username)
+            .cloned())
+    }
+
+    async fn increment_login_failures(
+        &self,
+        username: &str,
+        now: DateTime<Utc>,
+        window_start: DateTime<Utc>,
+        max_failures: i32,
+        locked_until: DateTime<Utc>,
+    ) -> Result<LoginThrottle, AppError> {
+        let mut data = self.data();
+        let failed_count = match data.login_throttles.iter().find(|t| t.username == username) {
+            Some(t) if t.last_failed_at > window_start => t.failed_count + 1,
+            _ => 1,
+        };
+        let throttle = LoginThrottle {
+            username: username.to_string(),
+            failed_count,
+            last_failed_at: now,
+            locked_until: (failed_count >= max_failures).then_some(locked_until),
+        };
+        data.login_throttles.retain(|t| t.username != username);
+        data.login_throttles.push(throttle.clone());
+        Ok(throttle)
+    }
+
+    async fn delete_login_throttle(&self, username: &str) -> Result<(), AppError> {
+        self.data().login_throttles.retain(|t| t.username != username);
+        Ok(())
+    }
+
+    async fn list_locked_accounts(&self, now: DateTime<Utc>) -> Result<Vec<LoginThrottle>, AppError> {
+        let mut locked: Vec<LoginThrottle> = self
+            .data()
+            .login_throttles
+            .iter()
+            .filter(|t| t.locked_until.is_some_and(|until| until > now))
+            .cloned()
+            .collect();
+        locked.sort_by_key(|t| t.locked_until);
+        Ok(locked)
+    }
+
+    async fn delete_login_attempts_before(&self, cutoff: DateTime<Utc>) -> Result<u64, AppError> {
+        let mut data = self.data();
+        let before = data.login_attempts.len();
+        data.login_attempts.retain(|a| a.attempted_at >= cutoff);
+        Ok((before - data.login_attempts.len()) as u64)
+    }
+}
+
+impl MentorRepository for MemoryRepository {
+    async fn assign_student(&self, mentor_id: i64, student_id: i64) -> Result<(), AppError> {
+        let mut data = self.data();
+        if !data.mentor_students.contains(&(mentor_id, student_id)) {
+            data.mentor_students.push((mentor_id, student_id));
+        }
+        Ok(())
+    }
+
+    async fn unassign_student(&self, mentor_id: i64, student_id: i64) -> Result<(), AppError> {
+        self.data().mentor_students.retain(|&pair| pair != (mentor_id, student_id));
+        Ok(())
+    }
+
+    async fn list_students(&self, mentor_id: i64) -> Result<Vec<User>, AppError> {
+        let data = self.data();
+        let mut students: Vec<User> = data
+            .users
+            .iter()
+            .filter(|u| data.mentor_students.contains(&(mentor_id, u.id)))
+            .cloned()
+            .collect();
+        students.sort_by(|a, b| a.username.cmp(&b.username));
+        Ok(students)
+    }
+
+    async fn is_student_assigned(&self, mentor_id: i64, student_id: i64) -> Result<bool, AppError> {
+        Ok(self.data().mentor_students.contains(&(mentor_id, student_id)))
+    }
+
+    async fn delete_mentor_students(&self, mentor_id: i64) -> Result<(), AppError> {
+        self.data().mentor_students.retain(|&(m, _)| m != mentor_id);
+        Ok(())
+    }
+}
+
+impl InviteCodeRepository for MemoryRepository {
+    async fn create_invite_code(
+        &self,
+        code: &str,
+        role: &UserRole,
+        max_uses: i32,
+        expires_at: DateTime<Utc>,
+        created_by: i64,
+    ) -> Result<i64, AppError> {
+        let mut data = self.data();
+        if data.invite_codes.iter().any(|i| i.code == code) {
+            return Err(AppError::Conflict("邀请码已存在".to_string()));
+        }
+
+        let id = data.next_id();
+        data.invite_codes.push(InviteCode {
+            id,
+            code: code.to_string(),
+            role: role.clone(),
+            max_uses,
+            used_count: 0,
+            expires_at,
+            created_by: Some(created_by),
+            created_at: Utc::now(),
+            revoked_at: None,
+        });
+        Ok(id)
+    }
+
+    async fn find_invite_code(&self, invite_id: i64) -> Result<Option<InviteCode>, AppError> {
+        Ok(self.data().invite_codes.iter().find(|i| i.id == invite_id).cloned())
+    }
+
+    async fn find_invite_code_by_code(&self, code: &str) -> Result<Option<InviteCode>, AppError> {
+        Ok(self.data().invite_codes.iter().find(|i| i.code == code).cloned())
+    }
+
+    async fn list_invite_codes(&self) -> Result<Vec<InviteCode>, AppError> {
+        let mut invites = self.data().invite_codes.clone();
+        invites.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+        Ok(invites)
+    }
+
+    async fn revoke_invite_code(&self, invite_id: i64, revoked_at: DateTime<Utc>) -> Result<(), AppError> {
+        if let Some(invite) = self
+            .data()
+            .invite_codes
+            .iter_mut()
+            .find(|i| i.id == invite_id && i.revoked_at.is_none())
+        {
+            invite.revoked_at = Some(revoked_at);
+        }
+        Ok(())
+    }
+
+    async fn claim_invite_use(&self, invite_id: i64, now: DateTime<Utc>) -> Result<bool, AppError> {
+        let mut data = self.data();
+        let invite = data.invite_codes.iter_mut().find(|i| {
+            i.id == invite_id && i.revoked_at.is_none() && i.expires_at > now && i.used_count < i.max_uses
+        });
+        Ok(match invite {
+            Some(invite) => {
+                invite.used_count += 1;
+                true
+            }
+            None => false,
+        })
+    }
+
+    async fn release_invite_use(&self, invite_id: i64) -> Result<(), AppError> {
+        if let Some(invite) = self
+            .data()
+            .invite_codes
+            .iter_mut()
+            .find(|i| i.id == invite_id && i.used_count > 0)
+        {
+            invite.used_count -= 1;
+        }
+        Ok(())
+    }
+
+    async fn record_invite_user(&self, invite_id: i64, user_id: i64, used_at: DateTime<Utc>) -> Result<(), AppError> {
+        self.data().invite_uses.push((invite_id, user_id, used_at));
+        Ok(())
+    }
+
+    async fn list_invite_users(&self, invite_id: i64) -> Result<Vec<User>, AppError> {
+        let data = self.data();
+        let mut uses: Vec<&(i64, i64, DateTime<Utc>)> =
+            data.invite_uses.iter().filter(|(i, _, _)| *i == invite_id).collect();
+        uses.sort_by(|a, b| a.2.cmp(&b.2).then(a.1.cmp(&b.1)));
+        Ok(uses
+            .iter()
+            .filter_map(|(_, user_id, _)| data.users.iter().find(|u| u.id == *user_id).cloned())
+            .collect())
+    }
+}
+
+impl AuditRepository for MemoryRepository {
+    async fn record_audit_event(
+        &self,
+        actor_id: Option<i64>,
+        action: AuditAction,
+        target_user_id: Option<i64>,
+        metadata: &serde_json::Value,
+        created_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut data = self.data();
+        let id = data.next_id();
+        data.audit_events.push(AuditEvent {
+            id,
+            actor_id,
+            action: action.as_str().to_string(),
+            target_user_id,
+            metadata: Some(metadata.to_string()),
+            created_at,
+        });
+        Ok(())
+    }
+
+    async fn list_audit_events(
+        &self,
+        filter: &AuditEventFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AuditEvent>, AppError> {
+        let mut events: Vec<AuditEvent> = self
+            .data()
+            .audit_events
+            .iter()
+            .filter(|e| filter.matches(e))
+            .cloned()
+            .collect();
+        events.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+        Ok(events
+            .into_iter()
+            .skip(usize::try_from(offset).unwrap_or(0))
+            .take(usize::try_from(limit).unwrap_or(0))
+            .collect())
+    }
+
+    async fn count_audit_events(&self, filter: &AuditEventFilter) -> Result<i64, AppError> {
+        Ok(self.data().audit_events.iter().filter(|e| filter.matches(e)).count() as i64)
+    }
+
+    async fn delete_audit_events_before(&self, cutoff: DateTime<Utc>) -> Result<u64, AppError> {
+        let mut data = self.data();
+        let before = data.audit_events.len();
+        data.audit_events.retain(|e| e.created_at >= cutoff);
+        Ok((before - data.audit_events.len()) as u64)
+    }
+}
+
+impl TwoFactorRepository for MemoryRepository {
+    async fn find_totp(&self, user_id: i64) -> Result<Option<UserTotp>, AppError> {
+        Ok(self.data().totps.iter().find(|t| t.user_id == user_id).cloned())
+    }
+
+    async fn save_pending_totp(&self, user_id: i64, secret: &str, created_at: DateTime<Utc>) -> Result<(), AppError> {
+        let mut data = self.data();
+        data.totps.retain(|t| t.user_id != user_id);
+        data.recovery_codes.retain(|c| c.user_id != user_id);
+        data.totps.push(UserTotp {
+            user_id,
+            secret: secret.to_string(),
+            enabled_at: None,
+            last_used_step: None,
+            created_at,
+        });
+        Ok(())
+    }
+
+    async fn enable_totp(&self, user_id: i64, enabled_at: DateTime<Utc>, step: i64) -> Result<(), AppError> {
+        if let Some(totp) = self.data().totps.iter_mut().find(|t| t.user_id == user_id) {
+            totp.enabled_at = Some(enabled_at);
+            totp.last_used_step = Some(step);
+        }
+        Ok(())
+    }
+
+    async fn claim_totp_step(&self, user_id: i64, step: i64) -> Result<bool, AppError> {
+        let mut data = self.data();
+        let Some(totp) = data.totps.iter_mut().find(|t| t.user_id == user_id) else {
+            return Ok(false);
+        };
+        if totp.last_used_step.is_some_and(|last| last >= step) {
+            return Ok(false);
+        }
+        totp.last_used_step = Some(step);
+        Ok(true)
+    }
+
+    async fn delete_totp(&self, user_id: i64) -> Result<(), AppError> {
+        let mut data = self.data();
+        data.totps.retain(|t| t.user_id != user_id);
+        data.recovery_codes.retain(|c| c.user_id != user_id);
+        Ok(())
+    }
+
+    async fn replace_recovery_codes(&self, user_id: i64, code_hashes: &[String]) -> Result<(), AppError> {
+        let mut data = self.data();
+        data.recovery_codes.retain(|c| c.user_id != user_id);
+        for code_hash in code_hashes {
+            let id = data.next_id();
+            data.recovery_codes.push(RecoveryCode {
+                id,
+                user_id,
+                code_hash: code_hash.clone(),
+                used_at: None,
+            });
+        }
+        Ok(())
+    }
+
+    async fn list_unused_recovery_codes(&self, user_id: i64) -> Result<Vec<RecoveryCode>, AppError> {
+        Ok(self
+            .data()
+            .recovery_codes
+            .iter()
+            .filter(|c| c.user_id == user_id && c.used_at.is_none())
+            .cloned()
+            .collect())
+    }
+
+    async fn use_recovery_code(&self, code_id: i64, used_at: DateTime<Utc>) -> Result<bool, AppError> {
+        let mut data = self.data();
+        match data.recovery_codes.iter_mut().find(|c| c.id == code_id && c.used_at.is_none()) {
+            Some(code) => {
+                code.used_at = Some(used_at);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn create_login_challenge(
+        &self,
+        user_id: i64,
+        challenge_token: &str,
+        created_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut data = self.data();
+        let id = data.next_id();
+        data.login_challenges.push(LoginChallenge {
+            id,
+            challenge_token: challenge_token.to_string(),
+            user_id,
+            created_at,
+            expires_at,
+        });
+        Ok(())
+    }
+
+    async fn find_login_challenge(&self, challenge_token: &str) -> Result<Option<LoginChallenge>, AppError> {
+        Ok(self
+            .data()
+            .login_challenges
+            .iter()
+            .find(|c| c.challenge_token == challenge_token)
+            .cloned())
+    }
+
+    async fn delete_login_challenge(&self, challenge_token: &str) -> Result<(), AppError> {
+        self.data().login_challenges.retain(|c| c.challenge_token != challenge_token);
+        Ok(())
+    }
+
+    async fn delete_expired_login_challenges(&self, now: DateTime<Utc>) -> Result<u64, AppError> {
+        let mut data = self.data();
+        let before = data.login_challenges.len();
+        data.login_challenges.retain(|c| c.expires_at > now);
+        Ok((before - data.login_challenges.len()) as u64)
+    }
+}
+
+impl SubjectRepository for MemoryRepository {
+    async fn list_subjects(&self, user_id: i64) -> Result<Vec<Subject>, AppError> {
+        let mut subjects: Vec<Subject> = self
+            .data()
+            .subjects
+            .iter()
+            .filter(|s| s.user_id == user_id)
+            .cloned()
+            .collect();
+        subjects.sort_by(|a, b| b.is_default.cmp(&a.is_default).then(a.name.cmp(&b.name)));
+        Ok(subjects)
+    }
+
+    async fn find_subject(&self, subject_id: i64) -> Result<Option<Subject>, AppError> {
+        Ok(self.data().subjects.iter().find(|s| s.id == subject_id).cloned())
+    }
+
+    async fn create_subject(
+        &self,
+        user_id: i64,
+        name: &str,
+        color: &str,
+        is_default: bool,
+    ) -> Result<i64, AppError> {
+        let mut data = self.data();
+        let id = data.next_id();
+        data.subjects.push(Subject {
+            id,
+            user_id,
+            name: name.to_string(),
+            color: color.to_string(),
+            is_default,
+            created_at: Utc::now(),
+        });
+        Ok(id)
+    }
+
+    async fn delete_subject(&self, user_id: i64, subject_id: i64) -> Result<(), AppError> {
+        let mut data = self.data();
+        let before = data.subjects.len();
+        data.subjects
+            .retain(|s| !(s.id == subject_id && s.user_id == user_id && !s.is_default));
+
+        if data.subjects.len() != before {
+            for task in data.tasks.iter_mut().filter(|t| t.subject_id == Some(subject_id)) {
+                task.subject_id = None;
+            }
+            for series in data.task_series.iter_mut().filter(|s| s.subject_id == Some(subject_id)) {
+                series.subject_id = None;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TaskRepository for MemoryRepository {
+    async fn list_tasks_by_date(&self, user_id: i64, date: NaiveDate) -> Result<Vec<Task>, AppError> {
+        let mut tasks: Vec<Task> = self
+            .data()
+            .tasks
+            .iter()
+            .filter(|t| t.user_id == user_id && t.task_date == date)
+            .cloned()
+            .collect();
+        tasks.sort_by_key(|t| t.start_time);
+        Ok(tasks)
+    }
+
+    async fn list_tasks_in_range(
+        &self,
+        user_id: i64,
+        start: NaiveDate,
+        end: NaiveDate,
+        filter: &TaskFilter,
+    ) -> Result<Vec<TaskWithSubject>, AppError> {
+        let data = self.data();
+        let mut tasks: Vec<TaskWithSubject> = data
+            .tasks
+            .iter()
+            .filter(|t| {
+                t.user_id == user_id
+                    && (start..=end).contains(&t.task_date)
+                    && filter.matches(t, &data.task_tag_ids(t.id))
+            })
+            .map(|t| {
+                let subject = t.subject_id.and_then(|sid| data.subjects.iter().find(|s| s.id == sid));
+                TaskWithSubject {
+                    task: t.clone(),
+                    subject_name: subject.map(|s| s.name.clone()),
+                    subject_color: subject.map(|s| s.color.clone()),
+                }
+            })
+            .collect();
+        tasks.sort_by_key(|t| (t.task.task_date, t.task.start_time));
+        Ok(tasks)
+    }
+
+    async fn find_task(&self, user_id: i64, task_id: i64) -> Result<Option<Task>, AppError> {
+        Ok(self
+            .data()
+            .tasks
+            .iter()
+            .find(|t| t.id == task_id && t.user_id == user_id)
+            .cloned())
+    }
+
+    async fn create_task(&self, user_id: i64, input: &CreateTaskInput) -> Result<i64, AppError> {
+        let mut data = self.data();
+        let id = data.next_id();
+        let now = Utc::now();
+        data.tasks.push(Task {
+            id,
+            user_id,
+            subject_id: input.subject_id,
+            task_date: input.task_date,
+            start_time: input.start_time,
+            end_time: input.end_time,
+            content: input.content.clone(),
+            status: TaskStatus::Pending,
+            alarm_enabled: input.alarm_enabled.unwrap_or(false),
+            alarm_time: input.alarm_time,
+            created_at: now,
+            updated_at: now,
+            series_id: None,
+            occurrence_date: None,
+            carried_from_id: None,
+            progress: 0,
+            priority: input.priority.unwrap_or_default(),
+            status_reason: None,
+        });
+        Ok(id)
+    }
+
+    async fn update_task(&self, user_id: i64, task_id: i64, input: &UpdateTaskInput) -> Result<(), AppError> {
+        let mut data = self.data();
+        let Some(task) = data.tasks.iter_mut().find(|t| t.id == task_id && t.user_id == user_id) else {
+            return Ok(());
+        };
+
+        if let Some(v) = input.subject_id {
+            task.subject_id = Some(v);
+        }
+        if let Some(v) = input.start_time {
+            task.start_time = v;
+        }
+        if let Some(v) = input.end_time {
+            task.end_time = v;
+        }
+        if let Some(v) = &input.content {
+            task.content = v.clone();
+        }
+        if let Some(v) = input.alarm_enabled {
+            task.alarm_enabled = v;
+        }
+        if let Some(v) = input.alarm_time {
+            task.alarm_time = Some(v);
+        }
+        if let Some(v) = input.priority {
+            task.priority = v;
+        }
+        task.updated_at = Utc::now();
+        Ok(())
+    }
+
+    async fn delete_task(&self, user_id: i64, task_id: i64) -> Result<(), AppError> {
+        let mut data = self.data();
+        data.tasks.retain(|t| !(t.id == task_id && t.user_id == user_id));
+        data.remove_orphan_task_rows();
+        Ok(())
+    }
+
+    async fn set_task_status(
+        &self,
+        user_id: i64,
+        task_id: i64,
+        status: &TaskStatus,
+        reason: Option<&str>,
+    ) -> Result<(), AppError> {
+        self.data().change_task_status(user_id, task_id, status, reason);
+        Ok(())
+    }
+
+    async fn list_task_status_history(&self, user_id: i64, task_id: i64) -> Result<Vec<TaskStatusChange>, AppError> {
+        let data = self.data();
+        if !data.tasks.iter().any(|t| t.id == task_id && t.user_id == user_id) {
+            return Ok(Vec::new());
+        }
+        Ok(data.task_status_history.iter().filter(|h| h.task_id == task_id).cloned().collect())
+    }
+
+    async fn carry_over_task(
+        &self,
+        user_id: i64,
+        task_id: i64,
+        input: &CreateTaskInput,
+        status: &TaskStatus,
+        reason: Option<&str>,
+    ) -> Result<i64, AppError> {
+        let new_id = self.create_task(user_id, input).await?;
+        let mut data = self.data();
+        if let Some(task) = data.tasks.iter_mut().find(|t| t.id == new_id) {
+            task.carried_from_id = Some(task_id);
+        }
+        data.change_task_status(user_id, task_id, status, reason);
+        Ok(new_id)
+    }
+}
+
+impl TagRepository for MemoryRepository {
+    async fn list_tags(&self, user_id: i64) -> Result<Vec<Tag>, AppError> {
+        let mut tags: Vec<Tag> = self.data().tags.iter().filter(|g| g.user_id == user_id).cloned().collect();
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(tags)
+    }
+
+    async fn find_tag(&self, user_id: i64, tag_id: i64) -> Result<Option<Tag>, AppError> {
+        Ok(self.data().tags.iter().find(|g| g.id == tag_id && g.user_id == user_id).cloned())
+    }
+
+    async fn create_tag(&self, user_id: i64, name: &str, color: &str) -> Result<i64, AppError> {
+        let mut data = self.data();
+        if data.tag_name_taken(user_id, name, None) {
+            return Err(AppError::Conflict("标签已存在".to_string()));
+        }
+
+        let id = data.next_id();
+        data.tags.push(Tag {
+            id,
+            user_id,
+            name: name.to_string(),
+            color: color.to_string(),
+            created_at: Utc::now(),
+        });
+        Ok(id)
+    }
+
+    async fn update_tag(&self, user_id: i64, tag_id: i64, input: &UpdateTagInput) -> Result<(), AppError> {
+        let mut data = self.data();
+        if input.name.as_deref().is_some_and(|name| data.tag_name_taken(user_id, name, Some(tag_id))) {
+            return Err(AppError::Conflict("标签已存在".to_string()));
+        }
+
+        if let Some(tag) = data.tags.iter_mut().find(|g| g.id == tag_id && g.user_id == user_id) {
+            if let Some(v) = &input.name {
+                tag.name = v.clone();
+            }
+            if let Some(v) = &input.color {
+                tag.color = v.clone();
+            }
+        }
+        Ok(())
+    }
+
+    async fn delete_tag(&self, user_id: i64, tag_id: i64) -> Result<(), AppError> {
+        let mut data = self.data();
+        data.tags.retain(|g| !(g.id == tag_id && g.user_id == user_id));
+        data.remove_orphan_task_rows();
+        Ok(())
+    }
+
+    async fn list_task_tags(&self, user_id: i64, task_id: i64) -> Result<Vec<Tag>, AppError> {
+        let data = self.data();
+        let tag_ids = data.task_tag_ids(task_id);
+        let mut tags: Vec<Tag> = data
+            .tags
+            .iter()
+            .filter(|g| g.user_id == user_id && tag_ids.contains(&g.id))
+            .cloned()
+            .collect();
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(tags)
+    }
+
+    async fn list_task_tag_links_in_range(
+        &self,
+        user_id: i64,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<TaskTagLink>, AppError> {
+        let data = self.data();
+        Ok(data
+            .task_tags
+            .iter()
+            .filter(|l| {
+                data.tasks
+                    .iter()
+                    .any(|t| t.id == l.task_id && t.user_id == user_id && (start..=end).contains(&t.task_date))
+            })
+            .copied()
+            .collect())
+    }
+
+    async fn set_task_tags(&self, task_id: i64, tag_ids: &[i64]) -> Result<(), AppError> {
+        let mut data = self.data();
+        data.task_tags.retain(|l| l.task_id != task_id);
+        data.task_tags.extend(tag_ids.iter().map(|&tag_id| TaskTagLink { task_id, tag_id }));
+        Ok(())
+    }
+}
+
+impl TaskSeriesRepository for MemoryRepository {
+    async fn list_task_series(&self, user_id: i64, until: NaiveDate) -> Result<Vec<TaskSeries>, AppError> {
+        let mut series: Vec<TaskSeries> = self
+            .data()
+            .task_series
+            .iter()
+            .filter(|s| s.user_id == user_id && s.start_date <= until)
+            .cloned()
+            .collect();
+        series.sort_by_key(|s| s.start_time);
+        Ok(series)
+    }
+
+    async fn find_task_series(&self, user_id: i64, series_id: i64) -> Result<Option<TaskSeries>, AppError> {
+        Ok(self
+            .data()
+            .task_series
+            .iter()
+            .find(|s| s.id == series_id && s.user_id == user_id)
+            .cloned())
+    }
+
+    async fn create_task_series(&self, user_id: i64, input: &CreateTaskSeriesInput) -> Result<i64, AppError> {
+        let mut data = self.data();
+        let id = data.next_id();
+        let now = Utc::now();
+        data.task_series.push(TaskSeries {
+            id,
+            user_id,
+            subject_id: input.subject_id,
+            start_date: input.start_date,
+            start_time: input.start_time,
+            end_time: input.end_time,
+            content: input.content.clone(),
+            alarm_enabled: input.alarm_enabled.unwrap_or(false),
+            alarm_time: input.alarm_time,
+            rule: input.rule.clone(),
+            created_at: now,
+            updated_at: now,
+            priority: input.priority.unwrap_or_default(),
+        });
+        Ok(id)
+    }
+
+    async fn update_task_series(&self, user_id: i64, series_id: i64, input: &UpdateTaskInput) -> Result<(), AppError> {
+        let mut data = self.data();
+        let Some(series) = data.task_series.iter_mut().find(|s| s.id == series_id && s.user_id == user_id) else {
+            return Ok(());
+        };
+
+        if let Some(v) = input.subject_id {
+            series.subject_id = Some(v);
+        }
+        if let Some(v) = input.start_time {
+            series.start_time = v;
+        }
+        if let Some(v) = input.end_time {
+            series.end_time = v;
+        }
+        if let Some(v) = &input.content {
+            series.content = v.clone();
+        }
+        if let Some(v) = input.alarm_enabled {
+            series.alarm_enabled = v;
+        }
+        if let Some(v) = input.alarm_time {
+            series.alarm_time = Some(v);
+        }
+        if let Some(v) = input.priority {
+            series.priority = v;
+        }
+        series.updated_at = Utc::now();
+        Ok(())
+    }
+
+    async fn set_task_series_rule(&self, user_id: i64, series_id: i64, rule: &RecurrenceRule) -> Result<(), AppError> {
+        let mut data = self.data();
+        if let Some(series) = data.task_series.iter_mut().find(|s| s.id == series_id && s.user_id == user_id) {
+            series.rule = rule.clone();
+            series.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+
+    async fn delete_task_series(&self, user_id: i64, series_id: i64) -> Result<(), AppError> {
+        let mut data = self.data();
+        let before = data.task_series.len();
+        data.task_series.retain(|s| !(s.id == series_id && s.user_id == user_id));
+
+        if data.task_series.len() != before {
+            data.task_series_skips.retain(|(id, _)| *id != series_id);
+            for task in data.tasks.iter_mut().filter(|t| t.series_id == Some(series_id)) {
+                task.series_id = None;
+            }
+        }
+        Ok(())
+    }
+
+    async fn occurrence_exists(&self, series_id: i64, date: NaiveDate) -> Result<bool, AppError> {
+        let data = self.data();
+        Ok(data
+            .tasks
+            .iter()
+            .any(|t| t.series_id == Some(series_id) && t.occurrence_date == Some(date))
+            || data.task_series_skips.contains(&(series_id, date)))
+    }
+
+    async fn list_occurrence_dates(
+        &self,
+        user_id: i64,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<(i64, NaiveDate)>, AppError> {
+        let data = self.data();
+        let in_range = |d: &NaiveDate| (start..=end).contains(d);
+        let created = data
+            .tasks
+            .iter()
+            .filter(|t| t.user_id == user_id)
+            .filter_map(|t| t.series_id.zip(t.occurrence_date))
+            .filter(|(_, d)| in_range(d));
+        let skipped = data
+            .task_series_skips
+            .iter()
+            .filter(|(id, d)| in_range(d) && data.task_series.iter().any(|s| s.id == *id && s.user_id == user_id))
+            .copied();
+        Ok(created.chain(skipped).collect())
+    }
+
+    async fn create_task_occurrence(&self, series: &TaskSeries, date: NaiveDate) -> Result<i64, AppError> {
+        let mut data = self.data();
+        if data
+            .tasks
+            .iter()
+            .any(|t| t.series_id == Some(series.id) && t.occurrence_date == Some(date))
+        {
+            return Err(AppError::Conflict("重复任务已生成".to_string()));
+        }
+
+        let id = data.next_id();
+        let now = Utc::now();
+        data.tasks.push(Task {
+            id,
+            user_id: series.user_id,
+            subject_id: series.subject_id,
+            task_date: date,
+            start_time: series.start_time,
+            end_time: series.end_time,
+            content: series.content.clone(),
+            status: TaskStatus::Pending,
+            alarm_enabled: series.alarm_enabled,
+            alarm_time: series.alarm_time,
+            created_at: now,
+            updated_at: now,
+            series_id: Some(series.id),
+            occurrence_date: Some(date),
+            carried_from_id: None,
+            progress: 0,
+            priority: series.priority,
+            status_reason: None,
+        });
+        Ok(id)
+    }
+
+    async fn skip_task_occurrence(&self, series_id: i64, date: NaiveDate) -> Result<(), AppError> {
+        let mut data = self.data();
+        if !data.task_series_skips.contains(&(series_id, date)) {
+            data.task_series_skips.push((series_id, date));
+        }
+        Ok(())
+    }
+
+    async fn list_task_occurrences(
+        &self,
+        user_id: i64,
+        series_id: i64,
+        from: NaiveDate,
+    ) -> Result<Vec<Task>, AppError> {
+        let mut tasks: Vec<Task> = self
+            .data()
+            .tasks
+            .iter()
+            .filter(|t| t.user_id == user_id && t.series_id == Some(series_id))
+            .filter(|t| t.occurrence_date.is_some_and(|d| d >= from))
+            .cloned()
+            .collect();
+        tasks.sort_by_key(|t| t.occurrence_date);
+        Ok(tasks)
+    }
+
+    async fn move_task_occurrences(
+        &self,
+        user_id: i64,
+        series_id: i64,
+        to_series_id: i64,
+        from: NaiveDate,
+    ) -> Result<(), AppError> {
+        let mut data = self.data();
+        for task in data.tasks.iter_mut().filter(|t| t.user_id == user_id && t.series_id == Some(series_id)) {
+            if task.occurrence_date.is_some_and(|d| d >= from) {
+                task.series_id = Some(to_series_id);
+            }
+        }
+        for (id, date) in data.task_series_skips.iter_mut() {
+            if *id == series_id && *date >= from {
+                *id = to_series_id;
+            }
+        }
+        Ok(())
+    }
+
+    async fn delete_task_occurrences(&self, user_id: i64, series_id: i64, from: NaiveDate) -> Result<(), AppError> {
+        let mut data = self.data();
+        data.tasks.retain(|t| {
+            !(t.user_id == user_id && t.series_id == Some(series_id) && t.occurrence_date.is_some_and(|d| d >= from))
+        });
+        data.task_series_skips.retain(|(id, date)| !(*id == series_id && *date >= from));
+        data.remove_orphan_task_rows();
+        Ok(())
+    }
+}
+
+impl ChecklistRepository for MemoryRepository {
+    async fn list_checklist_items(&self, user_id: i64, task_id: i64) -> Result<Vec<ChecklistItem>, AppError> {
+        let data = self.data();
+        if !data.tasks.iter().any(|t| t.id == task_id && t.user_id == user_id) {
+            return Ok(Vec::new());
+        }
+        let mut items: Vec<ChecklistItem> =
+            data.checklist_items.iter().filter(|c| c.task_id == task_id).cloned().collect();
+        items.sort_by_key(|c| (c.position, c.id));
+        Ok(items)
+    }
+
+    async fn list_checklist_items_in_range(
+        &self,
+        user_id: i64,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<ChecklistItem>, AppError> {
+        let data = self.data();
+        let mut items: Vec<ChecklistItem> = data
+            .checklist_items
+            .iter()
+            .filter(|c| {
+                data.tasks
+                    .iter()
+                    .any(|t| t.id == c.task_id && t.user_id == user_id && t.task_date >= start && t.task_date <= end)
+            })
+            .cloned()
+            .collect();
+        items.sort_by_key(|c| (c.task_id, c.position, c.id));
+        Ok(items)
+    }
+
+    async fn find_checklist_item(&self, user_id: i64, item_id: i64) -> Result<Option<ChecklistItem>, AppError> {
+        let data = self.data();
+        Ok(data
+            .checklist_items
+            .iter()
+            .find(|c| c.id == item_id && data.tasks.iter().any(|t| t.id == c.task_id && t.user_id == user_id))
+            .cloned())
+    }
+
+    async fn create_checklist_item(
+        &self,
+        task_id: i64,
+        position: i32,
+        content: &str,
+        completed: bool,
+    ) -> Result<i64, AppError> {
+        let mut data = self.data();
+        let id = data.next_id();
+        data.checklist_items.push(ChecklistItem {
+            id,
+            task_id,
+            position,
+            content: content.to_string(),
+            completed,
+            created_at: Utc::now(),
+        });
+        Ok(id)
+    }
+
+    async fn update_checklist_item(&self, item_id: i64, input: &UpdateChecklistItemInput) -> Result<(), AppError> {
+        if let Some(item) = self.data().checklist_items.iter_mut().find(|c| c.id == item_id) {
+            if let Some(content) = &input.content {
+                item.content = content.clone();
+            }
+            if let Some(completed) = input.completed {
+                item.completed = completed;
+            }
+        }
+        Ok(())
+    }
+
+    async fn delete_checklist_item(&self, item_id: i64) -> Result<(), AppError> {
+        self.data().checklist_items.retain(|c| c.id != item_id);
+        Ok(())
+    }
+
+    async fn reorder_checklist_items(&self, task_id: i64, item_ids: &[i64]) -> Result<(), AppError> {
+        for item in self.data().checklist_items.iter_mut().filter(|c| c.task_id == task_id) {
+            if let Some(position) = item_ids.iter().position(|id| *id == item.id) {
+                item.position = position as i32;
+            }
+        }
+        Ok(())
+    }
+
+    async fn set_task_progress(&self, user_id: i64, task_id: i64, progress: i32) -> Result<(), AppError> {
+        if let Some(task) = self
+            .data()
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id && t.user_id == user_id)
+        {
+            task.progress = progress;
+            task.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+}
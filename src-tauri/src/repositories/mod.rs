@@ -0,0 +1,622 @@
+// 数据访问层
+// 服务通过仓储接口读写数据：应用中使用基于连接池的 SqlRepository，测试中使用 MemoryRepository
+use crate::error::AppError;
+use crate::models::{
+    AuditAction, AuditEvent, AuditEventFilter, ChecklistItem, CreateTaskInput, CreateTaskSeriesInput,
+    CreateUserInput, InviteCode, LoginAttempt, LoginChallenge, LoginThrottle, RecurrenceRule, RecoveryCode,
+    Subject, Tag, Task, TaskFilter, TaskSeries, TaskStatus, TaskStatusChange, TaskTagLink, TaskWithSubject,
+    UpdateChecklistItemInput, UpdateTagInput, UpdateTaskInput, User, UserData, UserRole, UserSession, UserTotp,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::future::Future;
+
+pub mod memory;
+pub mod sql;
+
+pub use memory::MemoryRepository;
+pub use sql::SqlRepository;
+
+/// 用户仓储
+pub trait UserRepository {
+    /// 查询全部用户 (按创建时间倒序)
+    fn list_users(&self) -> impl Future<Output = Result<Vec<User>, AppError>> + Send;
+
+    /// 根据 ID 查询用户
+    fn find_user(&self, user_id: i64) -> impl Future<Output = Result<Option<User>, AppError>> + Send;
+
+    /// 根据用户名查询用户
+    fn find_user_by_username(
+        &self,
+        username: &str,
+    ) -> impl Future<Output = Result<Option<User>, AppError>> + Send;
+
+    /// 创建用户，返回新用户 ID
+    fn create_user(&self, input: &CreateUserInput) -> impl Future<Output = Result<i64, AppError>> + Send;
+
+    /// 在同一事务中批量创建用户并写入默认科目，返回新用户 ID (任一失败时全部回滚)
+    /// 批量创建的用户使用分发的初始密码，首次登录后必须修改密码
+    fn create_users(&self, inputs: &[CreateUserInput]) -> impl Future<Output = Result<Vec<i64>, AppError>> + Send;
+
+    /// 删除用户 (会话、科目、任务等数据一并删除)
+    fn delete_user(&self, user_id: i64) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 更新密码
+    fn update_password(
+        &self,
+        user_id: i64,
+        password: &str,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 更新昵称
+    fn update_display_name(
+        &self,
+        user_id: i64,
+        display_name: &str,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 更新角色
+    fn update_role(&self, user_id: i64, role: &UserRole) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 设置停用时间 (为空表示重新启用)
+    fn set_deactivated(
+        &self,
+        user_id: i64,
+        deactivated_at: Option<DateTime<Utc>>,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 设置是否需要先修改初始密码
+    fn set_must_change_password(
+        &self,
+        user_id: i64,
+        required: bool,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 读取用户的全部学习数据 (用于导出)
+    fn load_user_data(&self, user_id: i64) -> impl Future<Output = Result<UserData, AppError>> + Send;
+}
+
+/// 登录会话仓储
+pub trait SessionRepository {
+    /// 保存会话
+    fn create_session(
+        &self,
+        user_id: i64,
+        session_token: &str,
+        created_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 根据令牌查询会话 (包括已过期的会话)
+    fn find_session(
+        &self,
+        session_token: &str,
+    ) -> impl Future<Output = Result<Option<UserSession>, AppError>> + Send;
+
+    /// 查询用户的全部会话 (包括已过期的会话，按最近使用时间倒序)
+    fn list_sessions(&self, user_id: i64) -> impl Future<Output = Result<Vec<UserSession>, AppError>> + Send;
+
+    /// 更新会话的最近使用时间与过期时间
+    fn touch_session(
+        &self,
+        session_token: &str,
+        last_used_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 删除会话
+    fn delete_session(&self, session_token: &str) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 删除用户的指定会话
+    fn delete_user_session(&self, user_id: i64, session_id: i64) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 删除用户的全部会话，可保留指定令牌的会话
+    fn delete_user_sessions(
+        &self,
+        user_id: i64,
+        except_token: Option<&str>,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 删除在指定时间已过期的会话，返回删除数量
+    fn delete_expired_sessions(&self, now: DateTime<Utc>) -> impl Future<Output = Result<u64, AppError>> + Send;
+}
+
+/// 登录尝试与限流状态仓储
+pub trait LoginAttemptRepository {
+    /// 记录一次登录尝试
+    fn record_login_attempt(
+        &self,
+        username: &str,
+        success: bool,
+        attempted_at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 查询最近的登录尝试 (按时间倒序，可按用户名过滤)
+    fn list_login_attempts(
+        &self,
+        username: Option<&str>,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<LoginAttempt>, AppError>> + Send;
+
+    /// 查询用户名的限流状态
+    fn find_login_throttle(
+        &self,
+        username: &str,
+    ) -> impl Future<Output = Result<Option<LoginThrottle>, AppError>> + Send;
+
+    /// 原子地累计一次登录失败，返回更新后的限流状态
+    /// (上次失败早于 window_start 时从 1 重新计数，累计达到 max_failures 次时锁定到 locked_until)
+    fn increment_login_failures(
+        &self,
+        username: &str,
+        now: DateTime<Utc>,
+        window_start: DateTime<Utc>,
+        max_failures: i32,
+        locked_until: DateTime<Utc>,
+    ) -> impl Future<Output = Result<LoginThrottle, AppError>> + Send;
+
+    /// 删除限流状态
+    fn delete_login_throttle(&self, username: &str) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 查询在指定时间仍处于锁定中的账户 (按锁定截止时间排序)
+    fn list_locked_accounts(
+        &self,
+        now: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<LoginThrottle>, AppError>> + Send;
+
+    /// 删除指定时间之前的登录尝试记录，返回删除数量
+    fn delete_login_attempts_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> impl Future<Output = Result<u64, AppError>> + Send;
+}
+
+/// 导师与学生分配仓储
+pub trait MentorRepository {
+    /// 将学生分配给导师 (已分配时不变)
+    fn assign_student(&self, mentor_id: i64, student_id: i64) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 取消分配
+    fn unassign_student(&self, mentor_id: i64, student_id: i64) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 查询分配给导师的学生 (按用户名排序)
+    fn list_students(&self, mentor_id: i64) -> impl Future<Output = Result<Vec<User>, AppError>> + Send;
+
+    /// 学生是否已分配给导师
+    fn is_student_assigned(
+        &self,
+        mentor_id: i64,
+        student_id: i64,
+    ) -> impl Future<Output = Result<bool, AppError>> + Send;
+
+    /// 删除导师的全部分配
+    fn delete_mentor_students(&self, mentor_id: i64) -> impl Future<Output = Result<(), AppError>> + Send;
+}
+
+/// 邀请码仓储
+pub trait InviteCodeRepository {
+    /// 创建邀请码，返回新邀请码 ID
+    fn create_invite_code(
+        &self,
+        code: &str,
+        role: &UserRole,
+        max_uses: i32,
+        expires_at: DateTime<Utc>,
+        created_by: i64,
+    ) -> impl Future<Output = Result<i64, AppError>> + Send;
+
+    /// 根据 ID 查询邀请码
+    fn find_invite_code(&self, invite_id: i64) -> impl Future<Output = Result<Option<InviteCode>, AppError>> + Send;
+
+    /// 根据邀请码查询
+    fn find_invite_code_by_code(
+        &self,
+        code: &str,
+    ) -> impl Future<Output = Result<Option<InviteCode>, AppError>> + Send;
+
+    /// 查询全部邀请码 (按创建时间倒序)
+    fn list_invite_codes(&self) -> impl Future<Output = Result<Vec<InviteCode>, AppError>> + Send;
+
+    /// 撤销邀请码
+    fn revoke_invite_code(
+        &self,
+        invite_id: i64,
+        revoked_at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 占用一次使用次数 (邀请码未撤销、未过期且未用完时成功)
+    fn claim_invite_use(&self, invite_id: i64, now: DateTime<Utc>) -> impl Future<Output = Result<bool, AppError>> + Send;
+
+    /// 归还占用的使用次数 (注册失败时调用)
+    fn release_invite_use(&self, invite_id: i64) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 记录邀请码注册的账户
+    fn record_invite_user(
+        &self,
+        invite_id: i64,
+        user_id: i64,
+        used_at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 查询邀请码注册的账户 (按注册时间排序)
+    fn list_invite_users(&self, invite_id: i64) -> impl Future<Output = Result<Vec<User>, AppError>> + Send;
+}
+
+/// 审计记录仓储
+pub trait AuditRepository {
+    /// 写入审计记录
+    fn record_audit_event(
+        &self,
+        actor_id: Option<i64>,
+        action: AuditAction,
+        target_user_id: Option<i64>,
+        metadata: &serde_json::Value,
+        created_at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 分页查询满足条件的审计记录 (按时间倒序)
+    fn list_audit_events(
+        &self,
+        filter: &AuditEventFilter,
+        limit: i64,
+        offset: i64,
+    ) -> impl Future<Output = Result<Vec<AuditEvent>, AppError>> + Send;
+
+    /// 统计满足条件的审计记录数量
+    fn count_audit_events(&self, filter: &AuditEventFilter) -> impl Future<Output = Result<i64, AppError>> + Send;
+
+    /// 删除指定时间之前的审计记录，返回删除数量
+    fn delete_audit_events_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> impl Future<Output = Result<u64, AppError>> + Send;
+}
+
+/// 两步验证仓储
+pub trait TwoFactorRepository {
+    /// 查询用户的 TOTP 密钥
+    fn find_totp(&self, user_id: i64) -> impl Future<Output = Result<Option<UserTotp>, AppError>> + Send;
+
+    /// 保存尚未启用的 TOTP 密钥 (替换该用户原有的密钥)
+    fn save_pending_totp(
+        &self,
+        user_id: i64,
+        secret: &str,
+        created_at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 启用 TOTP 并记录绑定时使用的时间步
+    fn enable_totp(
+        &self,
+        user_id: i64,
+        enabled_at: DateTime<Utc>,
+        step: i64,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 占用时间步 (仅当该时间步晚于最近一次使用的时间步时成功，防止验证码重放)
+    fn claim_totp_step(&self, user_id: i64, step: i64) -> impl Future<Output = Result<bool, AppError>> + Send;
+
+    /// 删除用户的 TOTP 密钥和全部恢复码
+    fn delete_totp(&self, user_id: i64) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 替换用户的全部恢复码
+    fn replace_recovery_codes(
+        &self,
+        user_id: i64,
+        code_hashes: &[String],
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 查询用户尚未使用的恢复码
+    fn list_unused_recovery_codes(
+        &self,
+        user_id: i64,
+    ) -> impl Future<Output = Result<Vec<RecoveryCode>, AppError>> + Send;
+
+    /// 使用恢复码 (仅当尚未使用时成功)
+    fn use_recovery_code(
+        &self,
+        code_id: i64,
+        used_at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<bool, AppError>> + Send;
+
+    /// 保存登录挑战
+    fn create_login_challenge(
+        &self,
+        user_id: i64,
+        challenge_token: &str,
+        created_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 根据令牌查询登录挑战 (包括已过期的挑战)
+    fn find_login_challenge(
+        &self,
+        challenge_token: &str,
+    ) -> impl Future<Output = Result<Option<LoginChallenge>, AppError>> + Send;
+
+    /// 删除登录挑战
+    fn delete_login_challenge(&self, challenge_token: &str) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 删除已过期的登录挑战，返回删除数量
+    fn delete_expired_login_challenges(&self, now: DateTime<Utc>) -> impl Future<Output = Result<u64, AppError>> + Send;
+}
+
+/// 科目仓储
+pub trait SubjectRepository {
+    /// 查询用户的全部科目 (默认科目在前，按名称排序)
+    fn list_subjects(&self, user_id: i64) -> impl Future<Output = Result<Vec<Subject>, AppError>> + Send;
+
+    /// 根据 ID 查询科目
+    fn find_subject(&self, subject_id: i64) -> impl Future<Output = Result<Option<Subject>, AppError>> + Send;
+
+    /// 创建科目，返回新科目 ID
+    fn create_subject(
+        &self,
+        user_id: i64,
+        name: &str,
+        color: &str,
+        is_default: bool,
+    ) -> impl Future<Output = Result<i64, AppError>> + Send;
+
+    /// 删除非默认科目 (关联任务的科目置空)
+    fn delete_subject(&self, user_id: i64, subject_id: i64) -> impl Future<Output = Result<(), AppError>> + Send;
+}
+
+/// 任务仓储 (所有操作都限定在指定用户的任务内)
+pub trait TaskRepository {
+    /// 查询指定日期的任务 (按开始时间排序)
+    fn list_tasks_by_date(
+        &self,
+        user_id: i64,
+        date: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<Task>, AppError>> + Send;
+
+    /// 查询日期范围内 (含首尾) 满足筛选条件的任务及其科目 (按日期和开始时间排序)
+    fn list_tasks_in_range(
+        &self,
+        user_id: i64,
+        start: NaiveDate,
+        end: NaiveDate,
+        filter: &TaskFilter,
+    ) -> impl Future<Output = Result<Vec<TaskWithSubject>, AppError>> + Send;
+
+    /// 根据 ID 查询任务
+    fn find_task(&self, user_id: i64, task_id: i64) -> impl Future<Output = Result<Option<Task>, AppError>> + Send;
+
+    /// 创建任务，返回新任务 ID
+    fn create_task(
+        &self,
+        user_id: i64,
+        input: &CreateTaskInput,
+    ) -> impl Future<Output = Result<i64, AppError>> + Send;
+
+    /// 更新任务中已提供的字段
+    fn update_task(
+        &self,
+        user_id: i64,
+        task_id: i64,
+        input: &UpdateTaskInput,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 删除任务
+    fn delete_task(&self, user_id: i64, task_id: i64) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 更新任务状态及原因，同时记录这次状态变更 (同一事务)
+    fn set_task_status(
+        &self,
+        user_id: i64,
+        task_id: i64,
+        status: &TaskStatus,
+        reason: Option<&str>,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 查询任务的状态变更记录 (按时间先后排列)
+    fn list_task_status_history(
+        &self,
+        user_id: i64,
+        task_id: i64,
+    ) -> impl Future<Output = Result<Vec<TaskStatusChange>, AppError>> + Send;
+
+    /// 顺延任务：在目标日期创建记录原任务 ID 的新任务，同时更新原任务状态与原因并记录状态变更 (同一事务)，
+    /// 返回新任务 ID
+    fn carry_over_task(
+        &self,
+        user_id: i64,
+        task_id: i64,
+        input: &CreateTaskInput,
+        status: &TaskStatus,
+        reason: Option<&str>,
+    ) -> impl Future<Output = Result<i64, AppError>> + Send;
+}
+
+/// 任务清单仓储 (清单项通过所属任务限定在指定用户内)
+pub trait ChecklistRepository {
+    /// 查询任务的清单项 (按顺序排列)
+    fn list_checklist_items(
+        &self,
+        user_id: i64,
+        task_id: i64,
+    ) -> impl Future<Output = Result<Vec<ChecklistItem>, AppError>> + Send;
+
+    /// 查询日期范围内 (含首尾) 所有任务的清单项 (按任务和顺序排列)
+    fn list_checklist_items_in_range(
+        &self,
+        user_id: i64,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<ChecklistItem>, AppError>> + Send;
+
+    /// 根据 ID 查询清单项
+    fn find_checklist_item(
+        &self,
+        user_id: i64,
+        item_id: i64,
+    ) -> impl Future<Output = Result<Option<ChecklistItem>, AppError>> + Send;
+
+    /// 创建清单项，返回新清单项 ID
+    fn create_checklist_item(
+        &self,
+        task_id: i64,
+        position: i32,
+        content: &str,
+        completed: bool,
+    ) -> impl Future<Output = Result<i64, AppError>> + Send;
+
+    /// 更新清单项中已提供的字段
+    fn update_checklist_item(
+        &self,
+        item_id: i64,
+        input: &UpdateChecklistItemInput,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 删除清单项
+    fn delete_checklist_item(&self, item_id: i64) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 按给定的 ID 顺序重新排列任务的清单项 (同一事务)
+    fn reorder_checklist_items(
+        &self,
+        task_id: i64,
+        item_ids: &[i64],
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 更新任务的清单完成百分比
+    fn set_task_progress(
+        &self,
+        user_id: i64,
+        task_id: i64,
+        progress: i32,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+}
+
+/// 任务标签仓储 (标签属于用户，与任务多对多关联)
+pub trait TagRepository {
+    /// 查询用户的全部标签 (按名称排序)
+    fn list_tags(&self, user_id: i64) -> impl Future<Output = Result<Vec<Tag>, AppError>> + Send;
+
+    /// 根据 ID 查询标签
+    fn find_tag(&self, user_id: i64, tag_id: i64) -> impl Future<Output = Result<Option<Tag>, AppError>> + Send;
+
+    /// 创建标签，返回新标签 ID
+    fn create_tag(&self, user_id: i64, name: &str, color: &str) -> impl Future<Output = Result<i64, AppError>> + Send;
+
+    /// 修改标签中已提供的字段
+    fn update_tag(
+        &self,
+        user_id: i64,
+        tag_id: i64,
+        input: &UpdateTagInput,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 删除标签 (与任务的关联一并删除)
+    fn delete_tag(&self, user_id: i64, tag_id: i64) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 查询任务的标签 (按名称排序)
+    fn list_task_tags(&self, user_id: i64, task_id: i64) -> impl Future<Output = Result<Vec<Tag>, AppError>> + Send;
+
+    /// 查询日期范围内 (含首尾) 所有任务与标签的关联
+    fn list_task_tag_links_in_range(
+        &self,
+        user_id: i64,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<TaskTagLink>, AppError>> + Send;
+
+    /// 把任务的标签替换为给定的标签 (同一事务)
+    fn set_task_tags(&self, task_id: i64, tag_ids: &[i64]) -> impl Future<Output = Result<(), AppError>> + Send;
+}
+
+/// 重复任务仓储 (系列以及由系列生成的任务)
+pub trait TaskSeriesRepository {
+    /// 查询开始日期不晚于指定日期的系列
+    fn list_task_series(
+        &self,
+        user_id: i64,
+        until: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<TaskSeries>, AppError>> + Send;
+
+    /// 根据 ID 查询系列
+    fn find_task_series(
+        &self,
+        user_id: i64,
+        series_id: i64,
+    ) -> impl Future<Output = Result<Option<TaskSeries>, AppError>> + Send;
+
+    /// 创建系列，返回新系列 ID
+    fn create_task_series(
+        &self,
+        user_id: i64,
+        input: &CreateTaskSeriesInput,
+    ) -> impl Future<Output = Result<i64, AppError>> + Send;
+
+    /// 更新系列中已提供的字段
+    fn update_task_series(
+        &self,
+        user_id: i64,
+        series_id: i64,
+        input: &UpdateTaskInput,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 修改系列的重复规则
+    fn set_task_series_rule(
+        &self,
+        user_id: i64,
+        series_id: i64,
+        rule: &RecurrenceRule,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 删除系列 (已生成的任务保留为普通任务)
+    fn delete_task_series(&self, user_id: i64, series_id: i64) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 系列在指定日期的任务是否已生成或已被单独删除
+    fn occurrence_exists(
+        &self,
+        series_id: i64,
+        date: NaiveDate,
+    ) -> impl Future<Output = Result<bool, AppError>> + Send;
+
+    /// 日期范围内 (含首尾) 已生成或已被单独删除的重复，返回 (系列 ID, 日期)
+    fn list_occurrence_dates(
+        &self,
+        user_id: i64,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<(i64, NaiveDate)>, AppError>> + Send;
+
+    /// 按系列生成指定日期的任务，返回新任务 ID
+    fn create_task_occurrence(
+        &self,
+        series: &TaskSeries,
+        date: NaiveDate,
+    ) -> impl Future<Output = Result<i64, AppError>> + Send;
+
+    /// 记录被单独删除的一次重复，之后不再生成
+    fn skip_task_occurrence(
+        &self,
+        series_id: i64,
+        date: NaiveDate,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 查询系列从指定日期起已生成的任务
+    fn list_task_occurrences(
+        &self,
+        user_id: i64,
+        series_id: i64,
+        from: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<Task>, AppError>> + Send;
+
+    /// 将系列从指定日期起已生成的任务和删除记录转移到另一个系列
+    fn move_task_occurrences(
+        &self,
+        user_id: i64,
+        series_id: i64,
+        to_series_id: i64,
+        from: NaiveDate,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    /// 删除系列从指定日期起已生成的任务和删除记录
+    fn delete_task_occurrences(
+        &self,
+        user_id: i64,
+        series_id: i64,
+        from: NaiveDate,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+}
@@ -0,0 +1,1876 @@
+// 基于数据库连接池的仓储实现 (MySQL / SQLite)
+use super::{
+    AuditRepository, InviteCodeRepository, LoginAttemptRepository, MentorRepository,
+    ChecklistRepository, SessionRepository, SubjectRepository, TagRepository, TaskRepository, TaskSeriesRepository,
+    TwoFactorRepository, UserRepository,
+};
+use crate::config::DatabaseBackend;
+use crate::db::{self, with_pool, DbError, DbPool, ExecResult};
+use crate::error::AppError;
+use crate::models::{
+    AuditAction, AuditEvent, AuditEventFilter, ChecklistItem, Countdown, CreateTaskInput, CreateTaskSeriesInput,
+    CreateUserInput, DailyReview, InviteCode, LoginAttempt, LoginChallenge, LoginThrottle,
+    PomodoroSession, RecoveryCode, RecurrenceRule, StudyPreference, StudyPreferenceResponse, Subject, Tag,
+    Task, TaskFilter, TaskSeries, TaskStatus, TaskStatusChange, TaskTagLink, TaskWithSubject,
+    UpdateChecklistItemInput, UpdateTagInput, UpdateTaskInput, User, UserData, UserRole, UserSession, UserTotp,
+    DEFAULT_SUBJECTS,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// 数据库仓储
+#[derive(Debug, Clone)]
+pub struct SqlRepository {
+    pool: DbPool,
+}
+
+impl SqlRepository {
+    /// 使用指定连接池
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// 使用当前全局连接池
+    pub fn current() -> Result<Self, DbError> {
+        db::get_pool().map(Self::new)
+    }
+
+    /// 更新任务或重复任务系列中已提供的字段
+    async fn update_task_fields(
+        &self,
+        table: &str,
+        user_id: i64,
+        id: i64,
+        input: &UpdateTaskInput,
+    ) -> Result<(), sqlx::Error> {
+        // 构建动态更新语句
+        let mut updates = Vec::new();
+        if input.subject_id.is_some() {
+            updates.push("subject_id = ?");
+        }
+        if input.start_time.is_some() {
+            updates.push("start_time = ?");
+        }
+        if input.end_time.is_some() {
+            updates.push("end_time = ?");
+        }
+        if input.content.is_some() {
+            updates.push("content = ?");
+        }
+        if input.alarm_enabled.is_some() {
+            updates.push("alarm_enabled = ?");
+        }
+        if input.alarm_time.is_some() {
+            updates.push("alarm_time = ?");
+        }
+        if input.priority.is_some() {
+            updates.push("priority = ?");
+        }
+
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        let sql = format!(
+            "UPDATE {} SET {} WHERE id = ? AND user_id = ?",
+            table,
+            updates.join(", ")
+        );
+
+        with_pool!(&self.pool, pool => {
+            let mut query = sqlx::query(&sql);
+
+            if let Some(v) = input.subject_id {
+                query = query.bind(v);
+            }
+            if let Some(v) = input.start_time {
+                query = query.bind(v);
+            }
+            if let Some(v) = input.end_time {
+                query = query.bind(v);
+            }
+            if let Some(v) = &input.content {
+                query = query.bind(v);
+            }
+            if let Some(v) = input.alarm_enabled {
+                query = query.bind(v);
+            }
+            if let Some(v) = input.alarm_time {
+                query = query.bind(v);
+            }
+            if let Some(v) = input.priority {
+                query = query.bind(v.to_string());
+            }
+
+            query = query.bind(id).bind(user_id);
+
+            query.execute(pool).await.map(ExecResult::from)
+        })?;
+
+        Ok(())
+    }
+}
+
+impl UserRepository for SqlRepository {
+    async fn list_users(&self) -> Result<Vec<User>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, username, password, display_name, role, created_at, updated_at, deactivated_at,
+                    must_change_password
+             FROM users ORDER BY created_at DESC"
+        )
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询用户失败"))
+    }
+
+    async fn find_user(&self, user_id: i64) -> Result<Option<User>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, username, password, display_name, role, created_at, updated_at, deactivated_at,
+                    must_change_password
+             FROM users WHERE id = ?"
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+        .map_err(AppError::db("查询用户失败"))
+    }
+
+    async fn find_user_by_username(&self, username: &str) -> Result<Option<User>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, username, password, display_name, role, created_at, updated_at, deactivated_at,
+                    must_change_password
+             FROM users WHERE username = ?"
+        )
+        .bind(username)
+        .fetch_optional(pool)
+        .await)
+        .map_err(AppError::db("查询用户失败"))
+    }
+
+    async fn create_user(&self, input: &CreateUserInput) -> Result<i64, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO users (username, password, display_name, role) VALUES (?, ?, ?, ?)"
+        )
+        .bind(&input.username)
+        .bind(&input.password)
+        .bind(&input.display_name)
+        .bind(&input.role)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("创建用户失败"))?;
+
+        Ok(result.insert_id)
+    }
+
+    async fn create_users(&self, inputs: &[CreateUserInput]) -> Result<Vec<i64>, AppError> {
+        with_pool!(&self.pool, pool => async {
+            let mut tx = pool.begin().await?;
+            let mut user_ids = Vec::with_capacity(inputs.len());
+            for input in inputs {
+                let user_id = sqlx::query(
+                    "INSERT INTO users (username, password, display_name, role, must_change_password) \
+                     VALUES (?, ?, ?, ?, TRUE)",
+                )
+                .bind(&input.username)
+                .bind(&input.password)
+                .bind(&input.display_name)
+                .bind(&input.role)
+                .execute(&mut *tx)
+                .await
+                .map(ExecResult::from)?
+                .insert_id;
+
+                for (name, color) in DEFAULT_SUBJECTS {
+                    sqlx::query("INSERT INTO subjects (user_id, name, color, is_default) VALUES (?, ?, ?, TRUE)")
+                        .bind(user_id)
+                        .bind(name)
+                        .bind(color)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+                user_ids.push(user_id);
+            }
+            tx.commit().await?;
+            Ok(user_ids)
+        }
+        .await)
+        .map_err(AppError::db("批量创建用户失败"))
+    }
+
+    async fn delete_user(&self, user_id: i64) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("DELETE FROM users WHERE id = ?")
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("删除用户失败"))?;
+
+        Ok(())
+    }
+
+    async fn update_password(&self, user_id: i64, password: &str) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("UPDATE users SET password = ? WHERE id = ?")
+            .bind(password)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("修改密码失败"))?;
+
+        Ok(())
+    }
+
+    async fn update_display_name(&self, user_id: i64, display_name: &str) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("UPDATE users SET display_name = ? WHERE id = ?")
+            .bind(display_name)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("修改昵称失败"))?;
+
+        Ok(())
+    }
+
+    async fn update_role(&self, user_id: i64, role: &UserRole) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("UPDATE users SET role = ? WHERE id = ?")
+            .bind(role.to_string())
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("修改角色失败"))?;
+
+        Ok(())
+    }
+
+    async fn set_deactivated(&self, user_id: i64, deactivated_at: Option<DateTime<Utc>>) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("UPDATE users SET deactivated_at = ? WHERE id = ?")
+            .bind(deactivated_at)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("修改账户状态失败"))?;
+
+        Ok(())
+    }
+
+    async fn set_must_change_password(&self, user_id: i64, required: bool) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("UPDATE users SET must_change_password = ? WHERE id = ?")
+            .bind(required)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("修改账户状态失败"))?;
+
+        Ok(())
+    }
+
+    async fn load_user_data(&self, user_id: i64) -> Result<UserData, AppError> {
+        let subjects = self.list_subjects(user_id).await?;
+
+        let tasks: Vec<Task> = with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, subject_id, task_date, start_time, end_time, content, status,
+                    alarm_enabled, alarm_time, created_at, updated_at, series_id, occurrence_date,
+                    carried_from_id, progress, priority, status_reason
+             FROM tasks WHERE user_id = ? ORDER BY task_date ASC, start_time ASC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("导出任务失败"))?;
+
+        let checklist_items: Vec<ChecklistItem> = with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT c.id, c.task_id, c.position, c.content, c.completed, c.created_at
+             FROM task_checklist_items c JOIN tasks t ON t.id = c.task_id
+             WHERE t.user_id = ? ORDER BY c.task_id ASC, c.position ASC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("导出任务清单失败"))?;
+
+        let tags = self.list_tags(user_id).await?;
+
+        let task_tags: Vec<TaskTagLink> = with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT l.task_id, l.tag_id FROM task_tag_links l JOIN tasks t ON t.id = l.task_id
+             WHERE t.user_id = ? ORDER BY l.task_id ASC, l.tag_id ASC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("导出任务标签失败"))?;
+
+        let task_status_history: Vec<TaskStatusChange> = with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT h.id, h.task_id, h.from_status, h.to_status, h.reason, h.changed_at
+             FROM task_status_history h JOIN tasks t ON t.id = h.task_id
+             WHERE t.user_id = ? ORDER BY h.id ASC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("导出任务状态记录失败"))?;
+
+        let task_series: Vec<TaskSeries> = with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, subject_id, start_date, start_time, end_time, content,
+                    alarm_enabled, alarm_time, rrule, created_at, updated_at, priority
+             FROM task_series WHERE user_id = ? ORDER BY start_date ASC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("导出重复任务失败"))?;
+
+        let pomodoros: Vec<PomodoroSession> = with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, subject_id, task_id, start_time, end_time, duration_minutes, status
+             FROM pomodoro_sessions WHERE user_id = ? ORDER BY start_time ASC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("导出番茄钟记录失败"))?;
+
+        let reviews: Vec<DailyReview> = with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, review_date, feelings, difficulties, ai_suggestions, created_at
+             FROM daily_reviews WHERE user_id = ? ORDER BY review_date ASC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("导出复盘失败"))?;
+
+        let countdowns: Vec<Countdown> = with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, name, target_time, notify_enabled, created_at
+             FROM countdowns WHERE user_id = ? ORDER BY target_time ASC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("导出倒计时失败"))?;
+
+        let preference: Option<StudyPreference> = with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT * FROM study_preferences WHERE user_id = ?"
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+        .map_err(AppError::db("导出学习偏好失败"))?;
+
+        Ok(UserData {
+            subjects,
+            tasks,
+            checklist_items,
+            tags,
+            task_tags,
+            task_status_history,
+            task_series,
+            pomodoros,
+            reviews,
+            countdowns,
+            preference: preference.map(StudyPreferenceResponse::from),
+        })
+    }
+}
+
+impl SessionRepository for SqlRepository {
+    async fn create_session(
+        &self,
+        user_id: i64,
+        session_token: &str,
+        created_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO user_sessions (user_id, session_token, expires_at, created_at, last_used_at)
+             VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(session_token)
+        .bind(expires_at)
+        .bind(created_at)
+        .bind(created_at)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("创建会话失败"))?;
+
+        Ok(())
+    }
+
+    async fn find_session(&self, session_token: &str) -> Result<Option<UserSession>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, session_token, expires_at, created_at, last_used_at
+             FROM user_sessions WHERE session_token = ?"
+        )
+        .bind(session_token)
+        .fetch_optional(pool)
+        .await)
+        .map_err(AppError::db("查询会话失败"))
+    }
+
+    async fn list_sessions(&self, user_id: i64) -> Result<Vec<UserSession>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, session_token, expires_at, created_at, last_used_at
+             FROM user_sessions WHERE user_id = ? ORDER BY last_used_at DESC, id DESC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询会话失败"))
+    }
+
+    async fn touch_session(
+        &self,
+        session_token: &str,
+        last_used_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "UPDATE user_sessions SET last_used_at = ?, expires_at = ? WHERE session_token = ?"
+        )
+        .bind(last_used_at)
+        .bind(expires_at)
+        .bind(session_token)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("更新会话失败"))?;
+
+        Ok(())
+    }
+
+    async fn delete_session(&self, session_token: &str) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("DELETE FROM user_sessions WHERE session_token = ?")
+            .bind(session_token)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("退出登录失败"))?;
+
+        Ok(())
+    }
+
+    async fn delete_user_session(&self, user_id: i64, session_id: i64) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("DELETE FROM user_sessions WHERE id = ? AND user_id = ?")
+            .bind(session_id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("注销会话失败"))?;
+
+        Ok(())
+    }
+
+    async fn delete_user_sessions(&self, user_id: i64, except_token: Option<&str>) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "DELETE FROM user_sessions WHERE user_id = ? AND (? IS NULL OR session_token <> ?)"
+        )
+        .bind(user_id)
+        .bind(except_token)
+        .bind(except_token)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("注销会话失败"))?;
+
+        Ok(())
+    }
+
+    async fn delete_expired_sessions(&self, now: DateTime<Utc>) -> Result<u64, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query("DELETE FROM user_sessions WHERE expires_at <= ?")
+            .bind(now)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("清理过期会话失败"))?;
+
+        Ok(result.rows_affected)
+    }
+}
+
+impl LoginAttemptRepository for SqlRepository {
+    async fn record_login_attempt(
+        &self,
+        username: &str,
+        success: bool,
+        attempted_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO login_attempts (username, success, attempted_at) VALUES (?, ?, ?)"
+        )
+        .bind(username)
+        .bind(success)
+        .bind(attempted_at)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("记录登录尝试失败"))?;
+
+        Ok(())
+    }
+
+    async fn list_login_attempts(&self, username: Option<&str>, limit: i64) -> Result<Vec<LoginAttempt>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, username, success, attempted_at FROM login_attempts
+             WHERE (? IS NULL OR username = ?)
+             ORDER BY attempted_at DESC, id DESC LIMIT ?"
+        )
+        .bind(username)
+        .bind(username)
+        .bind(limit)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询登录记录失败"))
+    }
+
+    async fn find_login_throttle(&self, username: &str) -> Result<Option<LoginThrottle>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT username, failed_count, last_failed_at, locked_until
+             FROM login_throttles WHERE username = ?"
+        )
+        .bind(username)
+        .fetch_optional(pool)
+        .await)
+        .map_err(AppError::db("查询登录限流状态失败"))
+    }
+
+    async fn increment_login_failures(
+        &self,
+        username: &str,
+        now: DateTime<Utc>,
+        window_start: DateTime<Utc>,
+        max_failures: i32,
+        locked_until: DateTime<Utc>,
+    ) -> Result<LoginThrottle, AppError> {
+        // 同一条语句完成读取、累加与锁定，并发的失败登录不会互相覆盖计数；
+        // 各赋值只引用 failed_count 与 last_failed_at 的原值 (MySQL 按从左到右的顺序赋值)
+        let on_conflict = match self.pool.backend() {
+            DatabaseBackend::MySql => "ON DUPLICATE KEY UPDATE",
+            DatabaseBackend::Sqlite => "ON CONFLICT (username) DO UPDATE SET",
+        };
+        let count = "CASE WHEN last_failed_at > ? THEN failed_count + 1 ELSE 1 END";
+        let sql = format!(
+            "INSERT INTO login_throttles (username, failed_count, last_failed_at, locked_until)
+             VALUES (?, 1, ?, CASE WHEN 1 >= ? THEN ? ELSE NULL END)
+             {on_conflict}
+                 locked_until = CASE WHEN {count} >= ? THEN ? ELSE NULL END,
+                 failed_count = {count},
+                 last_failed_at = ?"
+        );
+
+        with_pool!(&self.pool, pool => sqlx::query(&sql)
+            .bind(username)
+            .bind(now)
+            .bind(max_failures)
+            .bind(locked_until)
+            .bind(window_start)
+            .bind(max_failures)
+            .bind(locked_until)
+            .bind(window_start)
+            .bind(now)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("保存登录限流状态失败"))?;
+
+        self.find_login_throttle(username)
+            .await?
+            .ok_or_else(|| AppError::NotFound("登录限流状态不存在".to_string()))
+    }
+
+    async fn delete_login_throttle(&self, username: &str) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("DELETE FROM login_throttles WHERE username = ?")
+            .bind(username)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("清除登录限流状态失败"))?;
+
+        Ok(())
+    }
+
+    async fn list_locked_accounts(&self, now: DateTime<Utc>) -> Result<Vec<LoginThrottle>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT username, failed_count, last_failed_at, locked_until
+             FROM login_throttles WHERE locked_until > ? ORDER BY locked_until ASC"
+        )
+        .bind(now)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询锁定账户失败"))
+    }
+
+    async fn delete_login_attempts_before(&self, cutoff: DateTime<Utc>) -> Result<u64, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query("DELETE FROM login_attempts WHERE attempted_at < ?")
+            .bind(cutoff)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("清理登录记录失败"))?;
+
+        Ok(result.rows_affected)
+    }
+}
+
+impl MentorRepository for SqlRepository {
+    async fn assign_student(&self, mentor_id: i64, student_id: i64) -> Result<(), AppError> {
+        self.unassign_student(mentor_id, student_id).await?;
+
+        with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO mentor_students (mentor_id, student_id, created_at) VALUES (?, ?, ?)"
+        )
+        .bind(mentor_id)
+        .bind(student_id)
+        .bind(Utc::now())
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("分配学生失败"))?;
+
+        Ok(())
+    }
+
+    async fn unassign_student(&self, mentor_id: i64, student_id: i64) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "DELETE FROM mentor_students WHERE mentor_id = ? AND student_id = ?"
+        )
+        .bind(mentor_id)
+        .bind(student_id)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("取消分配学生失败"))?;
+
+        Ok(())
+    }
+
+    async fn list_students(&self, mentor_id: i64) -> Result<Vec<User>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT u.id, u.username, u.password, u.display_name, u.role, u.created_at, u.updated_at,
+                    u.deactivated_at, u.must_change_password
+             FROM users u JOIN mentor_students m ON m.student_id = u.id
+             WHERE m.mentor_id = ? ORDER BY u.username"
+        )
+        .bind(mentor_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询学生失败"))
+    }
+
+    async fn is_student_assigned(&self, mentor_id: i64, student_id: i64) -> Result<bool, AppError> {
+        let (count,): (i64,) = with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT COUNT(*) FROM mentor_students WHERE mentor_id = ? AND student_id = ?"
+        )
+        .bind(mentor_id)
+        .bind(student_id)
+        .fetch_one(pool)
+        .await)
+        .map_err(AppError::db("查询学生分配失败"))?;
+
+        Ok(count > 0)
+    }
+
+    async fn delete_mentor_students(&self, mentor_id: i64) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("DELETE FROM mentor_students WHERE mentor_id = ?")
+            .bind(mentor_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("删除学生分配失败"))?;
+
+        Ok(())
+    }
+}
+
+impl InviteCodeRepository for SqlRepository {
+    async fn create_invite_code(
+        &self,
+        code: &str,
+        role: &UserRole,
+        max_uses: i32,
+        expires_at: DateTime<Utc>,
+        created_by: i64,
+    ) -> Result<i64, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO invite_codes (code, role, max_uses, used_count, expires_at, created_by, created_at)
+             VALUES (?, ?, ?, 0, ?, ?, ?)"
+        )
+        .bind(code)
+        .bind(role.to_string())
+        .bind(max_uses)
+        .bind(expires_at)
+        .bind(created_by)
+        .bind(Utc::now())
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("创建邀请码失败"))?;
+
+        Ok(result.insert_id)
+    }
+
+    async fn find_invite_code(&self, invite_id: i64) -> Result<Option<InviteCode>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, code, role, max_uses, used_count, expires_at, created_by, created_at, revoked_at
+             FROM invite_codes WHERE id = ?"
+        )
+        .bind(invite_id)
+        .fetch_optional(pool)
+        .await)
+        .map_err(AppError::db("查询邀请码失败"))
+    }
+
+    async fn find_invite_code_by_code(&self, code: &str) -> Result<Option<InviteCode>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, code, role, max_uses, used_count, expires_at, created_by, created_at, revoked_at
+             FROM invite_codes WHERE code = ?"
+        )
+        .bind(code)
+        .fetch_optional(pool)
+        .await)
+        .map_err(AppError::db("查询邀请码失败"))
+    }
+
+    async fn list_invite_codes(&self) -> Result<Vec<InviteCode>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, code, role, max_uses, used_count, expires_at, created_by, created_at, revoked_at
+             FROM invite_codes ORDER BY created_at DESC, id DESC"
+        )
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询邀请码失败"))
+    }
+
+    async fn revoke_invite_code(&self, invite_id: i64, revoked_at: DateTime<Utc>) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "UPDATE invite_codes SET revoked_at = ? WHERE id = ? AND revoked_at IS NULL"
+        )
+        .bind(revoked_at)
+        .bind(invite_id)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("撤销邀请码失败"))?;
+
+        Ok(())
+    }
+
+    async fn claim_invite_use(&self, invite_id: i64, now: DateTime<Utc>) -> Result<bool, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query(
+            "UPDATE invite_codes SET used_count = used_count + 1
+             WHERE id = ? AND revoked_at IS NULL AND expires_at > ? AND used_count < max_uses"
+        )
+        .bind(invite_id)
+        .bind(now)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("使用邀请码失败"))?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn release_invite_use(&self, invite_id: i64) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "UPDATE invite_codes SET used_count = used_count - 1 WHERE id = ? AND used_count > 0"
+        )
+        .bind(invite_id)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("归还邀请码使用次数失败"))?;
+
+        Ok(())
+    }
+
+    async fn record_invite_user(&self, invite_id: i64, user_id: i64, used_at: DateTime<Utc>) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO invite_code_uses (invite_code_id, user_id, used_at) VALUES (?, ?, ?)"
+        )
+        .bind(invite_id)
+        .bind(user_id)
+        .bind(used_at)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("记录邀请码注册失败"))?;
+
+        Ok(())
+    }
+
+    async fn list_invite_users(&self, invite_id: i64) -> Result<Vec<User>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT u.id, u.username, u.password, u.display_name, u.role, u.created_at, u.updated_at,
+                    u.deactivated_at, u.must_change_password
+             FROM users u JOIN invite_code_uses i ON i.user_id = u.id
+             WHERE i.invite_code_id = ? ORDER BY i.used_at, u.id"
+        )
+        .bind(invite_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询邀请码注册账户失败"))
+    }
+}
+
+impl AuditRepository for SqlRepository {
+    async fn record_audit_event(
+        &self,
+        actor_id: Option<i64>,
+        action: AuditAction,
+        target_user_id: Option<i64>,
+        metadata: &serde_json::Value,
+        created_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO audit_events (actor_id, action, target_user_id, metadata, created_at)
+             VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(actor_id)
+        .bind(action.as_str())
+        .bind(target_user_id)
+        .bind(metadata.to_string())
+        .bind(created_at)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("写入审计记录失败"))?;
+
+        Ok(())
+    }
+
+    async fn list_audit_events(
+        &self,
+        filter: &AuditEventFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AuditEvent>, AppError> {
+        let action = filter.action.map(|a| a.as_str());
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, actor_id, action, target_user_id, metadata, created_at FROM audit_events
+             WHERE (? IS NULL OR actor_id = ?) AND (? IS NULL OR target_user_id = ?)
+               AND (? IS NULL OR action = ?) AND (? IS NULL OR created_at >= ?)
+               AND (? IS NULL OR created_at < ?)
+             ORDER BY created_at DESC, id DESC LIMIT ? OFFSET ?"
+        )
+        .bind(filter.actor_id)
+        .bind(filter.actor_id)
+        .bind(filter.target_user_id)
+        .bind(filter.target_user_id)
+        .bind(action)
+        .bind(action)
+        .bind(filter.since)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(filter.until)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询审计记录失败"))
+    }
+
+    async fn count_audit_events(&self, filter: &AuditEventFilter) -> Result<i64, AppError> {
+        let action = filter.action.map(|a| a.as_str());
+        let (count,): (i64,) = with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT COUNT(*) FROM audit_events
+             WHERE (? IS NULL OR actor_id = ?) AND (? IS NULL OR target_user_id = ?)
+               AND (? IS NULL OR action = ?) AND (? IS NULL OR created_at >= ?)
+               AND (? IS NULL OR created_at < ?)"
+        )
+        .bind(filter.actor_id)
+        .bind(filter.actor_id)
+        .bind(filter.target_user_id)
+        .bind(filter.target_user_id)
+        .bind(action)
+        .bind(action)
+        .bind(filter.since)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(filter.until)
+        .fetch_one(pool)
+        .await)
+        .map_err(AppError::db("统计审计记录失败"))?;
+
+        Ok(count)
+    }
+
+    async fn delete_audit_events_before(&self, cutoff: DateTime<Utc>) -> Result<u64, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query(
+            "DELETE FROM audit_events WHERE created_at < ?"
+        )
+        .bind(cutoff)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("清理审计记录失败"))?;
+
+        Ok(result.rows_affected)
+    }
+}
+
+impl TwoFactorRepository for SqlRepository {
+    async fn find_totp(&self, user_id: i64) -> Result<Option<UserTotp>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT user_id, secret, enabled_at, last_used_step, created_at FROM user_totp WHERE user_id = ?"
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+        .map_err(AppError::db("查询两步验证配置失败"))
+    }
+
+    async fn save_pending_totp(&self, user_id: i64, secret: &str, created_at: DateTime<Utc>) -> Result<(), AppError> {
+        self.delete_totp(user_id).await?;
+
+        with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO user_totp (user_id, secret, created_at) VALUES (?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(secret)
+        .bind(created_at)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("保存两步验证密钥失败"))?;
+
+        Ok(())
+    }
+
+    async fn enable_totp(&self, user_id: i64, enabled_at: DateTime<Utc>, step: i64) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "UPDATE user_totp SET enabled_at = ?, last_used_step = ? WHERE user_id = ?"
+        )
+        .bind(enabled_at)
+        .bind(step)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("启用两步验证失败"))?;
+
+        Ok(())
+    }
+
+    async fn claim_totp_step(&self, user_id: i64, step: i64) -> Result<bool, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query(
+            "UPDATE user_totp SET last_used_step = ?
+             WHERE user_id = ? AND (last_used_step IS NULL OR last_used_step < ?)"
+        )
+        .bind(step)
+        .bind(user_id)
+        .bind(step)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("更新验证码使用记录失败"))?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn delete_totp(&self, user_id: i64) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("DELETE FROM user_recovery_codes WHERE user_id = ?")
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("删除恢复码失败"))?;
+
+        with_pool!(&self.pool, pool => sqlx::query("DELETE FROM user_totp WHERE user_id = ?")
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("删除两步验证配置失败"))?;
+
+        Ok(())
+    }
+
+    async fn replace_recovery_codes(&self, user_id: i64, code_hashes: &[String]) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("DELETE FROM user_recovery_codes WHERE user_id = ?")
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("删除恢复码失败"))?;
+
+        for code_hash in code_hashes {
+            with_pool!(&self.pool, pool => sqlx::query(
+                "INSERT INTO user_recovery_codes (user_id, code_hash) VALUES (?, ?)"
+            )
+            .bind(user_id)
+            .bind(code_hash)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("保存恢复码失败"))?;
+        }
+
+        Ok(())
+    }
+
+    async fn list_unused_recovery_codes(&self, user_id: i64) -> Result<Vec<RecoveryCode>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, code_hash, used_at FROM user_recovery_codes
+             WHERE user_id = ? AND used_at IS NULL ORDER BY id"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询恢复码失败"))
+    }
+
+    async fn use_recovery_code(&self, code_id: i64, used_at: DateTime<Utc>) -> Result<bool, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query(
+            "UPDATE user_recovery_codes SET used_at = ? WHERE id = ? AND used_at IS NULL"
+        )
+        .bind(used_at)
+        .bind(code_id)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("使用恢复码失败"))?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn create_login_challenge(
+        &self,
+        user_id: i64,
+        challenge_token: &str,
+        created_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO login_challenges (challenge_token, user_id, created_at, expires_at) VALUES (?, ?, ?, ?)"
+        )
+        .bind(challenge_token)
+        .bind(user_id)
+        .bind(created_at)
+        .bind(expires_at)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("创建登录验证失败"))?;
+
+        Ok(())
+    }
+
+    async fn find_login_challenge(&self, challenge_token: &str) -> Result<Option<LoginChallenge>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, challenge_token, user_id, created_at, expires_at FROM login_challenges
+             WHERE challenge_token = ?"
+        )
+        .bind(challenge_token)
+        .fetch_optional(pool)
+        .await)
+        .map_err(AppError::db("查询登录验证失败"))
+    }
+
+    async fn delete_login_challenge(&self, challenge_token: &str) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("DELETE FROM login_challenges WHERE challenge_token = ?")
+            .bind(challenge_token)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("删除登录验证失败"))?;
+
+        Ok(())
+    }
+
+    async fn delete_expired_login_challenges(&self, now: DateTime<Utc>) -> Result<u64, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query("DELETE FROM login_challenges WHERE expires_at <= ?")
+            .bind(now)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("清理过期登录验证失败"))?;
+
+        Ok(result.rows_affected)
+    }
+}
+
+impl SubjectRepository for SqlRepository {
+    async fn list_subjects(&self, user_id: i64) -> Result<Vec<Subject>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, name, color, is_default, created_at
+             FROM subjects WHERE user_id = ? ORDER BY is_default DESC, name ASC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询科目失败"))
+    }
+
+    async fn find_subject(&self, subject_id: i64) -> Result<Option<Subject>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, name, color, is_default, created_at FROM subjects WHERE id = ?"
+        )
+        .bind(subject_id)
+        .fetch_optional(pool)
+        .await)
+        .map_err(AppError::db("查询科目失败"))
+    }
+
+    async fn create_subject(
+        &self,
+        user_id: i64,
+        name: &str,
+        color: &str,
+        is_default: bool,
+    ) -> Result<i64, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO subjects (user_id, name, color, is_default) VALUES (?, ?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(color)
+        .bind(is_default)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("创建科目失败"))?;
+
+        Ok(result.insert_id)
+    }
+
+    async fn delete_subject(&self, user_id: i64, subject_id: i64) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("DELETE FROM subjects WHERE id = ? AND user_id = ? AND is_default = FALSE")
+            .bind(subject_id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("删除科目失败"))?;
+
+        Ok(())
+    }
+}
+
+impl TaskRepository for SqlRepository {
+    async fn list_tasks_by_date(&self, user_id: i64, date: NaiveDate) -> Result<Vec<Task>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, subject_id, task_date, start_time, end_time, content, status,
+                    alarm_enabled, alarm_time, created_at, updated_at, series_id, occurrence_date,
+                    carried_from_id, progress, priority, status_reason
+             FROM tasks WHERE user_id = ? AND task_date = ?
+             ORDER BY start_time ASC"
+        )
+        .bind(user_id)
+        .bind(date)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询任务失败"))
+    }
+
+    async fn list_tasks_in_range(
+        &self,
+        user_id: i64,
+        start: NaiveDate,
+        end: NaiveDate,
+        filter: &TaskFilter,
+    ) -> Result<Vec<TaskWithSubject>, AppError> {
+        let status = filter.status.as_ref().map(|s| s.to_string());
+        let priority = filter.priority.map(|p| p.to_string());
+        let pattern = filter.query.as_ref().map(|q| {
+            let escaped = q.to_lowercase().replace('!', "!!").replace('%', "!%").replace('_', "!_");
+            format!("%{}%", escaped)
+        });
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT t.id, t.user_id, t.subject_id, t.task_date, t.start_time, t.end_time, t.content, t.status,
+                    t.alarm_enabled, t.alarm_time, t.created_at, t.updated_at, t.series_id, t.occurrence_date,
+                    t.carried_from_id, t.progress, t.priority, t.status_reason,
+                    s.name AS subject_name, s.color AS subject_color
+             FROM tasks t LEFT JOIN subjects s ON s.id = t.subject_id
+             WHERE t.user_id = ? AND t.task_date BETWEEN ? AND ?
+               AND (? IS NULL OR t.subject_id = ?) AND (? IS NULL OR t.status = ?)
+               AND (? IS NULL OR LOWER(t.content) LIKE ? ESCAPE '!') AND (? IS NULL OR t.priority = ?)
+               AND (? IS NULL OR EXISTS (SELECT 1 FROM task_tag_links l WHERE l.task_id = t.id AND l.tag_id = ?))
+             ORDER BY t.task_date ASC, t.start_time ASC"
+        )
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .bind(filter.subject_id)
+        .bind(filter.subject_id)
+        .bind(&status)
+        .bind(&status)
+        .bind(&pattern)
+        .bind(&pattern)
+        .bind(&priority)
+        .bind(&priority)
+        .bind(filter.tag_id)
+        .bind(filter.tag_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询任务失败"))
+    }
+
+    async fn find_task(&self, user_id: i64, task_id: i64) -> Result<Option<Task>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, subject_id, task_date, start_time, end_time, content, status,
+                    alarm_enabled, alarm_time, created_at, updated_at, series_id, occurrence_date,
+                    carried_from_id, progress, priority, status_reason
+             FROM tasks WHERE id = ? AND user_id = ?"
+        )
+        .bind(task_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+        .map_err(AppError::db("查询任务失败"))
+    }
+
+    async fn create_task(&self, user_id: i64, input: &CreateTaskInput) -> Result<i64, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO tasks (user_id, subject_id, task_date, start_time, end_time, content,
+                                alarm_enabled, alarm_time, priority)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(input.subject_id)
+        .bind(input.task_date)
+        .bind(input.start_time)
+        .bind(input.end_time)
+        .bind(&input.content)
+        .bind(input.alarm_enabled.unwrap_or(false))
+        .bind(input.alarm_time)
+        .bind(input.priority.unwrap_or_default().to_string())
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("创建任务失败"))?;
+
+        Ok(result.insert_id)
+    }
+
+    async fn update_task(&self, user_id: i64, task_id: i64, input: &UpdateTaskInput) -> Result<(), AppError> {
+        self.update_task_fields("tasks", user_id, task_id, input)
+            .await
+            .map_err(AppError::db("更新任务失败"))
+    }
+
+    async fn delete_task(&self, user_id: i64, task_id: i64) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("DELETE FROM tasks WHERE id = ? AND user_id = ?")
+            .bind(task_id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("删除任务失败"))?;
+
+        Ok(())
+    }
+
+    async fn set_task_status(
+        &self,
+        user_id: i64,
+        task_id: i64,
+        status: &TaskStatus,
+        reason: Option<&str>,
+    ) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => async {
+            let mut tx = pool.begin().await?;
+            let from: Option<String> = sqlx::query_scalar("SELECT status FROM tasks WHERE id = ? AND user_id = ?")
+                .bind(task_id)
+                .bind(user_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+            if let Some(from) = from {
+                sqlx::query("UPDATE tasks SET status = ?, status_reason = ? WHERE id = ? AND user_id = ?")
+                    .bind(status.to_string())
+                    .bind(reason)
+                    .bind(task_id)
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query(
+                    "INSERT INTO task_status_history (task_id, from_status, to_status, reason) VALUES (?, ?, ?, ?)"
+                )
+                .bind(task_id)
+                .bind(from)
+                .bind(status.to_string())
+                .bind(reason)
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await
+        }
+        .await)
+        .map_err(AppError::db("更新状态失败"))
+    }
+
+    async fn list_task_status_history(&self, user_id: i64, task_id: i64) -> Result<Vec<TaskStatusChange>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT h.id, h.task_id, h.from_status, h.to_status, h.reason, h.changed_at
+             FROM task_status_history h JOIN tasks t ON t.id = h.task_id
+             WHERE h.task_id = ? AND t.user_id = ? ORDER BY h.id ASC"
+        )
+        .bind(task_id)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询状态记录失败"))
+    }
+
+    async fn carry_over_task(
+        &self,
+        user_id: i64,
+        task_id: i64,
+        input: &CreateTaskInput,
+        status: &TaskStatus,
+        reason: Option<&str>,
+    ) -> Result<i64, AppError> {
+        with_pool!(&self.pool, pool => async {
+            let mut tx = pool.begin().await?;
+            let new_id = sqlx::query(
+                "INSERT INTO tasks (user_id, subject_id, task_date, start_time, end_time, content,
+                                    alarm_enabled, alarm_time, priority, carried_from_id)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(user_id)
+            .bind(input.subject_id)
+            .bind(input.task_date)
+            .bind(input.start_time)
+            .bind(input.end_time)
+            .bind(&input.content)
+            .bind(input.alarm_enabled.unwrap_or(false))
+            .bind(input.alarm_time)
+            .bind(input.priority.unwrap_or_default().to_string())
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await
+            .map(ExecResult::from)?
+            .insert_id;
+
+            let from: String = sqlx::query_scalar("SELECT status FROM tasks WHERE id = ? AND user_id = ?")
+                .bind(task_id)
+                .bind(user_id)
+                .fetch_one(&mut *tx)
+                .await?;
+            sqlx::query("UPDATE tasks SET status = ?, status_reason = ? WHERE id = ? AND user_id = ?")
+                .bind(status.to_string())
+                .bind(reason)
+                .bind(task_id)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                "INSERT INTO task_status_history (task_id, from_status, to_status, reason) VALUES (?, ?, ?, ?)"
+            )
+            .bind(task_id)
+            .bind(from)
+            .bind(status.to_string())
+            .bind(reason)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            Ok(new_id)
+        }
+        .await)
+        .map_err(AppError::db("顺延任务失败"))
+    }
+}
+
+impl ChecklistRepository for SqlRepository {
+    async fn list_checklist_items(&self, user_id: i64, task_id: i64) -> Result<Vec<ChecklistItem>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT c.id, c.task_id, c.position, c.content, c.completed, c.created_at
+             FROM task_checklist_items c JOIN tasks t ON t.id = c.task_id
+             WHERE t.user_id = ? AND c.task_id = ?
+             ORDER BY c.position ASC, c.id ASC"
+        )
+        .bind(user_id)
+        .bind(task_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询任务清单失败"))
+    }
+
+    async fn list_checklist_items_in_range(
+        &self,
+        user_id: i64,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<ChecklistItem>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT c.id, c.task_id, c.position, c.content, c.completed, c.created_at
+             FROM task_checklist_items c JOIN tasks t ON t.id = c.task_id
+             WHERE t.user_id = ? AND t.task_date BETWEEN ? AND ?
+             ORDER BY c.task_id ASC, c.position ASC, c.id ASC"
+        )
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询任务清单失败"))
+    }
+
+    async fn find_checklist_item(&self, user_id: i64, item_id: i64) -> Result<Option<ChecklistItem>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT c.id, c.task_id, c.position, c.content, c.completed, c.created_at
+             FROM task_checklist_items c JOIN tasks t ON t.id = c.task_id
+             WHERE c.id = ? AND t.user_id = ?"
+        )
+        .bind(item_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+        .map_err(AppError::db("查询清单项失败"))
+    }
+
+    async fn create_checklist_item(
+        &self,
+        task_id: i64,
+        position: i32,
+        content: &str,
+        completed: bool,
+    ) -> Result<i64, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO task_checklist_items (task_id, position, content, completed) VALUES (?, ?, ?, ?)"
+        )
+        .bind(task_id)
+        .bind(position)
+        .bind(content)
+        .bind(completed)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("创建清单项失败"))?;
+
+        Ok(result.insert_id)
+    }
+
+    async fn update_checklist_item(&self, item_id: i64, input: &UpdateChecklistItemInput) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "UPDATE task_checklist_items SET content = COALESCE(?, content), completed = COALESCE(?, completed)
+             WHERE id = ?"
+        )
+        .bind(&input.content)
+        .bind(input.completed)
+        .bind(item_id)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("更新清单项失败"))?;
+
+        Ok(())
+    }
+
+    async fn delete_checklist_item(&self, item_id: i64) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("DELETE FROM task_checklist_items WHERE id = ?")
+            .bind(item_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("删除清单项失败"))?;
+
+        Ok(())
+    }
+
+    async fn reorder_checklist_items(&self, task_id: i64, item_ids: &[i64]) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => async {
+            let mut tx = pool.begin().await?;
+            for (position, item_id) in item_ids.iter().enumerate() {
+                sqlx::query("UPDATE task_checklist_items SET position = ? WHERE id = ? AND task_id = ?")
+                    .bind(position as i32)
+                    .bind(item_id)
+                    .bind(task_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await
+        }
+        .await)
+        .map_err(AppError::db("调整清单顺序失败"))
+    }
+
+    async fn set_task_progress(&self, user_id: i64, task_id: i64, progress: i32) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("UPDATE tasks SET progress = ? WHERE id = ? AND user_id = ?")
+            .bind(progress)
+            .bind(task_id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("更新任务进度失败"))?;
+
+        Ok(())
+    }
+}
+
+impl TagRepository for SqlRepository {
+    async fn list_tags(&self, user_id: i64) -> Result<Vec<Tag>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, name, color, created_at FROM task_tags WHERE user_id = ? ORDER BY name ASC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询标签失败"))
+    }
+
+    async fn find_tag(&self, user_id: i64, tag_id: i64) -> Result<Option<Tag>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, name, color, created_at FROM task_tags WHERE id = ? AND user_id = ?"
+        )
+        .bind(tag_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+        .map_err(AppError::db("查询标签失败"))
+    }
+
+    async fn create_tag(&self, user_id: i64, name: &str, color: &str) -> Result<i64, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO task_tags (user_id, name, color) VALUES (?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(color)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("创建标签失败"))?;
+
+        Ok(result.insert_id)
+    }
+
+    async fn update_tag(&self, user_id: i64, tag_id: i64, input: &UpdateTagInput) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "UPDATE task_tags SET name = COALESCE(?, name), color = COALESCE(?, color) WHERE id = ? AND user_id = ?"
+        )
+        .bind(&input.name)
+        .bind(&input.color)
+        .bind(tag_id)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("修改标签失败"))?;
+
+        Ok(())
+    }
+
+    async fn delete_tag(&self, user_id: i64, tag_id: i64) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("DELETE FROM task_tags WHERE id = ? AND user_id = ?")
+            .bind(tag_id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("删除标签失败"))?;
+
+        Ok(())
+    }
+
+    async fn list_task_tags(&self, user_id: i64, task_id: i64) -> Result<Vec<Tag>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT g.id, g.user_id, g.name, g.color, g.created_at
+             FROM task_tags g JOIN task_tag_links l ON l.tag_id = g.id
+             WHERE g.user_id = ? AND l.task_id = ?
+             ORDER BY g.name ASC"
+        )
+        .bind(user_id)
+        .bind(task_id)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询任务标签失败"))
+    }
+
+    async fn list_task_tag_links_in_range(
+        &self,
+        user_id: i64,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<TaskTagLink>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT l.task_id, l.tag_id FROM task_tag_links l JOIN tasks t ON t.id = l.task_id
+             WHERE t.user_id = ? AND t.task_date BETWEEN ? AND ?"
+        )
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询任务标签失败"))
+    }
+
+    async fn set_task_tags(&self, task_id: i64, tag_ids: &[i64]) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => async {
+            let mut tx = pool.begin().await?;
+            sqlx::query("DELETE FROM task_tag_links WHERE task_id = ?")
+                .bind(task_id)
+                .execute(&mut *tx)
+                .await?;
+            for tag_id in tag_ids {
+                sqlx::query("INSERT INTO task_tag_links (task_id, tag_id) VALUES (?, ?)")
+                    .bind(task_id)
+                    .bind(tag_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await
+        }
+        .await)
+        .map_err(AppError::db("设置任务标签失败"))
+    }
+}
+
+impl TaskSeriesRepository for SqlRepository {
+    async fn list_task_series(&self, user_id: i64, until: NaiveDate) -> Result<Vec<TaskSeries>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, subject_id, start_date, start_time, end_time, content,
+                    alarm_enabled, alarm_time, rrule, created_at, updated_at, priority
+             FROM task_series WHERE user_id = ? AND start_date <= ?
+             ORDER BY start_time ASC"
+        )
+        .bind(user_id)
+        .bind(until)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询重复任务失败"))
+    }
+
+    async fn find_task_series(&self, user_id: i64, series_id: i64) -> Result<Option<TaskSeries>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, subject_id, start_date, start_time, end_time, content,
+                    alarm_enabled, alarm_time, rrule, created_at, updated_at, priority
+             FROM task_series WHERE id = ? AND user_id = ?"
+        )
+        .bind(series_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await)
+        .map_err(AppError::db("查询重复任务失败"))
+    }
+
+    async fn create_task_series(&self, user_id: i64, input: &CreateTaskSeriesInput) -> Result<i64, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO task_series (user_id, subject_id, start_date, start_time, end_time, content,
+                                      alarm_enabled, alarm_time, rrule, priority)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(input.subject_id)
+        .bind(input.start_date)
+        .bind(input.start_time)
+        .bind(input.end_time)
+        .bind(&input.content)
+        .bind(input.alarm_enabled.unwrap_or(false))
+        .bind(input.alarm_time)
+        .bind(input.rule.to_string())
+        .bind(input.priority.unwrap_or_default().to_string())
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("创建重复任务失败"))?;
+
+        Ok(result.insert_id)
+    }
+
+    async fn update_task_series(&self, user_id: i64, series_id: i64, input: &UpdateTaskInput) -> Result<(), AppError> {
+        self.update_task_fields("task_series", user_id, series_id, input)
+            .await
+            .map_err(AppError::db("更新重复任务失败"))
+    }
+
+    async fn set_task_series_rule(&self, user_id: i64, series_id: i64, rule: &RecurrenceRule) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("UPDATE task_series SET rrule = ? WHERE id = ? AND user_id = ?")
+            .bind(rule.to_string())
+            .bind(series_id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("更新重复规则失败"))?;
+
+        Ok(())
+    }
+
+    async fn delete_task_series(&self, user_id: i64, series_id: i64) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query("DELETE FROM task_series WHERE id = ? AND user_id = ?")
+            .bind(series_id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("删除重复任务失败"))?;
+
+        Ok(())
+    }
+
+    async fn occurrence_exists(&self, series_id: i64, date: NaiveDate) -> Result<bool, AppError> {
+        let (count,): (i64,) = with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT CAST(
+                 (SELECT COUNT(*) FROM tasks WHERE series_id = ? AND occurrence_date = ?)
+                 + (SELECT COUNT(*) FROM task_series_skips WHERE series_id = ? AND occurrence_date = ?)
+             AS SIGNED INTEGER)"
+        )
+        .bind(series_id)
+        .bind(date)
+        .bind(series_id)
+        .bind(date)
+        .fetch_one(pool)
+        .await)
+        .map_err(AppError::db("查询重复任务失败"))?;
+
+        Ok(count > 0)
+    }
+
+    async fn list_occurrence_dates(
+        &self,
+        user_id: i64,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<(i64, NaiveDate)>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT series_id, occurrence_date FROM tasks
+             WHERE user_id = ? AND series_id IS NOT NULL AND occurrence_date BETWEEN ? AND ?
+             UNION ALL
+             SELECT k.series_id, k.occurrence_date FROM task_series_skips k
+             JOIN task_series s ON s.id = k.series_id
+             WHERE s.user_id = ? AND k.occurrence_date BETWEEN ? AND ?"
+        )
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询重复任务失败"))
+    }
+
+    async fn create_task_occurrence(&self, series: &TaskSeries, date: NaiveDate) -> Result<i64, AppError> {
+        let result = with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO tasks (user_id, subject_id, task_date, start_time, end_time, content,
+                                alarm_enabled, alarm_time, priority, series_id, occurrence_date)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(series.user_id)
+        .bind(series.subject_id)
+        .bind(date)
+        .bind(series.start_time)
+        .bind(series.end_time)
+        .bind(&series.content)
+        .bind(series.alarm_enabled)
+        .bind(series.alarm_time)
+        .bind(series.priority.to_string())
+        .bind(series.id)
+        .bind(date)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("生成重复任务失败"))?;
+
+        Ok(result.insert_id)
+    }
+
+    async fn skip_task_occurrence(&self, series_id: i64, date: NaiveDate) -> Result<(), AppError> {
+        // 先删除再插入，重复记录时不报错
+        with_pool!(&self.pool, pool => sqlx::query(
+            "DELETE FROM task_series_skips WHERE series_id = ? AND occurrence_date = ?"
+        )
+        .bind(series_id)
+        .bind(date)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("删除重复任务失败"))?;
+
+        with_pool!(&self.pool, pool => sqlx::query(
+            "INSERT INTO task_series_skips (series_id, occurrence_date) VALUES (?, ?)"
+        )
+        .bind(series_id)
+        .bind(date)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("删除重复任务失败"))?;
+
+        Ok(())
+    }
+
+    async fn list_task_occurrences(
+        &self,
+        user_id: i64,
+        series_id: i64,
+        from: NaiveDate,
+    ) -> Result<Vec<Task>, AppError> {
+        with_pool!(&self.pool, pool => sqlx::query_as(
+            "SELECT id, user_id, subject_id, task_date, start_time, end_time, content, status,
+                    alarm_enabled, alarm_time, created_at, updated_at, series_id, occurrence_date,
+                    carried_from_id, progress, priority, status_reason
+             FROM tasks WHERE user_id = ? AND series_id = ? AND occurrence_date >= ?
+             ORDER BY occurrence_date ASC"
+        )
+        .bind(user_id)
+        .bind(series_id)
+        .bind(from)
+        .fetch_all(pool)
+        .await)
+        .map_err(AppError::db("查询重复任务失败"))
+    }
+
+    async fn move_task_occurrences(
+        &self,
+        user_id: i64,
+        series_id: i64,
+        to_series_id: i64,
+        from: NaiveDate,
+    ) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "UPDATE tasks SET series_id = ? WHERE user_id = ? AND series_id = ? AND occurrence_date >= ?"
+        )
+        .bind(to_series_id)
+        .bind(user_id)
+        .bind(series_id)
+        .bind(from)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("拆分重复任务失败"))?;
+
+        with_pool!(&self.pool, pool => sqlx::query(
+            "UPDATE task_series_skips SET series_id = ? WHERE series_id = ? AND occurrence_date >= ?"
+        )
+        .bind(to_series_id)
+        .bind(series_id)
+        .bind(from)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("拆分重复任务失败"))?;
+
+        Ok(())
+    }
+
+    async fn delete_task_occurrences(&self, user_id: i64, series_id: i64, from: NaiveDate) -> Result<(), AppError> {
+        with_pool!(&self.pool, pool => sqlx::query(
+            "DELETE FROM tasks WHERE user_id = ? AND series_id = ? AND occurrence_date >= ?"
+        )
+        .bind(user_id)
+        .bind(series_id)
+        .bind(from)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("删除重复任务失败"))?;
+
+        with_pool!(&self.pool, pool => sqlx::query(
+            "DELETE FROM task_series_skips WHERE series_id = ? AND occurrence_date >= ?"
+        )
+        .bind(series_id)
+        .bind(from)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("删除重复任务失败"))?;
+
+        Ok(())
+    }
+}
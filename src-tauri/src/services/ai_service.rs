@@ -1,44 +1,43 @@
 // AI 服务
-use crate::db::get_pool;
-use crate::models::{AIConfig, AIConfigResponse, SaveAIConfigInput, AIContext, TaskSuggestion, StudyPreference};
+use crate::db::{self, with_pool, ExecResult};
+use crate::error::AppError;
+use crate::models::{AIConfig, AIConfigResponse, SaveAIConfigInput, AIContext, AuditAction, TaskSuggestion, StudyPreference};
+use crate::repositories::{AuditRepository, SqlRepository};
+use chrono::{Local, NaiveDate, Utc};
 use reqwest::Client;
 use serde::Deserialize;
 
 /// 获取 AI 配置
-pub async fn get_ai_config(user_id: i64) -> Result<AIConfigResponse, String> {
-    let pool = get_pool();
-    
-    let config: Option<AIConfig> = sqlx::query_as(
+pub async fn get_ai_config(user_id: i64) -> Result<AIConfigResponse, AppError> {
+    let config: Option<AIConfig> = with_pool!(pool => sqlx::query_as(
         "SELECT id, user_id, api_key, model_name, api_endpoint, updated_at 
          FROM ai_configs WHERE user_id = ?"
     )
     .bind(user_id)
     .fetch_optional(pool)
-    .await
-    .map_err(|e| format!("查询 AI 配置失败: {}", e))?;
+    .await)
+    .map_err(AppError::db("查询 AI 配置失败"))?;
 
     Ok(AIConfigResponse::from(config))
 }
 
 /// 保存 AI 配置
-pub async fn save_ai_config(user_id: i64, input: SaveAIConfigInput) -> Result<AIConfigResponse, String> {
-    let pool = get_pool();
-    
+pub async fn save_ai_config(user_id: i64, input: SaveAIConfigInput) -> Result<AIConfigResponse, AppError> {
     let model_name = input.model_name.unwrap_or_else(|| "Qwen/Qwen2.5-7B-Instruct".to_string());
     let api_endpoint = input.api_endpoint.unwrap_or_else(|| "https://api.siliconflow.cn/v1/chat/completions".to_string());
     
     // 检查是否已存在
-    let existing: Option<(i64,)> = sqlx::query_as(
-        "SELECT id FROM ai_configs WHERE user_id = ?"
+    let existing: Option<(i64, String)> = with_pool!(pool => sqlx::query_as(
+        "SELECT id, api_key FROM ai_configs WHERE user_id = ?"
     )
     .bind(user_id)
     .fetch_optional(pool)
-    .await
-    .map_err(|e| format!("查询失败: {}", e))?;
+    .await)
+    .map_err(AppError::db("查询失败"))?;
 
     if existing.is_some() {
         // 更新
-        sqlx::query(
+        with_pool!(pool => sqlx::query(
             "UPDATE ai_configs SET api_key = ?, model_name = ?, api_endpoint = ? WHERE user_id = ?"
         )
         .bind(&input.api_key)
@@ -47,10 +46,11 @@ pub async fn save_ai_config(user_id: i64, input: SaveAIConfigInput) -> Result<AI
         .bind(user_id)
         .execute(pool)
         .await
-        .map_err(|e| format!("更新 AI 配置失败: {}", e))?;
+        .map(ExecResult::from))
+        .map_err(AppError::db("更新 AI 配置失败"))?;
     } else {
         // 创建
-        sqlx::query(
+        with_pool!(pool => sqlx::query(
             "INSERT INTO ai_configs (user_id, api_key, model_name, api_endpoint) VALUES (?, ?, ?, ?)"
         )
         .bind(user_id)
@@ -59,24 +59,39 @@ pub async fn save_ai_config(user_id: i64, input: SaveAIConfigInput) -> Result<AI
         .bind(&api_endpoint)
         .execute(pool)
         .await
-        .map_err(|e| format!("创建 AI 配置失败: {}", e))?;
+        .map(ExecResult::from))
+        .map_err(AppError::db("创建 AI 配置失败"))?;
     }
 
+    // 审计记录不包含密钥本身，只记录密钥是否变更
+    let key_changed = existing.as_ref().is_none_or(|(_, key)| *key != input.api_key);
+    SqlRepository::current()?
+        .record_audit_event(
+            Some(user_id),
+            AuditAction::AiConfigSave,
+            Some(user_id),
+            &serde_json::json!({
+                "model_name": model_name,
+                "api_endpoint": api_endpoint,
+                "key_changed": key_changed,
+            }),
+            Utc::now(),
+        )
+        .await?;
+
     get_ai_config(user_id).await
 }
 
 /// 测试 AI 连接
-pub async fn test_ai_connection(user_id: i64) -> Result<bool, String> {
-    let pool = get_pool();
-    
-    let config: AIConfig = sqlx::query_as(
+pub async fn test_ai_connection(user_id: i64) -> Result<bool, AppError> {
+    let config: AIConfig = with_pool!(pool => sqlx::query_as(
         "SELECT id, user_id, api_key, model_name, api_endpoint, updated_at 
          FROM ai_configs WHERE user_id = ?"
     )
     .bind(user_id)
     .fetch_one(pool)
-    .await
-    .map_err(|_| "请先配置 AI API".to_string())?;
+    .await)
+    .map_err(|_| AppError::Validation("请先配置 AI API".to_string()))?;
 
     let client = Client::new();
     
@@ -93,13 +108,16 @@ pub async fn test_ai_connection(user_id: i64) -> Result<bool, String> {
         .json(&request_body)
         .send()
         .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+        .map_err(AppError::ai("请求失败"))?;
 
     if response.status().is_success() {
         Ok(true)
     } else {
         let error_text = response.text().await.unwrap_or_default();
-        Err(format!("API 返回错误: {}", error_text))
+        Err(AppError::AiProvider {
+            message: "API 返回错误".to_string(),
+            details: error_text,
+        })
     }
 }
 
@@ -121,21 +139,23 @@ struct AIMessage {
 
 /// 获取最近的复盘内容
 async fn get_recent_reviews(user_id: i64, days: i32) -> Vec<String> {
-    let pool = get_pool();
-    
-    let reviews: Vec<(String, Option<String>, Option<String>)> = sqlx::query_as(
-        "SELECT DATE_FORMAT(review_date, '%Y-%m-%d'), feelings, difficulties 
+    let Ok(db) = db::get_pool() else {
+        return Vec::new();
+    };
+
+    let reviews: Vec<(NaiveDate, Option<String>, Option<String>)> = with_pool!(&db, pool => sqlx::query_as(
+        "SELECT review_date, feelings, difficulties 
          FROM daily_reviews WHERE user_id = ? 
          ORDER BY review_date DESC LIMIT ?"
     )
     .bind(user_id)
     .bind(days)
     .fetch_all(pool)
-    .await
+    .await)
     .unwrap_or_default();
     
     reviews.into_iter().map(|(date, feelings, difficulties)| {
-        let mut s = format!("{}:", date);
+        let mut s = format!("{}:", date.format("%Y-%m-%d"));
         if let Some(f) = feelings {
             s.push_str(&format!(" 感受-{}", f));
         }
@@ -148,55 +168,83 @@ async fn get_recent_reviews(user_id: i64, days: i32) -> Vec<String> {
 
 /// 获取最近完成的任务
 async fn get_recent_completed_tasks(user_id: i64, days: i32) -> Vec<String> {
-    let pool = get_pool();
-    
-    let tasks: Vec<(String, String, Option<String>)> = sqlx::query_as(
-        "SELECT DATE_FORMAT(task_date, '%Y-%m-%d'), content, 
+    let since = Local::now().date_naive() - chrono::Duration::days(days as i64);
+
+    let Ok(db) = db::get_pool() else {
+        return Vec::new();
+    };
+
+    let tasks: Vec<(NaiveDate, String, Option<String>)> = with_pool!(&db, pool => sqlx::query_as(
+        "SELECT task_date, content, 
          (SELECT name FROM subjects WHERE id = tasks.subject_id) as subject_name
          FROM tasks WHERE user_id = ? AND status = 'completed'
-         AND task_date >= DATE_SUB(CURDATE(), INTERVAL ? DAY)
+         AND task_date >= ?
          ORDER BY task_date DESC LIMIT 20"
     )
     .bind(user_id)
-    .bind(days)
+    .bind(since)
     .fetch_all(pool)
-    .await
+    .await)
     .unwrap_or_default();
     
     tasks.into_iter().map(|(date, content, subject)| {
-        format!("{}: [{}] {}", date, subject.unwrap_or_default(), content)
+        format!("{}: [{}] {}", date.format("%Y-%m-%d"), subject.unwrap_or_default(), content)
+    }).collect()
+}
+
+/// 获取最近未完成的任务及原因
+async fn get_recent_failed_tasks(user_id: i64, days: i32) -> Vec<String> {
+    let since = Local::now().date_naive() - chrono::Duration::days(days as i64);
+
+    let Ok(db) = db::get_pool() else {
+        return Vec::new();
+    };
+
+    let tasks: Vec<(NaiveDate, String, Option<String>)> = with_pool!(&db, pool => sqlx::query_as(
+        "SELECT task_date, content, status_reason
+         FROM tasks WHERE user_id = ? AND status = 'failed'
+         AND task_date >= ?
+         ORDER BY task_date DESC LIMIT 20"
+    )
+    .bind(user_id)
+    .bind(since)
+    .fetch_all(pool)
+    .await)
+    .unwrap_or_default();
+
+    tasks.into_iter().map(|(date, content, reason)| {
+        format!("{}: {} (原因: {})", date.format("%Y-%m-%d"), content, reason.as_deref().unwrap_or("未填写"))
     }).collect()
 }
 
 /// 生成 AI 计划
-pub async fn generate_ai_plan(user_id: i64, context: AIContext, model_name: Option<String>) -> Result<Vec<TaskSuggestion>, String> {
-    let pool = get_pool();
-    
-    let config: AIConfig = sqlx::query_as(
+pub async fn generate_ai_plan(user_id: i64, context: AIContext, model_name: Option<String>) -> Result<Vec<TaskSuggestion>, AppError> {
+    let config: AIConfig = with_pool!(pool => sqlx::query_as(
         "SELECT id, user_id, api_key, model_name, api_endpoint, updated_at 
          FROM ai_configs WHERE user_id = ?"
     )
     .bind(user_id)
     .fetch_one(pool)
-    .await
-    .map_err(|_| "请先配置 AI API".to_string())?;
+    .await)
+    .map_err(|_| AppError::Validation("请先配置 AI API".to_string()))?;
     
     // 使用传入的模型名称，如果没有则使用配置的默认模型
     let use_model = model_name.unwrap_or(config.model_name.clone());
 
     // 获取学习偏好
-    let preference: Option<StudyPreference> = sqlx::query_as(
+    let preference: Option<StudyPreference> = with_pool!(pool => sqlx::query_as(
         "SELECT * FROM study_preferences WHERE user_id = ?"
     )
     .bind(user_id)
     .fetch_optional(pool)
-    .await
+    .await)
     .ok()
     .flatten();
     
     // 获取最近的复盘和完成任务
     let recent_reviews = get_recent_reviews(user_id, 3).await;
     let recent_tasks = get_recent_completed_tasks(user_id, 7).await;
+    let failed_tasks = get_recent_failed_tasks(user_id, 7).await;
 
     // 构建更智能的提示词
     let mut prompt = String::from(r#"你是一个专业的考研学习规划助手。请根据用户的学习偏好、历史学习情况和复盘反馈，生成一份科学合理、个性化的全天学习计划。
@@ -285,6 +333,14 @@ pub async fn generate_ai_plan(user_id: i64, context: AIContext, model_name: Opti
     if !recent_tasks.is_empty() {
         prompt.push_str(&format!("\n最近完成的任务（参考学习进度）:\n{}\n", recent_tasks.join("\n")));
     }
+
+    // 添加最近未完成的任务及原因
+    if !failed_tasks.is_empty() {
+        prompt.push_str(&format!(
+            "\n最近未完成的任务及原因（时间不够则减少任务量，太难则拆小或先补基础，状态差则降低强度）:\n{}\n",
+            failed_tasks.join("\n")
+        ));
+    }
     
     if let Some(review) = &context.review_content {
         prompt.push_str(&format!("\n用户额外说明: {}\n", review));
@@ -330,17 +386,20 @@ pub async fn generate_ai_plan(user_id: i64, context: AIContext, model_name: Opti
         .json(&request_body)
         .send()
         .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+        .map_err(AppError::ai("请求失败"))?;
 
     if !response.status().is_success() {
         let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("API 返回错误: {}", error_text));
+        return Err(AppError::AiProvider {
+            message: "API 返回错误".to_string(),
+            details: error_text,
+        });
     }
 
     let ai_response: AIResponse = response
         .json()
         .await
-        .map_err(|e| format!("解析响应失败: {}", e))?;
+        .map_err(AppError::ai("解析响应失败"))?;
 
     let content = ai_response.choices.first()
         .map(|c| c.message.content.clone())
@@ -348,24 +407,25 @@ pub async fn generate_ai_plan(user_id: i64, context: AIContext, model_name: Opti
 
     // 尝试解析 JSON
     let suggestions: Vec<TaskSuggestion> = serde_json::from_str(&content)
-        .map_err(|e| format!("解析计划失败: {}，原始内容: {}", e, content))?;
+        .map_err(|e| AppError::AiProvider {
+            message: "解析计划失败".to_string(),
+            details: format!("{}，原始内容: {}", e, content),
+        })?;
 
     Ok(suggestions)
 }
 
 
 /// AI 聊天答疑
-pub async fn ai_chat(user_id: i64, message: String, history: Vec<crate::models::ChatMessage>) -> Result<String, String> {
-    let pool = get_pool();
-    
-    let config: AIConfig = sqlx::query_as(
+pub async fn ai_chat(user_id: i64, message: String, history: Vec<crate::models::ChatMessage>) -> Result<String, AppError> {
+    let config: AIConfig = with_pool!(pool => sqlx::query_as(
         "SELECT id, user_id, api_key, model_name, api_endpoint, updated_at 
          FROM ai_configs WHERE user_id = ?"
     )
     .bind(user_id)
     .fetch_one(pool)
-    .await
-    .map_err(|_| "请先配置 AI API".to_string())?;
+    .await)
+    .map_err(|_| AppError::Validation("请先配置 AI API".to_string()))?;
 
     // 构建系统提示词
     let system_prompt = r#"你是一个专业的考研学习助手，专门帮助考研学生解答学习问题。你的特点：
@@ -414,17 +474,20 @@ pub async fn ai_chat(user_id: i64, message: String, history: Vec<crate::models::
         .json(&request_body)
         .send()
         .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+        .map_err(AppError::ai("请求失败"))?;
 
     if !response.status().is_success() {
         let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("API 返回错误: {}", error_text));
+        return Err(AppError::AiProvider {
+            message: "API 返回错误".to_string(),
+            details: error_text,
+        });
     }
 
     let ai_response: AIResponse = response
         .json()
         .await
-        .map_err(|e| format!("解析响应失败: {}", e))?;
+        .map_err(AppError::ai("解析响应失败"))?;
 
     let content = ai_response.choices.first()
         .map(|c| c.message.content.clone())
@@ -0,0 +1,138 @@
+// 审计日志服务
+use crate::config;
+use crate::error::AppError;
+use crate::models::{
+    AuditAction, AuditEvent, AuditEventFilter, AuditEventResponse, AuditLogPage, AuditLogQuery,
+};
+use crate::repositories::{AuditRepository, SqlRepository, UserRepository};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// 审计日志默认每页条数
+pub const DEFAULT_AUDIT_PAGE_SIZE: i64 = 50;
+
+/// 审计日志每页最大条数
+pub const MAX_AUDIT_PAGE_SIZE: i64 = 200;
+
+/// 后台清理过期审计记录的间隔 (秒)
+const AUDIT_CLEANUP_INTERVAL_SECS: u64 = 6 * 3600;
+
+/// 将查询参数转换为过滤条件，校验操作名称和时间范围
+fn to_filter(query: &AuditLogQuery) -> Result<AuditEventFilter, AppError> {
+    let action = query
+        .action
+        .as_deref()
+        .filter(|a| !a.is_empty())
+        .map(|a| AuditAction::parse(a).ok_or_else(|| AppError::Validation(format!("未知的审计操作: {}", a))))
+        .transpose()?;
+
+    if let (Some(since), Some(until)) = (query.since, query.until) {
+        if since >= until {
+            return Err(AppError::Validation("开始时间必须早于结束时间".to_string()));
+        }
+    }
+
+    Ok(AuditEventFilter {
+        actor_id: query.actor_id,
+        target_user_id: query.target_user_id,
+        action,
+        since: query.since,
+        until: query.until,
+    })
+}
+
+/// 转换为审计记录响应 (用户已删除时用户名为空，可从附加信息中查看)
+fn to_response(event: AuditEvent, usernames: &HashMap<i64, String>) -> AuditEventResponse {
+    let username = |id: Option<i64>| id.and_then(|id| usernames.get(&id).cloned());
+    AuditEventResponse {
+        id: event.id,
+        actor_id: event.actor_id,
+        actor_username: username(event.actor_id),
+        action: event.action,
+        target_user_id: event.target_user_id,
+        target_username: username(event.target_user_id),
+        metadata: event.metadata.and_then(|m| serde_json::from_str(&m).ok()),
+        created_at: event.created_at,
+    }
+}
+
+/// 分页查询审计日志 (按时间倒序)
+pub async fn get_audit_log<R>(repo: &R, query: AuditLogQuery) -> Result<AuditLogPage, AppError>
+where
+    R: AuditRepository + UserRepository,
+{
+    let filter = to_filter(&query)?;
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query
+        .page_size
+        .unwrap_or(DEFAULT_AUDIT_PAGE_SIZE)
+        .clamp(1, MAX_AUDIT_PAGE_SIZE);
+    let offset = (page - 1)
+        .checked_mul(page_size)
+        .ok_or_else(|| AppError::Validation("页码超出范围".to_string()))?;
+
+    let total = repo.count_audit_events(&filter).await?;
+    let events = repo.list_audit_events(&filter, page_size, offset).await?;
+
+    let usernames: HashMap<i64, String> = repo
+        .list_users()
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u.username))
+        .collect();
+
+    Ok(AuditLogPage {
+        events: events.into_iter().map(|e| to_response(e, &usernames)).collect(),
+        total,
+        page,
+        page_size,
+    })
+}
+
+/// 清理超过保留天数的审计记录，返回清理数量
+/// (保留天数为 0 或截止时间超出可表示范围时不清理)
+pub async fn purge_expired_events<R: AuditRepository>(
+    repo: &R,
+    retention_days: u32,
+    now: DateTime<Utc>,
+) -> Result<u64, AppError> {
+    if retention_days == 0 {
+        return Ok(0);
+    }
+
+    let Some(cutoff) = Duration::try_days(i64::from(retention_days)).and_then(|d| now.checked_sub_signed(d)) else {
+        return Ok(0);
+    };
+    let purged = repo.delete_audit_events_before(cutoff).await?;
+
+    // 清理本身也留下记录，便于确认保留策略生效
+    if purged > 0 {
+        repo.record_audit_event(
+            None,
+            AuditAction::AuditPurge,
+            None,
+            &json!({ "purged": purged, "retention_days": retention_days, "cutoff": cutoff }),
+            now,
+        )
+        .await?;
+    }
+
+    Ok(purged)
+}
+
+/// 后台定时清理过期审计记录 (由 setup 钩子启动，每次按最新配置的保留天数清理)
+pub async fn run_audit_cleanup() {
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(AUDIT_CLEANUP_INTERVAL_SECS)).await;
+
+        let Ok(repo) = SqlRepository::current() else {
+            continue;
+        };
+
+        let retention_days = config::load().unwrap_or_default().audit.retention_days;
+        if let Err(e) = purge_expired_events(&repo, retention_days, Utc::now()).await {
+            eprintln!("清理过期审计记录失败: {}", e);
+        }
+    }
+}
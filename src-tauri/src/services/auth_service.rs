@@ -1,43 +1,256 @@
 // 认证服务
-use crate::db::get_pool;
-use crate::models::{User, UserSession, LoginResponse, UserResponse};
-use chrono::{Duration, Utc};
+use crate::config;
+use crate::error::AppError;
+use crate::models::{
+    AuditAction, LoginAttempt, LoginResponse, LoginResult, LoginThrottle, Permission,
+    SessionResponse, TwoFactorChallenge, User, UserResponse, UserSession,
+};
+use crate::repositories::{
+    AuditRepository, LoginAttemptRepository, SessionRepository, SqlRepository,
+    TwoFactorRepository, UserRepository,
+};
+use crate::services::two_factor_service;
+use crate::utils::password;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
 use uuid::Uuid;
 
-/// 验证用户登录
-pub async fn login(username: &str, password: &str) -> Result<LoginResponse, String> {
-    let pool = get_pool();
-    
-    // 查询用户
-    let user: Option<User> = sqlx::query_as(
-        "SELECT id, username, password, display_name, role, created_at, updated_at 
-         FROM users WHERE username = ?"
+/// 会话有效期 (天)，每次使用会话时顺延
+pub const SESSION_TTL_DAYS: i64 = 7;
+
+/// 会话使用时间的最小记录间隔 (秒)，避免每次请求都写数据库
+pub const SESSION_TOUCH_INTERVAL_SECS: i64 = 60;
+
+/// 后台清理过期会话的间隔 (秒)
+const SESSION_CLEANUP_INTERVAL_SECS: u64 = 3600;
+
+/// 连续失败多少次后临时锁定账户
+pub const MAX_FAILED_ATTEMPTS: i32 = 5;
+
+/// 临时锁定时长 (分钟)，未锁定时最后一次失败超过该时长后连续失败次数也会重置
+pub const LOCKOUT_MINUTES: i64 = 15;
+
+/// 初始化时默认管理员的密码，使用该密码登录的账户需要先修改密码
+pub const INITIAL_ADMIN_PASSWORD: &str = "admin123";
+
+/// 通过密码验证后提交两步验证码的有效期 (分钟)
+pub const LOGIN_CHALLENGE_TTL_MINUTES: i64 = 5;
+
+/// 第 n 次连续失败后，下一次尝试前需要等待的时间 (1, 2, 4, 8 … 秒)
+pub fn login_delay(failed_count: i32) -> Duration {
+    Duration::seconds(1 << (failed_count.clamp(1, 10) - 1))
+}
+
+/// 限流状态在指定时间是否仍然有效
+fn is_throttle_active(throttle: &LoginThrottle, now: DateTime<Utc>) -> bool {
+    match throttle.locked_until {
+        Some(until) => until > now,
+        None => now - throttle.last_failed_at < Duration::minutes(LOCKOUT_MINUTES),
+    }
+}
+
+/// 检查账户是否处于锁定或退避等待中
+fn check_throttle(throttle: &LoginThrottle, now: DateTime<Utc>) -> Result<(), AppError> {
+    if let Some(until) = throttle.locked_until.filter(|until| *until > now) {
+        let minutes = ((until - now).num_seconds() + 59) / 60;
+        return Err(AppError::RateLimited(format!("账户已被临时锁定，请 {} 分钟后重试", minutes)));
+    }
+
+    let retry_at = throttle.last_failed_at + login_delay(throttle.failed_count);
+    if retry_at > now {
+        let seconds = ((retry_at - now).num_milliseconds() + 999) / 1000;
+        return Err(AppError::RateLimited(format!("登录尝试过于频繁，请 {} 秒后重试", seconds)));
+    }
+
+    Ok(())
+}
+
+/// 验证用户登录 (启用两步验证的账户需再调用 verify_login_code 才会发放会话)
+pub async fn login<R>(repo: &R, username: &str, password: &str) -> Result<LoginResult, AppError>
+where
+    R: UserRepository + SessionRepository + LoginAttemptRepository + AuditRepository + TwoFactorRepository,
+{
+    login_at(repo, username, password, Utc::now()).await
+}
+
+/// 检查用户名的连续失败限流 (被限流时记录失败的登录尝试)
+async fn check_login_throttle<R: LoginAttemptRepository>(
+    repo: &R,
+    username: &str,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    let throttle = repo
+        .find_login_throttle(username)
+        .await?
+        .filter(|t| is_throttle_active(t, now));
+    if let Some(throttle) = &throttle {
+        if let Err(e) = check_throttle(throttle, now) {
+            repo.record_login_attempt(username, false, now).await?;
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// 记录一次失败的登录 (密码或验证码错误)，累计连续失败次数，达到上限时锁定账户
+/// (累计在同一条语句中完成，并发的失败登录同样计数)
+async fn record_login_failure<R: LoginAttemptRepository>(
+    repo: &R,
+    username: &str,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    let window = Duration::minutes(LOCKOUT_MINUTES);
+    repo.increment_login_failures(username, now, now - window, MAX_FAILED_ATTEMPTS, now + window)
+        .await?;
+    repo.record_login_attempt(username, false, now).await
+}
+
+/// 完成登录：清除失败计数、创建会话并记录审计
+async fn complete_login<R>(
+    repo: &R,
+    user: User,
+    method: &str,
+    now: DateTime<Utc>,
+) -> Result<LoginResponse, AppError>
+where
+    R: SessionRepository + LoginAttemptRepository + AuditRepository,
+{
+    let (user_id, username) = (user.id, user.username.clone());
+    repo.record_login_attempt(&username, true, now).await?;
+    if repo.find_login_throttle(&username).await?.is_some() {
+        repo.delete_login_throttle(&username).await?;
+    }
+
+    let response = start_session(repo, user, now).await?;
+    repo.record_audit_event(
+        Some(user_id),
+        AuditAction::SessionLogin,
+        Some(user_id),
+        &json!({ "username": username, "method": method }),
+        now,
     )
-    .bind(username)
-    .fetch_optional(pool)
-    .await
-    .map_err(|e| format!("数据库查询失败: {}", e))?;
+    .await?;
+
+    Ok(response)
+}
 
-    let user = user.ok_or("用户名或密码错误")?;
+/// 以指定时间验证用户登录 (限流按该时间计算)
+pub(crate) async fn login_at<R>(
+    repo: &R,
+    username: &str,
+    password: &str,
+    now: DateTime<Utc>,
+) -> Result<LoginResult, AppError>
+where
+    R: UserRepository + SessionRepository + LoginAttemptRepository + AuditRepository + TwoFactorRepository,
+{
+    // 检查连续失败限流 (按用户名统计，用户不存在时同样计数)
+    check_login_throttle(repo, username, now).await?;
 
-    // 验证密码 (明文比对)
-    if user.password != password {
-        return Err("用户名或密码错误".to_string());
+    // 查询用户并验证密码 (用户不存在时同样执行一次哈希校验，响应耗时不暴露用户名是否存在)
+    let user = match repo.find_user_by_username(username).await? {
+        Some(user) => password::verify_password(password, &user.password).then_some(user),
+        None => {
+            password::verify_dummy_password(password);
+            None
+        }
+    };
+    let Some(user) = user else {
+        record_login_failure(repo, username, now).await?;
+        return Err(AppError::Unauthorized("用户名或密码错误".to_string()));
+    };
+
+    // 已停用的账户不能登录 (密码正确时才提示，不计入连续失败)
+    if !user.is_active() {
+        repo.record_login_attempt(username, false, now).await?;
+        return Err(AppError::Forbidden("账户已停用，请联系管理员".to_string()));
+    }
+
+    // 旧版明文密码登录成功后升级为哈希 (失败不影响本次登录)
+    if !password::is_hashed(&user.password) {
+        let upgraded = match password::hash_password(password) {
+            Ok(hash) => repo.update_password(user.id, &hash).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = upgraded {
+            eprintln!("升级用户 {} 的密码哈希失败: {}", user.id, e);
+        }
     }
 
-    // 创建会话
+    // 仍在使用初始密码的账户 (包括已升级为哈希的) 需要先修改密码
+    let mut user = user;
+    if password == INITIAL_ADMIN_PASSWORD && !user.must_change_password {
+        repo.set_must_change_password(user.id, true).await?;
+        user.must_change_password = true;
+    }
+
+    // 启用两步验证时只发放登录挑战，连续失败计数保留到验证码通过
+    if two_factor_service::is_enabled(repo, user.id).await? {
+        let challenge_token = Uuid::new_v4().to_string();
+        let expires_at = now + Duration::minutes(LOGIN_CHALLENGE_TTL_MINUTES);
+        repo.create_login_challenge(user.id, &challenge_token, now, expires_at).await?;
+        return Ok(LoginResult::TwoFactorRequired(TwoFactorChallenge {
+            challenge_token,
+            expires_at,
+        }));
+    }
+
+    complete_login(repo, user, "password", now).await.map(LoginResult::Authenticated)
+}
+
+/// 提交两步验证码 (TOTP 验证码或恢复码) 完成登录
+pub async fn verify_login_code<R>(repo: &R, challenge_token: &str, code: &str) -> Result<LoginResponse, AppError>
+where
+    R: UserRepository + SessionRepository + LoginAttemptRepository + AuditRepository + TwoFactorRepository,
+{
+    verify_login_code_at(repo, challenge_token, code, Utc::now()).await
+}
+
+/// 以指定时间提交两步验证码 (错误的验证码与错误的密码一样计入连续失败)
+pub(crate) async fn verify_login_code_at<R>(
+    repo: &R,
+    challenge_token: &str,
+    code: &str,
+    now: DateTime<Utc>,
+) -> Result<LoginResponse, AppError>
+where
+    R: UserRepository + SessionRepository + LoginAttemptRepository + AuditRepository + TwoFactorRepository,
+{
+    let expired = || AppError::Unauthorized("登录验证已过期，请重新登录".to_string());
+    let challenge = repo.find_login_challenge(challenge_token).await?.ok_or_else(expired)?;
+    if challenge.expires_at <= now {
+        repo.delete_login_challenge(challenge_token).await?;
+        return Err(expired());
+    }
+
+    let user = match repo.find_user(challenge.user_id).await? {
+        Some(user) if user.is_active() => user,
+        _ => {
+            repo.delete_login_challenge(challenge_token).await?;
+            return Err(expired());
+        }
+    };
+
+    check_login_throttle(repo, &user.username, now).await?;
+    let Some(method) = two_factor_service::verify_second_factor(repo, user.id, code, now).await? else {
+        record_login_failure(repo, &user.username, now).await?;
+        return Err(AppError::Unauthorized("验证码错误".to_string()));
+    };
+
+    repo.delete_login_challenge(challenge_token).await?;
+    complete_login(repo, user, method.as_str(), now).await
+}
+
+/// 为用户创建新会话
+pub(crate) async fn start_session<R: SessionRepository>(
+    repo: &R,
+    user: User,
+    now: DateTime<Utc>,
+) -> Result<LoginResponse, AppError> {
     let session_token = Uuid::new_v4().to_string();
-    let expires_at = Utc::now() + Duration::days(7);
+    let expires_at = now + Duration::days(SESSION_TTL_DAYS);
 
-    sqlx::query(
-        "INSERT INTO user_sessions (user_id, session_token, expires_at) VALUES (?, ?, ?)"
-    )
-    .bind(user.id)
-    .bind(&session_token)
-    .bind(expires_at)
-    .execute(pool)
-    .await
-    .map_err(|e| format!("创建会话失败: {}", e))?;
+    repo.create_session(user.id, &session_token, now, expires_at).await?;
 
     Ok(LoginResponse {
         user: UserResponse::from(user),
@@ -45,108 +258,273 @@ pub async fn login(username: &str, password: &str) -> Result<LoginResponse, Stri
     })
 }
 
-/// 验证会话令牌
-pub async fn validate_session(session_token: &str) -> Result<User, String> {
-    let pool = get_pool();
-    
-    // 查询会话
-    let session: Option<UserSession> = sqlx::query_as(
-        "SELECT id, user_id, session_token, expires_at, created_at 
-         FROM user_sessions WHERE session_token = ? AND expires_at > NOW()"
+/// 查询当前被临时锁定的账户
+pub async fn get_locked_accounts<R: LoginAttemptRepository>(repo: &R) -> Result<Vec<LoginThrottle>, AppError> {
+    repo.list_locked_accounts(Utc::now()).await
+}
+
+/// 解除账户锁定并清除连续失败计数 (用户名可能不存在，此时审计记录不关联用户)
+pub async fn unlock_account<R>(repo: &R, actor_id: i64, username: &str) -> Result<(), AppError>
+where
+    R: LoginAttemptRepository + UserRepository + AuditRepository,
+{
+    repo.delete_login_throttle(username).await?;
+
+    let target_user_id = repo.find_user_by_username(username).await?.map(|u| u.id);
+    repo.record_audit_event(
+        Some(actor_id),
+        AuditAction::AccountUnlock,
+        target_user_id,
+        &json!({ "username": username }),
+        Utc::now(),
     )
-    .bind(session_token)
-    .fetch_optional(pool)
     .await
-    .map_err(|e| format!("数据库查询失败: {}", e))?;
+}
 
-    let session = session.ok_or("会话无效或已过期")?;
+/// 查询最近的登录记录
+pub async fn get_login_attempts<R: LoginAttemptRepository>(
+    repo: &R,
+    username: Option<&str>,
+    limit: i64,
+) -> Result<Vec<LoginAttempt>, AppError> {
+    repo.list_login_attempts(username, limit.clamp(1, 500)).await
+}
+
+/// 验证会话令牌
+pub async fn validate_session<R>(repo: &R, session_token: &str) -> Result<User, AppError>
+where
+    R: UserRepository + SessionRepository,
+{
+    validate_session_at(repo, session_token, Utc::now()).await
+}
+
+/// 以指定时间验证会话令牌，有效时顺延会话过期时间
+pub(crate) async fn validate_session_at<R>(repo: &R, session_token: &str, now: DateTime<Utc>) -> Result<User, AppError>
+where
+    R: UserRepository + SessionRepository,
+{
+    // 查询会话
+    let session = repo
+        .find_session(session_token)
+        .await?
+        .filter(|s| s.expires_at > now)
+        .ok_or_else(|| AppError::Unauthorized("会话无效或已过期".to_string()))?;
 
     // 查询用户
-    let user: User = sqlx::query_as(
-        "SELECT id, username, password, display_name, role, created_at, updated_at 
-         FROM users WHERE id = ?"
-    )
-    .bind(session.user_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| format!("用户不存在: {}", e))?;
+    let user = repo
+        .find_user(session.user_id)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("用户不存在".to_string()))?;
+    if !user.is_active() {
+        return Err(AppError::Unauthorized("账户已停用".to_string()));
+    }
+
+    // 记录使用时间并顺延过期时间
+    if now - session.last_used_at >= Duration::seconds(SESSION_TOUCH_INTERVAL_SECS) {
+        repo.touch_session(session_token, now, now + Duration::days(SESSION_TTL_DAYS)).await?;
+    }
 
     Ok(user)
 }
 
-/// 退出登录
-pub async fn logout(session_token: &str) -> Result<(), String> {
-    let pool = get_pool();
-    
-    sqlx::query("DELETE FROM user_sessions WHERE session_token = ?")
-        .bind(session_token)
-        .execute(pool)
-        .await
-        .map_err(|e| format!("退出登录失败: {}", e))?;
+/// 检查用户的角色是否拥有指定权限
+pub fn require_permission(user: &User, permission: Permission) -> Result<(), AppError> {
+    if user.role.has_permission(permission) {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden("权限不足".to_string()))
+    }
+}
 
+/// 检查用户是否已修改初始密码 (需要修改时只能修改密码或退出登录)
+pub fn require_password_changed(user: &User) -> Result<(), AppError> {
+    if user.must_change_password {
+        return Err(AppError::Forbidden("请先修改初始密码".to_string()));
+    }
     Ok(())
 }
 
-/// 修改密码
-pub async fn change_password(user_id: i64, current_password: &str, new_password: &str) -> Result<(), String> {
-    let pool = get_pool();
-    
-    // 验证当前密码
-    let user: User = sqlx::query_as(
-        "SELECT id, username, password, display_name, role, created_at, updated_at 
-         FROM users WHERE id = ?"
+/// 退出登录
+pub async fn logout<R>(repo: &R, session_token: &str) -> Result<(), AppError>
+where
+    R: SessionRepository + AuditRepository,
+{
+    let Some(session) = repo.find_session(session_token).await? else {
+        return Ok(());
+    };
+
+    repo.delete_session(session_token).await?;
+    repo.record_audit_event(
+        Some(session.user_id),
+        AuditAction::SessionLogout,
+        Some(session.user_id),
+        &json!({ "session_id": session.id }),
+        Utc::now(),
     )
-    .bind(user_id)
-    .fetch_one(pool)
     .await
-    .map_err(|e| format!("用户不存在: {}", e))?;
+}
+
+/// 转换为会话响应
+fn to_session_response(session: UserSession, current_token: &str) -> SessionResponse {
+    SessionResponse {
+        id: session.id,
+        created_at: session.created_at,
+        last_used_at: session.last_used_at,
+        expires_at: session.expires_at,
+        is_current: session.session_token == current_token,
+    }
+}
+
+/// 获取用户未过期的会话
+pub async fn get_sessions<R: SessionRepository>(
+    repo: &R,
+    user_id: i64,
+    current_token: &str,
+) -> Result<Vec<SessionResponse>, AppError> {
+    let now = Utc::now();
+    let sessions = repo.list_sessions(user_id).await?;
+
+    Ok(sessions
+        .into_iter()
+        .filter(|s| s.expires_at > now)
+        .map(|s| to_session_response(s, current_token))
+        .collect())
+}
 
-    if user.password != current_password {
-        return Err("当前密码错误".to_string());
+/// 注销用户的指定会话 (不属于该用户的会话忽略)
+pub async fn revoke_session<R>(repo: &R, user_id: i64, session_id: i64) -> Result<(), AppError>
+where
+    R: SessionRepository + AuditRepository,
+{
+    if !repo.list_sessions(user_id).await?.iter().any(|s| s.id == session_id) {
+        return Ok(());
     }
 
-    // 更新密码
-    sqlx::query("UPDATE users SET password = ? WHERE id = ?")
-        .bind(new_password)
-        .bind(user_id)
-        .execute(pool)
+    repo.delete_user_session(user_id, session_id).await?;
+    repo.record_audit_event(
+        Some(user_id),
+        AuditAction::SessionRevoke,
+        Some(user_id),
+        &json!({ "session_id": session_id }),
+        Utc::now(),
+    )
+    .await
+}
+
+/// 注销用户除当前会话外的全部会话
+pub async fn revoke_other_sessions<R>(repo: &R, user_id: i64, current_token: &str) -> Result<(), AppError>
+where
+    R: SessionRepository + AuditRepository,
+{
+    repo.delete_user_sessions(user_id, Some(current_token)).await?;
+    repo.record_audit_event(Some(user_id), AuditAction::SessionRevokeOthers, Some(user_id), &json!({}), Utc::now())
         .await
-        .map_err(|e| format!("修改密码失败: {}", e))?;
+}
 
-    Ok(())
+/// 清理已过期的会话和登录挑战，返回清理的会话数量
+pub async fn purge_expired_sessions<R>(repo: &R) -> Result<u64, AppError>
+where
+    R: SessionRepository + TwoFactorRepository,
+{
+    let now = Utc::now();
+    repo.delete_expired_login_challenges(now).await?;
+    repo.delete_expired_sessions(now).await
+}
+
+/// 清理超过保留天数的登录尝试记录，返回清理数量
+/// (保留天数为 0 或截止时间超出可表示范围时不清理)
+pub async fn purge_login_attempts<R: LoginAttemptRepository>(
+    repo: &R,
+    retention_days: u32,
+    now: DateTime<Utc>,
+) -> Result<u64, AppError> {
+    if retention_days == 0 {
+        return Ok(0);
+    }
+    let Some(cutoff) = Duration::try_days(i64::from(retention_days)).and_then(|d| now.checked_sub_signed(d)) else {
+        return Ok(0);
+    };
+    repo.delete_login_attempts_before(cutoff).await
+}
+
+/// 后台定时清理过期会话和登录尝试记录 (由 setup 钩子启动)
+pub async fn run_session_cleanup() {
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(SESSION_CLEANUP_INTERVAL_SECS)).await;
+
+        let Ok(repo) = SqlRepository::current() else {
+            continue;
+        };
+
+        if let Err(e) = purge_expired_sessions(&repo).await {
+            eprintln!("清理过期会话失败: {}", e);
+        }
+
+        let retention_days = config::load().unwrap_or_default().security.login_attempt_retention_days;
+        if let Err(e) = purge_login_attempts(&repo, retention_days, Utc::now()).await {
+            eprintln!("清理登录尝试记录失败: {}", e);
+        }
+    }
+}
+
+/// 修改密码 (保留当前会话，注销其他会话)
+pub async fn change_password<R>(
+    repo: &R,
+    user_id: i64,
+    session_token: &str,
+    current_password: &str,
+    new_password: &str,
+) -> Result<(), AppError>
+where
+    R: UserRepository + SessionRepository + AuditRepository,
+{
+    // 验证当前密码
+    let user = repo
+        .find_user(user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("用户不存在".to_string()))?;
+
+    if !password::verify_password(current_password, &user.password) {
+        return Err(AppError::Validation("当前密码错误".to_string()));
+    }
+    if user.must_change_password && (new_password == current_password || new_password == INITIAL_ADMIN_PASSWORD) {
+        return Err(AppError::Validation("新密码不能与初始密码相同".to_string()));
+    }
+
+    // 检查新密码策略并更新
+    password::validate_password_policy(new_password)?;
+    let hash = password::hash_password(new_password)?;
+    repo.update_password(user_id, &hash).await?;
+    if user.must_change_password {
+        repo.set_must_change_password(user_id, false).await?;
+    }
+
+    repo.delete_user_sessions(user_id, Some(session_token)).await?;
+    repo.record_audit_event(Some(user_id), AuditAction::PasswordChange, Some(user_id), &json!({}), Utc::now())
+        .await
 }
 
 /// 修改昵称
-pub async fn change_display_name(user_id: i64, new_display_name: &str) -> Result<User, String> {
-    let pool = get_pool();
-    
+pub async fn change_display_name<R: UserRepository>(
+    repo: &R,
+    user_id: i64,
+    new_display_name: &str,
+) -> Result<User, AppError> {
     // 验证昵称不为空
     let display_name = new_display_name.trim();
     if display_name.is_empty() {
-        return Err("昵称不能为空".to_string());
+        return Err(AppError::Validation("昵称不能为空".to_string()));
     }
-    
+
     if display_name.len() > 50 {
-        return Err("昵称长度不能超过50个字符".to_string());
+        return Err(AppError::Validation("昵称长度不能超过50个字符".to_string()));
     }
 
     // 更新昵称
-    sqlx::query("UPDATE users SET display_name = ? WHERE id = ?")
-        .bind(display_name)
-        .bind(user_id)
-        .execute(pool)
-        .await
-        .map_err(|e| format!("修改昵称失败: {}", e))?;
+    repo.update_display_name(user_id, display_name).await?;
 
     // 返回更新后的用户信息
-    let user: User = sqlx::query_as(
-        "SELECT id, username, password, display_name, role, created_at, updated_at 
-         FROM users WHERE id = ?"
-    )
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| format!("获取用户信息失败: {}", e))?;
-
-    Ok(user)
+    repo.find_user(user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("用户不存在".to_string()))
 }
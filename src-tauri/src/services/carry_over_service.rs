@@ -0,0 +1,151 @@
+// 未完成任务顺延服务
+// 把某天未完成的任务安排到之后某天的空闲时段，并按顺延方式更新原任务的状态
+use crate::error::AppError;
+use crate::models::{CarryOverMode, CarryOverReport, CreateTaskInput, StudyWindow, Task, TaskStatus};
+use crate::repositories::{
+    ChecklistRepository, SqlRepository, SubjectRepository, TagRepository, TaskRepository, TaskSeriesRepository,
+};
+use crate::services::{checklist_service, preference_service, schedule_service, tag_service, task_service};
+use chrono::{Duration, Local, NaiveDate, NaiveTime};
+
+/// 后台检查是否需要自动顺延的间隔 (秒)
+const CARRY_OVER_INTERVAL_SECS: u64 = 10 * 60;
+
+/// 自动顺延时最多往前追溯的天数
+pub const MAX_CARRY_OVER_DAYS: i64 = 7;
+
+/// 为顺延的任务选择时间：原时间段空闲且符合学习偏好时保持不变，否则取最早的足够长的空闲时段
+pub fn choose_slot(
+    window: &StudyWindow,
+    tasks: &[Task],
+    start_time: NaiveTime,
+    end_time: NaiveTime,
+) -> Option<(NaiveTime, NaiveTime)> {
+    if schedule_service::find_conflicts(tasks, start_time, end_time, None).is_empty()
+        && schedule_service::preference_warnings(window, start_time, end_time).is_empty()
+    {
+        return Some((start_time, end_time));
+    }
+
+    let duration = end_time - start_time;
+    schedule_service::free_slots(window, tasks, duration.num_minutes())
+        .first()
+        .map(|slot| (slot.start_time, slot.start_time + duration))
+}
+
+/// 把指定日期未完成的任务顺延到之后的日期 (按开始时间依次安排，没有空闲时段的任务保持不变)
+/// 优先级、清单和标签随任务一起复制
+pub async fn carry_over_tasks<R>(
+    repo: &R,
+    user_id: i64,
+    from: NaiveDate,
+    to: NaiveDate,
+    mode: CarryOverMode,
+    window: &StudyWindow,
+) -> Result<CarryOverReport, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    if from >= to {
+        return Err(AppError::Validation("只能顺延到之后的日期".to_string()));
+    }
+
+    let pending: Vec<Task> = task_service::list_day_tasks(repo, user_id, from)
+        .await?
+        .into_iter()
+        .filter(|t| t.status == TaskStatus::Pending)
+        .collect();
+    let mut target = task_service::list_day_tasks(repo, user_id, to).await?;
+
+    let mut report = CarryOverReport {
+        from_date: from,
+        to_date: to,
+        mode,
+        carried: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for task in pending {
+        let Some((start_time, end_time)) = choose_slot(window, &target, task.start_time, task.end_time) else {
+            report.skipped.push(task_service::to_response(repo, task).await);
+            continue;
+        };
+
+        // 提醒时间随开始时间一起平移
+        let shift = start_time - task.start_time;
+        // 尚未生成的重复任务先生成，再记录顺延
+        let task = task_service::materialize_task(repo, user_id, task).await?;
+        let input = CreateTaskInput {
+            subject_id: task.subject_id,
+            task_date: to,
+            start_time,
+            end_time,
+            content: task.content.clone(),
+            alarm_enabled: Some(task.alarm_enabled),
+            alarm_time: task.alarm_time.map(|t| t + shift),
+            priority: Some(task.priority),
+        };
+        let task_id = repo
+            .carry_over_task(user_id, task.id, &input, &mode.original_status(), mode.original_reason())
+            .await?;
+        checklist_service::copy_checklist(repo, user_id, task.id, task_id).await?;
+        tag_service::copy_task_tags(repo, user_id, task.id, task_id).await?;
+        let carried = repo
+            .find_task(user_id, task_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("任务不存在".to_string()))?;
+
+        target.push(carried.clone());
+        report.carried.push(task_service::to_response(repo, carried).await);
+    }
+
+    Ok(report)
+}
+
+/// 把用户从指定日期起到昨天为止未完成的任务顺延到今天，并记录顺延日期
+async fn carry_over_until_today<R>(
+    repo: &R,
+    user_id: i64,
+    mode: CarryOverMode,
+    from: NaiveDate,
+    today: NaiveDate,
+) -> Result<(), AppError>
+where
+    R: TaskRepository + TaskSeriesRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    let window = preference_service::get_study_window(user_id).await?;
+    for date in from.iter_days().take_while(|d| *d < today) {
+        carry_over_tasks(repo, user_id, date, today, mode, &window).await?;
+    }
+    preference_service::set_last_carry_over_date(user_id, today).await
+}
+
+/// 为开启自动顺延且今天尚未顺延的用户执行顺延 (从上次顺延的日期起，最多追溯若干天)
+async fn carry_over_due<R>(repo: &R, today: NaiveDate) -> Result<(), AppError>
+where
+    R: TaskRepository + TaskSeriesRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    let earliest = today - Duration::days(MAX_CARRY_OVER_DAYS);
+    for (user_id, mode, last) in preference_service::list_due_carry_overs(today).await? {
+        let from = last.map_or(today - Duration::days(1), |d| d.max(earliest));
+        if let Err(e) = carry_over_until_today(repo, user_id, mode, from, today).await {
+            eprintln!("自动顺延用户 {} 的任务失败: {}", user_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// 后台定时自动顺延 (由 setup 钩子启动，每个用户每天执行一次)
+pub async fn run_carry_over() {
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(CARRY_OVER_INTERVAL_SECS)).await;
+
+        let Ok(repo) = SqlRepository::current() else {
+            continue;
+        };
+
+        if let Err(e) = carry_over_due(&repo, Local::now().date_naive()).await {
+            eprintln!("自动顺延任务失败: {}", e);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+// 任务清单服务
+// 清单项变化后重新计算任务的完成百分比：全部完成时自动完成任务，已完成的任务出现未完成的清单项时恢复为待完成
+use crate::error::AppError;
+use crate::models::{checklist_progress, ChecklistItem, Task, TaskResponse, TaskStatus, UpdateChecklistItemInput};
+use crate::repositories::{
+    ChecklistRepository, SubjectRepository, TagRepository, TaskRepository, TaskSeriesRepository,
+};
+use crate::services::task_service;
+
+/// 每个任务的清单项上限
+pub const MAX_CHECKLIST_ITEMS: usize = 100;
+
+/// 清单项内容的最大长度 (字符)
+pub const MAX_CHECKLIST_CONTENT_CHARS: usize = 500;
+
+/// 检查并去掉内容首尾的空白
+fn normalize_content(content: &str) -> Result<String, AppError> {
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(AppError::Validation("清单项内容不能为空".to_string()));
+    }
+    if content.chars().count() > MAX_CHECKLIST_CONTENT_CHARS {
+        return Err(AppError::Validation(format!("清单项内容不能超过 {} 个字符", MAX_CHECKLIST_CONTENT_CHARS)));
+    }
+    Ok(content.to_string())
+}
+
+async fn find_task<R: TaskRepository>(repo: &R, user_id: i64, task_id: i64) -> Result<Task, AppError> {
+    repo.find_task(user_id, task_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("任务不存在".to_string()))
+}
+
+async fn find_item<R: ChecklistRepository>(repo: &R, user_id: i64, item_id: i64) -> Result<ChecklistItem, AppError> {
+    repo.find_checklist_item(user_id, item_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("清单项不存在".to_string()))
+}
+
+/// 清单变化后任务应处的状态 (只在待完成与已完成之间自动切换)
+pub fn status_for_checklist(status: &TaskStatus, items: &[ChecklistItem]) -> TaskStatus {
+    let all_done = !items.is_empty() && items.iter().all(|item| item.completed);
+    match status {
+        TaskStatus::Pending if all_done => TaskStatus::Completed,
+        TaskStatus::Completed if !items.is_empty() && !all_done => TaskStatus::Pending,
+        other => other.clone(),
+    }
+}
+
+/// 重新计算任务的完成百分比和状态，返回最新的任务
+async fn refresh_task<R>(repo: &R, user_id: i64, task_id: i64) -> Result<TaskResponse, AppError>
+where
+    R: TaskRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    let task = find_task(repo, user_id, task_id).await?;
+    let items = repo.list_checklist_items(user_id, task_id).await?;
+
+    let progress = checklist_progress(&items);
+    if progress != task.progress {
+        repo.set_task_progress(user_id, task_id, progress).await?;
+    }
+    let status = status_for_checklist(&task.status, &items);
+    if status != task.status {
+        repo.set_task_status(user_id, task_id, &status, None).await?;
+    }
+
+    let task = find_task(repo, user_id, task_id).await?;
+    Ok(task_service::to_response(repo, task).await)
+}
+
+/// 在清单末尾添加一项 (尚未生成的重复任务先生成)
+pub async fn add_checklist_item<R>(
+    repo: &R,
+    user_id: i64,
+    task_id: i64,
+    content: &str,
+) -> Result<TaskResponse, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    let content = normalize_content(content)?;
+    let task = task_service::find_task_or_occurrence(repo, user_id, task_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("任务不存在".to_string()))?;
+    let task_id = task_service::materialize_task(repo, user_id, task).await?.id;
+    let items = repo.list_checklist_items(user_id, task_id).await?;
+    if items.len() >= MAX_CHECKLIST_ITEMS {
+        return Err(AppError::Validation(format!("每个任务最多 {} 个清单项", MAX_CHECKLIST_ITEMS)));
+    }
+
+    let position = items.iter().map(|item| item.position + 1).max().unwrap_or(0);
+    repo.create_checklist_item(task_id, position, &content, false).await?;
+    refresh_task(repo, user_id, task_id).await
+}
+
+/// 修改清单项的内容或完成状态
+pub async fn update_checklist_item<R>(
+    repo: &R,
+    user_id: i64,
+    item_id: i64,
+    input: UpdateChecklistItemInput,
+) -> Result<TaskResponse, AppError>
+where
+    R: TaskRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    let item = find_item(repo, user_id, item_id).await?;
+    let input = UpdateChecklistItemInput {
+        content: input.content.as_deref().map(normalize_content).transpose()?,
+        ..input
+    };
+
+    repo.update_checklist_item(item_id, &input).await?;
+    refresh_task(repo, user_id, item.task_id).await
+}
+
+/// 删除清单项
+pub async fn delete_checklist_item<R>(repo: &R, user_id: i64, item_id: i64) -> Result<TaskResponse, AppError>
+where
+    R: TaskRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    let item = find_item(repo, user_id, item_id).await?;
+    repo.delete_checklist_item(item_id).await?;
+    refresh_task(repo, user_id, item.task_id).await
+}
+
+/// 调整清单顺序 (须包含任务的全部清单项且不重复)
+pub async fn reorder_checklist_items<R>(
+    repo: &R,
+    user_id: i64,
+    task_id: i64,
+    item_ids: &[i64],
+) -> Result<TaskResponse, AppError>
+where
+    R: TaskRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    find_task(repo, user_id, task_id).await?;
+    let items = repo.list_checklist_items(user_id, task_id).await?;
+
+    let mut current: Vec<i64> = items.iter().map(|item| item.id).collect();
+    let mut requested = item_ids.to_vec();
+    current.sort_unstable();
+    requested.sort_unstable();
+    if current != requested {
+        return Err(AppError::Validation("清单顺序须包含该任务的全部清单项".to_string()));
+    }
+
+    repo.reorder_checklist_items(task_id, item_ids).await?;
+    refresh_task(repo, user_id, task_id).await
+}
+
+/// 把一个任务的清单复制到另一个任务 (保留完成状态，用于顺延任务)
+pub async fn copy_checklist<R>(repo: &R, user_id: i64, from_task_id: i64, to_task_id: i64) -> Result<(), AppError>
+where
+    R: ChecklistRepository,
+{
+    let items = repo.list_checklist_items(user_id, from_task_id).await?;
+    if items.is_empty() {
+        return Ok(());
+    }
+    for item in &items {
+        repo.create_checklist_item(to_task_id, item.position, &item.content, item.completed).await?;
+    }
+    repo.set_task_progress(user_id, to_task_id, checklist_progress(&items)).await
+}
@@ -0,0 +1,142 @@
+// 配置服务
+use crate::config::{self, AppConfig, DatabaseBackend};
+use crate::db;
+use crate::error::AppError;
+use crate::models::{
+    ConnectionStatus, DbConfigResponse, MigrationInfo, SaveDbConfigInput, SchemaVersionResponse,
+};
+
+/// 获取当前生效的数据库配置 (密码已隐藏)
+pub async fn get_db_config() -> Result<DbConfigResponse, AppError> {
+    let config = config::load()?;
+
+    let mut response = DbConfigResponse::from(&config.database);
+    response.is_configured = config::is_configured();
+    if config.database.backend == DatabaseBackend::Sqlite {
+        response.sqlite_path = config::sqlite_path(&config.database)?.display().to_string();
+    }
+    response.config_path = config::config_path().map(|p| p.display().to_string());
+    response.env_overrides = config::env_overrides();
+
+    Ok(response)
+}
+
+/// 获取数据库结构版本
+pub async fn get_schema_version() -> Result<SchemaVersionResponse, AppError> {
+    let pool = db::get_pool()?;
+    let backend = pool.backend();
+
+    let applied: Vec<MigrationInfo> = db::migrations::applied(&pool)
+        .await
+        .map_err(AppError::db("查询迁移记录失败"))?
+        .into_iter()
+        .map(|m| MigrationInfo {
+            version: m.version,
+            description: m.description,
+            installed_on: m.installed_on,
+        })
+        .collect();
+
+    let current_version = applied.iter().map(|m| m.version).max().unwrap_or(0);
+    let latest_version = db::migrations::latest_version(backend);
+
+    Ok(SchemaVersionResponse {
+        backend,
+        current_version,
+        latest_version,
+        is_up_to_date: current_version >= latest_version,
+        applied,
+    })
+}
+
+/// 首次运行配置数据库并初始化连接池
+pub async fn setup_db_config(input: SaveDbConfigInput) -> Result<DbConfigResponse, AppError> {
+    // 已配置且连接正常时不允许通过首次配置入口修改
+    if config::is_configured() && db::is_initialized() {
+        return Err(AppError::Conflict("数据库已配置，请使用修改配置功能".to_string()));
+    }
+
+    write_db_config(input)?;
+
+    // 立即连接，失败时由后台连接循环继续重试
+    let connected = db::connection::connect().await;
+    db::connection::wake();
+    connected.map_err(AppError::db("配置已保存，但数据库连接失败"))?;
+
+    get_db_config().await
+}
+
+/// 获取数据库连接状态
+pub async fn get_connection_status() -> Result<ConnectionStatus, AppError> {
+    Ok(db::connection::status())
+}
+
+/// 修改数据库配置 (重启应用后生效)
+pub async fn save_db_config(input: SaveDbConfigInput) -> Result<DbConfigResponse, AppError> {
+    write_db_config(input)?;
+    get_db_config().await
+}
+
+/// 合并输入到配置文件并保存
+fn write_db_config(input: SaveDbConfigInput) -> Result<(), AppError> {
+    let mut config: AppConfig = config::load_file()?;
+    let db = &mut config.database;
+
+    if let Some(v) = input.backend {
+        db.backend = v;
+    }
+    if let Some(v) = input.sqlite_path {
+        db.sqlite_path = v.trim().to_string();
+    }
+    if let Some(v) = input.host {
+        db.host = v.trim().to_string();
+    }
+    if let Some(v) = input.port {
+        db.port = v;
+    }
+    if let Some(v) = input.user {
+        db.user = v.trim().to_string();
+    }
+    if let Some(v) = input.password {
+        db.password = v;
+    }
+    if let Some(v) = input.database {
+        db.database = v.trim().to_string();
+    }
+    if let Some(v) = input.max_connections {
+        db.max_connections = v;
+    }
+    if let Some(v) = input.min_connections {
+        db.min_connections = v;
+    }
+    if let Some(v) = input.connect_timeout_secs {
+        db.connect_timeout_secs = v;
+    }
+    if let Some(v) = input.idle_timeout_secs {
+        db.idle_timeout_secs = v;
+    }
+
+    // 校验配置 (本地 SQLite 不需要连接信息)
+    if db.backend == DatabaseBackend::MySql {
+        if db.host.is_empty() {
+            return Err(AppError::Validation("数据库地址不能为空".to_string()));
+        }
+        if db.user.is_empty() {
+            return Err(AppError::Validation("数据库用户名不能为空".to_string()));
+        }
+        if db.database.is_empty() {
+            return Err(AppError::Validation("数据库名不能为空".to_string()));
+        }
+    }
+    if db.max_connections == 0 {
+        return Err(AppError::Validation("最大连接数必须大于0".to_string()));
+    }
+    if db.min_connections > db.max_connections {
+        return Err(AppError::Validation("最小连接数不能大于最大连接数".to_string()));
+    }
+    if db.connect_timeout_secs == 0 {
+        return Err(AppError::Validation("连接超时时间必须大于0".to_string()));
+    }
+
+    config::save(&config)
+}
@@ -0,0 +1,161 @@
+// 邀请码服务
+// 管理员生成带有效期、最大使用次数和预设角色的邀请码，用户凭邀请码自助注册并直接登录
+use crate::error::AppError;
+use crate::models::{
+    CreateInviteCodeInput, CreateUserInput, InviteCodeResponse, InviteCodeStatus, LoginResponse,
+    RegisterInput, UserResponse,
+};
+use crate::repositories::{
+    AuditRepository, InviteCodeRepository, SessionRepository, SubjectRepository, UserRepository,
+};
+use crate::services::{auth_service, user_service};
+use crate::utils::random;
+use chrono::{DateTime, Duration, Utc};
+
+/// 邀请码长度
+pub const INVITE_CODE_LENGTH: usize = 10;
+
+/// 单个邀请码的最大使用次数上限
+pub const MAX_INVITE_USES: i32 = 500;
+
+/// 邀请码最长有效期 (天)
+pub const MAX_INVITE_DAYS: i64 = 90;
+
+/// 邀请码字符集 (去掉易混淆的 0/O、1/I)
+const INVITE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// 生成随机邀请码
+fn generate_code() -> String {
+    random::random_string(INVITE_ALPHABET, INVITE_CODE_LENGTH)
+}
+
+/// 规范化用户输入的邀请码 (忽略首尾空白和大小写)
+fn normalize_code(code: &str) -> String {
+    code.trim().to_ascii_uppercase()
+}
+
+/// 创建邀请码
+pub async fn create_invite_code<R: InviteCodeRepository>(
+    repo: &R,
+    created_by: i64,
+    input: CreateInviteCodeInput,
+) -> Result<InviteCodeResponse, AppError> {
+    let role = user_service::parse_role(&input.role)?;
+
+    if !(1..=MAX_INVITE_USES).contains(&input.max_uses) {
+        return Err(AppError::Validation(format!("使用次数需在 1-{} 之间", MAX_INVITE_USES)));
+    }
+
+    if !(1..=MAX_INVITE_DAYS).contains(&input.expires_in_days) {
+        return Err(AppError::Validation(format!("有效期需在 1-{} 天之间", MAX_INVITE_DAYS)));
+    }
+
+    let now = Utc::now();
+    let expires_at = now + Duration::days(input.expires_in_days);
+    let invite_id = repo
+        .create_invite_code(&generate_code(), &role, input.max_uses, expires_at, created_by)
+        .await?;
+
+    let invite = repo
+        .find_invite_code(invite_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("邀请码不存在".to_string()))?;
+
+    Ok(InviteCodeResponse::from_code(invite, now))
+}
+
+/// 获取全部邀请码
+pub async fn get_invite_codes<R: InviteCodeRepository>(repo: &R) -> Result<Vec<InviteCodeResponse>, AppError> {
+    let now = Utc::now();
+    let invites = repo.list_invite_codes().await?;
+    Ok(invites.into_iter().map(|i| InviteCodeResponse::from_code(i, now)).collect())
+}
+
+/// 撤销邀请码 (已注册的账户不受影响)
+pub async fn revoke_invite_code<R: InviteCodeRepository>(repo: &R, invite_id: i64) -> Result<(), AppError> {
+    if repo.find_invite_code(invite_id).await?.is_none() {
+        return Err(AppError::NotFound("邀请码不存在".to_string()));
+    }
+
+    repo.revoke_invite_code(invite_id, Utc::now()).await
+}
+
+/// 获取通过邀请码注册的账户
+pub async fn get_invite_code_users<R: InviteCodeRepository>(
+    repo: &R,
+    invite_id: i64,
+) -> Result<Vec<UserResponse>, AppError> {
+    let users = repo.list_invite_users(invite_id).await?;
+    Ok(users.into_iter().map(UserResponse::from).collect())
+}
+
+/// 使用邀请码注册并登录
+pub async fn register_with_invite<R>(repo: &R, input: RegisterInput) -> Result<LoginResponse, AppError>
+where
+    R: UserRepository + SessionRepository + SubjectRepository + InviteCodeRepository + AuditRepository,
+{
+    register_with_invite_at(repo, input, Utc::now()).await
+}
+
+/// 以指定时间使用邀请码注册 (有效期按该时间计算)
+pub(crate) async fn register_with_invite_at<R>(
+    repo: &R,
+    input: RegisterInput,
+    now: DateTime<Utc>,
+) -> Result<LoginResponse, AppError>
+where
+    R: UserRepository + SessionRepository + SubjectRepository + InviteCodeRepository + AuditRepository,
+{
+    let username = input.username.trim().to_string();
+    let display_name = input.display_name.trim().to_string();
+    user_service::validate_names(&username, &display_name)?;
+
+    // 检查邀请码状态
+    let invite = repo
+        .find_invite_code_by_code(&normalize_code(&input.invite_code))
+        .await?
+        .ok_or_else(|| AppError::Validation("邀请码无效".to_string()))?;
+    match invite.status_at(now) {
+        InviteCodeStatus::Active => {}
+        InviteCodeStatus::Expired => return Err(AppError::Validation("邀请码已过期".to_string())),
+        InviteCodeStatus::Exhausted => return Err(AppError::Validation("邀请码已达到使用次数上限".to_string())),
+        InviteCodeStatus::Revoked => return Err(AppError::Validation("邀请码已撤销".to_string())),
+    }
+
+    if repo.find_user_by_username(&username).await?.is_some() {
+        return Err(AppError::Conflict("用户名已存在".to_string()));
+    }
+
+    // 先占用使用次数，避免并发注册超过上限
+    if !repo.claim_invite_use(invite.id, now).await? {
+        return Err(AppError::Validation("邀请码已达到使用次数上限".to_string()));
+    }
+
+    // 创建账户 (检查密码策略并写入默认科目)，失败时归还使用次数
+    let created = user_service::create_user(
+        repo,
+        None,
+        CreateUserInput {
+            username,
+            password: input.password,
+            display_name,
+            role: invite.role.to_string(),
+        },
+    )
+    .await;
+    let user = match created {
+        Ok(user) => user,
+        Err(e) => {
+            repo.release_invite_use(invite.id).await?;
+            return Err(e);
+        }
+    };
+    repo.record_invite_user(invite.id, user.id, now).await?;
+
+    let user = repo
+        .find_user(user.id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("用户不存在".to_string()))?;
+
+    auth_service::start_session(repo, user, now).await
+}
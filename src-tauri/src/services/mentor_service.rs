@@ -0,0 +1,57 @@
+// 导师服务
+// 导师只能查看分配给自己的学生；管理员拥有全部权限，可以查看任意用户
+use crate::error::AppError;
+use crate::models::{Permission, User, UserResponse, UserRole};
+use crate::repositories::{MentorRepository, UserRepository};
+use crate::services::auth_service;
+
+/// 检查用户能否以指定权限访问学生的数据 (导师仅限分配给自己的学生)
+pub async fn ensure_student_access<R: MentorRepository>(
+    repo: &R,
+    user: &User,
+    permission: Permission,
+    student_id: i64,
+) -> Result<(), AppError> {
+    auth_service::require_permission(user, permission)?;
+
+    if user.role.is_scoped_to_students() && !repo.is_student_assigned(user.id, student_id).await? {
+        return Err(AppError::Forbidden("只能查看分配给自己的学生".to_string()));
+    }
+
+    Ok(())
+}
+
+/// 获取分配给导师的学生
+pub async fn get_students<R: MentorRepository>(repo: &R, mentor_id: i64) -> Result<Vec<UserResponse>, AppError> {
+    let students = repo.list_students(mentor_id).await?;
+    Ok(students.into_iter().map(UserResponse::from).collect())
+}
+
+/// 将学生分配给导师
+pub async fn assign_student<R>(repo: &R, mentor_id: i64, student_id: i64) -> Result<(), AppError>
+where
+    R: UserRepository + MentorRepository,
+{
+    if mentor_id == student_id {
+        return Err(AppError::Validation("不能将导师分配给自己".to_string()));
+    }
+
+    let mentor = repo
+        .find_user(mentor_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("导师不存在".to_string()))?;
+    if mentor.role != UserRole::Mentor {
+        return Err(AppError::Validation("该用户不是导师".to_string()));
+    }
+
+    if repo.find_user(student_id).await?.is_none() {
+        return Err(AppError::NotFound("学生不存在".to_string()));
+    }
+
+    repo.assign_student(mentor_id, student_id).await
+}
+
+/// 取消学生分配
+pub async fn unassign_student<R: MentorRepository>(repo: &R, mentor_id: i64, student_id: i64) -> Result<(), AppError> {
+    repo.unassign_student(mentor_id, student_id).await
+}
@@ -2,11 +2,23 @@
 
 pub mod auth_service;
 pub mod user_service;
+pub mod user_csv_service;
+pub mod mentor_service;
+pub mod invite_service;
+pub mod audit_service;
+pub mod two_factor_service;
 pub mod subject_service;
 pub mod countdown_service;
 pub mod task_service;
+pub mod checklist_service;
+pub mod tag_service;
+pub mod task_status_service;
+pub mod schedule_service;
+pub mod carry_over_service;
 pub mod pomodoro_service;
 pub mod stats_service;
 pub mod review_service;
 pub mod ai_service;
 pub mod preference_service;
+pub mod config_service;
+pub mod sync_service;
@@ -1,51 +1,78 @@
-use crate::db::get_pool;
+use crate::db::{self, with_pool, DbPool, ExecResult};
+use crate::error::AppError;
 use crate::models::{PomodoroSession, PomodoroResponse, StartPomodoroInput, Subject};
+use crate::repositories::SqlRepository;
+use crate::services::task_service;
+use chrono::Utc;
 // 番茄钟服务
 
 /// 开始番茄钟
-pub async fn start_pomodoro(user_id: i64, input: StartPomodoroInput) -> Result<PomodoroResponse, String> {
-    let pool = get_pool();
-    
-    let result = sqlx::query(
-        "INSERT INTO pomodoro_sessions (user_id, subject_id, task_id, start_time, status) 
-         VALUES (?, ?, ?, NOW(), 'running')"
+pub async fn start_pomodoro(user_id: i64, input: StartPomodoroInput) -> Result<PomodoroResponse, AppError> {
+    let pomodoro_id = create_pomodoro(&db::get_pool()?, user_id, input).await?;
+    get_pomodoro_by_id(pomodoro_id).await
+}
+
+/// 在指定连接池上创建进行中的番茄钟，返回番茄钟 ID
+/// 关联的任务须属于该用户 (尚未生成的重复任务先生成，学习时长才能按任务和标签统计)，未选择科目时使用任务的科目
+pub(crate) async fn create_pomodoro(db: &DbPool, user_id: i64, input: StartPomodoroInput) -> Result<i64, AppError> {
+    let mut subject_id = input.subject_id;
+    let task_id = match input.task_id {
+        Some(task_id) => {
+            let repo = SqlRepository::new(db.clone());
+            let task = task_service::find_task_or_occurrence(&repo, user_id, task_id)
+                .await?
+                .ok_or_else(|| AppError::NotFound("任务不存在".to_string()))?;
+            let task = task_service::materialize_task(&repo, user_id, task).await?;
+            subject_id = subject_id.or(task.subject_id);
+            Some(task.id)
+        }
+        None => None,
+    };
+
+    let pomodoro_id = with_pool!(db, pool => sqlx::query(
+        "INSERT INTO pomodoro_sessions (user_id, subject_id, task_id, start_time, status)
+         VALUES (?, ?, ?, ?, 'running')"
     )
     .bind(user_id)
-    .bind(input.subject_id)
-    .bind(input.task_id)
+    .bind(subject_id)
+    .bind(task_id)
+    .bind(Utc::now())
     .execute(pool)
     .await
-    .map_err(|e| format!("创建番茄钟失败: {}", e))?;
+    .map(ExecResult::from))
+    .map_err(AppError::db("创建番茄钟失败"))?
+    .insert_id;
 
-    let pomodoro_id = result.last_insert_id() as i64;
-    get_pomodoro_by_id(pomodoro_id).await
+    Ok(pomodoro_id)
+}
+
+/// 查询科目名称 (查询失败时返回 None)
+async fn get_subject_name(subject_id: i64) -> Option<String> {
+    with_pool!(&db::get_pool().ok()?, pool => sqlx::query_as::<_, Subject>(
+        "SELECT id, user_id, name, color, is_default, created_at FROM subjects WHERE id = ?"
+    )
+    .bind(subject_id)
+    .fetch_optional(pool)
+    .await)
+    .ok()
+    .flatten()
+    .map(|s| s.name)
 }
 
 /// 根据 ID 获取番茄钟
-async fn get_pomodoro_by_id(pomodoro_id: i64) -> Result<PomodoroResponse, String> {
-    let pool = get_pool();
-    
-    let session: PomodoroSession = sqlx::query_as(
-        "SELECT id, user_id, subject_id, task_id, start_time, end_time, duration_minutes, status 
+async fn get_pomodoro_by_id(pomodoro_id: i64) -> Result<PomodoroResponse, AppError> {
+    let session: PomodoroSession = with_pool!(pool => sqlx::query_as(
+        "SELECT id, user_id, subject_id, task_id, start_time, end_time, duration_minutes, status
          FROM pomodoro_sessions WHERE id = ?"
     )
     .bind(pomodoro_id)
     .fetch_one(pool)
-    .await
-    .map_err(|e| format!("查询番茄钟失败: {}", e))?;
-
-    let subject_name = if let Some(sid) = session.subject_id {
-        sqlx::query_as::<_, Subject>(
-            "SELECT id, user_id, name, color, is_default, created_at FROM subjects WHERE id = ?"
-        )
-        .bind(sid)
-        .fetch_optional(pool)
-        .await
-        .ok()
-        .flatten()
-        .map(|s| s.name)
-    } else {
-        None
+    .await)
+    .map_err(AppError::db("查询番茄钟失败"))?;
+
+    let subject_name = match session.subject_id {
+        Some(sid) => get_subject_name(sid).await,
+        None => None,
     };
 
     Ok(PomodoroResponse {
@@ -61,69 +88,58 @@ async fn get_pomodoro_by_id(pomodoro_id: i64) -> Result<PomodoroResponse, String
 }
 
 /// 完成番茄钟
-pub async fn complete_pomodoro(user_id: i64, pomodoro_id: i64, duration_minutes: i32) -> Result<(), String> {
-    let pool = get_pool();
-    
-    sqlx::query(
-        "UPDATE pomodoro_sessions SET status = 'completed', end_time = NOW(), duration_minutes = ? 
+pub async fn complete_pomodoro(user_id: i64, pomodoro_id: i64, duration_minutes: i32) -> Result<(), AppError> {
+    with_pool!(pool => sqlx::query(
+        "UPDATE pomodoro_sessions SET status = 'completed', end_time = ?, duration_minutes = ?
          WHERE id = ? AND user_id = ?"
     )
+    .bind(Utc::now())
     .bind(duration_minutes)
     .bind(pomodoro_id)
     .bind(user_id)
     .execute(pool)
     .await
-    .map_err(|e| format!("完成番茄钟失败: {}", e))?;
+    .map(ExecResult::from))
+    .map_err(AppError::db("完成番茄钟失败"))?;
 
     Ok(())
 }
 
 /// 取消番茄钟
-pub async fn cancel_pomodoro(user_id: i64, pomodoro_id: i64, duration_minutes: i32) -> Result<(), String> {
-    let pool = get_pool();
-    
-    sqlx::query(
-        "UPDATE pomodoro_sessions SET status = 'cancelled', end_time = NOW(), duration_minutes = ? 
+pub async fn cancel_pomodoro(user_id: i64, pomodoro_id: i64, duration_minutes: i32) -> Result<(), AppError> {
+    with_pool!(pool => sqlx::query(
+        "UPDATE pomodoro_sessions SET status = 'cancelled', end_time = ?, duration_minutes = ?
          WHERE id = ? AND user_id = ?"
     )
+    .bind(Utc::now())
     .bind(duration_minutes)
     .bind(pomodoro_id)
     .bind(user_id)
     .execute(pool)
     .await
-    .map_err(|e| format!("取消番茄钟失败: {}", e))?;
+    .map(ExecResult::from))
+    .map_err(AppError::db("取消番茄钟失败"))?;
 
     Ok(())
 }
 
 /// 获取番茄钟历史
-pub async fn get_pomodoro_history(user_id: i64, limit: i32) -> Result<Vec<PomodoroResponse>, String> {
-    let pool = get_pool();
-    
-    let sessions: Vec<PomodoroSession> = sqlx::query_as(
-        "SELECT id, user_id, subject_id, task_id, start_time, end_time, duration_minutes, status 
+pub async fn get_pomodoro_history(user_id: i64, limit: i32) -> Result<Vec<PomodoroResponse>, AppError> {
+    let sessions: Vec<PomodoroSession> = with_pool!(pool => sqlx::query_as(
+        "SELECT id, user_id, subject_id, task_id, start_time, end_time, duration_minutes, status
          FROM pomodoro_sessions WHERE user_id = ? ORDER BY start_time DESC LIMIT ?"
     )
     .bind(user_id)
     .bind(limit)
     .fetch_all(pool)
-    .await
-    .map_err(|e| format!("查询番茄钟历史失败: {}", e))?;
+    .await)
+    .map_err(AppError::db("查询番茄钟历史失败"))?;
 
     let mut responses = Vec::new();
     for session in sessions {
-        let subject_name = if let Some(sid) = session.subject_id {
-            sqlx::query_as::<_, Subject>(
-                "SELECT id, user_id, name, color, is_default, created_at FROM subjects WHERE id = ?"
-            )
-            .bind(sid)
-            .fetch_optional(pool)
-            .await
-            .ok()
-            .flatten()
-            .map(|s| s.name)
-        } else {
-            None
+        let subject_name = match session.subject_id {
+            Some(sid) => get_subject_name(sid).await,
+            None => None,
         };
 
         responses.push(PomodoroResponse {
@@ -1,36 +1,42 @@
 // 学习偏好服务
-use crate::db::get_pool;
-use crate::models::{StudyPreference, StudyPreferenceResponse, SaveStudyPreferenceInput};
-use chrono::NaiveTime;
+use crate::db::{with_pool, ExecResult};
+use crate::error::AppError;
+use crate::models::{CarryOverMode, StudyPreference, StudyPreferenceResponse, SaveStudyPreferenceInput, StudyWindow};
+use chrono::{NaiveDate, NaiveTime};
 
-/// 获取学习偏好
-pub async fn get_study_preference(user_id: i64) -> Result<StudyPreferenceResponse, String> {
-    let pool = get_pool();
-    
-    let pref: Option<StudyPreference> = sqlx::query_as(
+async fn find_study_preference(user_id: i64) -> Result<Option<StudyPreference>, AppError> {
+    with_pool!(pool => sqlx::query_as(
         "SELECT * FROM study_preferences WHERE user_id = ?"
     )
     .bind(user_id)
     .fetch_optional(pool)
-    .await
-    .map_err(|e| format!("查询学习偏好失败: {}", e))?;
+    .await)
+    .map_err(AppError::db("查询学习偏好失败"))
+}
 
+/// 获取学习偏好
+pub async fn get_study_preference(user_id: i64) -> Result<StudyPreferenceResponse, AppError> {
+    let pref = find_study_preference(user_id).await?;
     Ok(pref.map(StudyPreferenceResponse::from).unwrap_or_default())
 }
 
+/// 获取学习时段 (用于检查任务时间，未设置偏好时使用默认时段)
+pub async fn get_study_window(user_id: i64) -> Result<StudyWindow, AppError> {
+    let pref = find_study_preference(user_id).await?;
+    Ok(pref.as_ref().map(StudyWindow::from).unwrap_or_default())
+}
+
 /// 保存学习偏好
-pub async fn save_study_preference(user_id: i64, input: SaveStudyPreferenceInput) -> Result<StudyPreferenceResponse, String> {
-    let pool = get_pool();
-    
+pub async fn save_study_preference(user_id: i64, input: SaveStudyPreferenceInput) -> Result<StudyPreferenceResponse, AppError> {
     // 解析时间
     let start_time = NaiveTime::parse_from_str(&format!("{}:00", input.start_time), "%H:%M:%S")
-        .map_err(|_| "开始时间格式错误")?;
+        .map_err(|_| AppError::Validation("开始时间格式错误".to_string()))?;
     let end_time = NaiveTime::parse_from_str(&format!("{}:00", input.end_time), "%H:%M:%S")
-        .map_err(|_| "结束时间格式错误")?;
+        .map_err(|_| AppError::Validation("结束时间格式错误".to_string()))?;
     let lunch_start = NaiveTime::parse_from_str(&format!("{}:00", input.lunch_break_start), "%H:%M:%S")
-        .map_err(|_| "午休开始时间格式错误")?;
+        .map_err(|_| AppError::Validation("午休开始时间格式错误".to_string()))?;
     let lunch_end = NaiveTime::parse_from_str(&format!("{}:00", input.lunch_break_end), "%H:%M:%S")
-        .map_err(|_| "午休结束时间格式错误")?;
+        .map_err(|_| AppError::Validation("午休结束时间格式错误".to_string()))?;
     
     // 序列化科目列表
     let focus_json = serde_json::to_string(&input.focus_subjects).ok();
@@ -42,22 +48,22 @@ pub async fn save_study_preference(user_id: i64, input: SaveStudyPreferenceInput
     });
     
     // 检查是否已存在
-    let existing: Option<(i64,)> = sqlx::query_as(
+    let existing: Option<(i64,)> = with_pool!(pool => sqlx::query_as(
         "SELECT id FROM study_preferences WHERE user_id = ?"
     )
     .bind(user_id)
     .fetch_optional(pool)
-    .await
-    .map_err(|e| format!("查询失败: {}", e))?;
+    .await)
+    .map_err(AppError::db("查询失败"))?;
 
     if existing.is_some() {
         // 更新
-        sqlx::query(
+        with_pool!(pool => sqlx::query(
             "UPDATE study_preferences SET 
              daily_hours = ?, start_time = ?, end_time = ?,
              lunch_break_start = ?, lunch_break_end = ?,
              study_phase = ?, focus_subjects = ?, weak_subjects = ?,
-             exam_date = ?, notes = ?
+             exam_date = ?, notes = ?, carry_over_mode = ?
              WHERE user_id = ?"
         )
         .bind(input.daily_hours)
@@ -70,17 +76,19 @@ pub async fn save_study_preference(user_id: i64, input: SaveStudyPreferenceInput
         .bind(&weak_json)
         .bind(exam_date)
         .bind(&input.notes)
+        .bind(input.carry_over_mode.map(|m| m.as_str()))
         .bind(user_id)
         .execute(pool)
         .await
-        .map_err(|e| format!("更新学习偏好失败: {}", e))?;
+        .map(ExecResult::from))
+        .map_err(AppError::db("更新学习偏好失败"))?;
     } else {
         // 创建
-        sqlx::query(
+        with_pool!(pool => sqlx::query(
             "INSERT INTO study_preferences 
              (user_id, daily_hours, start_time, end_time, lunch_break_start, lunch_break_end,
-              study_phase, focus_subjects, weak_subjects, exam_date, notes)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+              study_phase, focus_subjects, weak_subjects, exam_date, notes, carry_over_mode)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
         )
         .bind(user_id)
         .bind(input.daily_hours)
@@ -93,10 +101,44 @@ pub async fn save_study_preference(user_id: i64, input: SaveStudyPreferenceInput
         .bind(&weak_json)
         .bind(exam_date)
         .bind(&input.notes)
+        .bind(input.carry_over_mode.map(|m| m.as_str()))
         .execute(pool)
         .await
-        .map_err(|e| format!("创建学习偏好失败: {}", e))?;
+        .map(ExecResult::from))
+        .map_err(AppError::db("创建学习偏好失败"))?;
     }
 
     get_study_preference(user_id).await
 }
+
+/// 今天还未自动顺延的用户 (已停用的用户除外)，返回用户 ID、顺延方式和上次顺延的日期
+pub async fn list_due_carry_overs(today: NaiveDate) -> Result<Vec<(i64, CarryOverMode, Option<NaiveDate>)>, AppError> {
+    let rows: Vec<(i64, String, Option<NaiveDate>)> = with_pool!(pool => sqlx::query_as(
+        "SELECT p.user_id, p.carry_over_mode, p.last_carry_over_date
+         FROM study_preferences p JOIN users u ON u.id = p.user_id
+         WHERE p.carry_over_mode IS NOT NULL AND u.deactivated_at IS NULL
+           AND (p.last_carry_over_date IS NULL OR p.last_carry_over_date < ?)"
+    )
+    .bind(today)
+    .fetch_all(pool)
+    .await)
+    .map_err(AppError::db("查询自动顺延设置失败"))?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(user_id, mode, last)| CarryOverMode::parse(&mode).map(|m| (user_id, m, last)))
+        .collect())
+}
+
+/// 记录自动顺延的日期
+pub async fn set_last_carry_over_date(user_id: i64, date: NaiveDate) -> Result<(), AppError> {
+    with_pool!(pool => sqlx::query("UPDATE study_preferences SET last_carry_over_date = ? WHERE user_id = ?")
+        .bind(date)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("更新自动顺延日期失败"))?;
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+// 任务时间段服务
+// 时间范围无效或与当天任务重叠时拒绝保存，不符合学习偏好时只给出提醒
+use crate::error::AppError;
+use crate::models::{ScheduleWarning, ScheduleWarningKind, StudyWindow, Task, TaskStatus, TimeSlot};
+use crate::repositories::{TaskRepository, TaskSeriesRepository};
+use crate::services::task_service;
+use chrono::{NaiveDate, NaiveTime};
+
+/// 查询空闲时段时的最长时长 (分钟)
+pub const MAX_SLOT_MINUTES: i64 = 24 * 60;
+
+/// 两个时间段是否重叠 (首尾相接不算重叠)
+fn overlaps(a: (NaiveTime, NaiveTime), b: (NaiveTime, NaiveTime)) -> bool {
+    a.0 < b.1 && b.0 < a.1
+}
+
+/// 检查时间范围 (结束时间须晚于开始时间)
+pub fn validate_time_range(start_time: NaiveTime, end_time: NaiveTime) -> Result<(), AppError> {
+    if end_time <= start_time {
+        return Err(AppError::Validation("结束时间必须晚于开始时间".to_string()));
+    }
+    Ok(())
+}
+
+/// 任务是否占用时间 (已顺延到其他日期的任务不占用原来的时间)
+fn occupies_time(task: &Task) -> bool {
+    task.status != TaskStatus::RolledOver
+}
+
+/// 与时间段重叠的任务 ID (排除正在修改的任务)
+pub fn find_conflicts(tasks: &[Task], start_time: NaiveTime, end_time: NaiveTime, exclude: Option<i64>) -> Vec<i64> {
+    tasks
+        .iter()
+        .filter(|t| occupies_time(t) && Some(t.id) != exclude)
+        .filter(|t| overlaps((t.start_time, t.end_time), (start_time, end_time)))
+        .map(|t| t.id)
+        .collect()
+}
+
+/// 检查时间段与当天已有任务 (包含尚未生成的重复任务) 是否重叠
+pub async fn check_conflicts<R>(
+    repo: &R,
+    user_id: i64,
+    date: NaiveDate,
+    start_time: NaiveTime,
+    end_time: NaiveTime,
+    exclude: Option<i64>,
+) -> Result<(), AppError>
+where
+    R: TaskRepository + TaskSeriesRepository,
+{
+    let tasks = task_service::list_day_tasks(repo, user_id, date).await?;
+    let task_ids = find_conflicts(&tasks, start_time, end_time, exclude);
+    if task_ids.is_empty() {
+        return Ok(());
+    }
+    Err(AppError::ScheduleConflict {
+        message: format!("与当天已有的 {} 个任务时间重叠", task_ids.len()),
+        task_ids,
+    })
+}
+
+/// 不符合学习偏好的提醒
+pub fn preference_warnings(window: &StudyWindow, start_time: NaiveTime, end_time: NaiveTime) -> Vec<ScheduleWarning> {
+    let format = |t: NaiveTime| t.format("%H:%M").to_string();
+    let mut warnings = Vec::new();
+
+    if window.start_time < window.end_time && (start_time < window.start_time || end_time > window.end_time) {
+        warnings.push(ScheduleWarning {
+            kind: ScheduleWarningKind::OutsideStudyWindow,
+            message: format!("超出学习时段 {}-{}", format(window.start_time), format(window.end_time)),
+        });
+    }
+    let lunch = (window.lunch_break_start, window.lunch_break_end);
+    if lunch.0 < lunch.1 && overlaps(lunch, (start_time, end_time)) {
+        warnings.push(ScheduleWarning {
+            kind: ScheduleWarningKind::LunchBreak,
+            message: format!("与午休时间 {}-{} 重叠", format(lunch.0), format(lunch.1)),
+        });
+    }
+
+    warnings
+}
+
+/// 学习时段内去掉午休和已有任务后、不短于指定时长的空闲时段
+pub fn free_slots(window: &StudyWindow, tasks: &[Task], min_minutes: i64) -> Vec<TimeSlot> {
+    let mut busy: Vec<(NaiveTime, NaiveTime)> = tasks
+        .iter()
+        .filter(|t| occupies_time(t))
+        .map(|t| (t.start_time, t.end_time))
+        .chain(std::iter::once((window.lunch_break_start, window.lunch_break_end)))
+        .filter(|(start, end)| start < end)
+        .collect();
+    busy.sort();
+
+    let mut slots = Vec::new();
+    let mut cursor = window.start_time;
+    for (start, end) in busy {
+        if start > cursor {
+            slots.push(TimeSlot::new(cursor, start.min(window.end_time)));
+        }
+        cursor = cursor.max(end);
+        if cursor >= window.end_time {
+            break;
+        }
+    }
+    if cursor < window.end_time {
+        slots.push(TimeSlot::new(cursor, window.end_time));
+    }
+
+    slots.retain(|s| s.start_time < s.end_time && s.minutes >= min_minutes);
+    slots
+}
+
+/// 查询指定日期可安排指定时长的空闲时段 (包含当天的重复任务)
+pub async fn find_free_slots<R>(
+    repo: &R,
+    user_id: i64,
+    date: NaiveDate,
+    duration_minutes: i64,
+    window: &StudyWindow,
+) -> Result<Vec<TimeSlot>, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository,
+{
+    if !(1..=MAX_SLOT_MINUTES).contains(&duration_minutes) {
+        return Err(AppError::Validation(format!("时长需在 1-{} 分钟之间", MAX_SLOT_MINUTES)));
+    }
+
+    let tasks = task_service::list_day_tasks(repo, user_id, date).await?;
+    Ok(free_slots(window, &tasks, duration_minutes))
+}
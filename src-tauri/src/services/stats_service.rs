@@ -1,9 +1,40 @@
 // 统计服务
-use crate::db::get_pool;
-use chrono::NaiveDate;
+use crate::db::{self, day_bounds, with_pool, DbPool};
+use crate::error::AppError;
+use crate::models::{Task, TaskSeries, UserRole};
+use crate::services::task_service;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::Row;
-use rust_decimal::Decimal;
+use sqlx::FromRow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// 已完成番茄钟的过滤条件 (参数为时间范围的起止)
+const COMPLETED_POMODORO_FILTER: &str = "status = 'completed' AND start_time >= ? AND start_time < ?";
+
+/// 番茄钟总时长 - 使用 CAST 转换为整数 (SIGNED INTEGER 两种后端通用)
+const STUDY_MINUTES_SUM: &str = "CAST(COALESCE(SUM(duration_minutes), 0) AS SIGNED INTEGER)";
+
+/// 任务总数、已完成数与完成进度之和 (已顺延的任务计入顺延后的日期，不计入原日期)
+/// 进度按百分比累加：已完成的任务为 100，未完成的任务按清单的完成百分比计入
+const TASK_COUNTS: &str = "CAST(COALESCE(SUM(CASE WHEN status <> 'rolled_over' THEN 1 ELSE 0 END), 0) AS SIGNED INTEGER)
+    as total_tasks,
+    CAST(COALESCE(SUM(CASE WHEN status = 'completed' THEN 1 ELSE 0 END), 0) AS SIGNED INTEGER) as completed_tasks,
+    CAST(COALESCE(SUM(CASE WHEN status = 'completed' THEN 100 WHEN status = 'rolled_over' THEN 0 ELSE progress END), 0)
+        AS SIGNED INTEGER) as progress_points";
+
+/// 完成率 (百分比)，在 Rust 中计算，避免 DECIMAL 与 REAL 的差异
+pub fn completion_rate(completed: i64, total: i64) -> f64 {
+    if total > 0 {
+        completed as f64 * 100.0 / total as f64
+    } else {
+        0.0
+    }
+}
+
+/// 计入部分完成清单的完成率 (百分比)
+pub fn progress_rate(progress_points: i64, total: i64) -> f64 {
+    completion_rate(progress_points, total * 100)
+}
 
 /// 科目学习时长
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,12 +45,52 @@ pub struct SubjectStudyTime {
     pub total_minutes: i64,
 }
 
+/// 标签的学习时长与完成情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagStats {
+    pub tag_id: i64,
+    pub tag_name: String,
+    pub tag_color: String,
+    /// 关联到带该标签的任务的番茄钟时长 (开始番茄钟时选择任务)
+    pub study_minutes: i64,
+    pub total_tasks: i64,
+    pub completed_tasks: i64,
+    pub completion_rate: f64,
+}
+
+/// 标签统计查询结果
+#[derive(FromRow)]
+struct TagStatsRow {
+    tag_id: i64,
+    tag_name: String,
+    tag_color: String,
+    study_minutes: i64,
+    total_tasks: i64,
+    completed_tasks: i64,
+    progress_points: i64,
+}
+
+impl From<TagStatsRow> for TagStats {
+    fn from(row: TagStatsRow) -> Self {
+        TagStats {
+            tag_id: row.tag_id,
+            tag_name: row.tag_name,
+            tag_color: row.tag_color,
+            study_minutes: row.study_minutes,
+            total_tasks: row.total_tasks,
+            completed_tasks: row.completed_tasks,
+            completion_rate: progress_rate(row.progress_points, row.total_tasks),
+        }
+    }
+}
+
 /// 每日完成率
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DailyCompletion {
     pub date: NaiveDate,
     pub total_tasks: i64,
     pub completed_tasks: i64,
+    /// 完成率 (未完成的任务按清单的完成百分比计入)
     pub completion_rate: f64,
 }
 
@@ -31,112 +102,327 @@ pub struct Statistics {
     pub completed_tasks: i64,
     pub completion_rate: f64,
     pub subject_distribution: Vec<SubjectStudyTime>,
+    /// 按标签统计 (一个任务有多个标签时分别计入)
+    pub tag_distribution: Vec<TagStats>,
     pub daily_trend: Vec<DailyCompletion>,
 }
 
-/// 获取统计数据
-pub async fn get_stats(user_id: i64, start_date: NaiveDate, end_date: NaiveDate) -> Result<Statistics, String> {
-    let pool = get_pool();
-    
-    // 获取总学习时长 - 使用 CAST 转换为 SIGNED
-    let total_minutes_row = sqlx::query(
-        "SELECT CAST(COALESCE(SUM(duration_minutes), 0) AS SIGNED) as total FROM pomodoro_sessions 
-         WHERE user_id = ? AND status = 'completed' AND DATE(start_time) BETWEEN ? AND ?"
+/// 单个用户在时间范围内的学习活跃情况 (管理员总览)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserActivity {
+    pub user_id: i64,
+    pub username: String,
+    pub display_name: String,
+    pub role: String,
+    pub role_label: String,
+    pub is_active: bool,
+    /// 最近一次成功登录的时间
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub study_minutes: i64,
+    pub completed_pomodoros: i64,
+    pub total_tasks: i64,
+    pub completed_tasks: i64,
+    pub completion_rate: f64,
+    pub last_review_date: Option<NaiveDate>,
+    /// 距最近一次复盘的天数 (从未复盘时为空)
+    pub days_since_review: Option<i64>,
+    /// 是否配置了自己的 AI 密钥
+    pub ai_configured: bool,
+}
+
+/// 用户活跃情况查询结果
+#[derive(FromRow)]
+struct UserActivityRow {
+    id: i64,
+    username: String,
+    display_name: String,
+    #[sqlx(try_from = "String")]
+    role: UserRole,
+    deactivated_at: Option<DateTime<Utc>>,
+    last_login_at: Option<DateTime<Utc>>,
+    study_minutes: i64,
+    completed_pomodoros: i64,
+    total_tasks: i64,
+    completed_tasks: i64,
+    progress_points: i64,
+    last_review_date: Option<NaiveDate>,
+    ai_configured: i64,
+}
+
+impl UserActivityRow {
+    fn into_activity(self, today: NaiveDate) -> UserActivity {
+        UserActivity {
+            user_id: self.id,
+            username: self.username,
+            display_name: self.display_name,
+            role: self.role.to_string(),
+            role_label: self.role.label().to_string(),
+            is_active: self.deactivated_at.is_none(),
+            last_login_at: self.last_login_at,
+            study_minutes: self.study_minutes,
+            completed_pomodoros: self.completed_pomodoros,
+            total_tasks: self.total_tasks,
+            completed_tasks: self.completed_tasks,
+            completion_rate: progress_rate(self.progress_points, self.total_tasks),
+            last_review_date: self.last_review_date,
+            days_since_review: self.last_review_date.map(|date| (today - date).num_days()),
+            ai_configured: self.ai_configured != 0,
+        }
+    }
+}
+
+/// 日期范围内 (含首尾) 尚未生成的重复任务 (不指定用户时查询所有用户)，统计时计为待完成的任务
+async fn query_pending_occurrences(
+    db: &DbPool,
+    user_id: Option<i64>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<Task>, AppError> {
+    let series: Vec<TaskSeries> = with_pool!(db, pool => sqlx::query_as(
+        "SELECT id, user_id, subject_id, start_date, start_time, end_time, content,
+                alarm_enabled, alarm_time, rrule, created_at, updated_at, priority
+         FROM task_series WHERE start_date <= ? AND (? IS NULL OR user_id = ?)"
     )
+    .bind(end_date)
     .bind(user_id)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await)
+    .map_err(AppError::db("查询重复任务失败"))?;
+    if series.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let taken: Vec<(i64, NaiveDate)> = with_pool!(db, pool => sqlx::query_as(
+        "SELECT series_id, occurrence_date FROM tasks
+         WHERE series_id IS NOT NULL AND occurrence_date BETWEEN ? AND ?
+         UNION ALL
+         SELECT series_id, occurrence_date FROM task_series_skips WHERE occurrence_date BETWEEN ? AND ?"
+    )
+    .bind(start_date)
+    .bind(end_date)
     .bind(start_date)
     .bind(end_date)
+    .fetch_all(pool)
+    .await)
+    .map_err(AppError::db("查询重复任务失败"))?;
+    let taken: HashSet<(i64, NaiveDate)> = taken.into_iter().collect();
+
+    Ok(task_service::pending_occurrences(&series, &taken, start_date, end_date))
+}
+
+/// 获取统计数据
+pub async fn get_stats(user_id: i64, start_date: NaiveDate, end_date: NaiveDate) -> Result<Statistics, AppError> {
+    query_stats(&db::get_pool()?, user_id, start_date, end_date).await
+}
+
+/// 在指定连接池上查询统计数据
+pub(crate) async fn query_stats(
+    db: &DbPool,
+    user_id: i64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Statistics, AppError> {
+    let (range_start, range_end) = day_bounds(start_date, end_date);
+
+    // 获取总学习时长
+    let sql = format!(
+        "SELECT {} as total FROM pomodoro_sessions WHERE user_id = ? AND {}",
+        STUDY_MINUTES_SUM, COMPLETED_POMODORO_FILTER
+    );
+    let (total_study_minutes,): (i64,) = with_pool!(db, pool => sqlx::query_as(&sql)
+    .bind(user_id)
+    .bind(range_start)
+    .bind(range_end)
     .fetch_one(pool)
-    .await
-    .map_err(|e| format!("查询学习时长失败: {}", e))?;
-    
-    let total_study_minutes: i64 = total_minutes_row.get("total");
-
-    // 获取任务统计 - 使用 CAST 转换
-    let task_stats_row = sqlx::query(
-        "SELECT CAST(COUNT(*) AS SIGNED) as total, 
-                CAST(COALESCE(SUM(CASE WHEN status = 'completed' THEN 1 ELSE 0 END), 0) AS SIGNED) as completed
-         FROM tasks WHERE user_id = ? AND task_date BETWEEN ? AND ?"
-    )
+    .await)
+    .map_err(AppError::db("查询学习时长失败"))?;
+
+    // 获取任务统计
+    let sql = format!("SELECT {} FROM tasks WHERE user_id = ? AND task_date BETWEEN ? AND ?", TASK_COUNTS);
+    let (total_tasks, completed_tasks, progress_points): (i64, i64, i64) = with_pool!(db, pool => sqlx::query_as(&sql)
     .bind(user_id)
     .bind(start_date)
     .bind(end_date)
     .fetch_one(pool)
-    .await
-    .map_err(|e| format!("查询任务统计失败: {}", e))?;
+    .await)
+    .map_err(AppError::db("查询任务统计失败"))?;
 
-    let total_tasks: i64 = task_stats_row.get("total");
-    let completed_tasks: i64 = task_stats_row.get("completed");
-    let completion_rate = if total_tasks > 0 {
-        (completed_tasks as f64 / total_tasks as f64) * 100.0
-    } else {
-        0.0
-    };
-
-    // 获取科目分布 - 手动映射
-    let subject_rows = sqlx::query(
-        "SELECT s.id as subject_id, s.name as subject_name, s.color as subject_color, 
-                CAST(COALESCE(SUM(p.duration_minutes), 0) AS SIGNED) as total_minutes
+    // 获取科目分布
+    let subject_rows: Vec<(i64, String, String, i64)> = with_pool!(db, pool => sqlx::query_as(
+        "SELECT s.id as subject_id, s.name as subject_name, s.color as subject_color,
+                CAST(COALESCE(SUM(p.duration_minutes), 0) AS SIGNED INTEGER) as total_minutes
          FROM subjects s
-         LEFT JOIN pomodoro_sessions p ON s.id = p.subject_id 
-            AND p.status = 'completed' AND DATE(p.start_time) BETWEEN ? AND ?
+         LEFT JOIN pomodoro_sessions p ON s.id = p.subject_id
+            AND p.status = 'completed' AND p.start_time >= ? AND p.start_time < ?
          WHERE s.user_id = ?
          GROUP BY s.id, s.name, s.color
-         HAVING total_minutes > 0
+         HAVING SUM(p.duration_minutes) > 0
          ORDER BY total_minutes DESC"
     )
-    .bind(start_date)
-    .bind(end_date)
+    .bind(range_start)
+    .bind(range_end)
     .bind(user_id)
     .fetch_all(pool)
-    .await
-    .map_err(|e| format!("查询科目分布失败: {}", e))?;
+    .await)
+    .map_err(AppError::db("查询科目分布失败"))?;
 
     let subject_distribution: Vec<SubjectStudyTime> = subject_rows
-        .iter()
-        .map(|row| SubjectStudyTime {
-            subject_id: row.get("subject_id"),
-            subject_name: row.get("subject_name"),
-            subject_color: row.get("subject_color"),
-            total_minutes: row.get("total_minutes"),
+        .into_iter()
+        .map(|(subject_id, subject_name, subject_color, total_minutes)| SubjectStudyTime {
+            subject_id,
+            subject_name,
+            subject_color,
+            total_minutes,
         })
         .collect();
 
-    // 获取每日趋势 - 手动映射，处理 DECIMAL 类型
-    let daily_rows = sqlx::query(
-        "SELECT task_date as date, 
-                CAST(COUNT(*) AS SIGNED) as total_tasks, 
-                CAST(SUM(CASE WHEN status = 'completed' THEN 1 ELSE 0 END) AS SIGNED) as completed_tasks,
-                (SUM(CASE WHEN status = 'completed' THEN 1 ELSE 0 END) * 100.0 / COUNT(*)) as completion_rate
-         FROM tasks WHERE user_id = ? AND task_date BETWEEN ? AND ?
-         GROUP BY task_date ORDER BY task_date"
-    )
+    // 获取标签分布 (范围内有任务或学习记录的标签)
+    // 与总数使用相同的任务范围：标签只能加在实际任务上 (给重复任务加标签或开始番茄钟时先生成)，
+    // 下面计入总数的尚未生成的重复任务都没有标签，因此各标签的任务数不会超过总数
+    let sql = format!(
+        "SELECT g.id as tag_id, g.name as tag_name, g.color as tag_color,
+                CAST(COALESCE(p.study_minutes, 0) AS SIGNED INTEGER) as study_minutes,
+                CAST(COALESCE(t.total_tasks, 0) AS SIGNED INTEGER) as total_tasks,
+                CAST(COALESCE(t.completed_tasks, 0) AS SIGNED INTEGER) as completed_tasks,
+                CAST(COALESCE(t.progress_points, 0) AS SIGNED INTEGER) as progress_points
+         FROM task_tags g
+         LEFT JOIN (SELECT l.tag_id, {} FROM tasks JOIN task_tag_links l ON l.task_id = tasks.id
+                    WHERE tasks.user_id = ? AND task_date BETWEEN ? AND ?
+                    GROUP BY l.tag_id) t ON t.tag_id = g.id
+         LEFT JOIN (SELECT l.tag_id, {} as study_minutes
+                    FROM pomodoro_sessions JOIN task_tag_links l ON l.task_id = pomodoro_sessions.task_id
+                    WHERE {} GROUP BY l.tag_id) p ON p.tag_id = g.id
+         WHERE g.user_id = ? AND (t.tag_id IS NOT NULL OR p.tag_id IS NOT NULL)
+         ORDER BY study_minutes DESC, g.name ASC",
+        TASK_COUNTS, STUDY_MINUTES_SUM, COMPLETED_POMODORO_FILTER
+    );
+    let tag_rows: Vec<TagStatsRow> = with_pool!(db, pool => sqlx::query_as(&sql)
     .bind(user_id)
     .bind(start_date)
     .bind(end_date)
+    .bind(range_start)
+    .bind(range_end)
+    .bind(user_id)
     .fetch_all(pool)
-    .await
-    .map_err(|e| format!("查询每日趋势失败: {}", e))?;
-
-    let daily_trend: Vec<DailyCompletion> = daily_rows
-        .iter()
-        .map(|row| {
-            let rate: Decimal = row.get("completion_rate");
-            DailyCompletion {
-                date: row.get("date"),
-                total_tasks: row.get("total_tasks"),
-                completed_tasks: row.get("completed_tasks"),
-                completion_rate: rate.to_string().parse::<f64>().unwrap_or(0.0),
-            }
+    .await)
+    .map_err(AppError::db("查询标签统计失败"))?;
+    let tag_distribution: Vec<TagStats> = tag_rows.into_iter().map(TagStats::from).collect();
+
+    // 获取每日趋势
+    let sql = format!(
+        "SELECT task_date as date, {} FROM tasks WHERE user_id = ? AND task_date BETWEEN ? AND ?
+         GROUP BY task_date ORDER BY task_date",
+        TASK_COUNTS
+    );
+    let daily_rows: Vec<(NaiveDate, i64, i64, i64)> = with_pool!(db, pool => sqlx::query_as(&sql)
+    .bind(user_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await)
+    .map_err(AppError::db("查询每日趋势失败"))?;
+
+    // 尚未生成的重复任务计为待完成的任务
+    let pending = query_pending_occurrences(db, Some(user_id), start_date, end_date).await?;
+    let mut daily: BTreeMap<NaiveDate, (i64, i64, i64)> = daily_rows
+        .into_iter()
+        .map(|(date, total, completed, progress_points)| (date, (total, completed, progress_points)))
+        .collect();
+    for task in &pending {
+        daily.entry(task.task_date).or_default().0 += 1;
+    }
+
+    let daily_trend: Vec<DailyCompletion> = daily
+        .into_iter()
+        .map(|(date, (total_tasks, completed_tasks, progress_points))| DailyCompletion {
+            date,
+            total_tasks,
+            completed_tasks,
+            completion_rate: progress_rate(progress_points, total_tasks),
         })
         .collect();
+    let total_tasks = total_tasks + pending.len() as i64;
 
     Ok(Statistics {
         total_study_minutes,
         total_tasks,
         completed_tasks,
-        completion_rate,
+        completion_rate: progress_rate(progress_points, total_tasks),
         subject_distribution,
+        tag_distribution,
         daily_trend,
     })
 }
+
+/// 获取所有用户在时间范围内的活跃情况
+/// 与 get_stats 使用相同的统计口径，各项指标按用户分组后一次查询得到 (尚未生成的重复任务在内存中展开后按用户累加)
+pub async fn get_user_activity(
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    today: NaiveDate,
+) -> Result<Vec<UserActivity>, AppError> {
+    query_user_activity(&db::get_pool()?, start_date, end_date, today).await
+}
+
+/// 在指定连接池上查询所有用户的活跃情况
+pub(crate) async fn query_user_activity(
+    db: &DbPool,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    today: NaiveDate,
+) -> Result<Vec<UserActivity>, AppError> {
+    if start_date > end_date {
+        return Err(AppError::Validation("开始日期不能晚于结束日期".to_string()));
+    }
+    let (range_start, range_end) = day_bounds(start_date, end_date);
+
+    let sql = format!(
+        "SELECT u.id, u.username, u.display_name, u.role, u.deactivated_at,
+                l.last_login_at,
+                CAST(COALESCE(p.study_minutes, 0) AS SIGNED INTEGER) as study_minutes,
+                CAST(COALESCE(p.completed_pomodoros, 0) AS SIGNED INTEGER) as completed_pomodoros,
+                CAST(COALESCE(t.total_tasks, 0) AS SIGNED INTEGER) as total_tasks,
+                CAST(COALESCE(t.completed_tasks, 0) AS SIGNED INTEGER) as completed_tasks,
+                CAST(COALESCE(t.progress_points, 0) AS SIGNED INTEGER) as progress_points,
+                r.last_review_date,
+                CAST(CASE WHEN a.api_key IS NOT NULL AND a.api_key <> '' THEN 1 ELSE 0 END AS SIGNED INTEGER)
+                    as ai_configured
+         FROM users u
+         LEFT JOIN (SELECT username, MAX(attempted_at) as last_login_at FROM login_attempts
+                    WHERE success = TRUE GROUP BY username) l ON l.username = u.username
+         LEFT JOIN (SELECT user_id, {} as study_minutes,
+                           CAST(COUNT(*) AS SIGNED INTEGER) as completed_pomodoros
+                    FROM pomodoro_sessions WHERE {} GROUP BY user_id) p ON p.user_id = u.id
+         LEFT JOIN (SELECT user_id, {} FROM tasks WHERE task_date BETWEEN ? AND ?
+                    GROUP BY user_id) t ON t.user_id = u.id
+         LEFT JOIN (SELECT user_id, MAX(review_date) as last_review_date FROM daily_reviews
+                    GROUP BY user_id) r ON r.user_id = u.id
+         LEFT JOIN ai_configs a ON a.user_id = u.id
+         ORDER BY u.created_at DESC",
+        STUDY_MINUTES_SUM, COMPLETED_POMODORO_FILTER, TASK_COUNTS
+    );
+    let rows: Vec<UserActivityRow> = with_pool!(db, pool => sqlx::query_as(&sql)
+    .bind(range_start)
+    .bind(range_end)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await)
+    .map_err(AppError::db("查询用户活跃情况失败"))?;
+
+    // 尚未生成的重复任务计为待完成的任务
+    let mut pending: HashMap<i64, i64> = HashMap::new();
+    for task in query_pending_occurrences(db, None, start_date, end_date).await? {
+        *pending.entry(task.user_id).or_default() += 1;
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|row| UserActivityRow {
+            total_tasks: row.total_tasks + pending.get(&row.id).copied().unwrap_or(0),
+            ..row
+        })
+        .map(|row| row.into_activity(today))
+        .collect())
+}
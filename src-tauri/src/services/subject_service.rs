@@ -1,61 +1,44 @@
 // 科目服务
-use crate::db::get_pool;
-use crate::models::{Subject, CreateSubjectInput};
+use crate::error::AppError;
+use crate::models::{CreateSubjectInput, Subject, DEFAULT_SUBJECTS};
+use crate::repositories::SubjectRepository;
 
 /// 获取用户的所有科目
-pub async fn get_subjects(user_id: i64) -> Result<Vec<Subject>, String> {
-    let pool = get_pool();
-    
-    let subjects: Vec<Subject> = sqlx::query_as(
-        "SELECT id, user_id, name, color, is_default, created_at 
-         FROM subjects WHERE user_id = ? ORDER BY is_default DESC, name ASC"
-    )
-    .bind(user_id)
-    .fetch_all(pool)
-    .await
-    .map_err(|e| format!("查询科目失败: {}", e))?;
-
-    Ok(subjects)
+pub async fn get_subjects<R: SubjectRepository>(repo: &R, user_id: i64) -> Result<Vec<Subject>, AppError> {
+    repo.list_subjects(user_id).await
 }
 
 /// 创建科目
-pub async fn create_subject(user_id: i64, input: CreateSubjectInput) -> Result<Subject, String> {
-    let pool = get_pool();
+pub async fn create_subject<R: SubjectRepository>(
+    repo: &R,
+    user_id: i64,
+    input: CreateSubjectInput,
+) -> Result<Subject, AppError> {
     let color = input.color.unwrap_or_else(|| "#3B82F6".to_string());
-    
-    let result = sqlx::query(
-        "INSERT INTO subjects (user_id, name, color, is_default) VALUES (?, ?, ?, FALSE)"
-    )
-    .bind(user_id)
-    .bind(&input.name)
-    .bind(&color)
-    .execute(pool)
-    .await
-    .map_err(|e| format!("创建科目失败: {}", e))?;
-
-    let subject_id = result.last_insert_id() as i64;
-
-    let subject: Subject = sqlx::query_as(
-        "SELECT id, user_id, name, color, is_default, created_at FROM subjects WHERE id = ?"
-    )
-    .bind(subject_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| format!("查询科目失败: {}", e))?;
-
-    Ok(subject)
+
+    let subject_id = repo.create_subject(user_id, &input.name, &color, false).await?;
+
+    repo.find_subject(subject_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("科目不存在".to_string()))
 }
 
 /// 删除科目
-pub async fn delete_subject(user_id: i64, subject_id: i64) -> Result<(), String> {
-    let pool = get_pool();
-    
-    sqlx::query("DELETE FROM subjects WHERE id = ? AND user_id = ? AND is_default = FALSE")
-        .bind(subject_id)
-        .bind(user_id)
-        .execute(pool)
-        .await
-        .map_err(|e| format!("删除科目失败: {}", e))?;
+pub async fn delete_subject<R: SubjectRepository>(repo: &R, user_id: i64, subject_id: i64) -> Result<(), AppError> {
+    repo.delete_subject(user_id, subject_id).await
+}
+
+/// 为用户写入缺失的默认科目
+pub async fn seed_default_subjects<R: SubjectRepository>(repo: &R, user_id: i64) -> Result<(), AppError> {
+    let existing = repo.list_subjects(user_id).await?;
+
+    for (name, color) in DEFAULT_SUBJECTS {
+        if existing.iter().any(|s| s.name == name) {
+            continue;
+        }
+
+        repo.create_subject(user_id, name, color, true).await?;
+    }
 
     Ok(())
 }
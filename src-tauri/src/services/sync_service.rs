@@ -0,0 +1,1330 @@
+// 同步服务
+// 本地 SQLite 中的修改由触发器记录到 sync_changes，服务器可连接时先推送本地变更，再增量拉取服务器变更。
+// 记录通过 sync_id 对应，version 判断哪一端发生了修改；双方都修改时按 updated_at 后写者胜出
+// (时间相同时以服务器为准)，落败一方的数据保存在 sync_conflicts 中供用户查看。
+use crate::config::{self, DatabaseBackend, DatabaseConfig};
+use crate::db::{self, migrations, with_pool, DbPool, ExecResult};
+use crate::error::AppError;
+use crate::models::{ConflictResolution, SyncConflict, SyncReport, SyncStatusResponse};
+use crate::utils::password;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::Serialize;
+use sqlx::mysql::MySqlPoolOptions;
+use sqlx::query::Query;
+use sqlx::{ColumnIndex, Database, Decode, Encode, Row, Type};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+// 同一时间只允许一次同步
+static SYNC_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// 后台同步的最短间隔 (秒)
+const MIN_INTERVAL_SECS: u64 = 30;
+
+/// 参与同步的记录类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Entity {
+    Subject,
+    Series,
+    Task,
+    SeriesSkip,
+    Pomodoro,
+    Review,
+    ChecklistItem,
+    Tag,
+    TagLink,
+    StatusChange,
+}
+
+/// 同步顺序 (被引用的记录在前)
+const ENTITIES: [Entity; 10] = [
+    Entity::Subject,
+    Entity::Series,
+    Entity::Task,
+    Entity::SeriesSkip,
+    Entity::Pomodoro,
+    Entity::Review,
+    Entity::ChecklistItem,
+    Entity::Tag,
+    Entity::TagLink,
+    Entity::StatusChange,
+];
+
+/// 字段类型
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Int,
+    Bool,
+    Text,
+    Date,
+    Time,
+    DateTime,
+    /// 引用其他同步记录的 id，两端之间通过 sync_id 转换
+    Ref(Entity),
+    /// 所属的记录 (同 Ref，但对端不存在时不同步这条记录)
+    Owner(Entity),
+}
+
+/// 同步字段
+struct Field {
+    name: &'static str,
+    kind: Kind,
+}
+
+const fn field(name: &'static str, kind: Kind) -> Field {
+    Field { name, kind }
+}
+
+const SUBJECT_FIELDS: &[Field] = &[
+    field("name", Kind::Text),
+    field("color", Kind::Text),
+    field("is_default", Kind::Bool),
+    field("created_at", Kind::DateTime),
+];
+
+const SERIES_FIELDS: &[Field] = &[
+    field("subject_id", Kind::Ref(Entity::Subject)),
+    field("start_date", Kind::Date),
+    field("start_time", Kind::Time),
+    field("end_time", Kind::Time),
+    field("content", Kind::Text),
+    field("priority", Kind::Text),
+    field("alarm_enabled", Kind::Bool),
+    field("alarm_time", Kind::Time),
+    field("rrule", Kind::Text),
+    field("created_at", Kind::DateTime),
+];
+
+const TASK_FIELDS: &[Field] = &[
+    field("subject_id", Kind::Ref(Entity::Subject)),
+    field("task_date", Kind::Date),
+    field("start_time", Kind::Time),
+    field("end_time", Kind::Time),
+    field("content", Kind::Text),
+    field("status", Kind::Text),
+    field("status_reason", Kind::Text),
+    field("priority", Kind::Text),
+    field("progress", Kind::Int),
+    field("alarm_enabled", Kind::Bool),
+    field("alarm_time", Kind::Time),
+    field("series_id", Kind::Ref(Entity::Series)),
+    field("occurrence_date", Kind::Date),
+    field("carried_from_id", Kind::Ref(Entity::Task)),
+    field("created_at", Kind::DateTime),
+];
+
+const SERIES_SKIP_FIELDS: &[Field] = &[
+    field("series_id", Kind::Owner(Entity::Series)),
+    field("occurrence_date", Kind::Date),
+];
+
+const POMODORO_FIELDS: &[Field] = &[
+    field("subject_id", Kind::Ref(Entity::Subject)),
+    field("task_id", Kind::Ref(Entity::Task)),
+    field("start_time", Kind::DateTime),
+    field("end_time", Kind::DateTime),
+    field("duration_minutes", Kind::Int),
+    field("status", Kind::Text),
+];
+
+const REVIEW_FIELDS: &[Field] = &[
+    field("review_date", Kind::Date),
+    field("feelings", Kind::Text),
+    field("difficulties", Kind::Text),
+    field("ai_suggestions", Kind::Text),
+    field("created_at", Kind::DateTime),
+];
+
+const CHECKLIST_ITEM_FIELDS: &[Field] = &[
+    field("task_id", Kind::Owner(Entity::Task)),
+    field("position", Kind::Int),
+    field("content", Kind::Text),
+    field("completed", Kind::Bool),
+    field("created_at", Kind::DateTime),
+];
+
+const TAG_FIELDS: &[Field] = &[
+    field("name", Kind::Text),
+    field("color", Kind::Text),
+    field("created_at", Kind::DateTime),
+];
+
+const TAG_LINK_FIELDS: &[Field] = &[
+    field("task_id", Kind::Owner(Entity::Task)),
+    field("tag_id", Kind::Owner(Entity::Tag)),
+];
+
+const STATUS_CHANGE_FIELDS: &[Field] = &[
+    field("task_id", Kind::Owner(Entity::Task)),
+    field("from_status", Kind::Text),
+    field("to_status", Kind::Text),
+    field("reason", Kind::Text),
+    field("changed_at", Kind::DateTime),
+];
+
+impl Entity {
+    /// sync_changes / sync_tombstones 中使用的名称
+    fn name(self) -> &'static str {
+        match self {
+            Entity::Subject => "subject",
+            Entity::Series => "series",
+            Entity::Task => "task",
+            Entity::SeriesSkip => "series_skip",
+            Entity::Pomodoro => "pomodoro",
+            Entity::Review => "review",
+            Entity::ChecklistItem => "checklist_item",
+            Entity::Tag => "tag",
+            Entity::TagLink => "tag_link",
+            Entity::StatusChange => "status_change",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        ENTITIES.into_iter().find(|e| e.name() == name)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Entity::Subject => "科目",
+            Entity::Series => "重复任务",
+            Entity::Task => "任务",
+            Entity::SeriesSkip => "删除的重复",
+            Entity::Pomodoro => "番茄钟",
+            Entity::Review => "复盘",
+            Entity::ChecklistItem => "清单项",
+            Entity::Tag => "标签",
+            Entity::TagLink => "任务标签",
+            Entity::StatusChange => "状态变更记录",
+        }
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            Entity::Subject => "subjects",
+            Entity::Series => "task_series",
+            Entity::Task => "tasks",
+            Entity::SeriesSkip => "task_series_skips",
+            Entity::Pomodoro => "pomodoro_sessions",
+            Entity::Review => "daily_reviews",
+            Entity::ChecklistItem => "task_checklist_items",
+            Entity::Tag => "task_tags",
+            Entity::TagLink => "task_tag_links",
+            Entity::StatusChange => "task_status_history",
+        }
+    }
+
+    fn fields(self) -> &'static [Field] {
+        match self {
+            Entity::Subject => SUBJECT_FIELDS,
+            Entity::Series => SERIES_FIELDS,
+            Entity::Task => TASK_FIELDS,
+            Entity::SeriesSkip => SERIES_SKIP_FIELDS,
+            Entity::Pomodoro => POMODORO_FIELDS,
+            Entity::Review => REVIEW_FIELDS,
+            Entity::ChecklistItem => CHECKLIST_ITEM_FIELDS,
+            Entity::Tag => TAG_FIELDS,
+            Entity::TagLink => TAG_LINK_FIELDS,
+            Entity::StatusChange => STATUS_CHANGE_FIELDS,
+        }
+    }
+
+    /// 业务唯一键 (两端分别创建的同一条记录，如默认科目、同一天的复盘、各自生成的同一次重复、同名标签)
+    fn natural_key(self) -> &'static [&'static str] {
+        match self {
+            Entity::Subject | Entity::Tag => &["name"],
+            Entity::Task | Entity::SeriesSkip => &["series_id", "occurrence_date"],
+            Entity::Review => &["review_date"],
+            Entity::TagLink => &["task_id", "tag_id"],
+            _ => &[],
+        }
+    }
+
+    fn rank(self) -> usize {
+        ENTITIES.iter().position(|e| *e == self).unwrap_or(usize::MAX)
+    }
+
+    fn select_sql(self, filter: &str) -> String {
+        let columns: Vec<&str> = self.fields().iter().map(|f| f.name).collect();
+        format!(
+            "SELECT id, sync_id, version, synced_version, updated_at, {} FROM {} WHERE {}",
+            columns.join(", "),
+            self.table(),
+            filter
+        )
+    }
+}
+
+/// 字段值
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+enum Value {
+    Null,
+    Int(i64),
+    Bool(bool),
+    Text(String),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    DateTime(DateTime<Utc>),
+}
+
+/// 一条同步记录 (values 与 Entity::fields 一一对应)
+#[derive(Debug, Clone)]
+struct Record {
+    id: i64,
+    sync_id: String,
+    version: i64,
+    synced_version: i64,
+    updated_at: DateTime<Utc>,
+    values: Vec<Value>,
+}
+
+/// 待推送的本地变更
+#[derive(Debug, sqlx::FromRow)]
+struct PendingChange {
+    id: i64,
+    entity: String,
+    record_id: i64,
+    sync_id: Option<String>,
+    operation: String,
+    base_version: i64,
+    changed_at: DateTime<Utc>,
+}
+
+/// 一次同步过程中的上下文
+struct SyncContext<'a> {
+    local: &'a DbPool,
+    remote: &'a DbPool,
+    local_user: i64,
+    remote_user: i64,
+    report: SyncReport,
+}
+
+/// 冲突处理策略：updated_at 较新的一方胜出，时间相同时以服务器为准
+pub fn resolve_conflict(
+    local_updated_at: DateTime<Utc>,
+    remote_updated_at: DateTime<Utc>,
+) -> ConflictResolution {
+    if local_updated_at > remote_updated_at {
+        ConflictResolution::LocalWins
+    } else {
+        ConflictResolution::RemoteWins
+    }
+}
+
+/// 冲突合并后的版本号 (高于双方已有版本)
+pub fn merged_version(local_version: i64, remote_version: i64) -> i64 {
+    local_version.max(remote_version) + 1
+}
+
+/// 绑定字段值
+fn bind_value<'q, DB>(
+    query: Query<'q, DB, <DB as Database>::Arguments<'q>>,
+    value: &Value,
+) -> Query<'q, DB, <DB as Database>::Arguments<'q>>
+where
+    DB: Database,
+    Option<String>: Encode<'q, DB> + Type<DB>,
+    i64: Encode<'q, DB> + Type<DB>,
+    bool: Encode<'q, DB> + Type<DB>,
+    String: Encode<'q, DB> + Type<DB>,
+    NaiveDate: Encode<'q, DB> + Type<DB>,
+    NaiveTime: Encode<'q, DB> + Type<DB>,
+    DateTime<Utc>: Encode<'q, DB> + Type<DB>,
+{
+    match value {
+        Value::Null => query.bind(None::<String>),
+        Value::Int(v) => query.bind(*v),
+        Value::Bool(v) => query.bind(*v),
+        Value::Text(v) => query.bind(v.clone()),
+        Value::Date(v) => query.bind(*v),
+        Value::Time(v) => query.bind(*v),
+        Value::DateTime(v) => query.bind(*v),
+    }
+}
+
+/// 读取一行同步记录
+fn decode_record<'r, R>(row: &'r R, entity: Entity) -> Result<Record, sqlx::Error>
+where
+    R: Row,
+    &'static str: ColumnIndex<R>,
+    i64: Decode<'r, R::Database> + Type<R::Database>,
+    bool: Decode<'r, R::Database> + Type<R::Database>,
+    String: Decode<'r, R::Database> + Type<R::Database>,
+    NaiveDate: Decode<'r, R::Database> + Type<R::Database>,
+    NaiveTime: Decode<'r, R::Database> + Type<R::Database>,
+    DateTime<Utc>: Decode<'r, R::Database> + Type<R::Database>,
+{
+    let mut values = Vec::with_capacity(entity.fields().len());
+    for field in entity.fields() {
+        let name = field.name;
+        let value = match field.kind {
+            Kind::Int | Kind::Ref(_) | Kind::Owner(_) => row.try_get::<Option<i64>, _>(name)?.map(Value::Int),
+            Kind::Bool => row.try_get::<Option<bool>, _>(name)?.map(Value::Bool),
+            Kind::Text => row.try_get::<Option<String>, _>(name)?.map(Value::Text),
+            Kind::Date => row.try_get::<Option<NaiveDate>, _>(name)?.map(Value::Date),
+            Kind::Time => row.try_get::<Option<NaiveTime>, _>(name)?.map(Value::Time),
+            Kind::DateTime => row.try_get::<Option<DateTime<Utc>>, _>(name)?.map(Value::DateTime),
+        };
+        values.push(value.unwrap_or(Value::Null));
+    }
+
+    Ok(Record {
+        id: row.try_get("id")?,
+        sync_id: row.try_get("sync_id")?,
+        version: row.try_get("version")?,
+        synced_version: row.try_get("synced_version")?,
+        updated_at: row.try_get("updated_at")?,
+        values,
+    })
+}
+
+/// 记录转换为 JSON (用于保存冲突数据)
+fn to_json(entity: Entity, record: &Record) -> String {
+    let mut map = serde_json::Map::new();
+    for (field, value) in entity.fields().iter().zip(&record.values) {
+        map.insert(field.name.to_string(), serde_json::to_value(value).unwrap_or_default());
+    }
+    map.insert("version".to_string(), record.version.into());
+    map.insert("updated_at".to_string(), record.updated_at.to_rfc3339().into());
+    serde_json::Value::Object(map).to_string()
+}
+
+/// 两条记录内容是否相同 (忽略创建时间)
+fn same_content(entity: Entity, a: &[Value], b: &[Value]) -> bool {
+    entity
+        .fields()
+        .iter()
+        .zip(a.iter().zip(b))
+        .all(|(field, (x, y))| field.name == "created_at" || x == y)
+}
+
+/// 按条件查询同步记录
+async fn fetch(
+    db: &DbPool,
+    entity: Entity,
+    filter: &str,
+    params: &[Value],
+) -> Result<Vec<Record>, AppError> {
+    let sql = entity.select_sql(filter);
+
+    with_pool!(db, pool => {
+        let mut query = sqlx::query(&sql);
+        for v in params {
+            query = bind_value(query, v);
+        }
+        match query.fetch_all(pool).await {
+            Ok(rows) => rows.iter().map(|row| decode_record(row, entity)).collect(),
+            Err(e) => Err(e),
+        }
+    })
+    .map_err(AppError::db(format!("查询{}失败", entity.label())))
+}
+
+/// 查询对端的同一条记录 (先按 sync_id，再按业务唯一键)
+async fn find(db: &DbPool, entity: Entity, user_id: i64, record: &Record) -> Result<Option<Record>, AppError> {
+    let by_sync_id = fetch(
+        db,
+        entity,
+        "user_id = ? AND sync_id = ?",
+        &[Value::Int(user_id), Value::Text(record.sync_id.clone())],
+    )
+    .await?;
+    if let Some(found) = by_sync_id.into_iter().next() {
+        return Ok(Some(found));
+    }
+
+    let key = entity.natural_key();
+    if key.is_empty() {
+        return Ok(None);
+    }
+
+    let mut filter = "user_id = ?".to_string();
+    let mut params = vec![Value::Int(user_id)];
+    for (field, value) in entity.fields().iter().zip(&record.values) {
+        if key.contains(&field.name) {
+            filter.push_str(&format!(" AND {} = ?", field.name));
+            params.push(value.clone());
+        }
+    }
+    // 业务唯一键不完整 (如不属于重复任务的任务) 时不按唯一键匹配
+    if params.contains(&Value::Null) {
+        return Ok(None);
+    }
+
+    let by_key = fetch(db, entity, &filter, &params).await?;
+    Ok(by_key.into_iter().next())
+}
+
+/// 写入新记录，返回新记录 ID
+async fn insert_record(db: &DbPool, entity: Entity, user_id: i64, record: &Record) -> Result<i64, AppError> {
+    let columns: Vec<&str> = entity.fields().iter().map(|f| f.name).collect();
+    let sql = format!(
+        "INSERT INTO {} (user_id, sync_id, version, synced_version, updated_at, {}) VALUES (?, ?, ?, ?, ?, {})",
+        entity.table(),
+        columns.join(", "),
+        vec!["?"; columns.len()].join(", ")
+    );
+
+    with_pool!(db, pool => {
+        let mut query = sqlx::query(&sql)
+            .bind(user_id)
+            .bind(record.sync_id.clone())
+            .bind(record.version)
+            .bind(record.synced_version)
+            .bind(record.updated_at);
+        for v in &record.values {
+            query = bind_value(query, v);
+        }
+        query.execute(pool).await.map(ExecResult::from)
+    })
+    .map(|r| r.insert_id)
+    .map_err(AppError::db(format!("写入{}失败", entity.label())))
+}
+
+/// 覆盖已有记录
+async fn update_record(db: &DbPool, entity: Entity, id: i64, record: &Record) -> Result<(), AppError> {
+    let assignments: Vec<String> = entity.fields().iter().map(|f| format!("{} = ?", f.name)).collect();
+    let sql = format!(
+        "UPDATE {} SET sync_id = ?, version = ?, synced_version = ?, updated_at = ?, {} WHERE id = ?",
+        entity.table(),
+        assignments.join(", ")
+    );
+
+    with_pool!(db, pool => {
+        let mut query = sqlx::query(&sql)
+            .bind(record.sync_id.clone())
+            .bind(record.version)
+            .bind(record.synced_version)
+            .bind(record.updated_at);
+        for v in &record.values {
+            query = bind_value(query, v);
+        }
+        query.bind(id).execute(pool).await.map(ExecResult::from)
+    })
+    .map_err(AppError::db(format!("更新{}失败", entity.label())))?;
+
+    Ok(())
+}
+
+/// 删除记录 (先将 synced_version 标记为 -1，本地触发器不会把这次删除当作本地修改)
+async fn delete_record(db: &DbPool, entity: Entity, id: i64) -> Result<(), AppError> {
+    let mark_sql = format!("UPDATE {} SET synced_version = -1 WHERE id = ?", entity.table());
+    let delete_sql = format!("DELETE FROM {} WHERE id = ?", entity.table());
+
+    with_pool!(db, pool => {
+        match sqlx::query(&mark_sql).bind(id).execute(pool).await {
+            Ok(_) => sqlx::query(&delete_sql).bind(id).execute(pool).await.map(ExecResult::from),
+            Err(e) => Err(e),
+        }
+    })
+    .map_err(AppError::db(format!("删除{}失败", entity.label())))?;
+
+    Ok(())
+}
+
+/// 标记本地记录已同步到指定版本
+async fn mark_synced(db: &DbPool, entity: Entity, id: i64, version: i64) -> Result<(), AppError> {
+    let sql = format!(
+        "UPDATE {} SET synced_version = ? WHERE id = ? AND synced_version <> ?",
+        entity.table()
+    );
+
+    with_pool!(db, pool => sqlx::query(&sql)
+        .bind(version)
+        .bind(id)
+        .bind(version)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("更新同步版本失败"))?;
+
+    Ok(())
+}
+
+/// 将引用字段的 id 从一端转换到另一端 (对端不存在时置空)
+async fn translate(from: &DbPool, to: &DbPool, entity: Entity, values: &[Value]) -> Result<Vec<Value>, AppError> {
+    let mut translated = Vec::with_capacity(values.len());
+
+    for (field, value) in entity.fields().iter().zip(values) {
+        let (Kind::Ref(target) | Kind::Owner(target), Value::Int(id)) = (field.kind, value) else {
+            translated.push(value.clone());
+            continue;
+        };
+
+        let sync_id_sql = format!("SELECT sync_id FROM {} WHERE id = ?", target.table());
+        let sync_id: Option<(String,)> = with_pool!(from, pool => sqlx::query_as(&sync_id_sql)
+            .bind(*id)
+            .fetch_optional(pool)
+            .await)
+            .map_err(AppError::db(format!("查询{}失败", target.label())))?;
+
+        let mapped: Option<(i64,)> = match sync_id {
+            Some((sync_id,)) => {
+                let id_sql = format!("SELECT id FROM {} WHERE sync_id = ?", target.table());
+                with_pool!(to, pool => sqlx::query_as(&id_sql)
+                    .bind(sync_id)
+                    .fetch_optional(pool)
+                    .await)
+                    .map_err(AppError::db(format!("查询{}失败", target.label())))?
+            }
+            None => None,
+        };
+
+        translated.push(mapped.map(|(id,)| Value::Int(id)).unwrap_or(Value::Null));
+    }
+
+    Ok(translated)
+}
+
+/// 转换后的记录是否缺少所属的记录 (所属记录在对端已被删除或尚未同步)
+fn orphaned(entity: Entity, values: &[Value]) -> bool {
+    entity
+        .fields()
+        .iter()
+        .zip(values)
+        .any(|(field, value)| matches!(field.kind, Kind::Owner(_)) && *value == Value::Null)
+}
+
+/// 本地记录是否有尚未推送的修改
+async fn has_pending_change(db: &DbPool, entity: Entity, record_id: i64) -> Result<bool, AppError> {
+    let (count,): (i64,) = with_pool!(db, pool => sqlx::query_as(
+        "SELECT COUNT(*) FROM sync_changes WHERE entity = ? AND record_id = ?"
+    )
+    .bind(entity.name())
+    .bind(record_id)
+    .fetch_one(pool)
+    .await)
+    .map_err(AppError::db("查询待同步变更失败"))?;
+
+    Ok(count > 0)
+}
+
+/// 保存冲突记录
+async fn record_conflict(
+    ctx: &mut SyncContext<'_>,
+    entity: Entity,
+    sync_id: &str,
+    local: Option<&Record>,
+    remote: Option<&Record>,
+    resolution: ConflictResolution,
+) -> Result<(), AppError> {
+    with_pool!(ctx.local, pool => sqlx::query(
+        "INSERT INTO sync_conflicts (user_id, entity, sync_id, local_data, remote_data, resolution, detected_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(ctx.local_user)
+    .bind(entity.name())
+    .bind(sync_id)
+    .bind(local.map(|r| to_json(entity, r)))
+    .bind(remote.map(|r| to_json(entity, r)))
+    .bind(resolution.to_string())
+    .bind(Utc::now())
+    .execute(pool)
+    .await
+    .map(ExecResult::from))
+    .map_err(AppError::db("保存同步冲突失败"))?;
+
+    ctx.report.conflicts += 1;
+    Ok(())
+}
+
+/// 用服务器记录覆盖 (或新建) 本地记录
+async fn apply_remote(
+    ctx: &mut SyncContext<'_>,
+    entity: Entity,
+    local_id: Option<i64>,
+    remote: &Record,
+) -> Result<(), AppError> {
+    let values = translate(ctx.remote, ctx.local, entity, &remote.values).await?;
+    apply_translated(ctx, entity, local_id, remote, values).await
+}
+
+/// 用已转换为本地 ID 的服务器记录覆盖 (或新建) 本地记录
+async fn apply_translated(
+    ctx: &mut SyncContext<'_>,
+    entity: Entity,
+    local_id: Option<i64>,
+    remote: &Record,
+    values: Vec<Value>,
+) -> Result<(), AppError> {
+    if orphaned(entity, &values) {
+        return Ok(());
+    }
+
+    let record = Record {
+        values,
+        synced_version: remote.version,
+        ..remote.clone()
+    };
+
+    match local_id {
+        Some(id) => update_record(ctx.local, entity, id, &record).await?,
+        None => {
+            insert_record(ctx.local, entity, ctx.local_user, &record).await?;
+        }
+    }
+
+    ctx.report.pulled += 1;
+    Ok(())
+}
+
+/// 推送一条本地新增或修改的记录
+async fn push_upsert(ctx: &mut SyncContext<'_>, entity: Entity, record_id: i64) -> Result<(), AppError> {
+    // 记录已被删除时由对应的删除变更处理
+    let Some(local) = fetch(ctx.local, entity, "id = ?", &[Value::Int(record_id)]).await?.pop() else {
+        return Ok(());
+    };
+
+    let outgoing = Record {
+        values: translate(ctx.local, ctx.remote, entity, &local.values).await?,
+        synced_version: local.version,
+        ..local.clone()
+    };
+    // 所属的记录已在服务器上删除，拉取删除后本地记录随之删除
+    if orphaned(entity, &outgoing.values) {
+        return Ok(());
+    }
+
+    match find(ctx.remote, entity, ctx.remote_user, &outgoing).await? {
+        // 已同步过的记录在服务器上被删除
+        None if local.synced_version > 0 => {
+            let (deleted_at,): (Option<DateTime<Utc>>,) = with_pool!(ctx.remote, pool => sqlx::query_as(
+                "SELECT MAX(deleted_at) FROM sync_tombstones WHERE sync_id = ?"
+            )
+            .bind(&local.sync_id)
+            .fetch_one(pool)
+            .await)
+            .map_err(AppError::db("查询删除记录失败"))?;
+
+            let resolution = deleted_at
+                .map(|t| resolve_conflict(local.updated_at, t))
+                .unwrap_or(ConflictResolution::LocalWins);
+
+            if resolution == ConflictResolution::LocalWins {
+                with_pool!(ctx.remote, pool => sqlx::query("DELETE FROM sync_tombstones WHERE sync_id = ?")
+                    .bind(&local.sync_id)
+                    .execute(pool)
+                    .await
+                    .map(ExecResult::from))
+                    .map_err(AppError::db("清理删除记录失败"))?;
+
+                insert_record(ctx.remote, entity, ctx.remote_user, &outgoing).await?;
+                mark_synced(ctx.local, entity, local.id, local.version).await?;
+                ctx.report.pushed += 1;
+            } else {
+                record_conflict(ctx, entity, &local.sync_id, Some(&local), None, resolution).await?;
+                delete_record(ctx.local, entity, local.id).await?;
+            }
+        }
+        // 服务器上没有的新记录
+        None => {
+            insert_record(ctx.remote, entity, ctx.remote_user, &outgoing).await?;
+            mark_synced(ctx.local, entity, local.id, local.version).await?;
+            ctx.report.pushed += 1;
+        }
+        // 上次同步后服务器未修改，直接覆盖
+        Some(remote) if remote.sync_id == local.sync_id && remote.version == local.synced_version => {
+            update_record(ctx.remote, entity, remote.id, &outgoing).await?;
+            mark_synced(ctx.local, entity, local.id, local.version).await?;
+            ctx.report.pushed += 1;
+        }
+        // 两端内容一致 (如各自创建的默认科目)，沿用服务器的 sync_id 与版本
+        Some(remote) if same_content(entity, &outgoing.values, &remote.values) => {
+            apply_remote(ctx, entity, Some(local.id), &remote).await?;
+        }
+        // 两端都有修改
+        Some(remote) => match resolve_conflict(local.updated_at, remote.updated_at) {
+            ConflictResolution::LocalWins => {
+                let version = merged_version(local.version, remote.version);
+                let merged = Record {
+                    sync_id: remote.sync_id.clone(),
+                    version,
+                    synced_version: version,
+                    ..outgoing
+                };
+                update_record(ctx.remote, entity, remote.id, &merged).await?;
+
+                let local_merged = Record {
+                    values: local.values.clone(),
+                    ..merged
+                };
+                update_record(ctx.local, entity, local.id, &local_merged).await?;
+
+                record_conflict(
+                    ctx,
+                    entity,
+                    &remote.sync_id,
+                    Some(&local),
+                    Some(&remote),
+                    ConflictResolution::LocalWins,
+                )
+                .await?;
+                ctx.report.pushed += 1;
+            }
+            ConflictResolution::RemoteWins => {
+                apply_remote(ctx, entity, Some(local.id), &remote).await?;
+                record_conflict(
+                    ctx,
+                    entity,
+                    &remote.sync_id,
+                    Some(&local),
+                    Some(&remote),
+                    ConflictResolution::RemoteWins,
+                )
+                .await?;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// 推送一条本地删除
+async fn push_delete(ctx: &mut SyncContext<'_>, entity: Entity, change: &PendingChange) -> Result<(), AppError> {
+    let Some(sync_id) = &change.sync_id else {
+        return Ok(());
+    };
+
+    let Some(remote) = fetch(
+        ctx.remote,
+        entity,
+        "user_id = ? AND sync_id = ?",
+        &[Value::Int(ctx.remote_user), Value::Text(sync_id.clone())],
+    )
+    .await?
+    .pop() else {
+        return Ok(());
+    };
+
+    // 上次同步后服务器未修改，直接删除
+    if remote.version <= change.base_version {
+        delete_record(ctx.remote, entity, remote.id).await?;
+        ctx.report.pushed += 1;
+        return Ok(());
+    }
+
+    let resolution = resolve_conflict(change.changed_at, remote.updated_at);
+    record_conflict(ctx, entity, sync_id, None, Some(&remote), resolution).await?;
+
+    match resolution {
+        ConflictResolution::LocalWins => {
+            delete_record(ctx.remote, entity, remote.id).await?;
+            ctx.report.pushed += 1;
+        }
+        // 服务器上的修改较新，恢复本地记录
+        ConflictResolution::RemoteWins => apply_remote(ctx, entity, None, &remote).await?,
+    }
+
+    Ok(())
+}
+
+/// 推送本地变更
+async fn push(ctx: &mut SyncContext<'_>) -> Result<(), AppError> {
+    let mut changes: Vec<PendingChange> = with_pool!(ctx.local, pool => sqlx::query_as(
+        "SELECT id, entity, record_id, sync_id, operation, base_version, changed_at
+         FROM sync_changes WHERE user_id = ? ORDER BY id ASC"
+    )
+    .bind(ctx.local_user)
+    .fetch_all(pool)
+    .await)
+    .map_err(AppError::db("查询待同步变更失败"))?;
+
+    changes.sort_by_key(|c| Entity::from_name(&c.entity).map(Entity::rank).unwrap_or(usize::MAX));
+
+    for change in &changes {
+        if let Some(entity) = Entity::from_name(&change.entity) {
+            match change.operation.as_str() {
+                "delete" => push_delete(ctx, entity, change).await?,
+                _ => push_upsert(ctx, entity, change.record_id).await?,
+            }
+        }
+
+        // 推送期间再次修改的记录会生成新的变更，这里只删除已处理的这一条
+        with_pool!(ctx.local, pool => sqlx::query("DELETE FROM sync_changes WHERE id = ?")
+            .bind(change.id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("清理待同步变更失败"))?;
+    }
+
+    Ok(())
+}
+
+/// 拉取服务器上 since 之后的变更
+async fn pull(ctx: &mut SyncContext<'_>, since: DateTime<Utc>) -> Result<(), AppError> {
+    for entity in ENTITIES {
+        let changed = fetch(
+            ctx.remote,
+            entity,
+            // 按 ID 顺序拉取，任务之间的引用 (如顺延来源) 通常先于引用它的任务写入
+            "user_id = ? AND synced_at >= ? ORDER BY id",
+            &[Value::Int(ctx.remote_user), Value::DateTime(since)],
+        )
+        .await?;
+
+        for remote in changed {
+            // 业务唯一键可能包含引用字段，先转换为本地 ID 再查找
+            let incoming = Record {
+                values: translate(ctx.remote, ctx.local, entity, &remote.values).await?,
+                ..remote.clone()
+            };
+            match find(ctx.local, entity, ctx.local_user, &incoming).await? {
+                None => apply_translated(ctx, entity, None, &remote, incoming.values).await?,
+                Some(local) if local.sync_id == remote.sync_id && local.synced_version >= remote.version => {}
+                // 本地有未推送的修改，留到下次推送时处理
+                Some(local) if has_pending_change(ctx.local, entity, local.id).await? => {}
+                Some(local) => apply_translated(ctx, entity, Some(local.id), &remote, incoming.values).await?,
+            }
+        }
+    }
+
+    let tombstones: Vec<(String, String)> = with_pool!(ctx.remote, pool => sqlx::query_as(
+        "SELECT entity, sync_id FROM sync_tombstones WHERE user_id = ? AND deleted_at >= ? ORDER BY id ASC"
+    )
+    .bind(ctx.remote_user)
+    .bind(since)
+    .fetch_all(pool)
+    .await)
+    .map_err(AppError::db("查询删除记录失败"))?;
+
+    for (entity, sync_id) in tombstones {
+        let Some(entity) = Entity::from_name(&entity) else {
+            continue;
+        };
+
+        let local = fetch(
+            ctx.local,
+            entity,
+            "user_id = ? AND sync_id = ?",
+            &[Value::Int(ctx.local_user), Value::Text(sync_id)],
+        )
+        .await?
+        .pop();
+
+        if let Some(local) = local {
+            if !has_pending_change(ctx.local, entity, local.id).await? {
+                delete_record(ctx.local, entity, local.id).await?;
+                ctx.report.pulled += 1;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 连接同步服务器并确保其表结构为最新版本
+async fn connect_server(server: &DatabaseConfig) -> Result<DbPool, AppError> {
+    let pool = MySqlPoolOptions::new()
+        .max_connections(2)
+        .acquire_timeout(Duration::from_secs(server.connect_timeout_secs))
+        .connect_with(db::mysql_options(server))
+        .await
+        .map_err(|e| AppError::Sync {
+            message: "连接同步服务器失败".to_string(),
+            details: e.to_string(),
+        })?;
+
+    let pool = DbPool::MySql(pool);
+    migrations::run(&pool)
+        .await
+        .map_err(|e| AppError::Sync {
+            message: "同步服务器迁移失败".to_string(),
+            details: e.to_string(),
+        })?;
+
+    Ok(pool)
+}
+
+/// 查询同步进度 (上次拉取时间, 上次同步时间, 上次错误)
+async fn load_state(
+    local: &DbPool,
+    user_id: i64,
+) -> Result<Option<(Option<DateTime<Utc>>, Option<DateTime<Utc>>, Option<String>)>, AppError> {
+    with_pool!(local, pool => sqlx::query_as(
+        "SELECT last_pulled_at, last_sync_at, last_error FROM sync_state WHERE user_id = ?"
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await)
+    .map_err(AppError::db("查询同步状态失败"))
+}
+
+/// 保存同步进度，成功时更新拉取与同步时间，失败时只记录错误
+async fn save_state(
+    local: &DbPool,
+    user_id: i64,
+    pulled_at: Option<DateTime<Utc>>,
+    error: Option<&str>,
+) -> Result<(), AppError> {
+    let updated = match pulled_at {
+        Some(pulled_at) => with_pool!(local, pool => sqlx::query(
+            "UPDATE sync_state SET last_pulled_at = ?, last_sync_at = ?, last_error = NULL WHERE user_id = ?"
+        )
+        .bind(pulled_at)
+        .bind(Utc::now())
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map(ExecResult::from)),
+        None => with_pool!(local, pool => sqlx::query("UPDATE sync_state SET last_error = ? WHERE user_id = ?")
+            .bind(error)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map(ExecResult::from)),
+    }
+    .map_err(AppError::db("保存同步状态失败"))?;
+
+    if updated.rows_affected == 0 {
+        let last_sync_at = pulled_at.map(|_| Utc::now());
+        with_pool!(local, pool => sqlx::query(
+            "INSERT INTO sync_state (user_id, last_pulled_at, last_sync_at, last_error) VALUES (?, ?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(pulled_at)
+        .bind(last_sync_at)
+        .bind(error)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("保存同步状态失败"))?;
+    }
+
+    Ok(())
+}
+
+/// 同步令牌的格式为 "<id>.<secret>"，服务器只保存 secret 的哈希
+fn parse_token(token: &str) -> Option<(i64, &str)> {
+    let (id, secret) = token.split_once('.')?;
+    Some((id.parse().ok()?, secret))
+}
+
+/// 在阻塞线程中校验密码或令牌 (argon2 计算较慢，避免占用异步运行时)
+async fn verify_secret(secret: &str, hash: &str) -> bool {
+    let (secret, hash) = (secret.to_string(), hash.to_string());
+    tokio::task::spawn_blocking(move || password::verify_password(&secret, &hash))
+        .await
+        .unwrap_or(false)
+}
+
+/// 查询本地用户的用户名
+async fn local_username(local: &DbPool, user_id: i64) -> Result<String, AppError> {
+    let (username,): (String,) = with_pool!(local, pool => sqlx::query_as("SELECT username FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await)
+        .map_err(AppError::db("查询用户失败"))?;
+    Ok(username)
+}
+
+/// 查询本机保存的同步令牌
+async fn load_token(local: &DbPool, user_id: i64) -> Result<Option<String>, AppError> {
+    let token: Option<(Option<String>,)> = with_pool!(local, pool => sqlx::query_as(
+        "SELECT server_token FROM sync_state WHERE user_id = ?"
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await)
+    .map_err(AppError::db("查询同步状态失败"))?;
+    Ok(token.and_then(|(token,)| token))
+}
+
+/// 保存 (或清除) 本机的同步令牌
+async fn save_token(local: &DbPool, user_id: i64, token: Option<&str>) -> Result<(), AppError> {
+    let updated = with_pool!(local, pool => sqlx::query("UPDATE sync_state SET server_token = ? WHERE user_id = ?")
+        .bind(token)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("保存同步令牌失败"))?;
+
+    if updated.rows_affected == 0 && token.is_some() {
+        with_pool!(local, pool => sqlx::query("INSERT INTO sync_state (user_id, server_token) VALUES (?, ?)")
+            .bind(user_id)
+            .bind(token)
+            .execute(pool)
+            .await
+            .map(ExecResult::from))
+            .map_err(AppError::db("保存同步令牌失败"))?;
+    }
+
+    Ok(())
+}
+
+/// 用服务器上同名账户的密码换取同步令牌并保存在本机
+pub(crate) async fn issue_token(
+    local: &DbPool,
+    remote: &DbPool,
+    user_id: i64,
+    server_password: &str,
+) -> Result<(), AppError> {
+    let username = local_username(local, user_id).await?;
+    let remote_user: Option<(i64, String)> = with_pool!(remote, pool => sqlx::query_as(
+        "SELECT id, password FROM users WHERE username = ? AND deactivated_at IS NULL"
+    )
+    .bind(&username)
+    .fetch_optional(pool)
+    .await)
+    .map_err(AppError::db("查询服务器用户失败"))?;
+
+    let remote_user = match remote_user {
+        Some((id, hash)) if verify_secret(server_password, &hash).await => id,
+        _ => return Err(AppError::Unauthorized("同步服务器上的账户或密码错误".to_string())),
+    };
+
+    let secret = Uuid::new_v4().simple().to_string();
+    let hash = password::hash_password(&secret)?;
+    let inserted = with_pool!(remote, pool => sqlx::query("INSERT INTO sync_tokens (user_id, token_hash) VALUES (?, ?)")
+        .bind(remote_user)
+        .bind(hash)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("创建同步令牌失败"))?;
+
+    save_token(local, user_id, Some(&format!("{}.{}", inserted.insert_id, secret))).await
+}
+
+/// 凭本机的同步令牌确认服务器上对应的用户 (两端的用户通过用户名对应)
+async fn authenticate(local: &DbPool, remote: &DbPool, user_id: i64) -> Result<i64, AppError> {
+    let username = local_username(local, user_id).await?;
+    let token = load_token(local, user_id).await?;
+    let Some((token_id, secret)) = token.as_deref().and_then(parse_token) else {
+        return Err(AppError::Unauthorized("尚未验证同步服务器账户，请先输入服务器账户密码".to_string()));
+    };
+
+    let found: Option<(i64, String)> = with_pool!(remote, pool => sqlx::query_as(
+        "SELECT u.id, t.token_hash FROM sync_tokens t JOIN users u ON u.id = t.user_id
+         WHERE t.id = ? AND u.username = ? AND u.deactivated_at IS NULL"
+    )
+    .bind(token_id)
+    .bind(&username)
+    .fetch_optional(pool)
+    .await)
+    .map_err(AppError::db("查询同步令牌失败"))?;
+
+    match found {
+        Some((remote_user, hash)) if verify_secret(secret, &hash).await => {
+            with_pool!(remote, pool => sqlx::query("UPDATE sync_tokens SET last_used_at = ? WHERE id = ?")
+                .bind(Utc::now())
+                .bind(token_id)
+                .execute(pool)
+                .await
+                .map(ExecResult::from))
+                .map_err(AppError::db("更新同步令牌失败"))?;
+            Ok(remote_user)
+        }
+        _ => Err(AppError::Unauthorized("同步令牌无效或已被撤销，请重新输入服务器账户密码".to_string())),
+    }
+}
+
+/// 执行一次推送与拉取
+pub(crate) async fn run_sync(local: &DbPool, remote: &DbPool, user_id: i64) -> Result<SyncReport, AppError> {
+    let remote_user = authenticate(local, remote, user_id).await?;
+
+    // 以服务器时间作为下次增量拉取的起点，避免客户端时钟偏差
+    let (server_now,): (DateTime<Utc>,) = with_pool!(remote, pool => sqlx::query_as("SELECT CURRENT_TIMESTAMP")
+        .fetch_one(pool)
+        .await)
+        .map_err(AppError::db("查询服务器时间失败"))?;
+
+    let since = load_state(local, user_id)
+        .await?
+        .and_then(|(pulled_at, _, _)| pulled_at)
+        .unwrap_or(DateTime::UNIX_EPOCH);
+
+    let mut ctx = SyncContext {
+        local,
+        remote,
+        local_user: user_id,
+        remote_user,
+        report: SyncReport::default(),
+    };
+
+    push(&mut ctx).await?;
+    pull(&mut ctx, since).await?;
+
+    save_state(local, user_id, Some(server_now), None).await?;
+
+    ctx.report.finished_at = Some(Utc::now());
+    Ok(ctx.report)
+}
+
+/// 当前是否可以同步 (已启用同步且使用本地 SQLite 存储)
+fn sync_available(config: &config::AppConfig) -> bool {
+    config.sync.enabled
+        && db::get_pool().is_ok_and(|pool| pool.backend() == DatabaseBackend::Sqlite)
+}
+
+/// 检查同步配置，返回同步服务器的连接配置
+fn sync_server() -> Result<DatabaseConfig, AppError> {
+    let config = config::load()?;
+    if !config.sync.enabled {
+        return Err(AppError::Validation("未启用同步".to_string()));
+    }
+    if !sync_available(&config) {
+        return Err(AppError::Validation("同步仅适用于本地 SQLite 存储".to_string()));
+    }
+    Ok(config.sync.server)
+}
+
+/// 验证服务器上同名账户的密码，为本机启用同步
+pub async fn link_account(user_id: i64, server_password: &str) -> Result<(), AppError> {
+    let server = sync_server()?;
+    let _guard = SYNC_LOCK.lock().await;
+    let local = &db::get_pool()?;
+
+    let remote = connect_server(&server).await?;
+    let result = issue_token(local, &remote, user_id, server_password).await;
+    with_pool!(&remote, pool => pool.close().await);
+    result
+}
+
+/// 清除本机的同步令牌 (之后不再同步，直到重新验证服务器账户)
+pub async fn unlink_account(user_id: i64) -> Result<(), AppError> {
+    if db::get_pool()?.backend() != DatabaseBackend::Sqlite {
+        return Ok(());
+    }
+    let _guard = SYNC_LOCK.lock().await;
+    save_token(&db::get_pool()?, user_id, None).await
+}
+
+/// 同步指定用户的数据
+pub async fn sync_user(user_id: i64) -> Result<SyncReport, AppError> {
+    let server = sync_server()?;
+
+    let _guard = SYNC_LOCK.lock().await;
+    let local = &db::get_pool()?;
+
+    let result = match connect_server(&server).await {
+        Ok(remote) => {
+            let result = run_sync(local, &remote, user_id).await;
+            with_pool!(&remote, pool => pool.close().await);
+            result
+        }
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = &result {
+        save_state(local, user_id, None, Some(&e.to_string())).await?;
+    }
+
+    result
+}
+
+/// 后台定时同步已验证服务器账户的本地用户 (由 setup 钩子启动)
+pub async fn run_background() {
+    loop {
+        let config = config::load().unwrap_or_default();
+        let interval = config.sync.interval_secs.max(MIN_INTERVAL_SECS);
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+
+        if !sync_available(&config) {
+            continue;
+        }
+
+        let Ok(local) = db::get_pool() else {
+            continue;
+        };
+
+        let users: Vec<(i64,)> = match with_pool!(&local, pool => sqlx::query_as(
+            "SELECT user_id FROM sync_state WHERE server_token IS NOT NULL"
+        )
+        .fetch_all(pool)
+        .await)
+        {
+            Ok(users) => users,
+            Err(e) => {
+                eprintln!("后台同步查询用户失败: {}", e);
+                continue;
+            }
+        };
+
+        for (user_id,) in users {
+            if let Err(e) = sync_user(user_id).await {
+                eprintln!("用户 {} 后台同步失败: {}", user_id, e);
+            }
+        }
+    }
+}
+
+/// 获取同步状态
+pub async fn get_sync_status(user_id: i64) -> Result<SyncStatusResponse, AppError> {
+    let config = config::load()?;
+    let server = &config.sync.server;
+
+    let mut status = SyncStatusResponse {
+        enabled: sync_available(&config),
+        server: format!("{}:{}/{}", server.host, server.port, server.database),
+        linked: false,
+        pending_changes: 0,
+        pending_by_entity: Vec::new(),
+        unresolved_conflicts: 0,
+        last_sync_at: None,
+        last_error: None,
+    };
+
+    // 直接连接 MySQL 时没有本地变更记录
+    let local = db::get_pool()?;
+    if local.backend() != DatabaseBackend::Sqlite {
+        return Ok(status);
+    }
+
+    status.pending_by_entity = with_pool!(pool => sqlx::query_as(
+        "SELECT entity, COUNT(*) AS count FROM sync_changes WHERE user_id = ? GROUP BY entity ORDER BY entity"
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await)
+    .map_err(AppError::db("查询待同步变更失败"))?;
+    status.pending_changes = status.pending_by_entity.iter().map(|p| p.count).sum();
+
+    let (conflicts,): (i64,) = with_pool!(pool => sqlx::query_as(
+        "SELECT COUNT(*) FROM sync_conflicts WHERE user_id = ? AND resolved = FALSE"
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await)
+    .map_err(AppError::db("查询同步冲突失败"))?;
+    status.unresolved_conflicts = conflicts;
+
+    status.linked = load_token(&local, user_id).await?.is_some();
+    if let Some((_, last_sync_at, last_error)) = load_state(&local, user_id).await? {
+        status.last_sync_at = last_sync_at;
+        status.last_error = last_error;
+    }
+
+    Ok(status)
+}
+
+/// 获取未处理的同步冲突
+pub async fn get_sync_conflicts(user_id: i64) -> Result<Vec<SyncConflict>, AppError> {
+    if db::get_pool()?.backend() != DatabaseBackend::Sqlite {
+        return Ok(Vec::new());
+    }
+
+    with_pool!(pool => sqlx::query_as(
+        "SELECT id, entity, sync_id, local_data, remote_data, resolution, resolved, detected_at
+         FROM sync_conflicts WHERE user_id = ? AND resolved = FALSE ORDER BY detected_at DESC"
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await)
+    .map_err(AppError::db("查询同步冲突失败"))
+}
+
+/// 将同步冲突标记为已查看
+pub async fn resolve_sync_conflict(user_id: i64, conflict_id: i64) -> Result<(), AppError> {
+    with_pool!(pool => sqlx::query("UPDATE sync_conflicts SET resolved = TRUE WHERE id = ? AND user_id = ?")
+        .bind(conflict_id)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map(ExecResult::from))
+        .map_err(AppError::db("更新同步冲突失败"))?;
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+// 任务标签服务
+use crate::error::AppError;
+use crate::models::{CreateTagInput, Tag, TaskResponse, UpdateTagInput, DEFAULT_TAG_COLOR};
+use crate::repositories::{
+    ChecklistRepository, SubjectRepository, TagRepository, TaskRepository, TaskSeriesRepository,
+};
+use crate::services::task_service;
+
+/// 标签名称的最大长度 (字符)
+pub const MAX_TAG_NAME_CHARS: usize = 20;
+
+/// 每个任务最多的标签数
+pub const MAX_TAGS_PER_TASK: usize = 10;
+
+/// 检查并去掉名称首尾的空白
+fn normalize_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("标签名称不能为空".to_string()));
+    }
+    if name.chars().count() > MAX_TAG_NAME_CHARS {
+        return Err(AppError::Validation(format!("标签名称不能超过 {} 个字符", MAX_TAG_NAME_CHARS)));
+    }
+    Ok(name.to_string())
+}
+
+/// 同一用户的其他标签不能重名
+async fn ensure_name_available<R: TagRepository>(
+    repo: &R,
+    user_id: i64,
+    name: &str,
+    except: Option<i64>,
+) -> Result<(), AppError> {
+    let tags = repo.list_tags(user_id).await?;
+    if tags.iter().any(|g| g.name == name && Some(g.id) != except) {
+        return Err(AppError::Conflict("标签已存在".to_string()));
+    }
+    Ok(())
+}
+
+async fn find_tag<R: TagRepository>(repo: &R, user_id: i64, tag_id: i64) -> Result<Tag, AppError> {
+    repo.find_tag(user_id, tag_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("标签不存在".to_string()))
+}
+
+/// 获取用户的所有标签
+pub async fn get_tags<R: TagRepository>(repo: &R, user_id: i64) -> Result<Vec<Tag>, AppError> {
+    repo.list_tags(user_id).await
+}
+
+/// 创建标签
+pub async fn create_tag<R: TagRepository>(repo: &R, user_id: i64, input: CreateTagInput) -> Result<Tag, AppError> {
+    let name = normalize_name(&input.name)?;
+    ensure_name_available(repo, user_id, &name, None).await?;
+    let color = input.color.unwrap_or_else(|| DEFAULT_TAG_COLOR.to_string());
+
+    let tag_id = repo.create_tag(user_id, &name, &color).await?;
+    find_tag(repo, user_id, tag_id).await
+}
+
+/// 修改标签的名称或颜色
+pub async fn update_tag<R: TagRepository>(
+    repo: &R,
+    user_id: i64,
+    tag_id: i64,
+    input: UpdateTagInput,
+) -> Result<Tag, AppError> {
+    find_tag(repo, user_id, tag_id).await?;
+    let name = input.name.as_deref().map(normalize_name).transpose()?;
+    if let Some(name) = &name {
+        ensure_name_available(repo, user_id, name, Some(tag_id)).await?;
+    }
+
+    repo.update_tag(user_id, tag_id, &UpdateTagInput { name, ..input }).await?;
+    find_tag(repo, user_id, tag_id).await
+}
+
+/// 删除标签 (任务上的该标签一并移除)
+pub async fn delete_tag<R: TagRepository>(repo: &R, user_id: i64, tag_id: i64) -> Result<(), AppError> {
+    repo.delete_tag(user_id, tag_id).await
+}
+
+/// 设置任务的标签 (替换原有标签，重复的 ID 只保留一个；尚未生成的重复任务先生成)
+pub async fn set_task_tags<R>(repo: &R, user_id: i64, task_id: i64, tag_ids: &[i64]) -> Result<TaskResponse, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    let task = task_service::find_task_or_occurrence(repo, user_id, task_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("任务不存在".to_string()))?;
+
+    let mut ids = tag_ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.len() > MAX_TAGS_PER_TASK {
+        return Err(AppError::Validation(format!("每个任务最多 {} 个标签", MAX_TAGS_PER_TASK)));
+    }
+    for tag_id in &ids {
+        find_tag(repo, user_id, *tag_id).await?;
+    }
+
+    let task = task_service::materialize_task(repo, user_id, task).await?;
+    repo.set_task_tags(task.id, &ids).await?;
+    Ok(task_service::to_response(repo, task).await)
+}
+
+/// 把一个任务的标签复制到另一个任务 (用于顺延任务)
+pub async fn copy_task_tags<R>(repo: &R, user_id: i64, from_task_id: i64, to_task_id: i64) -> Result<(), AppError>
+where
+    R: TagRepository,
+{
+    let tags = repo.list_task_tags(user_id, from_task_id).await?;
+    if tags.is_empty() {
+        return Ok(());
+    }
+    let ids: Vec<i64> = tags.iter().map(|g| g.id).collect();
+    repo.set_task_tags(to_task_id, &ids).await
+}
@@ -1,250 +1,608 @@
 // 任务服务
-use crate::db::get_pool;
-use crate::models::{Task, TaskResponse, CreateTaskInput, UpdateTaskInput, Subject};
+use crate::error::AppError;
+use crate::models::{
+    occurrence_task_id, parse_occurrence_task_id, ChecklistItem, CreateTaskInput, CreateTaskSeriesInput, Frequency,
+    RecurrenceRule, RecurrenceScope, Subject, Tag, Task, TaskDay, TaskFilter, StudyWindow, TaskResponse, TaskSeries,
+    TaskSeriesResponse, TaskStatus, TaskWithSubject, UpdateTaskInput,
+};
+use crate::repositories::{
+    ChecklistRepository, SubjectRepository, TagRepository, TaskRepository, TaskSeriesRepository,
+};
+use crate::services::schedule_service;
 use crate::utils::fuzzy_match_default;
 use chrono::NaiveDate;
+use std::collections::{HashMap, HashSet};
 
-/// 获取指定日期的任务
-pub async fn get_tasks_by_date(user_id: i64, date: NaiveDate) -> Result<Vec<TaskResponse>, String> {
-    let pool = get_pool();
-    
-    let tasks: Vec<Task> = sqlx::query_as(
-        "SELECT t.id, t.user_id, t.subject_id, t.task_date, t.start_time, t.end_time, 
-                t.content, t.status, t.alarm_enabled, t.alarm_time, t.created_at, t.updated_at
-         FROM tasks t WHERE t.user_id = ? AND t.task_date = ? 
-         ORDER BY t.start_time ASC"
-    )
-    .bind(user_id)
-    .bind(date)
-    .fetch_all(pool)
-    .await
-    .map_err(|e| format!("查询任务失败: {}", e))?;
+/// 重复间隔上限 (天或周)
+pub const MAX_RECURRENCE_INTERVAL: u32 = 365;
+
+/// 重复次数上限
+pub const MAX_RECURRENCE_COUNT: u32 = 1000;
+
+/// 多日查询的最大天数 (足够覆盖月视图前后补齐的六周)
+pub const MAX_RANGE_DAYS: i64 = 62;
+
+/// 获取指定日期的任务 (包含当天尚未生成的重复任务)
+pub async fn get_tasks_by_date<R>(repo: &R, user_id: i64, date: NaiveDate) -> Result<Vec<TaskResponse>, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    let tasks = list_day_tasks(repo, user_id, date).await?;
 
     let mut responses = Vec::new();
     for task in tasks {
-        let subject = if let Some(sid) = task.subject_id {
-            sqlx::query_as::<_, Subject>(
-                "SELECT id, user_id, name, color, is_default, created_at FROM subjects WHERE id = ?"
-            )
-            .bind(sid)
-            .fetch_optional(pool)
-            .await
-            .ok()
-            .flatten()
-        } else {
-            None
-        };
+        responses.push(to_response(repo, task).await);
+    }
 
-        responses.push(TaskResponse {
-            id: task.id,
-            subject_id: task.subject_id,
+    Ok(responses)
+}
+
+/// 获取日期范围内 (含首尾) 的任务，按天分组并汇总 (没有任务的日期也会返回)
+pub async fn get_tasks_in_range<R>(
+    repo: &R,
+    user_id: i64,
+    start: NaiveDate,
+    end: NaiveDate,
+    filter: TaskFilter,
+) -> Result<Vec<TaskDay>, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    if start > end {
+        return Err(AppError::Validation("开始日期不能晚于结束日期".to_string()));
+    }
+    if (end - start).num_days() >= MAX_RANGE_DAYS {
+        return Err(AppError::Validation(format!("单次最多查询 {} 天的任务", MAX_RANGE_DAYS)));
+    }
+    let filter = TaskFilter {
+        query: filter.query.map(|q| q.trim().to_string()).filter(|q| !q.is_empty()),
+        ..filter
+    };
+
+    let mut tasks = repo.list_tasks_in_range(user_id, start, end, &filter).await?;
+    // 尚未生成的重复任务没有清单和标签
+    let mut subjects: HashMap<i64, Option<Subject>> = HashMap::new();
+    for task in list_pending_occurrences(repo, user_id, start, end).await? {
+        if !filter.matches(&task, &[]) {
+            continue;
+        }
+        let subject = match task.subject_id {
+            Some(sid) => match subjects.get(&sid) {
+                Some(subject) => subject.clone(),
+                None => {
+                    let subject = repo.find_subject(sid).await.ok().flatten();
+                    subjects.insert(sid, subject.clone());
+                    subject
+                }
+            },
+            None => None,
+        };
+        tasks.push(TaskWithSubject {
+            task,
             subject_name: subject.as_ref().map(|s| s.name.clone()),
-            subject_color: subject.as_ref().map(|s| s.color.clone()),
-            task_date: task.task_date,
-            start_time: task.start_time,
-            end_time: task.end_time,
-            content: task.content,
-            status: task.status.to_string(),
-            alarm_enabled: task.alarm_enabled,
-            alarm_time: task.alarm_time,
+            subject_color: subject.map(|s| s.color),
         });
     }
+    tasks.sort_by_key(|row| (row.task.task_date, row.task.start_time));
 
-    Ok(responses)
+    let mut checklists: HashMap<i64, Vec<ChecklistItem>> = HashMap::new();
+    for item in repo.list_checklist_items_in_range(user_id, start, end).await? {
+        checklists.entry(item.task_id).or_default().push(item);
+    }
+    let tags: HashMap<i64, Tag> = repo.list_tags(user_id).await?.into_iter().map(|g| (g.id, g)).collect();
+    let mut task_tags: HashMap<i64, Vec<Tag>> = HashMap::new();
+    for link in repo.list_task_tag_links_in_range(user_id, start, end).await? {
+        if let Some(tag) = tags.get(&link.tag_id) {
+            task_tags.entry(link.task_id).or_default().push(tag.clone());
+        }
+    }
+
+    let mut days: Vec<TaskDay> = start
+        .iter_days()
+        .take_while(|d| *d <= end)
+        .map(|date| TaskDay {
+            date,
+            tasks: Vec::new(),
+            total_tasks: 0,
+            completed_tasks: 0,
+            planned_minutes: 0,
+        })
+        .collect();
+    for row in tasks {
+        let Some(day) = days.get_mut((row.task.task_date - start).num_days() as usize) else {
+            continue;
+        };
+        // 已顺延的任务仍然列出，但不计入当天的汇总
+        if row.task.status != TaskStatus::RolledOver {
+            day.total_tasks += 1;
+            if row.task.status == TaskStatus::Completed {
+                day.completed_tasks += 1;
+            }
+            day.planned_minutes += (row.task.end_time - row.task.start_time).num_minutes().max(0);
+        }
+        let task_id = row.task.id;
+        let mut response: TaskResponse = row.into();
+        response.checklist = checklists.remove(&task_id).unwrap_or_default();
+        response.tags = task_tags.remove(&task_id).unwrap_or_default();
+        response.tags.sort_by(|a, b| a.name.cmp(&b.name));
+        day.tasks.push(response);
+    }
+
+    Ok(days)
 }
 
-/// 创建任务
-pub async fn create_task(user_id: i64, input: CreateTaskInput) -> Result<TaskResponse, String> {
-    let pool = get_pool();
-    
-    let result = sqlx::query(
-        "INSERT INTO tasks (user_id, subject_id, task_date, start_time, end_time, content, alarm_enabled, alarm_time) 
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
-    )
-    .bind(user_id)
-    .bind(input.subject_id)
-    .bind(input.task_date)
-    .bind(input.start_time)
-    .bind(input.end_time)
-    .bind(&input.content)
-    .bind(input.alarm_enabled.unwrap_or(false))
-    .bind(input.alarm_time)
-    .execute(pool)
-    .await
-    .map_err(|e| format!("创建任务失败: {}", e))?;
-
-    let task_id = result.last_insert_id() as i64;
-    get_task_by_id(task_id).await
-}
-
-/// 根据 ID 获取任务
-async fn get_task_by_id(task_id: i64) -> Result<TaskResponse, String> {
-    let pool = get_pool();
-    
-    let task: Task = sqlx::query_as(
-        "SELECT id, user_id, subject_id, task_date, start_time, end_time, 
-                content, status, alarm_enabled, alarm_time, created_at, updated_at
-         FROM tasks WHERE id = ?"
-    )
-    .bind(task_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| format!("查询任务失败: {}", e))?;
-
-    let subject = if let Some(sid) = task.subject_id {
-        sqlx::query_as::<_, Subject>(
-            "SELECT id, user_id, name, color, is_default, created_at FROM subjects WHERE id = ?"
-        )
-        .bind(sid)
-        .fetch_optional(pool)
-        .await
-        .ok()
-        .flatten()
-    } else {
-        None
+/// 转换为任务响应 (科目、清单或标签查询失败时不返回对应信息)
+pub(crate) async fn to_response<R>(repo: &R, task: Task) -> TaskResponse
+where
+    R: ChecklistRepository + TagRepository + SubjectRepository,
+{
+    let subject = match task.subject_id {
+        Some(sid) => repo.find_subject(sid).await.ok().flatten(),
+        None => None,
     };
+    let checklist = repo.list_checklist_items(task.user_id, task.id).await.unwrap_or_default();
+    let tags = repo.list_task_tags(task.user_id, task.id).await.unwrap_or_default();
 
-    Ok(TaskResponse {
-        id: task.id,
-        subject_id: task.subject_id,
+    let mut response: TaskResponse = TaskWithSubject {
+        task,
         subject_name: subject.as_ref().map(|s| s.name.clone()),
-        subject_color: subject.as_ref().map(|s| s.color.clone()),
-        task_date: task.task_date,
-        start_time: task.start_time,
-        end_time: task.end_time,
-        content: task.content,
-        status: task.status.to_string(),
-        alarm_enabled: task.alarm_enabled,
-        alarm_time: task.alarm_time,
-    })
+        subject_color: subject.map(|s| s.color),
+    }
+    .into();
+    response.checklist = checklist;
+    response.tags = tags;
+    response
+}
+
+/// 创建任务 (时间与当天任务重叠时拒绝，不符合学习偏好时在响应中给出提醒)
+pub async fn create_task<R>(
+    repo: &R,
+    user_id: i64,
+    input: CreateTaskInput,
+    window: &StudyWindow,
+) -> Result<TaskResponse, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    schedule_service::validate_time_range(input.start_time, input.end_time)?;
+    schedule_service::check_conflicts(repo, user_id, input.task_date, input.start_time, input.end_time, None).await?;
+
+    let task_id = repo.create_task(user_id, &input).await?;
+
+    let mut response = get_task_by_id(repo, user_id, task_id).await?;
+    response.warnings = schedule_service::preference_warnings(window, response.start_time, response.end_time);
+    Ok(response)
+}
+
+/// 根据 ID 获取任务 (包含尚未生成的重复任务)
+async fn get_task_by_id<R>(repo: &R, user_id: i64, task_id: i64) -> Result<TaskResponse, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    let task = find_task_or_occurrence(repo, user_id, task_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("任务不存在".to_string()))?;
+
+    Ok(to_response(repo, task).await)
+}
+
+/// 更新任务 (重复任务可选择仅此一次或此次及之后；时间有变化时检查受影响的每一天是否冲突)
+pub async fn update_task<R>(
+    repo: &R,
+    user_id: i64,
+    task_id: i64,
+    input: UpdateTaskInput,
+    scope: RecurrenceScope,
+    window: &StudyWindow,
+) -> Result<TaskResponse, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    let task = find_task_or_occurrence(repo, user_id, task_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("任务不存在".to_string()))?;
+
+    let task_id = match (scope, task.series_id, task.occurrence_date) {
+        (RecurrenceScope::Following, Some(series_id), Some(date)) => {
+            if input.start_time.is_some() || input.end_time.is_some() {
+                check_following_conflicts(repo, user_id, series_id, date, &input).await?;
+            }
+            let target_id = update_following(repo, user_id, series_id, date, &input).await?;
+            // 尚未生成的这一次随系列一起修改，归属于修改后的系列
+            if parse_occurrence_task_id(task.id).is_some() {
+                occurrence_task_id(target_id, date)
+            } else {
+                task.id
+            }
+        }
+        _ => {
+            let start_time = input.start_time.unwrap_or(task.start_time);
+            let end_time = input.end_time.unwrap_or(task.end_time);
+            if (start_time, end_time) != (task.start_time, task.end_time) {
+                schedule_service::validate_time_range(start_time, end_time)?;
+                schedule_service::check_conflicts(repo, user_id, task.task_date, start_time, end_time, Some(task.id))
+                    .await?;
+            }
+            let task = materialize_task(repo, user_id, task).await?;
+            repo.update_task(user_id, task.id, &input).await?;
+            task.id
+        }
+    };
+
+    let mut response = get_task_by_id(repo, user_id, task_id).await?;
+    response.warnings = schedule_service::preference_warnings(window, response.start_time, response.end_time);
+    Ok(response)
+}
+
+/// 删除任务 (重复任务可选择仅此一次或此次及之后)
+pub async fn delete_task<R>(repo: &R, user_id: i64, task_id: i64, scope: RecurrenceScope) -> Result<(), AppError>
+where
+    R: TaskRepository + TaskSeriesRepository,
+{
+    let Some(task) = find_task_or_occurrence(repo, user_id, task_id).await? else {
+        return Ok(());
+    };
+
+    match (scope, task.series_id, task.occurrence_date) {
+        (RecurrenceScope::Following, Some(series_id), Some(date)) => {
+            delete_following(repo, user_id, series_id, date).await?;
+        }
+        (RecurrenceScope::This, Some(series_id), Some(date)) => {
+            repo.skip_task_occurrence(series_id, date).await?;
+        }
+        _ => {}
+    }
+
+    // 尚未生成的重复任务只需记录删除
+    if parse_occurrence_task_id(task.id).is_some() {
+        return Ok(());
+    }
+    repo.delete_task(user_id, task.id).await
+}
+
+/// 切换任务状态 (在待完成和已完成之间切换)
+pub async fn toggle_task_status<R>(repo: &R, user_id: i64, task_id: i64) -> Result<TaskResponse, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    // 获取当前状态
+    let task = find_task_or_occurrence(repo, user_id, task_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("任务不存在".to_string()))?;
+    let Some(status) = task.status.next() else {
+        return Err(AppError::Validation("已顺延的任务不能修改状态".to_string()));
+    };
+    let task = materialize_task(repo, user_id, task).await?;
+
+    // 切换状态 (清除原来的原因)
+    repo.set_task_status(user_id, task.id, &status, None).await?;
+
+    get_task_by_id(repo, user_id, task.id).await
+}
+
+/// 内容检查 - 模糊匹配
+pub async fn check_content<R>(
+    repo: &R,
+    user_id: i64,
+    date: NaiveDate,
+    content: &str,
+) -> Result<Vec<TaskResponse>, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    let tasks = list_day_tasks(repo, user_id, date).await?;
+
+    let mut matched_tasks = Vec::new();
+
+    for task in tasks {
+        if task.status == TaskStatus::Pending && fuzzy_match_default(content, &task.content) {
+            // 自动标记为完成
+            let task = materialize_task(repo, user_id, task).await?;
+            repo.set_task_status(user_id, task.id, &TaskStatus::Completed, None).await?;
+
+            matched_tasks.push(get_task_by_id(repo, user_id, task.id).await?);
+        }
+    }
+
+    Ok(matched_tasks)
 }
 
-/// 更新任务
-pub async fn update_task(user_id: i64, task_id: i64, input: UpdateTaskInput) -> Result<TaskResponse, String> {
-    let pool = get_pool();
-    
-    // 构建动态更新语句
-    let mut updates = Vec::new();
-    if input.subject_id.is_some() {
-        updates.push("subject_id = ?");
+/// 检查并规范化重复规则 (星期去重排序)
+fn normalize_rule(rule: &RecurrenceRule, start_date: NaiveDate) -> Result<RecurrenceRule, AppError> {
+    if rule.interval == 0 || rule.interval > MAX_RECURRENCE_INTERVAL {
+        return Err(AppError::Validation(format!("重复间隔需在 1-{} 之间", MAX_RECURRENCE_INTERVAL)));
     }
-    if input.start_time.is_some() {
-        updates.push("start_time = ?");
+    if rule.weekdays.iter().any(|d| !(1..=7).contains(d)) {
+        return Err(AppError::Validation("星期需在 1-7 之间".to_string()));
     }
-    if input.end_time.is_some() {
-        updates.push("end_time = ?");
+    if rule.frequency == Frequency::Daily && !rule.weekdays.is_empty() {
+        return Err(AppError::Validation("按天重复不能指定星期".to_string()));
     }
-    if input.content.is_some() {
-        updates.push("content = ?");
+    if rule.until.is_some() && rule.count.is_some() {
+        return Err(AppError::Validation("结束日期和重复次数只能设置一个".to_string()));
     }
-    if input.alarm_enabled.is_some() {
-        updates.push("alarm_enabled = ?");
+    if rule.until.is_some_and(|until| until < start_date) {
+        return Err(AppError::Validation("结束日期不能早于开始日期".to_string()));
     }
-    if input.alarm_time.is_some() {
-        updates.push("alarm_time = ?");
+    if rule.count.is_some_and(|count| count == 0 || count > MAX_RECURRENCE_COUNT) {
+        return Err(AppError::Validation(format!("重复次数需在 1-{} 之间", MAX_RECURRENCE_COUNT)));
     }
 
-    if updates.is_empty() {
-        return get_task_by_id(task_id).await;
+    let mut weekdays = rule.weekdays.clone();
+    weekdays.sort_unstable();
+    weekdays.dedup();
+    Ok(RecurrenceRule { weekdays, ..rule.clone() })
+}
+
+/// 创建重复任务
+pub async fn create_task_series<R>(
+    repo: &R,
+    user_id: i64,
+    input: CreateTaskSeriesInput,
+) -> Result<TaskSeriesResponse, AppError>
+where
+    R: TaskSeriesRepository,
+{
+    schedule_service::validate_time_range(input.start_time, input.end_time)?;
+    let rule = normalize_rule(&input.rule, input.start_date)?;
+    if rule.occurrences(input.start_date).next().is_none() {
+        return Err(AppError::Validation("重复规则在结束日期前没有任何一次重复".to_string()));
     }
 
-    let sql = format!(
-        "UPDATE tasks SET {} WHERE id = ? AND user_id = ?",
-        updates.join(", ")
-    );
+    let series_id = repo.create_task_series(user_id, &CreateTaskSeriesInput { rule, ..input }).await?;
+    get_task_series(repo, user_id, series_id).await
+}
 
-    let mut query = sqlx::query(&sql);
-    
-    if let Some(v) = input.subject_id {
-        query = query.bind(v);
-    }
-    if let Some(v) = input.start_time {
-        query = query.bind(v);
-    }
-    if let Some(v) = input.end_time {
-        query = query.bind(v);
+/// 获取重复任务
+pub async fn get_task_series<R>(repo: &R, user_id: i64, series_id: i64) -> Result<TaskSeriesResponse, AppError>
+where
+    R: TaskSeriesRepository,
+{
+    find_series(repo, user_id, series_id).await.map(TaskSeriesResponse::from)
+}
+
+async fn find_series<R: TaskSeriesRepository>(repo: &R, user_id: i64, series_id: i64) -> Result<TaskSeries, AppError> {
+    repo.find_task_series(user_id, series_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("重复任务不存在".to_string()))
+}
+
+/// 日期范围内 (含首尾) 尚未生成的重复任务 (已生成或已被单独删除的不再列出)，按日期和开始时间排列
+pub(crate) fn pending_occurrences(
+    series: &[TaskSeries],
+    taken: &HashSet<(i64, NaiveDate)>,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Vec<Task> {
+    let mut tasks: Vec<Task> = series
+        .iter()
+        .flat_map(|s| {
+            s.rule
+                .occurrences(s.start_date)
+                .skip_while(move |d| *d < start)
+                .take_while(move |d| *d <= end)
+                .filter(|d| !taken.contains(&(s.id, *d)))
+                .map(|d| s.occurrence(d))
+        })
+        .collect();
+    tasks.sort_by_key(|t| (t.task_date, t.start_time));
+    tasks
+}
+
+/// 查询用户在日期范围内 (含首尾) 尚未生成的重复任务
+pub(crate) async fn list_pending_occurrences<R>(
+    repo: &R,
+    user_id: i64,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<Task>, AppError>
+where
+    R: TaskSeriesRepository,
+{
+    let series = repo.list_task_series(user_id, end).await?;
+    if series.is_empty() {
+        return Ok(Vec::new());
     }
-    if let Some(v) = &input.content {
-        query = query.bind(v);
+    let taken: HashSet<(i64, NaiveDate)> = repo.list_occurrence_dates(user_id, start, end).await?.into_iter().collect();
+    Ok(pending_occurrences(&series, &taken, start, end))
+}
+
+/// 当天的全部任务 (包含尚未生成的重复任务)，按开始时间排列
+pub(crate) async fn list_day_tasks<R>(repo: &R, user_id: i64, date: NaiveDate) -> Result<Vec<Task>, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository,
+{
+    let mut tasks = repo.list_tasks_by_date(user_id, date).await?;
+    tasks.extend(list_pending_occurrences(repo, user_id, date, date).await?);
+    tasks.sort_by_key(|t| t.start_time);
+    Ok(tasks)
+}
+
+/// 系列在指定日期已生成的任务
+async fn find_occurrence<R>(repo: &R, user_id: i64, series_id: i64, date: NaiveDate) -> Result<Option<Task>, AppError>
+where
+    R: TaskSeriesRepository,
+{
+    Ok(repo
+        .list_task_occurrences(user_id, series_id, date)
+        .await?
+        .into_iter()
+        .find(|t| t.occurrence_date == Some(date)))
+}
+
+/// 根据 ID 查询任务：尚未生成的重复任务按系列展开，已生成的返回实际任务，已被单独删除的视为不存在
+pub(crate) async fn find_task_or_occurrence<R>(repo: &R, user_id: i64, task_id: i64) -> Result<Option<Task>, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository,
+{
+    let Some((series_id, date)) = parse_occurrence_task_id(task_id) else {
+        return repo.find_task(user_id, task_id).await;
+    };
+    let Some(series) = repo.find_task_series(user_id, series_id).await? else {
+        return Ok(None);
+    };
+    if !series.rule.occurs_on(series.start_date, date) {
+        return Ok(None);
     }
-    if let Some(v) = input.alarm_enabled {
-        query = query.bind(v);
+    if let Some(task) = find_occurrence(repo, user_id, series_id, date).await? {
+        return Ok(Some(task));
     }
-    if let Some(v) = input.alarm_time {
-        query = query.bind(v);
+    if repo.occurrence_exists(series_id, date).await? {
+        return Ok(None);
     }
-    
-    query = query.bind(task_id).bind(user_id);
-    
-    query.execute(pool).await.map_err(|e| format!("更新任务失败: {}", e))?;
-
-    get_task_by_id(task_id).await
+    Ok(Some(series.occurrence(date)))
 }
 
-/// 删除任务
-pub async fn delete_task(user_id: i64, task_id: i64) -> Result<(), String> {
-    let pool = get_pool();
-    
-    sqlx::query("DELETE FROM tasks WHERE id = ? AND user_id = ?")
-        .bind(task_id)
-        .bind(user_id)
-        .execute(pool)
-        .await
-        .map_err(|e| format!("删除任务失败: {}", e))?;
+/// 把尚未生成的重复任务生成为实际的任务 (修改、完成或删除前调用；实际任务原样返回)
+pub(crate) async fn materialize_task<R>(repo: &R, user_id: i64, task: Task) -> Result<Task, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository,
+{
+    let Some((series_id, date)) = parse_occurrence_task_id(task.id) else {
+        return Ok(task);
+    };
+    let series = find_series(repo, user_id, series_id).await?;
 
-    Ok(())
+    // 并发修改同一次重复时可能已由另一请求生成
+    let task_id = match repo.create_task_occurrence(&series, date).await {
+        Ok(task_id) => task_id,
+        Err(e) => match find_occurrence(repo, user_id, series_id, date).await? {
+            Some(task) => task.id,
+            None => return Err(e),
+        },
+    };
+    repo.find_task(user_id, task_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("任务不存在".to_string()))
 }
 
-/// 切换任务状态
-pub async fn toggle_task_status(user_id: i64, task_id: i64) -> Result<TaskResponse, String> {
-    let pool = get_pool();
-    
-    // 获取当前状态
-    let task: Task = sqlx::query_as(
-        "SELECT id, user_id, subject_id, task_date, start_time, end_time, 
-                content, status, alarm_enabled, alarm_time, created_at, updated_at
-         FROM tasks WHERE id = ? AND user_id = ?"
-    )
-    .bind(task_id)
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| format!("查询任务失败: {}", e))?;
-
-    // 切换状态
-    let new_status = task.status.next();
-    
-    sqlx::query("UPDATE tasks SET status = ? WHERE id = ?")
-        .bind(new_status.to_string())
-        .bind(task_id)
-        .execute(pool)
-        .await
-        .map_err(|e| format!("更新状态失败: {}", e))?;
-
-    get_task_by_id(task_id).await
+/// 截止到指定日期前一天的重复规则
+fn rule_ending_before(rule: &RecurrenceRule, date: NaiveDate) -> RecurrenceRule {
+    RecurrenceRule {
+        until: date.pred_opt(),
+        count: None,
+        ..rule.clone()
+    }
 }
 
-/// 内容检查 - 模糊匹配
-pub async fn check_content(user_id: i64, date: NaiveDate, content: &str) -> Result<Vec<TaskResponse>, String> {
-    let tasks = get_tasks_by_date(user_id, date).await?;
-    let pool = get_pool();
-    
-    let mut matched_tasks = Vec::new();
-    
-    for task in tasks {
-        if task.status == "pending" && fuzzy_match_default(content, &task.content) {
-            // 自动标记为完成
-            sqlx::query("UPDATE tasks SET status = 'completed' WHERE id = ?")
-                .bind(task.id)
-                .execute(pool)
-                .await
-                .map_err(|e| format!("更新状态失败: {}", e))?;
-            
-            let mut updated_task = task;
-            updated_task.status = "completed".to_string();
-            matched_tasks.push(updated_task);
+/// 修改此次及之后的重复：从第一次开始时直接修改系列，否则拆分为两个系列 (返回修改后的系列 ID)
+async fn update_following<R>(
+    repo: &R,
+    user_id: i64,
+    series_id: i64,
+    date: NaiveDate,
+    input: &UpdateTaskInput,
+) -> Result<i64, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository,
+{
+    let series = find_series(repo, user_id, series_id).await?;
+
+    let target_id = if date <= series.start_date {
+        repo.update_task_series(user_id, series.id, input).await?;
+        series.id
+    } else {
+        let remaining = series
+            .rule
+            .count
+            .map(|count| count.saturating_sub(series.rule.count_before(series.start_date, date)).max(1));
+        let following = CreateTaskSeriesInput {
+            subject_id: input.subject_id.or(series.subject_id),
+            start_date: date,
+            start_time: input.start_time.unwrap_or(series.start_time),
+            end_time: input.end_time.unwrap_or(series.end_time),
+            content: input.content.clone().unwrap_or_else(|| series.content.clone()),
+            alarm_enabled: Some(input.alarm_enabled.unwrap_or(series.alarm_enabled)),
+            alarm_time: input.alarm_time.or(series.alarm_time),
+            priority: Some(input.priority.unwrap_or(series.priority)),
+            rule: RecurrenceRule {
+                count: remaining,
+                ..series.rule.clone()
+            },
+        };
+        let following_id = repo.create_task_series(user_id, &following).await?;
+        repo.set_task_series_rule(user_id, series.id, &rule_ending_before(&series.rule, date))
+            .await?;
+        repo.move_task_occurrences(user_id, series.id, following_id, date).await?;
+        following_id
+    };
+
+    // 已生成的任务同步修改 (保留各自的完成状态)
+    for task in repo.list_task_occurrences(user_id, target_id, date).await? {
+        repo.update_task(user_id, task.id, input).await?;
+    }
+    Ok(target_id)
+}
+
+/// 检查此次及之后的每一次重复修改时间后是否有效且不与当天的其他任务重叠 (不结束的系列最多检查重复次数上限次)
+async fn check_following_conflicts<R>(
+    repo: &R,
+    user_id: i64,
+    series_id: i64,
+    date: NaiveDate,
+    input: &UpdateTaskInput,
+) -> Result<(), AppError>
+where
+    R: TaskRepository + TaskSeriesRepository,
+{
+    let series = find_series(repo, user_id, series_id).await?;
+    let dates: Vec<NaiveDate> = series
+        .rule
+        .occurrences(series.start_date)
+        .skip_while(|d| *d < date)
+        .take(MAX_RECURRENCE_COUNT as usize)
+        .collect();
+    let mut affected = repo.list_task_occurrences(user_id, series.id, date).await?;
+    let mut others: HashMap<NaiveDate, Vec<Task>> = HashMap::new();
+    if let (Some(&first), Some(&last)) = (dates.first(), dates.last()) {
+        let existing = repo.list_tasks_in_range(user_id, first, last, &TaskFilter::default()).await?;
+        let pending = list_pending_occurrences(repo, user_id, first, last).await?;
+        for task in existing.into_iter().map(|row| row.task).chain(pending) {
+            if task.series_id != Some(series.id) || task.occurrence_date.is_none_or(|d| d < date) {
+                others.entry(task.task_date).or_default().push(task);
+            } else if parse_occurrence_task_id(task.id).is_some() {
+                affected.push(task);
+            }
         }
     }
-    
-    Ok(matched_tasks)
+
+    let mut task_ids = Vec::new();
+    let mut conflict_dates = Vec::new();
+    for task in &affected {
+        let start_time = input.start_time.unwrap_or(task.start_time);
+        let end_time = input.end_time.unwrap_or(task.end_time);
+        schedule_service::validate_time_range(start_time, end_time)?;
+        if task.status == TaskStatus::RolledOver {
+            continue;
+        }
+        let tasks = others.get(&task.task_date).map_or(&[][..], Vec::as_slice);
+        let conflicts = schedule_service::find_conflicts(tasks, start_time, end_time, None);
+        if !conflicts.is_empty() {
+            conflict_dates.push(task.task_date);
+            task_ids.extend(conflicts);
+        }
+    }
+    let Some(first) = conflict_dates.iter().min() else {
+        return Ok(());
+    };
+    Err(AppError::ScheduleConflict {
+        message: format!("此次及之后有 {} 次重复与当天已有的任务时间重叠 (最早为 {})", conflict_dates.len(), first),
+        task_ids,
+    })
+}
+
+/// 删除此次及之后的重复：从第一次开始时删除整个系列，否则让系列在前一天结束
+async fn delete_following<R>(repo: &R, user_id: i64, series_id: i64, date: NaiveDate) -> Result<(), AppError>
+where
+    R: TaskSeriesRepository,
+{
+    let series = find_series(repo, user_id, series_id).await?;
+    repo.delete_task_occurrences(user_id, series.id, date).await?;
+
+    if date <= series.start_date {
+        repo.delete_task_series(user_id, series.id).await
+    } else {
+        repo.set_task_series_rule(user_id, series.id, &rule_ending_before(&series.rule, date))
+            .await
+    }
 }
@@ -0,0 +1,103 @@
+// 任务状态服务：设置状态及原因、查询状态变更记录、统计未完成原因
+use crate::error::AppError;
+use crate::models::{FailureReasonStats, Task, TaskFilter, TaskResponse, TaskStatus, TaskStatusChange};
+use crate::repositories::{
+    ChecklistRepository, SubjectRepository, TagRepository, TaskRepository, TaskSeriesRepository,
+};
+use crate::services::task_service;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+/// 状态原因的最大长度 (字符)
+pub const MAX_STATUS_REASON_CHARS: usize = 100;
+
+/// 查询任务 (包含尚未生成的重复任务)
+async fn find_task<R>(repo: &R, user_id: i64, task_id: i64) -> Result<Task, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository,
+{
+    task_service::find_task_or_occurrence(repo, user_id, task_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("任务不存在".to_string()))
+}
+
+/// 检查并规范化原因 (去掉首尾空白，空字符串视为没有原因；标记为未完成时必须填写)
+fn normalize_reason(status: &TaskStatus, reason: Option<&str>) -> Result<Option<String>, AppError> {
+    let reason = reason.map(str::trim).filter(|r| !r.is_empty());
+    if let Some(reason) = reason {
+        if reason.chars().count() > MAX_STATUS_REASON_CHARS {
+            return Err(AppError::Validation(format!("原因不能超过 {} 个字符", MAX_STATUS_REASON_CHARS)));
+        }
+    } else if *status == TaskStatus::Failed {
+        return Err(AppError::Validation("请填写未完成的原因".to_string()));
+    }
+    Ok(reason.map(str::to_string))
+}
+
+/// 设置任务状态 (标记为未完成时必须填写原因；已顺延状态只能通过顺延任务设置，设置后不能再修改)
+pub async fn set_task_status<R>(
+    repo: &R,
+    user_id: i64,
+    task_id: i64,
+    status: TaskStatus,
+    reason: Option<String>,
+) -> Result<TaskResponse, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository + ChecklistRepository + TagRepository + SubjectRepository,
+{
+    let task = find_task(repo, user_id, task_id).await?;
+    if status == TaskStatus::RolledOver {
+        return Err(AppError::Validation("已顺延状态只能通过顺延任务设置".to_string()));
+    }
+    if task.status == TaskStatus::RolledOver {
+        return Err(AppError::Validation("已顺延的任务不能修改状态".to_string()));
+    }
+    let reason = normalize_reason(&status, reason.as_deref())?;
+    if status == task.status && reason == task.status_reason {
+        return Ok(task_service::to_response(repo, task).await);
+    }
+
+    let task = task_service::materialize_task(repo, user_id, task).await?;
+    repo.set_task_status(user_id, task.id, &status, reason.as_deref()).await?;
+    let task = find_task(repo, user_id, task.id).await?;
+    Ok(task_service::to_response(repo, task).await)
+}
+
+/// 获取任务的状态变更记录 (按时间先后排列)
+pub async fn get_task_status_history<R>(
+    repo: &R,
+    user_id: i64,
+    task_id: i64,
+) -> Result<Vec<TaskStatusChange>, AppError>
+where
+    R: TaskRepository + TaskSeriesRepository,
+{
+    let task = find_task(repo, user_id, task_id).await?;
+    repo.list_task_status_history(user_id, task.id).await
+}
+
+/// 统计日期范围内 (含首尾) 未完成任务的原因，按任务数从多到少排列
+pub async fn get_failure_reasons<R: TaskRepository>(
+    repo: &R,
+    user_id: i64,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<FailureReasonStats>, AppError> {
+    if start > end {
+        return Err(AppError::Validation("开始日期不能晚于结束日期".to_string()));
+    }
+    let filter = TaskFilter {
+        status: Some(TaskStatus::Failed),
+        ..Default::default()
+    };
+    let tasks = repo.list_tasks_in_range(user_id, start, end, &filter).await?;
+
+    let mut counts: HashMap<Option<String>, usize> = HashMap::new();
+    for row in tasks {
+        *counts.entry(row.task.status_reason).or_default() += 1;
+    }
+    let mut stats: Vec<FailureReasonStats> =
+        counts.into_iter().map(|(reason, count)| FailureReasonStats { reason, count }).collect();
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.reason.cmp(&b.reason)));
+    Ok(stats)
+}
@@ -0,0 +1,283 @@
+// 两步验证服务 (TOTP 绑定、恢复码与登录时的验证码校验)
+use crate::error::AppError;
+use crate::models::{
+    AuditAction, RecoveryCodesResponse, TotpEnrollmentResponse, TwoFactorStatus, User, UserRole,
+};
+use crate::repositories::{AuditRepository, TwoFactorRepository, UserRepository};
+use crate::utils::{password, random, totp};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+/// 身份验证器中显示的发行方名称
+pub const TOTP_ISSUER: &str = "考研学习助手";
+
+/// 每次生成的恢复码数量
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// 恢复码每段长度 (格式为 XXXXX-XXXXX)
+const RECOVERY_CODE_GROUP_LENGTH: usize = 5;
+
+/// 恢复码字符集 (去掉易混淆的 0/O、1/I)
+const RECOVERY_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// 登录时使用的第二因素
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecondFactor {
+    Totp,
+    RecoveryCode,
+}
+
+impl SecondFactor {
+    /// 审计记录中的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecondFactor::Totp => "totp",
+            SecondFactor::RecoveryCode => "recovery_code",
+        }
+    }
+}
+
+/// 生成一个恢复码
+fn generate_recovery_code() -> String {
+    let first = random::random_string(RECOVERY_CODE_ALPHABET, RECOVERY_CODE_GROUP_LENGTH);
+    let second = random::random_string(RECOVERY_CODE_ALPHABET, RECOVERY_CODE_GROUP_LENGTH);
+    format!("{}-{}", first, second)
+}
+
+/// 规范化用户输入的恢复码 (忽略空白、连字符和大小写)
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// 输入是否为 TOTP 验证码 (6 位数字)，否则按恢复码处理
+fn is_totp_code(code: &str) -> bool {
+    let digits: Vec<char> = code.chars().filter(|c| !c.is_whitespace()).collect();
+    digits.len() == totp::TOTP_DIGITS && digits.iter().all(|c| c.is_ascii_digit())
+}
+
+/// 生成并保存一组新的恢复码 (替换原有恢复码)，返回明文
+async fn issue_recovery_codes<R: TwoFactorRepository>(repo: &R, user_id: i64) -> Result<Vec<String>, AppError> {
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT).map(|_| generate_recovery_code()).collect();
+    let hashes = codes
+        .iter()
+        .map(|code| password::hash_password(&normalize_recovery_code(code)))
+        .collect::<Result<Vec<_>, _>>()?;
+    repo.replace_recovery_codes(user_id, &hashes).await?;
+    Ok(codes)
+}
+
+/// 用户是否已启用两步验证
+pub async fn is_enabled<R: TwoFactorRepository>(repo: &R, user_id: i64) -> Result<bool, AppError> {
+    Ok(repo.find_totp(user_id).await?.is_some_and(|t| t.is_enabled()))
+}
+
+/// 按安全策略，该用户是否必须启用两步验证
+pub fn is_required(user: &User, require_admin_two_factor: bool) -> bool {
+    require_admin_two_factor && user.role == UserRole::Admin
+}
+
+/// 检查用户是否满足两步验证策略 (被要求启用但尚未启用时拒绝)
+pub async fn check_policy<R: TwoFactorRepository>(
+    repo: &R,
+    user: &User,
+    require_admin_two_factor: bool,
+) -> Result<(), AppError> {
+    if is_required(user, require_admin_two_factor) && !is_enabled(repo, user.id).await? {
+        return Err(AppError::Forbidden("管理员账户需要先启用两步验证".to_string()));
+    }
+    Ok(())
+}
+
+/// 查询两步验证状态
+pub async fn get_status<R: TwoFactorRepository>(
+    repo: &R,
+    user: &User,
+    require_admin_two_factor: bool,
+) -> Result<TwoFactorStatus, AppError> {
+    let enabled_at = repo.find_totp(user.id).await?.and_then(|t| t.enabled_at);
+    let recovery_codes_remaining = match enabled_at {
+        Some(_) => repo.list_unused_recovery_codes(user.id).await?.len(),
+        None => 0,
+    };
+
+    Ok(TwoFactorStatus {
+        enabled: enabled_at.is_some(),
+        enabled_at,
+        recovery_codes_remaining,
+        required: is_required(user, require_admin_two_factor),
+    })
+}
+
+/// 开始绑定 TOTP：生成新密钥 (尚未启用，重复调用会替换未完成绑定的密钥)
+pub async fn begin_enrollment<R: TwoFactorRepository>(
+    repo: &R,
+    user: &User,
+) -> Result<TotpEnrollmentResponse, AppError> {
+    if is_enabled(repo, user.id).await? {
+        return Err(AppError::Conflict("已启用两步验证".to_string()));
+    }
+
+    let secret = totp::generate_secret();
+    repo.save_pending_totp(user.id, &secret, Utc::now()).await?;
+
+    Ok(TotpEnrollmentResponse {
+        provisioning_uri: totp::provisioning_uri(TOTP_ISSUER, &user.username, &secret),
+        secret,
+    })
+}
+
+/// 提交验证码完成绑定，返回恢复码
+pub async fn confirm_enrollment<R>(repo: &R, user_id: i64, code: &str) -> Result<RecoveryCodesResponse, AppError>
+where
+    R: TwoFactorRepository + AuditRepository,
+{
+    confirm_enrollment_at(repo, user_id, code, Utc::now()).await
+}
+
+/// 以指定时间完成绑定
+pub(crate) async fn confirm_enrollment_at<R>(
+    repo: &R,
+    user_id: i64,
+    code: &str,
+    now: DateTime<Utc>,
+) -> Result<RecoveryCodesResponse, AppError>
+where
+    R: TwoFactorRepository + AuditRepository,
+{
+    let pending = repo
+        .find_totp(user_id)
+        .await?
+        .ok_or_else(|| AppError::Validation("请先开始绑定两步验证".to_string()))?;
+    if pending.is_enabled() {
+        return Err(AppError::Conflict("已启用两步验证".to_string()));
+    }
+
+    let step = totp::verify_code(&pending.secret, code, now)
+        .ok_or_else(|| AppError::Validation("验证码错误".to_string()))?;
+    repo.enable_totp(user_id, now, step).await?;
+    let recovery_codes = issue_recovery_codes(repo, user_id).await?;
+
+    repo.record_audit_event(Some(user_id), AuditAction::TwoFactorEnable, Some(user_id), &json!({}), now)
+        .await?;
+
+    Ok(RecoveryCodesResponse { recovery_codes })
+}
+
+/// 关闭两步验证 (需要当前密码，被策略要求启用时不能关闭)
+pub async fn disable<R>(
+    repo: &R,
+    user: &User,
+    current_password: &str,
+    require_admin_two_factor: bool,
+) -> Result<(), AppError>
+where
+    R: TwoFactorRepository + AuditRepository,
+{
+    if !password::verify_password(current_password, &user.password) {
+        return Err(AppError::Validation("当前密码错误".to_string()));
+    }
+    if is_required(user, require_admin_two_factor) {
+        return Err(AppError::Forbidden("管理员账户必须启用两步验证".to_string()));
+    }
+    if !is_enabled(repo, user.id).await? {
+        return Err(AppError::Validation("未启用两步验证".to_string()));
+    }
+
+    repo.delete_totp(user.id).await?;
+    repo.record_audit_event(Some(user.id), AuditAction::TwoFactorDisable, Some(user.id), &json!({}), Utc::now())
+        .await
+}
+
+/// 重新生成恢复码 (需要当前的 TOTP 验证码，原有恢复码全部失效)
+pub async fn regenerate_recovery_codes<R>(repo: &R, user_id: i64, code: &str) -> Result<RecoveryCodesResponse, AppError>
+where
+    R: TwoFactorRepository + AuditRepository,
+{
+    regenerate_recovery_codes_at(repo, user_id, code, Utc::now()).await
+}
+
+/// 以指定时间重新生成恢复码
+pub(crate) async fn regenerate_recovery_codes_at<R>(
+    repo: &R,
+    user_id: i64,
+    code: &str,
+    now: DateTime<Utc>,
+) -> Result<RecoveryCodesResponse, AppError>
+where
+    R: TwoFactorRepository + AuditRepository,
+{
+    if !is_enabled(repo, user_id).await? {
+        return Err(AppError::Validation("未启用两步验证".to_string()));
+    }
+    if !is_totp_code(code) || verify_second_factor(repo, user_id, code, now).await?.is_none() {
+        return Err(AppError::Validation("验证码错误".to_string()));
+    }
+
+    let recovery_codes = issue_recovery_codes(repo, user_id).await?;
+    repo.record_audit_event(Some(user_id), AuditAction::TwoFactorRecoveryCodes, Some(user_id), &json!({}), now)
+        .await?;
+
+    Ok(RecoveryCodesResponse { recovery_codes })
+}
+
+/// 管理员重置用户的两步验证 (用户丢失身份验证器和恢复码时使用)
+pub async fn reset_for_user<R>(repo: &R, actor_id: i64, user_id: i64) -> Result<(), AppError>
+where
+    R: TwoFactorRepository + UserRepository + AuditRepository,
+{
+    let user = repo
+        .find_user(user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("用户不存在".to_string()))?;
+    if !is_enabled(repo, user_id).await? {
+        return Err(AppError::Validation("该用户未启用两步验证".to_string()));
+    }
+
+    repo.delete_totp(user_id).await?;
+    repo.record_audit_event(
+        Some(actor_id),
+        AuditAction::TwoFactorReset,
+        Some(user_id),
+        &json!({ "username": user.username }),
+        Utc::now(),
+    )
+    .await
+}
+
+/// 校验第二因素：6 位数字按 TOTP 验证码校验 (每个时间步只能使用一次)，
+/// 其余按恢复码校验 (使用后失效)
+pub(crate) async fn verify_second_factor<R: TwoFactorRepository>(
+    repo: &R,
+    user_id: i64,
+    code: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<SecondFactor>, AppError> {
+    let Some(user_totp) = repo.find_totp(user_id).await?.filter(|t| t.is_enabled()) else {
+        return Ok(None);
+    };
+
+    if is_totp_code(code) {
+        return Ok(match totp::verify_code(&user_totp.secret, code, now) {
+            Some(step) if repo.claim_totp_step(user_id, step).await? => Some(SecondFactor::Totp),
+            _ => None,
+        });
+    }
+
+    let normalized = normalize_recovery_code(code);
+    if normalized.len() != RECOVERY_CODE_GROUP_LENGTH * 2 {
+        return Ok(None);
+    }
+    for recovery_code in repo.list_unused_recovery_codes(user_id).await? {
+        if password::verify_password(&normalized, &recovery_code.code_hash) {
+            return Ok(repo
+                .use_recovery_code(recovery_code.id, now)
+                .await?
+                .then_some(SecondFactor::RecoveryCode));
+        }
+    }
+
+    Ok(None)
+}
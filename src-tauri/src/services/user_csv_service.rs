@@ -0,0 +1,262 @@
+// 用户批量导入与导出 (CSV)
+use crate::error::AppError;
+use crate::models::{
+    AuditAction, CreateUserInput, UserCredential, UserImportReport, UserImportRow, UserRole,
+};
+use crate::repositories::{AuditRepository, UserRepository};
+use crate::services::user_service;
+use crate::utils::{csv, password, random};
+use chrono::Utc;
+use serde_json::json;
+use std::collections::HashMap;
+
+/// 单次导入的最大行数
+pub const MAX_IMPORT_ROWS: usize = 500;
+
+/// 生成的初始密码长度
+pub const GENERATED_PASSWORD_LENGTH: usize = 12;
+
+/// 初始密码字符集 (去掉易混淆的 0/O/o、1/I/l)
+const PASSWORD_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnpqrstuvwxyz23456789";
+
+/// 导出的列 (用户名、昵称、角色可直接重新导入)
+const EXPORT_HEADER: [&str; 5] = ["username", "display_name", "role", "status", "created_at"];
+
+/// 导入时忽略的列 (导出文件中的只读信息)
+const IGNORED_COLUMNS: [&str; 3] = ["id", "status", "created_at"];
+
+/// 导入文件中的列
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Column {
+    Username,
+    DisplayName,
+    Role,
+    Ignored,
+}
+
+impl Column {
+    /// 解析表头 (不区分大小写，支持中文列名)
+    fn parse(header: &str) -> Option<Column> {
+        let header = header.trim().to_lowercase();
+        match header.as_str() {
+            "username" | "用户名" => Some(Column::Username),
+            "display_name" | "昵称" => Some(Column::DisplayName),
+            "role" | "角色" => Some(Column::Role),
+            h if IGNORED_COLUMNS.contains(&h) => Some(Column::Ignored),
+            _ => None,
+        }
+    }
+}
+
+/// 生成满足密码策略的随机初始密码
+fn generate_password() -> String {
+    loop {
+        let candidate = random::random_string(PASSWORD_ALPHABET, GENERATED_PASSWORD_LENGTH);
+        if password::validate_password_policy(&candidate).is_ok() {
+            return candidate;
+        }
+    }
+}
+
+/// 解析导入文件：第一行为表头且必须包含用户名列
+/// 昵称为空时使用用户名，角色为空时为普通用户
+fn parse_rows(content: &str) -> Result<Vec<UserImportRow>, AppError> {
+    let mut records = csv::parse(content)
+        .map_err(|line| AppError::Validation(format!("第 {} 行的引号未闭合", line)))?
+        .into_iter();
+
+    let (_, header) = records
+        .next()
+        .ok_or_else(|| AppError::Validation("导入文件为空".to_string()))?;
+    let columns = header
+        .iter()
+        .map(|h| Column::parse(h).ok_or_else(|| AppError::Validation(format!("未知的列: {}", h.trim()))))
+        .collect::<Result<Vec<_>, _>>()?;
+    if !columns.contains(&Column::Username) {
+        return Err(AppError::Validation("导入文件缺少 username 列".to_string()));
+    }
+
+    let rows: Vec<UserImportRow> = records
+        .map(|(line, fields)| {
+            let field = |column: Column| {
+                columns
+                    .iter()
+                    .position(|c| *c == column)
+                    .and_then(|i| fields.get(i))
+                    .map(|f| csv::unguard_formula(f.trim()).to_string())
+                    .unwrap_or_default()
+            };
+            let username = field(Column::Username);
+            let display_name = Some(field(Column::DisplayName))
+                .filter(|d| !d.is_empty())
+                .unwrap_or_else(|| username.clone());
+            let role = Some(field(Column::Role).to_lowercase())
+                .filter(|r| !r.is_empty())
+                .unwrap_or_else(|| UserRole::User.to_string());
+            let mut errors = Vec::new();
+            if fields.len() > columns.len() {
+                errors.push("列数多于表头".to_string());
+            }
+            UserImportRow {
+                line,
+                username,
+                display_name,
+                role,
+                errors,
+            }
+        })
+        .collect();
+
+    if rows.is_empty() {
+        return Err(AppError::Validation("导入文件中没有用户".to_string()));
+    }
+    if rows.len() > MAX_IMPORT_ROWS {
+        return Err(AppError::Validation(format!("单次最多导入 {} 个用户", MAX_IMPORT_ROWS)));
+    }
+
+    Ok(rows)
+}
+
+/// 校验每一行：用户名与昵称格式、角色、文件内重复，
+/// 以及与已有用户重复 (与创建用户时的唯一性检查一致)
+async fn validate_rows<R: UserRepository>(repo: &R, rows: &mut [UserImportRow]) -> Result<(), AppError> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for row in rows.iter_mut() {
+        if let Err(e) = user_service::validate_names(&row.username, &row.display_name) {
+            row.errors.push(e.message());
+        }
+        if let Err(e) = user_service::parse_role(&row.role) {
+            row.errors.push(e.message());
+        }
+        if row.username.is_empty() {
+            continue;
+        }
+
+        if let Some(first_line) = seen.get(&row.username) {
+            row.errors.push(format!("与第 {} 行的用户名重复", first_line));
+        } else {
+            seen.insert(row.username.clone(), row.line);
+            if repo.find_user_by_username(&row.username).await?.is_some() {
+                row.errors.push("用户名已存在".to_string());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 批量导入用户：试运行时只校验；否则在全部行通过校验后，
+/// 于同一事务中创建全部账户并生成初始密码 (导入的账户首次登录后必须修改密码)
+pub async fn import_users<R>(
+    repo: &R,
+    actor_id: i64,
+    content: &str,
+    dry_run: bool,
+) -> Result<UserImportReport, AppError>
+where
+    R: UserRepository + AuditRepository,
+{
+    let mut rows = parse_rows(content)?;
+    validate_rows(repo, &mut rows).await?;
+
+    let total = rows.len();
+    let valid = rows.iter().filter(|r| r.is_valid()).count();
+    let mut report = UserImportReport {
+        dry_run,
+        total,
+        valid,
+        created: 0,
+        rows,
+        credentials: Vec::new(),
+        credentials_csv: None,
+    };
+    if dry_run || valid < total {
+        return Ok(report);
+    }
+
+    let credentials: Vec<UserCredential> = report
+        .rows
+        .iter()
+        .map(|row| UserCredential {
+            username: row.username.clone(),
+            display_name: row.display_name.clone(),
+            role: row.role.clone(),
+            initial_password: generate_password(),
+        })
+        .collect();
+    let inputs = hash_credentials(credentials.clone()).await?;
+
+    let user_ids = repo.create_users(&inputs).await?;
+
+    // 账户已经提交，审计记录写入失败时只记录日志，否则生成的初始密码将无法再取回
+    let now = Utc::now();
+    for (user_id, credential) in user_ids.iter().zip(&credentials) {
+        let recorded = repo
+            .record_audit_event(
+                Some(actor_id),
+                AuditAction::UserCreate,
+                Some(*user_id),
+                &json!({ "username": credential.username, "role": credential.role, "source": "csv_import" }),
+                now,
+            )
+            .await;
+        if let Err(e) = recorded {
+            eprintln!("记录导入用户 {} 的审计事件失败: {}", user_id, e);
+        }
+    }
+
+    report.created = user_ids.len();
+    report.credentials_csv = Some(credentials_csv(&credentials));
+    report.credentials = credentials;
+    Ok(report)
+}
+
+/// 为初始密码计算哈希 (哈希计算耗时，放到阻塞线程池中执行)
+async fn hash_credentials(credentials: Vec<UserCredential>) -> Result<Vec<CreateUserInput>, AppError> {
+    tokio::task::spawn_blocking(move || {
+        credentials
+            .into_iter()
+            .map(|c| {
+                Ok(CreateUserInput {
+                    password: password::hash_password(&c.initial_password)?,
+                    username: c.username,
+                    display_name: c.display_name,
+                    role: c.role,
+                })
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| AppError::Validation(format!("生成初始密码失败: {}", e)))?
+}
+
+/// 初始密码清单 CSV
+fn credentials_csv(credentials: &[UserCredential]) -> String {
+    let header = vec!["username", "display_name", "role", "initial_password"];
+    let rows = credentials.iter().map(|c| {
+        vec![
+            c.username.as_str(),
+            c.display_name.as_str(),
+            c.role.as_str(),
+            c.initial_password.as_str(),
+        ]
+    });
+    csv::write(std::iter::once(header).chain(rows))
+}
+
+/// 导出用户列表为 CSV (按创建时间倒序，不包含密码)
+pub async fn export_users<R: UserRepository>(repo: &R) -> Result<String, AppError> {
+    let users = repo.list_users().await?;
+    let rows = users.into_iter().map(|u| {
+        let status = if u.is_active() { "active" } else { "deactivated" };
+        vec![
+            u.username,
+            u.display_name,
+            u.role.to_string(),
+            status.to_string(),
+            u.created_at.to_rfc3339(),
+        ]
+    });
+    let header = EXPORT_HEADER.iter().map(|h| h.to_string()).collect();
+    Ok(csv::write(std::iter::once(header).chain(rows)))
+}
@@ -1,90 +1,260 @@
 // 用户管理服务
-use crate::db::get_pool;
-use crate::models::{User, UserResponse, CreateUserInput};
+use crate::error::AppError;
+use crate::models::{AuditAction, CreateUserInput, User, UserArchive, UserArchiveResponse, UserResponse, UserRole};
+use crate::repositories::{
+    AuditRepository, MentorRepository, SessionRepository, SubjectRepository, UserRepository,
+};
+use crate::services::subject_service;
+use crate::utils::password;
+use chrono::Utc;
+use serde_json::json;
+use std::path::Path;
 
-/// 获取所有用户
-pub async fn get_all_users() -> Result<Vec<UserResponse>, String> {
-    let pool = get_pool();
-    
-    let users: Vec<User> = sqlx::query_as(
-        "SELECT id, username, password, display_name, role, created_at, updated_at 
-         FROM users ORDER BY created_at DESC"
-    )
-    .fetch_all(pool)
-    .await
-    .map_err(|e| format!("查询用户失败: {}", e))?;
+/// 用户名与昵称最大长度 (与数据库字段一致)
+pub const MAX_NAME_LENGTH: usize = 50;
+
+/// 解析角色名称，未知角色返回校验错误
+pub fn parse_role(role: &str) -> Result<UserRole, AppError> {
+    UserRole::parse(role).ok_or_else(|| AppError::Validation(format!("未知的角色: {}", role)))
+}
+
+/// 检查用户名与昵称 (自助注册和批量导入使用)
+pub(crate) fn validate_names(username: &str, display_name: &str) -> Result<(), AppError> {
+    if username.is_empty() || username.chars().count() > MAX_NAME_LENGTH {
+        return Err(AppError::Validation(format!("用户名长度需在 1-{} 个字符之间", MAX_NAME_LENGTH)));
+    }
+
+    if username.chars().any(char::is_whitespace) {
+        return Err(AppError::Validation("用户名不能包含空白字符".to_string()));
+    }
+
+    if display_name.is_empty() || display_name.chars().count() > MAX_NAME_LENGTH {
+        return Err(AppError::Validation(format!("昵称长度需在 1-{} 个字符之间", MAX_NAME_LENGTH)));
+    }
 
+    Ok(())
+}
+
+/// 获取所有用户
+pub async fn get_all_users<R: UserRepository>(repo: &R) -> Result<Vec<UserResponse>, AppError> {
+    let users = repo.list_users().await?;
     Ok(users.into_iter().map(UserResponse::from).collect())
 }
 
-/// 创建用户
-pub async fn create_user(input: CreateUserInput) -> Result<UserResponse, String> {
-    let pool = get_pool();
-    
+/// 创建用户 (操作者为空表示通过邀请码自行注册)
+pub async fn create_user<R>(repo: &R, actor_id: Option<i64>, input: CreateUserInput) -> Result<UserResponse, AppError>
+where
+    R: UserRepository + SubjectRepository + AuditRepository,
+{
+    parse_role(&input.role)?;
+
     // 检查用户名是否已存在
-    let exists: Option<(i64,)> = sqlx::query_as(
-        "SELECT id FROM users WHERE username = ?"
+    if repo.find_user_by_username(&input.username).await?.is_some() {
+        return Err(AppError::Conflict("用户名已存在".to_string()));
+    }
+
+    // 检查密码策略并哈希密码
+    password::validate_password_policy(&input.password)?;
+    let input = CreateUserInput {
+        password: password::hash_password(&input.password)?,
+        ..input
+    };
+
+    // 创建用户
+    let user_id = repo.create_user(&input).await?;
+
+    // 写入默认科目
+    subject_service::seed_default_subjects(repo, user_id).await?;
+
+    // 查询新创建的用户
+    let user = repo
+        .find_user(user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("用户不存在".to_string()))?;
+    repo.record_audit_event(
+        actor_id,
+        AuditAction::UserCreate,
+        Some(user_id),
+        &json!({ "username": user.username, "role": user.role }),
+        Utc::now(),
     )
-    .bind(&input.username)
-    .fetch_optional(pool)
-    .await
-    .map_err(|e| format!("查询失败: {}", e))?;
+    .await?;
+
+    Ok(UserResponse::from(user))
+}
 
-    if exists.is_some() {
-        return Err("用户名已存在".to_string());
+/// 查询被管理的用户 (不能对自己执行停用或删除)
+async fn find_managed_user<R: UserRepository>(repo: &R, actor_id: i64, user_id: i64) -> Result<User, AppError> {
+    if actor_id == user_id {
+        return Err(AppError::Validation("不能对自己执行该操作".to_string()));
     }
 
-    // 创建用户
-    let result = sqlx::query(
-        "INSERT INTO users (username, password, display_name, role) VALUES (?, ?, ?, ?)"
+    repo.find_user(user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("用户不存在".to_string()))
+}
+
+/// 停用用户 (禁止登录并注销全部会话，保留全部数据)
+pub async fn deactivate_user<R>(repo: &R, actor_id: i64, user_id: i64) -> Result<UserResponse, AppError>
+where
+    R: UserRepository + SessionRepository + AuditRepository,
+{
+    let mut user = find_managed_user(repo, actor_id, user_id).await?;
+    if !user.is_active() {
+        return Err(AppError::Validation("用户已停用".to_string()));
+    }
+
+    let now = Utc::now();
+    repo.set_deactivated(user_id, Some(now)).await?;
+    repo.delete_user_sessions(user_id, None).await?;
+    repo.record_audit_event(
+        Some(actor_id),
+        AuditAction::UserDeactivate,
+        Some(user_id),
+        &json!({ "username": user.username }),
+        now,
     )
-    .bind(&input.username)
-    .bind(&input.password)
-    .bind(&input.display_name)
-    .bind(&input.role)
-    .execute(pool)
-    .await
-    .map_err(|e| format!("创建用户失败: {}", e))?;
+    .await?;
 
-    let user_id = result.last_insert_id() as i64;
+    user.deactivated_at = Some(now);
+    Ok(UserResponse::from(user))
+}
 
-    // 查询新创建的用户
-    let user: User = sqlx::query_as(
-        "SELECT id, username, password, display_name, role, created_at, updated_at 
-         FROM users WHERE id = ?"
+/// 重新启用已停用的用户
+pub async fn reactivate_user<R>(repo: &R, actor_id: i64, user_id: i64) -> Result<UserResponse, AppError>
+where
+    R: UserRepository + AuditRepository,
+{
+    let mut user = find_managed_user(repo, actor_id, user_id).await?;
+    if user.is_active() {
+        return Err(AppError::Validation("用户未停用".to_string()));
+    }
+
+    let now = Utc::now();
+    repo.set_deactivated(user_id, None).await?;
+    repo.record_audit_event(
+        Some(actor_id),
+        AuditAction::UserReactivate,
+        Some(user_id),
+        &json!({ "username": user.username }),
+        now,
     )
-    .bind(user_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| format!("查询用户失败: {}", e))?;
+    .await?;
 
+    user.deactivated_at = None;
     Ok(UserResponse::from(user))
 }
 
-/// 删除用户
-pub async fn delete_user(user_id: i64) -> Result<(), String> {
-    let pool = get_pool();
-    
-    // 不允许删除自己
-    sqlx::query("DELETE FROM users WHERE id = ?")
-        .bind(user_id)
-        .execute(pool)
-        .await
-        .map_err(|e| format!("删除用户失败: {}", e))?;
+/// 导出用户的全部数据到归档目录，返回归档信息
+pub async fn export_user_archive<R: UserRepository>(
+    repo: &R,
+    user: &User,
+    archive_dir: &Path,
+) -> Result<UserArchiveResponse, AppError> {
+    let now = Utc::now();
+    let archive = UserArchive {
+        exported_at: now,
+        user: UserResponse::from(user.clone()),
+        data: repo.load_user_data(user.id).await?,
+    };
 
-    Ok(())
+    let content = serde_json::to_string_pretty(&archive).map_err(AppError::config("序列化用户数据失败"))?;
+    std::fs::create_dir_all(archive_dir).map_err(AppError::config("创建归档目录失败"))?;
+    // 文件名只保留用户名中的安全字符
+    let safe_name: String = user
+        .username
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect();
+    let path = archive_dir.join(format!("user-{}-{}-{}.json", user.id, safe_name, now.format("%Y%m%d%H%M%S")));
+    std::fs::write(&path, content).map_err(AppError::config("写入用户数据归档失败"))?;
+
+    Ok(UserArchiveResponse {
+        user_id: user.id,
+        username: user.username.clone(),
+        path,
+        tasks: archive.data.tasks.len(),
+        pomodoros: archive.data.pomodoros.len(),
+        reviews: archive.data.reviews.len(),
+        countdowns: archive.data.countdowns.len(),
+    })
 }
 
-/// 重置用户密码
-pub async fn reset_user_password(user_id: i64, new_password: &str) -> Result<(), String> {
-    let pool = get_pool();
-    
-    sqlx::query("UPDATE users SET password = ? WHERE id = ?")
-        .bind(new_password)
-        .bind(user_id)
-        .execute(pool)
+/// 永久删除已停用的用户 (删除前导出全部数据，归档写入失败时不删除)
+pub async fn delete_user<R>(
+    repo: &R,
+    actor_id: i64,
+    user_id: i64,
+    archive_dir: &Path,
+) -> Result<UserArchiveResponse, AppError>
+where
+    R: UserRepository + AuditRepository,
+{
+    let user = find_managed_user(repo, actor_id, user_id).await?;
+    if user.is_active() {
+        return Err(AppError::Validation("请先停用该用户再删除".to_string()));
+    }
+
+    let archive = export_user_archive(repo, &user, archive_dir).await?;
+    repo.record_audit_event(
+        Some(actor_id),
+        AuditAction::UserDelete,
+        Some(user_id),
+        &json!({ "username": user.username, "archive": archive.path }),
+        Utc::now(),
+    )
+    .await?;
+    repo.delete_user(user_id).await?;
+
+    Ok(archive)
+}
+
+/// 重置用户密码 (注销该用户的全部会话)
+pub async fn reset_user_password<R>(repo: &R, actor_id: i64, user_id: i64, new_password: &str) -> Result<(), AppError>
+where
+    R: UserRepository + SessionRepository + AuditRepository,
+{
+    password::validate_password_policy(new_password)?;
+    if repo.find_user(user_id).await?.is_none() {
+        return Err(AppError::NotFound("用户不存在".to_string()));
+    }
+    let hash = password::hash_password(new_password)?;
+    repo.update_password(user_id, &hash).await?;
+
+    repo.delete_user_sessions(user_id, None).await?;
+    repo.record_audit_event(Some(actor_id), AuditAction::PasswordReset, Some(user_id), &json!({}), Utc::now())
         .await
-        .map_err(|e| format!("重置密码失败: {}", e))?;
+}
 
-    Ok(())
+/// 修改用户角色 (不再是导师时清除其学生分配)
+pub async fn set_user_role<R>(repo: &R, actor_id: i64, user_id: i64, role: &str) -> Result<UserResponse, AppError>
+where
+    R: UserRepository + MentorRepository + AuditRepository,
+{
+    let role = parse_role(role)?;
+    let previous = repo
+        .find_user(user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("用户不存在".to_string()))?
+        .role;
+
+    repo.update_role(user_id, &role).await?;
+    if role != UserRole::Mentor {
+        repo.delete_mentor_students(user_id).await?;
+    }
+    repo.record_audit_event(
+        Some(actor_id),
+        AuditAction::UserRoleChange,
+        Some(user_id),
+        &json!({ "from": previous, "to": role }),
+        Utc::now(),
+    )
+    .await?;
+
+    let user = repo
+        .find_user(user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("用户不存在".to_string()))?;
+
+    Ok(UserResponse::from(user))
 }
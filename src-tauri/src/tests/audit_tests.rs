@@ -0,0 +1,270 @@
+// 审计日志属性测试
+
+use super::block_on;
+use crate::config::AppConfig;
+use crate::models::{AuditAction, AuditEventFilter, AuditLogQuery, CreateUserInput, UserResponse};
+use crate::repositories::{AuditRepository, MemoryRepository, SessionRepository};
+use crate::services::{audit_service, auth_service, user_service};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use proptest::prelude::*;
+use serde_json::json;
+
+const PASSWORD: &str = "password1";
+
+fn create_user(repo: &MemoryRepository, actor_id: Option<i64>, username: &str, role: &str) -> UserResponse {
+    block_on(user_service::create_user(
+        repo,
+        actor_id,
+        CreateUserInput {
+            username: username.to_string(),
+            password: PASSWORD.to_string(),
+            display_name: username.to_string(),
+            role: role.to_string(),
+        },
+    ))
+    .unwrap()
+}
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 1, 1, 8, 0, 0).unwrap()
+}
+
+/// 在指定时间写入一条审计记录
+fn record_at(repo: &MemoryRepository, action: AuditAction, target_user_id: Option<i64>, at: DateTime<Utc>) {
+    block_on(repo.record_audit_event(Some(1), action, target_user_id, &json!({}), at)).unwrap();
+}
+
+fn audit_log(repo: &MemoryRepository, query: AuditLogQuery) -> crate::models::AuditLogPage {
+    block_on(audit_service::get_audit_log(repo, query)).unwrap()
+}
+
+/// 按操作者查询的操作名称 (按时间倒序)
+fn actions_by(repo: &MemoryRepository, actor_id: i64) -> Vec<String> {
+    let filter = AuditEventFilter {
+        actor_id: Some(actor_id),
+        ..Default::default()
+    };
+    block_on(repo.list_audit_events(&filter, 100, 0))
+        .unwrap()
+        .into_iter()
+        .map(|e| e.action)
+        .collect()
+}
+
+proptest! {
+    /// 逐页查询覆盖全部记录，按时间倒序且不重复
+    #[test]
+    fn test_pagination_covers_all_events(count in 0usize..40, page_size in 1i64..15) {
+        let repo = MemoryRepository::new();
+        for i in 0..count {
+            record_at(&repo, AuditAction::SessionLogin, None, start() + Duration::minutes(i as i64));
+        }
+
+        let mut ids = Vec::new();
+        let mut page = 1;
+        loop {
+            let result = audit_log(&repo, AuditLogQuery {
+                page: Some(page),
+                page_size: Some(page_size),
+                ..Default::default()
+            });
+            prop_assert_eq!(result.total, count as i64);
+            prop_assert!(result.events.len() as i64 <= page_size);
+            if result.events.is_empty() {
+                break;
+            }
+            ids.extend(result.events.iter().map(|e| e.id));
+            page += 1;
+        }
+
+        prop_assert_eq!(ids.len(), count);
+        prop_assert!(ids.windows(2).all(|w| w[0] > w[1]));
+    }
+
+    /// 只清理超过保留天数的记录，并记录清理操作
+    #[test]
+    fn test_retention_purges_old_events(
+        ages in proptest::collection::vec(0i64..200, 0..20),
+        retention_days in 1u32..180
+    ) {
+        let repo = MemoryRepository::new();
+        let now = start();
+        for age in &ages {
+            record_at(&repo, AuditAction::SessionLogin, None, now - Duration::days(*age) - Duration::hours(1));
+        }
+
+        let expired = ages.iter().filter(|age| **age >= i64::from(retention_days)).count();
+        let purged = block_on(audit_service::purge_expired_events(&repo, retention_days, now)).unwrap();
+        prop_assert_eq!(purged as usize, expired);
+
+        let remaining = block_on(repo.count_audit_events(&AuditEventFilter::default())).unwrap();
+        let purge_events = block_on(repo.count_audit_events(&AuditEventFilter {
+            action: Some(AuditAction::AuditPurge),
+            ..Default::default()
+        }))
+        .unwrap();
+        prop_assert_eq!(purge_events, i64::from(expired > 0));
+        prop_assert_eq!(remaining as usize, ages.len() - expired + purge_events as usize);
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_action_names_roundtrip() {
+        for action in AuditAction::ALL {
+            assert_eq!(AuditAction::parse(action.as_str()), Some(action));
+            assert_eq!(serde_json::to_value(action).unwrap(), action.as_str());
+        }
+        assert_eq!(AuditAction::parse("user.unknown"), None);
+    }
+
+    #[test]
+    fn test_admin_actions_recorded() {
+        let repo = MemoryRepository::new();
+        let admin = create_user(&repo, None, "admin", "admin");
+        let student = create_user(&repo, Some(admin.id), "student", "user");
+        block_on(user_service::reset_user_password(&repo, admin.id, student.id, "newpass99")).unwrap();
+        block_on(user_service::set_user_role(&repo, admin.id, student.id, "mentor")).unwrap();
+        block_on(auth_service::unlock_account(&repo, admin.id, "student")).unwrap();
+
+        assert_eq!(
+            actions_by(&repo, admin.id),
+            vec!["account.unlock", "user.role_change", "password.reset", "user.create"]
+        );
+
+        let page = audit_log(&repo, AuditLogQuery {
+            action: Some("user.role_change".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(page.total, 1);
+        let event = &page.events[0];
+        assert_eq!(event.actor_username.as_deref(), Some("admin"));
+        assert_eq!(event.target_username.as_deref(), Some("student"));
+        assert_eq!(event.metadata, Some(json!({ "from": "user", "to": "mentor" })));
+    }
+
+    #[test]
+    fn test_session_actions_recorded() {
+        let repo = MemoryRepository::new();
+        let student = create_user(&repo, None, "student", "user");
+        let current = block_on(auth_service::login(&repo, "student", PASSWORD))
+            .unwrap()
+            .session()
+            .unwrap()
+            .session_token;
+        let other = block_on(auth_service::login(&repo, "student", PASSWORD))
+            .unwrap()
+            .session()
+            .unwrap()
+            .session_token;
+        let other_id = block_on(repo.find_session(&other)).unwrap().unwrap().id;
+
+        block_on(auth_service::revoke_session(&repo, student.id, other_id)).unwrap();
+        // 已不存在的会话不再记录
+        block_on(auth_service::revoke_session(&repo, student.id, other_id)).unwrap();
+        block_on(auth_service::revoke_other_sessions(&repo, student.id, &current)).unwrap();
+        block_on(auth_service::change_password(&repo, student.id, &current, PASSWORD, "newpass99")).unwrap();
+        block_on(auth_service::logout(&repo, &current)).unwrap();
+        block_on(auth_service::logout(&repo, &current)).unwrap();
+
+        assert_eq!(
+            actions_by(&repo, student.id),
+            vec![
+                "session.logout",
+                "password.change",
+                "session.revoke_others",
+                "session.revoke",
+                "session.login",
+                "session.login",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_failed_login_not_audited() {
+        let repo = MemoryRepository::new();
+        let student = create_user(&repo, None, "student", "user");
+        assert!(block_on(auth_service::login(&repo, "student", "wrong-password1")).is_err());
+        assert!(actions_by(&repo, student.id).is_empty());
+    }
+
+    #[test]
+    fn test_filter_by_target_and_time_range() {
+        let repo = MemoryRepository::new();
+        for day in 0..5 {
+            record_at(&repo, AuditAction::PasswordReset, Some(2), start() + Duration::days(day));
+            record_at(&repo, AuditAction::PasswordReset, Some(3), start() + Duration::days(day));
+        }
+
+        let page = audit_log(&repo, AuditLogQuery {
+            target_user_id: Some(2),
+            since: Some(start() + Duration::days(1)),
+            until: Some(start() + Duration::days(3)),
+            ..Default::default()
+        });
+        assert_eq!(page.total, 2);
+        assert!(page.events.iter().all(|e| e.target_user_id == Some(2)));
+        assert_eq!(page.events[0].created_at, start() + Duration::days(2));
+    }
+
+    #[test]
+    fn test_invalid_query_rejected() {
+        let repo = MemoryRepository::new();
+        let err = block_on(audit_service::get_audit_log(&repo, AuditLogQuery {
+            action: Some("user.unknown".to_string()),
+            ..Default::default()
+        }))
+        .unwrap_err();
+        assert_eq!(err.code(), "validation");
+
+        let err = block_on(audit_service::get_audit_log(&repo, AuditLogQuery {
+            since: Some(start()),
+            until: Some(start()),
+            ..Default::default()
+        }))
+        .unwrap_err();
+        assert_eq!(err.code(), "validation");
+    }
+
+    #[test]
+    fn test_page_size_clamped() {
+        let repo = MemoryRepository::new();
+        let page = audit_log(&repo, AuditLogQuery {
+            page: Some(0),
+            page_size: Some(10_000),
+            ..Default::default()
+        });
+        assert_eq!(page.page, 1);
+        assert_eq!(page.page_size, audit_service::MAX_AUDIT_PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_page_out_of_range_rejected() {
+        let repo = MemoryRepository::new();
+        let err = block_on(audit_service::get_audit_log(&repo, AuditLogQuery {
+            page: Some(i64::MAX),
+            page_size: Some(2),
+            ..Default::default()
+        }))
+        .unwrap_err();
+        assert_eq!(err.code(), "validation");
+    }
+
+    #[test]
+    fn test_zero_retention_keeps_everything() {
+        let repo = MemoryRepository::new();
+        record_at(&repo, AuditAction::SessionLogin, None, start() - Duration::days(10_000));
+        assert_eq!(block_on(audit_service::purge_expired_events(&repo, 0, start())).unwrap(), 0);
+        assert_eq!(block_on(audit_service::purge_expired_events(&repo, u32::MAX, start())).unwrap(), 0);
+        assert_eq!(block_on(repo.count_audit_events(&AuditEventFilter::default())).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_retention_defaults_when_missing_from_config() {
+        let config: AppConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.audit.retention_days, 365);
+    }
+}
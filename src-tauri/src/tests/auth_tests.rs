@@ -2,6 +2,11 @@
 // **Feature: study-planner, Property 17: 登录验证正确性**
 // **Validates: Requirements 12.2, 12.3**
 
+use super::block_on;
+use crate::error::AppError;
+use crate::models::{CreateUserInput, LoginResult};
+use crate::repositories::{MemoryRepository, UserRepository};
+use crate::services::{auth_service, user_service};
 use proptest::prelude::*;
 
 /// 生成有效的用户名 (3-20 个字母数字字符)
@@ -9,14 +14,31 @@ fn valid_username_strategy() -> impl Strategy<Value = String> {
     "[a-zA-Z][a-zA-Z0-9]{2,19}".prop_map(|s| s)
 }
 
-/// 生成有效的密码 (6-50 个字符)
+/// 生成有效的密码 (8-42 个字符，包含字母和数字)
 fn valid_password_strategy() -> impl Strategy<Value = String> {
-    "[a-zA-Z0-9!@#$%^&*]{6,50}".prop_map(|s| s)
+    "[a-zA-Z][a-zA-Z0-9!@#$%^&*]{6,40}[0-9]".prop_map(|s| s)
 }
 
-/// 密码验证函数 (与 auth_service 中的逻辑一致)
-fn verify_password(input: &str, stored: &str) -> bool {
-    input == stored
+/// 创建只包含一个用户的内存仓储
+fn repo_with_user(username: &str, password: &str) -> MemoryRepository {
+    let repo = MemoryRepository::new();
+    block_on(user_service::create_user(
+        &repo,
+        None,
+        CreateUserInput {
+            username: username.to_string(),
+            password: password.to_string(),
+            display_name: username.to_string(),
+            role: "user".to_string(),
+        },
+    ))
+    .unwrap();
+    repo
+}
+
+/// 使用给定凭据登录是否成功
+fn login_succeeds(repo: &MemoryRepository, username: &str, password: &str) -> bool {
+    block_on(auth_service::login(repo, username, password)).is_ok()
 }
 
 proptest! {
@@ -28,30 +50,40 @@ proptest! {
         correct_password in valid_password_strategy(),
         wrong_password in valid_password_strategy().prop_filter("不同的密码", |p| p.len() >= 6)
     ) {
+        let repo = repo_with_user(&username, &correct_password);
+
         // 正确密码应该验证成功
-        prop_assert!(verify_password(&correct_password, &correct_password));
-        
+        prop_assert!(login_succeeds(&repo, &username, &correct_password));
+
         // 错误密码应该验证失败 (除非碰巧相同)
         if wrong_password != correct_password {
-            prop_assert!(!verify_password(&wrong_password, &correct_password));
+            let err = block_on(auth_service::login(&repo, &username, &wrong_password)).unwrap_err();
+            prop_assert_eq!(err.code(), "unauthorized");
         }
     }
 
     /// 空密码应该验证失败
     #[test]
     fn test_empty_password_fails(
+        username in valid_username_strategy(),
         stored_password in valid_password_strategy()
     ) {
-        prop_assert!(!verify_password("", &stored_password));
+        let repo = repo_with_user(&username, &stored_password);
+        prop_assert!(!login_succeeds(&repo, &username, ""));
     }
 
-    /// 密码验证是对称的
+    /// 登录后返回的会话令牌可以通过验证
     #[test]
     fn test_password_verification_symmetric(
+        username in valid_username_strategy(),
         password in valid_password_strategy()
     ) {
+        let repo = repo_with_user(&username, &password);
+
         // 相同密码应该总是验证成功
-        prop_assert!(verify_password(&password, &password));
+        let login = block_on(auth_service::login(&repo, &username, &password)).unwrap().session().unwrap();
+        let user = block_on(auth_service::validate_session(&repo, &login.session_token)).unwrap();
+        prop_assert_eq!(user.username, username);
     }
 }
 
@@ -61,21 +93,62 @@ mod unit_tests {
 
     #[test]
     fn test_correct_password() {
-        assert!(verify_password("admin123", "admin123"));
+        let repo = repo_with_user("admin", "admin123");
+        assert!(login_succeeds(&repo, "admin", "admin123"));
     }
 
     #[test]
     fn test_wrong_password() {
-        assert!(!verify_password("wrong", "admin123"));
+        let repo = repo_with_user("admin", "admin123");
+        assert!(!login_succeeds(&repo, "admin", "wrong"));
     }
 
     #[test]
     fn test_empty_input() {
-        assert!(!verify_password("", "admin123"));
+        let repo = repo_with_user("admin", "admin123");
+        assert!(!login_succeeds(&repo, "admin", ""));
     }
 
     #[test]
     fn test_case_sensitive() {
-        assert!(!verify_password("Admin123", "admin123"));
+        let repo = repo_with_user("admin", "admin123");
+        assert!(!login_succeeds(&repo, "admin", "Admin123"));
+    }
+
+    #[test]
+    fn test_unknown_user() {
+        let repo = repo_with_user("admin", "admin123");
+        assert!(!login_succeeds(&repo, "nobody", "admin123"));
+        assert!(!login_succeeds(&repo, "nobody", "dummy-password-0"));
+    }
+
+    #[test]
+    fn test_unknown_user_error_matches_wrong_password() {
+        let repo = repo_with_user("admin", "admin123");
+        let error = |username: &str| {
+            let err = block_on(auth_service::login(&repo, username, "wrong123")).unwrap_err();
+            (err.code(), err.message())
+        };
+        assert_eq!(error("nobody"), error("admin"));
+    }
+
+    #[test]
+    fn test_initial_password_must_be_changed() {
+        let repo = repo_with_user("admin", auth_service::INITIAL_ADMIN_PASSWORD);
+        let Ok(LoginResult::Authenticated(response)) = block_on(auth_service::login(&repo, "admin", "admin123")) else {
+            panic!("使用初始密码应能登录");
+        };
+        assert!(response.user.must_change_password);
+        let user = block_on(repo.find_user(response.user.id)).unwrap().unwrap();
+        assert!(matches!(auth_service::require_password_changed(&user), Err(AppError::Forbidden(_))));
+
+        let token = &response.session_token;
+        let change = |new: &str| block_on(auth_service::change_password(&repo, user.id, token, "admin123", new));
+        assert!(matches!(change("admin123"), Err(AppError::Validation(_))));
+        change("NewPassw0rd").unwrap();
+        let user = block_on(repo.find_user(user.id)).unwrap().unwrap();
+        assert!(auth_service::require_password_changed(&user).is_ok());
+        assert!(login_succeeds(&repo, "admin", "NewPassw0rd"));
+        assert!(!block_on(repo.find_user(user.id)).unwrap().unwrap().must_change_password);
     }
 }
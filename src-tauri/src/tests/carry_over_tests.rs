@@ -0,0 +1,280 @@
+// 未完成任务顺延属性测试
+
+use super::block_on;
+use crate::error::AppError;
+use crate::models::{
+    CarryOverMode, CarryOverReport, CreateTaskInput, StudyWindow, TaskFilter, TaskResponse, TaskStatus,
+};
+use crate::repositories::{MemoryRepository, TaskRepository};
+use crate::services::{carry_over_service, schedule_service, task_service, task_status_service};
+use chrono::{NaiveDate, NaiveTime};
+use proptest::prelude::*;
+use std::collections::BTreeSet;
+
+const USER_ID: i64 = 1;
+
+fn from_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 3, 3).unwrap()
+}
+
+fn to_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 3, 4).unwrap()
+}
+
+fn time(h: u32, m: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(h, m, 0).unwrap()
+}
+
+fn create(repo: &MemoryRepository, date: NaiveDate, start: NaiveTime, end: NaiveTime) -> TaskResponse {
+    block_on(task_service::create_task(
+        repo,
+        USER_ID,
+        CreateTaskInput {
+            subject_id: None,
+            task_date: date,
+            start_time: start,
+            end_time: end,
+            content: format!("任务 {}", start.format("%H:%M")),
+            alarm_enabled: None,
+            alarm_time: None,
+            priority: None,
+        },
+        &StudyWindow::default(),
+    ))
+    .unwrap()
+}
+
+fn carry_over(repo: &MemoryRepository, mode: CarryOverMode) -> Result<CarryOverReport, AppError> {
+    block_on(carry_over_service::carry_over_tasks(
+        repo,
+        USER_ID,
+        from_date(),
+        to_date(),
+        mode,
+        &StudyWindow::default(),
+    ))
+}
+
+fn find(repo: &MemoryRepository, task_id: i64) -> crate::models::Task {
+    block_on(repo.find_task(USER_ID, task_id)).unwrap().unwrap()
+}
+
+fn mode_strategy() -> impl Strategy<Value = CarryOverMode> {
+    prop_oneof![Just(CarryOverMode::Move), Just(CarryOverMode::Copy)]
+}
+
+proptest! {
+    /// 每个未完成任务要么顺延 (原任务状态随方式变化，新任务指向原任务且不与目标日期的任务重叠)，要么保持不变
+    #[test]
+    fn test_carry_over_accounts_for_every_pending_task(
+        from_hours in proptest::collection::btree_set(7u32..21, 1..10),
+        to_hours in proptest::collection::btree_set(7u32..21, 0..14),
+        mode in mode_strategy(),
+    ) {
+        let repo = MemoryRepository::new();
+        let pending: BTreeSet<i64> = from_hours
+            .iter()
+            .map(|h| create(&repo, from_date(), time(*h, 0), time(*h + 1, 0)).id)
+            .collect();
+        for h in &to_hours {
+            create(&repo, to_date(), time(*h, 0), time(*h + 1, 0));
+        }
+
+        let report = carry_over(&repo, mode).unwrap();
+
+        let handled: BTreeSet<i64> = report
+            .carried
+            .iter()
+            .filter_map(|t| t.carried_from_id)
+            .chain(report.skipped.iter().map(|t| t.id))
+            .collect();
+        prop_assert_eq!(&handled, &pending);
+        prop_assert_eq!(report.carried.len() + report.skipped.len(), pending.len());
+
+        for task in &report.carried {
+            prop_assert_eq!(task.task_date, to_date());
+            prop_assert_eq!(task.status.as_str(), "pending");
+            prop_assert!(schedule_service::preference_warnings(&StudyWindow::default(), task.start_time, task.end_time)
+                .is_empty());
+            let original = find(&repo, task.carried_from_id.unwrap());
+            prop_assert_eq!(original.status, mode.original_status());
+            prop_assert_eq!(task.end_time - task.start_time, original.end_time - original.start_time);
+        }
+        for task in &report.skipped {
+            prop_assert_eq!(find(&repo, task.id).status, TaskStatus::Pending);
+        }
+
+        let target = block_on(repo.list_tasks_by_date(USER_ID, to_date())).unwrap();
+        for task in &target {
+            let conflicts = schedule_service::find_conflicts(&target, task.start_time, task.end_time, Some(task.id));
+            prop_assert!(conflicts.is_empty(), "任务 {} 与 {:?} 重叠", task.id, conflicts);
+        }
+    }
+
+    /// 再次顺延同一天不会重复顺延
+    #[test]
+    fn test_carry_over_is_idempotent(
+        from_hours in proptest::collection::btree_set(7u32..21, 1..8),
+        mode in mode_strategy(),
+    ) {
+        let repo = MemoryRepository::new();
+        for h in &from_hours {
+            create(&repo, from_date(), time(*h, 0), time(*h + 1, 0));
+        }
+
+        let first = carry_over(&repo, mode).unwrap();
+        let second = carry_over(&repo, mode).unwrap();
+        prop_assert!(second.carried.is_empty());
+        prop_assert_eq!(second.skipped.len(), first.skipped.len());
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_original_time_when_free() {
+        let repo = MemoryRepository::new();
+        let task = create(&repo, from_date(), time(9, 0), time(10, 0));
+
+        let report = carry_over(&repo, CarryOverMode::Move).unwrap();
+        assert_eq!(report.carried.len(), 1);
+        let carried = &report.carried[0];
+        assert_eq!((carried.start_time, carried.end_time), (time(9, 0), time(10, 0)));
+        assert_eq!(carried.carried_from_id, Some(task.id));
+        assert_eq!(carried.content, task.content);
+        assert_eq!(find(&repo, task.id).status, TaskStatus::RolledOver);
+    }
+
+    #[test]
+    fn test_moves_to_first_free_slot_on_conflict() {
+        let repo = MemoryRepository::new();
+        let task = create(&repo, from_date(), time(9, 0), time(10, 30));
+        create(&repo, to_date(), time(7, 0), time(8, 0));
+        create(&repo, to_date(), time(9, 0), time(10, 0));
+
+        let report = carry_over(&repo, CarryOverMode::Copy).unwrap();
+        let carried = &report.carried[0];
+        assert_eq!((carried.start_time, carried.end_time), (time(10, 0), time(11, 30)));
+        assert_eq!(find(&repo, task.id).status, TaskStatus::Failed);
+    }
+
+    #[test]
+    fn test_avoids_lunch_break_and_shifts_alarm() {
+        let repo = MemoryRepository::new();
+        let mut input = CreateTaskInput {
+            subject_id: None,
+            task_date: from_date(),
+            start_time: time(12, 30),
+            end_time: time(13, 0),
+            content: "午间背单词".to_string(),
+            alarm_enabled: Some(true),
+            alarm_time: Some(time(12, 25)),
+            priority: None,
+        };
+        block_on(repo.create_task(USER_ID, &input)).unwrap();
+        input.task_date = to_date();
+        input.start_time = time(7, 0);
+        input.end_time = time(12, 0);
+        block_on(repo.create_task(USER_ID, &input)).unwrap();
+
+        let report = carry_over(&repo, CarryOverMode::Move).unwrap();
+        let carried = &report.carried[0];
+        assert_eq!((carried.start_time, carried.end_time), (time(14, 0), time(14, 30)));
+        assert!(carried.alarm_enabled);
+        assert_eq!(carried.alarm_time, Some(time(13, 55)));
+    }
+
+    #[test]
+    fn test_skips_when_target_day_is_full() {
+        let repo = MemoryRepository::new();
+        let task = create(&repo, from_date(), time(9, 0), time(10, 0));
+        create(&repo, to_date(), time(7, 0), time(12, 0));
+        create(&repo, to_date(), time(14, 0), time(22, 0));
+
+        let report = carry_over(&repo, CarryOverMode::Move).unwrap();
+        assert!(report.carried.is_empty());
+        assert_eq!(report.skipped[0].id, task.id);
+        assert_eq!(find(&repo, task.id).status, TaskStatus::Pending);
+    }
+
+    #[test]
+    fn test_only_pending_tasks_are_carried() {
+        let repo = MemoryRepository::new();
+        let done = create(&repo, from_date(), time(8, 0), time(9, 0));
+        let failed = create(&repo, from_date(), time(9, 0), time(10, 0));
+        block_on(repo.set_task_status(USER_ID, done.id, &TaskStatus::Completed, None)).unwrap();
+        block_on(repo.set_task_status(USER_ID, failed.id, &TaskStatus::Failed, None)).unwrap();
+
+        let report = carry_over(&repo, CarryOverMode::Move).unwrap();
+        assert!(report.carried.is_empty() && report.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_non_forward_dates() {
+        let repo = MemoryRepository::new();
+        for (from, to) in [(to_date(), from_date()), (from_date(), from_date())] {
+            let result = block_on(carry_over_service::carry_over_tasks(
+                &repo,
+                USER_ID,
+                from,
+                to,
+                CarryOverMode::Move,
+                &StudyWindow::default(),
+            ));
+            assert!(matches!(result, Err(AppError::Validation(_))));
+        }
+    }
+
+    #[test]
+    fn test_rolled_over_tasks_are_excluded_from_day_totals_and_conflicts() {
+        let repo = MemoryRepository::new();
+        create(&repo, from_date(), time(9, 0), time(10, 0));
+        carry_over(&repo, CarryOverMode::Move).unwrap();
+
+        let days = block_on(task_service::get_tasks_in_range(
+            &repo,
+            USER_ID,
+            from_date(),
+            to_date(),
+            TaskFilter::default(),
+        ))
+        .unwrap();
+        assert_eq!(days[0].tasks.len(), 1);
+        assert_eq!((days[0].total_tasks, days[0].planned_minutes), (0, 0));
+        assert_eq!((days[1].total_tasks, days[1].planned_minutes), (1, 60));
+
+        // 原日期的时间段已经空出来
+        create(&repo, from_date(), time(9, 0), time(10, 0));
+    }
+
+    #[test]
+    fn test_rolled_over_tasks_cannot_be_reopened() {
+        let repo = MemoryRepository::new();
+        let task = create(&repo, from_date(), time(9, 0), time(10, 0));
+        carry_over(&repo, CarryOverMode::Move).unwrap();
+
+        // 顺延后的新任务已经存在，原任务不能再切换或设置为其他状态
+        let toggled = block_on(task_service::toggle_task_status(&repo, USER_ID, task.id));
+        assert!(matches!(toggled, Err(AppError::Validation(_))));
+        let set = block_on(task_status_service::set_task_status(
+            &repo,
+            USER_ID,
+            task.id,
+            TaskStatus::Completed,
+            None,
+        ));
+        assert!(matches!(set, Err(AppError::Validation(_))));
+        assert_eq!(find(&repo, task.id).status, TaskStatus::RolledOver);
+    }
+
+    #[test]
+    fn test_rolled_over_status_round_trips() {
+        assert_eq!(TaskStatus::RolledOver.to_string(), "rolled_over");
+        assert_eq!(TaskStatus::from("rolled_over".to_string()), TaskStatus::RolledOver);
+        assert_eq!(TaskStatus::RolledOver.next(), None);
+        assert_eq!(serde_json::to_string(&TaskStatus::RolledOver).unwrap(), "\"rolled_over\"");
+        assert_eq!(CarryOverMode::parse(CarryOverMode::Copy.as_str()), Some(CarryOverMode::Copy));
+    }
+}
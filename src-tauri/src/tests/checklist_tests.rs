@@ -0,0 +1,210 @@
+// 任务清单属性测试
+
+use super::block_on;
+use crate::error::AppError;
+use crate::models::{
+    CarryOverMode, CreateTaskInput, StudyWindow, TaskFilter, TaskResponse, TaskStatus, UpdateChecklistItemInput,
+};
+use crate::repositories::{MemoryRepository, TaskRepository, UserRepository};
+use crate::services::{carry_over_service, checklist_service, task_service};
+use chrono::{NaiveDate, NaiveTime};
+use proptest::prelude::*;
+
+const USER_ID: i64 = 1;
+
+fn date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 3, 3).unwrap()
+}
+
+fn create_task(repo: &MemoryRepository) -> TaskResponse {
+    block_on(task_service::create_task(
+        repo,
+        USER_ID,
+        CreateTaskInput {
+            subject_id: None,
+            task_date: date(),
+            start_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+            end_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            content: "数学 第三章 习题 1-40".to_string(),
+            alarm_enabled: None,
+            alarm_time: None,
+            priority: None,
+        },
+        &StudyWindow::default(),
+    ))
+    .unwrap()
+}
+
+fn add(repo: &MemoryRepository, task_id: i64, content: &str) -> Result<TaskResponse, AppError> {
+    block_on(checklist_service::add_checklist_item(repo, USER_ID, task_id, content))
+}
+
+fn set_completed(repo: &MemoryRepository, item_id: i64, completed: bool) -> TaskResponse {
+    let input = UpdateChecklistItemInput {
+        completed: Some(completed),
+        ..Default::default()
+    };
+    block_on(checklist_service::update_checklist_item(repo, USER_ID, item_id, input)).unwrap()
+}
+
+/// 生成清单项数量与完成状态
+fn checklist_strategy() -> impl Strategy<Value = Vec<bool>> {
+    proptest::collection::vec(any::<bool>(), 1..12)
+}
+
+proptest! {
+    /// 进度为已完成清单项的百分比 (向下取整)，任务恰好在全部完成时为已完成
+    #[test]
+    fn test_progress_and_status_follow_checklist(done in checklist_strategy()) {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo);
+        let mut response = task.clone();
+        for (i, _) in done.iter().enumerate() {
+            response = add(&repo, task.id, &format!("第 {} 题", i + 1)).unwrap();
+        }
+        let ids: Vec<i64> = response.checklist.iter().map(|item| item.id).collect();
+        for (id, completed) in ids.iter().zip(&done) {
+            response = set_completed(&repo, *id, *completed);
+        }
+
+        let completed = done.iter().filter(|d| **d).count();
+        let all_done = completed == done.len();
+        prop_assert_eq!(response.checklist.len(), done.len());
+        prop_assert_eq!(response.status.as_str(), if all_done { "completed" } else { "pending" });
+        let expected = if all_done { 100 } else { (completed * 100 / done.len()) as i32 };
+        prop_assert_eq!(response.progress, expected);
+        prop_assert_eq!(block_on(repo.find_task(USER_ID, task.id)).unwrap().unwrap().progress, expected);
+    }
+
+    /// 调整顺序后清单按给定顺序返回
+    #[test]
+    fn test_reorder_follows_given_order(ordering in Just((0..8).collect::<Vec<usize>>()).prop_shuffle()) {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo);
+        let mut ids = Vec::new();
+        for i in 0..ordering.len() {
+            ids.push(add(&repo, task.id, &format!("第 {} 项", i)).unwrap().checklist[i].id);
+        }
+
+        let reordered: Vec<i64> = ordering.iter().map(|i| ids[*i]).collect();
+        let response =
+            block_on(checklist_service::reorder_checklist_items(&repo, USER_ID, task.id, &reordered)).unwrap();
+        let result: Vec<i64> = response.checklist.iter().map(|item| item.id).collect();
+        prop_assert_eq!(result, reordered);
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_task_completes_and_reopens_with_checklist() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo);
+        add(&repo, task.id, "1-20").unwrap();
+        let response = add(&repo, task.id, "21-40").unwrap();
+        let (first, second) = (response.checklist[0].id, response.checklist[1].id);
+
+        assert_eq!(set_completed(&repo, first, true).progress, 50);
+        let done = set_completed(&repo, second, true);
+        assert_eq!((done.status.as_str(), done.progress), ("completed", 100));
+
+        let reopened = set_completed(&repo, first, false);
+        assert_eq!((reopened.status.as_str(), reopened.progress), ("pending", 50));
+
+        // 删除唯一未完成的一项后，剩下的全部完成
+        let response = block_on(checklist_service::delete_checklist_item(&repo, USER_ID, first)).unwrap();
+        assert_eq!((response.status.as_str(), response.checklist.len()), ("completed", 1));
+
+        // 新增未完成的一项，任务恢复为待完成
+        let response = add(&repo, task.id, "错题整理").unwrap();
+        assert_eq!((response.status.as_str(), response.progress), ("pending", 50));
+    }
+
+    #[test]
+    fn test_failed_task_keeps_status() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo);
+        let item = add(&repo, task.id, "1-20").unwrap().checklist[0].id;
+        block_on(repo.set_task_status(USER_ID, task.id, &TaskStatus::Failed, None)).unwrap();
+
+        let response = set_completed(&repo, item, true);
+        assert_eq!((response.status.as_str(), response.progress), ("failed", 100));
+    }
+
+    #[test]
+    fn test_content_is_trimmed_and_validated() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo);
+        assert_eq!(add(&repo, task.id, "  1-20  ").unwrap().checklist[0].content, "1-20");
+        assert!(matches!(add(&repo, task.id, "   "), Err(AppError::Validation(_))));
+        let long = "题".repeat(checklist_service::MAX_CHECKLIST_CONTENT_CHARS + 1);
+        assert!(matches!(add(&repo, task.id, &long), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn test_reorder_requires_all_items() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo);
+        add(&repo, task.id, "a").unwrap();
+        let ids: Vec<i64> = add(&repo, task.id, "b").unwrap().checklist.iter().map(|i| i.id).collect();
+
+        for bad in [vec![ids[0]], vec![ids[0], ids[0]], vec![ids[0], ids[1], 999]] {
+            let result = block_on(checklist_service::reorder_checklist_items(&repo, USER_ID, task.id, &bad));
+            assert!(matches!(result, Err(AppError::Validation(_))));
+        }
+    }
+
+    #[test]
+    fn test_other_users_items_are_not_found() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo);
+        let item = add(&repo, task.id, "a").unwrap().checklist[0].id;
+
+        let result = block_on(checklist_service::delete_checklist_item(&repo, USER_ID + 1, item));
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+        let result = block_on(checklist_service::add_checklist_item(&repo, USER_ID + 1, task.id, "b"));
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_checklist_is_returned_with_tasks_and_removed_with_task() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo);
+        add(&repo, task.id, "a").unwrap();
+        add(&repo, task.id, "b").unwrap();
+
+        let tasks = block_on(task_service::get_tasks_by_date(&repo, USER_ID, date())).unwrap();
+        assert_eq!(tasks[0].checklist.len(), 2);
+        let days =
+            block_on(task_service::get_tasks_in_range(&repo, USER_ID, date(), date(), TaskFilter::default())).unwrap();
+        assert_eq!(days[0].tasks[0].checklist.len(), 2);
+
+        block_on(repo.delete_task(USER_ID, task.id)).unwrap();
+        assert!(block_on(repo.load_user_data(USER_ID)).unwrap().checklist_items.is_empty());
+    }
+
+    #[test]
+    fn test_carry_over_copies_checklist() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo);
+        let item = add(&repo, task.id, "1-20").unwrap().checklist[0].id;
+        add(&repo, task.id, "21-40").unwrap();
+        set_completed(&repo, item, true);
+
+        let report = block_on(carry_over_service::carry_over_tasks(
+            &repo,
+            USER_ID,
+            date(),
+            date().succ_opt().unwrap(),
+            CarryOverMode::Move,
+            &StudyWindow::default(),
+        ))
+        .unwrap();
+        let carried = &report.carried[0];
+        assert_eq!(carried.progress, 50);
+        let items: Vec<(&str, bool)> = carried.checklist.iter().map(|i| (i.content.as_str(), i.completed)).collect();
+        assert_eq!(items, vec![("1-20", true), ("21-40", false)]);
+    }
+}
@@ -0,0 +1,34 @@
+// 数据库重连退避策略属性测试
+// 重试间隔从 1 秒开始翻倍增长，最长不超过 60 秒
+
+use crate::db::connection::backoff_delay;
+use proptest::prelude::*;
+use std::time::Duration;
+
+proptest! {
+    /// 重试间隔不超过上限
+    #[test]
+    fn test_backoff_is_capped(attempt in 0u32..=u32::MAX) {
+        prop_assert!(backoff_delay(attempt) <= Duration::from_secs(60));
+    }
+
+    /// 重试间隔随失败次数单调不减
+    #[test]
+    fn test_backoff_is_non_decreasing(attempt in 0u32..1_000) {
+        prop_assert!(backoff_delay(attempt + 1) >= backoff_delay(attempt));
+    }
+
+    /// 多次失败后达到上限
+    #[test]
+    fn test_backoff_reaches_cap(attempt in 7u32..1_000) {
+        prop_assert_eq!(backoff_delay(attempt), Duration::from_secs(60));
+    }
+}
+
+/// 首次失败后 1 秒重试
+#[test]
+fn test_backoff_starts_at_one_second() {
+    assert_eq!(backoff_delay(1), Duration::from_secs(1));
+    assert_eq!(backoff_delay(2), Duration::from_secs(2));
+    assert_eq!(backoff_delay(3), Duration::from_secs(4));
+}
@@ -0,0 +1,148 @@
+// 用户停用与删除前导出属性测试
+
+use super::block_on;
+use crate::models::{AuditEventFilter, CreateTaskInput, CreateUserInput, StudyWindow, User};
+use crate::repositories::{AuditRepository, MemoryRepository, UserRepository};
+use crate::services::{auth_service, task_service, user_service};
+use chrono::{NaiveDate, NaiveTime};
+use proptest::prelude::*;
+use std::path::PathBuf;
+
+const PASSWORD: &str = "password1";
+
+fn create_user(repo: &MemoryRepository, username: &str, role: &str) -> User {
+    let id = block_on(user_service::create_user(
+        repo,
+        None,
+        CreateUserInput {
+            username: username.to_string(),
+            password: PASSWORD.to_string(),
+            display_name: username.to_string(),
+            role: role.to_string(),
+        },
+    ))
+    .unwrap()
+    .id;
+    block_on(repo.find_user(id)).unwrap().unwrap()
+}
+
+/// 管理员对指定用户执行的操作
+fn admin_events(target_user_id: i64, admin_id: i64) -> AuditEventFilter {
+    AuditEventFilter {
+        actor_id: Some(admin_id),
+        target_user_id: Some(target_user_id),
+        ..Default::default()
+    }
+}
+
+/// 临时归档目录
+fn archive_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("study-planner-test-{}", uuid::Uuid::new_v4()))
+}
+
+proptest! {
+    /// 停用后不能登录、已有会话失效，但数据保留；重新启用后恢复登录
+    #[test]
+    fn test_deactivation_keeps_data(task_count in 0usize..5) {
+        let repo = MemoryRepository::new();
+        let admin = create_user(&repo, "admin", "admin");
+        let student = create_user(&repo, "student", "user");
+        let token = block_on(auth_service::login(&repo, "student", PASSWORD))
+            .unwrap()
+            .session()
+            .unwrap()
+            .session_token;
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        for i in 0..task_count {
+            block_on(task_service::create_task(&repo, student.id, CreateTaskInput {
+                subject_id: None,
+                task_date: date,
+                start_time: NaiveTime::from_hms_opt(8 + i as u32, 0, 0).unwrap(),
+                end_time: NaiveTime::from_hms_opt(9 + i as u32, 0, 0).unwrap(),
+                content: format!("任务{}", i),
+                alarm_enabled: None,
+                alarm_time: None,
+                priority: None,
+            }, &StudyWindow::default()))
+            .unwrap();
+        }
+
+        let response = block_on(user_service::deactivate_user(&repo, admin.id, student.id)).unwrap();
+        prop_assert!(!response.is_active);
+        prop_assert!(block_on(auth_service::validate_session(&repo, &token)).is_err());
+        let err = block_on(auth_service::login(&repo, "student", PASSWORD)).unwrap_err();
+        prop_assert_eq!(err.code(), "forbidden");
+        prop_assert_eq!(block_on(task_service::get_tasks_by_date(&repo, student.id, date)).unwrap().len(), task_count);
+
+        block_on(user_service::reactivate_user(&repo, admin.id, student.id)).unwrap();
+        prop_assert!(block_on(auth_service::login(&repo, "student", PASSWORD)).is_ok());
+
+        let events = block_on(repo.list_audit_events(&admin_events(student.id, admin.id), 10, 0)).unwrap();
+        let actions: Vec<&str> = events.iter().map(|e| e.action.as_str()).collect();
+        prop_assert_eq!(actions, vec!["user.reactivate", "user.deactivate"]);
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_delete_requires_deactivation() {
+        let repo = MemoryRepository::new();
+        let admin = create_user(&repo, "admin", "admin");
+        let student = create_user(&repo, "student", "user");
+        let dir = archive_dir();
+
+        let err = block_on(user_service::delete_user(&repo, admin.id, student.id, &dir)).unwrap_err();
+        assert_eq!(err.code(), "validation");
+        assert!(block_on(repo.find_user(student.id)).unwrap().is_some());
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_delete_writes_archive_and_audit() {
+        let repo = MemoryRepository::new();
+        let admin = create_user(&repo, "admin", "admin");
+        let student = create_user(&repo, "student", "user");
+        let dir = archive_dir();
+
+        block_on(user_service::deactivate_user(&repo, admin.id, student.id)).unwrap();
+        let archive = block_on(user_service::delete_user(&repo, admin.id, student.id, &dir)).unwrap();
+        assert!(block_on(repo.find_user(student.id)).unwrap().is_none());
+
+        // 归档包含用户信息和默认科目
+        let content: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&archive.path).unwrap()).unwrap();
+        assert_eq!(content["user"]["username"], "student");
+        assert!(!content["subjects"].as_array().unwrap().is_empty());
+        assert!(content["tasks"].as_array().unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // 删除记录在用户删除后仍然保留
+        let events = block_on(repo.list_audit_events(&admin_events(student.id, admin.id), 10, 0)).unwrap();
+        assert_eq!(events[0].action, "user.delete");
+        let metadata: serde_json::Value = serde_json::from_str(events[0].metadata.as_deref().unwrap()).unwrap();
+        assert_eq!(metadata["username"], "student");
+    }
+
+    #[test]
+    fn test_cannot_deactivate_self() {
+        let repo = MemoryRepository::new();
+        let admin = create_user(&repo, "admin", "admin");
+        let err = block_on(user_service::deactivate_user(&repo, admin.id, admin.id)).unwrap_err();
+        assert_eq!(err.code(), "validation");
+        assert!(block_on(repo.find_user(admin.id)).unwrap().unwrap().is_active());
+    }
+
+    #[test]
+    fn test_deactivated_login_with_wrong_password_not_revealed() {
+        let repo = MemoryRepository::new();
+        let admin = create_user(&repo, "admin", "admin");
+        let student = create_user(&repo, "student", "user");
+        block_on(user_service::deactivate_user(&repo, admin.id, student.id)).unwrap();
+
+        let err = block_on(auth_service::login(&repo, "student", "wrong-password1")).unwrap_err();
+        assert_eq!(err.code(), "unauthorized");
+    }
+}
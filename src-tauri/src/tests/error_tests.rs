@@ -0,0 +1,79 @@
+// 统一错误类型属性测试
+// 错误序列化为 {code, message, details}，错误码保持稳定，底层错误信息只出现在 details 中
+
+use crate::db::DbError;
+use crate::error::AppError;
+use proptest::prelude::*;
+
+/// 生成非空提示文本
+fn message_strategy() -> impl Strategy<Value = String> {
+    "[\\p{Han}a-zA-Z0-9 ]{1,30}"
+}
+
+proptest! {
+    /// 序列化结果包含错误码、提示文本和详情
+    #[test]
+    fn test_serializes_code_message_details(message in message_strategy()) {
+        let cases = [
+            (AppError::Unauthorized(message.clone()), "unauthorized"),
+            (AppError::Forbidden(message.clone()), "forbidden"),
+            (AppError::NotFound(message.clone()), "not_found"),
+            (AppError::Validation(message.clone()), "validation"),
+            (AppError::Conflict(message.clone()), "conflict"),
+            (AppError::RateLimited(message.clone()), "rate_limited"),
+        ];
+
+        for (error, code) in cases {
+            let json = serde_json::to_value(&error).unwrap();
+            prop_assert_eq!(json["code"].as_str(), Some(code));
+            prop_assert_eq!(json["message"].as_str(), Some(message.as_str()));
+            prop_assert!(json["details"].is_null());
+        }
+    }
+
+    /// 数据库错误的底层信息放在 details 中
+    #[test]
+    fn test_database_error_keeps_source_in_details(
+        message in message_strategy(),
+        source in message_strategy()
+    ) {
+        let error = AppError::db(message.clone())(sqlx::Error::Protocol(source.clone()));
+        let json = serde_json::to_value(&error).unwrap();
+
+        prop_assert_eq!(json["code"].as_str(), Some("database"));
+        prop_assert_eq!(json["message"].as_str(), Some(message.as_str()));
+        prop_assert!(json["details"].as_str().unwrap().contains(&source));
+    }
+
+    /// 查询不到记录视为 NotFound
+    #[test]
+    fn test_row_not_found_maps_to_not_found(message in message_strategy()) {
+        let error = AppError::db(message.clone())(sqlx::Error::RowNotFound);
+        prop_assert_eq!(error.code(), "not_found");
+        prop_assert_eq!(error.message(), message);
+    }
+}
+
+/// 连接池不可用时返回独立的错误码
+#[test]
+fn test_database_unavailable_code() {
+    let error = AppError::from(DbError::Unavailable);
+    let json = serde_json::to_value(&error).unwrap();
+
+    assert_eq!(json["code"], "database_unavailable");
+    assert_eq!(json["message"], DbError::Unavailable.to_string());
+}
+
+/// 时间冲突的任务 ID 放在 details 中
+#[test]
+fn test_schedule_conflict_lists_task_ids() {
+    let error = AppError::ScheduleConflict {
+        message: "与已有任务时间重叠".to_string(),
+        task_ids: vec![3, 5],
+    };
+    let json = serde_json::to_value(&error).unwrap();
+
+    assert_eq!(json["code"], "schedule_conflict");
+    assert_eq!(json["message"], "与已有任务时间重叠");
+    assert_eq!(json["details"], "3,5");
+}
@@ -0,0 +1,187 @@
+// 邀请码注册属性测试
+
+use super::block_on;
+use crate::error::AppError;
+use crate::models::{CreateInviteCodeInput, CreateUserInput, InviteCodeResponse, LoginResponse, RegisterInput};
+use crate::repositories::{InviteCodeRepository, MemoryRepository, SubjectRepository};
+use crate::services::invite_service::{self, INVITE_CODE_LENGTH};
+use crate::services::{auth_service, user_service};
+use chrono::{Duration, Utc};
+use proptest::prelude::*;
+use std::collections::HashSet;
+
+const PASSWORD: &str = "password1";
+
+/// 创建管理员，返回仓储与管理员 ID
+fn repo_with_admin() -> (MemoryRepository, i64) {
+    let repo = MemoryRepository::new();
+    let admin_id = block_on(user_service::create_user(
+        &repo,
+        None,
+        CreateUserInput {
+            username: "admin".to_string(),
+            password: PASSWORD.to_string(),
+            display_name: "admin".to_string(),
+            role: "admin".to_string(),
+        },
+    ))
+    .unwrap()
+    .id;
+    (repo, admin_id)
+}
+
+fn create_invite(repo: &MemoryRepository, admin_id: i64, role: &str, max_uses: i32) -> InviteCodeResponse {
+    block_on(invite_service::create_invite_code(
+        repo,
+        admin_id,
+        CreateInviteCodeInput {
+            role: role.to_string(),
+            max_uses,
+            expires_in_days: 7,
+        },
+    ))
+    .unwrap()
+}
+
+fn register(repo: &MemoryRepository, code: &str, username: &str) -> Result<LoginResponse, AppError> {
+    block_on(invite_service::register_with_invite(
+        repo,
+        RegisterInput {
+            invite_code: code.to_string(),
+            username: username.to_string(),
+            password: PASSWORD.to_string(),
+            display_name: username.to_string(),
+        },
+    ))
+}
+
+proptest! {
+    /// 邀请码最多注册 max_uses 个账户，注册的账户使用预设角色并可登录
+    #[test]
+    fn test_invite_use_limit(max_uses in 1i32..5, attempts in 1usize..8, mentor in any::<bool>()) {
+        let (repo, admin_id) = repo_with_admin();
+        let role = if mentor { "mentor" } else { "user" };
+        let invite = create_invite(&repo, admin_id, role, max_uses);
+        prop_assert_eq!(invite.code.len(), INVITE_CODE_LENGTH);
+
+        let mut registered = Vec::new();
+        for i in 0..attempts {
+            let username = format!("student{}", i);
+            match register(&repo, &invite.code.to_lowercase(), &username) {
+                Ok(response) => {
+                    prop_assert_eq!(response.user.role.as_str(), role);
+                    prop_assert!(block_on(auth_service::validate_session(&repo, &response.session_token)).is_ok());
+                    registered.push(response.user.id);
+                }
+                Err(e) => prop_assert_eq!(e.code(), "validation"),
+            }
+        }
+
+        prop_assert_eq!(registered.len(), attempts.min(max_uses as usize));
+        let users = block_on(invite_service::get_invite_code_users(&repo, invite.id)).unwrap();
+        prop_assert_eq!(users.iter().map(|u| u.id).collect::<Vec<_>>(), registered);
+
+        let stored = block_on(repo.find_invite_code(invite.id)).unwrap().unwrap();
+        prop_assert_eq!(stored.used_count as usize, users.len());
+    }
+
+    /// 注册失败时不占用使用次数
+    #[test]
+    fn test_failed_registration_keeps_use(password in "[a-z]{1,7}") {
+        let (repo, admin_id) = repo_with_admin();
+        let invite = create_invite(&repo, admin_id, "user", 1);
+
+        let err = block_on(invite_service::register_with_invite(&repo, RegisterInput {
+            invite_code: invite.code.clone(),
+            username: "student".to_string(),
+            password,
+            display_name: "student".to_string(),
+        }))
+        .unwrap_err();
+        prop_assert_eq!(err.code(), "validation");
+
+        prop_assert!(register(&repo, &invite.code, "student").is_ok());
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_registration_seeds_default_subjects() {
+        let (repo, admin_id) = repo_with_admin();
+        let invite = create_invite(&repo, admin_id, "user", 1);
+        let response = register(&repo, &invite.code, "student").unwrap();
+
+        let subjects = block_on(repo.list_subjects(response.user.id)).unwrap();
+        assert!(!subjects.is_empty());
+        assert!(subjects.iter().all(|s| s.is_default));
+    }
+
+    #[test]
+    fn test_revoked_and_expired_codes_rejected() {
+        let (repo, admin_id) = repo_with_admin();
+        let invite = create_invite(&repo, admin_id, "user", 5);
+
+        // 过期后不能注册
+        let later = Utc::now() + Duration::days(8);
+        let err = block_on(invite_service::register_with_invite_at(&repo, RegisterInput {
+            invite_code: invite.code.clone(),
+            username: "late".to_string(),
+            password: PASSWORD.to_string(),
+            display_name: "late".to_string(),
+        }, later))
+        .unwrap_err();
+        assert_eq!(err.message(), "邀请码已过期");
+
+        block_on(invite_service::revoke_invite_code(&repo, invite.id)).unwrap();
+        assert_eq!(register(&repo, &invite.code, "student").unwrap_err().message(), "邀请码已撤销");
+        assert_eq!(register(&repo, "NOSUCHCODE", "student").unwrap_err().message(), "邀请码无效");
+    }
+
+    #[test]
+    fn test_duplicate_username_rejected() {
+        let (repo, admin_id) = repo_with_admin();
+        let invite = create_invite(&repo, admin_id, "user", 2);
+        assert_eq!(register(&repo, &invite.code, "admin").unwrap_err().code(), "conflict");
+
+        let stored = block_on(repo.find_invite_code(invite.id)).unwrap().unwrap();
+        assert_eq!(stored.used_count, 0);
+    }
+
+    #[test]
+    fn test_invalid_invite_input_rejected() {
+        let (repo, admin_id) = repo_with_admin();
+        let create = |role: &str, max_uses: i32, expires_in_days: i64| {
+            block_on(invite_service::create_invite_code(&repo, admin_id, CreateInviteCodeInput {
+                role: role.to_string(),
+                max_uses,
+                expires_in_days,
+            }))
+        };
+        assert!(create("root", 1, 7).is_err());
+        assert!(create("user", 0, 7).is_err());
+        assert!(create("user", 1, 0).is_err());
+        assert!(block_on(invite_service::get_invite_codes(&repo)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_codes_avoid_ambiguous_characters() {
+        let (repo, admin_id) = repo_with_admin();
+        let codes: HashSet<String> = (0..50).map(|_| create_invite(&repo, admin_id, "user", 1).code).collect();
+        assert_eq!(codes.len(), 50);
+        for code in &codes {
+            assert!(code.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()), "{}", code);
+            assert!(!code.contains(['0', 'O', '1', 'I']), "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_blank_username_rejected() {
+        let (repo, admin_id) = repo_with_admin();
+        let invite = create_invite(&repo, admin_id, "user", 1);
+        assert_eq!(register(&repo, &invite.code, "  ").unwrap_err().code(), "validation");
+        assert_eq!(register(&repo, &invite.code, "two words").unwrap_err().code(), "validation");
+    }
+}
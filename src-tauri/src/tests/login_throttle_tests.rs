@@ -0,0 +1,228 @@
+// 登录限流与临时锁定属性测试
+
+use super::{block_on, memory_pool};
+use crate::error::AppError;
+use crate::models::{CreateUserInput, LoginResult};
+use crate::repositories::{LoginAttemptRepository, MemoryRepository, SqlRepository};
+use crate::services::auth_service::{self, login_delay, LOCKOUT_MINUTES, MAX_FAILED_ATTEMPTS};
+use crate::services::user_service;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use proptest::prelude::*;
+
+const PASSWORD: &str = "password1";
+
+/// 执行管理操作的管理员 (内存仓储不检查外键，无需真实账户)
+const ADMIN_ID: i64 = 0;
+
+/// 创建只包含一个用户的内存仓储
+fn repo_with_user(username: &str) -> MemoryRepository {
+    let repo = MemoryRepository::new();
+    block_on(user_service::create_user(
+        &repo,
+        None,
+        CreateUserInput {
+            username: username.to_string(),
+            password: PASSWORD.to_string(),
+            display_name: username.to_string(),
+            role: "user".to_string(),
+        },
+    ))
+    .unwrap();
+    repo
+}
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 1, 1, 8, 0, 0).unwrap()
+}
+
+fn login_at(repo: &MemoryRepository, username: &str, password: &str, now: DateTime<Utc>) -> Result<LoginResult, AppError> {
+    block_on(auth_service::login_at(repo, username, password, now))
+}
+
+/// 按最短等待间隔连续输错 count 次，返回最后一次失败的时间
+fn fail_times(repo: &MemoryRepository, username: &str, count: i32) -> DateTime<Utc> {
+    let mut now = start();
+    for i in 0..count {
+        if i > 0 {
+            now += login_delay(i);
+        }
+        let err = login_at(repo, username, "wrong-password1", now).unwrap_err();
+        assert_eq!(err.code(), "unauthorized");
+    }
+    now
+}
+
+proptest! {
+    /// 连续失败后需按指数退避等待，等待期间即使密码正确也被拒绝
+    #[test]
+    fn test_failures_require_exponential_delay(
+        failures in 1..MAX_FAILED_ATTEMPTS,
+        early_ms in 0i64..1000
+    ) {
+        let repo = repo_with_user("student");
+        let last = fail_times(&repo, "student", failures);
+        let delay = login_delay(failures);
+        prop_assert_eq!(delay, Duration::seconds(1 << (failures - 1)));
+
+        let too_early = last + delay - Duration::milliseconds(1000 - early_ms);
+        let err = login_at(&repo, "student", PASSWORD, too_early).unwrap_err();
+        prop_assert_eq!(err.code(), "rate_limited");
+
+        // 等待结束后可以正常登录，失败计数被清除
+        prop_assert!(login_at(&repo, "student", PASSWORD, last + delay).is_ok());
+        prop_assert!(block_on(repo.find_login_throttle("student")).unwrap().is_none());
+    }
+
+    /// 达到失败上限后账户被锁定，锁定期内正确密码也无法登录
+    #[test]
+    fn test_lockout_after_max_failures(minutes_in in 0i64..LOCKOUT_MINUTES) {
+        let repo = repo_with_user("student");
+        let last = fail_times(&repo, "student", MAX_FAILED_ATTEMPTS);
+
+        let locked = block_on(repo.list_locked_accounts(last)).unwrap();
+        prop_assert_eq!(locked.len(), 1);
+        prop_assert_eq!(locked[0].locked_until, Some(last + Duration::minutes(LOCKOUT_MINUTES)));
+
+        let during = last + Duration::minutes(minutes_in);
+        let err = login_at(&repo, "student", PASSWORD, during).unwrap_err();
+        prop_assert_eq!(err.code(), "rate_limited");
+
+        // 锁定结束后可以登录
+        let after = last + Duration::minutes(LOCKOUT_MINUTES);
+        prop_assert!(login_at(&repo, "student", PASSWORD, after).is_ok());
+    }
+
+    /// 每次登录尝试都会记录结果和时间
+    #[test]
+    fn test_attempts_recorded(failures in 0..MAX_FAILED_ATTEMPTS) {
+        let repo = repo_with_user("student");
+        let last = fail_times(&repo, "student", failures);
+        let success_at = last + login_delay(failures.max(1));
+        login_at(&repo, "student", PASSWORD, success_at).unwrap();
+
+        let attempts = block_on(auth_service::get_login_attempts(&repo, Some("student"), 100)).unwrap();
+        prop_assert_eq!(attempts.len() as i32, failures + 1);
+        prop_assert!(attempts[0].success);
+        prop_assert_eq!(attempts[0].attempted_at, success_at);
+        prop_assert!(attempts[1..].iter().all(|a| !a.success));
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_unlock_clears_lockout() {
+        let repo = repo_with_user("student");
+        let last = fail_times(&repo, "student", MAX_FAILED_ATTEMPTS);
+
+        block_on(auth_service::unlock_account(&repo, ADMIN_ID, "student")).unwrap();
+        assert!(block_on(repo.list_locked_accounts(last)).unwrap().is_empty());
+        assert!(login_at(&repo, "student", PASSWORD, last).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_username_throttled() {
+        let repo = repo_with_user("student");
+        let last = fail_times(&repo, "nobody", MAX_FAILED_ATTEMPTS);
+
+        let err = login_at(&repo, "nobody", PASSWORD, last).unwrap_err();
+        assert_eq!(err.code(), "rate_limited");
+
+        // 其他用户不受影响
+        assert!(login_at(&repo, "student", PASSWORD, last).is_ok());
+    }
+
+    #[test]
+    fn test_failure_count_resets_after_quiet_period() {
+        let repo = repo_with_user("student");
+        let last = fail_times(&repo, "student", MAX_FAILED_ATTEMPTS - 1);
+
+        // 长时间未再尝试后，再次输错不会直接锁定
+        let later = last + Duration::minutes(LOCKOUT_MINUTES);
+        assert!(login_at(&repo, "student", "wrong-password1", later).is_err());
+        let throttle = block_on(repo.find_login_throttle("student")).unwrap().unwrap();
+        assert_eq!(throttle.failed_count, 1);
+        assert!(throttle.locked_until.is_none());
+    }
+
+    #[test]
+    fn test_throttled_attempt_recorded_as_failure() {
+        let repo = repo_with_user("student");
+        let last = fail_times(&repo, "student", 1);
+        assert!(login_at(&repo, "student", PASSWORD, last).is_err());
+
+        let attempts = block_on(auth_service::get_login_attempts(&repo, None, 100)).unwrap();
+        assert_eq!(attempts.len(), 2);
+        assert!(attempts.iter().all(|a| !a.success));
+    }
+
+    #[test]
+    fn test_concurrent_failures_all_counted() {
+        block_on(async {
+            let repo = SqlRepository::new(memory_pool().await);
+            let now = start();
+            let window_start = now - Duration::minutes(LOCKOUT_MINUTES);
+            let locked_until = now + Duration::minutes(LOCKOUT_MINUTES);
+
+            let mut tasks = tokio::task::JoinSet::new();
+            for _ in 0..MAX_FAILED_ATTEMPTS {
+                let repo = repo.clone();
+                tasks.spawn(async move {
+                    repo.increment_login_failures("student", now, window_start, MAX_FAILED_ATTEMPTS, locked_until)
+                        .await
+                });
+            }
+            while let Some(result) = tasks.join_next().await {
+                result.unwrap().unwrap();
+            }
+
+            let throttle = repo.find_login_throttle("student").await.unwrap().unwrap();
+            assert_eq!(throttle.failed_count, MAX_FAILED_ATTEMPTS);
+            assert_eq!(throttle.locked_until, Some(locked_until));
+        });
+    }
+
+    #[test]
+    fn test_sql_failure_count_resets_after_window() {
+        block_on(async {
+            let repo = SqlRepository::new(memory_pool().await);
+            let window = Duration::minutes(LOCKOUT_MINUTES);
+            let mut now = start();
+            for expected in 1..MAX_FAILED_ATTEMPTS {
+                let throttle = repo
+                    .increment_login_failures("student", now, now - window, MAX_FAILED_ATTEMPTS, now + window)
+                    .await
+                    .unwrap();
+                assert_eq!(throttle.failed_count, expected);
+                assert!(throttle.locked_until.is_none());
+                now += Duration::minutes(1);
+            }
+
+            now += window;
+            let throttle = repo
+                .increment_login_failures("student", now, now - window, MAX_FAILED_ATTEMPTS, now + window)
+                .await
+                .unwrap();
+            assert_eq!(throttle.failed_count, 1);
+            assert_eq!(throttle.last_failed_at, now);
+        });
+    }
+
+    #[test]
+    fn test_purge_removes_old_attempts() {
+        block_on(async {
+            let repo = SqlRepository::new(memory_pool().await);
+            let now = start();
+            for days in [0, 30, 89, 90, 200] {
+                repo.record_login_attempt("student", false, now - Duration::days(days)).await.unwrap();
+            }
+
+            assert_eq!(auth_service::purge_login_attempts(&repo, 0, now).await.unwrap(), 0);
+            assert_eq!(auth_service::purge_login_attempts(&repo, u32::MAX, now).await.unwrap(), 0);
+            assert_eq!(auth_service::purge_login_attempts(&repo, 90, now).await.unwrap(), 1);
+            assert_eq!(repo.list_login_attempts(None, 100).await.unwrap().len(), 4);
+        });
+    }
+}
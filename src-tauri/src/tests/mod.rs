@@ -3,3 +3,78 @@
 pub mod auth_tests;
 #[cfg(test)]
 pub mod user_tests;
+#[cfg(test)]
+pub mod sync_tests;
+#[cfg(test)]
+pub mod connection_tests;
+#[cfg(test)]
+pub mod error_tests;
+#[cfg(test)]
+pub mod password_tests;
+#[cfg(test)]
+pub mod login_throttle_tests;
+#[cfg(test)]
+pub mod session_tests;
+#[cfg(test)]
+pub mod permission_tests;
+#[cfg(test)]
+pub mod invite_tests;
+#[cfg(test)]
+pub mod deactivation_tests;
+#[cfg(test)]
+pub mod audit_tests;
+#[cfg(test)]
+pub mod two_factor_tests;
+#[cfg(test)]
+pub mod user_import_tests;
+#[cfg(test)]
+pub mod stats_tests;
+#[cfg(test)]
+pub mod task_series_tests;
+#[cfg(test)]
+pub mod task_range_tests;
+#[cfg(test)]
+pub mod schedule_tests;
+#[cfg(test)]
+pub mod carry_over_tests;
+#[cfg(test)]
+pub mod checklist_tests;
+#[cfg(test)]
+pub mod tag_tests;
+#[cfg(test)]
+pub mod task_status_tests;
+
+/// 在同步测试中执行异步服务调用
+#[cfg(test)]
+pub fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+/// 创建已执行全部迁移的内存 SQLite 数据库 (只有一个连接，连接关闭前数据一直保留)
+#[cfg(test)]
+pub async fn memory_pool() -> crate::db::DbPool {
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect_with(SqliteConnectOptions::new().in_memory(true).foreign_keys(true))
+        .await
+        .unwrap();
+    let pool = crate::db::DbPool::Sqlite(pool);
+    crate::db::migrations::run(&pool).await.unwrap();
+    pool
+}
+
+/// 在测试数据库上执行 SQL (可包含多条语句)，返回最后插入的记录 ID
+#[cfg(test)]
+pub async fn execute(db: &crate::db::DbPool, sql: &str) -> i64 {
+    crate::db::with_pool!(db, p => sqlx::raw_sql(sql).execute(p).await.map(crate::db::ExecResult::from))
+        .unwrap()
+        .insert_id
+}
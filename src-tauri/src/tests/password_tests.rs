@@ -0,0 +1,154 @@
+// 密码哈希与密码策略属性测试
+
+use super::block_on;
+use crate::models::CreateUserInput;
+use crate::repositories::{MemoryRepository, UserRepository};
+use crate::services::{auth_service, user_service};
+use crate::utils::password::{hash_password, is_hashed, validate_password_policy, verify_password};
+use proptest::prelude::*;
+
+/// 执行管理操作的管理员 (内存仓储不检查外键，无需真实账户)
+const ADMIN_ID: i64 = 0;
+
+/// 生成满足密码策略的密码
+fn valid_password_strategy() -> impl Strategy<Value = String> {
+    "[a-zA-Z][a-zA-Z0-9!@#$%^&*]{6,40}[0-9]".prop_map(|s| s)
+}
+
+/// 直接写入明文密码的旧版用户 (绕过服务层哈希)
+fn repo_with_legacy_user(username: &str, password: &str) -> (MemoryRepository, i64) {
+    let repo = MemoryRepository::new();
+    let user_id = block_on(repo.create_user(&CreateUserInput {
+        username: username.to_string(),
+        password: password.to_string(),
+        display_name: username.to_string(),
+        role: "user".to_string(),
+    }))
+    .unwrap();
+    (repo, user_id)
+}
+
+/// 读取用户当前存储的密码
+fn stored_password(repo: &MemoryRepository, user_id: i64) -> String {
+    block_on(repo.find_user(user_id)).unwrap().unwrap().password
+}
+
+proptest! {
+    /// 哈希后的密码只能被原密码验证通过
+    #[test]
+    fn test_hash_verify_roundtrip(
+        password in valid_password_strategy(),
+        other in valid_password_strategy()
+    ) {
+        let hash = hash_password(&password).unwrap();
+        prop_assert!(is_hashed(&hash));
+        prop_assert!(hash.starts_with("$argon2id$"));
+        prop_assert!(verify_password(&password, &hash));
+
+        if other != password {
+            prop_assert!(!verify_password(&other, &hash));
+        }
+    }
+
+    /// 相同密码每次哈希使用不同的盐
+    #[test]
+    fn test_hash_is_salted(password in valid_password_strategy()) {
+        let first = hash_password(&password).unwrap();
+        let second = hash_password(&password).unwrap();
+        prop_assert_ne!(first, second);
+    }
+
+    /// 旧版明文密码登录成功后自动升级为哈希
+    #[test]
+    fn test_legacy_password_upgraded_on_login(password in valid_password_strategy()) {
+        let (repo, user_id) = repo_with_legacy_user("legacy", &password);
+
+        block_on(auth_service::login(&repo, "legacy", &password)).unwrap();
+
+        let stored = stored_password(&repo, user_id);
+        prop_assert!(is_hashed(&stored));
+        prop_assert!(block_on(auth_service::login(&repo, "legacy", &password)).is_ok());
+    }
+
+    /// 创建用户时不保存明文密码
+    #[test]
+    fn test_created_user_password_hashed(password in valid_password_strategy()) {
+        let repo = MemoryRepository::new();
+        let user = block_on(user_service::create_user(&repo, None, CreateUserInput {
+            username: "student".to_string(),
+            password: password.clone(),
+            display_name: "student".to_string(),
+            role: "user".to_string(),
+        }))
+        .unwrap();
+
+        let stored = stored_password(&repo, user.id);
+        prop_assert_ne!(&stored, &password);
+        prop_assert!(verify_password(&password, &stored));
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    fn policy_message(password: &str) -> String {
+        validate_password_policy(password).unwrap_err().message()
+    }
+
+    #[test]
+    fn test_policy_accepts_valid_password() {
+        assert!(validate_password_policy("admin123").is_ok());
+    }
+
+    #[test]
+    fn test_policy_names_failed_rule() {
+        assert_eq!(policy_message("abc123"), "密码长度不能少于8个字符");
+        assert_eq!(policy_message(&format!("a1{}", "x".repeat(127))), "密码长度不能超过128个字符");
+        assert_eq!(policy_message("abcd 1234"), "密码不能包含空白字符");
+        assert_eq!(policy_message("12345678"), "密码必须包含字母");
+        assert_eq!(policy_message("abcdefgh"), "密码必须包含数字");
+    }
+
+    #[test]
+    fn test_failed_legacy_login_keeps_plaintext() {
+        let (repo, user_id) = repo_with_legacy_user("legacy", "admin123");
+        assert!(block_on(auth_service::login(&repo, "legacy", "wrong")).is_err());
+        assert_eq!(stored_password(&repo, user_id), "admin123");
+    }
+
+    #[test]
+    fn test_weak_password_rejected_on_create() {
+        let repo = MemoryRepository::new();
+        let err = block_on(user_service::create_user(&repo, None, CreateUserInput {
+            username: "student".to_string(),
+            password: "short".to_string(),
+            display_name: "student".to_string(),
+            role: "user".to_string(),
+        }))
+        .unwrap_err();
+        assert_eq!(err.code(), "validation");
+        assert!(block_on(user_service::get_all_users(&repo)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_weak_password_rejected_on_reset_and_change() {
+        let (repo, user_id) = repo_with_legacy_user("legacy", "admin123");
+
+        let err = block_on(user_service::reset_user_password(&repo, ADMIN_ID, user_id, "password")).unwrap_err();
+        assert_eq!(err.message(), "密码必须包含数字");
+
+        let err = block_on(auth_service::change_password(&repo, user_id, "", "admin123", "12345678")).unwrap_err();
+        assert_eq!(err.message(), "密码必须包含字母");
+
+        assert_eq!(stored_password(&repo, user_id), "admin123");
+    }
+
+    #[test]
+    fn test_change_password_from_legacy() {
+        let (repo, user_id) = repo_with_legacy_user("legacy", "admin123");
+        block_on(auth_service::change_password(&repo, user_id, "", "admin123", "newpass99")).unwrap();
+        assert!(is_hashed(&stored_password(&repo, user_id)));
+        assert!(block_on(auth_service::login(&repo, "legacy", "newpass99")).is_ok());
+    }
+}
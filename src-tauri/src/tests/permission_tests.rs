@@ -0,0 +1,159 @@
+// 权限模型与导师访问范围属性测试
+
+use super::block_on;
+use crate::models::{CreateUserInput, Permission, User, UserRole};
+use crate::repositories::{MemoryRepository, MentorRepository, UserRepository};
+use crate::services::{auth_service, mentor_service, user_service};
+use proptest::prelude::*;
+
+/// 生成角色
+fn role_strategy() -> impl Strategy<Value = UserRole> {
+    prop_oneof![Just(UserRole::Admin), Just(UserRole::Mentor), Just(UserRole::User)]
+}
+
+/// 创建指定角色的用户
+fn create_user(repo: &MemoryRepository, username: &str, role: &str) -> User {
+    let id = block_on(user_service::create_user(
+        repo,
+        None,
+        CreateUserInput {
+            username: username.to_string(),
+            password: "password1".to_string(),
+            display_name: username.to_string(),
+            role: role.to_string(),
+        },
+    ))
+    .unwrap()
+    .id;
+    block_on(repo.find_user(id)).unwrap().unwrap()
+}
+
+proptest! {
+    /// 授权结果与角色的权限集合一致
+    #[test]
+    fn test_require_permission_matches_role(role in role_strategy(), index in 0..Permission::ALL.len()) {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "someone", &role.to_string());
+        let permission = Permission::ALL[index];
+
+        let result = auth_service::require_permission(&user, permission);
+        prop_assert_eq!(result.is_ok(), role.permissions().contains(&permission));
+        if let Err(err) = result {
+            prop_assert_eq!(err.code(), "forbidden");
+        }
+    }
+
+    /// 导师只能查看分配给自己的学生
+    #[test]
+    fn test_mentor_scoped_to_assigned_students(
+        assigned in proptest::collection::vec(any::<bool>(), 1..6),
+        plans in any::<bool>()
+    ) {
+        let repo = MemoryRepository::new();
+        let mentor = create_user(&repo, "mentor", "mentor");
+        let permission = if plans { Permission::UsersViewPlans } else { Permission::UsersViewStats };
+
+        for (i, is_assigned) in assigned.iter().enumerate() {
+            let student = create_user(&repo, &format!("student{}", i), "user");
+            if *is_assigned {
+                block_on(mentor_service::assign_student(&repo, mentor.id, student.id)).unwrap();
+            }
+
+            let access = block_on(mentor_service::ensure_student_access(&repo, &mentor, permission, student.id));
+            prop_assert_eq!(access.is_ok(), *is_assigned);
+        }
+
+        let students = block_on(mentor_service::get_students(&repo, mentor.id)).unwrap();
+        prop_assert_eq!(students.len(), assigned.iter().filter(|a| **a).count());
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_role_permissions() {
+        assert_eq!(UserRole::Admin.permissions(), &Permission::ALL);
+        assert!(UserRole::User.permissions().is_empty());
+
+        let mentor = UserRole::Mentor;
+        assert!(mentor.has_permission(Permission::UsersViewStats));
+        assert!(mentor.has_permission(Permission::UsersViewPlans));
+        assert!(!mentor.has_permission(Permission::UsersManage));
+        assert!(!mentor.has_permission(Permission::SystemConfig));
+    }
+
+    #[test]
+    fn test_permission_names_serialized() {
+        let json = serde_json::to_string(&Permission::ALL).unwrap();
+        let names: Vec<String> = Permission::ALL.iter().map(|p| format!("\"{}\"", p)).collect();
+        assert_eq!(json, format!("[{}]", names.join(",")));
+        assert_eq!(Permission::UsersViewStats.as_str(), "users.view_stats");
+    }
+
+    #[test]
+    fn test_admin_sees_any_student() {
+        let repo = MemoryRepository::new();
+        let admin = create_user(&repo, "admin", "admin");
+        let student = create_user(&repo, "student", "user");
+        assert!(block_on(mentor_service::ensure_student_access(&repo, &admin, Permission::UsersViewStats, student.id)).is_ok());
+
+        // 普通用户不能查看其他用户
+        let other = create_user(&repo, "other", "user");
+        let err = block_on(mentor_service::ensure_student_access(&repo, &other, Permission::UsersViewStats, student.id))
+            .unwrap_err();
+        assert_eq!(err.code(), "forbidden");
+    }
+
+    #[test]
+    fn test_unknown_role_rejected() {
+        let repo = MemoryRepository::new();
+        let err = block_on(user_service::create_user(&repo, None, CreateUserInput {
+            username: "someone".to_string(),
+            password: "password1".to_string(),
+            display_name: "someone".to_string(),
+            role: "superuser".to_string(),
+        }))
+        .unwrap_err();
+        assert_eq!(err.code(), "validation");
+
+        let admin = create_user(&repo, "admin", "admin");
+        let user = create_user(&repo, "student", "user");
+        assert!(block_on(user_service::set_user_role(&repo, admin.id, user.id, "root")).is_err());
+    }
+
+    #[test]
+    fn test_assign_requires_mentor_role() {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "user", "user");
+        let student = create_user(&repo, "student", "user");
+        let err = block_on(mentor_service::assign_student(&repo, user.id, student.id)).unwrap_err();
+        assert_eq!(err.code(), "validation");
+    }
+
+    #[test]
+    fn test_role_change_clears_assignments() {
+        let repo = MemoryRepository::new();
+        let admin = create_user(&repo, "admin", "admin");
+        let mentor = create_user(&repo, "mentor", "mentor");
+        let student = create_user(&repo, "student", "user");
+        block_on(mentor_service::assign_student(&repo, mentor.id, student.id)).unwrap();
+
+        let updated = block_on(user_service::set_user_role(&repo, admin.id, mentor.id, "user")).unwrap();
+        assert_eq!(updated.role, "user");
+        assert!(updated.permissions.is_empty());
+        assert!(!block_on(repo.is_student_assigned(mentor.id, student.id)).unwrap());
+    }
+
+    #[test]
+    fn test_delete_user_removes_assignments() {
+        let repo = MemoryRepository::new();
+        let mentor = create_user(&repo, "mentor", "mentor");
+        let student = create_user(&repo, "student", "user");
+        block_on(mentor_service::assign_student(&repo, mentor.id, student.id)).unwrap();
+
+        block_on(repo.delete_user(student.id)).unwrap();
+        assert!(block_on(repo.list_students(mentor.id)).unwrap().is_empty());
+    }
+}
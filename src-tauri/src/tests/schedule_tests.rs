@@ -0,0 +1,222 @@
+// 任务时间段属性测试
+
+use super::block_on;
+use crate::error::AppError;
+use crate::models::{
+    CreateTaskInput, CreateTaskSeriesInput, Frequency, RecurrenceRule, RecurrenceScope, ScheduleWarningKind,
+    StudyWindow, TaskResponse, UpdateTaskInput,
+};
+use crate::repositories::MemoryRepository;
+use crate::services::{schedule_service, task_service};
+use chrono::{NaiveDate, NaiveTime};
+use proptest::prelude::*;
+
+const USER_ID: i64 = 1;
+
+fn date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 3, 3).unwrap()
+}
+
+fn time(h: u32, m: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(h, m, 0).unwrap()
+}
+
+fn create(repo: &MemoryRepository, start: NaiveTime, end: NaiveTime) -> Result<TaskResponse, AppError> {
+    block_on(task_service::create_task(
+        repo,
+        USER_ID,
+        CreateTaskInput {
+            subject_id: None,
+            task_date: date(),
+            start_time: start,
+            end_time: end,
+            content: "复习".to_string(),
+            alarm_enabled: None,
+            alarm_time: None,
+            priority: None,
+        },
+        &StudyWindow::default(),
+    ))
+}
+
+/// 生成 (开始分钟, 时长分钟) 的时间段
+fn slot_strategy() -> impl Strategy<Value = (u32, u32)> {
+    (0u32..1380, 1u32..60)
+}
+
+fn minutes(m: u32) -> NaiveTime {
+    time(m / 60, m % 60)
+}
+
+proptest! {
+    /// 成功保存的任务两两不重叠；被拒绝的时间段与返回的任务确实重叠
+    #[test]
+    fn test_saved_tasks_never_overlap(slots in proptest::collection::vec(slot_strategy(), 1..12)) {
+        let repo = MemoryRepository::new();
+        let mut saved: Vec<TaskResponse> = Vec::new();
+        for (start, length) in slots {
+            let (start, end) = (minutes(start), minutes(start + length));
+            match create(&repo, start, end) {
+                Ok(task) => saved.push(task),
+                Err(AppError::ScheduleConflict { task_ids, .. }) => {
+                    prop_assert!(!task_ids.is_empty());
+                    for id in task_ids {
+                        let other = saved.iter().find(|t| t.id == id).unwrap();
+                        prop_assert!(other.start_time < end && start < other.end_time);
+                    }
+                }
+                Err(e) => prop_assert!(false, "unexpected error: {:?}", e),
+            }
+        }
+        for (i, a) in saved.iter().enumerate() {
+            for b in &saved[i + 1..] {
+                prop_assert!(a.end_time <= b.start_time || b.end_time <= a.start_time);
+            }
+        }
+    }
+
+    /// 空闲时段都在学习时段内，不与任务和午休重叠，且不短于要求的时长
+    #[test]
+    fn test_free_slots_avoid_busy_time(
+        slots in proptest::collection::vec(slot_strategy(), 0..8),
+        duration in 1i64..120
+    ) {
+        let repo = MemoryRepository::new();
+        let window = StudyWindow::default();
+        let tasks: Vec<TaskResponse> = slots
+            .into_iter()
+            .filter_map(|(start, length)| create(&repo, minutes(start), minutes(start + length)).ok())
+            .collect();
+
+        let free = block_on(schedule_service::find_free_slots(&repo, USER_ID, date(), duration, &window)).unwrap();
+        for slot in &free {
+            prop_assert!(slot.minutes >= duration);
+            prop_assert!(slot.start_time >= window.start_time && slot.end_time <= window.end_time);
+            prop_assert!(slot.end_time <= window.lunch_break_start || slot.start_time >= window.lunch_break_end);
+            for task in &tasks {
+                prop_assert!(slot.end_time <= task.start_time || task.end_time <= slot.start_time);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_impossible_ranges_rejected() {
+        let repo = MemoryRepository::new();
+        for (start, end) in [(time(9, 0), time(8, 0)), (time(9, 0), time(9, 0))] {
+            assert_eq!(create(&repo, start, end).unwrap_err().code(), "validation");
+        }
+
+        let rule = RecurrenceRule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            weekdays: vec![],
+            until: None,
+            count: None,
+        };
+        let input = CreateTaskSeriesInput {
+            subject_id: None,
+            start_date: date(),
+            start_time: time(10, 0),
+            end_time: time(9, 0),
+            content: "背单词".to_string(),
+            alarm_enabled: None,
+            alarm_time: None,
+            priority: None,
+            rule,
+        };
+        let err = block_on(task_service::create_task_series(&repo, USER_ID, input)).unwrap_err();
+        assert_eq!(err.code(), "validation");
+    }
+
+    #[test]
+    fn test_conflicts_report_task_ids() {
+        let repo = MemoryRepository::new();
+        let a = create(&repo, time(8, 0), time(9, 0)).unwrap();
+        let b = create(&repo, time(9, 30), time(10, 30)).unwrap();
+        // 首尾相接不算重叠
+        let c = create(&repo, time(9, 0), time(9, 30)).unwrap();
+
+        // 冲突任务按开始时间排列
+        let err = create(&repo, time(8, 30), time(10, 0)).unwrap_err();
+        assert_eq!(err.code(), "schedule_conflict");
+        assert_eq!(err.details(), Some(format!("{},{},{}", a.id, c.id, b.id)));
+
+        // 修改时不与自身冲突；只改内容时不重新检查时间
+        let window = StudyWindow::default();
+        let shift = UpdateTaskInput {
+            start_time: Some(time(7, 30)),
+            end_time: Some(time(8, 30)),
+            ..Default::default()
+        };
+        block_on(task_service::update_task(&repo, USER_ID, a.id, shift, RecurrenceScope::This, &window)).unwrap();
+        let overlap = UpdateTaskInput { end_time: Some(time(10, 0)), ..Default::default() };
+        let err = block_on(task_service::update_task(&repo, USER_ID, a.id, overlap, RecurrenceScope::This, &window))
+            .unwrap_err();
+        match err {
+            AppError::ScheduleConflict { task_ids, .. } => assert_eq!(task_ids.len(), 2),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let reversed = UpdateTaskInput { end_time: Some(time(7, 0)), ..Default::default() };
+        let err = block_on(task_service::update_task(&repo, USER_ID, a.id, reversed, RecurrenceScope::This, &window))
+            .unwrap_err();
+        assert_eq!(err.code(), "validation");
+    }
+
+    #[test]
+    fn test_preference_warnings() {
+        let window = StudyWindow::default();
+        let kinds = |start, end| -> Vec<ScheduleWarningKind> {
+            schedule_service::preference_warnings(&window, start, end).into_iter().map(|w| w.kind).collect()
+        };
+        assert!(kinds(time(8, 0), time(9, 0)).is_empty());
+        assert_eq!(kinds(time(6, 30), time(7, 30)), [ScheduleWarningKind::OutsideStudyWindow]);
+        assert_eq!(kinds(time(21, 30), time(22, 30)), [ScheduleWarningKind::OutsideStudyWindow]);
+        assert_eq!(kinds(time(11, 30), time(12, 30)), [ScheduleWarningKind::LunchBreak]);
+        assert!(kinds(time(11, 0), time(12, 0)).is_empty());
+
+        // 提醒随任务一起返回，但不阻止保存
+        let repo = MemoryRepository::new();
+        let task = create(&repo, time(12, 30), time(13, 0)).unwrap();
+        assert_eq!(task.warnings.len(), 1);
+        let listed = block_on(task_service::get_tasks_by_date(&repo, USER_ID, date())).unwrap();
+        assert!(listed[0].warnings.is_empty());
+    }
+
+    #[test]
+    fn test_free_slots() {
+        let repo = MemoryRepository::new();
+        let window = StudyWindow::default();
+        create(&repo, time(8, 0), time(9, 0)).unwrap();
+        create(&repo, time(9, 20), time(11, 30)).unwrap();
+        create(&repo, time(13, 0), time(15, 0)).unwrap();
+        create(&repo, time(21, 30), time(23, 0)).unwrap();
+
+        let free = |duration| -> Vec<(NaiveTime, NaiveTime)> {
+            block_on(schedule_service::find_free_slots(&repo, USER_ID, date(), duration, &window))
+                .unwrap()
+                .into_iter()
+                .map(|s| (s.start_time, s.end_time))
+                .collect()
+        };
+        assert_eq!(
+            free(20),
+            [
+                (time(7, 0), time(8, 0)),
+                (time(9, 0), time(9, 20)),
+                (time(11, 30), time(12, 0)),
+                (time(15, 0), time(21, 30)),
+            ]
+        );
+        assert_eq!(free(60), [(time(7, 0), time(8, 0)), (time(15, 0), time(21, 30))]);
+
+        for duration in [0, schedule_service::MAX_SLOT_MINUTES + 1] {
+            let result = block_on(schedule_service::find_free_slots(&repo, USER_ID, date(), duration, &window));
+            assert_eq!(result.unwrap_err().code(), "validation");
+        }
+    }
+}
@@ -0,0 +1,202 @@
+// 会话管理属性测试
+
+use super::block_on;
+use crate::models::CreateUserInput;
+use crate::repositories::{MemoryRepository, SessionRepository};
+use crate::services::auth_service::{self, SESSION_TOUCH_INTERVAL_SECS, SESSION_TTL_DAYS};
+use crate::services::user_service;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use proptest::prelude::*;
+
+const PASSWORD: &str = "password1";
+
+/// 执行管理操作的管理员 (内存仓储不检查外键，无需真实账户)
+const ADMIN_ID: i64 = 0;
+
+/// 创建用户，返回用户 ID
+fn create_user(repo: &MemoryRepository, username: &str) -> i64 {
+    block_on(user_service::create_user(
+        repo,
+        None,
+        CreateUserInput {
+            username: username.to_string(),
+            password: PASSWORD.to_string(),
+            display_name: username.to_string(),
+            role: "user".to_string(),
+        },
+    ))
+    .unwrap()
+    .id
+}
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 1, 1, 8, 0, 0).unwrap()
+}
+
+/// 在指定时间登录，返回会话令牌
+fn login_at(repo: &MemoryRepository, username: &str, now: DateTime<Utc>) -> String {
+    block_on(auth_service::login_at(repo, username, PASSWORD, now))
+        .unwrap()
+        .session()
+        .unwrap()
+        .session_token
+}
+
+fn is_valid_at(repo: &MemoryRepository, token: &str, now: DateTime<Utc>) -> bool {
+    block_on(auth_service::validate_session_at(repo, token, now)).is_ok()
+}
+
+proptest! {
+    /// 会话使用后过期时间顺延，长期未使用的会话过期
+    #[test]
+    fn test_sliding_expiry(uses in proptest::collection::vec(1i64..SESSION_TTL_DAYS * 24, 1..10)) {
+        let repo = MemoryRepository::new();
+        create_user(&repo, "student");
+        let token = login_at(&repo, "student", start());
+
+        // 每次在过期前使用，会话一直有效
+        let mut now = start();
+        for hours in uses {
+            now += Duration::hours(hours);
+            prop_assert!(is_valid_at(&repo, &token, now));
+            let session = block_on(repo.find_session(&token)).unwrap().unwrap();
+            prop_assert_eq!(session.last_used_at, now);
+            prop_assert_eq!(session.expires_at, now + Duration::days(SESSION_TTL_DAYS));
+        }
+
+        // 最后一次使用后超过有效期则失效
+        prop_assert!(!is_valid_at(&repo, &token, now + Duration::days(SESSION_TTL_DAYS)));
+    }
+
+    /// 注销其他会话后只保留当前会话，其他用户不受影响
+    #[test]
+    fn test_revoke_other_sessions(count in 2usize..6) {
+        let repo = MemoryRepository::new();
+        let user_id = create_user(&repo, "student");
+        create_user(&repo, "other");
+        let tokens: Vec<String> = (0..count).map(|_| login_at(&repo, "student", start())).collect();
+        let other_token = login_at(&repo, "other", start());
+
+        block_on(auth_service::revoke_other_sessions(&repo, user_id, &tokens[0])).unwrap();
+
+        prop_assert!(is_valid_at(&repo, &tokens[0], start()));
+        prop_assert!(tokens[1..].iter().all(|t| !is_valid_at(&repo, t, start())));
+        prop_assert!(is_valid_at(&repo, &other_token, start()));
+    }
+
+    /// 清理过期会话只删除已过期的记录
+    #[test]
+    fn test_purge_expired_sessions(expired in 0usize..5, active in 0usize..5) {
+        let repo = MemoryRepository::new();
+        create_user(&repo, "student");
+        let now = Utc::now();
+        let old = now - Duration::days(SESSION_TTL_DAYS + 1);
+        for _ in 0..expired {
+            login_at(&repo, "student", old);
+        }
+        let tokens: Vec<String> = (0..active).map(|_| login_at(&repo, "student", now)).collect();
+
+        let purged = block_on(auth_service::purge_expired_sessions(&repo)).unwrap();
+        prop_assert_eq!(purged as usize, expired);
+        prop_assert!(tokens.iter().all(|t| is_valid_at(&repo, t, now)));
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::commands::auth::SessionState;
+
+    #[test]
+    fn test_session_state_resolves_token() {
+        let state = SessionState::default();
+        assert_eq!(state.resolve(None).unwrap_err().code(), "unauthorized");
+
+        // 已保存会话时可以省略令牌，显式传入的令牌优先 (多窗口)
+        state.set("saved".to_string());
+        assert_eq!(state.resolve(None).unwrap(), "saved");
+        assert_eq!(state.resolve(Some(String::new())).unwrap(), "saved");
+        assert_eq!(state.resolve(Some("other".to_string())).unwrap(), "other");
+    }
+
+    #[test]
+    fn test_session_state_clear_only_matching_token() {
+        let state = SessionState::default();
+        state.set("saved".to_string());
+
+        // 其他窗口退出登录不影响保存的会话
+        state.clear("other");
+        assert_eq!(state.get().as_deref(), Some("saved"));
+
+        state.clear("saved");
+        assert!(state.get().is_none());
+    }
+
+    #[test]
+    fn test_recent_use_not_written() {
+        let repo = MemoryRepository::new();
+        create_user(&repo, "student");
+        let token = login_at(&repo, "student", start());
+
+        let soon = start() + Duration::seconds(SESSION_TOUCH_INTERVAL_SECS - 1);
+        assert!(is_valid_at(&repo, &token, soon));
+        let session = block_on(repo.find_session(&token)).unwrap().unwrap();
+        assert_eq!(session.last_used_at, start());
+    }
+
+    #[test]
+    fn test_list_sessions_marks_current() {
+        let repo = MemoryRepository::new();
+        let user_id = create_user(&repo, "student");
+        create_user(&repo, "other");
+        let now = Utc::now();
+        let current = login_at(&repo, "student", now);
+        login_at(&repo, "student", now);
+        login_at(&repo, "student", now - Duration::days(SESSION_TTL_DAYS + 1));
+        login_at(&repo, "other", now);
+
+        let sessions = block_on(auth_service::get_sessions(&repo, user_id, &current)).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions.iter().filter(|s| s.is_current).count(), 1);
+    }
+
+    #[test]
+    fn test_revoke_session_scoped_to_user() {
+        let repo = MemoryRepository::new();
+        let user_id = create_user(&repo, "student");
+        let other_id = create_user(&repo, "other");
+        let token = login_at(&repo, "student", start());
+        let session_id = block_on(repo.find_session(&token)).unwrap().unwrap().id;
+
+        // 不能注销其他用户的会话
+        block_on(auth_service::revoke_session(&repo, other_id, session_id)).unwrap();
+        assert!(is_valid_at(&repo, &token, start()));
+
+        block_on(auth_service::revoke_session(&repo, user_id, session_id)).unwrap();
+        assert!(!is_valid_at(&repo, &token, start()));
+    }
+
+    #[test]
+    fn test_change_password_keeps_only_current_session() {
+        let repo = MemoryRepository::new();
+        let user_id = create_user(&repo, "student");
+        let current = login_at(&repo, "student", start());
+        let other = login_at(&repo, "student", start());
+
+        block_on(auth_service::change_password(&repo, user_id, &current, PASSWORD, "newpass99")).unwrap();
+        assert!(is_valid_at(&repo, &current, start()));
+        assert!(!is_valid_at(&repo, &other, start()));
+    }
+
+    #[test]
+    fn test_reset_password_revokes_all_sessions() {
+        let repo = MemoryRepository::new();
+        let user_id = create_user(&repo, "student");
+        let first = login_at(&repo, "student", start());
+        let second = login_at(&repo, "student", start());
+
+        block_on(user_service::reset_user_password(&repo, ADMIN_ID, user_id, "newpass99")).unwrap();
+        assert!(!is_valid_at(&repo, &first, start()));
+        assert!(!is_valid_at(&repo, &second, start()));
+    }
+}
@@ -0,0 +1,263 @@
+// 统计属性测试
+// 统计查询在已执行迁移的内存 SQLite 数据库上验证
+
+use super::{block_on, execute, memory_pool};
+use crate::db::DbPool;
+use crate::models::{occurrence_task_id, CreateTagInput, StartPomodoroInput};
+use crate::repositories::SqlRepository;
+use crate::services::stats_service::{self, completion_rate, progress_rate};
+use crate::services::{pomodoro_service, tag_service};
+use chrono::NaiveDate;
+use proptest::prelude::*;
+
+fn date(day: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap()
+}
+
+/// 创建用户 alice (学习记录横跨统计范围内外) 和已停用且没有学习记录的 bob，返回 alice 的 ID
+/// 统计范围为 2025-03-01 至 2025-03-07
+async fn seed_activity(db: &DbPool) -> i64 {
+    let alice = execute(db, "INSERT INTO users (username, password, display_name, role, created_at)
+        VALUES ('alice', 'x', 'Alice', 'user', '2025-01-01T00:00:00Z')").await;
+    let bob = execute(db, "INSERT INTO users (username, password, display_name, role, created_at, deactivated_at)
+        VALUES ('bob', 'x', 'Bob', 'mentor', '2025-01-02T00:00:00Z', '2025-02-01T00:00:00+00:00')").await;
+
+    let subject = execute(db, &format!(
+        "INSERT INTO subjects (user_id, name, color) VALUES ({}, '数学', '#10B981')", alice
+    )).await;
+    // 已完成、完成一半清单、已顺延 (不计入) 和范围外的任务
+    let mut tasks = Vec::new();
+    for (day, status, progress) in [
+        ("2025-03-02", "completed", 0),
+        ("2025-03-03", "pending", 50),
+        ("2025-03-04", "rolled_over", 0),
+        ("2025-02-20", "completed", 0),
+    ] {
+        tasks.push(execute(db, &format!(
+            "INSERT INTO tasks (user_id, subject_id, task_date, start_time, end_time, content, status, progress)
+             VALUES ({}, {}, '{}', '08:00:00', '09:00:00', '复习', '{}', {})",
+            alice, subject, day, status, progress
+        )).await);
+    }
+    execute(db, &format!(
+        "INSERT INTO tasks (user_id, task_date, start_time, end_time, content)
+         VALUES ({}, '2025-03-02', '08:00:00', '09:00:00', '阅读')",
+        bob
+    )).await;
+
+    // 已完成、进行中 (不计入) 和范围外的番茄钟
+    for (task, start, minutes, status) in [
+        (tasks[0], "2025-03-02T08:00:00+00:00", 25, "completed"),
+        (tasks[1], "2025-03-03T08:00:00+00:00", 50, "completed"),
+        (tasks[1], "2025-03-03T10:00:00+00:00", 25, "running"),
+        (tasks[3], "2025-02-20T08:00:00+00:00", 30, "completed"),
+    ] {
+        execute(db, &format!(
+            "INSERT INTO pomodoro_sessions (user_id, subject_id, task_id, start_time, duration_minutes, status)
+             VALUES ({}, {}, {}, '{}', {}, '{}')",
+            alice, subject, task, start, minutes, status
+        )).await;
+    }
+
+    // 标签：重点覆盖前两个任务，错题只覆盖第二个任务，旧题只覆盖范围外的任务 (不计入)
+    for (name, linked) in [("重点", &tasks[..2]), ("错题", &tasks[1..2]), ("旧题", &tasks[3..])] {
+        let tag = execute(db, &format!("INSERT INTO task_tags (user_id, name) VALUES ({}, '{}')", alice, name)).await;
+        for task in linked {
+            execute(db, &format!("INSERT INTO task_tag_links (task_id, tag_id) VALUES ({}, {})", task, tag)).await;
+        }
+    }
+
+    execute(db, &format!(
+        "INSERT INTO daily_reviews (user_id, review_date) VALUES ({0}, '2025-02-01'), ({0}, '2025-03-05');
+         INSERT INTO login_attempts (username, success, attempted_at) VALUES
+            ('alice', 1, '2025-03-06T09:00:00+00:00'), ('alice', 0, '2025-03-07T09:00:00+00:00');
+         INSERT INTO ai_configs (user_id, api_key, model_name, api_endpoint) VALUES
+            ({0}, 'sk-test', 'model', 'https://example.com'), ({1}, '', 'model', 'https://example.com')",
+        alice, bob
+    )).await;
+
+    alice
+}
+
+proptest! {
+    /// 完成率在 0-100 之间，全部完成时为 100
+    #[test]
+    fn test_completion_rate_bounds(total in 1i64..10_000, completed_ratio in 0.0f64..=1.0) {
+        let completed = (total as f64 * completed_ratio) as i64;
+        let rate = completion_rate(completed, total);
+        prop_assert!((0.0..=100.0).contains(&rate));
+        prop_assert_eq!(completion_rate(total, total), 100.0);
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_rate_without_tasks() {
+        assert_eq!(completion_rate(0, 0), 0.0);
+        assert_eq!(completion_rate(1, 4), 25.0);
+    }
+
+    #[test]
+    fn test_progress_rate_counts_partial_tasks() {
+        // 一个已完成 (100) 与一个完成一半清单 (50) 的任务
+        assert_eq!(progress_rate(150, 2), 75.0);
+        assert_eq!(progress_rate(0, 0), 0.0);
+    }
+
+    #[test]
+    fn test_stats_query() {
+        block_on(async {
+            let db = memory_pool().await;
+            let alice = seed_activity(&db).await;
+            let stats = stats_service::query_stats(&db, alice, date("2025-03-01"), date("2025-03-07")).await.unwrap();
+
+            assert_eq!(stats.total_study_minutes, 75);
+            assert_eq!((stats.total_tasks, stats.completed_tasks), (2, 1));
+            assert_eq!(stats.completion_rate, 75.0);
+            assert_eq!(stats.subject_distribution.len(), 1);
+            assert_eq!(stats.subject_distribution[0].total_minutes, 75);
+
+            let trend: Vec<_> = stats.daily_trend.iter().map(|d| (d.date, d.total_tasks, d.completed_tasks)).collect();
+            assert_eq!(trend, [
+                (date("2025-03-02"), 1, 1),
+                (date("2025-03-03"), 1, 0),
+                (date("2025-03-04"), 0, 0),
+            ]);
+        });
+    }
+
+    #[test]
+    fn test_stats_count_pending_occurrences() {
+        block_on(async {
+            let db = memory_pool().await;
+            let alice = seed_activity(&db).await;
+            // 03-05 至 03-07 每天重复：03-05 尚未生成，03-06 已生成并完成，03-07 已被单独删除
+            let series = execute(&db, &format!(
+                "INSERT INTO task_series (user_id, start_date, start_time, end_time, content, rrule)
+                 VALUES ({}, '2025-03-05', '10:00:00', '11:00:00', '背单词', 'FREQ=DAILY;INTERVAL=1;COUNT=3')",
+                alice
+            )).await;
+            execute(&db, &format!(
+                "INSERT INTO tasks (user_id, task_date, start_time, end_time, content, status,
+                                    series_id, occurrence_date)
+                 VALUES ({0}, '2025-03-06', '10:00:00', '11:00:00', '背单词', 'completed', {1}, '2025-03-06');
+                 INSERT INTO task_series_skips (series_id, occurrence_date) VALUES ({1}, '2025-03-07')",
+                alice, series
+            )).await;
+
+            let stats = stats_service::query_stats(&db, alice, date("2025-03-01"), date("2025-03-07")).await.unwrap();
+            assert_eq!((stats.total_tasks, stats.completed_tasks), (4, 2));
+            let trend: Vec<_> = stats.daily_trend.iter().map(|d| (d.date, d.total_tasks, d.completed_tasks)).collect();
+            assert_eq!(&trend[3..], [(date("2025-03-05"), 1, 0), (date("2025-03-06"), 1, 1)]);
+
+            let today = date("2025-03-10");
+            let activity = stats_service::query_user_activity(&db, date("2025-03-01"), date("2025-03-07"), today)
+                .await
+                .unwrap();
+            assert_eq!((activity[2].total_tasks, activity[2].completed_tasks), (4, 2));
+            assert_eq!(activity[1].total_tasks, 1);
+        });
+    }
+
+    #[test]
+    fn test_tag_stats_query() {
+        block_on(async {
+            let db = memory_pool().await;
+            let alice = seed_activity(&db).await;
+            let stats = stats_service::query_stats(&db, alice, date("2025-03-01"), date("2025-03-07")).await.unwrap();
+
+            let tags: Vec<_> = stats
+                .tag_distribution
+                .iter()
+                .map(|t| (t.tag_name.as_str(), t.study_minutes, t.total_tasks, t.completed_tasks, t.completion_rate))
+                .collect();
+            assert_eq!(tags, [("重点", 75, 2, 1, 75.0), ("错题", 50, 1, 0, 50.0)]);
+
+            // 范围内没有任务和学习记录时不返回标签
+            let stats = stats_service::query_stats(&db, alice, date("2025-04-01"), date("2025-04-07")).await.unwrap();
+            assert!(stats.tag_distribution.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_tag_stats_count_pomodoros_on_tagged_occurrences() {
+        block_on(async {
+            let db = memory_pool().await;
+            let alice = seed_activity(&db).await;
+            let repo = SqlRepository::new(db.clone());
+            let series = execute(&db, &format!(
+                "INSERT INTO task_series (user_id, start_date, start_time, end_time, content, rrule)
+                 VALUES ({}, '2025-03-05', '10:00:00', '11:00:00', '背单词', 'FREQ=DAILY;INTERVAL=1;COUNT=3')",
+                alice
+            )).await;
+            let words = CreateTagInput { name: "单词".to_string(), color: None };
+            let tag = tag_service::create_tag(&repo, alice, words).await.unwrap();
+
+            // 给 03-05 的重复任务加标签 (生成实际任务)，再对它和尚未生成的 03-06 各开始一个番茄钟
+            let occurrence = |day: &str| occurrence_task_id(series, date(day));
+            let tagged = tag_service::set_task_tags(&repo, alice, occurrence("2025-03-05"), &[tag.id]).await.unwrap();
+            let mut pomodoros = Vec::new();
+            for task_id in [tagged.id, occurrence("2025-03-06")] {
+                let input = StartPomodoroInput { subject_id: None, task_id: Some(task_id) };
+                pomodoros.push(pomodoro_service::create_pomodoro(&db, alice, input).await.unwrap());
+            }
+            let missing = StartPomodoroInput { subject_id: None, task_id: Some(occurrence("2025-03-08")) };
+            let err = pomodoro_service::create_pomodoro(&db, alice, missing).await.unwrap_err();
+            assert_eq!(err.code(), "not_found");
+            execute(&db, &format!(
+                "UPDATE pomodoro_sessions SET status = 'completed', duration_minutes = 40,
+                    start_time = '2025-03-05T10:00:00+00:00' WHERE id IN ({}, {})",
+                pomodoros[0], pomodoros[1]
+            )).await;
+
+            // 开始番茄钟时生成的 03-06 任务与尚未生成的 03-07 任务一起计入总数，但没有标签
+            let stats = stats_service::query_stats(&db, alice, date("2025-03-01"), date("2025-03-07")).await.unwrap();
+            assert_eq!((stats.total_study_minutes, stats.total_tasks), (75 + 80, 2 + 3));
+            let words = stats.tag_distribution.iter().find(|t| t.tag_name == "单词").unwrap();
+            assert_eq!((words.study_minutes, words.total_tasks, words.completed_tasks), (40, 1, 0));
+        });
+    }
+
+    #[test]
+    fn test_user_activity_query() {
+        block_on(async {
+            let db = memory_pool().await;
+            seed_activity(&db).await;
+            let today = date("2025-03-10");
+            let activity = stats_service::query_user_activity(&db, date("2025-03-01"), date("2025-03-07"), today)
+                .await
+                .unwrap();
+
+            // 按创建时间倒序：迁移创建的管理员、bob、alice
+            let usernames: Vec<&str> = activity.iter().map(|a| a.username.as_str()).collect();
+            assert_eq!(usernames, ["admin", "bob", "alice"]);
+
+            let alice = &activity[2];
+            assert!(alice.is_active);
+            assert_eq!(alice.last_login_at.unwrap().to_rfc3339(), "2025-03-06T09:00:00+00:00");
+            assert_eq!((alice.study_minutes, alice.completed_pomodoros), (75, 2));
+            assert_eq!((alice.total_tasks, alice.completed_tasks), (2, 1));
+            assert_eq!(alice.completion_rate, 75.0);
+            assert_eq!(alice.last_review_date, Some(date("2025-03-05")));
+            assert_eq!(alice.days_since_review, Some(5));
+            assert!(alice.ai_configured);
+
+            let bob = &activity[1];
+            assert!(!bob.is_active);
+            assert_eq!(bob.role_label, "导师");
+            assert!(bob.last_login_at.is_none());
+            assert_eq!((bob.study_minutes, bob.completed_pomodoros), (0, 0));
+            assert_eq!((bob.total_tasks, bob.completed_tasks), (1, 0));
+            assert!(bob.days_since_review.is_none());
+            assert!(!bob.ai_configured);
+
+            let err = stats_service::query_user_activity(&db, date("2025-03-07"), date("2025-03-01"), today)
+                .await
+                .unwrap_err();
+            assert_eq!(err.code(), "validation");
+        });
+    }
+}
@@ -0,0 +1,552 @@
+// 同步测试
+// 双方都修改同一条记录时，updated_at 较新的一方胜出，时间相同时以服务器为准；
+// 推送、拉取与删除在两个内存 SQLite 数据库 (本地与模拟的服务器) 之间验证
+
+use super::{block_on, execute, memory_pool};
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::models::ConflictResolution;
+use crate::services::sync_service::{self, merged_version, resolve_conflict};
+use crate::utils::password;
+use chrono::{DateTime, Duration, Utc};
+use proptest::prelude::*;
+
+/// 服务器上参与同步的表及其记录类型
+const SERVER_TABLES: [(&str, &str); 10] = [
+    ("subjects", "subject"),
+    ("task_series", "series"),
+    ("tasks", "task"),
+    ("task_series_skips", "series_skip"),
+    ("pomodoro_sessions", "pomodoro"),
+    ("daily_reviews", "review"),
+    ("task_checklist_items", "checklist_item"),
+    ("task_tags", "tag"),
+    ("task_tag_links", "tag_link"),
+    ("task_status_history", "status_change"),
+];
+
+const SERVER_PASSWORD: &str = "Server1234";
+
+/// 在内存 SQLite 上模拟同步服务器 (MySQL) 的同步结构：不记录本地变更，
+/// 修改时递增版本并更新 synced_at，删除时写入墓碑
+async fn server_pool() -> DbPool {
+    let now = "strftime('%Y-%m-%dT%H:%M:%S+00:00', 'now')";
+    let mut sql = format!(
+        "CREATE TABLE sync_tombstones (
+            id INTEGER PRIMARY KEY AUTOINCREMENT, entity TEXT NOT NULL, sync_id TEXT NOT NULL,
+            user_id INTEGER NOT NULL, version INTEGER NOT NULL, deleted_at TEXT NOT NULL DEFAULT ({now}));
+        CREATE TABLE sync_tokens (
+            id INTEGER PRIMARY KEY AUTOINCREMENT, user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            token_hash TEXT NOT NULL, created_at TEXT NOT NULL DEFAULT ({now}), last_used_at TEXT);"
+    );
+    for (table, entity) in SERVER_TABLES {
+        sql.push_str(&format!(
+            "DROP TRIGGER trg_{table}_sync_id;
+            DROP TRIGGER trg_{table}_sync_insert;
+            DROP TRIGGER trg_{table}_sync_update;
+            DROP TRIGGER trg_{table}_sync_delete;
+            ALTER TABLE {table} ADD COLUMN synced_at TEXT;
+            CREATE TRIGGER trg_{table}_server_insert AFTER INSERT ON {table}
+            BEGIN
+                UPDATE {table} SET sync_id = COALESCE(sync_id, lower(hex(randomblob(16)))),
+                    updated_at = COALESCE(updated_at, {now}), synced_at = {now}
+                WHERE id = NEW.id;
+            END;
+            CREATE TRIGGER trg_{table}_server_update AFTER UPDATE ON {table}
+            WHEN NEW.synced_at IS OLD.synced_at
+            BEGIN
+                UPDATE {table} SET version = NEW.version + (NEW.version = OLD.version), synced_at = {now}
+                WHERE id = NEW.id;
+            END;
+            CREATE TRIGGER trg_{table}_server_delete AFTER DELETE ON {table}
+            BEGIN
+                INSERT INTO sync_tombstones (entity, sync_id, user_id, version)
+                VALUES ('{entity}', OLD.sync_id, OLD.user_id, OLD.version);
+            END;"
+        ));
+    }
+
+    let pool = memory_pool().await;
+    execute(&pool, &sql).await;
+    pool
+}
+
+/// 查询单个值
+async fn query_one<T>(db: &DbPool, sql: &str) -> T
+where
+    T: Send + Unpin + for<'r> sqlx::Decode<'r, sqlx::Sqlite> + sqlx::Type<sqlx::Sqlite>,
+{
+    let DbPool::Sqlite(pool) = db else { unreachable!() };
+    sqlx::query_scalar(sql).fetch_one(pool).await.unwrap()
+}
+
+/// 在服务器上创建用户 bob 和 alice (alice 在服务器上的用户 ID 与本地不同)
+async fn create_server_users(remote: &DbPool) {
+    let hash = password::hash_password(SERVER_PASSWORD).unwrap();
+    execute(remote, "INSERT INTO users (username, password, display_name) VALUES ('bob', 'x', 'Bob')").await;
+    execute(
+        remote,
+        &format!("INSERT INTO users (username, password, display_name) VALUES ('alice', '{hash}', 'Alice')"),
+    )
+    .await;
+}
+
+/// 在本地创建用户 alice，返回本地用户 ID
+async fn create_local_alice(local: &DbPool) -> i64 {
+    execute(local, "INSERT INTO users (username, password, display_name) VALUES ('alice', 'local', 'Alice')").await
+}
+
+/// 在两端创建同名用户 alice，返回本地用户 ID
+async fn create_alice(local: &DbPool, remote: &DbPool) -> i64 {
+    create_server_users(remote).await;
+    create_local_alice(local).await
+}
+
+/// 创建一台已验证服务器账户的设备，返回本地数据库与用户 ID
+async fn linked_device(remote: &DbPool) -> (DbPool, i64) {
+    let local = memory_pool().await;
+    let alice = create_local_alice(&local).await;
+    sync_service::issue_token(&local, remote, alice, SERVER_PASSWORD).await.unwrap();
+    (local, alice)
+}
+
+/// 同步一次并返回 (推送, 拉取, 冲突) 数量
+async fn sync(local: &DbPool, remote: &DbPool, user_id: i64) -> (u32, u32, u32) {
+    let report = sync_service::run_sync(local, remote, user_id).await.unwrap();
+    (report.pushed, report.pulled, report.conflicts)
+}
+
+/// 本地创建一个科目和一个属于该科目的任务，返回任务 ID
+async fn create_task(local: &DbPool, user_id: i64, content: &str) -> i64 {
+    let subject_id = execute(
+        local,
+        &format!("INSERT INTO subjects (user_id, name, color) VALUES ({user_id}, '科目 {content}', '#10B981')"),
+    )
+    .await;
+    execute(
+        local,
+        &format!(
+            "INSERT INTO tasks (user_id, subject_id, task_date, start_time, end_time, content)
+             VALUES ({user_id}, {subject_id}, '2025-03-03', '08:00:00', '09:00:00', '{content}')"
+        ),
+    )
+    .await
+}
+
+/// 在服务器上直接修改任务内容 (模拟直接连接服务器的客户端)
+async fn edit_on_server(remote: &DbPool, content: &str, new_content: &str, updated_at: &str) {
+    let sql = format!(
+        "UPDATE tasks SET content = '{new_content}', updated_at = '{updated_at}' WHERE content = '{content}'"
+    );
+    execute(remote, &sql).await;
+}
+
+/// 查询用户的任务内容及其科目名称 (按内容排序)
+async fn task_contents(db: &DbPool, username: &str) -> Vec<(String, Option<String>)> {
+    let DbPool::Sqlite(pool) = db else { unreachable!() };
+    sqlx::query_as(
+        "SELECT t.content, s.name FROM tasks t JOIN users u ON u.id = t.user_id
+         LEFT JOIN subjects s ON s.id = t.subject_id WHERE u.username = ? ORDER BY t.content",
+    )
+    .bind(username)
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+/// 生成 2000-2100 年之间的时间
+fn timestamp_strategy() -> impl Strategy<Value = DateTime<Utc>> {
+    (946_684_800i64..4_102_444_800i64).prop_map(|secs| DateTime::from_timestamp(secs, 0).unwrap())
+}
+
+proptest! {
+    /// 较新的修改总是胜出
+    #[test]
+    fn test_newer_update_wins(
+        base in timestamp_strategy(),
+        offset in 1i64..1_000_000
+    ) {
+        let newer = base + Duration::seconds(offset);
+
+        prop_assert_eq!(resolve_conflict(newer, base), ConflictResolution::LocalWins);
+        prop_assert_eq!(resolve_conflict(base, newer), ConflictResolution::RemoteWins);
+    }
+
+    /// 修改时间相同时以服务器为准
+    #[test]
+    fn test_tie_prefers_server(at in timestamp_strategy()) {
+        prop_assert_eq!(resolve_conflict(at, at), ConflictResolution::RemoteWins);
+    }
+
+    /// 合并后的版本号高于双方
+    #[test]
+    fn test_merged_version_exceeds_both(
+        local in 0i64..1_000_000,
+        remote in 0i64..1_000_000
+    ) {
+        let version = merged_version(local, remote);
+        prop_assert!(version > local);
+        prop_assert!(version > remote);
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_requires_server_password() {
+        block_on(async {
+            let (local, remote) = (memory_pool().await, server_pool().await);
+            let alice = create_alice(&local, &remote).await;
+
+            // 没有同步令牌时拒绝同步，密码错误时不发放令牌
+            let result = sync_service::run_sync(&local, &remote, alice).await;
+            assert!(matches!(result, Err(AppError::Unauthorized(_))));
+            let result = sync_service::issue_token(&local, &remote, alice, "Wrong1234").await;
+            assert!(matches!(result, Err(AppError::Unauthorized(_))));
+            // 本地的默认管理员在服务器上的密码不同
+            let result = sync_service::issue_token(&local, &remote, 1, "admin1234").await;
+            assert!(matches!(result, Err(AppError::Unauthorized(_))));
+            assert_eq!(query_one::<i64>(&remote, "SELECT COUNT(*) FROM sync_tokens").await, 0);
+
+            sync_service::issue_token(&local, &remote, alice, SERVER_PASSWORD).await.unwrap();
+            sync_service::run_sync(&local, &remote, alice).await.unwrap();
+
+            // 令牌被篡改或在服务器上被撤销后不能再同步
+            let token: String =
+                query_one(&local, &format!("SELECT server_token FROM sync_state WHERE user_id = {alice}")).await;
+            let set_token = |token: String| format!("UPDATE sync_state SET server_token = '{token}'");
+            execute(&local, &set_token(format!("{token}x"))).await;
+            let result = sync_service::run_sync(&local, &remote, alice).await;
+            assert!(matches!(result, Err(AppError::Unauthorized(_))));
+            execute(&local, &set_token(token)).await;
+            execute(&remote, "DELETE FROM sync_tokens").await;
+            let result = sync_service::run_sync(&local, &remote, alice).await;
+            assert!(matches!(result, Err(AppError::Unauthorized(_))));
+        });
+    }
+
+    #[test]
+    fn test_token_is_bound_to_username() {
+        block_on(async {
+            let (local, remote) = (memory_pool().await, server_pool().await);
+            let alice = create_alice(&local, &remote).await;
+            sync_service::issue_token(&local, &remote, alice, SERVER_PASSWORD).await.unwrap();
+
+            // 服务器上的账户改名或停用后令牌失效
+            execute(&remote, "UPDATE users SET username = 'alice2' WHERE username = 'alice'").await;
+            let result = sync_service::run_sync(&local, &remote, alice).await;
+            assert!(matches!(result, Err(AppError::Unauthorized(_))));
+            execute(
+                &remote,
+                "UPDATE users SET username = 'alice', deactivated_at = '2025-01-01T00:00:00Z'
+                 WHERE username = 'alice2'",
+            )
+            .await;
+            let result = sync_service::run_sync(&local, &remote, alice).await;
+            assert!(matches!(result, Err(AppError::Unauthorized(_))));
+        });
+    }
+
+    #[test]
+    fn test_push_and_pull_between_devices() {
+        block_on(async {
+            let remote = server_pool().await;
+            create_server_users(&remote).await;
+            let (laptop, laptop_alice) = linked_device(&remote).await;
+            let (desktop, desktop_alice) = linked_device(&remote).await;
+
+            create_task(&laptop, laptop_alice, "背单词").await;
+            assert_eq!(sync(&laptop, &remote, laptop_alice).await, (2, 0, 0));
+            // 服务器上的记录属于服务器上的 alice，科目引用转换为服务器上的 ID
+            let expected = vec![("背单词".to_string(), Some("科目 背单词".to_string()))];
+            assert_eq!(task_contents(&remote, "alice").await, expected);
+            let pending = format!("SELECT COUNT(*) FROM sync_changes WHERE user_id = {laptop_alice}");
+            assert_eq!(query_one::<i64>(&laptop, &pending).await, 0);
+
+            assert_eq!(sync(&desktop, &remote, desktop_alice).await, (0, 2, 0));
+            assert_eq!(task_contents(&desktop, "alice").await, expected);
+            // 拉取的记录不会被当作本地修改再次推送
+            assert_eq!(sync(&desktop, &remote, desktop_alice).await, (0, 0, 0));
+
+            execute(&desktop, "UPDATE tasks SET content = '背单词 100 个'").await;
+            assert_eq!(sync(&desktop, &remote, desktop_alice).await, (1, 0, 0));
+            assert_eq!(sync(&laptop, &remote, laptop_alice).await, (0, 1, 0));
+            assert_eq!(task_contents(&laptop, "alice").await[0].0, "背单词 100 个");
+        });
+    }
+
+    #[test]
+    fn test_deletes_propagate_through_tombstones() {
+        block_on(async {
+            let remote = server_pool().await;
+            create_server_users(&remote).await;
+            let (laptop, laptop_alice) = linked_device(&remote).await;
+            let (desktop, desktop_alice) = linked_device(&remote).await;
+            let task_id = create_task(&laptop, laptop_alice, "背单词").await;
+            create_task(&laptop, laptop_alice, "刷题").await;
+            sync(&laptop, &remote, laptop_alice).await;
+            sync(&desktop, &remote, desktop_alice).await;
+
+            // 本地删除推送到服务器并留下墓碑，其他设备拉取后删除
+            execute(&laptop, &format!("DELETE FROM tasks WHERE id = {task_id}")).await;
+            assert_eq!(sync(&laptop, &remote, laptop_alice).await, (1, 0, 0));
+            let tombstones = "SELECT COUNT(*) FROM sync_tombstones WHERE entity = 'task'";
+            assert_eq!(query_one::<i64>(&remote, tombstones).await, 1);
+            assert_eq!(sync(&desktop, &remote, desktop_alice).await, (0, 1, 0));
+            assert_eq!(task_contents(&desktop, "alice").await.len(), 1);
+
+            // 服务器上删除的记录在本地同样删除，且不会再作为本地删除推送
+            execute(&remote, "DELETE FROM tasks").await;
+            assert_eq!(sync(&desktop, &remote, desktop_alice).await, (0, 1, 0));
+            assert!(task_contents(&desktop, "alice").await.is_empty());
+            let changes: i64 = query_one(&desktop, "SELECT COUNT(*) FROM sync_changes WHERE entity = 'task'").await;
+            assert_eq!(changes, 0);
+        });
+    }
+
+    #[test]
+    fn test_pull_is_incremental() {
+        block_on(async {
+            let remote = server_pool().await;
+            create_server_users(&remote).await;
+            let (laptop, alice) = linked_device(&remote).await;
+            create_task(&laptop, alice, "背单词").await;
+            create_task(&laptop, alice, "刷题").await;
+            sync(&laptop, &remote, alice).await;
+
+            // 服务器收到修改的时间早于上次拉取时不会再次拉取
+            edit_on_server(&remote, "背单词", "背单词 100 个", "2030-01-01T00:00:00Z").await;
+            let backdate = "UPDATE tasks SET synced_at = '2000-01-01T00:00:00+00:00' WHERE content LIKE '背单词%'";
+            execute(&remote, backdate).await;
+            edit_on_server(&remote, "刷题", "刷题 2 套", "2030-01-01T00:00:00Z").await;
+            assert_eq!(sync(&laptop, &remote, alice).await, (0, 1, 0));
+            let contents: Vec<String> = task_contents(&laptop, "alice").await.into_iter().map(|(c, _)| c).collect();
+            assert_eq!(contents, vec!["刷题 2 套", "背单词"]);
+
+            // 清除同步进度后完整拉取
+            execute(&laptop, "UPDATE sync_state SET last_pulled_at = NULL").await;
+            assert_eq!(sync(&laptop, &remote, alice).await, (0, 1, 0));
+            let contents: Vec<String> = task_contents(&laptop, "alice").await.into_iter().map(|(c, _)| c).collect();
+            assert_eq!(contents, vec!["刷题 2 套", "背单词 100 个"]);
+        });
+    }
+
+    #[test]
+    fn test_conflicts_are_kept_for_review() {
+        block_on(async {
+            let remote = server_pool().await;
+            create_server_users(&remote).await;
+            let (laptop, alice) = linked_device(&remote).await;
+            create_task(&laptop, alice, "背单词").await;
+            create_task(&laptop, alice, "刷题").await;
+            sync(&laptop, &remote, alice).await;
+
+            // 两端都修改：本地较新时覆盖服务器，服务器较新时覆盖本地，落败的一方都保存为冲突记录
+            execute(&laptop, "UPDATE tasks SET content = '背单词 (本地)' WHERE content = '背单词'").await;
+            execute(&laptop, "UPDATE tasks SET content = '刷题 (本地)' WHERE content = '刷题'").await;
+            edit_on_server(&remote, "背单词", "背单词 (服务器)", "2000-01-01T00:00:00Z").await;
+            edit_on_server(&remote, "刷题", "刷题 (服务器)", "2999-01-01T00:00:00Z").await;
+            assert_eq!(sync(&laptop, &remote, alice).await, (1, 1, 2));
+
+            let expected = vec!["刷题 (服务器)".to_string(), "背单词 (本地)".to_string()];
+            for db in [&laptop, &remote] {
+                let contents: Vec<String> = task_contents(db, "alice").await.into_iter().map(|(c, _)| c).collect();
+                assert_eq!(contents, expected);
+            }
+
+            let DbPool::Sqlite(pool) = &laptop else { unreachable!() };
+            let conflicts: Vec<(String, String, String, String)> = sqlx::query_as(
+                "SELECT entity, resolution, local_data, remote_data FROM sync_conflicts
+                 WHERE user_id = ? AND resolved = 0 ORDER BY resolution",
+            )
+            .bind(alice)
+            .fetch_all(pool)
+            .await
+            .unwrap();
+            assert_eq!(conflicts.len(), 2);
+            assert_eq!((conflicts[0].0.as_str(), conflicts[0].1.as_str()), ("task", "local_wins"));
+            assert!(conflicts[0].3.contains("背单词 (服务器)"));
+            assert_eq!(conflicts[1].1, "remote_wins");
+            assert!(conflicts[1].2.contains("刷题 (本地)"));
+
+            // 合并后两端版本一致，再次同步没有新的变更
+            assert_eq!(sync(&laptop, &remote, alice).await, (0, 0, 0));
+        });
+    }
+
+    #[test]
+    fn test_recurring_tasks_sync_between_devices() {
+        block_on(async {
+            let remote = server_pool().await;
+            create_server_users(&remote).await;
+            let (laptop, laptop_alice) = linked_device(&remote).await;
+            let (desktop, desktop_alice) = linked_device(&remote).await;
+
+            // 03-03 至 03-05 每天重复：03-04 已生成并完成，03-05 已被单独删除
+            let series = execute(&laptop, &format!(
+                "INSERT INTO task_series (user_id, start_date, start_time, end_time, content, rrule)
+                 VALUES ({laptop_alice}, '2025-03-03', '08:00:00', '09:00:00', '背单词',
+                         'FREQ=DAILY;INTERVAL=1;COUNT=3')"
+            )).await;
+            execute(&laptop, &format!(
+                "INSERT INTO tasks (user_id, task_date, start_time, end_time, content, status,
+                                    series_id, occurrence_date)
+                 VALUES ({laptop_alice}, '2025-03-04', '08:00:00', '09:00:00', '背单词', 'completed', {series},
+                         '2025-03-04');
+                 INSERT INTO task_series_skips (series_id, occurrence_date) VALUES ({series}, '2025-03-05')"
+            )).await;
+            assert_eq!(sync(&laptop, &remote, laptop_alice).await, (3, 0, 0));
+            assert_eq!(sync(&desktop, &remote, desktop_alice).await, (0, 3, 0));
+
+            // 系列、生成的任务与删除记录的引用都转换为本机的 ID
+            let series: i64 = query_one(&desktop, "SELECT id FROM task_series WHERE content = '背单词'").await;
+            let occurrence: (i64, String, String) = {
+                let DbPool::Sqlite(pool) = &desktop else { unreachable!() };
+                sqlx::query_as("SELECT series_id, occurrence_date, status FROM tasks").fetch_one(pool).await.unwrap()
+            };
+            assert_eq!(occurrence, (series, "2025-03-04".to_string(), "completed".to_string()));
+            let skip = format!("SELECT occurrence_date FROM task_series_skips WHERE series_id = {series}");
+            assert_eq!(query_one::<String>(&desktop, &skip).await, "2025-03-05");
+
+            // 两台设备各自生成同一次重复时按系列与日期对应为同一条任务
+            let materialize = |user_id: i64, series: i64, status: &str| format!(
+                "INSERT INTO tasks (user_id, task_date, start_time, end_time, content, status,
+                                    series_id, occurrence_date)
+                 VALUES ({user_id}, '2025-03-03', '08:00:00', '09:00:00', '背单词', '{status}', {series}, '2025-03-03')"
+            );
+            execute(&desktop, &materialize(desktop_alice, series, "completed")).await;
+            assert_eq!(sync(&desktop, &remote, desktop_alice).await, (1, 0, 0));
+            let laptop_series: i64 = query_one(&laptop, "SELECT id FROM task_series").await;
+            execute(&laptop, &materialize(laptop_alice, laptop_series, "pending")).await;
+            assert_eq!(sync(&laptop, &remote, laptop_alice).await.2, 1);
+
+            let same_day = "SELECT COUNT(*) FROM tasks WHERE occurrence_date = '2025-03-03'";
+            assert_eq!(query_one::<i64>(&remote, same_day).await, 1);
+            assert_eq!(query_one::<i64>(&laptop, same_day).await, 1);
+            let sync_id = "SELECT sync_id FROM tasks WHERE occurrence_date = '2025-03-03'";
+            assert_eq!(query_one::<String>(&laptop, sync_id).await, query_one::<String>(&remote, sync_id).await);
+        });
+    }
+
+    #[test]
+    fn test_carried_tasks_keep_their_origin() {
+        block_on(async {
+            let remote = server_pool().await;
+            create_server_users(&remote).await;
+            let (laptop, laptop_alice) = linked_device(&remote).await;
+            let (desktop, desktop_alice) = linked_device(&remote).await;
+
+            let original = create_task(&laptop, laptop_alice, "背单词").await;
+            execute(&laptop, &format!(
+                "UPDATE tasks SET status = 'rolled_over' WHERE id = {original};
+                 INSERT INTO tasks (user_id, task_date, start_time, end_time, content, carried_from_id)
+                 VALUES ({laptop_alice}, '2025-03-04', '08:00:00', '09:00:00', '背单词 (顺延)', {original})"
+            )).await;
+            sync(&laptop, &remote, laptop_alice).await;
+            sync(&desktop, &remote, desktop_alice).await;
+
+            // 顺延来源转换为本机上原任务的 ID
+            let origin = "SELECT c.content FROM tasks t JOIN tasks c ON c.id = t.carried_from_id";
+            assert_eq!(query_one::<String>(&remote, origin).await, "背单词");
+            assert_eq!(query_one::<String>(&desktop, origin).await, "背单词");
+        });
+    }
+
+    #[test]
+    fn test_checklists_sync_with_their_task() {
+        block_on(async {
+            let remote = server_pool().await;
+            create_server_users(&remote).await;
+            let (laptop, laptop_alice) = linked_device(&remote).await;
+            let (desktop, desktop_alice) = linked_device(&remote).await;
+
+            let task_id = create_task(&laptop, laptop_alice, "背单词").await;
+            execute(&laptop, &format!(
+                "INSERT INTO task_checklist_items (task_id, position, content, completed)
+                 VALUES ({task_id}, 0, 'Unit 1', 1), ({task_id}, 1, 'Unit 2', 0);
+                 UPDATE tasks SET progress = 50 WHERE id = {task_id}"
+            )).await;
+            assert_eq!(sync(&laptop, &remote, laptop_alice).await, (4, 0, 0));
+            assert_eq!(sync(&desktop, &remote, desktop_alice).await, (0, 4, 0));
+
+            // 清单项归属本机上对应的任务，进度随任务同步
+            let checklist = "SELECT group_concat(c.content || ':' || c.completed, ',')
+                FROM task_checklist_items c JOIN tasks t ON t.id = c.task_id
+                WHERE t.content = '背单词' AND t.progress = 50 ORDER BY c.position";
+            assert_eq!(query_one::<String>(&desktop, checklist).await, "Unit 1:1,Unit 2:0");
+
+            // 删除清单项同样同步
+            execute(&desktop, "DELETE FROM task_checklist_items WHERE content = 'Unit 2'").await;
+            assert_eq!(sync(&desktop, &remote, desktop_alice).await, (1, 0, 0));
+            assert_eq!(sync(&laptop, &remote, laptop_alice).await, (0, 1, 0));
+            assert_eq!(query_one::<String>(&laptop, checklist).await, "Unit 1:1");
+        });
+    }
+
+    #[test]
+    fn test_priorities_and_tags_sync() {
+        block_on(async {
+            let remote = server_pool().await;
+            create_server_users(&remote).await;
+            let (laptop, laptop_alice) = linked_device(&remote).await;
+            let (desktop, desktop_alice) = linked_device(&remote).await;
+
+            let task_id = create_task(&laptop, laptop_alice, "背单词").await;
+            let tag_id = execute(&laptop, &format!(
+                "INSERT INTO task_tags (user_id, name, color) VALUES ({laptop_alice}, '重点', '#EF4444')"
+            )).await;
+            execute(&laptop, &format!(
+                "UPDATE tasks SET priority = 'high' WHERE id = {task_id};
+                 INSERT INTO task_tag_links (task_id, tag_id) VALUES ({task_id}, {tag_id})"
+            )).await;
+            assert_eq!(sync(&laptop, &remote, laptop_alice).await, (4, 0, 0));
+
+            // 另一台设备上各自创建的同名标签合并为同一个
+            execute(&desktop, &format!(
+                "INSERT INTO task_tags (user_id, name, color) VALUES ({desktop_alice}, '重点', '#EF4444')"
+            )).await;
+            assert_eq!(sync(&desktop, &remote, desktop_alice).await, (0, 4, 0));
+            let tags = "SELECT group_concat(g.name) FROM task_tag_links l
+                JOIN tasks t ON t.id = l.task_id JOIN task_tags g ON g.id = l.tag_id
+                WHERE t.content = '背单词' AND t.priority = 'high'";
+            assert_eq!(query_one::<String>(&desktop, tags).await, "重点");
+            assert_eq!(query_one::<i64>(&desktop, "SELECT COUNT(*) FROM task_tags").await, 1);
+
+            // 移除任务的标签同样同步
+            execute(&desktop, "DELETE FROM task_tag_links").await;
+            assert_eq!(sync(&desktop, &remote, desktop_alice).await, (1, 0, 0));
+            assert_eq!(sync(&laptop, &remote, laptop_alice).await, (0, 1, 0));
+            let links = "SELECT COUNT(*) FROM task_tag_links";
+            assert_eq!(query_one::<i64>(&laptop, links).await, 0);
+        });
+    }
+
+    #[test]
+    fn test_status_reasons_and_history_sync() {
+        block_on(async {
+            let remote = server_pool().await;
+            create_server_users(&remote).await;
+            let (laptop, laptop_alice) = linked_device(&remote).await;
+            let (desktop, desktop_alice) = linked_device(&remote).await;
+
+            let task_id = create_task(&laptop, laptop_alice, "背单词").await;
+            execute(&laptop, &format!(
+                "UPDATE tasks SET status = 'failed', status_reason = '时间不够' WHERE id = {task_id};
+                 INSERT INTO task_status_history (task_id, from_status, to_status, reason, changed_at)
+                 VALUES ({task_id}, 'pending', 'failed', '时间不够', '2025-03-03T20:00:00Z')"
+            )).await;
+            assert_eq!(sync(&laptop, &remote, laptop_alice).await, (3, 0, 0));
+            assert_eq!(sync(&desktop, &remote, desktop_alice).await, (0, 3, 0));
+
+            // 状态原因随任务同步，变更记录归属本机上对应的任务
+            let reason = "SELECT status_reason FROM tasks WHERE content = '背单词'";
+            assert_eq!(query_one::<String>(&desktop, reason).await, "时间不够");
+            let history = "SELECT h.from_status || '>' || h.to_status || ':' || h.reason || '@' || h.changed_at
+                FROM task_status_history h JOIN tasks t ON t.id = h.task_id WHERE t.content = '背单词'";
+            let expected = "pending>failed:时间不够@2025-03-03T20:00:00+00:00";
+            assert_eq!(query_one::<String>(&desktop, history).await, expected);
+        });
+    }
+}
@@ -0,0 +1,276 @@
+// 任务优先级与标签属性测试
+
+use super::block_on;
+use crate::error::AppError;
+use crate::models::{
+    CarryOverMode, CreateTagInput, CreateTaskInput, CreateTaskSeriesInput, Frequency, RecurrenceRule,
+    RecurrenceScope, StudyWindow, Tag, TaskFilter, TaskPriority, TaskResponse, UpdateTagInput, UpdateTaskInput,
+};
+use crate::repositories::{MemoryRepository, UserRepository};
+use crate::services::{carry_over_service, tag_service, task_service};
+use chrono::{Days, NaiveDate, NaiveTime};
+use proptest::prelude::*;
+use std::collections::BTreeSet;
+
+const USER_ID: i64 = 1;
+
+fn date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 3, 3).unwrap()
+}
+
+fn time(h: u32, m: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(h, m, 0).unwrap()
+}
+
+fn create_task(repo: &MemoryRepository, hour: u32, priority: Option<TaskPriority>) -> TaskResponse {
+    block_on(task_service::create_task(
+        repo,
+        USER_ID,
+        CreateTaskInput {
+            subject_id: None,
+            task_date: date(),
+            start_time: time(hour, 0),
+            end_time: time(hour, 45),
+            content: format!("任务 {}", hour),
+            alarm_enabled: None,
+            alarm_time: None,
+            priority,
+        },
+        &StudyWindow::default(),
+    ))
+    .unwrap()
+}
+
+fn create_tag(repo: &MemoryRepository, user_id: i64, name: &str) -> Result<Tag, AppError> {
+    let input = CreateTagInput {
+        name: name.to_string(),
+        color: None,
+    };
+    block_on(tag_service::create_tag(repo, user_id, input))
+}
+
+fn set_tags(repo: &MemoryRepository, task_id: i64, tag_ids: &[i64]) -> Result<TaskResponse, AppError> {
+    block_on(tag_service::set_task_tags(repo, USER_ID, task_id, tag_ids))
+}
+
+fn range_ids(repo: &MemoryRepository, filter: TaskFilter) -> BTreeSet<i64> {
+    block_on(task_service::get_tasks_in_range(repo, USER_ID, date(), date(), filter))
+        .unwrap()
+        .into_iter()
+        .flat_map(|day| day.tasks)
+        .map(|task| task.id)
+        .collect()
+}
+
+fn priority_strategy() -> impl Strategy<Value = TaskPriority> {
+    prop_oneof![Just(TaskPriority::Low), Just(TaskPriority::Normal), Just(TaskPriority::High)]
+}
+
+proptest! {
+    /// 按优先级和标签筛选的结果恰好是满足条件的任务
+    #[test]
+    fn test_range_filters_by_priority_and_tag(
+        tasks in proptest::collection::btree_map(6u32..22, (priority_strategy(), 0usize..8), 1..12),
+        priority in proptest::option::of(priority_strategy()),
+        tag in proptest::option::of(0usize..3),
+    ) {
+        let repo = MemoryRepository::new();
+        let tags: Vec<i64> = ["背诵", "刷题", "视频课"]
+            .iter()
+            .map(|name| create_tag(&repo, USER_ID, name).unwrap().id)
+            .collect();
+
+        // mask 的每一位表示任务是否带有对应的标签
+        let mut expected = BTreeSet::new();
+        for (hour, (task_priority, mask)) in &tasks {
+            let task = create_task(&repo, *hour, Some(*task_priority));
+            let task_tags: Vec<i64> = (0..3).filter(|i| mask & (1 << i) != 0).map(|i| tags[i]).collect();
+            let response = set_tags(&repo, task.id, &task_tags).unwrap();
+            prop_assert_eq!(response.tags.len(), task_tags.len());
+
+            if priority.is_none_or(|p| p == *task_priority) && tag.is_none_or(|i| mask & (1 << i) != 0) {
+                expected.insert(task.id);
+            }
+        }
+
+        let filter = TaskFilter {
+            priority,
+            tag_id: tag.map(|i| tags[i]),
+            ..Default::default()
+        };
+        prop_assert_eq!(range_ids(&repo, filter), expected);
+    }
+
+    /// 设置标签后任务的标签恰好是去重后的给定标签 (按名称排序)
+    #[test]
+    fn test_set_task_tags_replaces_tags(
+        first in proptest::collection::vec(0usize..5, 0..8),
+        second in proptest::collection::vec(0usize..5, 0..8),
+    ) {
+        let repo = MemoryRepository::new();
+        let tags: Vec<Tag> = (0..5).map(|i| create_tag(&repo, USER_ID, &format!("标签{}", i)).unwrap()).collect();
+        let task = create_task(&repo, 8, None);
+
+        for chosen in [&first, &second] {
+            let ids: Vec<i64> = chosen.iter().map(|i| tags[*i].id).collect();
+            let response = set_tags(&repo, task.id, &ids).unwrap();
+
+            let names: Vec<&str> = response.tags.iter().map(|g| g.name.as_str()).collect();
+            let expected: BTreeSet<&str> = chosen.iter().map(|i| tags[*i].name.as_str()).collect();
+            prop_assert_eq!(names, expected.into_iter().collect::<Vec<_>>());
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_crud_and_validation() {
+        let repo = MemoryRepository::new();
+        let tag = create_tag(&repo, USER_ID, "  刷题  ").unwrap();
+        assert_eq!((tag.name.as_str(), tag.color.as_str()), ("刷题", "#6B7280"));
+
+        assert!(matches!(create_tag(&repo, USER_ID, "刷题"), Err(AppError::Conflict(_))));
+        assert!(matches!(create_tag(&repo, USER_ID, " "), Err(AppError::Validation(_))));
+        let long = "题".repeat(tag_service::MAX_TAG_NAME_CHARS + 1);
+        assert!(matches!(create_tag(&repo, USER_ID, &long), Err(AppError::Validation(_))));
+        // 不同用户可以使用相同的名称
+        create_tag(&repo, USER_ID + 1, "刷题").unwrap();
+
+        let other = create_tag(&repo, USER_ID, "背诵").unwrap();
+        let rename = |name: &str| {
+            let input = UpdateTagInput {
+                name: Some(name.to_string()),
+                ..Default::default()
+            };
+            block_on(tag_service::update_tag(&repo, USER_ID, other.id, input))
+        };
+        assert!(matches!(rename("刷题"), Err(AppError::Conflict(_))));
+        assert_eq!(rename("背诵").unwrap().name, "背诵");
+        let updated = rename("视频课").unwrap();
+        assert_eq!((updated.name.as_str(), updated.color.as_str()), ("视频课", "#6B7280"));
+
+        let names: Vec<String> =
+            block_on(tag_service::get_tags(&repo, USER_ID)).unwrap().into_iter().map(|g| g.name).collect();
+        assert_eq!(names, vec!["刷题", "视频课"]);
+    }
+
+    #[test]
+    fn test_set_task_tags_rejects_foreign_and_too_many_tags() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo, 8, None);
+        let foreign = create_tag(&repo, USER_ID + 1, "刷题").unwrap();
+        assert!(matches!(set_tags(&repo, task.id, &[foreign.id]), Err(AppError::NotFound(_))));
+
+        let ids: Vec<i64> = (0..=tag_service::MAX_TAGS_PER_TASK)
+            .map(|i| create_tag(&repo, USER_ID, &format!("标签{}", i)).unwrap().id)
+            .collect();
+        assert!(matches!(set_tags(&repo, task.id, &ids), Err(AppError::Validation(_))));
+        let result = block_on(tag_service::set_task_tags(&repo, USER_ID + 1, task.id, &ids[..1]));
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_deleting_tag_or_task_removes_links() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo, 8, None);
+        let keep = create_tag(&repo, USER_ID, "背诵").unwrap();
+        let removed = create_tag(&repo, USER_ID, "刷题").unwrap();
+        set_tags(&repo, task.id, &[keep.id, removed.id]).unwrap();
+
+        block_on(tag_service::delete_tag(&repo, USER_ID, removed.id)).unwrap();
+        let tasks = block_on(task_service::get_tasks_by_date(&repo, USER_ID, date())).unwrap();
+        assert_eq!(tasks[0].tags.iter().map(|g| g.id).collect::<Vec<_>>(), vec![keep.id]);
+
+        let data = block_on(repo.load_user_data(USER_ID)).unwrap();
+        assert_eq!((data.tags.len(), data.task_tags.len()), (1, 1));
+        block_on(task_service::delete_task(&repo, USER_ID, task.id, RecurrenceScope::This)).unwrap();
+        assert!(block_on(repo.load_user_data(USER_ID)).unwrap().task_tags.is_empty());
+    }
+
+    #[test]
+    fn test_priority_defaults_and_updates() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo, 8, None);
+        assert_eq!(task.priority, TaskPriority::Normal);
+
+        let input = UpdateTaskInput {
+            priority: Some(TaskPriority::High),
+            ..Default::default()
+        };
+        let updated = block_on(task_service::update_task(
+            &repo,
+            USER_ID,
+            task.id,
+            input,
+            RecurrenceScope::This,
+            &StudyWindow::default(),
+        ))
+        .unwrap();
+        assert_eq!((updated.priority, updated.content), (TaskPriority::High, task.content));
+    }
+
+    #[test]
+    fn test_series_occurrences_inherit_priority() {
+        let repo = MemoryRepository::new();
+        let series = block_on(task_service::create_task_series(
+            &repo,
+            USER_ID,
+            CreateTaskSeriesInput {
+                subject_id: None,
+                start_date: date(),
+                start_time: time(8, 0),
+                end_time: time(11, 0),
+                content: "真题模考".to_string(),
+                alarm_enabled: None,
+                alarm_time: None,
+                priority: Some(TaskPriority::High),
+                rule: RecurrenceRule {
+                    frequency: Frequency::Weekly,
+                    interval: 1,
+                    weekdays: vec![1],
+                    until: None,
+                    count: Some(4),
+                },
+            },
+        ))
+        .unwrap();
+        assert_eq!(series.priority, TaskPriority::High);
+
+        let tasks = block_on(task_service::get_tasks_by_date(&repo, USER_ID, date() + Days::new(7))).unwrap();
+        assert_eq!(tasks[0].priority, TaskPriority::High);
+    }
+
+    #[test]
+    fn test_carry_over_keeps_priority_and_tags() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo, 9, Some(TaskPriority::High));
+        let tag = create_tag(&repo, USER_ID, "真题").unwrap();
+        set_tags(&repo, task.id, &[tag.id]).unwrap();
+
+        let report = block_on(carry_over_service::carry_over_tasks(
+            &repo,
+            USER_ID,
+            date(),
+            date() + Days::new(1),
+            CarryOverMode::Copy,
+            &StudyWindow::default(),
+        ))
+        .unwrap();
+        let carried = &report.carried[0];
+        assert_eq!(carried.priority, TaskPriority::High);
+        assert_eq!(carried.tags.iter().map(|g| g.id).collect::<Vec<_>>(), vec![tag.id]);
+    }
+
+    #[test]
+    fn test_priority_round_trips() {
+        for priority in [TaskPriority::Low, TaskPriority::Normal, TaskPriority::High] {
+            assert_eq!(TaskPriority::from(priority.to_string()), priority);
+            assert_eq!(serde_json::to_string(&priority).unwrap(), format!("\"{}\"", priority));
+        }
+        assert_eq!(TaskPriority::from("unknown".to_string()), TaskPriority::Normal);
+        assert!(TaskPriority::High > TaskPriority::Low);
+    }
+}
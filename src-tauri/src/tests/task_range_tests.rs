@@ -0,0 +1,161 @@
+// 多日任务查询属性测试
+
+use super::block_on;
+use crate::models::{
+    CreateSubjectInput, CreateTaskInput, CreateTaskSeriesInput, Frequency, RecurrenceRule, StudyWindow, TaskDay,
+    TaskFilter, TaskStatus,
+};
+use crate::repositories::MemoryRepository;
+use crate::services::{subject_service, task_service};
+use chrono::{Days, NaiveDate, NaiveTime};
+use proptest::prelude::*;
+
+const USER_ID: i64 = 1;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn time(h: u32, m: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(h, m, 0).unwrap()
+}
+
+fn create_task(repo: &MemoryRepository, task_date: NaiveDate, hour: u32, content: &str, subject: Option<i64>) -> i64 {
+    block_on(task_service::create_task(
+        repo,
+        USER_ID,
+        CreateTaskInput {
+            subject_id: subject,
+            task_date,
+            start_time: time(hour, 0),
+            end_time: time(hour, 45),
+            content: content.to_string(),
+            alarm_enabled: None,
+            alarm_time: None,
+            priority: None,
+        },
+        &StudyWindow::default(),
+    ))
+    .unwrap()
+    .id
+}
+
+fn range(repo: &MemoryRepository, start: NaiveDate, end: NaiveDate, filter: TaskFilter) -> Vec<TaskDay> {
+    block_on(task_service::get_tasks_in_range(repo, USER_ID, start, end, filter)).unwrap()
+}
+
+proptest! {
+    /// 按天分组的结果与逐日查询一致，汇总与当天的任务一致
+    #[test]
+    fn test_range_matches_daily_queries(
+        tasks in proptest::collection::btree_map((0u64..7, 6u32..22), any::<bool>(), 0..15)
+    ) {
+        let repo = MemoryRepository::new();
+        let start = date(2025, 3, 3);
+        let end = start + Days::new(6);
+        for ((offset, hour), completed) in &tasks {
+            let id = create_task(&repo, start + Days::new(*offset), *hour, "任务", None);
+            if *completed {
+                block_on(task_service::toggle_task_status(&repo, USER_ID, id)).unwrap();
+            }
+        }
+
+        let days = range(&repo, start, end, TaskFilter::default());
+        prop_assert_eq!(days.len(), 7);
+        for day in &days {
+            let daily = block_on(task_service::get_tasks_by_date(&repo, USER_ID, day.date)).unwrap();
+            let ids: Vec<i64> = day.tasks.iter().map(|t| t.id).collect();
+            prop_assert_eq!(ids, daily.iter().map(|t| t.id).collect::<Vec<_>>());
+            prop_assert_eq!(day.total_tasks, daily.len());
+            prop_assert_eq!(day.completed_tasks, daily.iter().filter(|t| t.status == "completed").count());
+            prop_assert_eq!(day.planned_minutes, 45 * daily.len() as i64);
+        }
+        prop_assert_eq!(days.iter().map(|d| d.total_tasks).sum::<usize>(), tasks.len());
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_range_filters() {
+        let repo = MemoryRepository::new();
+        let start = date(2025, 3, 3);
+        let math = block_on(subject_service::create_subject(
+            &repo,
+            USER_ID,
+            CreateSubjectInput { name: "数学".to_string(), color: Some("#EF4444".to_string()) },
+        ))
+        .unwrap();
+        create_task(&repo, start, 8, "高数 第三章", Some(math.id));
+        let done = create_task(&repo, start + Days::new(1), 8, "英语 Reading", None);
+        create_task(&repo, start + Days::new(2), 8, "100% 完成率", None);
+        block_on(task_service::toggle_task_status(&repo, USER_ID, done)).unwrap();
+
+        let count = |filter: TaskFilter| -> usize {
+            range(&repo, start, start + Days::new(6), filter).iter().map(|d| d.total_tasks).sum()
+        };
+        assert_eq!(count(TaskFilter::default()), 3);
+        assert_eq!(count(TaskFilter { subject_id: Some(math.id), ..Default::default() }), 1);
+        assert_eq!(count(TaskFilter { status: Some(TaskStatus::Completed), ..Default::default() }), 1);
+        assert_eq!(count(TaskFilter { query: Some("reading".to_string()), ..Default::default() }), 1);
+        assert_eq!(count(TaskFilter { query: Some("%".to_string()), ..Default::default() }), 1);
+        assert_eq!(count(TaskFilter { query: Some("  ".to_string()), ..Default::default() }), 3);
+
+        // 科目信息随任务一起返回
+        let first = &range(&repo, start, start, TaskFilter::default())[0].tasks[0];
+        assert_eq!(first.subject_name.as_deref(), Some("数学"));
+        assert_eq!(first.subject_color.as_deref(), Some("#EF4444"));
+    }
+
+    #[test]
+    fn test_range_expands_recurring_tasks() {
+        let repo = MemoryRepository::new();
+        let start = date(2025, 3, 3);
+        block_on(task_service::create_task_series(
+            &repo,
+            USER_ID,
+            CreateTaskSeriesInput {
+                subject_id: None,
+                start_date: start,
+                start_time: time(7, 0),
+                end_time: time(7, 30),
+                content: "背单词".to_string(),
+                alarm_enabled: None,
+                alarm_time: None,
+                priority: None,
+                rule: RecurrenceRule {
+                    frequency: Frequency::Weekly,
+                    interval: 1,
+                    weekdays: vec![1, 3, 5],
+                    until: None,
+                    count: None,
+                },
+            },
+        ))
+        .unwrap();
+
+        let days = range(&repo, start + Days::new(7), start + Days::new(13), TaskFilter::default());
+        let counts: Vec<usize> = days.iter().map(|d| d.total_tasks).collect();
+        assert_eq!(counts, [1, 0, 1, 0, 1, 0, 0]);
+        assert_eq!(days[0].planned_minutes, 30);
+
+        // 再次查询不会重复生成
+        let again = range(&repo, start + Days::new(7), start + Days::new(13), TaskFilter::default());
+        assert_eq!(again.iter().map(|d| d.total_tasks).sum::<usize>(), 3);
+    }
+
+    #[test]
+    fn test_range_validation() {
+        let repo = MemoryRepository::new();
+        let start = date(2025, 3, 3);
+        for end in [start - Days::new(1), start + Days::new(task_service::MAX_RANGE_DAYS as u64)] {
+            let err = block_on(task_service::get_tasks_in_range(&repo, USER_ID, start, end, TaskFilter::default()))
+                .unwrap_err();
+            assert_eq!(err.code(), "validation");
+        }
+        let longest = start + Days::new(task_service::MAX_RANGE_DAYS as u64 - 1);
+        assert_eq!(range(&repo, start, longest, TaskFilter::default()).len(), task_service::MAX_RANGE_DAYS as usize);
+    }
+}
@@ -0,0 +1,407 @@
+// 重复任务属性测试
+
+use super::block_on;
+use crate::error::AppError;
+use crate::models::{
+    occurrence_task_id, parse_occurrence_task_id, CreateTaskInput, CreateTaskSeriesInput, Frequency, RecurrenceRule,
+    RecurrenceScope, StudyWindow, TaskFilter, TaskResponse, TaskSeriesResponse, UpdateTaskInput,
+};
+use crate::repositories::{MemoryRepository, TaskRepository};
+use crate::services::task_service;
+use chrono::{Datelike, Days, NaiveDate, NaiveTime};
+use proptest::prelude::*;
+
+const USER_ID: i64 = 1;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn rule(frequency: Frequency, interval: u32, weekdays: Vec<u32>) -> RecurrenceRule {
+    RecurrenceRule {
+        frequency,
+        interval,
+        weekdays,
+        until: None,
+        count: None,
+    }
+}
+
+fn series_input(start_date: NaiveDate, rule: RecurrenceRule) -> CreateTaskSeriesInput {
+    CreateTaskSeriesInput {
+        subject_id: None,
+        start_date,
+        start_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+        end_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        content: "背单词".to_string(),
+        alarm_enabled: None,
+        alarm_time: None,
+        priority: None,
+        rule,
+    }
+}
+
+/// 创建从指定日期开始、每天重复且不结束的任务
+fn create_daily(repo: &MemoryRepository, start: NaiveDate) -> TaskSeriesResponse {
+    block_on(task_service::create_task_series(repo, USER_ID, series_input(start, rule(Frequency::Daily, 1, vec![]))))
+        .unwrap()
+}
+
+fn tasks_on(repo: &MemoryRepository, day: NaiveDate) -> Vec<TaskResponse> {
+    block_on(task_service::get_tasks_by_date(repo, USER_ID, day)).unwrap()
+}
+
+fn rule_strategy() -> impl Strategy<Value = RecurrenceRule> {
+    (
+        prop_oneof![Just(Frequency::Daily), Just(Frequency::Weekly)],
+        1u32..10,
+        proptest::collection::btree_set(1u32..=7, 0..4),
+        prop_oneof![
+            Just((None, None)),
+            (0u64..60).prop_map(|d| (Some(date(2025, 1, 1) + Days::new(d)), None)),
+            (1u32..20).prop_map(|c| (None, Some(c))),
+        ],
+    )
+        .prop_map(|(frequency, interval, weekdays, (until, count))| RecurrenceRule {
+            frequency,
+            interval,
+            weekdays: match frequency {
+                Frequency::Daily => Vec::new(),
+                Frequency::Weekly => weekdays.into_iter().collect(),
+            },
+            until,
+            count,
+        })
+}
+
+proptest! {
+    /// RRULE 文本可以原样解析回重复规则
+    #[test]
+    fn test_rrule_roundtrip(rule in rule_strategy()) {
+        prop_assert_eq!(RecurrenceRule::parse(&rule.to_string()), Some(rule));
+    }
+
+    /// 生成的重复日期按时间递增，且不超过结束日期和重复次数
+    #[test]
+    fn test_occurrences_respect_bounds(rule in rule_strategy(), offset in 0u64..7) {
+        let start = date(2025, 1, 1) + Days::new(offset);
+        let days: Vec<NaiveDate> = rule.occurrences(start).take(50).collect();
+        prop_assert!(days.windows(2).all(|w| w[0] < w[1]));
+        prop_assert!(days.iter().all(|d| *d >= start && rule.occurs_on(start, *d)));
+        if let Some(until) = rule.until {
+            prop_assert!(days.iter().all(|d| *d <= until));
+        }
+        if let Some(count) = rule.count {
+            prop_assert!(days.len() <= count as usize);
+        }
+    }
+
+    /// 尚未生成的重复任务的 ID 可以解析回系列和日期，且不会与实际任务的 ID 混淆
+    #[test]
+    fn test_occurrence_id_roundtrip(series_id in 1i64..1_000_000, offset in 0u64..36_500) {
+        let day = date(1990, 1, 1) + Days::new(offset);
+        let task_id = occurrence_task_id(series_id, day);
+        prop_assert!(task_id < 0);
+        prop_assert_eq!(parse_occurrence_task_id(task_id), Some((series_id, day)));
+        prop_assert_eq!(parse_occurrence_task_id(series_id), None);
+    }
+
+    /// 多次查询同一天都只展开出一次
+    #[test]
+    fn test_expansion_is_idempotent(offset in 0u64..30, queries in 1usize..4) {
+        let repo = MemoryRepository::new();
+        let start = date(2025, 3, 3);
+        create_daily(&repo, start);
+        let day = start + Days::new(offset);
+        for _ in 0..queries {
+            prop_assert_eq!(tasks_on(&repo, day).len(), 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_patterns() {
+        let start = date(2025, 3, 3); // 周一
+        let first = |rule: RecurrenceRule, n: usize| rule.occurrences(start).take(n).collect::<Vec<_>>();
+
+        let every_other_day = first(rule(Frequency::Daily, 2, vec![]), 3);
+        assert_eq!(every_other_day, [date(2025, 3, 3), date(2025, 3, 5), date(2025, 3, 7)]);
+
+        let weekdays = first(rule(Frequency::Weekly, 1, vec![1, 2, 3, 4, 5]), 6);
+        assert!(weekdays.iter().all(|d| d.weekday().number_from_monday() <= 5));
+        assert_eq!(weekdays[5], date(2025, 3, 10));
+
+        // 未指定星期时按开始日期的星期重复
+        let weekly = first(rule(Frequency::Weekly, 2, vec![]), 2);
+        assert_eq!(weekly, [date(2025, 3, 3), date(2025, 3, 17)]);
+
+        let until = RecurrenceRule { until: Some(date(2025, 3, 5)), ..rule(Frequency::Daily, 1, vec![]) };
+        assert_eq!(until.occurrences(start).count(), 3);
+        let count = RecurrenceRule { count: Some(4), ..rule(Frequency::Weekly, 1, vec![2, 4]) };
+        assert_eq!(count.occurrences(start).last(), Some(date(2025, 3, 13)));
+        assert_eq!(count.count_before(start, date(2025, 3, 11)), 2);
+
+        assert_eq!(
+            RecurrenceRule::parse("RRULE:FREQ=WEEKLY;BYDAY=mo,fr;UNTIL=20251231T000000Z"),
+            Some(RecurrenceRule { until: Some(date(2025, 12, 31)), ..rule(Frequency::Weekly, 1, vec![1, 5]) })
+        );
+        assert_eq!(RecurrenceRule::parse("FREQ=MONTHLY"), None);
+        assert_eq!(RecurrenceRule::parse("INTERVAL=2"), None);
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        let repo = MemoryRepository::new();
+        let start = date(2025, 3, 3);
+        let invalid = [
+            rule(Frequency::Daily, 0, vec![]),
+            rule(Frequency::Daily, task_service::MAX_RECURRENCE_INTERVAL + 1, vec![]),
+            rule(Frequency::Daily, 1, vec![1]),
+            rule(Frequency::Weekly, 1, vec![8]),
+            RecurrenceRule { until: Some(date(2025, 3, 2)), ..rule(Frequency::Daily, 1, vec![]) },
+            RecurrenceRule { count: Some(0), ..rule(Frequency::Daily, 1, vec![]) },
+            RecurrenceRule { until: Some(date(2025, 4, 1)), count: Some(3), ..rule(Frequency::Daily, 1, vec![]) },
+            // 结束日期前没有符合的星期
+            RecurrenceRule { until: Some(date(2025, 3, 4)), ..rule(Frequency::Weekly, 1, vec![5]) },
+        ];
+        for rule in invalid {
+            let err = block_on(task_service::create_task_series(&repo, USER_ID, series_input(start, rule.clone())))
+                .unwrap_err();
+            assert_eq!(err.code(), "validation", "{}", rule);
+        }
+
+        // 星期去重排序
+        let created = block_on(task_service::create_task_series(
+            &repo,
+            USER_ID,
+            series_input(start, rule(Frequency::Weekly, 1, vec![5, 1, 5])),
+        ))
+        .unwrap();
+        assert_eq!(created.rule.weekdays, [1, 5]);
+        assert_eq!(created.rrule, "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,FR");
+    }
+
+    #[test]
+    fn test_reads_do_not_create_tasks() {
+        let repo = MemoryRepository::new();
+        let start = date(2025, 3, 3);
+        create_daily(&repo, start);
+
+        let end = start + Days::new(6);
+        let days = block_on(task_service::get_tasks_in_range(&repo, USER_ID, start, end, TaskFilter::default()))
+            .unwrap();
+        assert!(days.iter().all(|d| d.total_tasks == 1 && d.tasks[0].id < 0));
+        assert_eq!(tasks_on(&repo, start)[0].id, days[0].tasks[0].id);
+        assert!(block_on(repo.list_tasks_in_range(USER_ID, start, end, &TaskFilter::default())).unwrap().is_empty());
+
+        // 完成时才生成为实际的任务，之后查询返回生成的任务
+        let toggled = block_on(task_service::toggle_task_status(&repo, USER_ID, days[0].tasks[0].id)).unwrap();
+        assert!(toggled.id > 0);
+        assert_eq!(block_on(repo.list_tasks_by_date(USER_ID, start)).unwrap().len(), 1);
+        assert_eq!(tasks_on(&repo, start)[0].id, toggled.id);
+        let days = block_on(task_service::get_tasks_in_range(&repo, USER_ID, start, end, TaskFilter::default()))
+            .unwrap();
+        assert!(days.iter().all(|d| d.total_tasks == 1));
+        assert_eq!(days[0].completed_tasks, 1);
+    }
+
+    #[test]
+    fn test_occurrences_complete_independently() {
+        let repo = MemoryRepository::new();
+        let start = date(2025, 3, 3);
+        let series = create_daily(&repo, start);
+
+        assert!(tasks_on(&repo, start - Days::new(1)).is_empty());
+        let first = tasks_on(&repo, start).remove(0);
+        assert_eq!(first.series_id, Some(series.id));
+        block_on(task_service::toggle_task_status(&repo, USER_ID, first.id)).unwrap();
+
+        assert_eq!(tasks_on(&repo, start)[0].status, "completed");
+        assert_eq!(tasks_on(&repo, start + Days::new(1))[0].status, "pending");
+    }
+
+    #[test]
+    fn test_delete_this_occurrence() {
+        let repo = MemoryRepository::new();
+        let start = date(2025, 3, 3);
+        create_daily(&repo, start);
+
+        let day = start + Days::new(2);
+        let task = tasks_on(&repo, day).remove(0);
+        block_on(task_service::delete_task(&repo, USER_ID, task.id, RecurrenceScope::This)).unwrap();
+
+        // 被单独删除的一次不会再生成，其他日期不受影响
+        assert!(tasks_on(&repo, day).is_empty());
+        assert_eq!(tasks_on(&repo, day + Days::new(1)).len(), 1);
+    }
+
+    #[test]
+    fn test_delete_following_truncates_series() {
+        let repo = MemoryRepository::new();
+        let start = date(2025, 3, 3);
+        let series = create_daily(&repo, start);
+
+        let later = tasks_on(&repo, start + Days::new(5)).remove(0);
+        let day = start + Days::new(3);
+        let task = tasks_on(&repo, day).remove(0);
+        block_on(task_service::delete_task(&repo, USER_ID, task.id, RecurrenceScope::Following)).unwrap();
+
+        assert_eq!(tasks_on(&repo, day - Days::new(1)).len(), 1);
+        assert!(tasks_on(&repo, day).is_empty());
+        assert!(tasks_on(&repo, day + Days::new(30)).is_empty());
+        // 已生成的之后的任务一并删除
+        assert!(tasks_on(&repo, later.task_date).is_empty());
+        let truncated = block_on(task_service::get_task_series(&repo, USER_ID, series.id)).unwrap();
+        assert_eq!(truncated.rule.until, Some(day - Days::new(1)));
+
+        // 从第一次开始删除时删除整个系列
+        let first = tasks_on(&repo, start).remove(0);
+        block_on(task_service::delete_task(&repo, USER_ID, first.id, RecurrenceScope::Following)).unwrap();
+        assert!(block_on(task_service::get_task_series(&repo, USER_ID, series.id)).is_err());
+        assert!(tasks_on(&repo, start + Days::new(1)).is_empty());
+    }
+
+    #[test]
+    fn test_update_following_splits_series() {
+        let repo = MemoryRepository::new();
+        let start = date(2025, 3, 3);
+        let series = block_on(task_service::create_task_series(
+            &repo,
+            USER_ID,
+            series_input(start, RecurrenceRule { count: Some(10), ..rule(Frequency::Daily, 1, vec![]) }),
+        ))
+        .unwrap();
+
+        let earlier = tasks_on(&repo, start + Days::new(1)).remove(0);
+        let later = tasks_on(&repo, start + Days::new(6)).remove(0);
+        block_on(task_service::toggle_task_status(&repo, USER_ID, later.id)).unwrap();
+
+        let day = start + Days::new(4);
+        let task = tasks_on(&repo, day).remove(0);
+        let input = UpdateTaskInput { content: Some("做真题".to_string()), ..Default::default() };
+        let updated = block_on(task_service::update_task(
+            &repo,
+            USER_ID,
+            task.id,
+            input,
+            RecurrenceScope::Following,
+            &StudyWindow::default(),
+        ))
+        .unwrap();
+        assert_eq!(updated.content, "做真题");
+        assert_ne!(updated.series_id, Some(series.id));
+
+        // 之前的不变，之后已生成的一并修改并保留完成状态，之后新生成的使用新内容
+        assert_eq!(tasks_on(&repo, earlier.task_date)[0].content, "背单词");
+        let moved = tasks_on(&repo, later.task_date).remove(0);
+        assert_eq!((moved.content.as_str(), moved.status.as_str()), ("做真题", "completed"));
+        assert_eq!(moved.series_id, updated.series_id);
+        assert_eq!(tasks_on(&repo, start + Days::new(8))[0].content, "做真题");
+
+        // 重复次数在两个系列间拆分
+        assert!(tasks_on(&repo, start + Days::new(10)).is_empty());
+        let following = block_on(task_service::get_task_series(&repo, USER_ID, updated.series_id.unwrap())).unwrap();
+        assert_eq!(following.rule.count, Some(6));
+        let original = block_on(task_service::get_task_series(&repo, USER_ID, series.id)).unwrap();
+        assert_eq!((original.rule.until, original.rule.count), (Some(day - Days::new(1)), None));
+    }
+
+    #[test]
+    fn test_update_following_checks_every_date() {
+        let repo = MemoryRepository::new();
+        let start = date(2025, 3, 3);
+        let series = create_daily(&repo, start);
+        let window = StudyWindow::default();
+        let time = |h: u32, m: u32| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+
+        // 之后某天 10:00-11:00 已有其他任务
+        let busy = block_on(task_service::create_task(
+            &repo,
+            USER_ID,
+            CreateTaskInput {
+                subject_id: None,
+                task_date: start + Days::new(5),
+                start_time: time(10, 0),
+                end_time: time(11, 0),
+                content: "模考".to_string(),
+                alarm_enabled: None,
+                alarm_time: None,
+                priority: None,
+            },
+            &window,
+        ))
+        .unwrap();
+
+        let task = tasks_on(&repo, start + Days::new(1)).remove(0);
+        let input = UpdateTaskInput {
+            start_time: Some(time(10, 30)),
+            end_time: Some(time(11, 30)),
+            ..Default::default()
+        };
+        let err = block_on(task_service::update_task(
+            &repo,
+            USER_ID,
+            task.id,
+            input,
+            RecurrenceScope::Following,
+            &window,
+        ))
+        .unwrap_err();
+        assert_eq!(err.code(), "schedule_conflict");
+        assert!(matches!(err, AppError::ScheduleConflict { ref task_ids, .. } if *task_ids == [busy.id]));
+
+        // 单独修改过结束时间的一次在只修改开始时间后无效，整个修改都被拒绝
+        let shortened = tasks_on(&repo, start + Days::new(3)).remove(0);
+        let input = UpdateTaskInput { end_time: Some(time(8, 30)), ..Default::default() };
+        block_on(task_service::update_task(&repo, USER_ID, shortened.id, input, RecurrenceScope::This, &window))
+            .unwrap();
+        let input = UpdateTaskInput { start_time: Some(time(8, 45)), ..Default::default() };
+        let err = block_on(task_service::update_task(
+            &repo,
+            USER_ID,
+            task.id,
+            input,
+            RecurrenceScope::Following,
+            &window,
+        ))
+        .unwrap_err();
+        assert_eq!(err.code(), "validation");
+
+        let unchanged = block_on(task_service::get_task_series(&repo, USER_ID, series.id)).unwrap();
+        assert_eq!((unchanged.start_time, unchanged.rule.until), (time(8, 0), None));
+        assert_eq!(tasks_on(&repo, start + Days::new(4))[0].start_time, time(8, 0));
+
+        // 没有冲突时修改生效
+        let input = UpdateTaskInput { start_time: Some(time(7, 0)), ..Default::default() };
+        let updated = block_on(task_service::update_task(
+            &repo,
+            USER_ID,
+            task.id,
+            input,
+            RecurrenceScope::Following,
+            &window,
+        ))
+        .unwrap();
+        assert_eq!(updated.start_time, time(7, 0));
+        assert_eq!(tasks_on(&repo, start + Days::new(3))[0].start_time, time(7, 0));
+    }
+
+    #[test]
+    fn test_update_this_occurrence_only() {
+        let repo = MemoryRepository::new();
+        let start = date(2025, 3, 3);
+        create_daily(&repo, start);
+
+        let task = tasks_on(&repo, start).remove(0);
+        let input = UpdateTaskInput { content: Some("休息".to_string()), ..Default::default() };
+        let window = StudyWindow::default();
+        block_on(task_service::update_task(&repo, USER_ID, task.id, input, RecurrenceScope::This, &window)).unwrap();
+
+        assert_eq!(tasks_on(&repo, start)[0].content, "休息");
+        assert_eq!(tasks_on(&repo, start + Days::new(1))[0].content, "背单词");
+    }
+}
@@ -0,0 +1,244 @@
+// 任务状态变更与未完成原因属性测试
+
+use super::block_on;
+use crate::error::AppError;
+use crate::models::{CarryOverMode, CreateTaskInput, RecurrenceScope, StudyWindow, TaskResponse, TaskStatus};
+use crate::repositories::{MemoryRepository, TaskRepository, UserRepository};
+use crate::services::{carry_over_service, task_service, task_status_service};
+use chrono::{Days, NaiveDate, NaiveTime};
+use proptest::prelude::*;
+use std::collections::BTreeMap;
+
+const USER_ID: i64 = 1;
+
+const REASONS: [&str; 3] = ["时间不够", "太难", "状态差"];
+
+fn date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 3, 3).unwrap()
+}
+
+fn create_task(repo: &MemoryRepository, task_date: NaiveDate, hour: u32) -> TaskResponse {
+    block_on(task_service::create_task(
+        repo,
+        USER_ID,
+        CreateTaskInput {
+            subject_id: None,
+            task_date,
+            start_time: NaiveTime::from_hms_opt(hour, 0, 0).unwrap(),
+            end_time: NaiveTime::from_hms_opt(hour, 45, 0).unwrap(),
+            content: format!("任务 {}", hour),
+            alarm_enabled: None,
+            alarm_time: None,
+            priority: None,
+        },
+        &StudyWindow::default(),
+    ))
+    .unwrap()
+}
+
+fn set_status(
+    repo: &MemoryRepository,
+    task_id: i64,
+    status: TaskStatus,
+    reason: Option<&str>,
+) -> Result<TaskResponse, AppError> {
+    block_on(task_status_service::set_task_status(repo, USER_ID, task_id, status, reason.map(str::to_string)))
+}
+
+fn status_strategy() -> impl Strategy<Value = TaskStatus> {
+    prop_oneof![Just(TaskStatus::Pending), Just(TaskStatus::Completed), Just(TaskStatus::Failed)]
+}
+
+proptest! {
+    /// 每次实际的状态变更恰好记录一条，记录首尾相接并以任务当前的状态结束
+    #[test]
+    fn test_status_changes_are_recorded(
+        changes in proptest::collection::vec((status_strategy(), proptest::option::of(0usize..3)), 0..12)
+    ) {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo, date(), 8);
+
+        let (mut status, mut reason): (TaskStatus, Option<&str>) = (TaskStatus::Pending, None);
+        let mut recorded = 0;
+        for (next, reason_index) in changes {
+            let next_reason = reason_index.map(|i| REASONS[i]);
+            let result = set_status(&repo, task.id, next.clone(), next_reason);
+            if next == TaskStatus::Failed && next_reason.is_none() {
+                prop_assert!(matches!(result, Err(AppError::Validation(_))));
+                continue;
+            }
+            let response = result.unwrap();
+            prop_assert_eq!(response.status, next.to_string());
+            prop_assert_eq!(response.status_reason.as_deref(), next_reason);
+            if (&next, next_reason) != (&status, reason) {
+                recorded += 1;
+            }
+            (status, reason) = (next, next_reason);
+        }
+
+        let history = block_on(task_status_service::get_task_status_history(&repo, USER_ID, task.id)).unwrap();
+        prop_assert_eq!(history.len(), recorded);
+        let mut previous = TaskStatus::Pending;
+        for change in &history {
+            prop_assert_eq!(&change.from_status, &previous);
+            prop_assert!(change.to_status != TaskStatus::Failed || change.reason.is_some());
+            previous = change.to_status.clone();
+        }
+        prop_assert_eq!(previous, status);
+    }
+
+    /// 未完成原因的统计与范围内未完成任务的原因一致，按任务数从多到少排列
+    #[test]
+    fn test_failure_reasons_match_failed_tasks(
+        tasks in proptest::collection::btree_map((0u64..10, 6u32..22), proptest::option::of(0usize..3), 0..15)
+    ) {
+        let repo = MemoryRepository::new();
+        let mut expected: BTreeMap<&str, usize> = BTreeMap::new();
+        for ((offset, hour), reason) in &tasks {
+            let task = create_task(&repo, date() + Days::new(*offset), *hour);
+            // 没有原因的任务保持待完成
+            if let Some(i) = reason {
+                set_status(&repo, task.id, TaskStatus::Failed, Some(REASONS[*i])).unwrap();
+                if *offset < 7 {
+                    *expected.entry(REASONS[*i]).or_default() += 1;
+                }
+            }
+        }
+
+        let stats =
+            block_on(task_status_service::get_failure_reasons(&repo, USER_ID, date(), date() + Days::new(6))).unwrap();
+        let actual: BTreeMap<&str, usize> =
+            stats.iter().map(|s| (s.reason.as_deref().unwrap(), s.count)).collect();
+        prop_assert_eq!(actual, expected);
+        prop_assert!(stats.windows(2).all(|w| w[0].count >= w[1].count));
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_status_requires_reason() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo, date(), 8);
+
+        assert!(matches!(set_status(&repo, task.id, TaskStatus::Failed, None), Err(AppError::Validation(_))));
+        assert!(matches!(set_status(&repo, task.id, TaskStatus::Failed, Some("  ")), Err(AppError::Validation(_))));
+        let long = "难".repeat(task_status_service::MAX_STATUS_REASON_CHARS + 1);
+        assert!(matches!(set_status(&repo, task.id, TaskStatus::Failed, Some(&long)), Err(AppError::Validation(_))));
+        let result = set_status(&repo, task.id, TaskStatus::RolledOver, None);
+        assert!(matches!(result, Err(AppError::Validation(_))));
+
+        let failed = set_status(&repo, task.id, TaskStatus::Failed, Some(" 时间不够 ")).unwrap();
+        assert_eq!((failed.status.as_str(), failed.status_reason.as_deref()), ("failed", Some("时间不够")));
+        // 其他状态的原因可以不填
+        let pending = set_status(&repo, task.id, TaskStatus::Pending, Some("")).unwrap();
+        assert_eq!((pending.status.as_str(), pending.status_reason), ("pending", None));
+    }
+
+    #[test]
+    fn test_toggle_skips_failed_and_clears_reason() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo, date(), 8);
+        let toggle = || block_on(task_service::toggle_task_status(&repo, USER_ID, task.id)).unwrap();
+
+        assert_eq!(toggle().status, "completed");
+        assert_eq!(toggle().status, "pending");
+        set_status(&repo, task.id, TaskStatus::Failed, Some("太难")).unwrap();
+        let toggled = toggle();
+        assert_eq!((toggled.status.as_str(), toggled.status_reason), ("pending", None));
+
+        let history = block_on(task_status_service::get_task_status_history(&repo, USER_ID, task.id)).unwrap();
+        let steps: Vec<(TaskStatus, Option<&str>)> =
+            history.iter().map(|h| (h.to_status.clone(), h.reason.as_deref())).collect();
+        assert_eq!(
+            steps,
+            vec![
+                (TaskStatus::Completed, None),
+                (TaskStatus::Pending, None),
+                (TaskStatus::Failed, Some("太难")),
+                (TaskStatus::Pending, None),
+            ]
+        );
+        assert_eq!(TaskStatus::Completed.next(), Some(TaskStatus::Pending));
+        assert_eq!(TaskStatus::Failed.next(), Some(TaskStatus::Pending));
+    }
+
+    #[test]
+    fn test_carry_over_records_status_change() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo, date(), 9);
+        block_on(carry_over_service::carry_over_tasks(
+            &repo,
+            USER_ID,
+            date(),
+            date() + Days::new(1),
+            CarryOverMode::Move,
+            &StudyWindow::default(),
+        ))
+        .unwrap();
+
+        let history = block_on(task_status_service::get_task_status_history(&repo, USER_ID, task.id)).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!((&history[0].from_status, &history[0].to_status), (&TaskStatus::Pending, &TaskStatus::RolledOver));
+    }
+
+    #[test]
+    fn test_history_is_private_and_removed_with_task() {
+        let repo = MemoryRepository::new();
+        let task = create_task(&repo, date(), 8);
+        set_status(&repo, task.id, TaskStatus::Failed, Some("状态差")).unwrap();
+
+        let other = block_on(task_status_service::get_task_status_history(&repo, USER_ID + 1, task.id));
+        assert!(matches!(other, Err(AppError::NotFound(_))));
+        let other = block_on(task_status_service::set_task_status(
+            &repo,
+            USER_ID + 1,
+            task.id,
+            TaskStatus::Completed,
+            None,
+        ));
+        assert!(matches!(other, Err(AppError::NotFound(_))));
+
+        assert_eq!(block_on(repo.load_user_data(USER_ID)).unwrap().task_status_history.len(), 1);
+        block_on(task_service::delete_task(&repo, USER_ID, task.id, RecurrenceScope::This)).unwrap();
+        assert!(block_on(repo.load_user_data(USER_ID)).unwrap().task_status_history.is_empty());
+    }
+
+    #[test]
+    fn test_failure_reasons_without_reason_and_invalid_range() {
+        let repo = MemoryRepository::new();
+        let copied = create_task(&repo, date(), 9);
+        let failed = create_task(&repo, date(), 10);
+        set_status(&repo, failed.id, TaskStatus::Failed, Some("时间不够")).unwrap();
+        // 升级前标记为未完成、没有原因的任务
+        let legacy = create_task(&repo, date(), 11);
+        block_on(repo.set_task_status(USER_ID, legacy.id, &TaskStatus::Failed, None)).unwrap();
+        // 按复制方式顺延时原任务标记为未完成，原因为已顺延
+        block_on(carry_over_service::carry_over_tasks(
+            &repo,
+            USER_ID,
+            date(),
+            date() + Days::new(1),
+            CarryOverMode::Copy,
+            &StudyWindow::default(),
+        ))
+        .unwrap();
+        let copied = block_on(task_service::get_tasks_by_date(&repo, USER_ID, date()))
+            .unwrap()
+            .into_iter()
+            .find(|t| t.id == copied.id)
+            .unwrap();
+        assert_eq!((copied.status.as_str(), copied.status_reason.as_deref()), ("failed", Some("已顺延")));
+        let history = block_on(task_status_service::get_task_status_history(&repo, USER_ID, copied.id)).unwrap();
+        assert_eq!(history[0].reason.as_deref(), Some("已顺延"));
+
+        let stats = block_on(task_status_service::get_failure_reasons(&repo, USER_ID, date(), date())).unwrap();
+        let stats: Vec<(Option<&str>, usize)> = stats.iter().map(|s| (s.reason.as_deref(), s.count)).collect();
+        assert_eq!(stats, vec![(None, 1), (Some("已顺延"), 1), (Some("时间不够"), 1)]);
+
+        let result = block_on(task_status_service::get_failure_reasons(&repo, USER_ID, date() + Days::new(1), date()));
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+}
@@ -0,0 +1,414 @@
+// 两步验证属性测试
+
+use super::block_on;
+use crate::config::AppConfig;
+use crate::models::{AuditEventFilter, CreateUserInput, LoginResult, TwoFactorChallenge, User};
+use crate::repositories::{
+    AuditRepository, LoginAttemptRepository, MemoryRepository, SessionRepository, TwoFactorRepository,
+    UserRepository,
+};
+use crate::services::auth_service::{self, LOGIN_CHALLENGE_TTL_MINUTES, MAX_FAILED_ATTEMPTS};
+use crate::services::{two_factor_service, user_service};
+use crate::utils::totp;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use proptest::prelude::*;
+
+const PASSWORD: &str = "password1";
+
+fn create_user(repo: &MemoryRepository, username: &str, role: &str) -> User {
+    let user = block_on(user_service::create_user(
+        repo,
+        None,
+        CreateUserInput {
+            username: username.to_string(),
+            password: PASSWORD.to_string(),
+            display_name: username.to_string(),
+            role: role.to_string(),
+        },
+    ))
+    .unwrap();
+    block_on(repo.find_user(user.id)).unwrap().unwrap()
+}
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 1, 1, 8, 0, 0).unwrap()
+}
+
+/// 指定时间的验证码
+fn code_at(secret: &str, now: DateTime<Utc>) -> String {
+    totp::code_at(&totp::base32_decode(secret).unwrap(), totp::time_step(now))
+}
+
+/// 为用户完成 TOTP 绑定，返回密钥和恢复码
+fn enroll(repo: &MemoryRepository, user: &User) -> (String, Vec<String>) {
+    let secret = block_on(two_factor_service::begin_enrollment(repo, user)).unwrap().secret;
+    let code = code_at(&secret, start());
+    let codes = block_on(two_factor_service::confirm_enrollment_at(repo, user.id, &code, start()))
+        .unwrap()
+        .recovery_codes;
+    (secret, codes)
+}
+
+/// 登录并期望返回两步验证挑战
+fn challenge_at(repo: &MemoryRepository, username: &str, now: DateTime<Utc>) -> TwoFactorChallenge {
+    match block_on(auth_service::login_at(repo, username, PASSWORD, now)).unwrap() {
+        LoginResult::TwoFactorRequired(challenge) => challenge,
+        LoginResult::Authenticated(_) => panic!("启用两步验证后不应直接登录"),
+    }
+}
+
+fn verify_at(
+    repo: &MemoryRepository,
+    challenge: &TwoFactorChallenge,
+    code: &str,
+    now: DateTime<Utc>,
+) -> Result<String, crate::error::AppError> {
+    block_on(auth_service::verify_login_code_at(repo, &challenge.challenge_token, code, now))
+        .map(|r| r.session_token)
+}
+
+proptest! {
+    /// Base32 编码后可以无损解码
+    #[test]
+    fn test_base32_roundtrip(bytes in proptest::collection::vec(any::<u8>(), 0..64)) {
+        let encoded = totp::base32_encode(&bytes);
+        prop_assert_eq!(totp::base32_decode(&encoded), Some(bytes.clone()));
+        prop_assert_eq!(totp::base32_decode(&encoded.to_lowercase()), Some(bytes));
+    }
+
+    /// 当前时间步及前后各一个时间步的验证码都能通过校验，并返回对应的时间步
+    #[test]
+    fn test_code_accepted_within_skew(
+        bytes in proptest::collection::vec(any::<u8>(), 10..32),
+        seconds in 0i64..4_000_000_000,
+        offset in -1i64..=1
+    ) {
+        let secret = totp::base32_encode(&bytes);
+        let now = Utc.timestamp_opt(seconds, 0).unwrap();
+        let step = totp::time_step(now) + offset;
+        let code = totp::code_at(&bytes, step);
+
+        prop_assert_eq!(code.len(), totp::TOTP_DIGITS);
+        let matched = totp::verify_code(&secret, &code, now);
+        prop_assert!(matched.is_some());
+        prop_assert_eq!(totp::code_at(&bytes, matched.unwrap()), code);
+    }
+
+    /// 连续提交错误的验证码与错误的密码一样计入连续失败，达到上限后锁定
+    #[test]
+    fn test_wrong_codes_lock_account(wrong in "[0-9]{6}") {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "student", "user");
+        let (secret, _) = enroll(&repo, &user);
+        let now = start() + Duration::minutes(1);
+        prop_assume!(wrong != code_at(&secret, now)
+            && wrong != code_at(&secret, now - Duration::seconds(30))
+            && wrong != code_at(&secret, now + Duration::seconds(30)));
+
+        let challenge = challenge_at(&repo, "student", now);
+        let mut at = now;
+        for attempt in 1..=MAX_FAILED_ATTEMPTS {
+            let err = verify_at(&repo, &challenge, &wrong, at).unwrap_err();
+            prop_assert_eq!(err.code(), "unauthorized");
+            at += auth_service::login_delay(attempt);
+        }
+
+        let err = verify_at(&repo, &challenge, &code_at(&secret, at), at).unwrap_err();
+        prop_assert_eq!(err.code(), "rate_limited");
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc6238_vectors() {
+        let secret = b"12345678901234567890";
+        for (seconds, expected) in [
+            (59, "287082"),
+            (1_111_111_109, "081804"),
+            (1_234_567_890, "005924"),
+            (2_000_000_000, "279037"),
+        ] {
+            let now = Utc.timestamp_opt(seconds, 0).unwrap();
+            assert_eq!(totp::code_at(secret, totp::time_step(now)), expected);
+        }
+    }
+
+    #[test]
+    fn test_provisioning_uri() {
+        let uri = totp::provisioning_uri("Study Planner", "张 三", "JBSWY3DPEHPK3PXP");
+        assert_eq!(
+            uri,
+            "otpauth://totp/Study%20Planner:%E5%BC%A0%20%E4%B8%89?secret=JBSWY3DPEHPK3PXP\
+             &issuer=Study%20Planner&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    #[test]
+    fn test_login_without_two_factor_authenticates() {
+        let repo = MemoryRepository::new();
+        create_user(&repo, "student", "user");
+        let result = block_on(auth_service::login_at(&repo, "student", PASSWORD, start())).unwrap();
+        assert!(matches!(result, LoginResult::Authenticated(_)));
+    }
+
+    #[test]
+    fn test_challenge_issued_before_session() {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "student", "user");
+        let (secret, _) = enroll(&repo, &user);
+
+        let now = start() + Duration::minutes(1);
+        let challenge = challenge_at(&repo, "student", now);
+        assert_eq!(challenge.expires_at, now + Duration::minutes(LOGIN_CHALLENGE_TTL_MINUTES));
+        assert!(block_on(repo.list_sessions(user.id)).unwrap().is_empty());
+
+        let token = verify_at(&repo, &challenge, &code_at(&secret, now), now).unwrap();
+        assert_eq!(block_on(auth_service::validate_session_at(&repo, &token, now)).unwrap().id, user.id);
+
+        // 挑战只能使用一次
+        let err = verify_at(&repo, &challenge, &code_at(&secret, now), now).unwrap_err();
+        assert_eq!(err.code(), "unauthorized");
+    }
+
+    #[test]
+    fn test_code_cannot_be_replayed() {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "student", "user");
+        let (secret, _) = enroll(&repo, &user);
+
+        let now = start() + Duration::minutes(1);
+        let code = code_at(&secret, now);
+        let first = challenge_at(&repo, "student", now);
+        verify_at(&repo, &first, &code, now).unwrap();
+
+        let second = challenge_at(&repo, "student", now);
+        assert_eq!(verify_at(&repo, &second, &code, now).unwrap_err().code(), "unauthorized");
+
+        // 下一个时间步的验证码可以使用
+        let later = now + Duration::seconds(totp::TOTP_PERIOD_SECS);
+        let third = challenge_at(&repo, "student", later + Duration::seconds(2));
+        assert!(verify_at(&repo, &third, &code_at(&secret, later), later + Duration::seconds(2)).is_ok());
+    }
+
+    #[test]
+    fn test_enrollment_code_cannot_be_reused_for_login() {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "student", "user");
+        let (secret, _) = enroll(&repo, &user);
+
+        let challenge = challenge_at(&repo, "student", start());
+        assert!(verify_at(&repo, &challenge, &code_at(&secret, start()), start()).is_err());
+    }
+
+    #[test]
+    fn test_recovery_code_single_use() {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "student", "user");
+        let (_, codes) = enroll(&repo, &user);
+        assert_eq!(codes.len(), two_factor_service::RECOVERY_CODE_COUNT);
+        for code in &codes {
+            let (first, second) = code.split_once('-').unwrap();
+            assert_eq!((first.len(), second.len()), (5, 5));
+            assert!(!code.contains(['0', 'O', '1', 'I']), "{}", code);
+        }
+
+        let now = start() + Duration::minutes(1);
+        let challenge = challenge_at(&repo, "student", now);
+        // 大小写和连字符不影响校验
+        let input = codes[0].to_lowercase().replace('-', " ");
+        assert!(verify_at(&repo, &challenge, &input, now).is_ok());
+
+        let later = now + Duration::minutes(1);
+        let challenge = challenge_at(&repo, "student", later);
+        assert_eq!(verify_at(&repo, &challenge, &codes[0], later).unwrap_err().code(), "unauthorized");
+
+        let status = block_on(two_factor_service::get_status(&repo, &user, false)).unwrap();
+        assert_eq!(status.recovery_codes_remaining, two_factor_service::RECOVERY_CODE_COUNT - 1);
+
+        let filter = AuditEventFilter {
+            actor_id: Some(user.id),
+            ..Default::default()
+        };
+        let events = block_on(repo.list_audit_events(&filter, 1, 0)).unwrap();
+        assert_eq!(events[0].action, "session.login");
+        assert!(events[0].metadata.as_deref().unwrap().contains("recovery_code"));
+    }
+
+    #[test]
+    fn test_expired_challenge_rejected() {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "student", "user");
+        let (secret, _) = enroll(&repo, &user);
+
+        let challenge = challenge_at(&repo, "student", start() + Duration::minutes(1));
+        let late = challenge.expires_at + Duration::seconds(1);
+        assert_eq!(verify_at(&repo, &challenge, &code_at(&secret, late), late).unwrap_err().code(), "unauthorized");
+        assert!(block_on(repo.find_login_challenge(&challenge.challenge_token)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_expired_challenges_purged() {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "student", "user");
+        enroll(&repo, &user);
+
+        let challenge = challenge_at(&repo, "student", start());
+        assert_eq!(block_on(repo.delete_expired_login_challenges(start())).unwrap(), 0);
+        assert_eq!(block_on(repo.delete_expired_login_challenges(challenge.expires_at)).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_successful_code_clears_failures() {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "student", "user");
+        let (secret, _) = enroll(&repo, &user);
+
+        let now = start() + Duration::minutes(1);
+        let challenge = challenge_at(&repo, "student", now);
+        assert!(verify_at(&repo, &challenge, "abcdefghij", now).is_err());
+        assert!(block_on(repo.find_login_throttle("student")).unwrap().is_some());
+
+        let later = now + Duration::seconds(5);
+        verify_at(&repo, &challenge, &code_at(&secret, later), later).unwrap();
+        assert!(block_on(repo.find_login_throttle("student")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_confirm_requires_valid_code() {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "student", "user");
+        let err = block_on(two_factor_service::confirm_enrollment_at(&repo, user.id, "123456", start())).unwrap_err();
+        assert_eq!(err.code(), "validation");
+
+        let secret = block_on(two_factor_service::begin_enrollment(&repo, &user)).unwrap().secret;
+        let wrong = code_at(&secret, start() + Duration::minutes(10));
+        let err = block_on(two_factor_service::confirm_enrollment_at(&repo, user.id, &wrong, start())).unwrap_err();
+        assert_eq!(err.code(), "validation");
+        assert!(!block_on(two_factor_service::is_enabled(&repo, user.id)).unwrap());
+
+        // 未完成绑定时登录不需要验证码
+        let result = block_on(auth_service::login_at(&repo, "student", PASSWORD, start())).unwrap();
+        assert!(matches!(result, LoginResult::Authenticated(_)));
+    }
+
+    #[test]
+    fn test_begin_enrollment_when_enabled_conflicts() {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "student", "user");
+        enroll(&repo, &user);
+        let err = block_on(two_factor_service::begin_enrollment(&repo, &user)).unwrap_err();
+        assert_eq!(err.code(), "conflict");
+    }
+
+    #[test]
+    fn test_disable_requires_password() {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "student", "user");
+        enroll(&repo, &user);
+
+        let err = block_on(two_factor_service::disable(&repo, &user, "wrong-password1", false)).unwrap_err();
+        assert_eq!(err.code(), "validation");
+        block_on(two_factor_service::disable(&repo, &user, PASSWORD, false)).unwrap();
+        assert!(!block_on(two_factor_service::is_enabled(&repo, user.id)).unwrap());
+        assert!(block_on(repo.list_unused_recovery_codes(user.id)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_admin_policy() {
+        let repo = MemoryRepository::new();
+        let admin = create_user(&repo, "admin", "admin");
+        let student = create_user(&repo, "student", "user");
+
+        // 策略只约束管理员
+        let err = block_on(two_factor_service::check_policy(&repo, &admin, true)).unwrap_err();
+        assert_eq!(err.code(), "forbidden");
+        assert!(block_on(two_factor_service::check_policy(&repo, &admin, false)).is_ok());
+        assert!(block_on(two_factor_service::check_policy(&repo, &student, true)).is_ok());
+
+        enroll(&repo, &admin);
+        assert!(block_on(two_factor_service::check_policy(&repo, &admin, true)).is_ok());
+        assert!(block_on(two_factor_service::get_status(&repo, &admin, true)).unwrap().required);
+
+        // 被要求启用时不能自行关闭
+        let err = block_on(two_factor_service::disable(&repo, &admin, PASSWORD, true)).unwrap_err();
+        assert_eq!(err.code(), "forbidden");
+    }
+
+    #[test]
+    fn test_regenerate_invalidates_old_codes() {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "student", "user");
+        let (secret, old_codes) = enroll(&repo, &user);
+
+        let now = start() + Duration::minutes(1);
+        let err = block_on(two_factor_service::regenerate_recovery_codes_at(&repo, user.id, &old_codes[0], now))
+            .unwrap_err();
+        assert_eq!(err.code(), "validation");
+
+        let new_codes = block_on(two_factor_service::regenerate_recovery_codes_at(
+            &repo,
+            user.id,
+            &code_at(&secret, now),
+            now,
+        ))
+        .unwrap()
+        .recovery_codes;
+        assert_eq!(new_codes.len(), two_factor_service::RECOVERY_CODE_COUNT);
+
+        let later = now + Duration::minutes(1);
+        let challenge = challenge_at(&repo, "student", later);
+        assert!(verify_at(&repo, &challenge, &old_codes[1], later).is_err());
+        let later = later + Duration::seconds(5);
+        assert!(verify_at(&repo, &challenge, &new_codes[0], later).is_ok());
+    }
+
+    #[test]
+    fn test_admin_reset_and_audit() {
+        let repo = MemoryRepository::new();
+        let admin = create_user(&repo, "admin", "admin");
+        let student = create_user(&repo, "student", "user");
+        let err = block_on(two_factor_service::reset_for_user(&repo, admin.id, student.id)).unwrap_err();
+        assert_eq!(err.code(), "validation");
+
+        enroll(&repo, &student);
+        block_on(two_factor_service::reset_for_user(&repo, admin.id, student.id)).unwrap();
+        assert!(block_on(repo.find_totp(student.id)).unwrap().is_none());
+
+        let result = block_on(auth_service::login_at(&repo, "student", PASSWORD, start())).unwrap();
+        assert!(matches!(result, LoginResult::Authenticated(_)));
+
+        let filter = AuditEventFilter {
+            target_user_id: Some(student.id),
+            ..Default::default()
+        };
+        let actions: Vec<String> = block_on(repo.list_audit_events(&filter, 10, 0))
+            .unwrap()
+            .into_iter()
+            .map(|e| e.action)
+            .collect();
+        assert!(actions.iter().any(|a| a == "two_factor.enable"));
+        assert!(actions.iter().any(|a| a == "two_factor.reset"));
+    }
+
+    #[test]
+    fn test_delete_user_removes_two_factor_data() {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "student", "user");
+        enroll(&repo, &user);
+        let challenge = challenge_at(&repo, "student", start());
+
+        block_on(repo.delete_user(user.id)).unwrap();
+        assert!(block_on(repo.find_totp(user.id)).unwrap().is_none());
+        assert!(block_on(repo.list_unused_recovery_codes(user.id)).unwrap().is_empty());
+        assert!(block_on(repo.find_login_challenge(&challenge.challenge_token)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_policy_defaults_when_missing_from_config() {
+        let config: AppConfig = serde_json::from_str("{}").unwrap();
+        assert!(!config.security.require_admin_two_factor);
+    }
+}
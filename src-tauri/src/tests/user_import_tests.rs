@@ -0,0 +1,235 @@
+// 用户批量导入与导出属性测试
+
+use super::{block_on, memory_pool};
+use crate::models::{AuditAction, AuditEventFilter, CreateUserInput, UserImportReport};
+use crate::repositories::{AuditRepository, MemoryRepository, SqlRepository, SubjectRepository, UserRepository};
+use crate::services::{auth_service, user_csv_service, user_service};
+use crate::utils::csv;
+use proptest::prelude::*;
+
+fn create_user(repo: &MemoryRepository, username: &str) -> i64 {
+    block_on(user_service::create_user(
+        repo,
+        None,
+        CreateUserInput {
+            username: username.to_string(),
+            password: "password1".to_string(),
+            display_name: username.to_string(),
+            role: "admin".to_string(),
+        },
+    ))
+    .unwrap()
+    .id
+}
+
+fn import(repo: &MemoryRepository, actor_id: i64, content: &str, dry_run: bool) -> UserImportReport {
+    block_on(user_csv_service::import_users(repo, actor_id, content, dry_run)).unwrap()
+}
+
+fn user_count(repo: &MemoryRepository) -> usize {
+    block_on(repo.list_users()).unwrap().len()
+}
+
+proptest! {
+    /// 任意字段 (含逗号、引号和换行) 写入 CSV 后可以原样解析
+    #[test]
+    fn test_csv_roundtrip(
+        rows in proptest::collection::vec(
+            proptest::collection::vec("[a-z0-9 ,\"\n考研]{0,8}", 1..5),
+            1..6
+        )
+    ) {
+        // 全部字段为空的行会被跳过，这里只比较非空行
+        let expected: Vec<Vec<String>> = rows
+            .iter()
+            .filter(|r| r.iter().any(|f| !f.trim().is_empty()))
+            .cloned()
+            .collect();
+        let parsed: Vec<Vec<String>> = csv::parse(&csv::write(&rows))
+            .unwrap()
+            .into_iter()
+            .map(|(_, fields)| fields)
+            .collect();
+        prop_assert_eq!(parsed, expected);
+    }
+
+    /// 试运行只返回校验结果，不会创建任何账户
+    #[test]
+    fn test_dry_run_creates_nothing(names in proptest::collection::hash_set("[a-z][a-z0-9]{0,9}", 1..8)) {
+        let repo = MemoryRepository::new();
+        let admin_id = create_user(&repo, "admin_");
+        let content = std::iter::once("username".to_string()).chain(names.iter().cloned()).collect::<Vec<_>>();
+
+        let report = import(&repo, admin_id, &content.join("\n"), true);
+        prop_assert!(report.dry_run);
+        prop_assert_eq!(report.total, names.len());
+        prop_assert_eq!(report.valid, names.len());
+        prop_assert_eq!(report.created, 0);
+        prop_assert!(report.credentials.is_empty());
+        prop_assert_eq!(user_count(&repo), 1);
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_parse_quotes_and_bom() {
+        let parsed = csv::parse("\u{feff}a,\"b,c\"\r\n\r\n\"x\"\"y\",\"multi\nline\"\n").unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0], (1, vec!["a".to_string(), "b,c".to_string()]));
+        assert_eq!(parsed[1], (3, vec!["x\"y".to_string(), "multi\nline".to_string()]));
+        assert_eq!(csv::parse("a\n\"unclosed\nb"), Err(2));
+    }
+
+    #[test]
+    fn test_csv_guards_formulas() {
+        let row = ["=SUM(A1:A9)", "+1", "-1", "@cmd", "\tx", "\rx", "a=b"];
+        assert_eq!(
+            csv::write([row]),
+            "'=SUM(A1:A9),'+1,'-1,'@cmd,'\tx,\"'\rx\",a=b\r\n"
+        );
+
+        let parsed = csv::parse(&csv::write([row])).unwrap();
+        let unguarded: Vec<&str> = parsed[0].1.iter().map(|f| csv::unguard_formula(f)).collect();
+        assert_eq!(unguarded, row);
+    }
+
+    #[test]
+    fn test_import_reports_row_errors() {
+        let repo = MemoryRepository::new();
+        let admin_id = create_user(&repo, "admin");
+        let content = "username,display_name,role\n\
+                       alice,Alice,user\n\
+                       admin,Taken,user\n\
+                       bob,,teacher\n\
+                       alice,Again,user\n\
+                       bad name,,\n";
+
+        let report = import(&repo, admin_id, content, false);
+        assert_eq!(report.total, 5);
+        assert_eq!(report.valid, 1);
+        assert_eq!(report.created, 0);
+        assert!(report.credentials_csv.is_none());
+        assert_eq!(user_count(&repo), 1);
+
+        let errors: Vec<(usize, &[String])> = report.rows.iter().map(|r| (r.line, r.errors.as_slice())).collect();
+        assert!(errors[0].1.is_empty());
+        assert_eq!(errors[1].1, ["用户名已存在"]);
+        assert_eq!(errors[2].1, ["未知的角色: teacher"]);
+        assert_eq!(errors[3].1, ["与第 2 行的用户名重复"]);
+        assert_eq!(errors[4].1, ["用户名不能包含空白字符"]);
+        // 昵称为空时使用用户名
+        assert_eq!(report.rows[2].display_name, "bob");
+    }
+
+    #[test]
+    fn test_import_rejects_malformed_files() {
+        let repo = MemoryRepository::new();
+        let admin_id = create_user(&repo, "admin");
+        for content in ["", "display_name\nAlice", "username,email\nalice,a@b.c", "username\n\"alice", "username\n"] {
+            let err = block_on(user_csv_service::import_users(&repo, admin_id, content, true)).unwrap_err();
+            assert_eq!(err.code(), "validation", "{:?}", content);
+        }
+
+        let too_many = std::iter::once("username".to_string())
+            .chain((0..=user_csv_service::MAX_IMPORT_ROWS).map(|i| format!("user{}", i)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let err = block_on(user_csv_service::import_users(&repo, admin_id, &too_many, true)).unwrap_err();
+        assert_eq!(err.code(), "validation");
+    }
+
+    #[test]
+    fn test_import_creates_accounts_with_credentials() {
+        let repo = MemoryRepository::new();
+        let admin_id = create_user(&repo, "admin");
+        let content = "用户名,昵称,角色\nalice,\"Alice, A\",mentor\nbob,Bob,\n";
+
+        let report = import(&repo, admin_id, content, false);
+        assert_eq!(report.created, 2);
+        assert_eq!(report.credentials.len(), 2);
+        assert_eq!(user_count(&repo), 3);
+
+        let alice = &report.credentials[0];
+        assert_eq!(alice.display_name, "Alice, A");
+        assert_eq!(alice.role, "mentor");
+        assert_eq!(report.credentials[1].role, "user");
+        assert_eq!(alice.initial_password.len(), user_csv_service::GENERATED_PASSWORD_LENGTH);
+        assert_ne!(alice.initial_password, report.credentials[1].initial_password);
+
+        // 初始密码可以登录，新账户带有默认科目
+        let result = block_on(auth_service::login(&repo, "alice", &alice.initial_password)).unwrap();
+        let session = result.session().unwrap();
+        assert!(session.user.must_change_password);
+        assert!(!block_on(repo.list_subjects(session.user.id)).unwrap().is_empty());
+
+        // 初始密码清单 CSV 与返回的凭据一致
+        let sheet = csv::parse(report.credentials_csv.as_deref().unwrap()).unwrap();
+        assert_eq!(sheet[0].1, ["username", "display_name", "role", "initial_password"]);
+        assert_eq!(sheet[1].1[3], alice.initial_password);
+
+        let filter = AuditEventFilter {
+            actor_id: Some(admin_id),
+            action: Some(AuditAction::UserCreate),
+            ..Default::default()
+        };
+        assert_eq!(block_on(repo.list_audit_events(&filter, 10, 0)).unwrap().len(), 2);
+
+        // 再次导入相同的文件时全部报告为已存在
+        let again = import(&repo, admin_id, content, true);
+        assert_eq!(again.valid, 0);
+    }
+
+    #[test]
+    fn test_create_users_is_atomic() {
+        let repo = MemoryRepository::new();
+        create_user(&repo, "admin");
+        let input = |username: &str| CreateUserInput {
+            username: username.to_string(),
+            password: "hash".to_string(),
+            display_name: username.to_string(),
+            role: "user".to_string(),
+        };
+
+        let err = block_on(repo.create_users(&[input("alice"), input("admin")])).unwrap_err();
+        assert_eq!(err.code(), "conflict");
+        assert_eq!(user_count(&repo), 1);
+    }
+
+    #[test]
+    fn test_sql_created_users_must_change_password() {
+        block_on(async {
+            let repo = SqlRepository::new(memory_pool().await);
+            let input = CreateUserInput {
+                username: "alice".to_string(),
+                password: "hash".to_string(),
+                display_name: "Alice".to_string(),
+                role: "user".to_string(),
+            };
+            let user_ids = repo.create_users(&[input]).await.unwrap();
+            assert!(repo.find_user(user_ids[0]).await.unwrap().unwrap().must_change_password);
+        });
+    }
+
+    #[test]
+    fn test_export_roundtrips_into_import() {
+        let repo = MemoryRepository::new();
+        let admin_id = create_user(&repo, "admin");
+        import(&repo, admin_id, "username,display_name\nalice,\"Alice \"\"A\"\"\"\nbob,=1+1\n", false);
+
+        let exported = block_on(user_csv_service::export_users(&repo)).unwrap();
+        let parsed = csv::parse(&exported).unwrap();
+        assert_eq!(parsed[0].1, ["username", "display_name", "role", "status", "created_at"]);
+        assert_eq!(parsed.len(), 4);
+        assert!(parsed.iter().any(|(_, r)| r[0] == "alice" && r[1] == "Alice \"A\"" && r[3] == "active"));
+        assert!(parsed.iter().any(|(_, r)| r[0] == "bob" && r[1] == "'=1+1"));
+
+        // 导出的文件可直接用于导入 (已有用户全部报告为重复)
+        let report = import(&repo, admin_id, &exported, true);
+        assert_eq!(report.total, 3);
+        assert!(report.rows.iter().any(|r| r.username == "bob" && r.display_name == "=1+1"));
+        assert!(report.rows.iter().all(|r| r.errors == ["用户名已存在"]));
+    }
+}
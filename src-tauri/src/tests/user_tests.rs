@@ -1,5 +1,10 @@
 // 用户管理属性测试
 
+use super::block_on;
+use crate::models::{AuditLogQuery, CreateTaskInput, CreateUserInput, RecurrenceScope, StudyWindow, UserResponse};
+use crate::repositories::MemoryRepository;
+use crate::services::{audit_service, auth_service, task_service, user_service};
+use chrono::{NaiveDate, NaiveTime};
 use proptest::prelude::*;
 
 // **Feature: study-planner, Property 18: 用户数据隔离**
@@ -20,9 +25,9 @@ use proptest::prelude::*;
 // **Feature: study-planner, Property 23: 密码修改验证**
 // **Validates: Requirements 14.2, 14.3**
 
-/// 生成有效的用户 ID
-fn valid_user_id_strategy() -> impl Strategy<Value = i64> {
-    1i64..1000000i64
+/// 生成有效的日期
+fn valid_date_strategy() -> impl Strategy<Value = NaiveDate> {
+    (0u64..3650).prop_map(|days| NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Days::new(days))
 }
 
 /// 生成有效的用户名
@@ -30,30 +35,58 @@ fn valid_username_strategy() -> impl Strategy<Value = String> {
     "[a-zA-Z][a-zA-Z0-9]{2,19}".prop_map(|s| s)
 }
 
-/// 生成有效的密码
+/// 生成满足密码策略的密码
 fn valid_password_strategy() -> impl Strategy<Value = String> {
-    "[a-zA-Z0-9!@#$%^&*]{6,50}".prop_map(|s| s)
+    "[a-zA-Z][a-zA-Z0-9!@#$%^&*]{6,40}[0-9]".prop_map(|s| s)
 }
 
-/// 模拟用户数据隔离检查
-fn check_data_isolation(user_a_id: i64, user_b_id: i64, data_owner_id: i64) -> bool {
-    // 数据只属于其所有者
-    data_owner_id == user_a_id || data_owner_id == user_b_id
+/// 创建普通用户
+fn create_user(repo: &MemoryRepository, username: &str, password: &str) -> UserResponse {
+    block_on(user_service::create_user(
+        repo,
+        None,
+        CreateUserInput {
+            username: username.to_string(),
+            password: password.to_string(),
+            display_name: username.to_string(),
+            role: "user".to_string(),
+        },
+    ))
+    .unwrap()
 }
 
-/// 模拟会话验证
-fn is_session_valid(session_token: &str, is_logged_out: bool) -> bool {
-    !is_logged_out && !session_token.is_empty()
+/// 登录并返回会话令牌
+fn login(repo: &MemoryRepository, username: &str, password: &str) -> Option<String> {
+    block_on(auth_service::login(repo, username, password))
+        .ok()
+        .and_then(|r| r.session())
+        .map(|r| r.session_token)
 }
 
-/// 模拟用户名唯一性检查
-fn is_username_unique(existing_usernames: &[String], new_username: &str) -> bool {
-    !existing_usernames.contains(&new_username.to_string())
+/// 会话令牌是否有效
+fn is_session_valid(repo: &MemoryRepository, session_token: &str) -> bool {
+    block_on(auth_service::validate_session(repo, session_token)).is_ok()
 }
 
-/// 模拟密码修改验证
-fn can_change_password(current_input: &str, stored_password: &str) -> bool {
-    current_input == stored_password
+/// 为用户创建一条任务
+fn create_task(repo: &MemoryRepository, user_id: i64, date: NaiveDate, content: &str) -> i64 {
+    block_on(task_service::create_task(
+        repo,
+        user_id,
+        CreateTaskInput {
+            subject_id: None,
+            task_date: date,
+            start_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+            end_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            content: content.to_string(),
+            alarm_enabled: None,
+            alarm_time: None,
+            priority: None,
+        },
+        &StudyWindow::default(),
+    ))
+    .unwrap()
+    .id
 }
 
 proptest! {
@@ -61,33 +94,46 @@ proptest! {
     /// 对于任意两个不同用户，用户 A 查询数据时不应返回用户 B 的任何数据
     #[test]
     fn test_user_data_isolation(
-        user_a_id in valid_user_id_strategy(),
-        user_b_id in valid_user_id_strategy().prop_filter("不同用户", |id| *id != 1),
-        data_owner_id in valid_user_id_strategy()
+        user_a_name in valid_username_strategy(),
+        user_b_name in valid_username_strategy(),
+        date in valid_date_strategy(),
+        content in "[a-z]{1,20}"
     ) {
-        if user_a_id != user_b_id {
-            // 数据应该只属于一个用户
-            let belongs_to_a = data_owner_id == user_a_id;
-            let belongs_to_b = data_owner_id == user_b_id;
-            
-            // 如果数据属于 A，则不属于 B（除非 A == B）
-            if belongs_to_a && user_a_id != user_b_id {
-                prop_assert!(!belongs_to_b);
-            }
-        }
+        prop_assume!(user_a_name != user_b_name);
+        let repo = MemoryRepository::new();
+        let user_a = create_user(&repo, &user_a_name, "password1");
+        let user_b = create_user(&repo, &user_b_name, "password1");
+
+        let task_id = create_task(&repo, user_b.id, date, &content);
+
+        // 用户 A 查询不到也无法修改用户 B 的任务
+        let tasks = block_on(task_service::get_tasks_by_date(&repo, user_a.id, date)).unwrap();
+        prop_assert!(tasks.iter().all(|t| t.id != task_id));
+        prop_assert!(block_on(task_service::toggle_task_status(&repo, user_a.id, task_id)).is_err());
+
+        block_on(task_service::delete_task(&repo, user_a.id, task_id, RecurrenceScope::This)).unwrap();
+        let tasks = block_on(task_service::get_tasks_by_date(&repo, user_b.id, date)).unwrap();
+        prop_assert_eq!(tasks.len(), 1);
+        prop_assert_eq!(tasks[0].status.as_str(), "pending");
     }
 
     /// Property 19: 会话失效
     /// 对于任意已登录用户，退出登录后使用原会话令牌的请求应被拒绝
     #[test]
     fn test_session_invalidation(
-        session_token in "[a-f0-9]{32}".prop_map(|s| s)
+        username in valid_username_strategy(),
+        password in valid_password_strategy()
     ) {
+        let repo = MemoryRepository::new();
+        create_user(&repo, &username, &password);
+        let session_token = login(&repo, &username, &password).unwrap();
+
         // 登录状态下会话有效
-        prop_assert!(is_session_valid(&session_token, false));
-        
+        prop_assert!(is_session_valid(&repo, &session_token));
+
         // 退出后会话无效
-        prop_assert!(!is_session_valid(&session_token, true));
+        block_on(auth_service::logout(&repo, &session_token)).unwrap();
+        prop_assert!(!is_session_valid(&repo, &session_token));
     }
 
     /// Property 20: 用户名唯一性约束
@@ -97,32 +143,95 @@ proptest! {
         existing_username in valid_username_strategy(),
         new_username in valid_username_strategy()
     ) {
-        let existing = vec![existing_username.clone()];
-        
+        let repo = MemoryRepository::new();
+        create_user(&repo, &existing_username, "password1");
+
         // 相同用户名应该不唯一
-        prop_assert!(!is_username_unique(&existing, &existing_username));
-        
+        let duplicate = block_on(user_service::create_user(&repo, None, CreateUserInput {
+            username: existing_username.clone(),
+            password: "password1".to_string(),
+            display_name: "重复".to_string(),
+            role: "user".to_string(),
+        }));
+        prop_assert_eq!(duplicate.unwrap_err().code(), "conflict");
+
         // 不同用户名应该唯一
         if new_username != existing_username {
-            prop_assert!(is_username_unique(&existing, &new_username));
+            create_user(&repo, &new_username, "password1");
+            prop_assert_eq!(block_on(user_service::get_all_users(&repo)).unwrap().len(), 2);
         }
     }
 
+    /// Property 21: 用户删除级联
+    /// 删除用户前导出数据，删除后其会话失效，数据不再可查
+    #[test]
+    fn test_user_deletion_cascades(
+        username in valid_username_strategy(),
+        date in valid_date_strategy()
+    ) {
+        let repo = MemoryRepository::new();
+        let admin = create_user(&repo, "admin0", "password1");
+        prop_assume!(username != admin.username);
+        let user = create_user(&repo, &username, "password1");
+        let session_token = login(&repo, &username, "password1").unwrap();
+        create_task(&repo, user.id, date, "复习");
+
+        let dir = std::env::temp_dir().join(format!("study-planner-test-{}", uuid::Uuid::new_v4()));
+        block_on(user_service::deactivate_user(&repo, admin.id, user.id)).unwrap();
+        let archive = block_on(user_service::delete_user(&repo, admin.id, user.id, &dir)).unwrap();
+        prop_assert_eq!(archive.tasks, 1);
+        prop_assert!(archive.path.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        prop_assert!(!is_session_valid(&repo, &session_token));
+        prop_assert!(block_on(task_service::get_tasks_by_date(&repo, user.id, date)).unwrap().is_empty());
+        let remaining = block_on(user_service::get_all_users(&repo)).unwrap();
+        prop_assert_eq!(remaining.len(), 1);
+        prop_assert_eq!(remaining[0].id, admin.id);
+    }
+
+    /// Property 22: 密码重置有效性
+    /// 管理员重置密码后只能使用新密码登录
+    #[test]
+    fn test_password_reset(
+        username in valid_username_strategy(),
+        old_password in valid_password_strategy(),
+        new_password in valid_password_strategy()
+    ) {
+        prop_assume!(old_password != new_password);
+        let repo = MemoryRepository::new();
+        let admin = create_user(&repo, "admin0", "password1");
+        prop_assume!(username != admin.username);
+        let user = create_user(&repo, &username, &old_password);
+
+        block_on(user_service::reset_user_password(&repo, admin.id, user.id, &new_password)).unwrap();
+
+        prop_assert!(login(&repo, &username, &new_password).is_some());
+        prop_assert!(login(&repo, &username, &old_password).is_none());
+    }
+
     /// Property 23: 密码修改验证
     /// 对于任意用户，只有提供正确的当前密码才能修改密码
     #[test]
     fn test_password_change_validation(
+        username in valid_username_strategy(),
         stored_password in valid_password_strategy(),
-        correct_input in valid_password_strategy(),
+        new_password in valid_password_strategy(),
         wrong_input in valid_password_strategy()
     ) {
-        // 正确密码可以修改
-        prop_assert!(can_change_password(&stored_password, &stored_password));
-        
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, &username, &stored_password);
+
         // 错误密码不能修改
         if wrong_input != stored_password {
-            prop_assert!(!can_change_password(&wrong_input, &stored_password));
+            let err = block_on(auth_service::change_password(&repo, user.id, "", &wrong_input, &new_password)).unwrap_err();
+            prop_assert_eq!(err.code(), "validation");
+            prop_assert!(login(&repo, &username, &stored_password).is_some());
         }
+
+        // 正确密码可以修改
+        block_on(auth_service::change_password(&repo, user.id, "", &stored_password, &new_password)).unwrap();
+        prop_assert!(login(&repo, &username, &new_password).is_some());
     }
 }
 
@@ -132,23 +241,62 @@ mod unit_tests {
 
     #[test]
     fn test_session_valid_when_logged_in() {
-        assert!(is_session_valid("abc123def456", false));
+        let repo = MemoryRepository::new();
+        create_user(&repo, "student", "password1");
+        let token = login(&repo, "student", "password1").unwrap();
+        assert!(is_session_valid(&repo, &token));
     }
 
     #[test]
     fn test_session_invalid_when_logged_out() {
-        assert!(!is_session_valid("abc123def456", true));
+        let repo = MemoryRepository::new();
+        create_user(&repo, "student", "password1");
+        let token = login(&repo, "student", "password1").unwrap();
+        block_on(auth_service::logout(&repo, &token)).unwrap();
+        assert!(!is_session_valid(&repo, &token));
     }
 
     #[test]
     fn test_empty_session_invalid() {
-        assert!(!is_session_valid("", false));
+        let repo = MemoryRepository::new();
+        create_user(&repo, "student", "password1");
+        assert!(!is_session_valid(&repo, ""));
     }
 
     #[test]
     fn test_username_uniqueness_check() {
-        let existing = vec!["admin".to_string(), "user1".to_string()];
-        assert!(!is_username_unique(&existing, "admin"));
-        assert!(is_username_unique(&existing, "newuser"));
+        let repo = MemoryRepository::new();
+        create_user(&repo, "admin", "password1");
+        create_user(&repo, "user1", "password1");
+
+        let duplicate = block_on(user_service::create_user(&repo, None, CreateUserInput {
+            username: "admin".to_string(),
+            password: "password1".to_string(),
+            display_name: "admin".to_string(),
+            role: "admin".to_string(),
+        }));
+        assert!(duplicate.is_err());
+        create_user(&repo, "newuser", "password1");
+    }
+
+    #[test]
+    fn test_new_user_gets_default_subjects() {
+        let repo = MemoryRepository::new();
+        let user = create_user(&repo, "student", "password1");
+        let subjects = block_on(crate::services::subject_service::get_subjects(&repo, user.id)).unwrap();
+        assert_eq!(subjects.len(), 4);
+    }
+
+    #[test]
+    fn test_reset_password_of_missing_user() {
+        let repo = MemoryRepository::new();
+        let admin = create_user(&repo, "admin", "password1");
+        let err = block_on(user_service::reset_user_password(&repo, admin.id, admin.id + 1, "newpass99")).unwrap_err();
+        assert_eq!(err.code(), "not_found");
+
+        // 不存在的用户不会留下重置记录
+        let query = AuditLogQuery { action: Some("password.reset".to_string()), ..Default::default() };
+        let log = block_on(audit_service::get_audit_log(&repo, query)).unwrap();
+        assert!(log.events.is_empty());
     }
 }
@@ -0,0 +1,108 @@
+// CSV 读写 (RFC 4180：逗号分隔，字段可用双引号包裹，引号内的双引号写作两个双引号)
+
+/// 解析 CSV 文本，返回 (行号, 字段) 列表 (行号从 1 开始，跳过空行)
+/// 引号未闭合时返回出错的行号
+pub fn parse(input: &str) -> Result<Vec<(usize, Vec<String>)>, usize> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                push_record(&mut records, record_line, std::mem::take(&mut record));
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(record_line);
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        push_record(&mut records, record_line, record);
+    }
+
+    Ok(records)
+}
+
+/// 保存一行记录 (忽略全部字段为空的行)
+fn push_record(records: &mut Vec<(usize, Vec<String>)>, line: usize, record: Vec<String>) {
+    if record.iter().any(|f| !f.trim().is_empty()) {
+        records.push((line, record));
+    }
+}
+
+/// 电子表格会当作公式执行的起始字符
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// 以公式字符开头的字段前加上单引号，避免在电子表格中打开时被当作公式执行
+fn guard_formula(field: &str) -> String {
+    if field.starts_with(FORMULA_PREFIXES) {
+        format!("'{}", field)
+    } else {
+        field.to_string()
+    }
+}
+
+/// 去掉写出时为公式字符加上的单引号 (导入导出的文件时使用)
+pub fn unguard_formula(field: &str) -> &str {
+    match field.strip_prefix('\'') {
+        Some(rest) if rest.starts_with(FORMULA_PREFIXES) => rest,
+        _ => field,
+    }
+}
+
+/// 转义单个字段 (以公式字符开头时加单引号，包含逗号、引号或换行时用双引号包裹)
+pub fn escape(field: &str) -> String {
+    let field = guard_formula(field);
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// 将多行记录写为 CSV 文本 (每行以 CRLF 结尾)
+pub fn write<I, R, F>(rows: I) -> String
+where
+    I: IntoIterator<Item = R>,
+    R: IntoIterator<Item = F>,
+    F: AsRef<str>,
+{
+    let mut output = String::new();
+    for row in rows {
+        let fields: Vec<String> = row.into_iter().map(|f| escape(f.as_ref())).collect();
+        output.push_str(&fields.join(","));
+        output.push_str("\r\n");
+    }
+    output
+}
@@ -1,5 +1,9 @@
 // 工具模块
 
+pub mod csv;
 pub mod fuzzy_match;
+pub mod password;
+pub mod random;
+pub mod totp;
 
 pub use fuzzy_match::*;
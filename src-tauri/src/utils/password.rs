@@ -0,0 +1,108 @@
+// 密码哈希与密码策略
+use crate::error::AppError;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use std::sync::OnceLock;
+
+/// 密码最小长度
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// 密码最大长度
+pub const MAX_PASSWORD_LENGTH: usize = 128;
+
+/// argon2 哈希的 PHC 字符串前缀
+const ARGON2_PREFIX: &str = "$argon2";
+
+/// 哈希器 (测试中使用最小参数，避免属性测试过慢)
+fn hasher() -> Argon2<'static> {
+    #[cfg(test)]
+    {
+        use argon2::{Algorithm, Params, Version};
+        let params = Params::new(Params::MIN_M_COST, Params::MIN_T_COST, Params::MIN_P_COST, None)
+            .expect("argon2 最小参数无效");
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+    }
+    #[cfg(not(test))]
+    Argon2::default()
+}
+
+/// 使用 argon2id 和随机盐哈希密码，返回 PHC 格式字符串
+pub fn hash_password(password: &str) -> Result<String, AppError> {
+    let salt = SaltString::generate(&mut OsRng);
+    hasher()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::Config {
+            message: "密码哈希失败".to_string(),
+            details: e.to_string(),
+        })
+}
+
+/// 存储的密码是否为 argon2 哈希 (否则为旧版明文)
+pub fn is_hashed(stored: &str) -> bool {
+    stored.starts_with(ARGON2_PREFIX)
+}
+
+/// 校验密码，兼容旧版明文存储
+pub fn verify_password(password: &str, stored: &str) -> bool {
+    if !is_hashed(stored) {
+        return constant_time_eq(password.as_bytes(), stored.as_bytes());
+    }
+
+    match PasswordHash::new(stored) {
+        Ok(parsed) => hasher().verify_password(password.as_bytes(), &parsed).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// 对固定的占位哈希执行一次校验并丢弃结果
+/// 用户名不存在时调用，使响应耗时与密码错误时一致，避免暴露用户名是否存在
+pub fn verify_dummy_password(password: &str) {
+    static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+    let stored = DUMMY_HASH.get_or_init(|| {
+        let salt = SaltString::generate(&mut OsRng);
+        hasher()
+            .hash_password(b"dummy-password-0", &salt)
+            .map(|hash| hash.to_string())
+            .expect("占位密码哈希失败")
+    });
+
+    std::hint::black_box(verify_password(password, stored));
+}
+
+/// 常量时间比较 (长度不同时直接返回)
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// 检查密码是否满足密码策略，错误信息指明未满足的规则
+pub fn validate_password_policy(password: &str) -> Result<(), AppError> {
+    let length = password.chars().count();
+
+    if length < MIN_PASSWORD_LENGTH {
+        return Err(AppError::Validation(format!("密码长度不能少于{}个字符", MIN_PASSWORD_LENGTH)));
+    }
+
+    if length > MAX_PASSWORD_LENGTH {
+        return Err(AppError::Validation(format!("密码长度不能超过{}个字符", MAX_PASSWORD_LENGTH)));
+    }
+
+    if password.chars().any(char::is_whitespace) {
+        return Err(AppError::Validation("密码不能包含空白字符".to_string()));
+    }
+
+    if !password.chars().any(|c| c.is_ascii_alphabetic()) {
+        return Err(AppError::Validation("密码必须包含字母".to_string()));
+    }
+
+    if !password.chars().any(|c| c.is_ascii_digit()) {
+        return Err(AppError::Validation("密码必须包含数字".to_string()));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,10 @@
+// 安全随机字符串 (邀请码、恢复码、初始密码)
+use rand::rngs::OsRng;
+use rand::Rng;
+
+/// 从字符集中均匀抽取字符，生成指定长度的随机字符串 (使用操作系统随机源)
+pub fn random_string(alphabet: &[u8], length: usize) -> String {
+    (0..length)
+        .map(|_| alphabet[OsRng.gen_range(0..alphabet.len())] as char)
+        .collect()
+}
@@ -0,0 +1,117 @@
+// 基于时间的一次性密码 (RFC 6238 TOTP，HMAC-SHA1、6 位、30 秒步长)
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+/// 验证码位数
+pub const TOTP_DIGITS: usize = 6;
+
+/// 时间步长 (秒)
+pub const TOTP_PERIOD_SECS: i64 = 30;
+
+/// 允许的时钟偏差 (前后各一个步长)
+const TOTP_SKEW_STEPS: i64 = 1;
+
+/// 密钥长度 (字节，RFC 4226 推荐 160 位)
+const SECRET_BYTES: usize = 20;
+
+/// Base32 字符集 (RFC 4648)
+const BASE32_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// 生成随机密钥，返回 Base32 编码 (无填充)
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; SECRET_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    base32_encode(&bytes)
+}
+
+/// Base32 编码 (无填充)
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        output.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    output
+}
+
+/// Base32 解码 (忽略大小写、空白和填充)，包含非法字符时返回 None
+pub fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(encoded.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in encoded.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&b| b as char == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+    Some(output)
+}
+
+/// 指定时间所在的时间步
+pub fn time_step(now: DateTime<Utc>) -> i64 {
+    now.timestamp().div_euclid(TOTP_PERIOD_SECS)
+}
+
+/// 计算指定时间步的验证码 (RFC 4226 动态截断)
+pub fn code_at(secret: &[u8], step: i64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC 接受任意长度的密钥");
+    mac.update(&step.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([hash[offset], hash[offset + 1], hash[offset + 2], hash[offset + 3]]) & 0x7fff_ffff;
+    format!("{:0width$}", binary % 10u32.pow(TOTP_DIGITS as u32), width = TOTP_DIGITS)
+}
+
+/// 校验验证码，返回匹配的时间步 (允许前后一个步长的时钟偏差)
+pub fn verify_code(secret: &str, code: &str, now: DateTime<Utc>) -> Option<i64> {
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    if code.len() != TOTP_DIGITS || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let secret = base32_decode(secret)?;
+    let current = time_step(now);
+    (current - TOTP_SKEW_STEPS..=current + TOTP_SKEW_STEPS).find(|&step| code_at(&secret, step) == code)
+}
+
+/// 生成身份验证器应用使用的 otpauth 链接 (可转换为二维码)
+pub fn provisioning_uri(issuer: &str, account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account),
+        secret,
+        percent_encode(issuer),
+        TOTP_DIGITS,
+        TOTP_PERIOD_SECS
+    )
+}
+
+/// URI 百分号编码 (保留 RFC 3986 非保留字符)
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -3,6 +3,7 @@ import { Routes, Route, Navigate, useNavigate, useLocation } from 'react-router-
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuthStore } from './stores/authStore';
+import { errorMessage } from './utils/error';
 import { useThemeStore, THEMES, ThemeType } from './stores/themeStore';
 import Login from './pages/Login';
 import UserManagement from './pages/UserManagement';
@@ -14,6 +15,7 @@ import Stats from './pages/Stats';
 import Review from './pages/Review';
 import AIChat from './pages/AIChat';
 import WrongNotes from './pages/WrongNotes';
+import PasswordChangeRequired from './components/PasswordChangeRequired';
 
 // 类型定义
 interface CountdownResponse {
@@ -30,6 +32,7 @@ interface UserResponse {
   display_name: string;
   role: string;
   role_label: string;
+  permissions?: string[];
   created_at: string;
 }
 
@@ -38,6 +41,26 @@ interface TaskResponse {
   status: string;
 }
 
+interface SessionResponse {
+  id: number;
+  created_at: string;
+  last_used_at: string;
+  expires_at: string;
+  is_current: boolean;
+}
+
+interface TwoFactorStatus {
+  enabled: boolean;
+  enabled_at: string | null;
+  recovery_codes_remaining: number;
+  required: boolean;
+}
+
+interface TotpEnrollment {
+  secret: string;
+  provisioning_uri: string;
+}
+
 interface StatsResponse {
   total_study_minutes: number;
   total_pomodoros: number;
@@ -78,6 +101,13 @@ function AppLayout({ children }: { children: React.ReactNode }) {
   const [showThemeDialog, setShowThemeDialog] = useState(false);
   const [newDisplayName, setNewDisplayName] = useState('');
   const [isSavingProfile, setIsSavingProfile] = useState(false);
+  const [sessions, setSessions] = useState<SessionResponse[]>([]);
+  const [twoFactor, setTwoFactor] = useState<TwoFactorStatus | null>(null);
+  const [enrollment, setEnrollment] = useState<TotpEnrollment | null>(null);
+  const [twoFactorCode, setTwoFactorCode] = useState('');
+  const [twoFactorPassword, setTwoFactorPassword] = useState('');
+  const [recoveryCodes, setRecoveryCodes] = useState<string[]>([]);
+  const [twoFactorError, setTwoFactorError] = useState('');
   const [sidebarCollapsed, setSidebarCollapsed] = useState(false);
 
   useEffect(() => {
@@ -91,6 +121,68 @@ function AppLayout({ children }: { children: React.ReactNode }) {
     loadCountdowns();
   }, [sessionToken]);
 
+  const loadSessions = async () => {
+    if (!sessionToken) return;
+    try { setSessions(await invoke<SessionResponse[]>('get_my_sessions', { sessionToken })); }
+    catch (e) { console.error(e); }
+  };
+
+  const handleRevokeSession = async (sessionId: number) => {
+    if (!sessionToken) return;
+    try { await invoke('revoke_session', { sessionToken, sessionId }); await loadSessions(); }
+    catch (e) { console.error(e); }
+  };
+
+  const handleRevokeOtherSessions = async () => {
+    if (!sessionToken) return;
+    try { await invoke('revoke_other_sessions', { sessionToken }); await loadSessions(); }
+    catch (e) { console.error(e); }
+  };
+
+  const loadTwoFactor = async () => {
+    if (!sessionToken) return;
+    try { setTwoFactor(await invoke<TwoFactorStatus>('get_two_factor_status', { sessionToken })); }
+    catch (e) { console.error(e); }
+  };
+
+  // 两步验证操作：成功后清空输入并刷新状态，失败时显示错误
+  const runTwoFactorAction = async (action: () => Promise<void>) => {
+    setTwoFactorError('');
+    try { await action(); setTwoFactorCode(''); setTwoFactorPassword(''); await loadTwoFactor(); }
+    catch (e) { setTwoFactorError(errorMessage(e)); }
+  };
+
+  const handleBeginEnrollment = () => runTwoFactorAction(async () => {
+    setRecoveryCodes([]);
+    setEnrollment(await invoke<TotpEnrollment>('begin_totp_enrollment', { sessionToken }));
+  });
+
+  const handleConfirmEnrollment = () => runTwoFactorAction(async () => {
+    const res = await invoke<{ recovery_codes: string[] }>('confirm_totp_enrollment', { sessionToken, code: twoFactorCode });
+    setEnrollment(null);
+    setRecoveryCodes(res.recovery_codes);
+  });
+
+  const handleRegenerateRecoveryCodes = () => runTwoFactorAction(async () => {
+    const res = await invoke<{ recovery_codes: string[] }>('regenerate_recovery_codes', { sessionToken, code: twoFactorCode });
+    setRecoveryCodes(res.recovery_codes);
+  });
+
+  const handleDisableTwoFactor = () => runTwoFactorAction(async () => {
+    await invoke('disable_two_factor', { sessionToken, currentPassword: twoFactorPassword });
+    setRecoveryCodes([]);
+  });
+
+  const openProfileDialog = () => {
+    setNewDisplayName(user?.display_name || '');
+    setEnrollment(null);
+    setRecoveryCodes([]);
+    setTwoFactorError('');
+    setShowProfileDialog(true);
+    loadSessions();
+    loadTwoFactor();
+  };
+
   const handleSaveProfile = async () => {
     if (!sessionToken || !newDisplayName.trim()) return;
     setIsSavingProfile(true);
@@ -140,7 +232,7 @@ function AppLayout({ children }: { children: React.ReactNode }) {
             );
           })}
           
-          {user?.role === 'admin' && (
+          {user?.permissions?.includes('users.manage') && (
             <>
               <div className="border-t border-white/5 my-3"></div>
               <button onClick={() => navigate('/users')}
@@ -163,7 +255,7 @@ function AppLayout({ children }: { children: React.ReactNode }) {
 
         {/* 底部用户信息 */}
         <div className="p-3 border-t border-white/5">
-          <button onClick={() => { setNewDisplayName(user?.display_name || ''); setShowProfileDialog(true); }}
+          <button onClick={openProfileDialog}
             className="w-full flex items-center gap-3 px-3 py-2 rounded-xl hover:bg-white/5 transition-all">
             <div className="w-9 h-9 bg-gradient-to-br from-violet-500 to-purple-500 rounded-full flex items-center justify-center text-white text-sm font-bold">
               {user?.display_name?.charAt(0) || '?'}
@@ -190,13 +282,85 @@ function AppLayout({ children }: { children: React.ReactNode }) {
       {/* 主内容区 */}
       <main className="flex-1 overflow-auto">{children}</main>
 
-      {/* 修改昵称对话框 */}
+      {/* 个人设置对话框 (昵称、登录设备、两步验证) */}
       {showProfileDialog && (
         <div className="fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50">
           <div className="bg-slate-800 rounded-2xl shadow-2xl w-full max-w-md p-6 m-4 border border-white/10">
             <h3 className="text-xl font-bold text-white mb-4">修改昵称</h3>
             <input type="text" value={newDisplayName} onChange={(e) => setNewDisplayName(e.target.value)}
               className="w-full px-4 py-3 bg-slate-700/50 border border-slate-600 rounded-xl text-white mb-4" placeholder="输入新昵称" />
+            <div className="mb-4">
+              <div className="flex justify-between items-center mb-2">
+                <h4 className="text-sm text-slate-400">登录设备</h4>
+                {sessions.some(s => !s.is_current) && (
+                  <button onClick={handleRevokeOtherSessions} className="text-xs text-rose-400 hover:text-rose-300">注销其他设备</button>
+                )}
+              </div>
+              <div className="space-y-2 max-h-48 overflow-auto">
+                {sessions.map((s) => (
+                  <div key={s.id} className="flex justify-between items-center px-3 py-2 bg-slate-700/30 rounded-lg text-xs">
+                    <div className="text-slate-300">
+                      <div>登录于 {new Date(s.created_at).toLocaleString('zh-CN')}{s.is_current && <span className="ml-2 text-emerald-400">当前设备</span>}</div>
+                      <div className="text-slate-500">最近使用 {new Date(s.last_used_at).toLocaleString('zh-CN')}</div>
+                    </div>
+                    {!s.is_current && (
+                      <button onClick={() => handleRevokeSession(s.id)} className="text-rose-400 hover:text-rose-300">注销</button>
+                    )}
+                  </div>
+                ))}
+              </div>
+            </div>
+            <div className="mb-4">
+              <h4 className="text-sm text-slate-400 mb-2">
+                两步验证
+                {twoFactor?.enabled
+                  ? <span className="ml-2 text-emerald-400">已启用 · 剩余 {twoFactor.recovery_codes_remaining} 个恢复码</span>
+                  : <span className="ml-2 text-slate-500">未启用</span>}
+                {twoFactor?.required && !twoFactor.enabled && <span className="ml-2 text-amber-400">管理员账户必须启用</span>}
+              </h4>
+              {twoFactorError && <div className="mb-2 text-xs text-rose-400">{twoFactorError}</div>}
+              {recoveryCodes.length > 0 && (
+                <div className="mb-2 p-3 bg-slate-700/30 rounded-lg text-xs">
+                  <div className="text-amber-400 mb-2">请妥善保存以下恢复码，每个只能使用一次，关闭后不再显示</div>
+                  <div className="grid grid-cols-2 gap-1 font-mono text-slate-200">
+                    {recoveryCodes.map((c) => <span key={c}>{c}</span>)}
+                  </div>
+                </div>
+              )}
+              {enrollment && (
+                <div className="mb-2 p-3 bg-slate-700/30 rounded-lg text-xs text-slate-300 space-y-1">
+                  <div>在身份验证器中添加账户，手动输入密钥：</div>
+                  <div className="font-mono text-slate-100 break-all select-all">{enrollment.secret}</div>
+                  <div className="text-slate-500 break-all select-all">{enrollment.provisioning_uri}</div>
+                </div>
+              )}
+              {twoFactor && (!twoFactor.enabled || enrollment) ? (
+                enrollment ? (
+                  <div className="flex gap-2">
+                    <input type="text" value={twoFactorCode} onChange={(e) => setTwoFactorCode(e.target.value)} placeholder="输入 6 位验证码"
+                      className="flex-1 px-3 py-2 bg-slate-700/50 border border-slate-600 rounded-lg text-white text-sm font-mono" />
+                    <button onClick={handleConfirmEnrollment} disabled={!twoFactorCode.trim()} className="px-3 py-2 text-sm bg-violet-500 text-white rounded-lg disabled:opacity-50">完成绑定</button>
+                  </div>
+                ) : (
+                  <button onClick={handleBeginEnrollment} className="text-xs text-violet-400 hover:text-violet-300">启用两步验证</button>
+                )
+              ) : twoFactor?.enabled && (
+                <div className="space-y-2">
+                  <div className="flex gap-2">
+                    <input type="text" value={twoFactorCode} onChange={(e) => setTwoFactorCode(e.target.value)} placeholder="当前验证码"
+                      className="flex-1 px-3 py-2 bg-slate-700/50 border border-slate-600 rounded-lg text-white text-sm font-mono" />
+                    <button onClick={handleRegenerateRecoveryCodes} disabled={!twoFactorCode.trim()} className="px-3 py-2 text-xs text-violet-400 hover:bg-slate-700 rounded-lg disabled:opacity-50">重新生成恢复码</button>
+                  </div>
+                  {!twoFactor.required && (
+                    <div className="flex gap-2">
+                      <input type="password" value={twoFactorPassword} onChange={(e) => setTwoFactorPassword(e.target.value)} placeholder="当前密码"
+                        className="flex-1 px-3 py-2 bg-slate-700/50 border border-slate-600 rounded-lg text-white text-sm" />
+                      <button onClick={handleDisableTwoFactor} disabled={!twoFactorPassword} className="px-3 py-2 text-xs text-rose-400 hover:bg-slate-700 rounded-lg disabled:opacity-50">关闭两步验证</button>
+                    </div>
+                  )}
+                </div>
+              )}
+            </div>
             <div className="flex justify-end gap-3">
               <button onClick={() => setShowProfileDialog(false)} className="px-4 py-2 text-slate-300 hover:bg-slate-700 rounded-xl">取消</button>
               <button onClick={handleSaveProfile} disabled={isSavingProfile || !newDisplayName.trim()}
@@ -412,7 +576,7 @@ function Dashboard() {
 
 // 受保护的路由组件
 function ProtectedRoute({ children }: { children: React.ReactNode }) {
-  const { sessionToken, checkSession } = useAuthStore();
+  const { user, sessionToken, checkSession } = useAuthStore();
   const [isChecking, setIsChecking] = useState(true);
   const [isValid, setIsValid] = useState(false);
 
@@ -439,6 +603,7 @@ function ProtectedRoute({ children }: { children: React.ReactNode }) {
   }
 
   if (!isValid) return <Navigate to="/login" replace />;
+  if (user?.must_change_password) return <PasswordChangeRequired />;
   return <AppLayout>{children}</AppLayout>;
 }
 
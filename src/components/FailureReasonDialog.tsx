@@ -0,0 +1,49 @@
+// 未完成原因对话框 - 标记任务为未完成时选择或填写原因
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useAuthStore } from '../stores/authStore';
+import { errorMessage } from '../utils/error';
+
+export const FAILURE_REASONS = ['时间不够', '太难', '状态差', '临时有事', '计划不合理'];
+
+interface FailureReasonDialogProps<T> {
+  task: { id: number; content: string };
+  onDone: (task: T) => void;
+  onClose: () => void;
+}
+
+export default function FailureReasonDialog<T>({ task, onDone, onClose }: FailureReasonDialogProps<T>) {
+  const { sessionToken } = useAuthStore();
+  const [reason, setReason] = useState('');
+  const [isSaving, setIsSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleSave = async () => {
+    if (!sessionToken || !reason.trim()) return;
+    setIsSaving(true); setError(null);
+    try { onDone(await invoke<T>('set_task_status', { sessionToken, taskId: task.id, status: 'failed', reason: reason.trim() })); }
+    catch (e) { setError(errorMessage(e)); }
+    finally { setIsSaving(false); }
+  };
+
+  return (
+    <div className="fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50">
+      <div className="bg-slate-800 rounded-2xl shadow-2xl w-full max-w-md p-6 m-4 border border-white/10">
+        <h3 className="text-xl font-bold text-white mb-2">✕ 标记为未完成</h3>
+        <p className="text-slate-400 text-sm mb-4">"{task.content}" 没有完成的原因是？</p>
+        <div className="flex flex-wrap gap-2 mb-3">
+          {FAILURE_REASONS.map(r => (
+            <button key={r} onClick={() => setReason(r)} className={`px-3 py-1.5 rounded-lg text-sm ${reason === r ? 'bg-rose-500 text-white' : 'bg-slate-700/50 text-slate-300 hover:bg-slate-700'}`}>{r}</button>
+          ))}
+        </div>
+        <input value={reason} onChange={e => setReason(e.target.value)} maxLength={100} placeholder="或者写下其他原因"
+          className="w-full px-3 py-2 bg-slate-700/50 border border-white/10 rounded-lg text-white text-sm" />
+        {error && <p className="mt-2 text-sm text-rose-400">{error}</p>}
+        <div className="mt-6 flex justify-end gap-3">
+          <button onClick={onClose} className="px-4 py-2 text-slate-400 hover:text-white">取消</button>
+          <button onClick={handleSave} disabled={isSaving || !reason.trim()} className="px-4 py-2 bg-rose-500 text-white rounded-lg disabled:opacity-50">{isSaving ? '保存中...' : '确定'}</button>
+        </div>
+      </div>
+    </div>
+  );
+}
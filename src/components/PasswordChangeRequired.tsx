@@ -0,0 +1,46 @@
+// 修改初始密码 - 使用初始密码登录的账户必须先设置新密码
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useAuthStore } from '../stores/authStore';
+import { errorMessage } from '../utils/error';
+
+export default function PasswordChangeRequired() {
+  const { user, sessionToken, setUser, logout } = useAuthStore();
+  const [currentPassword, setCurrentPassword] = useState('');
+  const [newPassword, setNewPassword] = useState('');
+  const [confirmPassword, setConfirmPassword] = useState('');
+  const [isSaving, setIsSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleSave = async () => {
+    if (!sessionToken || !user) return;
+    if (newPassword !== confirmPassword) { setError('两次输入的新密码不一致'); return; }
+    setIsSaving(true); setError(null);
+    try {
+      await invoke('change_password', { sessionToken, currentPassword, newPassword });
+      setUser({ ...user, must_change_password: false });
+    }
+    catch (e) { setError(errorMessage(e)); }
+    finally { setIsSaving(false); }
+  };
+
+  const inputClass = 'w-full px-3 py-2 bg-slate-700/50 border border-white/10 rounded-lg text-white text-sm';
+  return (
+    <div className="min-h-screen flex items-center justify-center bg-gradient-to-br from-slate-900 via-slate-800 to-slate-900">
+      <div className="bg-slate-800 rounded-2xl shadow-2xl w-full max-w-md p-6 m-4 border border-white/10">
+        <h3 className="text-xl font-bold text-white mb-2">🔒 请先修改初始密码</h3>
+        <p className="text-slate-400 text-sm mb-4">账户 {user?.username} 仍在使用初始密码，修改密码后才能继续使用。</p>
+        <div className="space-y-3">
+          <input type="password" value={currentPassword} onChange={e => setCurrentPassword(e.target.value)} placeholder="当前密码" className={inputClass} />
+          <input type="password" value={newPassword} onChange={e => setNewPassword(e.target.value)} placeholder="新密码 (至少 8 位，包含字母和数字)" className={inputClass} />
+          <input type="password" value={confirmPassword} onChange={e => setConfirmPassword(e.target.value)} placeholder="确认新密码" className={inputClass} />
+        </div>
+        {error && <p className="mt-2 text-sm text-rose-400">{error}</p>}
+        <div className="mt-6 flex justify-end gap-3">
+          <button onClick={logout} className="px-4 py-2 text-slate-400 hover:text-white">退出登录</button>
+          <button onClick={handleSave} disabled={isSaving || !currentPassword || !newPassword} className="px-4 py-2 bg-violet-500 text-white rounded-lg disabled:opacity-50">{isSaving ? '保存中...' : '修改密码'}</button>
+        </div>
+      </div>
+    </div>
+  );
+}
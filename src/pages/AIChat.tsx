@@ -4,6 +4,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { useAuthStore } from '../stores/authStore';
 import { useThemeStore, THEMES } from '../stores/themeStore';
 import ReactMarkdown from 'react-markdown';
+import { errorMessage } from '../utils/error';
 
 interface Message {
   id: number;
@@ -93,7 +94,7 @@ export default function AIChat() {
       const errorMessage: Message = {
         id: Date.now() + 1,
         role: 'assistant',
-        content: `抱歉，出现了错误：${e}`,
+        content: `抱歉，出现了错误：${errorMessage(e)}`,
         timestamp: new Date(),
       };
       setMessages(prev => [...prev, errorMessage]);
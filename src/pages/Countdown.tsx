@@ -3,6 +3,7 @@ import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuthStore } from '../stores/authStore';
 import { useThemeStore, THEMES } from '../stores/themeStore';
+import { errorMessage } from '../utils/error';
 
 interface CountdownResponse { id: number; name: string; target_time: string; notify_enabled: boolean; remaining_days: number; remaining_hours: number; remaining_minutes: number; is_expired: boolean; }
 
@@ -130,7 +131,7 @@ export default function Countdown() {
     if (!sessionToken) return;
     setIsLoading(true); setError(null);
     try { const result = await invoke<CountdownResponse[]>('get_countdowns', { sessionToken }); setCountdowns(result); }
-    catch (e) { setError(e as string); }
+    catch (e) { setError(errorMessage(e)); }
     finally { setIsLoading(false); }
   };
 
@@ -152,7 +153,7 @@ export default function Countdown() {
       setShowCreateDialog(false); setNewName(''); setNewTargetDate(''); setNewTargetTime('00:00'); setNewNotifyEnabled(true);
       setNewColor(PRESET_COLORS[0].gradient); setNewBgImage('');
       await loadCountdowns();
-    } catch (e) { setError(e as string); }
+    } catch (e) { setError(errorMessage(e)); }
     finally { setIsCreating(false); }
   };
 
@@ -165,7 +166,7 @@ export default function Countdown() {
       const newStyles = { ...styles }; delete newStyles[deleteCountdown.id];
       setStyles(newStyles); saveStyles(newStyles);
       setDeleteCountdown(null); await loadCountdowns();
-    } catch (e) { setError(e as string); }
+    } catch (e) { setError(errorMessage(e)); }
     finally { setIsDeleting(false); }
   };
 
@@ -6,17 +6,33 @@ import { useAuthStore } from '../stores/authStore';
 export default function Login() {
   const [username, setUsername] = useState('');
   const [password, setPassword] = useState('');
-  const { login, isLoading, error, clearError } = useAuthStore();
+  const [isRegister, setIsRegister] = useState(false);
+  const [inviteCode, setInviteCode] = useState('');
+  const [displayName, setDisplayName] = useState('');
+  const [code, setCode] = useState('');
+  const { login, register, verifyLoginCode, cancelTwoFactor, challengeToken, isLoading, error, clearError } = useAuthStore();
   const navigate = useNavigate();
 
+  const canSubmit = challengeToken
+    ? code.trim()
+    : username.trim() && password.trim() && (!isRegister || (inviteCode.trim() && displayName.trim()));
+
   const handleSubmit = async (e: FormEvent) => {
     e.preventDefault();
     clearError();
-    if (!username.trim() || !password.trim()) return;
-    const success = await login(username, password);
+    if (!canSubmit) return;
+    const success = challengeToken
+      ? await verifyLoginCode(code)
+      : isRegister
+        ? await register(inviteCode, username, password, displayName)
+        : await login(username, password);
     if (success) navigate('/');
   };
 
+  const backToPassword = () => { setCode(''); cancelTwoFactor(); };
+
+  const toggleMode = () => { clearError(); setIsRegister(!isRegister); };
+
   return (
     <div className="min-h-screen flex items-center justify-center bg-gradient-to-br from-slate-900 via-purple-900 to-slate-900 relative overflow-hidden">
       {/* 动态背景 */}
@@ -35,7 +51,9 @@ export default function Login() {
             <span className="text-5xl">📚</span>
           </div>
           <h1 className="text-3xl font-black bg-gradient-to-r from-indigo-400 via-purple-400 to-pink-400 bg-clip-text text-transparent">考研学习规划助手</h1>
-          <p className="text-slate-400 mt-2">登录以开始你的学习之旅 ✨</p>
+          <p className="text-slate-400 mt-2">
+            {challengeToken ? '请输入身份验证器中的验证码 🔐' : isRegister ? '使用邀请码注册账户 🎟️' : '登录以开始你的学习之旅 ✨'}
+          </p>
         </div>
 
         {/* 错误提示 */}
@@ -48,6 +66,23 @@ export default function Login() {
 
         {/* 登录表单 */}
         <form onSubmit={handleSubmit} className="space-y-5">
+          {challengeToken ? (
+            <div>
+              <label className="block text-sm font-semibold text-slate-300 mb-2">🔐 验证码</label>
+              <input type="text" value={code} onChange={(e) => setCode(e.target.value)} autoFocus
+                className="w-full px-5 py-4 bg-slate-700/50 border border-slate-600 rounded-2xl focus:ring-2 focus:ring-purple-500 focus:border-transparent text-white text-lg placeholder-slate-500 transition-all hover:bg-slate-700/70 font-mono tracking-widest"
+                placeholder="6 位验证码或恢复码" disabled={isLoading} />
+            </div>
+          ) : (<>
+          {isRegister && (
+            <div>
+              <label className="block text-sm font-semibold text-slate-300 mb-2">🎟️ 邀请码</label>
+              <input type="text" value={inviteCode} onChange={(e) => setInviteCode(e.target.value)}
+                className="w-full px-5 py-4 bg-slate-700/50 border border-slate-600 rounded-2xl focus:ring-2 focus:ring-purple-500 focus:border-transparent text-white text-lg placeholder-slate-500 transition-all hover:bg-slate-700/70 font-mono uppercase"
+                placeholder="请输入邀请码" disabled={isLoading} />
+            </div>
+          )}
+
           <div>
             <label className="block text-sm font-semibold text-slate-300 mb-2">👤 用户名</label>
             <input type="text" value={username} onChange={(e) => setUsername(e.target.value)}
@@ -59,10 +94,20 @@ export default function Login() {
             <label className="block text-sm font-semibold text-slate-300 mb-2">🔒 密码</label>
             <input type="password" value={password} onChange={(e) => setPassword(e.target.value)}
               className="w-full px-5 py-4 bg-slate-700/50 border border-slate-600 rounded-2xl focus:ring-2 focus:ring-purple-500 focus:border-transparent text-white text-lg placeholder-slate-500 transition-all hover:bg-slate-700/70"
-              placeholder="请输入密码" disabled={isLoading} />
+              placeholder={isRegister ? '至少8位，包含字母和数字' : '请输入密码'} disabled={isLoading} />
           </div>
 
-          <button type="submit" disabled={isLoading || !username.trim() || !password.trim()}
+          {isRegister && (
+            <div>
+              <label className="block text-sm font-semibold text-slate-300 mb-2">✏️ 昵称</label>
+              <input type="text" value={displayName} onChange={(e) => setDisplayName(e.target.value)}
+                className="w-full px-5 py-4 bg-slate-700/50 border border-slate-600 rounded-2xl focus:ring-2 focus:ring-purple-500 focus:border-transparent text-white text-lg placeholder-slate-500 transition-all hover:bg-slate-700/70"
+                placeholder="请输入昵称" disabled={isLoading} />
+            </div>
+          )}
+          </>)}
+
+          <button type="submit" disabled={isLoading || !canSubmit}
             className="w-full py-4 bg-gradient-to-r from-indigo-600 via-purple-600 to-pink-600 hover:from-indigo-500 hover:via-purple-500 hover:to-pink-500 disabled:from-slate-600 disabled:to-slate-700 text-white font-bold text-lg rounded-2xl transition-all duration-300 flex items-center justify-center shadow-xl shadow-purple-500/30 hover:shadow-2xl hover:shadow-purple-500/40 hover:-translate-y-1 disabled:shadow-none disabled:translate-y-0">
             {isLoading ? (
               <>
@@ -70,14 +115,20 @@ export default function Login() {
                   <circle className="opacity-25" cx="12" cy="12" r="10" stroke="currentColor" strokeWidth="4" />
                   <path className="opacity-75" fill="currentColor" d="M4 12a8 8 0 018-8V0C5.373 0 0 5.373 0 12h4zm2 5.291A7.962 7.962 0 014 12H0c0 3.042 1.135 5.824 3 7.938l3-2.647z" />
                 </svg>
-                登录中...
+                {challengeToken ? '验证中...' : isRegister ? '注册中...' : '登录中...'}
               </>
             ) : (
-              <>🚀 开始学习</>
+              <>{challengeToken ? '✅ 验证并登录' : isRegister ? '🎉 注册并登录' : '🚀 开始学习'}</>
             )}
           </button>
         </form>
 
+        <div className="mt-5 text-center">
+          <button type="button" onClick={challengeToken ? backToPassword : toggleMode} className="text-sm text-purple-300 hover:text-purple-200 transition-colors">
+            {challengeToken ? '返回重新输入密码' : isRegister ? '已有账户？返回登录' : '有邀请码？注册新账户'}
+          </button>
+        </div>
+
         {/* 底部装饰 */}
         <div className="mt-8 text-center">
           <p className="text-slate-500 text-sm">考研加油，你一定行！💪</p>
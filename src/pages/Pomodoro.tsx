@@ -4,16 +4,28 @@ import { invoke } from '@tauri-apps/api/core';
 import { useAuthStore } from '../stores/authStore';
 import { useThemeStore, THEMES } from '../stores/themeStore';
 import SubjectSelect from '../components/SubjectSelect';
+import { errorMessage } from '../utils/error';
 
 interface PomodoroResponse {
   id: number;
   subject_id: number | null;
   subject_name: string | null;
+  task_id: number | null;
   start_time: string;
   duration_minutes: number;
   status: string;
 }
 
+// 可关联番茄钟的当天任务 (关联后学习时长按任务及其标签统计)
+interface TaskOption {
+  id: number;
+  subject_id: number | null;
+  start_time: string;
+  end_time: string;
+  content: string;
+  status: string;
+}
+
 type TimerMode = 'work' | 'break';
 type TimerState = 'idle' | 'running' | 'paused';
 
@@ -185,6 +197,8 @@ export default function Pomodoro() {
   const [timeLeft, setTimeLeft] = useState(25 * 60);
   const [currentPomodoroId, setCurrentPomodoroId] = useState<number | null>(null);
   const [selectedSubjectId, setSelectedSubjectId] = useState<number | null>(null);
+  const [todayTasks, setTodayTasks] = useState<TaskOption[]>([]);
+  const [selectedTaskId, setSelectedTaskId] = useState<number | null>(null);
   const [history, setHistory] = useState<PomodoroResponse[]>([]);
   const [isLoadingHistory, setIsLoadingHistory] = useState(true);
   const [todayMinutes, setTodayMinutes] = useState(0);
@@ -216,7 +230,23 @@ export default function Pomodoro() {
     finally { setIsLoadingHistory(false); }
   };
 
-  useEffect(() => { loadHistory(); }, [sessionToken]);
+  const loadTodayTasks = async () => {
+    if (!sessionToken) return;
+    try {
+      const today = new Date().toISOString().split('T')[0];
+      const result = await invoke<TaskOption[]>('get_tasks_by_date', { sessionToken, date: today });
+      setTodayTasks(result.filter(t => t.status !== 'rolled_over'));
+    } catch (e) { console.error(e); }
+  };
+
+  // 选择任务时同时使用任务的科目
+  const handleSelectTask = (taskId: number | null) => {
+    setSelectedTaskId(taskId);
+    const task = todayTasks.find(t => t.id === taskId);
+    if (task?.subject_id) setSelectedSubjectId(task.subject_id);
+  };
+
+  useEffect(() => { loadHistory(); loadTodayTasks(); }, [sessionToken]);
   useEffect(() => { if (state === 'idle') setTimeLeft(mode === 'work' ? workDuration * 60 : breakDuration * 60); }, [workDuration, breakDuration, mode, state]);
 
   useEffect(() => {
@@ -235,7 +265,7 @@ export default function Pomodoro() {
     setState('idle');
     if (mode === 'work' && currentPomodoroId && sessionToken) {
       try { await invoke('complete_pomodoro', { sessionToken, pomodoroId: currentPomodoroId, durationMinutes: workDuration }); await loadHistory(); }
-      catch (e) { setError(e as string); }
+      catch (e) { setError(errorMessage(e)); }
       setCurrentPomodoroId(null);
       setMode('break'); setTimeLeft(breakDuration * 60);
     } else { setMode('work'); setTimeLeft(workDuration * 60); }
@@ -244,9 +274,11 @@ export default function Pomodoro() {
   const handleStart = async () => {
     if (mode === 'work' && !currentPomodoroId && sessionToken) {
       try {
-        const result = await invoke<PomodoroResponse>('start_pomodoro', { sessionToken, subjectId: selectedSubjectId, taskId: null });
+        const result = await invoke<PomodoroResponse>('start_pomodoro', { sessionToken, subjectId: selectedSubjectId, taskId: selectedTaskId });
         setCurrentPomodoroId(result.id);
-      } catch (e) { setError(e as string); return; }
+        // 尚未生成的重复任务在开始时生成，刷新后使用新的任务 ID
+        if (selectedTaskId !== null) { setSelectedTaskId(result.task_id); loadTodayTasks(); }
+      } catch (e) { setError(errorMessage(e)); return; }
     }
     setState('running');
     // 播放白噪音
@@ -262,7 +294,7 @@ export default function Pomodoro() {
       const totalSeconds = mode === 'work' ? workDuration * 60 : breakDuration * 60;
       const elapsedMinutes = Math.floor((totalSeconds - timeLeft) / 60);
       try { await invoke('cancel_pomodoro', { sessionToken, pomodoroId: currentPomodoroId, durationMinutes: elapsedMinutes }); await loadHistory(); }
-      catch (e) { setError(e as string); }
+      catch (e) { setError(errorMessage(e)); }
       setCurrentPomodoroId(null);
     }
     setMode('work'); setTimeLeft(workDuration * 60);
@@ -446,6 +478,14 @@ export default function Pomodoro() {
             {mode === 'work' && state === 'idle' && (
               <div className="mb-4"><label className="block text-sm text-slate-400 mb-2">选择科目（可选）</label><SubjectSelect value={selectedSubjectId} onChange={setSelectedSubjectId} placeholder="选择科目" /></div>
             )}
+            {mode === 'work' && state === 'idle' && todayTasks.length > 0 && (
+              <div className="mb-4"><label className="block text-sm text-slate-400 mb-2">关联今日任务（可选）</label>
+                <select value={selectedTaskId ?? ''} onChange={e => handleSelectTask(e.target.value ? Number(e.target.value) : null)} className="w-full px-3 py-2 bg-slate-700/50 border border-white/10 rounded-lg text-white text-sm">
+                  <option value="">不关联任务</option>
+                  {todayTasks.map(t => <option key={t.id} value={t.id}>{t.start_time.slice(0, 5)}-{t.end_time.slice(0, 5)} {t.content}</option>)}
+                </select>
+              </div>
+            )}
 
             {/* 白噪音按钮 */}
             <div className="mb-4 flex justify-center">
@@ -3,9 +3,19 @@ import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuthStore } from '../stores/authStore';
 import { useThemeStore, THEMES } from '../stores/themeStore';
+import { errorMessage } from '../utils/error';
+import FailureReasonDialog from '../components/FailureReasonDialog';
 
 interface DailyReview { id: number; review_date: string; feelings: string | null; difficulties: string | null; ai_suggestions: string | null; }
-interface TaskResponse { id: number; subject_name: string | null; subject_color: string | null; start_time: string; end_time: string; content: string; status: string; }
+interface TaskResponse { id: number; subject_name: string | null; subject_color: string | null; start_time: string; end_time: string; content: string; status: string; status_reason: string | null; }
+interface FailureReasonStats { reason: string | null; count: number; }
+
+// 以某天为结束的最近 30 天 (YYYY-MM-DD)
+const last30Days = (date: string): [string, string] => {
+  const [y, m, d] = date.split('-').map(Number);
+  const start = new Date(y, m - 1, d - 29);
+  return [`${start.getFullYear()}-${String(start.getMonth() + 1).padStart(2, '0')}-${String(start.getDate()).padStart(2, '0')}`, date];
+};
 
 export default function Review() {
   const { sessionToken } = useAuthStore();
@@ -23,6 +33,8 @@ export default function Review() {
   const [isLoadingHistory, setIsLoadingHistory] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [viewingReview, setViewingReview] = useState<DailyReview | null>(null);
+  const [failureReasons, setFailureReasons] = useState<FailureReasonStats[]>([]);
+  const [failingTask, setFailingTask] = useState<TaskResponse | null>(null);
 
   const loadTasks = async () => {
     if (!sessionToken) return;
@@ -48,7 +60,20 @@ export default function Review() {
     finally { setIsLoadingHistory(false); }
   };
 
-  useEffect(() => { loadTasks(); loadReview(); }, [sessionToken, selectedDate]);
+  const loadFailureReasons = async () => {
+    if (!sessionToken) return;
+    const [startDate, endDate] = last30Days(selectedDate);
+    try { setFailureReasons(await invoke<FailureReasonStats[]>('get_failure_reasons', { sessionToken, startDate, endDate })); }
+    catch (e) { console.error(e); }
+  };
+
+  // 把当天未完成的任务及原因写入“遇到的困难”
+  const appendFailedTasks = () => {
+    const lines = tasks.filter(t => t.status === 'failed').map(t => `${t.content}：${t.status_reason || '未填写原因'}`);
+    setDifficulties(d => [d.trim(), ...lines].filter(Boolean).join('\n'));
+  };
+
+  useEffect(() => { loadTasks(); loadReview(); loadFailureReasons(); }, [sessionToken, selectedDate]);
   useEffect(() => { loadHistory(); }, [sessionToken]);
 
   const handleSave = async () => {
@@ -57,7 +82,7 @@ export default function Review() {
     try {
       await invoke('save_review', { sessionToken, reviewDate: selectedDate, feelings: feelings || null, difficulties: difficulties || null });
       await loadReview(); await loadHistory(); setSaveSuccess(true); setTimeout(() => setSaveSuccess(false), 3000);
-    } catch (e) { setError(e as string); }
+    } catch (e) { setError(errorMessage(e)); }
     finally { setIsSaving(false); }
   };
 
@@ -125,12 +150,30 @@ export default function Review() {
                       <span className="text-xs text-slate-500 font-mono w-24">{formatTime(task.start_time)}-{formatTime(task.end_time)}</span>
                       {task.subject_name && <span className="px-2 py-0.5 text-xs rounded text-white" style={{ backgroundColor: task.subject_color || '#6B7280' }}>{task.subject_name}</span>}
                       <span className={`flex-1 text-sm ${task.status === 'completed' ? 'line-through text-slate-500' : 'text-slate-300'}`}>{task.content}</span>
+                      {task.status === 'failed' && task.status_reason && <span className="text-xs text-rose-400">{task.status_reason}</span>}
+                      {task.status === 'pending' && <button onClick={() => setFailingTask(task)} className="text-xs text-slate-500 hover:text-rose-400" title="标记为未完成">⊘ 未完成</button>}
                     </div>
                   ))}
                 </div>
               )}
             </div>
 
+            {/* 未完成原因 */}
+            {failureReasons.length > 0 && (
+              <div className={`${themeConfig.bgSecondary} rounded-xl border ${themeConfig.border} p-4`}>
+                <h2 className={`${themeConfig.text} font-bold mb-3`}>🔍 近 30 天未完成原因</h2>
+                <div className="space-y-2">
+                  {failureReasons.map(r => (
+                    <div key={r.reason ?? ''} className="flex items-center gap-3">
+                      <span className={`w-24 text-sm truncate ${themeConfig.textSecondary}`}>{r.reason ?? '未填写'}</span>
+                      <div className="flex-1 h-2 bg-slate-700 rounded-full overflow-hidden"><div className="h-full bg-rose-500 rounded-full" style={{ width: `${(r.count / failureReasons[0].count) * 100}%` }} /></div>
+                      <span className="w-12 text-xs text-right text-slate-500">{r.count} 个</span>
+                    </div>
+                  ))}
+                </div>
+              </div>
+            )}
+
             {/* 复盘表单 */}
             <div className={`${themeConfig.bgSecondary} rounded-xl border ${themeConfig.border} p-4`}>
               <h2 className={`${themeConfig.text} font-bold mb-4`}>✍️ 今日复盘</h2>
@@ -141,7 +184,10 @@ export default function Review() {
                     className={`w-full px-3 py-3 ${themeConfig.bgSecondary} border ${themeConfig.border} rounded-lg ${themeConfig.text} resize-none`} rows={3} placeholder="记录今天学习的感受、收获..." />
                 </div>
                 <div>
-                  <label className={`block text-sm ${themeConfig.textSecondary} mb-2`}>🤔 遇到的困难和问题</label>
+                  <div className="flex justify-between items-center mb-2">
+                    <label className={`block text-sm ${themeConfig.textSecondary}`}>🤔 遇到的困难和问题</label>
+                    {failedCount > 0 && <button onClick={appendFailedTasks} className="text-xs text-violet-400 hover:text-violet-300">+ 写入未完成原因</button>}
+                  </div>
                   <textarea value={difficulties} onChange={(e) => setDifficulties(e.target.value)}
                     className={`w-full px-3 py-3 ${themeConfig.bgSecondary} border ${themeConfig.border} rounded-lg ${themeConfig.text} resize-none`} rows={3} placeholder="记录今天遇到的困难、问题..." />
                 </div>
@@ -233,6 +279,11 @@ export default function Review() {
           </div>
         </div>
       )}
+
+      {failingTask && (
+        <FailureReasonDialog<TaskResponse> task={failingTask} onClose={() => setFailingTask(null)}
+          onDone={task => { setFailingTask(null); setTasks(items => items.map(t => (t.id === task.id ? task : t))); loadFailureReasons(); }} />
+      )}
     </div>
   );
 }
@@ -4,10 +4,12 @@ import { invoke } from '@tauri-apps/api/core';
 import { useAuthStore } from '../stores/authStore';
 import { useThemeStore, THEMES } from '../stores/themeStore';
 import { PieChart, Pie, Cell, LineChart, Line, XAxis, YAxis, CartesianGrid, Tooltip, ResponsiveContainer, Legend, BarChart, Bar } from 'recharts';
+import { errorMessage } from '../utils/error';
 
 interface SubjectStudyTime { subject_id: number; subject_name: string; subject_color: string; total_minutes: number; }
 interface DailyCompletion { date: string; total_tasks: number; completed_tasks: number; completion_rate: number; }
-interface Statistics { total_study_minutes: number; total_tasks: number; completed_tasks: number; completion_rate: number; subject_distribution: SubjectStudyTime[]; daily_trend: DailyCompletion[]; }
+interface TagStats { tag_id: number; tag_name: string; tag_color: string; study_minutes: number; total_tasks: number; completed_tasks: number; completion_rate: number; }
+interface Statistics { total_study_minutes: number; total_tasks: number; completed_tasks: number; completion_rate: number; subject_distribution: SubjectStudyTime[]; daily_trend: DailyCompletion[]; tag_distribution: TagStats[]; }
 
 // 学习目标
 interface StudyGoal { dailyMinutes: number; weeklyMinutes: number; dailyTasks: number; }
@@ -62,7 +64,7 @@ export default function Stats() {
     if (!sessionToken) return;
     setIsLoading(true); setError(null);
     try { const result = await invoke<Statistics>('get_stats', { sessionToken, startDate, endDate }); setStats(result); }
-    catch (e) { setError(e as string); }
+    catch (e) { setError(errorMessage(e)); }
     finally { setIsLoading(false); }
   };
 
@@ -107,7 +109,7 @@ export default function Stats() {
       a.download = `学习统计_${startDate}_${endDate}.csv`;
       a.click();
       URL.revokeObjectURL(url);
-    } catch (e) { setError(e as string); }
+    } catch (e) { setError(errorMessage(e)); }
     finally { setIsExporting(false); }
   };
 
@@ -244,6 +246,21 @@ export default function Stats() {
                 </div>
               )}
 
+              {/* 标签统计 */}
+              {stats.tag_distribution.length > 0 && (
+                <div className={`lg:col-span-2 ${themeConfig.bgSecondary} rounded-xl border ${themeConfig.border} p-4`}>
+                  <h2 className={`${themeConfig.text} font-bold mb-4`}>🏷️ 标签统计</h2>
+                  <div className="space-y-3">{stats.tag_distribution.map(t => (
+                    <div key={t.tag_id} className="flex items-center gap-3">
+                      <span className="w-24 text-sm truncate" style={{ color: t.tag_color }}>#{t.tag_name}</span>
+                      <div className="flex-1 h-2 bg-slate-700 rounded-full overflow-hidden"><div className="h-full rounded-full" style={{ width: `${t.completion_rate}%`, backgroundColor: t.tag_color }} /></div>
+                      <span className={`w-20 text-xs text-right ${themeConfig.textSecondary}`}>{t.completed_tasks}/{t.total_tasks} 任务</span>
+                      <span className={`w-28 text-xs text-right ${themeConfig.textSecondary}`}>{formatDurationLong(t.study_minutes)}</span>
+                    </div>
+                  ))}</div>
+                </div>
+              )}
+
               {/* 周对比 */}
               {stats.daily_trend.length >= 7 && (
                 <div className={`lg:col-span-2 ${themeConfig.bgSecondary} rounded-xl border ${themeConfig.border} p-4`}>
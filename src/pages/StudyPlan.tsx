@@ -4,9 +4,11 @@ import { invoke } from '@tauri-apps/api/core';
 import { useAuthStore } from '../stores/authStore';
 import { useThemeStore, THEMES } from '../stores/themeStore';
 import SubjectSelect from '../components/SubjectSelect';
+import FailureReasonDialog from '../components/FailureReasonDialog';
 import { DndContext, closestCenter, KeyboardSensor, PointerSensor, useSensor, useSensors, DragEndEvent } from '@dnd-kit/core';
 import { arrayMove, SortableContext, sortableKeyboardCoordinates, useSortable, verticalListSortingStrategy } from '@dnd-kit/sortable';
 import { CSS } from '@dnd-kit/utilities';
+import { errorMessage, isAppError } from '../utils/error';
 
 interface TaskResponse {
   id: number;
@@ -18,15 +20,49 @@ interface TaskResponse {
   end_time: string;
   content: string;
   status: string;
+  status_reason: string | null;
   alarm_enabled: boolean;
   alarm_time: string | null;
+  series_id: number | null;
+  carried_from_id: number | null;
+  progress: number;
+  checklist: ChecklistItem[];
+  priority: TaskPriority;
+  tags: Tag[];
+  warnings?: { kind: string; message: string }[];
 }
 
+type TaskPriority = 'low' | 'normal' | 'high';
+const PRIORITY_LABELS: Record<TaskPriority, string> = { high: '必做', normal: '普通', low: '选做' };
+interface Tag { id: number; name: string; color: string; }
+
+interface ChecklistItem { id: number; task_id: number; position: number; content: string; completed: boolean; }
+
+interface TimeSlot { start_time: string; end_time: string; minutes: number; }
+
+type CarryOverMode = 'move' | 'copy';
+interface CarryOverReport { from_date: string; to_date: string; mode: CarryOverMode; carried: TaskResponse[]; skipped: TaskResponse[]; }
+
+interface TaskDay { date: string; tasks: TaskResponse[]; total_tasks: number; completed_tasks: number; planned_minutes: number; }
+
+// 所在周的周一至周日 (YYYY-MM-DD)
+const weekRange = (date: string): [string, string] => {
+  const [y, m, d] = date.split('-').map(Number);
+  const monday = new Date(y, m - 1, d - (new Date(y, m - 1, d).getDay() + 6) % 7);
+  const fmt = (t: Date) => `${t.getFullYear()}-${String(t.getMonth() + 1).padStart(2, '0')}-${String(t.getDate()).padStart(2, '0')}`;
+  return [fmt(monday), fmt(new Date(monday.getFullYear(), monday.getMonth(), monday.getDate() + 6))];
+};
+
+type RepeatMode = 'none' | 'daily' | 'weekdays' | 'weekly' | 'interval';
+type RepeatEnd = 'never' | 'until' | 'count';
+type RecurrenceScope = 'this' | 'following';
+const WEEKDAY_LABELS = ['一', '二', '三', '四', '五', '六', '日'];
+
 interface AIConfigResponse { api_key_masked: string; model_name: string; is_configured: boolean; }
 interface AIContext { exam_date: string | null; subjects: string[]; incomplete_tasks: string[]; review_content: string | null; long_term_plans?: string[]; }
 interface TaskSuggestion { start_time: string; end_time: string; content: string; subject: string; }
 interface Subject { id: number; name: string; color: string; }
-interface StudyPreference { id: number; daily_hours: number; start_time: string; end_time: string; lunch_break_start: string; lunch_break_end: string; study_phase: string; study_phase_label: string; focus_subjects: string[]; weak_subjects: string[]; exam_date: string | null; days_until_exam: number | null; notes: string | null; }
+interface StudyPreference { id: number; daily_hours: number; start_time: string; end_time: string; lunch_break_start: string; lunch_break_end: string; study_phase: string; study_phase_label: string; focus_subjects: string[]; weak_subjects: string[]; exam_date: string | null; days_until_exam: number | null; notes: string | null; carry_over_mode: CarryOverMode | null; }
 
 // 长期计划类型
 interface LongTermPlan {
@@ -73,24 +109,86 @@ const STUDY_PHASES = [
 ];
 
 // 可拖拽任务项组件
-function SortableTaskItem({ task, getStatusDisplay, formatTime, handleToggleStatus, openEditDialog, setDeleteTask }: {
+// 任务清单：勾选、添加、删除、调整顺序，每次修改后用返回的任务替换列表中的任务
+function TaskChecklist({ task, sessionToken, onChange, onError }: {
+  task: TaskResponse; sessionToken: string | null;
+  onChange: (t: TaskResponse) => void; onError: (e: unknown) => void;
+}) {
+  const [newItem, setNewItem] = useState('');
+  const run = async (command: string, args: Record<string, unknown>) => {
+    if (!sessionToken) return;
+    try { onChange(await invoke<TaskResponse>(command, { sessionToken, ...args })); }
+    catch (e) { onError(e); }
+  };
+  const move = (index: number, offset: number) => {
+    const ids = task.checklist.map(i => i.id);
+    run('reorder_checklist_items', { taskId: task.id, itemIds: arrayMove(ids, index, index + offset) });
+  };
+  const handleAdd = async () => {
+    if (!newItem.trim()) return;
+    await run('add_checklist_item', { taskId: task.id, content: newItem }); setNewItem('');
+  };
+  return (
+    <div className="ml-20 mt-2 space-y-1">
+      {task.checklist.map((item, index) => (
+        <div key={item.id} className="flex items-center gap-2 text-sm group/item">
+          <input type="checkbox" checked={item.completed} className="accent-violet-500"
+            onChange={() => run('update_checklist_item', { itemId: item.id, input: { completed: !item.completed } })} />
+          <span className={`flex-1 ${item.completed ? 'line-through text-slate-500' : 'text-slate-300'}`}>{item.content}</span>
+          <div className="flex gap-1 opacity-0 group-hover/item:opacity-100 transition-all text-slate-500">
+            <button onClick={() => move(index, -1)} disabled={index === 0} className="hover:text-slate-300 disabled:opacity-30" title="上移">↑</button>
+            <button onClick={() => move(index, 1)} disabled={index === task.checklist.length - 1} className="hover:text-slate-300 disabled:opacity-30" title="下移">↓</button>
+            <button onClick={() => run('delete_checklist_item', { itemId: item.id })} className="hover:text-rose-400" title="删除">✕</button>
+          </div>
+        </div>
+      ))}
+      <div className="flex gap-2">
+        <input value={newItem} onChange={e => setNewItem(e.target.value)} onKeyDown={e => e.key === 'Enter' && handleAdd()} placeholder="添加清单项，如：习题 1-20"
+          className="flex-1 px-2 py-1 bg-slate-700/50 border border-slate-600 rounded text-sm text-white placeholder-slate-500 focus:outline-none focus:border-violet-500" />
+        <button onClick={handleAdd} disabled={!newItem.trim()} className="px-2 py-1 bg-slate-700/50 text-slate-300 rounded text-sm hover:bg-slate-700 disabled:opacity-40">添加</button>
+      </div>
+    </div>
+  );
+}
+
+function SortableTaskItem({ task, getStatusDisplay, formatTime, handleToggleStatus, onFail, openEditDialog, setDeleteTask, sessionToken, onTaskChange, onError }: {
   task: TaskResponse; getStatusDisplay: (s: string) => { icon: string; color: string; bg: string };
-  formatTime: (t: string) => string; handleToggleStatus: (id: number) => void;
+  formatTime: (t: string) => string; handleToggleStatus: (id: number) => void; onFail: (t: TaskResponse) => void;
   openEditDialog: (t: TaskResponse) => void; setDeleteTask: (t: TaskResponse) => void;
+  sessionToken: string | null; onTaskChange: (t: TaskResponse) => void; onError: (e: unknown) => void;
 }) {
   const { attributes, listeners, setNodeRef, transform, transition, isDragging } = useSortable({ id: task.id });
+  const [showChecklist, setShowChecklist] = useState(false);
   const style = { transform: CSS.Transform.toString(transform), transition, opacity: isDragging ? 0.5 : 1, zIndex: isDragging ? 1000 : 1 };
   const status = getStatusDisplay(task.status);
+  const doneItems = task.checklist.filter(i => i.completed).length;
   return (
-    <div ref={setNodeRef} style={style} className={`p-4 flex items-center gap-3 hover:bg-white/5 transition-colors group ${task.status === 'completed' ? 'opacity-60' : ''} ${isDragging ? 'bg-slate-700/50 rounded-lg' : ''}`}>
-      <div {...attributes} {...listeners} className="cursor-grab active:cursor-grabbing text-slate-500 hover:text-slate-300 px-1">⋮⋮</div>
-      <button onClick={() => handleToggleStatus(task.id)} className={`w-8 h-8 rounded-lg flex items-center justify-center text-sm font-bold ${status.bg} ${status.color} hover:scale-110 transition-all`}>{status.icon}</button>
-      <div className="w-24 text-xs font-mono text-slate-500 bg-slate-700/50 px-2 py-1 rounded">{formatTime(task.start_time)}-{formatTime(task.end_time)}</div>
-      {task.subject_name && <span className="px-2 py-0.5 text-xs rounded text-white" style={{ backgroundColor: task.subject_color || '#6B7280' }}>{task.subject_name}</span>}
-      <div className={`flex-1 text-sm ${task.status === 'completed' ? 'line-through text-slate-500' : 'text-slate-200'}`}>{task.content}</div>
-      {task.alarm_enabled && <span className="text-amber-400 text-sm">🔔</span>}
-      <button onClick={() => openEditDialog(task)} className="w-6 h-6 text-slate-500 hover:text-violet-400 opacity-0 group-hover:opacity-100 transition-all" title="编辑">✎</button>
-      <button onClick={() => setDeleteTask(task)} className="w-6 h-6 text-slate-500 hover:text-rose-400 opacity-0 group-hover:opacity-100 transition-all" title="删除">✕</button>
+    <div ref={setNodeRef} style={style} className={`p-4 hover:bg-white/5 transition-colors group ${task.status === 'completed' || task.status === 'rolled_over' ? 'opacity-60' : ''} ${isDragging ? 'bg-slate-700/50 rounded-lg' : ''}`}>
+      <div className="flex items-center gap-3">
+        <div {...attributes} {...listeners} className="cursor-grab active:cursor-grabbing text-slate-500 hover:text-slate-300 px-1">⋮⋮</div>
+        <button onClick={() => handleToggleStatus(task.id)} disabled={task.status === 'rolled_over'} className={`w-8 h-8 rounded-lg flex items-center justify-center text-sm font-bold ${status.bg} ${status.color} enabled:hover:scale-110 transition-all`}>{status.icon}</button>
+        <div className="w-24 text-xs font-mono text-slate-500 bg-slate-700/50 px-2 py-1 rounded">{formatTime(task.start_time)}-{formatTime(task.end_time)}</div>
+        {task.subject_name && <span className="px-2 py-0.5 text-xs rounded text-white" style={{ backgroundColor: task.subject_color || '#6B7280' }}>{task.subject_name}</span>}
+        {task.priority !== 'normal' && <span className={`px-1.5 py-0.5 text-xs rounded ${task.priority === 'high' ? 'bg-rose-500/20 text-rose-400' : 'bg-slate-700/50 text-slate-400'}`}>{PRIORITY_LABELS[task.priority]}</span>}
+        <div className={`flex-1 text-sm ${task.status === 'completed' ? 'line-through text-slate-500' : 'text-slate-200'}`}>
+          {task.content}{task.status === 'failed' && task.status_reason && <span className="ml-2 text-xs text-rose-400">（{task.status_reason}）</span>}
+        </div>
+        {task.tags.map(g => <span key={g.id} className="px-1.5 py-0.5 text-xs rounded border" style={{ borderColor: g.color, color: g.color }}>#{g.name}</span>)}
+        {task.checklist.length > 0 && (
+          <div className="flex items-center gap-2 w-24" title={`清单完成 ${task.progress}%`}>
+            <div className="flex-1 h-1.5 bg-slate-700 rounded-full overflow-hidden"><div className="h-full bg-violet-500 transition-all" style={{ width: `${task.progress}%` }} /></div>
+            <span className="text-xs text-slate-500">{doneItems}/{task.checklist.length}</span>
+          </div>
+        )}
+        {task.series_id && <span className="text-sky-400 text-sm" title="重复任务">🔁</span>}
+        {task.carried_from_id && <span className="text-amber-400 text-sm" title="由之前未完成的任务顺延而来">↪</span>}
+        {task.alarm_enabled && <span className="text-amber-400 text-sm">🔔</span>}
+        {task.status === 'pending' && <button onClick={() => onFail(task)} className="w-6 h-6 text-slate-500 hover:text-rose-400 opacity-0 group-hover:opacity-100 transition-all" title="标记为未完成">⊘</button>}
+        <button onClick={() => setShowChecklist(v => !v)} className={`w-6 h-6 transition-all ${showChecklist ? 'text-violet-400' : 'text-slate-500 hover:text-violet-400 opacity-0 group-hover:opacity-100'}`} title="清单">☑</button>
+        <button onClick={() => openEditDialog(task)} className="w-6 h-6 text-slate-500 hover:text-violet-400 opacity-0 group-hover:opacity-100 transition-all" title="编辑">✎</button>
+        <button onClick={() => setDeleteTask(task)} className="w-6 h-6 text-slate-500 hover:text-rose-400 opacity-0 group-hover:opacity-100 transition-all" title="删除">✕</button>
+      </div>
+      {showChecklist && <TaskChecklist task={task} sessionToken={sessionToken} onChange={onTaskChange} onError={onError} />}
     </div>
   );
 }
@@ -102,6 +200,7 @@ export default function StudyPlan() {
   const [activeTab, setActiveTab] = useState<TabType>('plan');
   const [selectedDate, setSelectedDate] = useState(() => new Date().toISOString().split('T')[0]);
   const [tasks, setTasks] = useState<TaskResponse[]>([]);
+  const [weekDays, setWeekDays] = useState<TaskDay[]>([]);
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   
@@ -115,8 +214,26 @@ export default function StudyPlan() {
   const [taskAlarmEnabled, setTaskAlarmEnabled] = useState(false);
   const [taskAlarmTime, setTaskAlarmTime] = useState('07:55');
   const [isSavingTask, setIsSavingTask] = useState(false);
+  const [taskPriority, setTaskPriority] = useState<TaskPriority>('normal');
+  const [taskTagIds, setTaskTagIds] = useState<number[]>([]);
+  const [newTagName, setNewTagName] = useState('');
   const [deleteTask, setDeleteTask] = useState<TaskResponse | null>(null);
+  const [failingTask, setFailingTask] = useState<TaskResponse | null>(null);
+  const [taskRepeat, setTaskRepeat] = useState<RepeatMode>('none');
+  const [taskRepeatInterval, setTaskRepeatInterval] = useState(2);
+  const [taskRepeatDays, setTaskRepeatDays] = useState<number[]>([]);
+  const [taskRepeatEnd, setTaskRepeatEnd] = useState<RepeatEnd>('never');
+  const [taskRepeatUntil, setTaskRepeatUntil] = useState('');
+  const [taskRepeatCount, setTaskRepeatCount] = useState(10);
+  const [taskScope, setTaskScope] = useState<RecurrenceScope>('this');
+  const [freeSlots, setFreeSlots] = useState<TimeSlot[] | null>(null);
+  const [notice, setNotice] = useState<string | null>(null);
   const [isDeleting, setIsDeleting] = useState(false);
+
+  // 标签与筛选
+  const [tags, setTags] = useState<Tag[]>([]);
+  const [filterPriority, setFilterPriority] = useState<TaskPriority | ''>('');
+  const [filterTagId, setFilterTagId] = useState<number | null>(null);
   
   // AI 相关
   const [aiConfig, setAiConfig] = useState<AIConfigResponse | null>(null);
@@ -165,41 +282,59 @@ export default function StudyPlan() {
   const [prefWeakSubjects, setPrefWeakSubjects] = useState<string[]>([]);
   const [prefExamDate, setPrefExamDate] = useState('');
   const [prefNotes, setPrefNotes] = useState('');
+  const [prefCarryOver, setPrefCarryOver] = useState<CarryOverMode | ''>('');
   const [isSavingPref, setIsSavingPref] = useState(false);
 
+  // 周视图的优先级/标签筛选条件
+  const taskFilter = () => ({ priority: filterPriority || null, tag_id: filterTagId });
+  const matchesFilter = (t: TaskResponse) =>
+    (!filterPriority || t.priority === filterPriority) && (filterTagId === null || t.tags.some(g => g.id === filterTagId));
+
   const loadTasks = async () => {
     if (!sessionToken) return;
     setIsLoading(true);
-    try { const result = await invoke<TaskResponse[]>('get_tasks_by_date', { sessionToken, date: selectedDate }); setTasks(result); }
-    catch (e) { setError(e as string); }
+    try {
+      const [startDate, endDate] = weekRange(selectedDate);
+      const [result, week] = await Promise.all([
+        invoke<TaskResponse[]>('get_tasks_by_date', { sessionToken, date: selectedDate }),
+        invoke<TaskDay[]>('get_tasks_in_range', { sessionToken, startDate, endDate, filter: taskFilter() }),
+      ]);
+      setTasks(result); setWeekDays(week);
+    }
+    catch (e) { setError(errorMessage(e)); }
     finally { setIsLoading(false); }
   };
 
   const loadAIData = async () => {
     if (!sessionToken) return;
     try {
-      const [config, subjectList, pref] = await Promise.all([
+      const [config, subjectList, pref, tagList] = await Promise.all([
         invoke<AIConfigResponse>('get_ai_config', { sessionToken }),
         invoke<Subject[]>('get_subjects', { sessionToken }),
         invoke<StudyPreference>('get_study_preference', { sessionToken }),
+        invoke<Tag[]>('get_tags', { sessionToken }),
       ]);
-      setAiConfig(config); setSubjects(subjectList); setModelName(config.model_name); setPreference(pref);
+      setAiConfig(config); setTags(tagList); setSubjects(subjectList); setModelName(config.model_name); setPreference(pref);
       setSelectedAISubjects(subjectList.map(s => s.name));
       if (pref.id > 0) {
         setPrefDailyHours(pref.daily_hours); setPrefStartTime(pref.start_time); setPrefEndTime(pref.end_time);
         setPrefLunchStart(pref.lunch_break_start); setPrefLunchEnd(pref.lunch_break_end); setPrefPhase(pref.study_phase);
         setPrefFocusSubjects(pref.focus_subjects); setPrefWeakSubjects(pref.weak_subjects);
         setPrefExamDate(pref.exam_date || ''); setPrefNotes(pref.notes || '');
+        setPrefCarryOver(pref.carry_over_mode || '');
       }
     } catch (e) { console.error(e); }
   };
 
   useEffect(() => { loadTasks(); loadAIData(); }, [sessionToken, selectedDate]);
+  useEffect(() => { loadTasks(); }, [filterPriority, filterTagId]);
 
   // 打开创建对话框
   const openCreateDialog = () => {
     setEditingTask(null); setTaskSubjectId(null); setTaskStartTime('08:00'); setTaskEndTime('09:00');
-    setTaskContent(''); setTaskAlarmEnabled(false); setTaskAlarmTime('07:55'); setShowTaskDialog(true);
+    setTaskContent(''); setTaskAlarmEnabled(false); setTaskAlarmTime('07:55'); setTaskPriority('normal'); setTaskTagIds([]); setNewTagName('');
+    setTaskRepeat('none'); setTaskRepeatInterval(2); setTaskRepeatDays([(new Date(selectedDate).getDay() + 6) % 7 + 1]);
+    setTaskRepeatEnd('never'); setTaskRepeatUntil(''); setTaskRepeatCount(10); setFreeSlots(null); setShowTaskDialog(true);
   };
 
   // 打开编辑对话框
@@ -207,38 +342,150 @@ export default function StudyPlan() {
     setEditingTask(task); setTaskSubjectId(task.subject_id);
     setTaskStartTime(task.start_time.substring(0, 5)); setTaskEndTime(task.end_time.substring(0, 5));
     setTaskContent(task.content); setTaskAlarmEnabled(task.alarm_enabled);
-    setTaskAlarmTime(task.alarm_time ? task.alarm_time.substring(0, 5) : '07:55'); setShowTaskDialog(true);
+    setTaskPriority(task.priority); setTaskTagIds(task.tags.map(g => g.id)); setNewTagName('');
+    setTaskAlarmTime(task.alarm_time ? task.alarm_time.substring(0, 5) : '07:55'); setTaskScope('this'); setFreeSlots(null); setShowTaskDialog(true);
+  };
+
+  // 对话框中时间段的时长 (分钟)
+  const taskDuration = () => {
+    const toMinutes = (t: string) => Number(t.substring(0, 2)) * 60 + Number(t.substring(3, 5));
+    return toMinutes(taskEndTime) - toMinutes(taskStartTime);
   };
 
+  // 查找当天能放下当前时长的空闲时段
+  const handleFindFreeSlots = async () => {
+    if (!sessionToken) return;
+    try {
+      const durationMinutes = Math.max(taskDuration(), 15);
+      setFreeSlots(await invoke<TimeSlot[]>('find_free_slots', { sessionToken, date: selectedDate, durationMinutes }));
+    } catch (e) { setError(errorMessage(e)); }
+  };
+
+  // 把当天未完成的任务顺延到第二天的空闲时段
+  const handleCarryOver = async () => {
+    if (!sessionToken) return;
+    const [y, m, d] = selectedDate.split('-').map(Number);
+    const next = new Date(y, m - 1, d + 1);
+    const toDate = `${next.getFullYear()}-${String(next.getMonth() + 1).padStart(2, '0')}-${String(next.getDate()).padStart(2, '0')}`;
+    try {
+      const report = await invoke<CarryOverReport>('carry_over_tasks', { sessionToken, fromDate: selectedDate, toDate, mode: preference?.carry_over_mode || 'move' });
+      setNotice(report.carried.length === 0 && report.skipped.length === 0 ? '没有需要顺延的任务'
+        : `已顺延 ${report.carried.length} 个任务到 ${toDate}` + (report.skipped.length ? `，${report.skipped.length} 个任务没有足够的空闲时段` : ''));
+      await loadTasks();
+    } catch (e) { setError(errorMessage(e)); }
+  };
+
+  // 使用空闲时段的开始时间，保持当前时长
+  const applyFreeSlot = (slot: TimeSlot) => {
+    const duration = Math.min(Math.max(taskDuration(), 15), slot.minutes);
+    const [h, m] = slot.start_time.split(':').map(Number);
+    const end = h * 60 + m + duration;
+    setTaskStartTime(slot.start_time.substring(0, 5));
+    setTaskEndTime(`${String(Math.floor(end / 60)).padStart(2, '0')}:${String(end % 60).padStart(2, '0')}`);
+    setFreeSlots(null);
+  };
+
+  // 由对话框中的重复选项生成重复规则
+  const buildRecurrenceRule = () => ({
+    frequency: taskRepeat === 'daily' || taskRepeat === 'interval' ? 'daily' : 'weekly',
+    interval: taskRepeat === 'interval' ? taskRepeatInterval : 1,
+    weekdays: taskRepeat === 'weekdays' ? [1, 2, 3, 4, 5] : taskRepeat === 'weekly' ? taskRepeatDays : [],
+    until: taskRepeatEnd === 'until' && taskRepeatUntil ? taskRepeatUntil : null,
+    count: taskRepeatEnd === 'count' ? taskRepeatCount : null,
+  });
+
   // 保存任务（创建或更新）
   const handleSaveTask = async () => {
     if (!sessionToken || !taskContent.trim()) return;
     setIsSavingTask(true);
     try {
+      let saved: TaskResponse | null = null;
       if (editingTask) {
-        await invoke('update_task', {
+        saved = await invoke<TaskResponse>('update_task', {
           sessionToken, taskId: editingTask.id, subjectId: taskSubjectId,
           startTime: taskStartTime + ':00', endTime: taskEndTime + ':00',
           content: taskContent.trim(), alarmEnabled: taskAlarmEnabled,
           alarmTime: taskAlarmEnabled ? taskAlarmTime + ':00' : null,
+          scope: editingTask.series_id ? taskScope : null, priority: taskPriority,
+        });
+      } else if (taskRepeat !== 'none') {
+        await invoke('create_task_series', {
+          sessionToken,
+          input: {
+            subject_id: taskSubjectId, start_date: selectedDate,
+            start_time: taskStartTime + ':00', end_time: taskEndTime + ':00',
+            content: taskContent.trim(), alarm_enabled: taskAlarmEnabled,
+            alarm_time: taskAlarmEnabled ? taskAlarmTime + ':00' : null,
+            priority: taskPriority, rule: buildRecurrenceRule(),
+          },
         });
       } else {
-        await invoke('create_task', {
+        saved = await invoke<TaskResponse>('create_task', {
           sessionToken, subjectId: taskSubjectId, taskDate: selectedDate,
           startTime: taskStartTime + ':00', endTime: taskEndTime + ':00',
           content: taskContent.trim(), alarmEnabled: taskAlarmEnabled,
-          alarmTime: taskAlarmEnabled ? taskAlarmTime + ':00' : null,
+          alarmTime: taskAlarmEnabled ? taskAlarmTime + ':00' : null, priority: taskPriority,
         });
       }
+      // 标签有变化时再保存
+      const oldTagIds = editingTask ? editingTask.tags.map(g => g.id) : [];
+      if (saved && [...taskTagIds].sort().join() !== [...oldTagIds].sort().join()) {
+        await invoke('set_task_tags', { sessionToken, taskId: saved.id, tagIds: taskTagIds });
+      }
+      setNotice(saved?.warnings?.length ? saved.warnings.map(w => w.message).join('；') : null);
       setShowTaskDialog(false); await loadTasks();
-    } catch (e) { setError(e as string); }
+    } catch (e) { setError(conflictMessage(e)); }
     finally { setIsSavingTask(false); }
   };
 
+  // 在任务对话框中新建标签并选中
+  const handleCreateTag = async () => {
+    if (!sessionToken || !newTagName.trim()) return;
+    try {
+      const tag = await invoke<Tag>('create_tag', { sessionToken, name: newTagName.trim(), color: null });
+      setTags(list => [...list, tag].sort((a, b) => a.name.localeCompare(b.name)));
+      setTaskTagIds(ids => [...ids, tag.id]); setNewTagName('');
+    } catch (e) { setError(errorMessage(e)); }
+  };
+
+  const handleRenameTag = async (tag: Tag) => {
+    const name = window.prompt('新的标签名称', tag.name);
+    if (!sessionToken || !name || name.trim() === tag.name) return;
+    try {
+      const updated = await invoke<Tag>('update_tag', { sessionToken, tagId: tag.id, input: { name: name.trim() } });
+      setTags(list => list.map(g => (g.id === tag.id ? updated : g))); await loadTasks();
+    } catch (e) { setError(errorMessage(e)); }
+  };
+
+  const handleDeleteTag = async (tag: Tag) => {
+    if (!sessionToken || !window.confirm(`删除标签「${tag.name}」？任务上的该标签会一并移除`)) return;
+    try {
+      await invoke('delete_tag', { sessionToken, tagId: tag.id });
+      setTags(list => list.filter(g => g.id !== tag.id));
+      if (filterTagId === tag.id) setFilterTagId(null); else await loadTasks();
+    } catch (e) { setError(errorMessage(e)); }
+  };
+
+  // 时间冲突时列出冲突的任务
+  const conflictMessage = (e: unknown) => {
+    if (!isAppError(e) || e.code !== 'schedule_conflict' || !e.details) return errorMessage(e);
+    const ids = e.details.split(',').map(Number);
+    const names = tasks.filter(t => ids.includes(t.id)).map(t => `${formatTime(t.start_time)}-${formatTime(t.end_time)} ${t.content}`);
+    return names.length ? `${e.message}：${names.join('、')}` : e.message;
+  };
+
   const handleToggleStatus = async (taskId: number) => {
     if (!sessionToken) return;
     try { await invoke('toggle_task_status', { sessionToken, taskId }); await loadTasks(); }
-    catch (e) { setError(e as string); }
+    catch (e) { setError(errorMessage(e)); }
+  };
+
+  // 清单修改后替换当前任务，并刷新周视图的统计 (尚未生成的重复任务修改后 ID 会变化，需重新加载当天的任务)
+  const handleTaskChange = (task: TaskResponse) => {
+    if (tasks.some(t => t.id === task.id)) setTasks(items => items.map(t => (t.id === task.id ? task : t)));
+    else loadTasks();
+    const [startDate, endDate] = weekRange(selectedDate);
+    invoke<TaskDay[]>('get_tasks_in_range', { sessionToken, startDate, endDate, filter: taskFilter() }).then(setWeekDays).catch(() => {});
   };
 
   // 拖拽排序
@@ -258,11 +505,16 @@ export default function StudyPlan() {
     }
   };
 
+  const openDeleteDialog = (task: TaskResponse) => { setTaskScope('this'); setDeleteTask(task); };
+
   const handleDelete = async () => {
     if (!sessionToken || !deleteTask) return;
     setIsDeleting(true);
-    try { await invoke('delete_task', { sessionToken, taskId: deleteTask.id }); setDeleteTask(null); await loadTasks(); }
-    catch (e) { setError(e as string); }
+    try {
+      await invoke('delete_task', { sessionToken, taskId: deleteTask.id, scope: deleteTask.series_id ? taskScope : null });
+      setDeleteTask(null); await loadTasks();
+    }
+    catch (e) { setError(errorMessage(e)); }
     finally { setIsDeleting(false); }
   };
 
@@ -282,13 +534,14 @@ export default function StudyPlan() {
       const context: AIContext = { 
         exam_date: prefExamDate || null, 
         subjects: selectedAISubjects,
-        incomplete_tasks: tasks.filter(t => t.status === 'pending').map(t => t.content), 
+        incomplete_tasks: tasks.filter(t => t.status === 'pending' || t.status === 'failed')
+          .map(t => (t.status_reason ? `${t.content}（未完成原因：${t.status_reason}）` : t.content)), 
         review_content: aiUserInput || null,
         long_term_plans: longTermGoals.length > 0 ? longTermGoals : undefined,
       };
       const result = await invoke<TaskSuggestion[]>('generate_ai_plan', { sessionToken, context, modelName: selectedModel });
       setSuggestions(result);
-    } catch (e) { setError(e as string); }
+    } catch (e) { setError(errorMessage(e)); }
     finally { setIsGenerating(false); }
   };
 
@@ -384,7 +637,7 @@ export default function StudyPlan() {
         content: suggestion.content, alarmEnabled: false, alarmTime: null,
       });
       setSuggestions(prev => prev.filter(s => s !== suggestion)); await loadTasks();
-    } catch (e) { setError(e as string); }
+    } catch (e) { setError(conflictMessage(e)); }
   };
 
   const handleImportAll = async () => { for (const s of suggestions) await handleImportTask(s); };
@@ -393,7 +646,7 @@ export default function StudyPlan() {
     if (!sessionToken || !apiKey) return;
     setIsSavingConfig(true);
     try { await invoke('save_ai_config', { sessionToken, apiKey, modelName, apiEndpoint: null }); await loadAIData(); setShowAIConfigDialog(false); setApiKey(''); }
-    catch (e) { setError(e as string); }
+    catch (e) { setError(errorMessage(e)); }
     finally { setIsSavingConfig(false); }
   };
 
@@ -405,9 +658,10 @@ export default function StudyPlan() {
         daily_hours: prefDailyHours, start_time: prefStartTime, end_time: prefEndTime,
         lunch_break_start: prefLunchStart, lunch_break_end: prefLunchEnd, study_phase: prefPhase,
         focus_subjects: prefFocusSubjects, weak_subjects: prefWeakSubjects, exam_date: prefExamDate || null, notes: prefNotes || null,
+        carry_over_mode: prefCarryOver || null,
       }});
       await loadAIData();
-    } catch (e) { setError(e as string); }
+    } catch (e) { setError(errorMessage(e)); }
     finally { setIsSavingPref(false); }
   };
 
@@ -448,7 +702,7 @@ export default function StudyPlan() {
     if (!sessionToken || !inputContent.trim()) return;
     setIsChecking(true); setError(null); setHasChecked(false);
     try { const result = await invoke<TaskResponse[]>('check_content', { sessionToken, date: selectedDate, content: inputContent.trim() }); setMatchedTasks(result); setHasChecked(true); await loadTasks(); }
-    catch (e) { setError(e as string); }
+    catch (e) { setError(errorMessage(e)); }
     finally { setIsChecking(false); }
   };
 
@@ -460,13 +714,16 @@ export default function StudyPlan() {
     switch (status) {
       case 'completed': return { icon: '✓', color: 'text-emerald-400', bg: 'bg-emerald-500/20' };
       case 'failed': return { icon: '✗', color: 'text-rose-400', bg: 'bg-rose-500/20' };
+      case 'rolled_over': return { icon: '⏭', color: 'text-amber-400', bg: 'bg-amber-500/20' };
       default: return { icon: '○', color: 'text-slate-400', bg: 'bg-slate-500/20' };
     }
   };
 
   const formatTime = (time: string) => time.substring(0, 5);
-  const completedCount = tasks.filter(t => t.status === 'completed').length;
-  const progressPercent = tasks.length > 0 ? (completedCount / tasks.length) * 100 : 0;
+  // 已顺延的任务不计入当天进度
+  const activeTasks = tasks.filter(t => t.status !== 'rolled_over');
+  const completedCount = activeTasks.filter(t => t.status === 'completed').length;
+  const progressPercent = activeTasks.length > 0 ? (completedCount / activeTasks.length) * 100 : 0;
 
 
   return (
@@ -484,7 +741,7 @@ export default function StudyPlan() {
           <span className={`${themeConfig.textSecondary} text-sm`}>{new Date(selectedDate).toLocaleDateString('zh-CN', { weekday: 'long', month: 'long', day: 'numeric' })}</span>
         </div>
         <div className="flex items-center gap-3">
-          <span className={`${themeConfig.textSecondary} text-sm`}>完成 <span className="text-violet-400 font-bold">{completedCount}</span>/{tasks.length}</span>
+          <span className={`${themeConfig.textSecondary} text-sm`}>完成 <span className="text-violet-400 font-bold">{completedCount}</span>/{activeTasks.length}</span>
           <div className={`w-24 h-2 ${themeConfig.bgSecondary} rounded-full overflow-hidden`}><div className={`h-full bg-gradient-to-r ${themeConfig.accent} transition-all`} style={{ width: `${progressPercent}%` }} /></div>
         </div>
       </div>
@@ -496,6 +753,7 @@ export default function StudyPlan() {
         ))}
       </div>
 
+      {notice && (<div className="mx-4 mb-4 p-3 bg-amber-500/20 border border-amber-500/30 rounded-lg text-amber-400 text-sm flex justify-between"><span>💡 {notice}</span><button onClick={() => setNotice(null)} className="hover:text-white">✕</button></div>)}
       {error && (<div className="mx-4 mb-4 p-3 bg-rose-500/20 border border-rose-500/30 rounded-lg text-rose-400 text-sm flex justify-between"><span>⚠️ {error}</span><button onClick={() => setError(null)} className="hover:text-white">✕</button></div>)}
 
       <div className="flex-1 overflow-auto p-4">
@@ -504,17 +762,24 @@ export default function StudyPlan() {
           <div className="lg:col-span-2 bg-slate-800/30 rounded-xl border border-white/5 overflow-hidden">
             <div className="p-4 border-b border-white/5 flex justify-between items-center">
               <h2 className="text-white font-bold">今日任务</h2>
+              <div className="flex gap-2">
+              <select value={filterPriority} onChange={e => setFilterPriority(e.target.value as TaskPriority | '')} className="px-2 py-1.5 bg-slate-700/50 border border-white/10 rounded-lg text-slate-300 text-sm"><option value="">全部优先级</option>{(['high', 'normal', 'low'] as TaskPriority[]).map(p => <option key={p} value={p}>{PRIORITY_LABELS[p]}</option>)}</select>
+              {tags.length > 0 && <select value={filterTagId ?? ''} onChange={e => setFilterTagId(e.target.value ? Number(e.target.value) : null)} className="px-2 py-1.5 bg-slate-700/50 border border-white/10 rounded-lg text-slate-300 text-sm"><option value="">全部标签</option>{tags.map(g => <option key={g.id} value={g.id}>#{g.name}</option>)}</select>}
+              <button onClick={handleCarryOver} disabled={!tasks.some(t => t.status === 'pending')} className="px-3 py-1.5 bg-slate-700/50 text-slate-300 rounded-lg text-sm hover:bg-slate-700 disabled:opacity-40" title="把未完成的任务顺延到第二天">⏭ 顺延</button>
               <button onClick={openCreateDialog} className="px-3 py-1.5 bg-gradient-to-r from-violet-500 to-purple-500 text-white rounded-lg text-sm font-medium hover:shadow-lg transition-all">+ 添加</button>
+              </div>
             </div>
             
             {isLoading ? (<div className="p-8 text-center"><div className="w-8 h-8 border-3 border-violet-400/30 border-t-violet-400 rounded-full animate-spin mx-auto mb-3"></div><p className="text-slate-500">加载中...</p></div>)
             : tasks.length === 0 ? (<div className="p-8 text-center"><div className="text-4xl mb-3">📝</div><p className="text-slate-500">今天还没有任务</p></div>)
+            : !tasks.some(matchesFilter) ? (<div className="p-8 text-center"><p className="text-slate-500">没有符合筛选条件的任务</p></div>)
             : (<DndContext sensors={sensors} collisionDetection={closestCenter} onDragEnd={handleDragEnd}>
-                <SortableContext items={tasks.map(t => t.id)} strategy={verticalListSortingStrategy}>
+                <SortableContext items={tasks.filter(matchesFilter).map(t => t.id)} strategy={verticalListSortingStrategy}>
                   <div className="divide-y divide-white/5">
-                    {tasks.map((task) => (
+                    {tasks.filter(matchesFilter).map((task) => (
                       <SortableTaskItem key={task.id} task={task} getStatusDisplay={getStatusDisplay} formatTime={formatTime}
-                        handleToggleStatus={handleToggleStatus} openEditDialog={openEditDialog} setDeleteTask={setDeleteTask} />
+                        handleToggleStatus={handleToggleStatus} onFail={setFailingTask} openEditDialog={openEditDialog} setDeleteTask={openDeleteDialog}
+                        sessionToken={sessionToken} onTaskChange={handleTaskChange} onError={e => setError(errorMessage(e))} />
                     ))}
                   </div>
                 </SortableContext>
@@ -625,10 +890,24 @@ export default function StudyPlan() {
                   <button onClick={() => setActiveTab('longterm')} className="w-full py-2.5 bg-slate-700/50 text-slate-300 rounded-lg hover:bg-slate-700">📅 长期计划</button>
                 </div>
                 <div className="mt-4 pt-4 border-t border-white/5 grid grid-cols-3 gap-2">
-                  <div className="text-center p-2 bg-slate-700/30 rounded-lg"><div className="text-lg font-bold text-white">{tasks.length}</div><div className="text-xs text-slate-500">总任务</div></div>
+                  <div className="text-center p-2 bg-slate-700/30 rounded-lg"><div className="text-lg font-bold text-white">{activeTasks.length}</div><div className="text-xs text-slate-500">总任务</div></div>
                   <div className="text-center p-2 bg-emerald-500/10 rounded-lg"><div className="text-lg font-bold text-emerald-400">{completedCount}</div><div className="text-xs text-emerald-500">已完成</div></div>
-                  <div className="text-center p-2 bg-amber-500/10 rounded-lg"><div className="text-lg font-bold text-amber-400">{tasks.length - completedCount}</div><div className="text-xs text-amber-500">待完成</div></div>
+                  <div className="text-center p-2 bg-amber-500/10 rounded-lg"><div className="text-lg font-bold text-amber-400">{activeTasks.length - completedCount}</div><div className="text-xs text-amber-500">待完成</div></div>
                 </div>
+                {weekDays.length > 0 && (
+                  <div className="mt-4 pt-4 border-t border-white/5">
+                    <h4 className="text-sm text-slate-400 mb-2">📆 本周</h4>
+                    <div className="space-y-1">
+                      {weekDays.map((day, i) => (
+                        <button key={day.date} onClick={() => setSelectedDate(day.date)}
+                          className={`w-full flex items-center justify-between px-2 py-1.5 rounded-lg text-xs ${day.date === selectedDate ? 'bg-violet-500/20 text-white' : 'text-slate-400 hover:bg-slate-700/50'}`}>
+                          <span>周{WEEKDAY_LABELS[i]} {day.date.substring(5)}</span>
+                          <span>{day.total_tasks > 0 ? `${day.completed_tasks}/${day.total_tasks} · ${(day.planned_minutes / 60).toFixed(1)}h` : '-'}</span>
+                        </button>
+                      ))}
+                    </div>
+                  </div>
+                )}
               </div>
             )}
 
@@ -701,10 +980,26 @@ export default function StudyPlan() {
                   </div>
                   <div><label className="block text-sm text-slate-400 mb-2">重点科目</label><div className="flex flex-wrap gap-2">{subjects.map(s => (<button key={s.id} onClick={() => toggleSubject(s.name, prefFocusSubjects, setPrefFocusSubjects)} className={`px-2 py-1 rounded text-xs transition-all ${prefFocusSubjects.includes(s.name) ? 'text-white' : 'bg-slate-700/50 text-slate-400'}`} style={prefFocusSubjects.includes(s.name) ? { backgroundColor: s.color } : {}}>{prefFocusSubjects.includes(s.name) && '✓ '}{s.name}</button>))}</div></div>
                   <div><label className="block text-sm text-slate-400 mb-2">薄弱科目</label><div className="flex flex-wrap gap-2">{subjects.map(s => (<button key={s.id} onClick={() => toggleSubject(s.name, prefWeakSubjects, setPrefWeakSubjects)} className={`px-2 py-1 rounded text-xs transition-all ${prefWeakSubjects.includes(s.name) ? 'bg-rose-500 text-white' : 'bg-slate-700/50 text-slate-400'}`}>{prefWeakSubjects.includes(s.name) && '✓ '}{s.name}</button>))}</div></div>
+                  <div><label className="block text-sm text-slate-400 mb-2">未完成任务</label><select value={prefCarryOver} onChange={(e) => setPrefCarryOver(e.target.value as CarryOverMode | '')} className="w-full px-3 py-2 bg-slate-700/50 border border-white/10 rounded-lg text-white text-sm"><option value="">不自动顺延</option><option value="move">每天自动移动到当天（原任务标记为已顺延）</option><option value="copy">每天自动复制到当天（原任务标记为未完成）</option></select></div>
                   <div><label className="block text-sm text-slate-400 mb-2">备注</label><textarea value={prefNotes} onChange={(e) => setPrefNotes(e.target.value)} className="w-full px-3 py-2 bg-slate-700/50 border border-white/10 rounded-lg text-white text-sm resize-none" rows={2} placeholder="其他说明..." /></div>
                   <button onClick={handleSavePreference} disabled={isSavingPref} className="w-full py-2.5 bg-gradient-to-r from-violet-500 to-purple-500 text-white rounded-lg font-medium disabled:opacity-50">{isSavingPref ? '保存中...' : '💾 保存设置'}</button>
                   <button onClick={() => setShowAIConfigDialog(true)} className="w-full py-2.5 bg-slate-700/50 text-slate-300 rounded-lg hover:bg-slate-700">🔑 配置 AI API</button>
                   
+                  {/* 任务标签 */}
+                  <div className="mt-6 pt-4 border-t border-white/10">
+                    <h4 className="text-white font-bold mb-3">🏷️ 任务标签</h4>
+                    {tags.length === 0 ? <p className="text-xs text-slate-500">还没有标签，可以在添加任务时创建</p> : (
+                      <div className="space-y-1">{tags.map(g => (
+                        <div key={g.id} className="flex items-center gap-2 px-2 py-1.5 rounded-lg hover:bg-slate-700/30 group">
+                          <span className="w-3 h-3 rounded-full" style={{ backgroundColor: g.color }} />
+                          <span className="flex-1 text-sm text-slate-300">{g.name}</span>
+                          <button onClick={() => handleRenameTag(g)} className="text-slate-500 hover:text-violet-400 opacity-0 group-hover:opacity-100" title="重命名">✎</button>
+                          <button onClick={() => handleDeleteTag(g)} className="text-slate-500 hover:text-rose-400 opacity-0 group-hover:opacity-100" title="删除">✕</button>
+                        </div>
+                      ))}</div>
+                    )}
+                  </div>
+
                   {/* 学习计划模板 */}
                   <div className="mt-6 pt-4 border-t border-white/10">
                     <h4 className="text-white font-bold mb-3">📋 学习计划模板</h4>
@@ -738,16 +1033,84 @@ export default function StudyPlan() {
             <h3 className="text-xl font-bold text-white mb-4">{editingTask ? '✎ 编辑任务' : '✨ 添加任务'}</h3>
             <div className="space-y-4">
               <div><label className="block text-sm text-slate-400 mb-2">科目</label><SubjectSelect value={taskSubjectId} onChange={setTaskSubjectId} placeholder="选择科目（可选）" /></div>
+              <div><label className="block text-sm text-slate-400 mb-2">优先级</label>
+                <div className="flex gap-2">{(['high', 'normal', 'low'] as TaskPriority[]).map(p => (
+                  <button key={p} onClick={() => setTaskPriority(p)} className={`flex-1 py-1.5 rounded-lg text-sm ${taskPriority === p ? (p === 'high' ? 'bg-rose-500 text-white' : 'bg-violet-500 text-white') : 'bg-slate-700/50 text-slate-400'}`}>{PRIORITY_LABELS[p]}</button>
+                ))}</div>
+              </div>
+              {(editingTask || taskRepeat === 'none') && (
+                <div><label className="block text-sm text-slate-400 mb-2">标签</label>
+                  <div className="flex flex-wrap gap-2">
+                    {tags.map(g => (
+                      <button key={g.id} onClick={() => setTaskTagIds(ids => ids.includes(g.id) ? ids.filter(id => id !== g.id) : [...ids, g.id])} className={`px-2 py-1 rounded text-xs border ${taskTagIds.includes(g.id) ? 'text-white' : 'text-slate-400 border-white/10'}`} style={taskTagIds.includes(g.id) ? { backgroundColor: g.color, borderColor: g.color } : {}}>#{g.name}</button>
+                    ))}
+                    <input value={newTagName} onChange={e => setNewTagName(e.target.value)} onKeyDown={e => e.key === 'Enter' && handleCreateTag()} maxLength={20} placeholder="新标签，回车添加" className="flex-1 min-w-[8rem] px-2 py-1 bg-slate-700/50 border border-white/10 rounded text-white text-xs" />
+                  </div>
+                </div>
+              )}
               <div className="grid grid-cols-2 gap-3">
                 <div><label className="block text-sm text-slate-400 mb-2">开始</label><input type="time" value={taskStartTime} onChange={(e) => setTaskStartTime(e.target.value)} className="w-full px-3 py-2 bg-slate-700/50 border border-white/10 rounded-lg text-white" /></div>
                 <div><label className="block text-sm text-slate-400 mb-2">结束</label><input type="time" value={taskEndTime} onChange={(e) => setTaskEndTime(e.target.value)} className="w-full px-3 py-2 bg-slate-700/50 border border-white/10 rounded-lg text-white" /></div>
               </div>
+              <div>
+                <button onClick={handleFindFreeSlots} className="text-xs text-violet-400 hover:text-violet-300">🔍 查找空闲时段</button>
+                {freeSlots && (
+                  <div className="mt-2 flex flex-wrap gap-2">
+                    {freeSlots.length === 0 ? <span className="text-xs text-slate-500">当天没有足够长的空闲时段</span> : freeSlots.map(slot => (
+                      <button key={slot.start_time} onClick={() => applyFreeSlot(slot)} className="px-2 py-1 text-xs bg-slate-700/50 text-slate-300 rounded-lg hover:bg-violet-500/30">
+                        {slot.start_time.substring(0, 5)}-{slot.end_time.substring(0, 5)}
+                      </button>
+                    ))}
+                  </div>
+                )}
+              </div>
               <div><label className="block text-sm text-slate-400 mb-2">内容</label><input type="text" value={taskContent} onChange={(e) => setTaskContent(e.target.value)} className="w-full px-3 py-2 bg-slate-700/50 border border-white/10 rounded-lg text-white" placeholder="如：复习高数第三章" /></div>
               <div className="flex items-center gap-3">
                 <input type="checkbox" id="alarm" checked={taskAlarmEnabled} onChange={(e) => setTaskAlarmEnabled(e.target.checked)} className="w-4 h-4 text-violet-500 rounded" />
                 <label htmlFor="alarm" className="text-sm text-slate-400">设置闹钟</label>
                 {taskAlarmEnabled && <input type="time" value={taskAlarmTime} onChange={(e) => setTaskAlarmTime(e.target.value)} className="px-2 py-1 bg-slate-700/50 border border-white/10 rounded-lg text-white text-sm" />}
               </div>
+              {!editingTask && (
+                <div className="space-y-3">
+                  <div><label className="block text-sm text-slate-400 mb-2">重复</label>
+                    <select value={taskRepeat} onChange={(e) => setTaskRepeat(e.target.value as RepeatMode)} className="w-full px-3 py-2 bg-slate-700/50 border border-white/10 rounded-lg text-white">
+                      <option value="none">不重复</option>
+                      <option value="daily">每天</option>
+                      <option value="weekdays">每个工作日</option>
+                      <option value="weekly">每周指定日期</option>
+                      <option value="interval">每隔 N 天</option>
+                    </select>
+                  </div>
+                  {taskRepeat === 'interval' && (
+                    <div className="flex items-center gap-2 text-sm text-slate-400">每隔<input type="number" min={1} value={taskRepeatInterval} onChange={(e) => setTaskRepeatInterval(Math.max(1, Number(e.target.value)))} className="w-20 px-2 py-1 bg-slate-700/50 border border-white/10 rounded-lg text-white" />天</div>
+                  )}
+                  {taskRepeat === 'weekly' && (
+                    <div className="flex gap-1">
+                      {WEEKDAY_LABELS.map((label, i) => (
+                        <button key={label} onClick={() => setTaskRepeatDays(days => days.includes(i + 1) ? days.filter(d => d !== i + 1) : [...days, i + 1])}
+                          className={`w-8 h-8 rounded-lg text-sm ${taskRepeatDays.includes(i + 1) ? 'bg-violet-500 text-white' : 'bg-slate-700/50 text-slate-400'}`}>{label}</button>
+                      ))}
+                    </div>
+                  )}
+                  {taskRepeat !== 'none' && (
+                    <div className="flex items-center gap-2 text-sm text-slate-400">
+                      <select value={taskRepeatEnd} onChange={(e) => setTaskRepeatEnd(e.target.value as RepeatEnd)} className="px-2 py-1 bg-slate-700/50 border border-white/10 rounded-lg text-white">
+                        <option value="never">一直重复</option>
+                        <option value="until">截止日期</option>
+                        <option value="count">重复次数</option>
+                      </select>
+                      {taskRepeatEnd === 'until' && <input type="date" value={taskRepeatUntil} min={selectedDate} onChange={(e) => setTaskRepeatUntil(e.target.value)} className="px-2 py-1 bg-slate-700/50 border border-white/10 rounded-lg text-white" />}
+                      {taskRepeatEnd === 'count' && <><input type="number" min={1} value={taskRepeatCount} onChange={(e) => setTaskRepeatCount(Math.max(1, Number(e.target.value)))} className="w-20 px-2 py-1 bg-slate-700/50 border border-white/10 rounded-lg text-white" />次</>}
+                    </div>
+                  )}
+                </div>
+              )}
+              {editingTask?.series_id && (
+              <div className="flex gap-4 text-sm text-slate-300">
+                <label className="flex items-center gap-2"><input type="radio" checked={taskScope === 'this'} onChange={() => setTaskScope('this')} />仅修改这一次</label>
+                <label className="flex items-center gap-2"><input type="radio" checked={taskScope === 'following'} onChange={() => setTaskScope('following')} />修改这一次及之后</label>
+              </div>
+              )}
             </div>
             <div className="mt-6 flex justify-end gap-3">
               <button onClick={() => setShowTaskDialog(false)} className="px-4 py-2 text-slate-400 hover:text-white">取消</button>
@@ -758,11 +1121,24 @@ export default function StudyPlan() {
       )}
 
       {/* 删除确认 */}
+      {failingTask && (
+        <FailureReasonDialog<TaskResponse> task={failingTask} onClose={() => setFailingTask(null)}
+          onDone={task => { setFailingTask(null); handleTaskChange(task); }} />
+      )}
+
       {deleteTask && (
         <div className="fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50">
           <div className="bg-slate-800 rounded-2xl shadow-2xl w-full max-w-md p-6 m-4 border border-white/10">
             <h3 className="text-xl font-bold text-rose-400 mb-4">⚠️ 确认删除</h3>
             <p className="text-slate-300">确定删除 <span className="text-white font-medium">"{deleteTask.content}"</span>？</p>
+            {deleteTask.series_id && (
+              <div className="mt-4">
+              <div className="flex gap-4 text-sm text-slate-300">
+                <label className="flex items-center gap-2"><input type="radio" checked={taskScope === 'this'} onChange={() => setTaskScope('this')} />仅删除这一次</label>
+                <label className="flex items-center gap-2"><input type="radio" checked={taskScope === 'following'} onChange={() => setTaskScope('following')} />删除这一次及之后</label>
+              </div>
+              </div>
+            )}
             <div className="mt-6 flex justify-end gap-3">
               <button onClick={() => setDeleteTask(null)} className="px-4 py-2 text-slate-400 hover:text-white">取消</button>
               <button onClick={handleDelete} disabled={isDeleting} className="px-4 py-2 bg-rose-500 text-white rounded-lg disabled:opacity-50">{isDeleting ? '删除中...' : '删除'}</button>
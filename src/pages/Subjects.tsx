@@ -3,6 +3,7 @@ import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuthStore } from '../stores/authStore';
 import { useThemeStore, THEMES } from '../stores/themeStore';
+import { errorMessage } from '../utils/error';
 
 interface Subject { id: number; name: string; color: string; is_default: boolean; }
 
@@ -30,7 +31,7 @@ export default function Subjects() {
     if (!sessionToken) return;
     setIsLoading(true); setError(null);
     try { const result = await invoke<Subject[]>('get_subjects', { sessionToken }); setSubjects(result); }
-    catch (e) { setError(e as string); }
+    catch (e) { setError(errorMessage(e)); }
     finally { setIsLoading(false); }
   };
 
@@ -42,7 +43,7 @@ export default function Subjects() {
     try {
       await invoke('create_subject', { sessionToken, name: newName.trim(), color: newColor });
       setShowCreateDialog(false); setNewName(''); setNewColor(COLORS[0]); await loadSubjects();
-    } catch (e) { setError(e as string); }
+    } catch (e) { setError(errorMessage(e)); }
     finally { setIsCreating(false); }
   };
 
@@ -50,7 +51,7 @@ export default function Subjects() {
     if (!sessionToken || !deleteSubject) return;
     setIsDeleting(true);
     try { await invoke('delete_subject', { sessionToken, subjectId: deleteSubject.id }); setDeleteSubject(null); await loadSubjects(); }
-    catch (e) { setError(e as string); }
+    catch (e) { setError(errorMessage(e)); }
     finally { setIsDeleting(false); }
   };
 
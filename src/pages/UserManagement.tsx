@@ -3,8 +3,37 @@ import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuthStore } from '../stores/authStore';
 import { useThemeStore, THEMES } from '../stores/themeStore';
+import { errorMessage } from '../utils/error';
 
-interface User { id: number; username: string; display_name: string; role: string; role_label: string; created_at: string; }
+interface User { id: number; username: string; display_name: string; role: string; role_label: string; is_active: boolean; deactivated_at: string | null; created_at: string; }
+interface UserArchive { user_id: number; username: string; path: string; tasks: number; pomodoros: number; reviews: number; countdowns: number; }
+interface InviteCode { id: number; code: string; role: string; role_label: string; max_uses: number; used_count: number; expires_at: string; created_at: string; revoked_at: string | null; status: 'active' | 'expired' | 'exhausted' | 'revoked'; }
+interface LockedAccount { username: string; failed_count: number; last_failed_at: string; locked_until: string | null; }
+interface AuditEvent { id: number; actor_id: number | null; actor_username: string | null; action: string; target_user_id: number | null; target_username: string | null; metadata: Record<string, unknown> | null; created_at: string; }
+interface AuditLogPage { events: AuditEvent[]; total: number; page: number; page_size: number; }
+interface UserActivity { user_id: number; username: string; display_name: string; role_label: string; is_active: boolean; last_login_at: string | null; study_minutes: number; completed_pomodoros: number; total_tasks: number; completed_tasks: number; completion_rate: number; last_review_date: string | null; days_since_review: number | null; ai_configured: boolean; }
+interface UserImportRow { line: number; username: string; display_name: string; role: string; errors: string[]; }
+interface UserImportReport { dry_run: boolean; total: number; valid: number; created: number; rows: UserImportRow[]; credentials: { username: string; initial_password: string }[]; credentials_csv: string | null; }
+
+const AUDIT_ACTIONS: Record<string, string> = {
+  'session.login': '登录', 'session.logout': '退出登录', 'session.revoke': '注销会话', 'session.revoke_others': '注销其他会话',
+  'password.change': '修改密码', 'password.reset': '重置密码', 'account.unlock': '解除锁定',
+  'user.create': '创建用户', 'user.role_change': '修改角色', 'user.deactivate': '停用用户', 'user.reactivate': '启用用户', 'user.delete': '删除用户',
+  'two_factor.enable': '启用两步验证', 'two_factor.disable': '关闭两步验证', 'two_factor.reset': '重置两步验证', 'two_factor.recovery_codes': '重新生成恢复码',
+  'ai_config.save': '保存 AI 配置', 'audit.purge': '清理审计记录',
+};
+const AUDIT_PAGE_SIZE = 20;
+
+// 下载 CSV 文件 (带 BOM，便于 Excel 识别编码)
+const downloadCsv = (csv: string, filename: string) => {
+  const blob = new Blob(['\ufeff' + csv], { type: 'text/csv;charset=utf-8' });
+  const url = URL.createObjectURL(blob);
+  const a = document.createElement('a');
+  a.href = url;
+  a.download = filename;
+  a.click();
+  URL.revokeObjectURL(url);
+};
 
 export default function UserManagement() {
   const { sessionToken, user: currentUser } = useAuthStore();
@@ -20,36 +49,151 @@ export default function UserManagement() {
   const [newRole, setNewRole] = useState('user');
   const [isCreating, setIsCreating] = useState(false);
   const [deleteUser, setDeleteUser] = useState<User | null>(null);
+  const [lastArchive, setLastArchive] = useState<UserArchive | null>(null);
   const [isDeleting, setIsDeleting] = useState(false);
   const [resetUser, setResetUser] = useState<User | null>(null);
+  const [resetTwoFactorUser, setResetTwoFactorUser] = useState<User | null>(null);
   const [resetPassword, setResetPassword] = useState('');
   const [isResetting, setIsResetting] = useState(false);
+  const [lockedAccounts, setLockedAccounts] = useState<LockedAccount[]>([]);
+  const [studentsMentor, setStudentsMentor] = useState<User | null>(null);
+  const [mentorStudents, setMentorStudents] = useState<User[]>([]);
+  const [newStudentId, setNewStudentId] = useState('');
+  const [inviteCodes, setInviteCodes] = useState<InviteCode[]>([]);
+  const [inviteRole, setInviteRole] = useState('user');
+  const [inviteMaxUses, setInviteMaxUses] = useState(1);
+  const [inviteDays, setInviteDays] = useState(7);
+  const [inviteUsers, setInviteUsers] = useState<{ invite: InviteCode; users: User[] } | null>(null);
+  const [auditLog, setAuditLog] = useState<AuditLogPage | null>(null);
+  const [auditAction, setAuditAction] = useState('');
+  const [auditUserId, setAuditUserId] = useState('');
+  const [auditSince, setAuditSince] = useState('');
+  const [auditUntil, setAuditUntil] = useState('');
+  const [activity, setActivity] = useState<UserActivity[]>([]);
+  const [activityStart, setActivityStart] = useState(() => { const d = new Date(); d.setDate(d.getDate() - 7); return d.toISOString().split('T')[0]; });
+  const [activityEnd, setActivityEnd] = useState(() => new Date().toISOString().split('T')[0]);
+  const [showImportDialog, setShowImportDialog] = useState(false);
+  const [importCsv, setImportCsv] = useState('');
+  const [importReport, setImportReport] = useState<UserImportReport | null>(null);
+  const [isImporting, setIsImporting] = useState(false);
 
   const loadUsers = async () => {
     if (!sessionToken) return;
     setIsLoading(true); setError(null);
-    try { const result = await invoke<User[]>('get_all_users', { sessionToken }); setUsers(result); }
-    catch (e) { setError(e as string); }
+    try {
+      const [result, locked, invites] = await Promise.all([
+        invoke<User[]>('get_all_users', { sessionToken }),
+        invoke<LockedAccount[]>('get_locked_accounts', { sessionToken }),
+        invoke<InviteCode[]>('get_invite_codes', { sessionToken }),
+      ]);
+      setUsers(result); setLockedAccounts(locked); setInviteCodes(invites);
+    }
+    catch (e) { setError(errorMessage(e)); }
     finally { setIsLoading(false); }
   };
 
+  const handleUnlock = async (username: string) => {
+    if (!sessionToken) return;
+    try { await invoke('unlock_account', { sessionToken, username }); setLockedAccounts((prev) => prev.filter((a) => a.username !== username)); }
+    catch (e) { setError(errorMessage(e)); }
+  };
+
   useEffect(() => { loadUsers(); }, [sessionToken]);
 
+  const loadActivity = async () => {
+    if (!sessionToken) return;
+    try { setActivity(await invoke<UserActivity[]>('get_user_activity', { sessionToken, startDate: activityStart, endDate: activityEnd })); }
+    catch (e) { setError(errorMessage(e)); }
+  };
+
+  useEffect(() => { loadActivity(); }, [sessionToken, activityStart, activityEnd]);
+
+  // 日期按本地时间解析，结束日期包含当天
+  const loadAuditLog = async (page = 1) => {
+    if (!sessionToken) return;
+    const until = auditUntil ? new Date(`${auditUntil}T00:00:00`) : null;
+    if (until) until.setDate(until.getDate() + 1);
+    const query = {
+      page, page_size: AUDIT_PAGE_SIZE,
+      action: auditAction || null,
+      target_user_id: auditUserId ? Number(auditUserId) : null,
+      since: auditSince ? new Date(`${auditSince}T00:00:00`).toISOString() : null,
+      until: until ? until.toISOString() : null,
+    };
+    try { setAuditLog(await invoke<AuditLogPage>('get_audit_log', { sessionToken, query })); }
+    catch (e) { setError(errorMessage(e)); }
+  };
+
+  useEffect(() => { loadAuditLog(); }, [sessionToken, auditAction, auditUserId, auditSince, auditUntil]);
+
+  const handleCreateInvite = async () => {
+    if (!sessionToken) return;
+    try {
+      const invite = await invoke<InviteCode>('create_invite_code', { sessionToken, role: inviteRole, maxUses: inviteMaxUses, expiresInDays: inviteDays });
+      setInviteCodes((prev) => [invite, ...prev]);
+    } catch (e) { setError(errorMessage(e)); }
+  };
+
+  const handleRevokeInvite = async (invite: InviteCode) => {
+    if (!sessionToken) return;
+    try { await invoke('revoke_invite_code', { sessionToken, inviteId: invite.id }); setInviteCodes(await invoke<InviteCode[]>('get_invite_codes', { sessionToken })); }
+    catch (e) { setError(errorMessage(e)); }
+  };
+
+  const openInviteUsers = async (invite: InviteCode) => {
+    if (!sessionToken) return;
+    try { setInviteUsers({ invite, users: await invoke<User[]>('get_invite_code_users', { sessionToken, inviteId: invite.id }) }); }
+    catch (e) { setError(errorMessage(e)); }
+  };
+
+  const handleRoleChange = async (user: User, role: string) => {
+    if (!sessionToken) return;
+    try {
+      const updated = await invoke<User>('set_user_role', { sessionToken, userId: user.id, role });
+      setUsers((prev) => prev.map((u) => (u.id === updated.id ? updated : u)));
+    } catch (e) { setError(errorMessage(e)); }
+  };
+
+  const openStudents = async (mentor: User) => {
+    if (!sessionToken) return;
+    try {
+      setMentorStudents(await invoke<User[]>('get_mentor_students', { sessionToken, mentorId: mentor.id }));
+      setStudentsMentor(mentor); setNewStudentId('');
+    } catch (e) { setError(errorMessage(e)); }
+  };
+
+  const handleAssign = async (studentId: number, assign: boolean) => {
+    if (!sessionToken || !studentsMentor) return;
+    try {
+      await invoke(assign ? 'assign_student' : 'unassign_student', { sessionToken, mentorId: studentsMentor.id, studentId });
+      setMentorStudents(await invoke<User[]>('get_mentor_students', { sessionToken, mentorId: studentsMentor.id }));
+      setNewStudentId('');
+    } catch (e) { setError(errorMessage(e)); }
+  };
+
   const handleCreate = async () => {
     if (!sessionToken || !newUsername.trim() || !newPassword || !newDisplayName.trim()) return;
     setIsCreating(true);
     try {
       await invoke('create_user', { sessionToken, username: newUsername.trim(), password: newPassword, displayName: newDisplayName.trim(), role: newRole });
       setShowCreateDialog(false); setNewUsername(''); setNewPassword(''); setNewDisplayName(''); setNewRole('user'); await loadUsers();
-    } catch (e) { setError(e as string); }
+    } catch (e) { setError(errorMessage(e)); }
     finally { setIsCreating(false); }
   };
 
+  const handleSetActive = async (user: User, active: boolean) => {
+    if (!sessionToken) return;
+    try {
+      const updated = await invoke<User>(active ? 'reactivate_user' : 'deactivate_user', { sessionToken, userId: user.id });
+      setUsers((prev) => prev.map((u) => (u.id === updated.id ? updated : u)));
+    } catch (e) { setError(errorMessage(e)); }
+  };
+
   const handleDelete = async () => {
     if (!sessionToken || !deleteUser) return;
     setIsDeleting(true);
-    try { await invoke('delete_user', { sessionToken, userId: deleteUser.id }); setDeleteUser(null); await loadUsers(); }
-    catch (e) { setError(e as string); }
+    try { setLastArchive(await invoke<UserArchive>('delete_user', { sessionToken, userId: deleteUser.id })); setDeleteUser(null); await loadUsers(); }
+    catch (e) { setError(errorMessage(e)); }
     finally { setIsDeleting(false); }
   };
 
@@ -57,10 +201,42 @@ export default function UserManagement() {
     if (!sessionToken || !resetUser || !resetPassword) return;
     setIsResetting(true);
     try { await invoke('reset_user_password', { sessionToken, userId: resetUser.id, newPassword: resetPassword }); setResetUser(null); setResetPassword(''); }
-    catch (e) { setError(e as string); }
+    catch (e) { setError(errorMessage(e)); }
     finally { setIsResetting(false); }
   };
 
+  const handleResetTwoFactor = async () => {
+    if (!sessionToken || !resetTwoFactorUser) return;
+    try { await invoke('reset_user_two_factor', { sessionToken, userId: resetTwoFactorUser.id }); }
+    catch (e) { setError(errorMessage(e)); }
+    finally { setResetTwoFactorUser(null); }
+  };
+
+  const handleImport = async (dryRun: boolean) => {
+    if (!sessionToken || !importCsv.trim()) return;
+    setIsImporting(true);
+    try {
+      const report = await invoke<UserImportReport>('import_users_csv', { sessionToken, csv: importCsv, dryRun });
+      setImportReport(report);
+      if (report.created > 0) await loadUsers();
+    } catch (e) { setError(errorMessage(e)); setImportReport(null); }
+    finally { setIsImporting(false); }
+  };
+
+  const handleImportFile = async (file: File | undefined) => {
+    if (!file) return;
+    setImportCsv(await file.text());
+    setImportReport(null);
+  };
+
+  const closeImportDialog = () => { setShowImportDialog(false); setImportCsv(''); setImportReport(null); };
+
+  const handleExport = async () => {
+    if (!sessionToken) return;
+    try { downloadCsv(await invoke<string>('export_users_csv', { sessionToken }), `用户列表_${new Date().toISOString().slice(0, 10)}.csv`); }
+    catch (e) { setError(errorMessage(e)); }
+  };
+
   return (
     <div className="h-full flex flex-col">
       <div className={`p-4 border-b ${themeConfig.border} flex justify-between items-center`}>
@@ -68,12 +244,23 @@ export default function UserManagement() {
           <h1 className={`text-xl font-bold ${themeConfig.text}`}>👥 用户管理</h1>
           <p className={`${themeConfig.textSecondary} text-sm`}>管理系统用户</p>
         </div>
-        <button onClick={() => setShowCreateDialog(true)}
-          className={`px-4 py-2 bg-gradient-to-r ${themeConfig.accent} text-white rounded-lg font-medium hover:shadow-lg transition-all`}>
-          + 添加用户
-        </button>
+        <div className="flex gap-2">
+          <button onClick={handleExport} className={`px-4 py-2 ${themeConfig.textSecondary} hover:${themeConfig.text} border ${themeConfig.border} rounded-lg transition-all`}>导出 CSV</button>
+          <button onClick={() => setShowImportDialog(true)} className={`px-4 py-2 ${themeConfig.textSecondary} hover:${themeConfig.text} border ${themeConfig.border} rounded-lg transition-all`}>批量导入</button>
+          <button onClick={() => setShowCreateDialog(true)}
+            className={`px-4 py-2 bg-gradient-to-r ${themeConfig.accent} text-white rounded-lg font-medium hover:shadow-lg transition-all`}>
+            + 添加用户
+          </button>
+        </div>
       </div>
 
+      {lastArchive && (
+        <div className="mx-4 mt-4 p-3 bg-emerald-500/20 border border-emerald-500/30 rounded-lg text-emerald-400 text-sm flex justify-between gap-3">
+          <span>✅ 已删除 {lastArchive.username}，数据已归档（任务 {lastArchive.tasks}、番茄钟 {lastArchive.pomodoros}、复盘 {lastArchive.reviews}、倒计时 {lastArchive.countdowns}）：<span className="font-mono break-all">{lastArchive.path}</span></span>
+          <button onClick={() => setLastArchive(null)} className="hover:text-white">✕</button>
+        </div>
+      )}
+
       {error && (
         <div className="mx-4 mt-4 p-3 bg-rose-500/20 border border-rose-500/30 rounded-lg text-rose-400 text-sm flex justify-between">
           <span>⚠️ {error}</span><button onClick={() => setError(null)} className="hover:text-white">✕</button>
@@ -97,23 +284,41 @@ export default function UserManagement() {
               </thead>
               <tbody className={`divide-y ${themeConfig.border}`}>
                 {users.map((user) => (
-                  <tr key={user.id} className="hover:bg-white/5 transition-colors">
+                  <tr key={user.id} className={`hover:bg-white/5 transition-colors ${user.is_active ? '' : 'opacity-60'}`}>
                     <td className="px-4 py-3">
                       <div className="flex items-center gap-3">
                         <div className={`w-9 h-9 bg-gradient-to-br ${themeConfig.accent} rounded-full flex items-center justify-center text-white text-sm font-bold`}>{user.display_name.charAt(0)}</div>
                         <span className={`${themeConfig.text} font-medium`}>{user.display_name}</span>
+                        {!user.is_active && <span className="px-2 py-0.5 rounded text-xs bg-slate-500/20 text-slate-400">已停用</span>}
                       </div>
                     </td>
                     <td className={`px-4 py-3 ${themeConfig.textSecondary} font-mono text-sm`}>{user.username}</td>
                     <td className="px-4 py-3">
-                      <span className={`px-2 py-1 rounded text-xs font-medium ${user.role === 'admin' ? 'bg-violet-500/20 text-violet-400' : `${themeConfig.bgSecondary} ${themeConfig.textSecondary}`}`}>{user.role_label}</span>
+                      {user.id === currentUser?.id ? (
+                        <span className="px-2 py-1 rounded text-xs font-medium bg-violet-500/20 text-violet-400">{user.role_label}</span>
+                      ) : (
+                        <select value={user.role} onChange={(e) => handleRoleChange(user, e.target.value)} className={`px-2 py-1 rounded text-xs font-medium bg-transparent border ${themeConfig.border} ${user.role === 'admin' ? 'text-violet-400' : user.role === 'mentor' ? 'text-cyan-400' : themeConfig.textSecondary}`}>
+                          <option value="user">普通用户</option><option value="mentor">导师</option><option value="admin">管理员</option>
+                        </select>
+                      )}
                     </td>
                     <td className={`px-4 py-3 ${themeConfig.textSecondary} text-sm`}>{new Date(user.created_at).toLocaleDateString('zh-CN')}</td>
                     <td className="px-4 py-3 text-right">
                       {user.id !== currentUser?.id && (
                         <div className="flex justify-end gap-2">
+                          {user.role === 'mentor' && (
+                            <button onClick={() => openStudents(user)} className="px-2 py-1 text-xs text-cyan-400 hover:text-cyan-300 hover:bg-cyan-500/10 rounded transition-all">学生</button>
+                          )}
                           <button onClick={() => setResetUser(user)} className={`px-2 py-1 text-xs ${themeConfig.textSecondary} hover:${themeConfig.text} hover:bg-white/10 rounded transition-all`}>重置密码</button>
-                          <button onClick={() => setDeleteUser(user)} className="px-2 py-1 text-xs text-rose-400 hover:text-rose-300 hover:bg-rose-500/10 rounded transition-all">删除</button>
+                          <button onClick={() => setResetTwoFactorUser(user)} className={`px-2 py-1 text-xs ${themeConfig.textSecondary} hover:${themeConfig.text} hover:bg-white/10 rounded transition-all`}>重置两步验证</button>
+                          {user.is_active ? (
+                            <button onClick={() => handleSetActive(user, false)} className="px-2 py-1 text-xs text-amber-400 hover:text-amber-300 hover:bg-amber-500/10 rounded transition-all">停用</button>
+                          ) : (
+                            <>
+                              <button onClick={() => handleSetActive(user, true)} className="px-2 py-1 text-xs text-emerald-400 hover:text-emerald-300 hover:bg-emerald-500/10 rounded transition-all">启用</button>
+                              <button onClick={() => setDeleteUser(user)} className="px-2 py-1 text-xs text-rose-400 hover:text-rose-300 hover:bg-rose-500/10 rounded transition-all">永久删除</button>
+                            </>
+                          )}
                         </div>
                       )}
                     </td>
@@ -123,6 +328,152 @@ export default function UserManagement() {
             </table>
           </div>
         )}
+
+        {/* 邀请码 */}
+        {!isLoading && (
+          <div className={`mt-4 ${themeConfig.bgSecondary} rounded-xl border ${themeConfig.border} p-4`}>
+            <div className="flex flex-wrap items-center justify-between gap-2 mb-3">
+              <h2 className={`${themeConfig.text} font-medium`}>🎟️ 邀请码</h2>
+              <div className="flex items-center gap-2 text-sm">
+                <select value={inviteRole} onChange={(e) => setInviteRole(e.target.value)} className={`px-2 py-1 rounded bg-transparent border ${themeConfig.border} ${themeConfig.text}`}>
+                  <option value="user">普通用户</option><option value="mentor">导师</option><option value="admin">管理员</option>
+                </select>
+                <label className={themeConfig.textSecondary}>次数</label>
+                <input type="number" min={1} max={500} value={inviteMaxUses} onChange={(e) => setInviteMaxUses(Number(e.target.value))} className={`w-16 px-2 py-1 rounded bg-transparent border ${themeConfig.border} ${themeConfig.text}`} />
+                <label className={themeConfig.textSecondary}>有效天数</label>
+                <input type="number" min={1} max={90} value={inviteDays} onChange={(e) => setInviteDays(Number(e.target.value))} className={`w-16 px-2 py-1 rounded bg-transparent border ${themeConfig.border} ${themeConfig.text}`} />
+                <button onClick={handleCreateInvite} className={`px-3 py-1 bg-gradient-to-r ${themeConfig.accent} text-white rounded-lg`}>生成</button>
+              </div>
+            </div>
+            {inviteCodes.length === 0 ? (
+              <p className={`${themeConfig.textSecondary} text-sm`}>暂无邀请码</p>
+            ) : (
+              <div className="space-y-2">
+                {inviteCodes.map((invite) => (
+                  <div key={invite.id} className="flex items-center justify-between text-sm">
+                    <div>
+                      <span className={`${themeConfig.text} font-mono`}>{invite.code}</span>
+                      <span className={`${themeConfig.textSecondary} ml-3`}>{invite.role_label} · 已用 {invite.used_count}/{invite.max_uses} · {new Date(invite.expires_at).toLocaleDateString('zh-CN')} 到期</span>
+                      <span className={`ml-2 text-xs ${invite.status === 'active' ? 'text-emerald-400' : 'text-slate-500'}`}>{{ active: '有效', expired: '已过期', exhausted: '已用完', revoked: '已撤销' }[invite.status]}</span>
+                    </div>
+                    <div className="flex gap-2">
+                      <button onClick={() => openInviteUsers(invite)} className={`px-2 py-1 text-xs ${themeConfig.textSecondary} hover:bg-white/10 rounded transition-all`}>注册账户</button>
+                      {invite.status === 'active' && (
+                        <button onClick={() => handleRevokeInvite(invite)} className="px-2 py-1 text-xs text-rose-400 hover:text-rose-300 hover:bg-rose-500/10 rounded transition-all">撤销</button>
+                      )}
+                    </div>
+                  </div>
+                ))}
+              </div>
+            )}
+          </div>
+        )}
+
+        {/* 学习活跃度 */}
+        {!isLoading && (
+          <div className={`mt-4 ${themeConfig.bgSecondary} rounded-xl border ${themeConfig.border} p-4`}>
+            <div className="flex flex-wrap items-center justify-between gap-2 mb-3">
+              <h2 className={`${themeConfig.text} font-medium`}>📈 学习活跃度</h2>
+              <div className="flex items-center gap-2 text-sm">
+                <input type="date" value={activityStart} onChange={(e) => setActivityStart(e.target.value)} className={`px-2 py-1 rounded bg-transparent border ${themeConfig.border} ${themeConfig.text}`} />
+                <span className={themeConfig.textSecondary}>至</span>
+                <input type="date" value={activityEnd} onChange={(e) => setActivityEnd(e.target.value)} className={`px-2 py-1 rounded bg-transparent border ${themeConfig.border} ${themeConfig.text}`} />
+              </div>
+            </div>
+            <div className="overflow-auto">
+              <table className="w-full text-sm">
+                <thead>
+                  <tr className={`${themeConfig.textSecondary} text-left`}>
+                    <th className="py-2 pr-3 font-medium">用户</th><th className="py-2 pr-3 font-medium">最近登录</th><th className="py-2 pr-3 font-medium text-right">学习时长</th>
+                    <th className="py-2 pr-3 font-medium text-right">番茄钟</th><th className="py-2 pr-3 font-medium text-right">任务完成率</th><th className="py-2 pr-3 font-medium text-right">距上次复盘</th><th className="py-2 font-medium text-center">AI</th>
+                  </tr>
+                </thead>
+                <tbody className={`divide-y ${themeConfig.border}`}>
+                  {activity.map((a) => (
+                    <tr key={a.user_id} className={a.is_active ? '' : 'opacity-60'}>
+                      <td className={`py-2 pr-3 ${themeConfig.text}`}>{a.display_name} <span className="text-slate-500 font-mono text-xs">{a.username}</span></td>
+                      <td className={`py-2 pr-3 ${themeConfig.textSecondary}`}>{a.last_login_at ? new Date(a.last_login_at).toLocaleString('zh-CN') : '从未登录'}</td>
+                      <td className={`py-2 pr-3 text-right ${themeConfig.text}`}>{(a.study_minutes / 60).toFixed(1)} 小时</td>
+                      <td className={`py-2 pr-3 text-right ${themeConfig.text}`}>{a.completed_pomodoros}</td>
+                      <td className={`py-2 pr-3 text-right ${themeConfig.text}`}>{a.total_tasks > 0 ? `${a.completion_rate.toFixed(0)}% (${a.completed_tasks}/${a.total_tasks})` : '—'}</td>
+                      <td className={`py-2 pr-3 text-right ${a.days_since_review === null || a.days_since_review > 7 ? 'text-amber-400' : themeConfig.text}`}>{a.days_since_review === null ? '从未复盘' : `${a.days_since_review} 天`}</td>
+                      <td className="py-2 text-center">{a.ai_configured ? '✓' : <span className="text-slate-500">—</span>}</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            </div>
+          </div>
+        )}
+
+        {/* 审计日志 */}
+        {!isLoading && (
+          <div className={`mt-4 ${themeConfig.bgSecondary} rounded-xl border ${themeConfig.border} p-4`}>
+            <div className="flex flex-wrap items-center justify-between gap-2 mb-3">
+              <h2 className={`${themeConfig.text} font-medium`}>🧾 审计日志</h2>
+              <div className="flex items-center gap-2 text-sm">
+                <select value={auditAction} onChange={(e) => setAuditAction(e.target.value)} className={`px-2 py-1 rounded bg-transparent border ${themeConfig.border} ${themeConfig.text}`}>
+                  <option value="">全部操作</option>
+                  {Object.entries(AUDIT_ACTIONS).map(([action, label]) => <option key={action} value={action}>{label}</option>)}
+                </select>
+                <select value={auditUserId} onChange={(e) => setAuditUserId(e.target.value)} className={`px-2 py-1 rounded bg-transparent border ${themeConfig.border} ${themeConfig.text}`}>
+                  <option value="">全部用户</option>
+                  {users.map((u) => <option key={u.id} value={u.id}>{u.username}</option>)}
+                </select>
+                <input type="date" value={auditSince} onChange={(e) => setAuditSince(e.target.value)} className={`px-2 py-1 rounded bg-transparent border ${themeConfig.border} ${themeConfig.text}`} />
+                <span className={themeConfig.textSecondary}>至</span>
+                <input type="date" value={auditUntil} onChange={(e) => setAuditUntil(e.target.value)} className={`px-2 py-1 rounded bg-transparent border ${themeConfig.border} ${themeConfig.text}`} />
+              </div>
+            </div>
+            {!auditLog || auditLog.events.length === 0 ? (
+              <p className={`${themeConfig.textSecondary} text-sm`}>暂无审计记录</p>
+            ) : (
+              <>
+                <div className="space-y-2">
+                  {auditLog.events.map((event) => (
+                    <div key={event.id} className="flex items-start justify-between gap-3 text-sm">
+                      <div>
+                        <span className={themeConfig.text}>{AUDIT_ACTIONS[event.action] ?? event.action}</span>
+                        <span className={`${themeConfig.textSecondary} ml-3`}>
+                          {event.actor_username ?? (event.actor_id ? `#${event.actor_id}` : '系统')}
+                          {event.target_user_id && event.target_user_id !== event.actor_id && ` → ${event.target_username ?? `#${event.target_user_id}`}`}
+                        </span>
+                        {event.metadata && Object.keys(event.metadata).length > 0 && (
+                          <span className="ml-3 text-xs text-slate-500 font-mono">{JSON.stringify(event.metadata)}</span>
+                        )}
+                      </div>
+                      <span className={`${themeConfig.textSecondary} text-xs whitespace-nowrap`}>{new Date(event.created_at).toLocaleString('zh-CN')}</span>
+                    </div>
+                  ))}
+                </div>
+                <div className={`flex items-center justify-end gap-3 mt-3 text-sm ${themeConfig.textSecondary}`}>
+                  <span>共 {auditLog.total} 条</span>
+                  <button disabled={auditLog.page <= 1} onClick={() => loadAuditLog(auditLog.page - 1)} className="px-2 py-1 hover:bg-white/10 rounded disabled:opacity-40">上一页</button>
+                  <span>{auditLog.page} / {Math.max(1, Math.ceil(auditLog.total / auditLog.page_size))}</span>
+                  <button disabled={auditLog.page * auditLog.page_size >= auditLog.total} onClick={() => loadAuditLog(auditLog.page + 1)} className="px-2 py-1 hover:bg-white/10 rounded disabled:opacity-40">下一页</button>
+                </div>
+              </>
+            )}
+          </div>
+        )}
+
+        {/* 临时锁定的账户 */}
+        {!isLoading && lockedAccounts.length > 0 && (
+          <div className={`mt-4 ${themeConfig.bgSecondary} rounded-xl border ${themeConfig.border} p-4`}>
+            <h2 className={`${themeConfig.text} font-medium mb-3`}>🔒 临时锁定的账户</h2>
+            <div className="space-y-2">
+              {lockedAccounts.map((account) => (
+                <div key={account.username} className="flex items-center justify-between text-sm">
+                  <div>
+                    <span className={`${themeConfig.text} font-mono`}>{account.username}</span>
+                    <span className={`${themeConfig.textSecondary} ml-3`}>连续失败 {account.failed_count} 次{account.locked_until && `，锁定至 ${new Date(account.locked_until).toLocaleTimeString('zh-CN')}`}</span>
+                  </div>
+                  <button onClick={() => handleUnlock(account.username)} className="px-2 py-1 text-xs text-amber-400 hover:text-amber-300 hover:bg-amber-500/10 rounded transition-all">解除锁定</button>
+                </div>
+              ))}
+            </div>
+          </div>
+        )}
       </div>
 
       {/* 创建用户对话框 */}
@@ -136,7 +487,7 @@ export default function UserManagement() {
               <div><label className="block text-sm text-slate-400 mb-2">昵称</label><input type="text" value={newDisplayName} onChange={(e) => setNewDisplayName(e.target.value)} className="w-full px-3 py-2 bg-slate-700/50 border border-white/10 rounded-lg text-white" placeholder="显示名称" /></div>
               <div><label className="block text-sm text-slate-400 mb-2">角色</label>
                 <select value={newRole} onChange={(e) => setNewRole(e.target.value)} className="w-full px-3 py-2 bg-slate-700/50 border border-white/10 rounded-lg text-white">
-                  <option value="user">普通用户</option><option value="admin">管理员</option>
+                  <option value="user">普通用户</option><option value="mentor">导师</option><option value="admin">管理员</option>
                 </select>
               </div>
             </div>
@@ -148,12 +499,116 @@ export default function UserManagement() {
         </div>
       )}
 
+      {/* 导师的学生 */}
+      {studentsMentor && (
+        <div className="fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50">
+          <div className="bg-slate-800 rounded-2xl shadow-2xl w-full max-w-md p-6 m-4 border border-white/10">
+            <h3 className="text-xl font-bold text-white mb-4">🎓 {studentsMentor.display_name} 的学生</h3>
+            <div className="space-y-2 max-h-60 overflow-auto">
+              {mentorStudents.length === 0 && <p className="text-slate-500 text-sm">尚未分配学生</p>}
+              {mentorStudents.map((student) => (
+                <div key={student.id} className="flex items-center justify-between text-sm">
+                  <span className="text-white">{student.display_name} <span className="text-slate-500 font-mono">{student.username}</span></span>
+                  <button onClick={() => handleAssign(student.id, false)} className="px-2 py-1 text-xs text-rose-400 hover:text-rose-300 hover:bg-rose-500/10 rounded transition-all">移除</button>
+                </div>
+              ))}
+            </div>
+            <div className="mt-4 flex gap-2">
+              <select value={newStudentId} onChange={(e) => setNewStudentId(e.target.value)} className="flex-1 px-3 py-2 bg-slate-700/50 border border-white/10 rounded-lg text-white">
+                <option value="">选择学生</option>
+                {users.filter((u) => u.id !== studentsMentor.id && !mentorStudents.some((s) => s.id === u.id)).map((u) => (
+                  <option key={u.id} value={u.id}>{u.display_name} ({u.username})</option>
+                ))}
+              </select>
+              <button onClick={() => handleAssign(Number(newStudentId), true)} disabled={!newStudentId} className="px-4 py-2 bg-gradient-to-r from-cyan-500 to-sky-500 text-white rounded-lg disabled:opacity-50">分配</button>
+            </div>
+            <div className="mt-6 flex justify-end">
+              <button onClick={() => setStudentsMentor(null)} className="px-4 py-2 text-slate-400 hover:text-white">关闭</button>
+            </div>
+          </div>
+        </div>
+      )}
+
+      {/* 邀请码注册的账户 */}
+      {inviteUsers && (
+        <div className="fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50">
+          <div className="bg-slate-800 rounded-2xl shadow-2xl w-full max-w-md p-6 m-4 border border-white/10">
+            <h3 className="text-xl font-bold text-white mb-4">🎟️ <span className="font-mono">{inviteUsers.invite.code}</span> 注册的账户</h3>
+            <div className="space-y-2 max-h-60 overflow-auto">
+              {inviteUsers.users.length === 0 && <p className="text-slate-500 text-sm">尚无账户使用该邀请码</p>}
+              {inviteUsers.users.map((u) => (
+                <div key={u.id} className="flex items-center justify-between text-sm">
+                  <span className="text-white">{u.display_name} <span className="text-slate-500 font-mono">{u.username}</span></span>
+                  <span className="text-slate-500">{new Date(u.created_at).toLocaleString('zh-CN')}</span>
+                </div>
+              ))}
+            </div>
+            <div className="mt-6 flex justify-end">
+              <button onClick={() => setInviteUsers(null)} className="px-4 py-2 text-slate-400 hover:text-white">关闭</button>
+            </div>
+          </div>
+        </div>
+      )}
+
+      {/* 批量导入 */}
+      {showImportDialog && (
+        <div className="fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50">
+          <div className="bg-slate-800 rounded-2xl shadow-2xl w-full max-w-2xl p-6 m-4 border border-white/10">
+            <h3 className="text-xl font-bold text-white mb-2">📥 批量导入用户</h3>
+            <p className="text-slate-400 text-sm mb-4">第一行为表头：username（必填）、display_name、role（user / mentor / admin，默认 user）。先预览校验，全部通过后才能导入，所有账户一次性创建。</p>
+            {importReport?.created ? (
+              <div className="space-y-3">
+                <p className="text-emerald-400 text-sm">✅ 已创建 {importReport.created} 个账户。初始密码只显示这一次，请下载后分发给用户。</p>
+                <div className="max-h-60 overflow-auto text-sm font-mono space-y-1">
+                  {importReport.credentials.map((c) => (
+                    <div key={c.username} className="flex justify-between text-slate-300"><span>{c.username}</span><span className="text-white">{c.initial_password}</span></div>
+                  ))}
+                </div>
+              </div>
+            ) : (
+              <>
+                <input type="file" accept=".csv,text/csv" onChange={(e) => handleImportFile(e.target.files?.[0])} className="text-sm text-slate-400 mb-2" />
+                <textarea value={importCsv} onChange={(e) => { setImportCsv(e.target.value); setImportReport(null); }} rows={6}
+                  className="w-full px-3 py-2 bg-slate-700/50 border border-white/10 rounded-lg text-white font-mono text-sm" placeholder={'username,display_name,role\nzhangsan,张三,user'} />
+                {importReport && (
+                  <div className="mt-3">
+                    <p className={`text-sm mb-2 ${importReport.valid === importReport.total ? 'text-emerald-400' : 'text-amber-400'}`}>共 {importReport.total} 行，{importReport.valid} 行通过校验{importReport.valid < importReport.total && '，请修正后重新预览'}</p>
+                    <div className="max-h-48 overflow-auto text-sm space-y-1">
+                      {importReport.rows.map((row) => (
+                        <div key={row.line} className="flex gap-3">
+                          <span className="text-slate-500 w-12">第 {row.line} 行</span>
+                          <span className="text-white font-mono">{row.username || '—'}</span>
+                          <span className="text-slate-400">{row.display_name} · {row.role}</span>
+                          <span className={row.errors.length ? 'text-rose-400' : 'text-emerald-400'}>{row.errors.length ? row.errors.join('；') : '✓'}</span>
+                        </div>
+                      ))}
+                    </div>
+                  </div>
+                )}
+              </>
+            )}
+            <div className="mt-6 flex justify-end gap-3">
+              <button onClick={closeImportDialog} className="px-4 py-2 text-slate-400 hover:text-white">{importReport?.created ? '关闭' : '取消'}</button>
+              {importReport?.credentials_csv ? (
+                <button onClick={() => downloadCsv(importReport.credentials_csv!, `初始密码_${new Date().toISOString().slice(0, 10)}.csv`)} className="px-4 py-2 bg-gradient-to-r from-violet-500 to-purple-500 text-white rounded-lg">下载初始密码</button>
+              ) : (
+                <>
+                  <button onClick={() => handleImport(true)} disabled={isImporting || !importCsv.trim()} className="px-4 py-2 border border-white/10 text-slate-300 rounded-lg disabled:opacity-50">预览</button>
+                  <button onClick={() => handleImport(false)} disabled={isImporting || !importReport || importReport.valid < importReport.total}
+                    className="px-4 py-2 bg-gradient-to-r from-violet-500 to-purple-500 text-white rounded-lg disabled:opacity-50">{isImporting ? '处理中...' : `导入 ${importReport?.valid ?? 0} 个用户`}</button>
+                </>
+              )}
+            </div>
+          </div>
+        </div>
+      )}
+
       {/* 删除确认 */}
       {deleteUser && (
         <div className="fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50">
           <div className="bg-slate-800 rounded-2xl shadow-2xl w-full max-w-md p-6 m-4 border border-white/10">
             <h3 className="text-xl font-bold text-rose-400 mb-4">⚠️ 确认删除</h3>
-            <p className="text-slate-300">确定删除用户 <span className="text-white font-medium">"{deleteUser.display_name}"</span>？该用户的所有数据将被删除。</p>
+            <p className="text-slate-300">确定永久删除用户 <span className="text-white font-medium">"{deleteUser.display_name}"</span>？删除前会将该用户的全部数据导出为归档文件，随后从数据库中删除，无法恢复。</p>
             <div className="mt-6 flex justify-end gap-3">
               <button onClick={() => setDeleteUser(null)} className="px-4 py-2 text-slate-400 hover:text-white">取消</button>
               <button onClick={handleDelete} disabled={isDeleting} className="px-4 py-2 bg-rose-500 text-white rounded-lg disabled:opacity-50">{isDeleting ? '删除中...' : '删除'}</button>
@@ -162,6 +617,20 @@ export default function UserManagement() {
         </div>
       )}
 
+      {/* 重置两步验证 */}
+      {resetTwoFactorUser && (
+        <div className="fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50">
+          <div className="bg-slate-800 rounded-2xl shadow-2xl w-full max-w-md p-6 m-4 border border-white/10">
+            <h3 className="text-xl font-bold text-white mb-4">🔐 重置两步验证</h3>
+            <p className="text-slate-300">确定重置用户 <span className="text-white font-medium">"{resetTwoFactorUser.display_name}"</span> 的两步验证？身份验证器和恢复码将全部失效，该用户下次登录只需密码，可重新绑定。</p>
+            <div className="mt-6 flex justify-end gap-3">
+              <button onClick={() => setResetTwoFactorUser(null)} className="px-4 py-2 text-slate-400 hover:text-white">取消</button>
+              <button onClick={handleResetTwoFactor} className="px-4 py-2 bg-gradient-to-r from-violet-500 to-purple-500 text-white rounded-lg">重置</button>
+            </div>
+          </div>
+        </div>
+      )}
+
       {/* 重置密码 */}
       {resetUser && (
         <div className="fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50">
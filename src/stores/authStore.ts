@@ -2,6 +2,7 @@
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
 import { invoke } from '@tauri-apps/api/core';
+import { errorMessage, isAppError } from '../utils/error';
 
 // 用户响应类型
 interface UserResponse {
@@ -10,6 +11,9 @@ interface UserResponse {
   display_name: string;
   role: string;
   role_label: string;
+  permissions?: string[];
+  // 仍在使用初始密码，需要先修改密码
+  must_change_password?: boolean;
   created_at: string;
 }
 
@@ -19,15 +23,25 @@ interface LoginResponse {
   session_token: string;
 }
 
+// 登录结果类型 (启用两步验证时需要再提交验证码)
+type LoginResult =
+  | ({ status: 'authenticated' } & LoginResponse)
+  | { status: 'two_factor_required'; challenge_token: string; expires_at: string };
+
 // 认证状态
 interface AuthState {
   user: UserResponse | null;
   sessionToken: string | null;
+  // 等待提交两步验证码的登录挑战
+  challengeToken: string | null;
   isLoading: boolean;
   error: string | null;
   
   // 操作
   login: (username: string, password: string) => Promise<boolean>;
+  verifyLoginCode: (code: string) => Promise<boolean>;
+  cancelTwoFactor: () => void;
+  register: (inviteCode: string, username: string, password: string, displayName: string) => Promise<boolean>;
   logout: () => Promise<void>;
   checkSession: () => Promise<boolean>;
   clearError: () => void;
@@ -39,13 +53,55 @@ export const useAuthStore = create<AuthState>()(
     (set, get) => ({
       user: null,
       sessionToken: null,
+      challengeToken: null,
       isLoading: false,
       error: null,
 
       login: async (username: string, password: string) => {
         set({ isLoading: true, error: null });
         try {
-          const response = await invoke<LoginResponse>('login', { username, password });
+          const result = await invoke<LoginResult>('login', { username, password });
+          if (result.status === 'two_factor_required') {
+            set({ challengeToken: result.challenge_token, isLoading: false });
+            return false;
+          }
+          set({
+            user: result.user,
+            sessionToken: result.session_token,
+            isLoading: false,
+          });
+          return true;
+        } catch (e) {
+          set({ error: errorMessage(e), isLoading: false });
+          return false;
+        }
+      },
+
+      verifyLoginCode: async (code: string) => {
+        const { challengeToken } = get();
+        if (!challengeToken) return false;
+        set({ isLoading: true, error: null });
+        try {
+          const response = await invoke<LoginResponse>('verify_login_code', { challengeToken, code });
+          set({
+            user: response.user,
+            sessionToken: response.session_token,
+            challengeToken: null,
+            isLoading: false,
+          });
+          return true;
+        } catch (e) {
+          set({ error: errorMessage(e), isLoading: false });
+          return false;
+        }
+      },
+
+      cancelTwoFactor: () => set({ challengeToken: null, error: null }),
+
+      register: async (inviteCode: string, username: string, password: string, displayName: string) => {
+        set({ isLoading: true, error: null });
+        try {
+          const response = await invoke<LoginResponse>('register_with_invite', { inviteCode, username, password, displayName });
           set({
             user: response.user,
             sessionToken: response.session_token,
@@ -53,7 +109,7 @@ export const useAuthStore = create<AuthState>()(
           });
           return true;
         } catch (e) {
-          set({ error: e as string, isLoading: false });
+          set({ error: errorMessage(e), isLoading: false });
           return false;
         }
       },
@@ -82,7 +138,10 @@ export const useAuthStore = create<AuthState>()(
           set({ user });
           return true;
         } catch (e) {
-          set({ user: null, sessionToken: null });
+          // 仅在会话失效时清除登录状态，数据库暂不可用时保留会话
+          if (isAppError(e) && e.code === 'unauthorized') {
+            set({ user: null, sessionToken: null });
+          }
           return false;
         }
       },
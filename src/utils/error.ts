@@ -0,0 +1,32 @@
+// 后端命令错误处理
+// 命令失败时返回 {code, message, details}，按 code 区分错误类型，message 用于展示
+
+export type AppErrorCode =
+  | 'unauthorized'
+  | 'forbidden'
+  | 'not_found'
+  | 'validation'
+  | 'conflict'
+  | 'schedule_conflict'
+  | 'rate_limited'
+  | 'database_unavailable'
+  | 'database'
+  | 'ai_provider'
+  | 'sync'
+  | 'config';
+
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+  details: string | null;
+}
+
+// 是否为后端返回的错误
+export function isAppError(e: unknown): e is AppError {
+  return typeof e === 'object' && e !== null && 'code' in e && 'message' in e;
+}
+
+// 获取可展示的错误信息
+export function errorMessage(e: unknown): string {
+  return isAppError(e) ? e.message : String(e);
+}